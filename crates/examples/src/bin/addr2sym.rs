@@ -0,0 +1,148 @@
+//! Example that resolves addresses to symbols using `SymbolMap` and `ObjectMap`.
+
+use object::{Object, ObjectMap, ObjectSection, SymbolMap, SymbolMapName};
+use object_examples::demangle;
+use std::io::{self, BufRead};
+use std::{env, fs, process};
+
+fn main() {
+    let mut args = env::args().peekable();
+    let cmd = args.next().unwrap();
+    let mut demangle = false;
+    while let Some(arg) = args.next_if(|arg| arg.starts_with('-')) {
+        match arg.as_str() {
+            "-C" | "--demangle" => demangle = true,
+            _ => {
+                eprintln!("Unknown option '{}'", arg);
+                process::exit(1);
+            }
+        }
+    }
+    let Some(file_path) = args.next() else {
+        eprintln!("Usage: {} [-C] <file> [address] ...", cmd);
+        eprintln!("Addresses are read from stdin if none are given.");
+        process::exit(1);
+    };
+
+    let file = match fs::File::open(&file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Failed to open file '{}': {}", file_path, err);
+            process::exit(1);
+        }
+    };
+    let file = match unsafe { memmap2::Mmap::map(&file) } {
+        Ok(mmap) => mmap,
+        Err(err) => {
+            eprintln!("Failed to map file '{}': {}", file_path, err);
+            process::exit(1);
+        }
+    };
+    let file = match object::File::parse(&*file) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Failed to parse file '{}': {}", file_path, err);
+            process::exit(1);
+        }
+    };
+
+    let symbolizer = Symbolizer {
+        symbols: file.symbol_map(),
+        objects: file.object_map(),
+        sections: file
+            .sections()
+            .filter(|section| section.size() != 0)
+            .map(|section| (section.address(), section.address() + section.size()))
+            .collect(),
+        demangle,
+    };
+
+    let addresses: Vec<String> = args.collect();
+    if addresses.is_empty() {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => {
+                    eprintln!("Failed to read stdin: {}", err);
+                    process::exit(1);
+                }
+            };
+            for address in line.split_whitespace() {
+                symbolizer.print(address);
+            }
+        }
+    } else {
+        for address in &addresses {
+            symbolizer.print(address);
+        }
+    }
+}
+
+struct Symbolizer<'data> {
+    symbols: SymbolMap<SymbolMapName<'data>>,
+    objects: ObjectMap<'data>,
+    /// The address ranges of the sections, used to bound symbol lookups.
+    sections: Vec<(u64, u64)>,
+    demangle: bool,
+}
+
+impl<'data> Symbolizer<'data> {
+    fn print(&self, arg: &str) {
+        let digits = arg
+            .strip_prefix("0x")
+            .or_else(|| arg.strip_prefix("0X"))
+            .unwrap_or(arg);
+        let address = match u64::from_str_radix(digits, 16) {
+            Ok(address) => address,
+            Err(err) => {
+                eprintln!("Invalid address '{}': {}", arg, err);
+                return;
+            }
+        };
+
+        print!("0x{:x}: ", address);
+        match self.lookup(address) {
+            Some(symbol) => {
+                let name = symbol.name();
+                let name = if self.demangle {
+                    demangle::demangle(name).unwrap_or_else(|| name.to_string())
+                } else {
+                    name.to_string()
+                };
+                let offset = address - symbol.address();
+                if offset == 0 {
+                    print!("{}", name);
+                } else {
+                    print!("{}+0x{:x}", name, offset);
+                }
+            }
+            None => print!("??"),
+        }
+        if let Some(entry) = self.objects.get(address) {
+            let object = entry.object(&self.objects);
+            print!(" [{}", String::from_utf8_lossy(object.path()));
+            if let Some(member) = object.member() {
+                print!("({})", String::from_utf8_lossy(member));
+            }
+            print!("]");
+        }
+        println!();
+    }
+
+    /// Find the symbol containing the given address.
+    ///
+    /// `SymbolMap` doesn't record symbol sizes, so the symbol is only accepted
+    /// if it is in the same section as the address.
+    fn lookup(&self, address: u64) -> Option<&SymbolMapName<'data>> {
+        let symbol = self.symbols.get(address)?;
+        let in_section = self.sections.iter().any(|&(start, end)| {
+            (start..end).contains(&address) && (start..end).contains(&symbol.address())
+        });
+        if in_section {
+            Some(symbol)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::{env, fs, process};
+
+use object::pe;
+use object::read::pe::{ImageNtHeaders, PeFile};
+use object::write::coff::{write_import_library, ImportLibraryExport, ImportLibraryFormat};
+use object::LittleEndian as LE;
+
+fn main() {
+    let mut args = env::args().collect::<Vec<_>>();
+    let format = match args.iter().position(|arg| arg == "--mingw") {
+        Some(index) => {
+            args.remove(index);
+            ImportLibraryFormat::Gnu
+        }
+        None => ImportLibraryFormat::Msvc,
+    };
+    if args.len() != 3 {
+        eprintln!("Usage: {} [--mingw] <dll> <lib>", args[0]);
+        process::exit(1);
+    }
+
+    let in_file_path = &args[1];
+    let out_file_path = &args[2];
+
+    let in_file = match fs::File::open(in_file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Failed to open file '{}': {}", in_file_path, err,);
+            process::exit(1);
+        }
+    };
+    let in_data = match unsafe { memmap2::Mmap::map(&in_file) } {
+        Ok(mmap) => mmap,
+        Err(err) => {
+            eprintln!("Failed to map file '{}': {}", in_file_path, err,);
+            process::exit(1);
+        }
+    };
+    let in_data = &*in_data;
+
+    let kind = match object::FileKind::parse(in_data) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Failed to parse file: {}", err);
+            process::exit(1);
+        }
+    };
+    let out_data = match kind {
+        object::FileKind::Pe32 => implib::<pe::ImageNtHeaders32>(in_data, format),
+        object::FileKind::Pe64 => implib::<pe::ImageNtHeaders64>(in_data, format),
+        _ => {
+            eprintln!("Not a PE file");
+            process::exit(1);
+        }
+    };
+    let out_data = match out_data {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to write import library: {}", err);
+            process::exit(1);
+        }
+    };
+    if let Err(err) = fs::write(out_file_path, out_data) {
+        eprintln!("Failed to write file '{}': {}", out_file_path, err);
+        process::exit(1);
+    }
+}
+
+fn implib<Pe: ImageNtHeaders>(
+    in_data: &[u8],
+    format: ImportLibraryFormat,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let file = PeFile::<Pe>::parse(in_data)?;
+    let machine = file.nt_headers().file_header().machine.get(LE);
+    let export_table = file.export_table()?.ok_or("No export table")?;
+    let dll_name = export_table.name_from_pointer(export_table.directory().name.get(LE))?;
+
+    // Decorate the names for I386, and let the linker undecorate them.
+    let (prefix, name_type) = if machine == pe::IMAGE_FILE_MACHINE_I386 {
+        (&b"_"[..], pe::IMPORT_OBJECT_NAME_NO_PREFIX)
+    } else {
+        (&b""[..], pe::IMPORT_OBJECT_NAME)
+    };
+
+    // Only exports with names are included. The hint is the index into the name table.
+    let mut names = Vec::new();
+    for (hint, (name_pointer, _)) in export_table.name_iter().enumerate() {
+        let name = export_table.name_from_pointer(name_pointer)?;
+        names.push(([prefix, name].concat(), hint as u16));
+    }
+    let exports = names
+        .iter()
+        .map(|(name, hint)| ImportLibraryExport {
+            name,
+            export_name: None,
+            ordinal_or_hint: *hint,
+            // The export table doesn't record whether exports are code or data.
+            import_type: pe::IMPORT_OBJECT_CODE,
+            name_type,
+        })
+        .collect::<Vec<_>>();
+
+    let mut out_data = Vec::new();
+    write_import_library(&mut out_data, dll_name, machine, format, &exports)?;
+    Ok(out_data)
+}
@@ -2,23 +2,27 @@ use object::{
     Object, ObjectSection, ObjectSymbol, SectionIndex, SectionKind, Symbol, SymbolKind,
     SymbolSection,
 };
+use object_examples::demangle;
 use std::collections::HashMap;
 use std::{env, fs, process};
 
 fn main() {
-    let arg_len = env::args().len();
-    if arg_len <= 1 {
-        eprintln!("Usage: {} <file> ...", env::args().next().unwrap());
+    let mut args = env::args().peekable();
+    let cmd = args.next().unwrap();
+    let demangle = args.next_if(|arg| arg == "--demangle").is_some();
+    let file_paths: Vec<_> = args.collect();
+    if file_paths.is_empty() {
+        eprintln!("Usage: {} [--demangle] <file> ...", cmd);
         process::exit(1);
     }
 
-    for file_path in env::args().skip(1) {
-        if arg_len > 2 {
+    for file_path in &file_paths {
+        if file_paths.len() > 1 {
             println!();
             println!("{}:", file_path);
         }
 
-        let file = match fs::File::open(&file_path) {
+        let file = match fs::File::open(file_path) {
             Ok(file) => file,
             Err(err) => {
                 println!("Failed to open file '{}': {}", file_path, err,);
@@ -44,18 +48,22 @@ fn main() {
 
         println!("Debugging symbols:");
         for symbol in file.symbols() {
-            print_symbol(&symbol, &section_kinds);
+            print_symbol(&symbol, &section_kinds, demangle);
         }
         println!();
 
         println!("Dynamic symbols:");
         for symbol in file.dynamic_symbols() {
-            print_symbol(&symbol, &section_kinds);
+            print_symbol(&symbol, &section_kinds, demangle);
         }
     }
 }
 
-fn print_symbol(symbol: &Symbol<'_, '_>, section_kinds: &HashMap<SectionIndex, SectionKind>) {
+fn print_symbol(
+    symbol: &Symbol<'_, '_>,
+    section_kinds: &HashMap<SectionIndex, SectionKind>,
+    demangle: bool,
+) {
     if let SymbolKind::Section | SymbolKind::File = symbol.kind() {
         return;
     }
@@ -86,10 +94,16 @@ fn print_symbol(symbol: &Symbol<'_, '_>, section_kinds: &HashMap<SectionIndex, S
     } else {
         print!("{:016x} ", symbol.address());
     }
+    let name = symbol.name().unwrap_or("<unknown>");
+    let demangled = if demangle {
+        demangle::demangle(name)
+    } else {
+        None
+    };
     println!(
         "{:016x} {} {}",
         symbol.size(),
         kind,
-        symbol.name().unwrap_or("<unknown>"),
+        demangled.as_deref().unwrap_or(name),
     );
 }
@@ -1,19 +1,87 @@
-use object_examples::objcopy;
+use object::BinaryFormat;
+use object_examples::objcopy::{self, CopyOptions, CopySectionFlags};
 
 use std::{env, fs, process};
 
 fn main() {
-    let mut args = env::args();
-    if args.len() != 3 {
-        eprintln!("Usage: {} <infile> <outfile>", args.next().unwrap());
+    let mut args = env::args().peekable();
+    let cmd = args.next().unwrap();
+    let mut options = CopyOptions::default();
+    let mut file_paths = Vec::new();
+    while let Some(arg) = args.next() {
+        // Support both "--option=value" and "--option value".
+        let (option, inline_value) = match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => (option, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || match inline_value.clone().or_else(|| args.next()) {
+            Some(value) => value,
+            None => {
+                eprintln!("Option '{}' requires a value", option);
+                process::exit(1);
+            }
+        };
+        match option {
+            "-O" | "--output-target" => {
+                let target = value();
+                match parse_format(&target) {
+                    Some(format) => options.output_format = Some(format),
+                    None => {
+                        eprintln!("Unsupported output target '{}'", target);
+                        process::exit(1);
+                    }
+                }
+            }
+            "-g" | "--strip-debug" => options.strip_debug = true,
+            "--add-section" => {
+                let (name, path) = split_pair(option, &value());
+                let data = match fs::read(&path) {
+                    Ok(data) => data,
+                    Err(err) => {
+                        eprintln!("Failed to read file '{}': {}", path, err);
+                        process::exit(1);
+                    }
+                };
+                options.add_sections.push((name, data));
+            }
+            "--redefine-sym" => {
+                let (old, new) = split_pair(option, &value());
+                options
+                    .redefine_syms
+                    .insert(old.into_bytes(), new.into_bytes());
+            }
+            "--set-section-flags" => {
+                let (name, flags) = split_pair(option, &value());
+                match CopySectionFlags::parse(&flags) {
+                    Ok(flags) => {
+                        options.set_section_flags.insert(name, flags);
+                    }
+                    Err(err) => {
+                        eprintln!("Invalid flags for section '{}': {}", name, err);
+                        process::exit(1);
+                    }
+                }
+            }
+            _ if option.starts_with('-') && option.len() > 1 => {
+                eprintln!("Unknown option '{}'", arg);
+                process::exit(1);
+            }
+            _ => file_paths.push(arg),
+        }
+    }
+    if file_paths.len() != 2 {
+        eprintln!(
+            "Usage: {} [-O <format>] [--strip-debug] [--add-section <name>=<file>] \
+             [--redefine-sym <old>=<new>] [--set-section-flags <name>=<flags>] \
+             <infile> <outfile>",
+            cmd
+        );
         process::exit(1);
     }
+    let in_file_path = &file_paths[0];
+    let out_file_path = &file_paths[1];
 
-    args.next();
-    let in_file_path = args.next().unwrap();
-    let out_file_path = args.next().unwrap();
-
-    let in_file = match fs::File::open(&in_file_path) {
+    let in_file = match fs::File::open(in_file_path) {
         Ok(file) => file,
         Err(err) => {
             eprintln!("Failed to open file '{}': {}", in_file_path, err,);
@@ -28,10 +96,38 @@ fn main() {
         }
     };
 
-    let out_data = objcopy::copy(&in_data);
+    let out_data = objcopy::copy_with_options(&in_data, &options);
 
-    if let Err(err) = fs::write(&out_file_path, out_data) {
+    if let Err(err) = fs::write(out_file_path, out_data) {
         eprintln!("Failed to write file '{}': {}", out_file_path, err);
         process::exit(1);
     }
 }
+
+/// Parse a GNU style target name, such as `elf64-x86-64` or `pe-x86-64`.
+///
+/// Only the file format is used. The architecture is always copied from the input.
+fn parse_format(target: &str) -> Option<BinaryFormat> {
+    if target.starts_with("elf") {
+        Some(BinaryFormat::Elf)
+    } else if target == "coff" || target.starts_with("pe-") {
+        Some(BinaryFormat::Coff)
+    } else if target == "macho" || target.starts_with("mach-o") {
+        Some(BinaryFormat::MachO)
+    } else if target == "xcoff" || target.starts_with("aixcoff") {
+        Some(BinaryFormat::Xcoff)
+    } else {
+        None
+    }
+}
+
+/// Split an option value of the form `<a>=<b>`.
+fn split_pair(option: &str, value: &str) -> (String, String) {
+    match value.split_once('=') {
+        Some((a, b)) => (a.to_string(), b.to_string()),
+        None => {
+            eprintln!("Option '{}' requires a value of the form <a>=<b>", option);
+            process::exit(1);
+        }
+    }
+}
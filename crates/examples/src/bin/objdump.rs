@@ -4,12 +4,46 @@ use object_examples::objdump;
 use std::{env, fs, io, process};
 
 fn main() {
-    let mut args = env::args();
+    let mut args = env::args().peekable();
     let cmd = args.next().unwrap();
-    if args.len() == 0 {
-        eprintln!("Usage: {} <file> [<member>...]", cmd);
+    let mut disassemble = false;
+    let mut options = objdump::PrintOptions::default();
+    let mut deps = None;
+    while let Some(arg) = args.next_if(|arg| arg.starts_with('-')) {
+        match arg.as_str() {
+            "-d" => disassemble = true,
+            "--demangle" => options.demangle = true,
+            "-s" | "--full-contents" => options.full_contents = true,
+            "--deps=json" => deps = Some(objdump::DependencyFormat::Json),
+            "--deps=dot" => deps = Some(objdump::DependencyFormat::Dot),
+            _ if arg.starts_with("--hex-dump=") => {
+                options
+                    .hex_dump
+                    .push(arg["--hex-dump=".len()..].to_string());
+            }
+            _ if arg.starts_with("--string-dump=") => {
+                options
+                    .string_dump
+                    .push(arg["--string-dump=".len()..].to_string());
+            }
+            _ => {
+                eprintln!("Unknown option '{}'", arg);
+                process::exit(1);
+            }
+        }
+    }
+    if args.peek().is_none() {
+        eprintln!(
+            "Usage: {} [-d] [-s] [--hex-dump=<section>] [--string-dump=<section>] [--demangle] <file> [<member>...]",
+            cmd
+        );
+        eprintln!("       {} --deps=<json|dot> <file>...", cmd);
         process::exit(1);
     }
+    if let Some(format) = deps {
+        print_dependencies(args.collect(), format);
+        return;
+    }
     let file_path = args.next().unwrap();
     let member_names: Vec<_> = args.collect();
 
@@ -54,12 +88,69 @@ fn main() {
 
     let stdout = io::stdout();
     let stderr = io::stderr();
-    objdump::print(
-        &mut stdout.lock(),
-        &mut stderr.lock(),
-        data,
-        &extra_file_data,
-        member_names,
-    )
-    .unwrap();
+    if disassemble {
+        #[cfg(feature = "disasm")]
+        objdump::print_disassembly(
+            &mut stdout.lock(),
+            &mut stderr.lock(),
+            data,
+            &extra_file_data,
+            member_names,
+            &options,
+        )
+        .unwrap();
+        #[cfg(not(feature = "disasm"))]
+        {
+            eprintln!("Disassembly requires the `disasm` feature");
+            process::exit(1);
+        }
+    } else if options.full_contents
+        || !options.hex_dump.is_empty()
+        || !options.string_dump.is_empty()
+    {
+        objdump::print_contents(
+            &mut stdout.lock(),
+            &mut stderr.lock(),
+            data,
+            &extra_file_data,
+            member_names,
+            &options,
+        )
+        .unwrap();
+    } else {
+        objdump::print(
+            &mut stdout.lock(),
+            &mut stderr.lock(),
+            data,
+            &extra_file_data,
+            member_names,
+            &options,
+        )
+        .unwrap();
+    }
+}
+
+fn print_dependencies(file_paths: Vec<String>, format: objdump::DependencyFormat) {
+    let mut files = Vec::new();
+    for file_path in file_paths {
+        let file = match fs::File::open(&file_path) {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("Failed to open file '{}': {}", file_path, err);
+                continue;
+            }
+        };
+        match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(mmap) => files.push((file_path, mmap)),
+            Err(err) => eprintln!("Failed to map file '{}': {}", file_path, err),
+        }
+    }
+    let files: Vec<(&str, &[u8])> = files
+        .iter()
+        .map(|(path, mmap)| (path.as_str(), &**mmap))
+        .collect();
+
+    let stdout = io::stdout();
+    let stderr = io::stderr();
+    objdump::print_dependencies(&mut stdout.lock(), &mut stderr.lock(), &files, format).unwrap();
 }
@@ -4,6 +4,7 @@ use clap::{Arg, ArgAction, Command};
 use object::read::macho::DyldCache;
 use object::Endianness;
 use object_examples::readobj;
+use std::ops::Range;
 use std::path::PathBuf;
 use std::{fs, io};
 
@@ -76,12 +77,24 @@ fn main() {
                 .action(ArgAction::SetTrue)
                 .help("Print the ELF attribute sections"),
         )
+        .arg(
+            Arg::new("elf-hash-check")
+                .long("elf-hash-check")
+                .action(ArgAction::SetTrue)
+                .help("Check the ELF hash tables against the dynamic symbol table"),
+        )
         .arg(
             Arg::new("macho-load-commands")
                 .long("macho-load-commands")
                 .action(ArgAction::SetTrue)
                 .help("Print the Mach-O load commands"),
         )
+        .arg(
+            Arg::new("macho-dyld-info")
+                .long("macho-dyld-info")
+                .action(ArgAction::SetTrue)
+                .help("Print the Mach-O rebase, bind, export and chained fixup information"),
+        )
         .arg(
             Arg::new("pe-rich")
                 .long("pe-rich")
@@ -112,12 +125,67 @@ fn main() {
                 .action(ArgAction::SetTrue)
                 .help("Print the PE resource directory"),
         )
+        .arg(
+            Arg::new("section")
+                .long("section")
+                .value_name("name")
+                .action(ArgAction::Append)
+                .help("Only print the section with the given name or index"),
+        )
+        .arg(
+            Arg::new("symbol")
+                .long("symbol")
+                .value_name("name")
+                .action(ArgAction::Append)
+                .help("Only print the symbol with the given name"),
+        )
+        .arg(
+            Arg::new("section-range")
+                .long("section-range")
+                .value_name("start..end")
+                .value_parser(parse_range)
+                .help("Only print the sections with an index in the given range"),
+        )
+        .arg(
+            Arg::new("symbol-range")
+                .long("symbol-range")
+                .value_name("start..end")
+                .value_parser(parse_range)
+                .help("Only print the symbols with an index in the given range"),
+        )
         .arg(
             Arg::new("no-string-indices")
                 .long("no-string-indices")
                 .action(ArgAction::SetTrue)
                 .help("Don't print string table indices"),
         )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Print a JSON document for each file"),
+        )
+        .arg(
+            Arg::new("diff")
+                .long("diff")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["json", "elf-output-style"])
+                .help("Print the differences between two files"),
+        )
+        .arg(
+            Arg::new("demangle")
+                .long("demangle")
+                .action(ArgAction::SetTrue)
+                .help("Demangle symbol names"),
+        )
+        .arg(
+            Arg::new("elf-output-style")
+                .long("elf-output-style")
+                .value_name("style")
+                .value_parser(["default", "GNU"])
+                .conflicts_with("json")
+                .help("Print ELF files in the given style. GNU matches the output of llvm-readelf"),
+        )
         .get_matches();
     let mut options = readobj::PrintOptions {
         file: matches.get_flag("file-header"),
@@ -130,7 +198,9 @@ fn main() {
         elf_notes: matches.get_flag("elf-notes"),
         elf_versions: matches.get_flag("elf-version-info"),
         elf_attributes: matches.get_flag("elf-attributes"),
+        elf_hash_check: matches.get_flag("elf-hash-check"),
         macho_load_commands: matches.get_flag("macho-load-commands"),
+        macho_dyld_info: matches.get_flag("macho-dyld-info"),
         pe_rich: matches.get_flag("pe-rich"),
         pe_base_relocs: matches.get_flag("pe-base-relocs"),
         pe_imports: matches.get_flag("pe-imports"),
@@ -141,12 +211,40 @@ fn main() {
     if options == readobj::PrintOptions::none() {
         options = readobj::PrintOptions::all();
     }
+    if let Some(names) = matches.get_many::<String>("section") {
+        options.section_names = names.cloned().collect();
+    }
+    if let Some(names) = matches.get_many::<String>("symbol") {
+        options.symbol_names = names.cloned().collect();
+    }
+    options.section_range = matches.get_one::<Range<usize>>("section-range").cloned();
+    options.symbol_range = matches.get_one::<Range<usize>>("symbol-range").cloned();
     options.string_indices = !matches.get_flag("no-string-indices");
+    options.json = matches.get_flag("json");
+    options.demangle = matches.get_flag("demangle");
+    options.elf_gnu_style = matches
+        .get_one::<String>("elf-output-style")
+        .map(String::as_str)
+        == Some("GNU");
 
     let file_paths = matches.get_many::<PathBuf>("file").unwrap();
+    if matches.get_flag("diff") {
+        let file_paths: Vec<_> = file_paths.collect();
+        let [old_path, new_path] = file_paths[..] else {
+            eprintln!("--diff requires two files");
+            std::process::exit(1);
+        };
+        let (Some(old), Some(new)) = (map_file(old_path), map_file(new_path)) else {
+            std::process::exit(1);
+        };
+        let stdout = io::stdout();
+        let stderr = io::stderr();
+        readobj::print_diff(&mut stdout.lock(), &mut stderr.lock(), &old, &new, &options).unwrap();
+        return;
+    }
     let file_count = file_paths.len();
     for file_path in file_paths {
-        if file_count > 1 {
+        if file_count > 1 && !options.json {
             println!();
             println!("{}:", file_path.display());
         }
@@ -206,3 +304,37 @@ fn main() {
         );
     }
 }
+
+fn map_file(file_path: &PathBuf) -> Option<memmap2::Mmap> {
+    let file = match fs::File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            println!("Failed to open file '{}': {}", file_path.display(), err);
+            return None;
+        }
+    };
+    match unsafe { memmap2::Mmap::map(&file) } {
+        Ok(mmap) => Some(mmap),
+        Err(err) => {
+            println!("Failed to map file '{}': {}", file_path.display(), err);
+            None
+        }
+    }
+}
+
+/// Parse a range of the form `start..end`, where either bound may be omitted.
+fn parse_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("invalid range '{}'", s))?;
+    let parse = |bound: &str, default| {
+        if bound.is_empty() {
+            Ok(default)
+        } else {
+            bound
+                .parse::<usize>()
+                .map_err(|err| format!("invalid range bound '{}': {}", bound, err))
+        }
+    };
+    Ok(parse(start, 0)?..parse(end, usize::MAX)?)
+}
@@ -0,0 +1,228 @@
+use object::read::archive::ArchiveFile;
+use object::read::elf::SectionHeader;
+use object::{elf, Object, ObjectSection, SectionFlags, SectionKind};
+use std::{env, fs, process};
+
+/// The totals for a file in the Berkeley format.
+#[derive(Default)]
+struct Totals {
+    text: u64,
+    data: u64,
+    bss: u64,
+}
+
+fn main() {
+    let mut args = env::args().peekable();
+    let cmd = args.next().unwrap();
+    let mut sysv = false;
+    while let Some(arg) = args.next_if(|arg| arg.starts_with('-')) {
+        match arg.as_str() {
+            "-A" | "--format=sysv" => sysv = true,
+            "-B" | "--format=berkeley" => sysv = false,
+            _ => {
+                eprintln!("Unknown option '{}'", arg);
+                process::exit(1);
+            }
+        }
+    }
+    let file_paths: Vec<_> = args.collect();
+    if file_paths.is_empty() {
+        eprintln!("Usage: {} [-A|-B] <file> ...", cmd);
+        process::exit(1);
+    }
+
+    if !sysv {
+        println!("   text\t   data\t    bss\t    dec\t    hex\tfilename");
+    }
+    for file_path in &file_paths {
+        let file = match fs::File::open(file_path) {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("Failed to open file '{}': {}", file_path, err,);
+                continue;
+            }
+        };
+        let file = match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(mmap) => mmap,
+            Err(err) => {
+                eprintln!("Failed to map file '{}': {}", file_path, err,);
+                continue;
+            }
+        };
+
+        if let Ok(archive) = ArchiveFile::parse(&*file) {
+            for member in archive.members() {
+                let member = match member {
+                    Ok(member) => member,
+                    Err(err) => {
+                        eprintln!("Failed to parse archive member in '{}': {}", file_path, err);
+                        break;
+                    }
+                };
+                let name = String::from_utf8_lossy(member.name());
+                match member.data(&*file) {
+                    Ok(data) => print_file(&name, Some(file_path), data, sysv),
+                    Err(err) => eprintln!("Failed to read '{}': {}", name, err),
+                }
+            }
+        } else {
+            print_file(file_path, None, &file, sysv);
+        }
+    }
+}
+
+fn print_file(name: &str, archive: Option<&str>, data: &[u8], sysv: bool) {
+    let file = match object::File::parse(data) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Failed to parse file '{}': {}", name, err);
+            return;
+        }
+    };
+    if sysv {
+        match archive {
+            Some(archive) => println!("{}   (ex {}):", name, archive),
+            None => println!("{}  :", name),
+        }
+        print_sysv(&file);
+    } else {
+        match archive {
+            Some(archive) => print_berkeley(&format!("{} (ex {})", name, archive), &file),
+            None => print_berkeley(name, &file),
+        }
+    }
+}
+
+fn print_berkeley(name: &str, file: &object::File<'_>) {
+    let mut totals = Totals::default();
+    for section in file.sections() {
+        let size = section.size();
+        match section.kind() {
+            SectionKind::Text
+            | SectionKind::ReadOnlyData
+            | SectionKind::ReadOnlyDataWithRel
+            | SectionKind::ReadOnlyString => totals.text += size,
+            SectionKind::Data | SectionKind::Tls | SectionKind::TlsVariables => totals.data += size,
+            SectionKind::UninitializedData
+            | SectionKind::UninitializedTls
+            | SectionKind::Common => totals.bss += size,
+            // Other kinds are only counted if they are loaded. Writable sections
+            // are data, and everything else is text.
+            _ => match elf_flags(&section) {
+                Some(sh_flags) if sh_flags & u64::from(elf::SHF_ALLOC) != 0 => {
+                    if sh_flags & u64::from(elf::SHF_WRITE) != 0 {
+                        totals.data += size;
+                    } else {
+                        totals.text += size;
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+    let total = totals.text + totals.data + totals.bss;
+    println!(
+        "{:7}\t{:7}\t{:7}\t{:7}\t{:7x}\t{}",
+        totals.text, totals.data, totals.bss, total, total, name
+    );
+}
+
+fn print_sysv(file: &object::File<'_>) {
+    let sections: Vec<_> = file
+        .sections()
+        .filter(|section| is_listed(file, section))
+        .map(|section| {
+            let name = section.name().unwrap_or("<invalid name>").to_string();
+            (name, section.size(), section.address())
+        })
+        .collect();
+    let total: u64 = sections.iter().map(|(_, size, _)| size).sum();
+
+    let name_width = sections
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .chain(["section".len(), "Total".len()])
+        .max()
+        .unwrap_or(0);
+    let size_width = sections
+        .iter()
+        .map(|(_, size, _)| size.to_string().len())
+        .chain(["size".len(), total.to_string().len()])
+        .max()
+        .unwrap_or(0);
+    let addr_width = sections
+        .iter()
+        .map(|(_, _, address)| address.to_string().len())
+        .chain(["addr".len()])
+        .max()
+        .unwrap_or(0);
+
+    println!(
+        "{:<name_width$}   {:>size_width$}   {:>addr_width$}",
+        "section",
+        "size",
+        "addr",
+        name_width = name_width,
+        size_width = size_width,
+        addr_width = addr_width,
+    );
+    for (name, size, address) in &sections {
+        println!(
+            "{:<name_width$}   {:>size_width$}   {:>addr_width$}",
+            name,
+            size,
+            address,
+            name_width = name_width,
+            size_width = size_width,
+            addr_width = addr_width,
+        );
+    }
+    println!(
+        "{:<name_width$}   {:>size_width$}",
+        "Total",
+        total,
+        name_width = name_width,
+        size_width = size_width,
+    );
+    println!();
+    println!();
+}
+
+/// Return false for ELF symbol, string and relocation tables that are not loaded.
+///
+/// This matches the sections that GNU size prints.
+fn is_listed(file: &object::File<'_>, section: &object::Section<'_, '_>) -> bool {
+    let sh_type = match file {
+        object::File::Elf32(elf) => elf
+            .section_by_index(section.index())
+            .ok()
+            .map(|section| section.elf_section_header().sh_type(elf.endian())),
+        object::File::Elf64(elf) => elf
+            .section_by_index(section.index())
+            .ok()
+            .map(|section| section.elf_section_header().sh_type(elf.endian())),
+        _ => None,
+    };
+    let alloc = elf_flags(section)
+        .map(|sh_flags| sh_flags & u64::from(elf::SHF_ALLOC) != 0)
+        .unwrap_or(true);
+    alloc
+        || !matches!(
+            sh_type,
+            Some(
+                elf::SHT_SYMTAB
+                    | elf::SHT_STRTAB
+                    | elf::SHT_REL
+                    | elf::SHT_RELA
+                    | elf::SHT_RELR
+                    | elf::SHT_SYMTAB_SHNDX
+            )
+        )
+}
+
+fn elf_flags(section: &object::Section<'_, '_>) -> Option<u64> {
+    match section.flags() {
+        SectionFlags::Elf { sh_flags } => Some(sh_flags),
+        _ => None,
+    }
+}
@@ -0,0 +1,549 @@
+//! Demangling of symbol names.
+//!
+//! Rust and Itanium C++ names are demangled using the `rustc-demangle` and
+//! `cpp_demangle` crates. MSVC C++ names are demangled by a small decoder that
+//! supports the encodings that are commonly seen in symbol tables.
+
+/// Demangle a symbol name.
+///
+/// Returns `None` if the name is not mangled, or uses an unsupported encoding.
+pub fn demangle(name: &str) -> Option<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        return Some(format!("{:#}", demangled));
+    }
+    // Mach-O adds an extra leading underscore.
+    let itanium = name.strip_prefix("__Z").map_or(name, |_| &name[1..]);
+    if itanium.starts_with("_Z") {
+        let options = cpp_demangle::DemangleOptions::default();
+        return cpp_demangle::Symbol::new(itanium)
+            .ok()?
+            .demangle(&options)
+            .ok();
+    }
+    if name.starts_with('?') {
+        return Msvc::new(name.as_bytes()).symbol();
+    }
+    None
+}
+
+/// Demangle a symbol name that may not be valid UTF-8.
+pub fn demangle_bytes(name: &[u8]) -> Option<String> {
+    demangle(std::str::from_utf8(name).ok()?)
+}
+
+/// The maximum number of names and types that can be back referenced.
+const MAX_BACKREFS: usize = 10;
+
+/// A decoder for MSVC C++ names.
+///
+/// The output matches `llvm-undname`.
+struct Msvc<'a> {
+    input: &'a [u8],
+    /// Back references for names.
+    names: Vec<String>,
+    /// Back references for function parameter types.
+    types: Vec<String>,
+}
+
+/// The first component of a qualified name.
+enum Unqualified {
+    Name(String),
+    Constructor,
+    Destructor,
+    Conversion,
+}
+
+/// The kind of function that a function encoding is for.
+#[derive(PartialEq, Eq)]
+enum FunctionKind {
+    Global,
+    Member,
+    Static,
+    Virtual,
+}
+
+impl<'a> Msvc<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Msvc {
+            input,
+            names: Vec::new(),
+            types: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.first().copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let (c, rest) = self.input.split_first()?;
+        self.input = rest;
+        Some(*c)
+    }
+
+    fn consume(&mut self, prefix: &[u8]) -> bool {
+        match self.input.strip_prefix(prefix) {
+            Some(rest) => {
+                self.input = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn symbol(&mut self) -> Option<String> {
+        if !self.consume(b"?") {
+            return None;
+        }
+        if self.consume(b"?_C@_") {
+            return Some("`string'".to_string());
+        }
+        let (first, scopes) = self.qualified_name(true)?;
+        let demangled = match self.next()? {
+            c @ b'0'..=b'4' => {
+                let access = match c {
+                    b'0' => "private: static ",
+                    b'1' => "protected: static ",
+                    b'2' => "public: static ",
+                    _ => "",
+                };
+                let ty = self.ty()?;
+                self.consume(b"E");
+                let cv = self.cv()?;
+                let name = join_name(&first, &scopes, "")?;
+                if ty.ends_with(['*', '&']) {
+                    format!("{}{}{}", access, ty, name)
+                } else if cv.is_empty() {
+                    format!("{}{} {}", access, ty, name)
+                } else {
+                    format!("{}{} {} {}", access, ty, cv, name)
+                }
+            }
+            b'6' | b'7' => {
+                let Unqualified::Name(name) = first else {
+                    return None;
+                };
+                self.cv()?;
+                if !self.consume(b"@") {
+                    return None;
+                }
+                format!("const {}", join_scopes(&name, &scopes))
+            }
+            c => self.function(c, &first, &scopes)?,
+        };
+        if !self.input.is_empty() {
+            return None;
+        }
+        Some(demangled)
+    }
+
+    fn function(&mut self, c: u8, first: &Unqualified, scopes: &[String]) -> Option<String> {
+        let (access, kind) = match c {
+            b'A' | b'B' => ("private: ", FunctionKind::Member),
+            b'C' | b'D' => ("private: ", FunctionKind::Static),
+            b'E' | b'F' => ("private: ", FunctionKind::Virtual),
+            b'I' | b'J' => ("protected: ", FunctionKind::Member),
+            b'K' | b'L' => ("protected: ", FunctionKind::Static),
+            b'M' | b'N' => ("protected: ", FunctionKind::Virtual),
+            b'Q' | b'R' => ("public: ", FunctionKind::Member),
+            b'S' | b'T' => ("public: ", FunctionKind::Static),
+            b'U' | b'V' => ("public: ", FunctionKind::Virtual),
+            b'Y' | b'Z' => ("", FunctionKind::Global),
+            _ => return None,
+        };
+        let mut this_cv = "";
+        if kind == FunctionKind::Member || kind == FunctionKind::Virtual {
+            self.consume(b"E");
+            this_cv = self.cv()?;
+        }
+        let calling_convention = self.calling_convention()?;
+        let ret = if self.consume(b"@") {
+            None
+        } else {
+            Some(self.return_type()?)
+        };
+        let params = self.params()?;
+        self.throw_spec()?;
+
+        let conversion = match ret {
+            Some(ref ret) => format!("operator {}", ret),
+            None => String::new(),
+        };
+        let name = join_name(first, scopes, &conversion)?;
+        let mut out = String::from(access);
+        match kind {
+            FunctionKind::Static => out.push_str("static "),
+            FunctionKind::Virtual => out.push_str("virtual "),
+            FunctionKind::Global | FunctionKind::Member => {}
+        }
+        if let Some(ret) = ret {
+            out.push_str(&ret);
+            out.push(' ');
+        }
+        out.push_str(calling_convention);
+        out.push(' ');
+        out.push_str(&name);
+        out.push('(');
+        out.push_str(&params);
+        out.push(')');
+        if !this_cv.is_empty() {
+            out.push(' ');
+            out.push_str(this_cv);
+        }
+        Some(out)
+    }
+
+    /// Parse a qualified name, and return its first component and scopes.
+    ///
+    /// The scopes are returned outermost first.
+    fn qualified_name(&mut self, special: bool) -> Option<(Unqualified, Vec<String>)> {
+        let first = if special && self.input.starts_with(b"?") && !self.input.starts_with(b"?$") {
+            self.next();
+            self.special_name()?
+        } else {
+            Unqualified::Name(self.name_fragment()?)
+        };
+        let mut scopes = Vec::new();
+        while !self.consume(b"@") {
+            scopes.push(self.scope()?);
+        }
+        scopes.reverse();
+        Some((first, scopes))
+    }
+
+    /// Parse a qualified name that is used in a type.
+    fn type_name(&mut self) -> Option<String> {
+        let (first, scopes) = self.qualified_name(false)?;
+        join_name(&first, &scopes, "")
+    }
+
+    fn special_name(&mut self) -> Option<Unqualified> {
+        let name = match self.next()? {
+            b'0' => return Some(Unqualified::Constructor),
+            b'1' => return Some(Unqualified::Destructor),
+            b'B' => return Some(Unqualified::Conversion),
+            b'2' => "operator new",
+            b'3' => "operator delete",
+            b'4' => "operator=",
+            b'5' => "operator>>",
+            b'6' => "operator<<",
+            b'7' => "operator!",
+            b'8' => "operator==",
+            b'9' => "operator!=",
+            b'A' => "operator[]",
+            b'C' => "operator->",
+            b'D' => "operator*",
+            b'E' => "operator++",
+            b'F' => "operator--",
+            b'G' => "operator-",
+            b'H' => "operator+",
+            b'I' => "operator&",
+            b'J' => "operator->*",
+            b'K' => "operator/",
+            b'L' => "operator%",
+            b'M' => "operator<",
+            b'N' => "operator<=",
+            b'O' => "operator>",
+            b'P' => "operator>=",
+            b'Q' => "operator,",
+            b'R' => "operator()",
+            b'S' => "operator~",
+            b'T' => "operator^",
+            b'U' => "operator|",
+            b'V' => "operator&&",
+            b'W' => "operator||",
+            b'X' => "operator*=",
+            b'Y' => "operator+=",
+            b'Z' => "operator-=",
+            b'_' => match self.next()? {
+                b'0' => "operator/=",
+                b'1' => "operator%=",
+                b'2' => "operator>>=",
+                b'3' => "operator<<=",
+                b'4' => "operator&=",
+                b'5' => "operator|=",
+                b'6' => "operator^=",
+                b'7' => "`vftable'",
+                b'8' => "`vbtable'",
+                b'U' => "operator new[]",
+                b'V' => "operator delete[]",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(Unqualified::Name(name.to_string()))
+    }
+
+    /// Parse a simple name, a name back reference, or a template name.
+    fn name_fragment(&mut self) -> Option<String> {
+        if let Some(c @ b'0'..=b'9') = self.peek() {
+            self.next();
+            return self.names.get(usize::from(c - b'0')).cloned();
+        }
+        if self.consume(b"?$") {
+            return self.template_name();
+        }
+        let end = self.input.iter().position(|c| *c == b'@')?;
+        if end == 0 {
+            return None;
+        }
+        let name = String::from_utf8_lossy(&self.input[..end]).into_owned();
+        self.input = &self.input[end + 1..];
+        self.memorize_name(&name);
+        Some(name)
+    }
+
+    /// Parse a template name and its arguments, after the `?$` prefix.
+    fn template_name(&mut self) -> Option<String> {
+        let names = std::mem::take(&mut self.names);
+        let types = std::mem::take(&mut self.types);
+        let result = self.template_name_inner();
+        self.names = names;
+        self.types = types;
+        let name = result?;
+        self.memorize_name(&name);
+        Some(name)
+    }
+
+    fn template_name_inner(&mut self) -> Option<String> {
+        let mut name = self.name_fragment()?;
+        let mut args = Vec::new();
+        while !self.consume(b"@") {
+            if self.consume(b"$0") {
+                args.push(self.number()?.to_string());
+            } else if self.consume(b"$$V") || self.consume(b"$$Z") {
+                // Empty parameter pack.
+            } else {
+                args.push(self.ty()?);
+            }
+        }
+        name.push('<');
+        name.push_str(&args.join(", "));
+        name.push('>');
+        Some(name)
+    }
+
+    fn scope(&mut self) -> Option<String> {
+        if self.consume(b"?A") {
+            // Anonymous namespace, named by a unique identifier.
+            let end = self.input.iter().position(|c| *c == b'@')?;
+            self.input = &self.input[end + 1..];
+            let name = "`anonymous namespace'".to_string();
+            self.memorize_name(&name);
+            return Some(name);
+        }
+        self.name_fragment()
+    }
+
+    fn memorize_name(&mut self, name: &str) {
+        if self.names.len() < MAX_BACKREFS && !self.names.iter().any(|x| x == name) {
+            self.names.push(name.to_string());
+        }
+    }
+
+    fn number(&mut self) -> Option<i64> {
+        let negative = self.consume(b"?");
+        let value = match self.next()? {
+            c @ b'0'..=b'9' => i64::from(c - b'0') + 1,
+            mut c => {
+                let mut value = 0i64;
+                while c != b'@' {
+                    if !(b'A'..=b'P').contains(&c) {
+                        return None;
+                    }
+                    value = value.checked_mul(16)? + i64::from(c - b'A');
+                    c = self.next()?;
+                }
+                value
+            }
+        };
+        Some(if negative { -value } else { value })
+    }
+
+    fn cv(&mut self) -> Option<&'static str> {
+        Some(match self.next()? {
+            b'A' => "",
+            b'B' => "const",
+            b'C' => "volatile",
+            b'D' => "const volatile",
+            _ => return None,
+        })
+    }
+
+    fn calling_convention(&mut self) -> Option<&'static str> {
+        Some(match self.next()? {
+            b'A' | b'B' => "__cdecl",
+            b'C' | b'D' => "__pascal",
+            b'E' | b'F' => "__thiscall",
+            b'G' | b'H' => "__stdcall",
+            b'I' | b'J' => "__fastcall",
+            b'M' | b'N' => "__clrcall",
+            b'Q' => "__vectorcall",
+            _ => return None,
+        })
+    }
+
+    fn return_type(&mut self) -> Option<String> {
+        if self.consume(b"?") {
+            let cv = self.cv()?;
+            let ty = self.ty()?;
+            return Some(with_cv(ty, cv));
+        }
+        self.ty()
+    }
+
+    fn params(&mut self) -> Option<String> {
+        if self.consume(b"X") {
+            return Some("void".to_string());
+        }
+        let mut params = Vec::new();
+        loop {
+            if self.consume(b"@") {
+                break;
+            }
+            if self.consume(b"Z") {
+                params.push("...".to_string());
+                break;
+            }
+            if let Some(c @ b'0'..=b'9') = self.peek() {
+                self.next();
+                params.push(self.types.get(usize::from(c - b'0'))?.clone());
+                continue;
+            }
+            let len = self.input.len();
+            let ty = self.ty()?;
+            if len - self.input.len() > 1 && self.types.len() < MAX_BACKREFS {
+                self.types.push(ty.clone());
+            }
+            params.push(ty);
+        }
+        Some(params.join(", "))
+    }
+
+    fn throw_spec(&mut self) -> Option<()> {
+        self.consume(b"_E");
+        if self.consume(b"Z") {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn ty(&mut self) -> Option<String> {
+        let name = match self.next()? {
+            b'C' => "signed char",
+            b'D' => "char",
+            b'E' => "unsigned char",
+            b'F' => "short",
+            b'G' => "unsigned short",
+            b'H' => "int",
+            b'I' => "unsigned int",
+            b'J' => "long",
+            b'K' => "unsigned long",
+            b'M' => "float",
+            b'N' => "double",
+            b'O' => "long double",
+            b'X' => "void",
+            b'_' => match self.next()? {
+                b'D' => "__int8",
+                b'E' => "unsigned __int8",
+                b'F' => "__int16",
+                b'G' => "unsigned __int16",
+                b'H' => "__int32",
+                b'I' => "unsigned __int32",
+                b'J' => "__int64",
+                b'K' => "unsigned __int64",
+                b'L' => "__int128",
+                b'M' => "unsigned __int128",
+                b'N' => "bool",
+                b'Q' => "char8_t",
+                b'S' => "char16_t",
+                b'U' => "char32_t",
+                b'W' => "wchar_t",
+                _ => return None,
+            },
+            b'T' => return Some(format!("union {}", self.type_name()?)),
+            b'U' => return Some(format!("struct {}", self.type_name()?)),
+            b'V' => return Some(format!("class {}", self.type_name()?)),
+            b'W' => {
+                if !self.consume(b"4") {
+                    return None;
+                }
+                return Some(format!("enum {}", self.type_name()?));
+            }
+            b'P' => return self.pointer("*"),
+            b'Q' => return self.pointer("*const"),
+            b'R' => return self.pointer("*volatile"),
+            b'S' => return self.pointer("*const volatile"),
+            b'A' => return self.pointer("&"),
+            b'B' => return self.pointer("& volatile"),
+            b'?' => {
+                let cv = self.cv()?;
+                let ty = self.ty()?;
+                return Some(with_cv(ty, cv));
+            }
+            b'$' => {
+                if self.consume(b"$Q") {
+                    return self.pointer("&&");
+                }
+                if self.consume(b"$T") {
+                    return Some("std::nullptr_t".to_string());
+                }
+                return None;
+            }
+            _ => return None,
+        };
+        Some(name.to_string())
+    }
+
+    fn pointer(&mut self, op: &str) -> Option<String> {
+        if self.consume(b"6") {
+            let calling_convention = self.calling_convention()?;
+            let ret = self.return_type()?;
+            let params = self.params()?;
+            self.throw_spec()?;
+            return Some(format!(
+                "{} ({} {})({})",
+                ret, calling_convention, op, params
+            ));
+        }
+        // Pointer modifiers: `__ptr64`, `__unaligned` and `__restrict`.
+        while self.consume(b"E") || self.consume(b"F") || self.consume(b"I") {}
+        let cv = self.cv()?;
+        let ty = self.ty()?;
+        Some(format!("{} {}", with_cv(ty, cv), op))
+    }
+}
+
+fn with_cv(ty: String, cv: &str) -> String {
+    if cv.is_empty() {
+        ty
+    } else {
+        format!("{} {}", ty, cv)
+    }
+}
+
+fn join_scopes(name: &str, scopes: &[String]) -> String {
+    let mut out = String::new();
+    for scope in scopes {
+        out.push_str(scope);
+        out.push_str("::");
+    }
+    out.push_str(name);
+    out
+}
+
+/// Join a name with its scopes.
+///
+/// Constructors and destructors use the name of the innermost scope, and
+/// conversion operators use the given name.
+fn join_name(first: &Unqualified, scopes: &[String], conversion: &str) -> Option<String> {
+    let name = match first {
+        Unqualified::Name(name) => name.clone(),
+        Unqualified::Constructor => scopes.last()?.clone(),
+        Unqualified::Destructor => format!("~{}", scopes.last()?),
+        Unqualified::Conversion if !conversion.is_empty() => conversion.to_string(),
+        Unqualified::Conversion => return None,
+    };
+    Some(join_scopes(&name, scopes))
+}
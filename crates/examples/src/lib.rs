@@ -2,6 +2,8 @@
 #![allow(clippy::nonminimal_bool)]
 #![allow(clippy::single_match)]
 
+pub mod demangle;
+
 #[cfg(all(feature = "read", feature = "write"))]
 pub mod objcopy;
 
@@ -1,11 +1,119 @@
 use std::collections::HashMap;
 use std::process;
 
+use object::write::{StandardSection, StandardSegment};
 use object::{
-    write, Object, ObjectComdat, ObjectKind, ObjectSection, ObjectSymbol, RelocationTarget,
-    SectionKind, SymbolFlags, SymbolKind, SymbolSection,
+    elf, write, BinaryFormat, FileFlags, Object, ObjectComdat, ObjectKind, ObjectSection,
+    ObjectSymbol, RelocationFlags, RelocationKind, RelocationTarget, SectionFlags, SectionKind,
+    SymbolFlags, SymbolKind, SymbolSection,
 };
 
+/// Options for [`copy_with_options`].
+///
+/// These correspond to the GNU `objcopy` options of the same name.
+#[derive(Debug, Default, Clone)]
+pub struct CopyOptions {
+    /// The format of the output file.
+    ///
+    /// Defaults to the format of the input file.
+    pub output_format: Option<BinaryFormat>,
+    /// Remove debugging sections.
+    pub strip_debug: bool,
+    /// Sections to add, with their name and contents.
+    pub add_sections: Vec<(String, Vec<u8>)>,
+    /// Symbols to rename, as pairs of the old name and the new name.
+    pub redefine_syms: HashMap<Vec<u8>, Vec<u8>>,
+    /// Flags to set for the sections with the given names.
+    pub set_section_flags: HashMap<String, CopySectionFlags>,
+}
+
+/// Section flags in the style of the GNU `objcopy` `--set-section-flags` option.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CopySectionFlags {
+    pub alloc: bool,
+    pub contents: bool,
+    pub readonly: bool,
+    pub code: bool,
+    pub debug: bool,
+    pub exclude: bool,
+    pub merge: bool,
+    pub strings: bool,
+}
+
+impl CopySectionFlags {
+    /// Parse a comma separated list of flags.
+    ///
+    /// `load`, `data`, `noload`, `rom` and `share` are accepted for
+    /// compatibility, but only `noload` and `rom` have any effect.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut flags = CopySectionFlags::default();
+        for flag in s.split(',') {
+            match flag.trim() {
+                "alloc" => flags.alloc = true,
+                "contents" => flags.contents = true,
+                "load" | "data" | "share" => {}
+                "noload" => flags.contents = false,
+                "readonly" => flags.readonly = true,
+                "rom" => flags.readonly = true,
+                "code" => flags.code = true,
+                "debug" => flags.debug = true,
+                "exclude" => flags.exclude = true,
+                "merge" => flags.merge = true,
+                "strings" => flags.strings = true,
+                flag => return Err(format!("unrecognized section flag '{}'", flag)),
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Return the section kind that best matches these flags.
+    fn kind(&self) -> SectionKind {
+        if !self.alloc {
+            if self.debug {
+                SectionKind::Debug
+            } else if self.strings {
+                SectionKind::OtherString
+            } else {
+                SectionKind::Other
+            }
+        } else if self.code {
+            SectionKind::Text
+        } else if !self.contents {
+            SectionKind::UninitializedData
+        } else if self.readonly && self.strings {
+            SectionKind::ReadOnlyString
+        } else if self.readonly {
+            SectionKind::ReadOnlyData
+        } else {
+            SectionKind::Data
+        }
+    }
+
+    /// Return the ELF section flags, using the same mapping as GNU `objcopy`.
+    fn elf_flags(&self) -> u64 {
+        let mut sh_flags = 0;
+        if self.alloc {
+            sh_flags |= elf::SHF_ALLOC;
+        }
+        if !self.readonly {
+            sh_flags |= elf::SHF_WRITE;
+        }
+        if self.code {
+            sh_flags |= elf::SHF_EXECINSTR;
+        }
+        if self.exclude {
+            sh_flags |= elf::SHF_EXCLUDE;
+        }
+        if self.merge {
+            sh_flags |= elf::SHF_MERGE;
+        }
+        if self.strings {
+            sh_flags |= elf::SHF_STRINGS;
+        }
+        sh_flags.into()
+    }
+}
+
 /// An example of how to use the read and write APIs of the `object` crate
 /// for relocatable object files.
 ///
@@ -16,6 +124,14 @@ use object::{
 /// This function is also used for testing the `object` crate on inputs that
 /// are known to be supported.
 pub fn copy(in_data: &[u8]) -> Vec<u8> {
+    copy_with_options(in_data, &CopyOptions::default())
+}
+
+/// Copy a relocatable object file, applying the changes in `options`.
+///
+/// Converting to a different format discards all format specific flags,
+/// and relocations are converted to their generic form.
+pub fn copy_with_options(in_data: &[u8], options: &CopyOptions) -> Vec<u8> {
     let in_object = match object::File::parse(in_data) {
         Ok(object) => object,
         Err(err) => {
@@ -28,39 +144,98 @@ pub fn copy(in_data: &[u8]) -> Vec<u8> {
         process::exit(1);
     }
 
-    let mut out_object = write::Object::new(
-        in_object.format(),
-        in_object.architecture(),
-        in_object.endianness(),
-    );
+    let out_format = options.output_format.unwrap_or(in_object.format());
+    let convert = out_format != in_object.format();
+    let mut out_object =
+        write::Object::new(out_format, in_object.architecture(), in_object.endianness());
     out_object.mangling = write::Mangling::None;
-    out_object.flags = in_object.flags();
+    if !convert {
+        out_object.flags = in_object.flags();
+    } else {
+        out_object.flags = FileFlags::None;
+    }
 
     let mut out_sections = HashMap::new();
+    let mut standard_sections = Vec::new();
     for in_section in in_object.sections() {
         if in_section.kind() == SectionKind::Metadata {
             continue;
         }
-        let section_id = out_object.add_section(
-            in_section
-                .segment_name()
-                .unwrap()
-                .unwrap_or("")
-                .as_bytes()
-                .to_vec(),
-            in_section.name().unwrap().as_bytes().to_vec(),
-            in_section.kind(),
-        );
+        if options.strip_debug && is_debug(&in_section) {
+            continue;
+        }
+        let name = in_section.name().unwrap();
+        let set_flags = options.set_section_flags.get(name);
+        let kind = match set_flags {
+            Some(flags) => flags.kind(),
+            // Not all formats support sections of unknown kind.
+            None if convert && in_section.kind() == SectionKind::Unknown => SectionKind::Other,
+            None => in_section.kind(),
+        };
+        // When converting, use the output format's names for the standard sections.
+        let standard = standard_section(name).filter(|standard| {
+            convert && set_flags.is_none() && !standard_sections.contains(standard)
+        });
+        let section_id = if let Some(standard) = standard {
+            standard_sections.push(standard);
+            out_object.section_id(standard)
+        } else {
+            let segment_name = if convert {
+                default_segment_name(&out_object, kind)
+            } else {
+                in_section.segment_name().unwrap().unwrap_or("").as_bytes()
+            };
+            out_object.add_section(segment_name.to_vec(), name.as_bytes().to_vec(), kind)
+        };
         let out_section = out_object.section_mut(section_id);
         if out_section.is_bss() {
             out_section.append_bss(in_section.size(), in_section.align());
         } else {
             out_section.set_data(in_section.data().unwrap(), in_section.align());
         }
-        out_section.flags = in_section.flags();
+        out_section.flags = match set_flags {
+            Some(flags) if out_format == BinaryFormat::Elf => SectionFlags::Elf {
+                sh_flags: flags.elf_flags(),
+            },
+            Some(_) => SectionFlags::None,
+            None if convert => SectionFlags::None,
+            None => in_section.flags(),
+        };
         out_sections.insert(in_section.index(), section_id);
     }
 
+    for (name, data) in &options.add_sections {
+        let kind = match options.set_section_flags.get(name) {
+            Some(flags) => flags.kind(),
+            None => SectionKind::Other,
+        };
+        // Mach-O section names may be given as "segment,section".
+        let (segment_name, section_name) = match name.split_once(',') {
+            Some((segment, section)) if out_format == BinaryFormat::MachO => {
+                (segment.as_bytes(), section)
+            }
+            _ => (default_segment_name(&out_object, kind), name.as_str()),
+        };
+        let section_id = out_object.add_section(
+            segment_name.to_vec(),
+            section_name.as_bytes().to_vec(),
+            kind,
+        );
+        let out_section = out_object.section_mut(section_id);
+        if out_section.is_bss() {
+            out_section.append_bss(data.len() as u64, 1);
+        } else {
+            out_section.set_data(data.clone(), 1);
+        }
+        if let Some(flags) = options.set_section_flags.get(name) {
+            if out_format == BinaryFormat::Elf {
+                out_section.flags = SectionFlags::Elf {
+                    sh_flags: flags.elf_flags(),
+                };
+            }
+        }
+    }
+
     let mut out_symbols = HashMap::new();
     for in_symbol in in_object.symbols() {
         let (section, value) = match in_symbol.section() {
@@ -76,13 +251,23 @@ pub fn copy(in_data: &[u8]) -> Vec<u8> {
                     )
                 } else {
                     // Ignore symbols for sections that we have skipped.
-                    assert_eq!(in_symbol.kind(), SymbolKind::Section);
+                    assert!(
+                        in_symbol.kind() == SymbolKind::Section || options.strip_debug,
+                        "symbol in skipped section: {:?}",
+                        in_symbol
+                    );
                     continue;
                 }
             }
             _ => panic!("unknown symbol section for {:?}", in_symbol),
         };
+        // Mach-O doesn't have section symbols, and other formats
+        // create their own section symbols as needed.
+        if convert && in_symbol.kind() == SymbolKind::Section {
+            continue;
+        }
         let flags = match in_symbol.flags() {
+            _ if convert => SymbolFlags::None,
             SymbolFlags::None => SymbolFlags::None,
             SymbolFlags::Elf { st_info, st_other } => SymbolFlags::Elf { st_info, st_other },
             SymbolFlags::MachO { n_desc } => SymbolFlags::MachO { n_desc },
@@ -114,11 +299,22 @@ pub fn copy(in_data: &[u8]) -> Vec<u8> {
             }
             _ => panic!("unknown symbol flags for {:?}", in_symbol),
         };
+        let name = in_symbol.name().unwrap_or("").as_bytes();
+        let name = options
+            .redefine_syms
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or(name);
+        let kind = match in_symbol.kind() {
+            // Not all formats support untyped symbols.
+            SymbolKind::Unknown if convert => SymbolKind::Data,
+            kind => kind,
+        };
         let out_symbol = write::Symbol {
-            name: in_symbol.name().unwrap_or("").as_bytes().to_vec(),
+            name: name.to_vec(),
             value,
             size: in_symbol.size(),
-            kind: in_symbol.kind(),
+            kind,
             scope: in_symbol.scope(),
             weak: in_symbol.is_weak(),
             section,
@@ -132,31 +328,68 @@ pub fn copy(in_data: &[u8]) -> Vec<u8> {
         if in_section.kind() == SectionKind::Metadata {
             continue;
         }
-        let out_section = *out_sections.get(&in_section.index()).unwrap();
+        let Some(out_section) = out_sections.get(&in_section.index()).copied() else {
+            continue;
+        };
         for (offset, in_relocation) in in_section.relocations() {
             let symbol = match in_relocation.target() {
-                RelocationTarget::Symbol(symbol) => *out_symbols.get(&symbol).unwrap(),
+                RelocationTarget::Symbol(symbol) => match out_symbols.get(&symbol) {
+                    Some(symbol) => *symbol,
+                    None => {
+                        // Section symbols are not copied when converting.
+                        let in_symbol = in_object.symbol_by_index(symbol).unwrap();
+                        let section = in_symbol.section_index().unwrap();
+                        out_object.section_symbol(*out_sections.get(&section).unwrap())
+                    }
+                },
                 RelocationTarget::Section(section) => {
                     out_object.section_symbol(*out_sections.get(&section).unwrap())
                 }
                 _ => panic!("unknown relocation target for {:?}", in_relocation),
             };
+            let flags = if convert {
+                if in_relocation.kind() == RelocationKind::Unknown {
+                    eprintln!(
+                        "Unsupported relocation for conversion: {:?}",
+                        in_relocation.flags()
+                    );
+                    process::exit(1);
+                }
+                // PLT entries are only created by ELF linkers.
+                let kind = match in_relocation.kind() {
+                    RelocationKind::PltRelative if out_format != BinaryFormat::Elf => {
+                        RelocationKind::Relative
+                    }
+                    kind => kind,
+                };
+                RelocationFlags::Generic {
+                    kind,
+                    encoding: in_relocation.encoding(),
+                    size: in_relocation.size(),
+                }
+            } else {
+                in_relocation.flags()
+            };
             let out_relocation = write::Relocation {
                 offset,
                 symbol,
                 addend: in_relocation.addend(),
-                flags: in_relocation.flags(),
+                flags,
             };
-            out_object
-                .add_relocation(out_section, out_relocation)
-                .unwrap();
+            if let Err(err) = out_object.add_relocation(out_section, out_relocation) {
+                eprintln!("Failed to add relocation: {}", err);
+                process::exit(1);
+            }
         }
     }
 
     for in_comdat in in_object.comdats() {
         let mut sections = Vec::new();
         for in_section in in_comdat.sections() {
-            sections.push(*out_sections.get(&in_section).unwrap());
+            // Debug sections may have been stripped.
+            if let Some(out_section) = out_sections.get(&in_section) {
+                sections.push(*out_section);
+            }
         }
         out_object.add_comdat(write::Comdat {
             kind: in_comdat.kind(),
@@ -177,5 +410,46 @@ pub fn copy(in_data: &[u8]) -> Vec<u8> {
         out_object.set_macho_build_version(out_build_version);
     }
 
-    out_object.write().unwrap()
+    match out_object.write() {
+        Ok(out_data) => out_data,
+        Err(err) => {
+            eprintln!("Failed to write file: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Return true if the section contains debugging information.
+fn is_debug(section: &object::Section<'_, '_>) -> bool {
+    match section.kind() {
+        SectionKind::Debug | SectionKind::DebugString => true,
+        _ => section
+            .name()
+            .map(|name| name.starts_with(".debug") || name.starts_with(".zdebug"))
+            .unwrap_or(false),
+    }
+}
+
+/// Return the standard section with the given name in any file format.
+fn standard_section(name: &str) -> Option<StandardSection> {
+    match name {
+        ".text" | "__text" => Some(StandardSection::Text),
+        ".data" | "__data" => Some(StandardSection::Data),
+        ".rodata" | ".rdata" | "__const" => Some(StandardSection::ReadOnlyData),
+        ".bss" | "__bss" => Some(StandardSection::UninitializedData),
+        _ => None,
+    }
+}
+
+/// Return the segment name to use for a section in the output file.
+fn default_segment_name(out_object: &write::Object<'_>, kind: SectionKind) -> &'static [u8] {
+    if out_object.format() != BinaryFormat::MachO {
+        return &[];
+    }
+    let segment = match kind {
+        SectionKind::Text => StandardSegment::Text,
+        SectionKind::Debug | SectionKind::DebugString => StandardSegment::Debug,
+        _ => StandardSegment::Data,
+    };
+    out_object.segment_name(segment)
 }
@@ -1,15 +1,135 @@
 use object::read::archive::ArchiveFile;
 use object::read::coff;
 use object::read::macho::{DyldCache, FatArch, MachOFatFile32, MachOFatFile64};
-use object::{Endianness, FileKind, Object, ObjectComdat, ObjectSection, ObjectSymbol};
+use object::{
+    Endianness, FileKind, Object, ObjectComdat, ObjectSection, ObjectSymbol, RelocationTarget,
+};
 use std::io::{Result, Write};
 
+use crate::demangle;
+
+mod contents;
+mod deps;
+pub use deps::DependencyFormat;
+#[cfg(feature = "disasm")]
+mod disasm;
+
+/// Options for printing files.
+#[derive(Debug, Default, Clone)]
+pub struct PrintOptions {
+    /// Demangle symbol names.
+    pub demangle: bool,
+    /// Print the contents of all sections for [`print_contents`].
+    pub full_contents: bool,
+    /// Sections to print in hexadecimal for [`print_contents`].
+    ///
+    /// Sections may be specified by name or index.
+    pub hex_dump: Vec<String>,
+    /// Sections to print the strings of for [`print_contents`].
+    ///
+    /// Sections may be specified by name or index.
+    pub string_dump: Vec<String>,
+}
+
+impl PrintOptions {
+    /// Return the demangled name if demangling is enabled.
+    fn demangle(&self, name: &[u8]) -> Option<String> {
+        if self.demangle {
+            demangle::demangle_bytes(name)
+        } else {
+            None
+        }
+    }
+}
+
 pub fn print<W: Write, E: Write>(
     w: &mut W,
     e: &mut E,
     file: &[u8],
     extra_files: &[&[u8]],
     member_names: Vec<String>,
+    options: &PrintOptions,
+) -> Result<()> {
+    print_files(
+        w,
+        e,
+        file,
+        extra_files,
+        member_names,
+        options,
+        dump_parsed_object,
+    )
+}
+
+/// Print the raw contents of sections in the file.
+///
+/// The sections to print are selected by `options`.
+pub fn print_contents<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    file: &[u8],
+    extra_files: &[&[u8]],
+    member_names: Vec<String>,
+    options: &PrintOptions,
+) -> Result<()> {
+    print_files(
+        w,
+        e,
+        file,
+        extra_files,
+        member_names,
+        options,
+        contents::print,
+    )
+}
+
+/// Print the disassembly of the text sections in the file.
+///
+/// Only x86 and x86-64 are currently supported.
+#[cfg(feature = "disasm")]
+pub fn print_disassembly<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    file: &[u8],
+    extra_files: &[&[u8]],
+    member_names: Vec<String>,
+    options: &PrintOptions,
+) -> Result<()> {
+    print_files(
+        w,
+        e,
+        file,
+        extra_files,
+        member_names,
+        options,
+        disasm::print,
+    )
+}
+
+/// Print the import and export relationships between the given files.
+///
+/// Each file is given as a path and its data. Imports are matched to the
+/// other files by library name, or by exported symbol name if the import
+/// doesn't specify a library.
+pub fn print_dependencies<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    files: &[(&str, &[u8])],
+    format: DependencyFormat,
+) -> Result<()> {
+    deps::print(w, e, files, format)
+}
+
+type DumpFn<W, E> = fn(&mut W, &mut E, &object::File, &PrintOptions) -> Result<()>;
+
+fn print_files<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    file: &[u8],
+    extra_files: &[&[u8]],
+    member_names: Vec<String>,
+    options: &PrintOptions,
+    dump: DumpFn<W, E>,
 ) -> Result<()> {
     let mut member_names: Vec<_> = member_names.into_iter().map(|name| (name, false)).collect();
 
@@ -25,7 +145,7 @@ pub fn print<W: Write, E: Write>(
                             if FileKind::parse(data) == Ok(FileKind::CoffImport) {
                                 dump_import(w, e, data)?;
                             } else {
-                                dump_object(w, e, data)?;
+                                dump_object(w, e, data, options, dump)?;
                             }
                         }
                     }
@@ -39,7 +159,7 @@ pub fn print<W: Write, E: Write>(
             writeln!(w)?;
             writeln!(w, "Fat Arch: {:?}", arch.architecture())?;
             match arch.data(file) {
-                Ok(data) => dump_object(w, e, data)?,
+                Ok(data) => dump_object(w, e, data, options, dump)?,
                 Err(err) => writeln!(e, "Failed to parse Fat 32 data: {}", err)?,
             }
         }
@@ -49,7 +169,7 @@ pub fn print<W: Write, E: Write>(
             writeln!(w)?;
             writeln!(w, "Fat Arch: {:?}", arch.architecture())?;
             match arch.data(file) {
-                Ok(data) => dump_object(w, e, data)?,
+                Ok(data) => dump_object(w, e, data, options, dump)?,
                 Err(err) => writeln!(e, "Failed to parse Fat 64 data: {}", err)?,
             }
         }
@@ -76,10 +196,10 @@ pub fn print<W: Write, E: Write>(
                     continue;
                 }
             };
-            dump_parsed_object(w, e, &file)?;
+            dump(w, e, &file, options)?;
         }
     } else {
-        dump_object(w, e, file)?;
+        dump_object(w, e, file, options, dump)?;
     }
 
     for (name, found) in member_names {
@@ -103,10 +223,16 @@ fn find_member(member_names: &mut [(String, bool)], name: &[u8]) -> bool {
     }
 }
 
-fn dump_object<W: Write, E: Write>(w: &mut W, e: &mut E, data: &[u8]) -> Result<()> {
+fn dump_object<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    data: &[u8],
+    options: &PrintOptions,
+    dump: DumpFn<W, E>,
+) -> Result<()> {
     match object::File::parse(data) {
         Ok(file) => {
-            dump_parsed_object(w, e, &file)?;
+            dump(w, e, &file, options)?;
         }
         Err(err) => {
             writeln!(e, "Failed to parse file: {}", err)?;
@@ -115,7 +241,12 @@ fn dump_object<W: Write, E: Write>(w: &mut W, e: &mut E, data: &[u8]) -> Result<
     Ok(())
 }
 
-fn dump_parsed_object<W: Write, E: Write>(w: &mut W, e: &mut E, file: &object::File) -> Result<()> {
+fn dump_parsed_object<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    file: &object::File,
+    options: &PrintOptions,
+) -> Result<()> {
     writeln!(
         w,
         "Format: {:?} {:?}-endian {}-bit",
@@ -197,7 +328,8 @@ fn dump_parsed_object<W: Write, E: Write>(w: &mut W, e: &mut E, file: &object::F
     writeln!(w)?;
     writeln!(w, "Symbols")?;
     for symbol in file.symbols() {
-        writeln!(w, "{}: {:x?}", symbol.index(), symbol)?;
+        write!(w, "{}: {:x?}", symbol.index(), symbol)?;
+        print_demangled(w, options, symbol.name_bytes().unwrap_or_default())?;
     }
 
     for section in file.sections() {
@@ -208,7 +340,8 @@ fn dump_parsed_object<W: Write, E: Write>(w: &mut W, e: &mut E, file: &object::F
                 section.name().unwrap_or("<invalid name>")
             )?;
             for relocation in section.relocations() {
-                writeln!(w, "{:x?}", relocation)?;
+                write!(w, "{:x?}", relocation)?;
+                print_relocation_demangled(w, file, options, &relocation.1)?;
             }
         }
     }
@@ -216,14 +349,16 @@ fn dump_parsed_object<W: Write, E: Write>(w: &mut W, e: &mut E, file: &object::F
     writeln!(w)?;
     writeln!(w, "Dynamic symbols")?;
     for symbol in file.dynamic_symbols() {
-        writeln!(w, "{}: {:x?}", symbol.index(), symbol)?;
+        write!(w, "{}: {:x?}", symbol.index(), symbol)?;
+        print_demangled(w, options, symbol.name_bytes().unwrap_or_default())?;
     }
 
     if let Some(relocations) = file.dynamic_relocations() {
         writeln!(w)?;
         writeln!(w, "Dynamic relocations")?;
         for relocation in relocations {
-            writeln!(w, "{:x?}", relocation)?;
+            write!(w, "{:x?}", relocation)?;
+            print_relocation_demangled(w, file, options, &relocation.1)?;
         }
     }
 
@@ -232,7 +367,8 @@ fn dump_parsed_object<W: Write, E: Write>(w: &mut W, e: &mut E, file: &object::F
             if !imports.is_empty() {
                 writeln!(w)?;
                 for import in imports {
-                    writeln!(w, "{:x?}", import)?;
+                    write!(w, "{:x?}", import)?;
+                    print_demangled(w, options, import.name())?;
                 }
             }
         }
@@ -244,7 +380,8 @@ fn dump_parsed_object<W: Write, E: Write>(w: &mut W, e: &mut E, file: &object::F
             if !exports.is_empty() {
                 writeln!(w)?;
                 for export in exports {
-                    writeln!(w, "{:x?}", export)?;
+                    write!(w, "{:x?}", export)?;
+                    print_demangled(w, options, export.name())?;
                 }
             }
         }
@@ -254,12 +391,44 @@ fn dump_parsed_object<W: Write, E: Write>(w: &mut W, e: &mut E, file: &object::F
     writeln!(w)?;
     writeln!(w, "Symbol map")?;
     for symbol in file.symbol_map().symbols() {
-        writeln!(w, "0x{:x} \"{}\"", symbol.address(), symbol.name())?;
+        let name = symbol.name();
+        let demangled = options.demangle(name.as_bytes());
+        writeln!(
+            w,
+            "0x{:x} \"{}\"",
+            symbol.address(),
+            demangled.as_deref().unwrap_or(name)
+        )?;
     }
 
     Ok(())
 }
 
+/// Finish a line, appending the demangled name if there is one.
+fn print_demangled<W: Write>(w: &mut W, options: &PrintOptions, name: &[u8]) -> Result<()> {
+    if let Some(demangled) = options.demangle(name) {
+        write!(w, " Demangled: \"{}\"", demangled)?;
+    }
+    writeln!(w)
+}
+
+/// Finish a line, appending the demangled name of the relocation target if there is one.
+fn print_relocation_demangled<W: Write>(
+    w: &mut W,
+    file: &object::File,
+    options: &PrintOptions,
+    relocation: &object::Relocation,
+) -> Result<()> {
+    let name = match relocation.target() {
+        RelocationTarget::Symbol(index) => file
+            .symbol_by_index(index)
+            .and_then(|symbol| symbol.name_bytes())
+            .unwrap_or_default(),
+        _ => &[],
+    };
+    print_demangled(w, options, name)
+}
+
 fn dump_import<W: Write, E: Write>(w: &mut W, e: &mut E, data: &[u8]) -> Result<()> {
     let file = match coff::ImportFile::parse(data) {
         Ok(import) => import,
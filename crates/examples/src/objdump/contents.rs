@@ -0,0 +1,195 @@
+//! Printing of raw section contents.
+
+use object::read::elf::SectionHeader;
+use object::{elf, Object, ObjectSection, SectionFlags, SectionIndex, SectionKind};
+use std::io::{Result, Write};
+
+use super::PrintOptions;
+
+/// The number of bytes to print on each line.
+const BYTES_PER_LINE: usize = 16;
+
+pub(super) fn print<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    file: &object::File,
+    options: &PrintOptions,
+) -> Result<()> {
+    if options.full_contents {
+        for section in file.sections() {
+            if !has_contents(file, &section) {
+                continue;
+            }
+            let data = match section.data() {
+                Ok(data) => data,
+                Err(err) => {
+                    writeln!(e, "Failed to parse section data: {}", err)?;
+                    continue;
+                }
+            };
+            if data.is_empty() {
+                continue;
+            }
+            writeln!(
+                w,
+                "Contents of section {}:",
+                section.name().unwrap_or("<invalid name>")
+            )?;
+            print_full_contents(w, section.address(), data)?;
+        }
+    }
+
+    for name in &options.hex_dump {
+        if let Some((section_name, address, data)) = find_section(e, file, name)? {
+            writeln!(w, "Hex dump of section '{}':", section_name)?;
+            print_hex_dump(w, address, data)?;
+        }
+    }
+
+    for name in &options.string_dump {
+        if let Some((section_name, _, data)) = find_section(e, file, name)? {
+            writeln!(w, "String dump of section '{}':", section_name)?;
+            print_string_dump(w, data)?;
+        }
+    }
+    Ok(())
+}
+
+/// Return true if the section contents should be printed by `--full-contents`.
+///
+/// This matches GNU objdump, which skips sections without file contents,
+/// as well as non-allocated ELF symbol, string and relocation tables.
+fn has_contents(file: &object::File, section: &object::Section<'_, '_>) -> bool {
+    if matches!(
+        section.kind(),
+        SectionKind::UninitializedData | SectionKind::UninitializedTls
+    ) {
+        return false;
+    }
+    let sh_type = match file {
+        object::File::Elf32(elf) => elf
+            .section_by_index(section.index())
+            .ok()
+            .map(|section| section.elf_section_header().sh_type(elf.endian())),
+        object::File::Elf64(elf) => elf
+            .section_by_index(section.index())
+            .ok()
+            .map(|section| section.elf_section_header().sh_type(elf.endian())),
+        _ => None,
+    };
+    let alloc = match section.flags() {
+        SectionFlags::Elf { sh_flags } => sh_flags & u64::from(elf::SHF_ALLOC) != 0,
+        _ => true,
+    };
+    alloc
+        || !matches!(
+            sh_type,
+            Some(
+                elf::SHT_SYMTAB
+                    | elf::SHT_STRTAB
+                    | elf::SHT_REL
+                    | elf::SHT_RELA
+                    | elf::SHT_RELR
+                    | elf::SHT_SYMTAB_SHNDX
+            )
+        )
+}
+
+/// Find a section by name or index, and return its name, address and data.
+fn find_section<'data, E: Write>(
+    e: &mut E,
+    file: &object::File<'data>,
+    name: &str,
+) -> Result<Option<(String, u64, &'data [u8])>> {
+    let section = match name.parse::<usize>() {
+        Ok(index) => file.section_by_index(SectionIndex(index)).ok(),
+        Err(_) => file.section_by_name(name),
+    };
+    let Some(section) = section else {
+        writeln!(
+            e,
+            "Section '{}' was not dumped because it does not exist",
+            name
+        )?;
+        return Ok(None);
+    };
+    let data = match section.data() {
+        Ok(data) => data,
+        Err(err) => {
+            writeln!(e, "Failed to parse section data: {}", err)?;
+            return Ok(None);
+        }
+    };
+    let section_name = section.name().unwrap_or("<invalid name>").to_string();
+    Ok(Some((section_name, section.address(), data)))
+}
+
+/// Print data in the style of `objdump -s`.
+fn print_full_contents<W: Write>(w: &mut W, address: u64, data: &[u8]) -> Result<()> {
+    let end = address.wrapping_add(data.len() as u64);
+    let width = format!("{:x}", end).len().max(4);
+    for (i, line) in data.chunks(BYTES_PER_LINE).enumerate() {
+        let address = address.wrapping_add((i * BYTES_PER_LINE) as u64);
+        write!(w, " {:01$x} ", address, width)?;
+        print_hex(w, line)?;
+        write!(w, "  ")?;
+        print_ascii(w, line)?;
+        writeln!(w, "{:1$}", "", BYTES_PER_LINE - line.len())?;
+    }
+    Ok(())
+}
+
+/// Print data in the style of `llvm-readelf -x`.
+fn print_hex_dump<W: Write>(w: &mut W, address: u64, data: &[u8]) -> Result<()> {
+    for (i, line) in data.chunks(BYTES_PER_LINE).enumerate() {
+        let address = address.wrapping_add((i * BYTES_PER_LINE) as u64);
+        write!(w, "0x{:08x} ", address)?;
+        print_hex(w, line)?;
+        write!(w, " ")?;
+        print_ascii(w, line)?;
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Print the hex bytes for a line, in groups of 4 bytes.
+///
+/// Missing bytes are padded with spaces.
+fn print_hex<W: Write>(w: &mut W, line: &[u8]) -> Result<()> {
+    for i in 0..BYTES_PER_LINE {
+        match line.get(i) {
+            Some(byte) => write!(w, "{:02x}", byte)?,
+            None => write!(w, "  ")?,
+        }
+        if i % 4 == 3 && i + 1 != BYTES_PER_LINE {
+            write!(w, " ")?;
+        }
+    }
+    Ok(())
+}
+
+fn print_ascii<W: Write>(w: &mut W, line: &[u8]) -> Result<()> {
+    for byte in line {
+        let c = if byte.is_ascii_graphic() || *byte == b' ' {
+            char::from(*byte)
+        } else {
+            '.'
+        };
+        write!(w, "{}", c)?;
+    }
+    Ok(())
+}
+
+/// Print the null terminated strings in the data, in the style of `llvm-readelf -p`.
+fn print_string_dump<W: Write>(w: &mut W, data: &[u8]) -> Result<()> {
+    let mut offset = 0;
+    for s in data.split(|byte| *byte == 0) {
+        if !s.is_empty() {
+            write!(w, "[{:6x}] ", offset)?;
+            print_ascii(w, s)?;
+            writeln!(w)?;
+        }
+        offset += s.len() + 1;
+    }
+    Ok(())
+}
@@ -0,0 +1,315 @@
+//! Import and export dependency graphs for a set of files.
+
+use object::{BinaryFormat, Object};
+use std::io::{Result, Write};
+use std::path::Path;
+
+/// The output format for [`print_dependencies`](super::print_dependencies).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyFormat {
+    /// A JSON array with an object for each file.
+    Json,
+    /// A Graphviz DOT digraph.
+    Dot,
+}
+
+/// The imports and exports of an input file.
+struct Binary {
+    path: String,
+    format: BinaryFormat,
+    exports: Vec<Vec<u8>>,
+    imports: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Binary {
+    /// Return true if this file provides the given library name.
+    fn is_library(&self, library: &[u8]) -> bool {
+        let Some(file_name) = Path::new(&self.path).file_name() else {
+            return false;
+        };
+        let file_name = file_name.to_string_lossy();
+        if self.format == BinaryFormat::Pe {
+            file_name.as_bytes().eq_ignore_ascii_case(library)
+        } else {
+            file_name.as_bytes() == library
+        }
+    }
+
+    fn exports(&self, name: &[u8]) -> bool {
+        self.exports.iter().any(|export| export == name)
+    }
+}
+
+/// The imports of a file from a single library.
+struct Dependency {
+    /// The library name, or empty if it couldn't be determined.
+    library: Vec<u8>,
+    /// The index of the input file that provides the library.
+    file: Option<usize>,
+    symbols: Vec<Vec<u8>>,
+    /// The symbols that aren't exported by `file`.
+    missing: Vec<Vec<u8>>,
+}
+
+pub(super) fn print<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    files: &[(&str, &[u8])],
+    format: DependencyFormat,
+) -> Result<()> {
+    let mut binaries = Vec::new();
+    for (path, data) in files {
+        let file = match object::File::parse(*data) {
+            Ok(file) => file,
+            Err(err) => {
+                writeln!(e, "Failed to parse file '{}': {}", path, err)?;
+                continue;
+            }
+        };
+        let exports = match file.exports() {
+            Ok(exports) => exports
+                .iter()
+                .map(|export| export.name().to_vec())
+                .collect(),
+            Err(err) => {
+                writeln!(e, "Failed to parse exports of '{}': {}", path, err)?;
+                Vec::new()
+            }
+        };
+        let imports = match file.imports() {
+            Ok(imports) => imports
+                .iter()
+                .map(|import| (import.library().to_vec(), import.name().to_vec()))
+                .collect(),
+            Err(err) => {
+                writeln!(e, "Failed to parse imports of '{}': {}", path, err)?;
+                Vec::new()
+            }
+        };
+        binaries.push(Binary {
+            path: path.to_string(),
+            format: file.format(),
+            exports,
+            imports,
+        });
+    }
+
+    let dependencies: Vec<_> = (0..binaries.len())
+        .map(|index| resolve(&binaries, index))
+        .collect();
+    match format {
+        DependencyFormat::Json => print_json(w, &binaries, &dependencies),
+        DependencyFormat::Dot => print_dot(w, &binaries, &dependencies),
+    }
+}
+
+/// Group the imports of a file by library, and match the libraries to the input files.
+///
+/// Imports without a library name (such as unversioned ELF symbols) are matched
+/// to the first other input file that exports them.
+fn resolve(binaries: &[Binary], index: usize) -> Vec<Dependency> {
+    let mut dependencies: Vec<Dependency> = Vec::new();
+    for (library, name) in &binaries[index].imports {
+        let file = if library.is_empty() {
+            binaries
+                .iter()
+                .enumerate()
+                .position(|(i, binary)| i != index && binary.exports(name))
+        } else {
+            binaries
+                .iter()
+                .position(|binary| binary.is_library(library))
+        };
+        let library = match file {
+            Some(file) if library.is_empty() => Path::new(&binaries[file].path)
+                .file_name()
+                .map(|name| name.to_string_lossy().as_bytes().to_vec())
+                .unwrap_or_default(),
+            _ => library.clone(),
+        };
+        let dependency = match dependencies
+            .iter_mut()
+            .position(|dependency| dependency.library == library && dependency.file == file)
+        {
+            Some(i) => &mut dependencies[i],
+            None => {
+                dependencies.push(Dependency {
+                    library,
+                    file,
+                    symbols: Vec::new(),
+                    missing: Vec::new(),
+                });
+                dependencies.last_mut().unwrap()
+            }
+        };
+        dependency.symbols.push(name.clone());
+        if let Some(file) = file {
+            if !binaries[file].exports(name) {
+                dependency.missing.push(name.clone());
+            }
+        }
+    }
+    dependencies
+}
+
+fn print_json<W: Write>(
+    w: &mut W,
+    binaries: &[Binary],
+    dependencies: &[Vec<Dependency>],
+) -> Result<()> {
+    writeln!(w, "[")?;
+    for (i, (binary, dependencies)) in binaries.iter().zip(dependencies).enumerate() {
+        writeln!(w, "  {{")?;
+        write!(w, "    \"Path\": ")?;
+        write_json_string(w, binary.path.as_bytes())?;
+        writeln!(w, ",")?;
+        writeln!(w, "    \"Format\": \"{:?}\",", binary.format)?;
+        write!(w, "    \"Exports\": ")?;
+        write_json_strings(w, &binary.exports)?;
+        writeln!(w, ",")?;
+        write!(w, "    \"Imports\": [")?;
+        for (j, dependency) in dependencies.iter().enumerate() {
+            if j != 0 {
+                write!(w, ",")?;
+            }
+            writeln!(w)?;
+            writeln!(w, "      {{")?;
+            write!(w, "        \"Library\": ")?;
+            if dependency.library.is_empty() {
+                write!(w, "null")?;
+            } else {
+                write_json_string(w, &dependency.library)?;
+            }
+            writeln!(w, ",")?;
+            write!(w, "        \"File\": ")?;
+            match dependency.file {
+                Some(file) => write_json_string(w, binaries[file].path.as_bytes())?,
+                None => write!(w, "null")?,
+            }
+            writeln!(w, ",")?;
+            write!(w, "        \"Symbols\": ")?;
+            write_json_strings(w, &dependency.symbols)?;
+            writeln!(w, ",")?;
+            write!(w, "        \"Missing\": ")?;
+            write_json_strings(w, &dependency.missing)?;
+            writeln!(w)?;
+            write!(w, "      }}")?;
+        }
+        if !dependencies.is_empty() {
+            writeln!(w)?;
+            write!(w, "    ")?;
+        }
+        writeln!(w, "]")?;
+        write!(w, "  }}")?;
+        if i + 1 != binaries.len() {
+            write!(w, ",")?;
+        }
+        writeln!(w)?;
+    }
+    writeln!(w, "]")
+}
+
+fn write_json_strings<W: Write>(w: &mut W, strings: &[Vec<u8>]) -> Result<()> {
+    write!(w, "[")?;
+    for (i, s) in strings.iter().enumerate() {
+        if i != 0 {
+            write!(w, ", ")?;
+        }
+        write_json_string(w, s)?;
+    }
+    write!(w, "]")
+}
+
+fn write_json_string<W: Write>(w: &mut W, s: &[u8]) -> Result<()> {
+    write!(w, "\"")?;
+    for c in String::from_utf8_lossy(s).chars() {
+        match c {
+            '"' => write!(w, "\\\"")?,
+            '\\' => write!(w, "\\\\")?,
+            '\n' => write!(w, "\\n")?,
+            '\r' => write!(w, "\\r")?,
+            '\t' => write!(w, "\\t")?,
+            c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+            c => write!(w, "{}", c)?,
+        }
+    }
+    write!(w, "\"")
+}
+
+/// Print a digraph with a node for each file and library.
+///
+/// Input files are boxes, and other libraries are ellipses. Edges are labelled
+/// with the number of imported symbols, and are red if any are missing.
+fn print_dot<W: Write>(
+    w: &mut W,
+    binaries: &[Binary],
+    dependencies: &[Vec<Dependency>],
+) -> Result<()> {
+    writeln!(w, "digraph dependencies {{")?;
+    for binary in binaries {
+        writeln!(
+            w,
+            "    {} [shape=box, label={}];",
+            dot_id(binary.path.as_bytes()),
+            dot_id(file_name(&binary.path).as_bytes())
+        )?;
+    }
+    let mut libraries: Vec<&[u8]> = Vec::new();
+    for dependency in dependencies.iter().flatten() {
+        if dependency.file.is_none() && !libraries.contains(&&*dependency.library) {
+            libraries.push(&dependency.library);
+        }
+    }
+    for library in &libraries {
+        let label = if library.is_empty() {
+            "<unresolved>".as_bytes()
+        } else {
+            library
+        };
+        writeln!(
+            w,
+            "    {} [shape=ellipse, label={}];",
+            dot_library_id(library),
+            dot_id(label)
+        )?;
+    }
+    for (binary, dependencies) in binaries.iter().zip(dependencies) {
+        for dependency in dependencies {
+            let target = match dependency.file {
+                Some(file) => dot_id(binaries[file].path.as_bytes()),
+                None => dot_library_id(&dependency.library),
+            };
+            write!(
+                w,
+                "    {} -> {} [label=\"{}\"",
+                dot_id(binary.path.as_bytes()),
+                target,
+                dependency.symbols.len()
+            )?;
+            if !dependency.missing.is_empty() {
+                write!(w, ", color=red")?;
+            }
+            writeln!(w, "];")?;
+        }
+    }
+    writeln!(w, "}}")
+}
+
+/// Libraries use a separate namespace so they can't collide with file paths.
+fn dot_library_id(library: &[u8]) -> String {
+    let mut id = b"lib:".to_vec();
+    id.extend_from_slice(library);
+    dot_id(&id)
+}
+
+fn dot_id(s: &[u8]) -> String {
+    let s = String::from_utf8_lossy(s);
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
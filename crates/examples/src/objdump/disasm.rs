@@ -0,0 +1,241 @@
+//! Disassembly of text sections using `iced-x86`.
+
+use iced_x86::{
+    Decoder, DecoderOptions, Formatter, GasFormatter, Instruction, OpKind, SymbolResolver,
+    SymbolResult,
+};
+use object::{
+    Architecture, Object, ObjectKind, ObjectSection, ObjectSymbol, Relocation, RelocationKind,
+    RelocationTarget, SectionIndex, SectionKind, SymbolKind,
+};
+use std::io::{Result, Write};
+
+use super::PrintOptions;
+
+/// The maximum number of instruction bytes to print on each line.
+const BYTES_PER_LINE: usize = 7;
+
+pub(super) fn print<W: Write, E: Write>(
+    w: &mut W,
+    e: &mut E,
+    file: &object::File,
+    options: &PrintOptions,
+) -> Result<()> {
+    let bitness = match file.architecture() {
+        Architecture::I386 => 32,
+        Architecture::X86_64 | Architecture::X86_64_X32 => 64,
+        architecture => {
+            writeln!(
+                e,
+                "Disassembly is not supported for architecture {:?}",
+                architecture
+            )?;
+            return Ok(());
+        }
+    };
+    let address_width = if file.is_64() { 16 } else { 8 };
+
+    // Sections in relocatable files may overlap, so branch targets can only be
+    // resolved to symbols in the same section. Otherwise, use all symbols.
+    let relocatable = file.kind() == ObjectKind::Relocatable;
+    let file_symbols = if relocatable {
+        None
+    } else {
+        let symbol_map = file.symbol_map();
+        Some(Symbols(
+            symbol_map
+                .symbols()
+                .iter()
+                .map(|symbol| (symbol.address(), symbol_name(options, symbol.name())))
+                .collect(),
+        ))
+    };
+
+    for section in file.sections() {
+        if section.kind() != SectionKind::Text {
+            continue;
+        }
+        let data = match section.data() {
+            Ok(data) => data,
+            Err(err) => {
+                writeln!(e, "Failed to parse section data: {}", err)?;
+                continue;
+            }
+        };
+        if data.is_empty() {
+            continue;
+        }
+        let section_address = section.address();
+        let labels = section_symbols(file, options, section.index());
+        let symbols = file_symbols.as_ref().unwrap_or(&labels).clone();
+
+        let mut relocations: Vec<_> = section
+            .relocations()
+            .map(|(offset, relocation)| {
+                // Convert addresses to section offsets.
+                let offset = if relocatable {
+                    offset
+                } else {
+                    offset.wrapping_sub(section_address)
+                };
+                (offset, relocation)
+            })
+            .collect();
+        relocations.sort_by_key(|(offset, _)| *offset);
+        let mut relocations = relocations.iter().peekable();
+
+        writeln!(w)?;
+        writeln!(
+            w,
+            "Disassembly of section {}:",
+            section.name().unwrap_or("<invalid name>")
+        )?;
+
+        let mut formatter = GasFormatter::with_options(Some(Box::new(symbols)), None);
+        let formatter_options = formatter.options_mut();
+        formatter_options.set_first_operand_char_index(7);
+        formatter_options.set_show_symbol_address(true);
+        formatter_options.set_space_after_operand_separator(false);
+        formatter_options.set_uppercase_hex(false);
+        formatter_options.set_small_hex_numbers_in_decimal(false);
+        formatter_options.set_branch_leading_zeros(false);
+
+        let mut decoder = Decoder::with_ip(bitness, data, section_address, DecoderOptions::NONE);
+        let mut instruction = Instruction::default();
+        let mut text = String::new();
+        let mut labels = labels.0.iter().peekable();
+        while decoder.can_decode() {
+            let offset = decoder.position();
+            decoder.decode_out(&mut instruction);
+            let address = instruction.ip();
+            let end = offset + instruction.len();
+
+            while let Some((label_address, name)) = labels.next_if(|(a, _)| *a <= address) {
+                if *label_address == address {
+                    writeln!(w)?;
+                    writeln!(w, "{:01$x} <{2}>:", address, address_width, name)?;
+                }
+            }
+
+            text.clear();
+            formatter.format(&instruction, &mut text);
+            for (i, bytes) in data[offset..end].chunks(BYTES_PER_LINE).enumerate() {
+                let address = address + (i * BYTES_PER_LINE) as u64;
+                write!(w, "{:8x}:\t", address)?;
+                for byte in bytes {
+                    write!(w, "{:02x} ", byte)?;
+                }
+                if i == 0 {
+                    let padding = (BYTES_PER_LINE - bytes.len()) * 3;
+                    write!(w, "{:1$}\t{2}", "", padding, text)?;
+                }
+                writeln!(w)?;
+            }
+
+            while let Some((offset, relocation)) =
+                relocations.next_if(|(offset, _)| *offset < end as u64)
+            {
+                write!(w, "\t\t\t{:x}: ", section_address.wrapping_add(*offset))?;
+                print_relocation(w, file, options, relocation)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print a relocation that applies to an instruction.
+fn print_relocation<W: Write>(
+    w: &mut W,
+    file: &object::File,
+    options: &PrintOptions,
+    relocation: &Relocation,
+) -> Result<()> {
+    match relocation.kind() {
+        RelocationKind::Unknown => write!(w, "{:?}", relocation.flags())?,
+        kind => write!(w, "{:?}({})", kind, relocation.size())?,
+    }
+    write!(w, "\t")?;
+    match relocation.target() {
+        RelocationTarget::Symbol(index) => match file.symbol_by_index(index) {
+            Ok(symbol) => match symbol.section_index() {
+                Some(section) if symbol.kind() == SymbolKind::Section => {
+                    write!(w, "{}", section_name(file, section))?
+                }
+                _ => write!(
+                    w,
+                    "{}",
+                    symbol_name(options, symbol.name().unwrap_or("<invalid name>"))
+                )?,
+            },
+            Err(_) => write!(w, "<invalid symbol {}>", index.0)?,
+        },
+        RelocationTarget::Section(index) => write!(w, "{}", section_name(file, index))?,
+        RelocationTarget::Absolute => write!(w, "*ABS*")?,
+        _ => write!(w, "<unknown>")?,
+    }
+    let addend = relocation.addend();
+    if addend > 0 {
+        write!(w, "+{:#x}", addend)?;
+    } else if addend < 0 {
+        write!(w, "-{:#x}", addend.unsigned_abs())?;
+    }
+    writeln!(w)
+}
+
+fn section_name<'a>(file: &'a object::File, index: SectionIndex) -> &'a str {
+    file.section_by_index(index)
+        .and_then(|section| section.name())
+        .unwrap_or("<invalid name>")
+}
+
+/// Return the symbol name, demangled if enabled.
+fn symbol_name(options: &PrintOptions, name: &str) -> String {
+    options
+        .demangle(name.as_bytes())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Return the named symbols that are defined in a section, sorted by address.
+fn section_symbols(file: &object::File, options: &PrintOptions, index: SectionIndex) -> Symbols {
+    let mut symbols: Vec<_> = file
+        .symbols()
+        .filter(|symbol| {
+            symbol.section_index() == Some(index)
+                && !matches!(symbol.kind(), SymbolKind::Section | SymbolKind::File)
+        })
+        .filter_map(|symbol| {
+            let name = symbol.name().ok().filter(|name| !name.is_empty())?;
+            Some((symbol.address(), symbol_name(options, name)))
+        })
+        .collect();
+    symbols.sort_by_key(|(address, _)| *address);
+    symbols.dedup_by_key(|(address, _)| *address);
+    Symbols(symbols)
+}
+
+/// Symbols sorted by address, used for labels and resolving branch targets.
+#[derive(Clone)]
+struct Symbols(Vec<(u64, String)>);
+
+impl SymbolResolver for Symbols {
+    fn symbol(
+        &mut self,
+        instruction: &Instruction,
+        _operand: u32,
+        instruction_operand: Option<u32>,
+        address: u64,
+        _address_size: u32,
+    ) -> Option<SymbolResult<'_>> {
+        let is_target = match instruction.op_kind(instruction_operand?) {
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => true,
+            OpKind::Memory => instruction.is_ip_rel_memory_operand(),
+            _ => false,
+        };
+        if !is_target {
+            return None;
+        }
+        let index = self.0.partition_point(|(a, _)| *a <= address);
+        let (symbol_address, name) = &self.0[index.checked_sub(1)?];
+        Some(SymbolResult::with_str(*symbol_address, name))
+    }
+}
@@ -0,0 +1,343 @@
+//! Printing of the differences between two files.
+//!
+//! This uses the unified read API, so that it works for all file formats.
+
+use std::collections::HashMap;
+use std::io::{Result, Write};
+
+use object::{Object, ObjectSection, ObjectSymbol, SymbolSection};
+
+use super::PrintOptions;
+use crate::demangle;
+
+/// An item that is compared between the two files.
+struct Item<'data> {
+    /// The name used to match items, made unique if needed.
+    key: String,
+    /// The name to print.
+    name: String,
+    /// Properties that are compared, in the order they are printed.
+    fields: Vec<(&'static str, String)>,
+    /// Data that is compared but not printed.
+    contents: Option<&'data [u8]>,
+}
+
+/// Print the differences between the headers, sections, symbols, imports and
+/// exports of two files.
+pub fn print_diff(
+    w: &mut dyn Write,
+    e: &mut dyn Write,
+    old: &[u8],
+    new: &[u8],
+    options: &PrintOptions,
+) -> Result<()> {
+    let old = match object::File::parse(old) {
+        Ok(file) => file,
+        Err(err) => return writeln!(e, "Failed to parse old file: {}", err),
+    };
+    let new = match object::File::parse(new) {
+        Ok(file) => file,
+        Err(err) => return writeln!(e, "Failed to parse new file: {}", err),
+    };
+
+    let mut changed = false;
+    changed |= diff_fields(w, "Header", &header(&old), &header(&new))?;
+    changed |= diff_items(w, "Sections", sections(&old), sections(&new))?;
+    changed |= diff_items(
+        w,
+        "Symbols",
+        symbols(&old, old.symbols(), options),
+        symbols(&new, new.symbols(), options),
+    )?;
+    changed |= diff_items(
+        w,
+        "DynamicSymbols",
+        symbols(&old, old.dynamic_symbols(), options),
+        symbols(&new, new.dynamic_symbols(), options),
+    )?;
+    changed |= diff_items(
+        w,
+        "Imports",
+        imports(e, &old, options)?,
+        imports(e, &new, options)?,
+    )?;
+    changed |= diff_items(
+        w,
+        "Exports",
+        exports(e, &old, options)?,
+        exports(e, &new, options)?,
+    )?;
+    if !changed {
+        writeln!(w, "No differences")?;
+    }
+    Ok(())
+}
+
+fn header(file: &object::File<'_>) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("Format", format!("{:?}", file.format())),
+        ("Architecture", format!("{:?}", file.architecture())),
+        ("SubArchitecture", format!("{:?}", file.sub_architecture())),
+        ("Endianness", format!("{:?}", file.endianness())),
+        ("Is64", format!("{}", file.is_64())),
+        ("Kind", format!("{:?}", file.kind())),
+        ("Entry", format!("{:#x}", file.entry())),
+        ("Flags", format!("{:x?}", file.flags())),
+        (
+            "RelativeAddressBase",
+            format!("{:#x}", file.relative_address_base()),
+        ),
+    ];
+    if let Ok(Some(build_id)) = file.build_id() {
+        fields.push(("BuildId", hex(build_id)));
+    }
+    if let Ok(Some((filename, crc))) = file.gnu_debuglink() {
+        fields.push((
+            "GnuDebugLink",
+            format!("{} {:08x}", String::from_utf8_lossy(filename), crc),
+        ));
+    }
+    if let Ok(Some(uuid)) = file.mach_uuid() {
+        fields.push(("MachUuid", hex(&uuid)));
+    }
+    fields
+}
+
+fn sections<'data>(file: &object::File<'data>) -> Vec<Item<'data>> {
+    let items = file.sections().map(|section| {
+        let name = section.name().unwrap_or("<invalid name>").to_string();
+        let mut fields = vec![
+            ("Address", format!("{:#x}", section.address())),
+            ("Size", format!("{:#x}", section.size())),
+            ("Align", format!("{:#x}", section.align())),
+            ("Kind", format!("{:?}", section.kind())),
+            ("Flags", format!("{:x?}", section.flags())),
+        ];
+        if let Some(segment) = section.segment_name().ok().flatten() {
+            fields.push(("Segment", segment.to_string()));
+        }
+        Item {
+            key: name.clone(),
+            name,
+            fields,
+            contents: section.data().ok(),
+        }
+    });
+    unique_items(items.collect())
+}
+
+fn symbols<'data, 'file>(
+    file: &'file object::File<'data>,
+    symbols: object::SymbolIterator<'data, 'file>,
+    options: &PrintOptions,
+) -> Vec<Item<'data>> {
+    let items = symbols.filter_map(|symbol| {
+        let key = symbol.name().ok().filter(|name| !name.is_empty())?;
+        let section = match symbol.section() {
+            SymbolSection::Section(index) => file
+                .section_by_index(index)
+                .and_then(|section| section.name())
+                .unwrap_or("<invalid name>")
+                .to_string(),
+            section => format!("{:?}", section),
+        };
+        let fields = vec![
+            ("Address", format!("{:#x}", symbol.address())),
+            ("Size", format!("{:#x}", symbol.size())),
+            ("Kind", format!("{:?}", symbol.kind())),
+            ("Section", section),
+            ("Scope", format!("{:?}", symbol.scope())),
+            ("Weak", format!("{}", symbol.is_weak())),
+        ];
+        Some(Item {
+            key: key.to_string(),
+            name: display_name(key.as_bytes(), options),
+            fields,
+            contents: None,
+        })
+    });
+    unique_items(items.collect())
+}
+
+fn imports<'data>(
+    e: &mut dyn Write,
+    file: &object::File<'data>,
+    options: &PrintOptions,
+) -> Result<Vec<Item<'data>>> {
+    let imports = match file.imports() {
+        Ok(imports) => imports,
+        Err(err) => {
+            writeln!(e, "Failed to parse imports: {}", err)?;
+            return Ok(Vec::new());
+        }
+    };
+    let items = imports.iter().map(|import| {
+        let library = String::from_utf8_lossy(import.library());
+        let name = display_name(import.name(), options);
+        let key = format!("{}!{}", library, String::from_utf8_lossy(import.name()));
+        Item {
+            key,
+            name,
+            fields: vec![("Library", library.into_owned())],
+            contents: None,
+        }
+    });
+    Ok(unique_items(items.collect()))
+}
+
+fn exports<'data>(
+    e: &mut dyn Write,
+    file: &object::File<'data>,
+    options: &PrintOptions,
+) -> Result<Vec<Item<'data>>> {
+    let exports = match file.exports() {
+        Ok(exports) => exports,
+        Err(err) => {
+            writeln!(e, "Failed to parse exports: {}", err)?;
+            return Ok(Vec::new());
+        }
+    };
+    let items = exports.iter().map(|export| {
+        let key = String::from_utf8_lossy(export.name()).into_owned();
+        let name = display_name(export.name(), options);
+        Item {
+            key,
+            name,
+            fields: vec![("Address", format!("{:#x}", export.address()))],
+            contents: None,
+        }
+    });
+    Ok(unique_items(items.collect()))
+}
+
+/// Append a suffix to duplicate keys and names.
+///
+/// Duplicates are matched in the order they occur in each file.
+fn unique_items(mut items: Vec<Item<'_>>) -> Vec<Item<'_>> {
+    let mut counts = HashMap::new();
+    for item in &mut items {
+        let count = counts.entry(item.key.clone()).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            item.key = format!("{} #{}", item.key, count);
+            item.name = format!("{} #{}", item.name, count);
+        }
+    }
+    items
+}
+
+fn display_name(name: &[u8], options: &PrintOptions) -> String {
+    if options.demangle {
+        if let Some(demangled) = demangle::demangle_bytes(name) {
+            return demangled;
+        }
+    }
+    String::from_utf8_lossy(name).into_owned()
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Print the fields that changed, and return true if there were any.
+fn diff_fields(
+    w: &mut dyn Write,
+    title: &str,
+    old: &[(&'static str, String)],
+    new: &[(&'static str, String)],
+) -> Result<bool> {
+    let changes = field_changes(old, new);
+    if changes.is_empty() {
+        return Ok(false);
+    }
+    writeln!(w, "{} {{", title)?;
+    for change in changes {
+        writeln!(w, "    {}", change)?;
+    }
+    writeln!(w, "}}")?;
+    Ok(true)
+}
+
+/// Return a description of each field that was added, removed or changed.
+fn field_changes(old: &[(&'static str, String)], new: &[(&'static str, String)]) -> Vec<String> {
+    let mut changes = Vec::new();
+    for (name, old_value) in old {
+        match new.iter().find(|(new_name, _)| new_name == name) {
+            Some((_, new_value)) if new_value != old_value => {
+                changes.push(format!("{}: {} -> {}", name, old_value, new_value));
+            }
+            Some(_) => {}
+            None => changes.push(format!("{}: {} -> <none>", name, old_value)),
+        }
+    }
+    for (name, new_value) in new {
+        if !old.iter().any(|(old_name, _)| old_name == name) {
+            changes.push(format!("{}: <none> -> {}", name, new_value));
+        }
+    }
+    changes
+}
+
+/// Print the items that were removed, changed or added, and return true if there were any.
+fn diff_items(
+    w: &mut dyn Write,
+    title: &str,
+    old: Vec<Item<'_>>,
+    new: Vec<Item<'_>>,
+) -> Result<bool> {
+    let new_keys: HashMap<_, _> = new
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.key.as_str(), i))
+        .collect();
+    let old_keys: HashMap<_, _> = old
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.key.as_str(), i))
+        .collect();
+
+    let mut lines = Vec::new();
+    for old_item in &old {
+        let Some(new_item) = new_keys.get(old_item.key.as_str()).map(|i| &new[*i]) else {
+            lines.push(format!("- {}{}", old_item.name, summary(old_item)));
+            continue;
+        };
+        let mut changes = field_changes(&old_item.fields, &new_item.fields);
+        if old_item.contents != new_item.contents {
+            changes.push("Contents changed".to_string());
+        }
+        if !changes.is_empty() {
+            lines.push(format!("~ {}", old_item.name));
+            for change in changes {
+                lines.push(format!("    {}", change));
+            }
+        }
+    }
+    for new_item in &new {
+        if !old_keys.contains_key(new_item.key.as_str()) {
+            lines.push(format!("+ {}{}", new_item.name, summary(new_item)));
+        }
+    }
+
+    if lines.is_empty() {
+        return Ok(false);
+    }
+    writeln!(w, "{} {{", title)?;
+    for line in lines {
+        writeln!(w, "    {}", line)?;
+    }
+    writeln!(w, "}}")?;
+    Ok(true)
+}
+
+/// Return a summary of the fields of an item that was added or removed.
+fn summary(item: &Item<'_>) -> String {
+    let mut s = String::new();
+    for (i, (name, value)) in item.fields.iter().enumerate() {
+        s.push_str(if i == 0 { ": " } else { ", " });
+        s.push_str(name);
+        s.push(' ');
+        s.push_str(value);
+    }
+    s
+}
@@ -3,16 +3,27 @@ use object::elf::*;
 use object::read::elf::*;
 use object::read::{SectionIndex, StringTable, SymbolIndex};
 
+mod gnu;
+mod hash;
+
 pub(super) fn print_elf32(p: &mut Printer<'_>, data: &[u8]) {
     if let Some(elf) = FileHeader32::<Endianness>::parse(data).print_err(p) {
-        writeln!(p.w(), "Format: ELF 32-bit").unwrap();
+        if p.options.elf_gnu_style {
+            gnu::print_elf(p, elf, data);
+            return;
+        }
+        p.format(format_args!("ELF 32-bit"));
         print_elf(p, elf, data);
     }
 }
 
 pub(super) fn print_elf64(p: &mut Printer<'_>, data: &[u8]) {
     if let Some(elf) = FileHeader64::<Endianness>::parse(data).print_err(p) {
-        writeln!(p.w(), "Format: ELF 64-bit").unwrap();
+        if p.options.elf_gnu_style {
+            gnu::print_elf(p, elf, data);
+            return;
+        }
+        p.format(format_args!("ELF 64-bit"));
         print_elf(p, elf, data);
     }
 }
@@ -98,13 +109,13 @@ fn print_file_header<Elf: FileHeader>(p: &mut Printer<'_>, endian: Elf::Endian,
 }
 
 fn print_ident(p: &mut Printer<'_>, ident: &Ident) {
-    p.field("Magic", format!("{:X?}", ident.magic));
+    p.field_bytes("Magic", &ident.magic);
     p.field_enum("Class", ident.class, FLAGS_EI_CLASS);
     p.field_enum("Data", ident.data, FLAGS_EI_DATA);
     p.field_enum("Version", ident.version, FLAGS_EV);
     p.field_enum("OsAbi", ident.os_abi, FLAGS_EI_OSABI);
     p.field_hex("AbiVersion", ident.abi_version);
-    p.field("Unused", format!("{:X?}", ident.padding));
+    p.field_bytes("Unused", &ident.padding);
 }
 
 fn print_program_headers<Elf: FileHeader>(
@@ -252,28 +263,25 @@ fn print_section_headers<Elf: FileHeader>(
             && !(p.options.elf_versions && sh_type == SHT_GNU_VERNEED)
             && !(p.options.elf_versions && sh_type == SHT_GNU_VERSYM)
             && !(p.options.elf_attributes && sh_type == SHT_GNU_ATTRIBUTES)
+            && !(p.options.elf_hash_check && sh_type == SHT_HASH)
+            && !(p.options.elf_hash_check && sh_type == SHT_GNU_HASH)
         {
             continue;
         }
+        let name = sections.section_name(endian, section);
+        if !p.options.filter_section(index.0, name.ok()) {
+            continue;
+        }
         p.group("SectionHeader", |p| {
             p.field("Index", index.0);
-            p.field_string(
-                "Name",
-                section.sh_name(endian),
-                sections.section_name(endian, section),
-            );
+            p.field_string("Name", section.sh_name(endian), name);
 
-            let proc = match elf.e_machine(endian) {
-                EM_MIPS => FLAGS_SHT_MIPS,
-                EM_PARISC => FLAGS_SHT_PARISC,
-                EM_ALPHA => FLAGS_SHT_ALPHA,
-                EM_ARM => FLAGS_SHT_ARM,
-                EM_CSKY => FLAGS_SHT_CSKY,
-                EM_IA_64 => FLAGS_SHT_IA_64,
-                EM_X86_64 => FLAGS_SHT_X86_64,
-                _ => &[],
-            };
-            p.field_enums("Type", section.sh_type(endian), &[FLAGS_SHT, proc]);
+            p.field_enums_ranges(
+                "Type",
+                section.sh_type(endian),
+                &sht_flags(elf.e_machine(endian), elf.e_ident().os_abi),
+                RANGES_SHT,
+            );
 
             let flags = section.sh_flags(endian).into();
             p.field_hex("Flags", flags);
@@ -305,15 +313,11 @@ fn print_section_headers<Elf: FileHeader>(
             }
 
             match section.sh_type(endian) {
-                SHT_SYMTAB => {
-                    if p.options.symbols {
-                        print_section_symbols(p, endian, data, elf, sections, index, section);
-                    }
+                SHT_SYMTAB if p.options.symbols => {
+                    print_section_symbols(p, endian, data, elf, sections, index, section);
                 }
-                SHT_DYNSYM => {
-                    if p.options.elf_dynamic_symbols {
-                        print_section_symbols(p, endian, data, elf, sections, index, section);
-                    }
+                SHT_DYNSYM if p.options.elf_dynamic_symbols => {
+                    print_section_symbols(p, endian, data, elf, sections, index, section);
                 }
                 SHT_REL => print_section_rel(p, endian, data, elf, sections, section),
                 SHT_RELA => print_section_rela(p, endian, data, elf, sections, section),
@@ -336,15 +340,11 @@ fn print_section_headers<Elf: FileHeader>(
                 _ => {}
             }
             match elf.e_machine(endian) {
-                EM_ARM => {
-                    if section.sh_type(endian) == SHT_ARM_ATTRIBUTES {
-                        print_attributes(p, endian, data, elf, section);
-                    }
+                EM_ARM if section.sh_type(endian) == SHT_ARM_ATTRIBUTES => {
+                    print_attributes(p, endian, data, elf, section);
                 }
-                EM_AARCH64 => {
-                    if section.sh_type(endian) == SHT_AARCH64_ATTRIBUTES {
-                        print_attributes(p, endian, data, elf, section);
-                    }
+                EM_AARCH64 if section.sh_type(endian) == SHT_AARCH64_ATTRIBUTES => {
+                    print_attributes(p, endian, data, elf, section);
                 }
                 _ => {}
             }
@@ -370,17 +370,7 @@ fn print_section_symbols<Elf: FileHeader>(
         } else {
             None
         };
-        let os_stt = match elf.e_ident().os_abi {
-            ELFOSABI_GNU => FLAGS_STT_GNU,
-            ELFOSABI_HPUX => FLAGS_STT_HP,
-            _ => &[],
-        };
-        let proc_stt = match elf.e_machine(endian) {
-            EM_SPARC => FLAGS_STT_SPARC,
-            EM_PARISC => FLAGS_STT_PARISC,
-            EM_ARM => FLAGS_STT_ARM,
-            _ => &[],
-        };
+        let stt = stt_flags(elf.e_machine(endian), elf.e_ident().os_abi);
         let os_stb = match elf.e_ident().os_abi {
             ELFOSABI_GNU => FLAGS_STB_GNU,
             _ => &[],
@@ -395,16 +385,17 @@ fn print_section_symbols<Elf: FileHeader>(
             _ => &[],
         };
         for (index, symbol) in symbols.enumerate() {
+            let name = symbol.name(endian, symbols.strings());
+            if !p.options.filter_symbol(index.0, name.ok()) {
+                continue;
+            }
             p.group("Symbol", |p| {
                 p.field("Index", index.0);
                 if index == SymbolIndex(0) {
                     p.field_hex("Name", symbol.st_name(endian));
                 } else {
-                    p.field_string(
-                        "Name",
-                        symbol.st_name(endian),
-                        symbol.name(endian, symbols.strings()),
-                    );
+                    let name = name.map(|name| p.demangle(name));
+                    p.field_string("Name", symbol.st_name(endian), name.as_deref());
                 }
                 if let Some(versions) = versions.as_ref() {
                     let version_index = versions.version_index(endian, index);
@@ -412,7 +403,7 @@ fn print_section_symbols<Elf: FileHeader>(
                 }
                 p.field_hex("Value", symbol.st_value(endian).into());
                 p.field_hex("Size", symbol.st_size(endian).into());
-                p.field_enums("Type", symbol.st_type(), &[FLAGS_STT, os_stt, proc_stt]);
+                p.field_enums_ranges("Type", symbol.st_type(), &stt, RANGES_STT);
                 p.field_enums("Bind", symbol.st_bind(), &[FLAGS_STB, os_stb, proc_stb]);
 
                 let other = symbol.st_other();
@@ -492,11 +483,12 @@ fn print_section_rela<Elf: FileHeader>(
         for relocation in relocations {
             p.group("Relocation", |p| {
                 p.field_hex("Offset", relocation.r_offset(endian).into());
-                p.field_enum(
-                    "Type",
-                    relocation.r_type(endian, elf.is_mips64el(endian)),
-                    proc,
-                );
+                let r_type = relocation.r_type(endian, elf.is_mips64el(endian));
+                if elf.e_machine(endian) == EM_MIPS && elf.is_type_64() {
+                    print_mips64_rel_type(p, r_type);
+                } else {
+                    p.field_enum("Type", r_type, proc);
+                }
                 let sym = relocation.symbol(endian, elf.is_mips64el(endian));
                 print_rel_symbol(p, endian, symbols, sym);
                 let addend = relocation.r_addend(endian).into() as u64;
@@ -508,6 +500,20 @@ fn print_section_rela<Elf: FileHeader>(
     }
 }
 
+fn print_mips64_rel_type(p: &mut Printer<'_>, r_type: u32) {
+    let r_type = Mips64RelocationType::from_r_type(r_type);
+    p.field_enum("Type", r_type.r_type.into(), FLAGS_R_MIPS);
+    if u32::from(r_type.r_type2) != R_MIPS_NONE {
+        p.field_enum("Type2", r_type.r_type2.into(), FLAGS_R_MIPS);
+    }
+    if u32::from(r_type.r_type3) != R_MIPS_NONE {
+        p.field_enum("Type3", r_type.r_type3.into(), FLAGS_R_MIPS);
+    }
+    if r_type.r_ssym != RSS_UNDEF {
+        p.field_enum("SpecialSym", r_type.r_ssym, FLAGS_RSS);
+    }
+}
+
 fn print_rel_symbol<Elf: FileHeader>(
     p: &mut Printer<'_>,
     endian: Elf::Endian,
@@ -524,7 +530,8 @@ fn print_rel_symbol<Elf: FileHeader>(
             .and_then(|symbol| symbol.name(endian, symbols.strings()))
             .print_err(p)
     });
-    p.field_string_option("Symbol", index.0, name);
+    let name = name.map(|name| p.demangle(name));
+    p.field_string_option("Symbol", index.0, name.as_deref());
 }
 
 fn rel_flag_type<Elf: FileHeader>(endian: Elf::Endian, elf: &Elf) -> &'static [Flag<u32>] {
@@ -623,16 +630,23 @@ fn print_section_group<Elf: FileHeader>(
         p.group("GroupSections", |p| {
             for member in members {
                 let index = member.get(endian);
-                p.print_indent();
-                if let Some(section) = sections.section(SectionIndex(index as usize)).print_err(p) {
-                    if let Some(name) = sections.section_name(endian, section).print_err(p) {
-                        p.print_string(name);
-                        writeln!(p.w, " ({})", index).unwrap();
-                    } else {
-                        writeln!(p.w, "{}", index).unwrap();
-                    }
+                let name = sections
+                    .section(SectionIndex(index as usize))
+                    .print_err(p)
+                    .and_then(|section| sections.section_name(endian, section).print_err(p));
+                if let Some(name) = name {
+                    p.field_raw(
+                        "",
+                        format_args!("{} ({})", PrintString(name), index),
+                        || {
+                            json::Value::Object(json::Object::new(vec![
+                                ("Value", json::Value::display(index)),
+                                ("String", json::Value::string(name)),
+                            ]))
+                        },
+                    );
                 } else {
-                    writeln!(p.w, "{}", index).unwrap();
+                    p.field("", index);
                 }
             }
         });
@@ -764,7 +778,7 @@ fn print_hash<Elf: FileHeader>(
     endian: Elf::Endian,
     data: &[u8],
     _elf: &Elf,
-    _sections: &SectionTable<Elf>,
+    sections: &SectionTable<Elf>,
     section: &Elf::SectionHeader,
 ) {
     if let Some(Some(hash)) = section.hash_header(endian, data).print_err(p) {
@@ -773,36 +787,17 @@ fn print_hash<Elf: FileHeader>(
             p.field("ChainCount", hash.chain_count.get(endian));
         });
     }
-    /* TODO: add this in a test somewhere
-    if let Ok(Some((hash_table, link))) = section.hash(endian, data) {
-        if let Ok(symbols) = _sections.symbol_table_by_index(endian, data, link) {
-            if let Ok(versions) = _sections.versions(endian, data) {
-                for (index, symbol) in symbols.symbols().enumerate() {
-                    let name = symbols.symbol_name(endian, symbol).unwrap();
-                    if name.is_empty() {
-                        continue;
-                    }
-                    let hash = hash(name);
-                    let version = versions.version(versions.version_index(endian, index));
-                    let (hash_index, hash_symbol) = hash_table
-                        .find(endian, name, hash, version, &symbols, &versions)
-                        .unwrap();
-                    let hash_name = symbols.symbol_name(endian, hash_symbol).unwrap();
-                    assert_eq!(name, hash_name);
-                    assert_eq!(index, hash_index);
-                }
-            }
-        }
+    if p.options.elf_hash_check {
+        hash::check_hash(p, endian, data, sections, section);
     }
-    */
 }
 
 fn print_gnu_hash<Elf: FileHeader>(
     p: &mut Printer<'_>,
     endian: Elf::Endian,
     data: &[u8],
-    _elf: &Elf,
-    _sections: &SectionTable<Elf>,
+    elf: &Elf,
+    sections: &SectionTable<Elf>,
     section: &Elf::SectionHeader,
 ) {
     if let Some(Some(hash)) = section.gnu_hash_header(endian, data).print_err(p) {
@@ -813,29 +808,9 @@ fn print_gnu_hash<Elf: FileHeader>(
             p.field("BloomShift", hash.bloom_shift.get(endian));
         });
     }
-    /* TODO: add this in a test somewhere
-    if let Ok(Some((hash_table, link))) = section.gnu_hash(endian, data) {
-        if let Ok(symbols) = _sections.symbol_table_by_index(endian, data, link) {
-            if let Ok(versions) = _sections.versions(endian, data) {
-                for (index, symbol) in symbols
-                    .symbols()
-                    .enumerate()
-                    .skip(hash_table.symbol_base() as usize)
-                {
-                    let name = symbols.symbol_name(endian, symbol).unwrap();
-                    let hash = gnu_hash(name);
-                    let version = versions.version(versions.version_index(endian, index));
-                    let (hash_index, hash_symbol) = hash_table
-                        .find(endian, name, hash, version, &symbols, &versions)
-                        .unwrap();
-                    let hash_name = symbols.symbol_name(endian, hash_symbol).unwrap();
-                    assert_eq!(name, hash_name);
-                    assert_eq!(index, hash_index);
-                }
-            }
-        }
+    if p.options.elf_hash_check {
+        hash::check_gnu_hash(p, endian, data, elf, sections, section);
     }
-    */
 }
 
 fn print_gnu_verdef<Elf: FileHeader>(
@@ -1392,7 +1367,10 @@ const FLAGS_SHT: &[Flag<u32>] = &flags!(
     SHT_GROUP,
     SHT_SYMTAB_SHNDX,
     SHT_RELR,
+);
+const FLAGS_SHT_GNU: &[Flag<u32>] = &flags!(
     SHT_LLVM_DEPENDENT_LIBRARIES,
+    SHT_GNU_SFRAME,
     SHT_GNU_ATTRIBUTES,
     SHT_GNU_HASH,
     SHT_GNU_LIBLIST,
@@ -1404,6 +1382,25 @@ const FLAGS_SHT: &[Flag<u32>] = &flags!(
     SHT_GNU_VERNEED,
     SHT_GNU_VERSYM,
 );
+const FLAGS_SHT_SUNW: &[Flag<u32>] = &flags!(
+    SHT_SUNW_capchain,
+    SHT_SUNW_capinfo,
+    SHT_SUNW_symsort,
+    SHT_SUNW_tlssort,
+    SHT_SUNW_LDYNSYM,
+    SHT_SUNW_dof,
+    SHT_SUNW_cap,
+    SHT_SUNW_SIGNATURE,
+    SHT_SUNW_ANNOTATE,
+    SHT_SUNW_DEBUGSTR,
+    SHT_SUNW_DEBUG,
+    SHT_SUNW_move,
+    SHT_SUNW_COMDAT,
+    SHT_SUNW_syminfo,
+    SHT_SUNW_verdef,
+    SHT_SUNW_verneed,
+    SHT_SUNW_versym,
+);
 const FLAGS_SHT_MIPS: &[Flag<u32>] = &flags!(
     SHT_MIPS_LIBLIST,
     SHT_MIPS_MSYM,
@@ -1444,13 +1441,50 @@ const FLAGS_SHT_MIPS: &[Flag<u32>] = &flags!(
     SHT_MIPS_EH_REGION,
     SHT_MIPS_XLATE_OLD,
     SHT_MIPS_PDR_EXCEPTION,
+    SHT_MIPS_ABIFLAGS,
 );
 const FLAGS_SHT_PARISC: &[Flag<u32>] = &flags!(SHT_PARISC_EXT, SHT_PARISC_UNWIND, SHT_PARISC_DOC);
 const FLAGS_SHT_ALPHA: &[Flag<u32>] = &flags!(SHT_ALPHA_DEBUG, SHT_ALPHA_REGINFO);
-const FLAGS_SHT_ARM: &[Flag<u32>] = &flags!(SHT_ARM_EXIDX, SHT_ARM_PREEMPTMAP, SHT_ARM_ATTRIBUTES);
+const FLAGS_SHT_ARM: &[Flag<u32>] = &flags!(
+    SHT_ARM_EXIDX,
+    SHT_ARM_PREEMPTMAP,
+    SHT_ARM_ATTRIBUTES,
+    SHT_ARM_DEBUGOVERLAY,
+    SHT_ARM_OVERLAYSECTION,
+);
+const FLAGS_SHT_AARCH64: &[Flag<u32>] = &flags!(SHT_AARCH64_ATTRIBUTES);
 const FLAGS_SHT_CSKY: &[Flag<u32>] = &flags!(SHT_CSKY_ATTRIBUTES);
 const FLAGS_SHT_IA_64: &[Flag<u32>] = &flags!(SHT_IA_64_EXT, SHT_IA_64_UNWIND);
 const FLAGS_SHT_X86_64: &[Flag<u32>] = &flags!(SHT_X86_64_UNWIND);
+const FLAGS_SHT_RISCV: &[Flag<u32>] = &flags!(SHT_RISCV_ATTRIBUTES);
+const FLAGS_SHT_SHARC: &[Flag<u32>] = &flags!(SHT_SHARC_ADI_ATTRIBUTES);
+const RANGES_SHT: &[FlagRange<u32>] = &flag_ranges!(
+    (SHT_LOOS, SHT_HIOS),
+    (SHT_LOPROC, SHT_HIPROC),
+    (SHT_LOUSER, SHT_HIUSER),
+);
+
+/// Return the tables of section type names for the given machine and OS ABI.
+fn sht_flags(machine: u16, os_abi: u8) -> [&'static [Flag<u32>]; 3] {
+    let os = match os_abi {
+        ELFOSABI_SOLARIS => FLAGS_SHT_SUNW,
+        _ => FLAGS_SHT_GNU,
+    };
+    let proc = match machine {
+        EM_MIPS => FLAGS_SHT_MIPS,
+        EM_PARISC => FLAGS_SHT_PARISC,
+        EM_ALPHA => FLAGS_SHT_ALPHA,
+        EM_ARM => FLAGS_SHT_ARM,
+        EM_AARCH64 => FLAGS_SHT_AARCH64,
+        EM_CSKY => FLAGS_SHT_CSKY,
+        EM_IA_64 => FLAGS_SHT_IA_64,
+        EM_X86_64 => FLAGS_SHT_X86_64,
+        EM_RISCV => FLAGS_SHT_RISCV,
+        EM_SHARC => FLAGS_SHT_SHARC,
+        _ => &[],
+    };
+    [FLAGS_SHT, os, proc]
+}
 const FLAGS_SHF: &[Flag<u32>] = &flags!(
     SHF_WRITE,
     SHF_ALLOC,
@@ -1496,6 +1530,23 @@ const FLAGS_STT_HP: &[Flag<u8>] = &flags!(STT_HP_OPAQUE, STT_HP_STUB);
 const FLAGS_STT_SPARC: &[Flag<u8>] = &flags!(STT_SPARC_REGISTER);
 const FLAGS_STT_PARISC: &[Flag<u8>] = &flags!(STT_PARISC_MILLICODE);
 const FLAGS_STT_ARM: &[Flag<u8>] = &flags!(STT_ARM_TFUNC, STT_ARM_16BIT);
+const RANGES_STT: &[FlagRange<u8>] = &flag_ranges!((STT_LOOS, STT_HIOS), (STT_LOPROC, STT_HIPROC));
+
+/// Return the tables of symbol type names for the given machine and OS ABI.
+fn stt_flags(machine: u16, os_abi: u8) -> [&'static [Flag<u8>]; 3] {
+    let os = match os_abi {
+        ELFOSABI_GNU | ELFOSABI_FREEBSD => FLAGS_STT_GNU,
+        ELFOSABI_HPUX => FLAGS_STT_HP,
+        _ => &[],
+    };
+    let proc = match machine {
+        EM_SPARC | EM_SPARC32PLUS | EM_SPARCV9 => FLAGS_STT_SPARC,
+        EM_PARISC => FLAGS_STT_PARISC,
+        EM_ARM => FLAGS_STT_ARM,
+        _ => &[],
+    };
+    [FLAGS_STT, os, proc]
+}
 const FLAGS_STB: &[Flag<u8>] = &flags!(STB_LOCAL, STB_GLOBAL, STB_WEAK);
 const FLAGS_STB_GNU: &[Flag<u8>] = &flags!(STB_GNU_UNIQUE);
 const FLAGS_STB_MIPS: &[Flag<u8>] = &flags!(STB_MIPS_SPLIT_COMMON);
@@ -1694,6 +1745,7 @@ const FLAGS_R_SPARC: &[Flag<u32>] = &flags!(
     R_SPARC_GNU_VTENTRY,
     R_SPARC_REV32,
 );
+const FLAGS_RSS: &[Flag<u8>] = &flags!(RSS_UNDEF, RSS_GP, RSS_GP0, RSS_LOC);
 const FLAGS_R_MIPS: &[Flag<u32>] = &flags!(
     R_MIPS_NONE,
     R_MIPS_16,
@@ -3427,6 +3479,9 @@ const FLAGS_DT: &[Flag<u32>] = &flags!(
     DT_PREINIT_ARRAY,
     DT_PREINIT_ARRAYSZ,
     DT_SYMTAB_SHNDX,
+    DT_RELRSZ,
+    DT_RELR,
+    DT_RELRENT,
     DT_GNU_PRELINKED,
     DT_GNU_CONFLICTSZ,
     DT_GNU_LIBLISTSZ,
@@ -0,0 +1,1479 @@
+//! Printing of ELF files in the style of `llvm-readelf`.
+//!
+//! This is enabled by [`PrintOptions::elf_gnu_style`]. The output uses the
+//! same layouts and field names as `llvm-readelf`, so that it can be diffed
+//! against the output of that tool.
+
+use std::borrow::Cow;
+use std::convert::{TryFrom, TryInto};
+
+use object::Endian;
+
+use super::*;
+
+// These constants aren't defined by the object crate.
+const NT_ARCH: u32 = 2;
+const SHF_X86_64_LARGE: u32 = 0x1000_0000;
+const SHF_ARM_PURECODE: u32 = 0x2000_0000;
+const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xc000_8001;
+const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xc001_0001;
+
+pub(super) fn print_elf<Elf: FileHeader<Endian = Endianness>>(
+    p: &mut Printer<'_>,
+    elf: &Elf,
+    data: &[u8],
+) {
+    let Some(endian) = elf.endian().print_err(p) else {
+        return;
+    };
+    let segments = elf.program_headers(endian, data).print_err(p);
+    let segments = segments.unwrap_or(&[]);
+    let sections = elf.sections(endian, data).print_err(p);
+    let sections = sections.unwrap_or_default();
+    if p.options.file {
+        print_file_header(p, endian, elf, &sections);
+    }
+    if p.options.sections {
+        print_section_headers(p, endian, elf, &sections);
+    }
+    if p.options.segments {
+        print_program_headers(p, endian, elf, data, segments, &sections);
+    }
+    if p.options.elf_dynamic {
+        print_dynamic(p, endian, elf, data, segments, &sections);
+    }
+    if p.options.relocations {
+        print_relocations(p, endian, elf, data, &sections);
+    }
+    if p.options.elf_dynamic_symbols {
+        print_symbols(p, endian, elf, data, &sections, SHT_DYNSYM);
+    }
+    if p.options.symbols {
+        print_symbols(p, endian, elf, data, &sections, SHT_SYMTAB);
+    }
+    if p.options.elf_notes {
+        print_notes(p, endian, elf, data, segments, &sections);
+    }
+}
+
+/// A line of output containing fields that start at fixed columns.
+#[derive(Default)]
+struct Line(String);
+
+impl Line {
+    /// Append a field at the given column.
+    ///
+    /// A field is always separated from the previous field by at least one space.
+    fn field(&mut self, column: usize, s: &str) -> &mut Self {
+        if column != 0 {
+            let pad = column.saturating_sub(self.0.chars().count()).max(1);
+            self.0.push_str(&" ".repeat(pad));
+        }
+        self.0.push_str(s);
+        self
+    }
+
+    /// Append a field at the given column, right justified to the given width.
+    fn right(&mut self, column: usize, width: usize, s: &str) -> &mut Self {
+        self.field(column, &format!("{:>1$}", s, width))
+    }
+
+    fn print(&self, p: &mut Printer<'_>) {
+        writeln!(p.w, "{}", self.0).unwrap();
+    }
+}
+
+/// Return the name of the first flag in the tables that matches the value.
+fn flag_name<T: Eq + Copy>(value: T, tables: &[&[Flag<T>]]) -> Option<&'static str> {
+    tables
+        .iter()
+        .flat_map(|flags| flags.iter())
+        .find(|flag| flag.value == value)
+        .map(|flag| flag.name)
+}
+
+fn lossy(s: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(s)
+}
+
+fn section_name<'data, Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    sections: &SectionTable<'data, Elf>,
+    section: &Elf::SectionHeader,
+) -> Cow<'data, str> {
+    sections
+        .section_name(endian, section)
+        .print_err(p)
+        .map(lossy)
+        .unwrap_or(Cow::Borrowed("<?>"))
+}
+
+fn print_file_header<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    sections: &SectionTable<Elf>,
+) {
+    fn field(p: &mut Printer<'_>, name: &str, value: &dyn fmt::Display) {
+        writeln!(p.w, "  {:<35}{}", name, value).unwrap();
+    }
+
+    let ident = elf.e_ident();
+    writeln!(p.w, "ELF Header:").unwrap();
+    write!(p.w, "  Magic:  ").unwrap();
+    for byte in ident
+        .magic
+        .iter()
+        .chain(&[
+            ident.class,
+            ident.data,
+            ident.version,
+            ident.os_abi,
+            ident.abi_version,
+        ])
+        .chain(&ident.padding)
+    {
+        write!(p.w, " {:02x}", byte).unwrap();
+    }
+    writeln!(p.w).unwrap();
+    let class = match ident.class {
+        ELFCLASSNONE => "none".into(),
+        ELFCLASS32 => "ELF32".into(),
+        ELFCLASS64 => "ELF64".into(),
+        x => format!("{:x}", x),
+    };
+    field(p, "Class:", &class);
+    let data = match ident.data {
+        ELFDATANONE => "none".into(),
+        ELFDATA2LSB => "2's complement, little endian".into(),
+        ELFDATA2MSB => "2's complement, big endian".into(),
+        x => format!("{:x}", x),
+    };
+    field(p, "Data:", &data);
+    let version = match ident.version {
+        EV_CURRENT => "1 (current)".into(),
+        x => x.to_string(),
+    };
+    field(p, "Version:", &version);
+    field(
+        p,
+        "OS/ABI:",
+        &os_abi_name(ident.os_abi, elf.e_machine(endian)),
+    );
+    field(p, "ABI Version:", &ident.abi_version);
+    field(p, "Type:", &file_type_name(elf.e_type(endian)));
+    field(p, "Machine:", &machine_name(elf.e_machine(endian)));
+    field(
+        p,
+        "Version:",
+        &format_args!("0x{:X}", elf.e_version(endian)),
+    );
+    field(
+        p,
+        "Entry point address:",
+        &format_args!("0x{:X}", elf.e_entry(endian).into()),
+    );
+    field(
+        p,
+        "Start of program headers:",
+        &format_args!("{} (bytes into file)", elf.e_phoff(endian).into()),
+    );
+    field(
+        p,
+        "Start of section headers:",
+        &format_args!("{} (bytes into file)", elf.e_shoff(endian).into()),
+    );
+    field(p, "Flags:", &format_args!("0x{:X}", elf.e_flags(endian)));
+    field(
+        p,
+        "Size of this header:",
+        &format_args!("{} (bytes)", elf.e_ehsize(endian)),
+    );
+    field(
+        p,
+        "Size of program headers:",
+        &format_args!("{} (bytes)", elf.e_phentsize(endian)),
+    );
+    field(p, "Number of program headers:", &elf.e_phnum(endian));
+    field(
+        p,
+        "Size of section headers:",
+        &format_args!("{} (bytes)", elf.e_shentsize(endian)),
+    );
+    let shnum = elf.e_shnum(endian);
+    if shnum == 0 && !sections.is_empty() {
+        field(
+            p,
+            "Number of section headers:",
+            &format_args!("0 ({})", sections.len()),
+        );
+    } else {
+        field(p, "Number of section headers:", &shnum);
+    }
+    let shstrndx = elf.e_shstrndx(endian);
+    match sections.section(SectionIndex(0)) {
+        Ok(section) if shstrndx == SHN_XINDEX => field(
+            p,
+            "Section header string table index:",
+            &format_args!("{} ({})", shstrndx, section.sh_link(endian)),
+        ),
+        _ => field(p, "Section header string table index:", &shstrndx),
+    }
+}
+
+fn os_abi_name(os_abi: u8, machine: u16) -> Cow<'static, str> {
+    let name = match os_abi {
+        ELFOSABI_SYSV => "UNIX - System V",
+        ELFOSABI_HPUX => "UNIX - HP-UX",
+        ELFOSABI_NETBSD => "UNIX - NetBSD",
+        ELFOSABI_GNU => "UNIX - GNU",
+        ELFOSABI_SOLARIS => "UNIX - Solaris",
+        ELFOSABI_AIX => "UNIX - AIX",
+        ELFOSABI_IRIX => "UNIX - IRIX",
+        ELFOSABI_FREEBSD => "UNIX - FreeBSD",
+        ELFOSABI_TRU64 => "UNIX - TRU64",
+        ELFOSABI_MODESTO => "Novell - Modesto",
+        ELFOSABI_OPENBSD => "UNIX - OpenBSD",
+        ELFOSABI_OPENVMS => "VMS - OpenVMS",
+        ELFOSABI_NSK => "HP - Non-Stop Kernel",
+        ELFOSABI_AROS => "AROS",
+        ELFOSABI_FENIXOS => "FenixOS",
+        ELFOSABI_CLOUDABI => "Nuxi CloudABI",
+        ELFOSABI_ARM if machine == EM_ARM => "ARM",
+        ELFOSABI_STANDALONE => "Standalone App",
+        x => return format!("{:x}", x).into(),
+    };
+    name.into()
+}
+
+fn file_type_name(e_type: u16) -> Cow<'static, str> {
+    let name = match e_type {
+        ET_NONE => "NONE (none)",
+        ET_REL => "REL (Relocatable file)",
+        ET_EXEC => "EXEC (Executable file)",
+        ET_DYN => "DYN (Shared object file)",
+        ET_CORE => "CORE (Core file)",
+        x => return format!("{:x}", x).into(),
+    };
+    name.into()
+}
+
+fn machine_name(machine: u16) -> Cow<'static, str> {
+    let name = match machine {
+        EM_NONE => "None",
+        EM_M32 => "WE32100",
+        EM_SPARC => "Sparc",
+        EM_386 => "Intel 80386",
+        EM_68K => "MC68000",
+        EM_88K => "MC88000",
+        EM_860 => "Intel 80860",
+        EM_MIPS => "MIPS R3000",
+        EM_S370 => "IBM System/370",
+        EM_MIPS_RS3_LE => "MIPS R3000 little-endian",
+        EM_PARISC => "HPPA",
+        EM_SPARC32PLUS => "Sparc v8+",
+        EM_960 => "Intel 80960",
+        EM_PPC => "PowerPC",
+        EM_PPC64 => "PowerPC64",
+        EM_S390 => "IBM S/390",
+        EM_ARM => "ARM",
+        EM_SH => "Hitachi SH",
+        EM_SPARCV9 => "Sparc v9",
+        EM_IA_64 => "Intel IA-64",
+        EM_X86_64 => "Advanced Micro Devices X86-64",
+        EM_AVR => "Atmel AVR 8-bit microcontroller",
+        EM_MSP430 => "Texas Instruments msp430 microcontroller",
+        EM_HEXAGON => "Qualcomm Hexagon",
+        EM_AARCH64 => "AArch64",
+        EM_MICROBLAZE => "Xilinx MicroBlaze",
+        EM_RISCV => "RISC-V",
+        EM_CSKY => "C-SKY",
+        EM_LOONGARCH => "LoongArch",
+        EM_XTENSA => "Tensilica Xtensa Processor",
+        x => match flag_name(x, &[FLAGS_EM]) {
+            Some(name) => name,
+            None => return format!("{:x}", x).into(),
+        },
+    };
+    name.into()
+}
+
+fn print_section_headers<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    sections: &SectionTable<Elf>,
+) {
+    if sections.is_empty() {
+        writeln!(p.w, "\nThere are no sections in this file.").unwrap();
+        return;
+    }
+    writeln!(
+        p.w,
+        "There are {} section headers, starting at offset 0x{:x}:",
+        sections.len(),
+        elf.e_shoff(endian).into()
+    )
+    .unwrap();
+    writeln!(p.w, "\nSection Headers:").unwrap();
+
+    let is_64 = elf.is_type_64();
+    let bias = if is_64 { 0 } else { 8 };
+    let address_width = if is_64 { 16 } else { 8 };
+    Line::default()
+        .field(2, "[Nr]")
+        .field(7, "Name")
+        .field(25, "Type")
+        .field(41, "Address")
+        .field(58 - bias, "Off")
+        .field(65 - bias, "Size")
+        .field(72 - bias, "ES")
+        .field(75 - bias, "Flg")
+        .field(79 - bias, "Lk")
+        .field(82 - bias, "Inf")
+        .field(86 - bias, "Al")
+        .print(p);
+    let machine = elf.e_machine(endian);
+    let os_abi = elf.e_ident().os_abi;
+    for (index, section) in sections.enumerate() {
+        if !p
+            .options
+            .filter_section(index.0, sections.section_name(endian, section).ok())
+        {
+            continue;
+        }
+        let name = section_name(p, endian, sections, section);
+        Line::default()
+            .field(2, &format!("[{:2}]", index.0))
+            .field(7, &name)
+            .field(
+                25,
+                &section_type_name(machine, os_abi, section.sh_type(endian)),
+            )
+            .field(
+                41,
+                &format!("{:01$x}", section.sh_addr(endian).into(), address_width),
+            )
+            .field(
+                58 - bias,
+                &format!("{:06x}", section.sh_offset(endian).into()),
+            )
+            .field(
+                65 - bias,
+                &format!("{:06x}", section.sh_size(endian).into()),
+            )
+            .field(
+                72 - bias,
+                &format!("{:02x}", section.sh_entsize(endian).into()),
+            )
+            .right(
+                75 - bias,
+                3,
+                &section_flags(machine, section.sh_flags(endian).into()),
+            )
+            .right(79 - bias, 2, &section.sh_link(endian).to_string())
+            .right(82 - bias, 3, &section.sh_info(endian).to_string())
+            .right(
+                86 - bias,
+                2,
+                &section.sh_addralign(endian).into().to_string(),
+            )
+            .print(p);
+    }
+    writeln!(p.w, "Key to Flags:").unwrap();
+    writeln!(
+        p.w,
+        "  W (write), A (alloc), X (execute), M (merge), S (strings), I (info),"
+    )
+    .unwrap();
+    writeln!(
+        p.w,
+        "  L (link order), O (extra OS processing required), G (group), T (TLS),"
+    )
+    .unwrap();
+    writeln!(
+        p.w,
+        "  C (compressed), x (unknown), o (OS specific), E (exclude),"
+    )
+    .unwrap();
+    write!(p.w, "  R (retain)").unwrap();
+    match machine {
+        EM_X86_64 => write!(p.w, ", l (large)").unwrap(),
+        EM_ARM => write!(p.w, ", y (purecode)").unwrap(),
+        _ => {}
+    }
+    writeln!(p.w, ", p (processor specific)").unwrap();
+}
+
+fn section_type_name(machine: u16, os_abi: u8, sh_type: u32) -> String {
+    match flag_name(sh_type, &sht_flags(machine, os_abi)) {
+        Some("SHT_GNU_HASH") => "GNU_HASH".into(),
+        Some("SHT_SYMTAB_SHNDX") => "SYMTAB SECTION INDICES".into(),
+        Some(name) if name.starts_with("SHT_GNU_") => name[8..].to_uppercase(),
+        Some(name) if name.starts_with("SHT_") => name[4..].into(),
+        _ => {
+            if (SHT_LOOS..=SHT_HIOS).contains(&sh_type) {
+                format!("LOOS+0x{:X}", sh_type - SHT_LOOS)
+            } else if (SHT_LOPROC..=SHT_HIPROC).contains(&sh_type) {
+                format!("LOPROC+0x{:X}", sh_type - SHT_LOPROC)
+            } else if (SHT_LOUSER..=SHT_HIUSER).contains(&sh_type) {
+                format!("LOUSER+0x{:X}", sh_type - SHT_LOUSER)
+            } else {
+                format!("0x{:X}: <unknown>", sh_type)
+            }
+        }
+    }
+}
+
+/// Return the section flags as a string of single letter codes.
+fn section_flags(machine: u16, mut flags: u64) -> String {
+    let mut letters = vec![
+        (SHF_WRITE, 'W'),
+        (SHF_ALLOC, 'A'),
+        (SHF_EXECINSTR, 'X'),
+        (SHF_MERGE, 'M'),
+        (SHF_STRINGS, 'S'),
+        (SHF_INFO_LINK, 'I'),
+        (SHF_LINK_ORDER, 'L'),
+        (SHF_OS_NONCONFORMING, 'O'),
+        (SHF_GROUP, 'G'),
+        (SHF_TLS, 'T'),
+        (SHF_COMPRESSED, 'C'),
+        (SHF_EXCLUDE, 'E'),
+        (SHF_GNU_RETAIN, 'R'),
+    ];
+    match machine {
+        EM_X86_64 => letters.push((SHF_X86_64_LARGE, 'l')),
+        EM_ARM => letters.push((SHF_ARM_PURECODE, 'y')),
+        _ => {}
+    }
+    let mut s = String::new();
+    let mut os = false;
+    let mut proc = false;
+    let mut unknown = false;
+    // Flags are printed in bit order. A known processor specific flag such as
+    // SHF_EXCLUDE is only printed if there are no unknown processor specific flags.
+    while flags != 0 {
+        let flag = flags & flags.wrapping_neg();
+        flags &= !flag;
+        if let Some((_, letter)) = letters.iter().find(|(x, _)| u64::from(*x) == flag) {
+            s.push(*letter);
+        } else if flag & u64::from(SHF_MASKOS) != 0 {
+            os = true;
+            flags &= !u64::from(SHF_MASKOS);
+        } else if flag & u64::from(SHF_MASKPROC) != 0 {
+            proc = true;
+            flags &= !u64::from(SHF_MASKPROC);
+        } else {
+            unknown = true;
+        }
+    }
+    if os {
+        s.push('o');
+    }
+    if proc {
+        s.push('p');
+    }
+    if unknown {
+        s.push('x');
+    }
+    s
+}
+
+fn print_program_headers<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    data: &[u8],
+    segments: &[Elf::ProgramHeader],
+    sections: &SectionTable<Elf>,
+) {
+    writeln!(
+        p.w,
+        "\nElf file type is {}",
+        file_type_name(elf.e_type(endian))
+    )
+    .unwrap();
+    writeln!(p.w, "Entry point 0x{:x}", elf.e_entry(endian).into()).unwrap();
+    writeln!(
+        p.w,
+        "There are {} program headers, starting at offset {}",
+        segments.len(),
+        elf.e_phoff(endian).into()
+    )
+    .unwrap();
+    writeln!(p.w, "\nProgram Headers:").unwrap();
+
+    let is_64 = elf.is_type_64();
+    let bias = if is_64 { 8 } else { 0 };
+    let address_width = if is_64 { 16 } else { 8 };
+    let size_width = if is_64 { 6 } else { 5 };
+    if is_64 {
+        writeln!(p.w, "  Type           Offset   VirtAddr           PhysAddr           FileSiz  MemSiz   Flg Align").unwrap();
+    } else {
+        writeln!(
+            p.w,
+            "  Type           Offset   VirtAddr   PhysAddr   FileSiz MemSiz  Flg Align"
+        )
+        .unwrap();
+    }
+    let machine = elf.e_machine(endian);
+    for segment in segments {
+        let p_type = segment.p_type(endian);
+        let p_flags = segment.p_flags(endian);
+        let flags = format!(
+            "{}{}{}",
+            if p_flags & PF_R != 0 { 'R' } else { ' ' },
+            if p_flags & PF_W != 0 { 'W' } else { ' ' },
+            if p_flags & PF_X != 0 { 'E' } else { ' ' },
+        );
+        let mut line = Line::default();
+        line.field(2, &segment_type_name(machine, p_type))
+            .field(17, &format!("0x{:06x}", segment.p_offset(endian).into()))
+            .field(
+                26,
+                &format!("0x{:01$x}", segment.p_vaddr(endian).into(), address_width),
+            )
+            .field(
+                37 + bias,
+                &format!("0x{:01$x}", segment.p_paddr(endian).into(), address_width),
+            )
+            .field(
+                48 + 2 * bias,
+                &format!("0x{:01$x}", segment.p_filesz(endian).into(), size_width),
+            )
+            .field(
+                56 + 2 * bias,
+                &format!("0x{:01$x}", segment.p_memsz(endian).into(), size_width),
+            )
+            .field(64 + 2 * bias, &flags)
+            .field(
+                68 + 2 * bias,
+                &format!("0x{:x}", segment.p_align(endian).into()),
+            );
+        if p_type == PT_INTERP {
+            if let Some(interp) = segment.interpreter(endian, data).print_err(p).flatten() {
+                line.0.push_str(&format!(
+                    "\n      [Requesting program interpreter: {}]",
+                    lossy(interp)
+                ));
+            }
+        }
+        line.print(p);
+    }
+
+    writeln!(p.w, "\n Section to Segment mapping:").unwrap();
+    writeln!(p.w, "  Segment Sections...").unwrap();
+    let mut in_segment = vec![false; sections.len()];
+    for (index, segment) in segments.iter().enumerate() {
+        write!(p.w, "   {:02}     ", index).unwrap();
+        for (section_index, section) in sections.enumerate() {
+            if section_index.0 != 0 && is_section_in_segment::<Elf>(endian, segment, section) {
+                in_segment[section_index.0] = true;
+                let name = section_name(p, endian, sections, section);
+                write!(p.w, "{} ", name).unwrap();
+            }
+        }
+        writeln!(p.w).unwrap();
+    }
+    if in_segment.iter().any(|x| !x) {
+        write!(p.w, "   None  ").unwrap();
+        for (section_index, section) in sections.enumerate() {
+            if !in_segment[section_index.0] {
+                let name = section_name(p, endian, sections, section);
+                write!(p.w, "{} ", name).unwrap();
+            }
+        }
+        writeln!(p.w).unwrap();
+    }
+}
+
+fn segment_type_name(machine: u16, p_type: u32) -> String {
+    let proc = match machine {
+        EM_MIPS => FLAGS_PT_MIPS,
+        EM_PARISC => FLAGS_PT_PARISC,
+        EM_ARM => FLAGS_PT_ARM,
+        EM_IA_64 => FLAGS_PT_IA_64,
+        _ => &[],
+    };
+    match flag_name(p_type, &[proc, FLAGS_PT]) {
+        Some(name) if name.starts_with("PT_ARM_") => name[7..].into(),
+        Some(name) if name.starts_with("PT_MIPS_") => name[8..].into(),
+        Some(name) if name.starts_with("PT_") => name[3..].into(),
+        _ => format!("<unknown>: 0x{:x}", p_type),
+    }
+}
+
+/// Return true if the section is displayed as part of the segment.
+///
+/// Empty sections at the end of a segment are not included, and
+/// `.tbss` is only included in `PT_TLS` segments.
+fn is_section_in_segment<Elf: FileHeader>(
+    endian: Elf::Endian,
+    segment: &Elf::ProgramHeader,
+    section: &Elf::SectionHeader,
+) -> bool {
+    let p_type = segment.p_type(endian);
+    let p_offset = segment.p_offset(endian).into();
+    let p_filesz = segment.p_filesz(endian).into();
+    let p_vaddr = segment.p_vaddr(endian).into();
+    let p_memsz = segment.p_memsz(endian).into();
+    let sh_type = section.sh_type(endian);
+    let sh_flags = section.sh_flags(endian).into();
+    let sh_offset = section.sh_offset(endian).into();
+    let sh_addr = section.sh_addr(endian).into();
+    let sh_size = section.sh_size(endian).into();
+    let is_tls = sh_flags & u64::from(SHF_TLS) != 0;
+    let is_tbss = is_tls && sh_type == SHT_NOBITS;
+
+    if is_tls {
+        if is_tbss && p_type != PT_TLS {
+            return false;
+        }
+        if !matches!(p_type, PT_TLS | PT_LOAD | PT_GNU_RELRO) {
+            return false;
+        }
+    } else if p_type == PT_TLS {
+        return false;
+    }
+
+    if sh_type != SHT_NOBITS {
+        if sh_offset < p_offset {
+            return false;
+        }
+        let end = if sh_size == 0 {
+            sh_offset + 1
+        } else {
+            sh_offset + sh_size
+        };
+        if end > p_offset + p_filesz {
+            return false;
+        }
+    }
+
+    if sh_flags & u64::from(SHF_ALLOC) != 0 {
+        if sh_addr < p_vaddr {
+            return false;
+        }
+        let end = if sh_size == 0 || (is_tbss && p_type != PT_TLS) {
+            sh_addr + 1
+        } else {
+            sh_addr + sh_size
+        };
+        if end > p_vaddr + p_memsz {
+            return false;
+        }
+    }
+
+    // Empty sections can't be at the start or end of `PT_DYNAMIC`.
+    if p_type == PT_DYNAMIC && p_memsz != 0 && sh_size == 0 {
+        let offset_ok =
+            sh_type == SHT_NOBITS || (sh_offset > p_offset && sh_offset < p_offset + p_filesz);
+        let addr_ok =
+            sh_flags & u64::from(SHF_ALLOC) == 0 || (sh_addr > p_vaddr && sh_addr < p_memsz);
+        return offset_ok && addr_ok;
+    }
+    true
+}
+
+fn print_dynamic<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    data: &[u8],
+    segments: &[Elf::ProgramHeader],
+    sections: &SectionTable<Elf>,
+) {
+    let mut dynamic = None;
+    for section in sections.iter() {
+        if let Some(Some((entries, link))) = section.dynamic(endian, data).print_err(p) {
+            let strings = sections.strings(endian, data, link).unwrap_or_default();
+            dynamic = Some((section.sh_offset(endian).into(), entries, strings));
+            break;
+        }
+    }
+    if dynamic.is_none() {
+        for segment in segments {
+            if let Some(Some(entries)) = segment.dynamic(endian, data).print_err(p) {
+                dynamic = Some((
+                    segment.p_offset(endian).into(),
+                    entries,
+                    StringTable::default(),
+                ));
+                break;
+            }
+        }
+    }
+    let Some((offset, entries, strings)) = dynamic else {
+        return;
+    };
+    let count = entries
+        .iter()
+        .position(|d| d.d_tag(endian).into() == u64::from(DT_NULL))
+        .map_or(entries.len(), |x| x + 1);
+    let entries = &entries[..count];
+
+    let machine = elf.e_machine(endian);
+    let types: Vec<String> = entries
+        .iter()
+        .map(|d| format!("({})", dynamic_tag_name(machine, d.d_tag(endian).into())))
+        .collect();
+    let type_width = types.iter().map(|x| x.len()).max().unwrap_or(0);
+    let tag_width = if elf.is_type_64() { 16 } else { 8 };
+    writeln!(
+        p.w,
+        "Dynamic section at offset 0x{:x} contains {} entries:",
+        offset, count
+    )
+    .unwrap();
+    writeln!(
+        p.w,
+        "  Tag{:tag$}Type{:ty$}Name/Value",
+        "",
+        "",
+        tag = tag_width,
+        ty = type_width.saturating_sub(3)
+    )
+    .unwrap();
+    for (d, ty) in entries.iter().zip(types) {
+        let tag = d.d_tag(endian).into();
+        let value = d.d_val(endian).into();
+        let value = dynamic_value::<Elf>(endian, d, tag, value, strings);
+        writeln!(
+            p.w,
+            "  0x{:0tag$x} {:ty$} {}",
+            tag,
+            ty,
+            value,
+            tag = tag_width,
+            ty = type_width
+        )
+        .unwrap();
+    }
+}
+
+fn dynamic_tag_name(machine: u16, tag: u64) -> String {
+    let proc = match machine {
+        EM_SPARC => FLAGS_DT_SPARC,
+        EM_MIPS => FLAGS_DT_MIPS,
+        EM_ALPHA => FLAGS_DT_ALPHA,
+        EM_PPC => FLAGS_DT_PPC,
+        EM_PPC64 => FLAGS_DT_PPC64,
+        EM_IA_64 => FLAGS_DT_IA_64,
+        EM_ALTERA_NIOS2 => FLAGS_DT_NIOS2,
+        _ => &[],
+    };
+    let name = u32::try_from(tag)
+        .ok()
+        .and_then(|tag| flag_name(tag, &[proc, FLAGS_DT]));
+    match name {
+        Some(name) if name.starts_with("DT_") => name[3..].into(),
+        _ => format!("<unknown:>0x{:x}", tag),
+    }
+}
+
+fn dynamic_value<Elf: FileHeader>(
+    endian: Elf::Endian,
+    d: &Elf::Dyn,
+    tag: u64,
+    value: u64,
+    strings: StringTable,
+) -> String {
+    let Ok(tag32) = u32::try_from(tag) else {
+        return format!("0x{:x}", value);
+    };
+    let library = match tag32 {
+        DT_NEEDED => Some("Shared library"),
+        DT_SONAME => Some("Library soname"),
+        DT_AUXILIARY => Some("Auxiliary library"),
+        DT_FILTER => Some("Filter library"),
+        DT_RPATH => Some("Library rpath"),
+        DT_RUNPATH => Some("Library runpath"),
+        _ => None,
+    };
+    if let Some(library) = library {
+        let s = d
+            .string(endian, strings)
+            .map(lossy)
+            .unwrap_or(Cow::Borrowed("<?>"));
+        return format!("{}: [{}]", library, s);
+    }
+    match tag32 {
+        DT_PLTREL if value == u64::from(DT_REL) => "REL".into(),
+        DT_PLTREL if value == u64::from(DT_RELA) => "RELA".into(),
+        DT_RELACOUNT | DT_RELCOUNT | DT_VERDEFNUM | DT_VERNEEDNUM => value.to_string(),
+        DT_PLTRELSZ | DT_RELASZ | DT_RELAENT | DT_STRSZ | DT_SYMENT | DT_RELSZ | DT_RELENT
+        | DT_INIT_ARRAYSZ | DT_FINI_ARRAYSZ | DT_PREINIT_ARRAYSZ | DT_RELRSZ | DT_RELRENT => {
+            format!("{} (bytes)", value)
+        }
+        DT_FLAGS => dynamic_flags(value, FLAGS_DF, "DF_"),
+        DT_FLAGS_1 => dynamic_flags(value, FLAGS_DF_1, "DF_1_"),
+        _ => format!("0x{:x}", value),
+    }
+}
+
+fn dynamic_flags(value: u64, flags: &[Flag<u32>], prefix: &str) -> String {
+    let mut s = String::new();
+    for flag in flags {
+        if value & u64::from(flag.value) != 0 {
+            s.push_str(&flag.name[prefix.len()..]);
+            s.push(' ');
+        }
+    }
+    s
+}
+
+/// The symbols and versions used for printing a symbol table or relocations.
+struct Symbols<'data, Elf: FileHeader> {
+    symbols: SymbolTable<'data, Elf>,
+    versions: Option<VersionTable<'data, Elf>>,
+}
+
+impl<'data, Elf: FileHeader> Symbols<'data, Elf> {
+    fn new(
+        p: &mut Printer<'_>,
+        endian: Elf::Endian,
+        data: &'data [u8],
+        sections: &SectionTable<'data, Elf>,
+        symbols: SymbolTable<'data, Elf>,
+    ) -> Self {
+        let is_dynamic = matches!(
+            sections.section(symbols.section()),
+            Ok(section) if section.sh_type(endian) == SHT_DYNSYM
+        );
+        let versions = if is_dynamic {
+            sections.versions(endian, data).print_err(p).flatten()
+        } else {
+            None
+        };
+        Symbols { symbols, versions }
+    }
+
+    /// Return the name of a symbol, including its version.
+    ///
+    /// Section symbols without a name use the name of their section.
+    fn name(
+        &self,
+        p: &mut Printer<'_>,
+        endian: Elf::Endian,
+        sections: &SectionTable<'data, Elf>,
+        index: SymbolIndex,
+        symbol: &Elf::Sym,
+    ) -> String {
+        let name = symbol
+            .name(endian, self.symbols.strings())
+            .print_err(p)
+            .unwrap_or(b"<?>");
+        if name.is_empty() && symbol.st_type() == STT_SECTION {
+            if let Some(section) = self
+                .symbols
+                .symbol_section(endian, symbol, index)
+                .print_err(p)
+                .flatten()
+                .and_then(|index| sections.section(index).print_err(p))
+            {
+                return section_name(p, endian, sections, section).into_owned();
+            }
+        }
+        let mut name = lossy(&p.demangle(name)).into_owned();
+        if let Some(versions) = &self.versions {
+            let version_index = versions.version_index(endian, index);
+            if let Some(Some(version)) = versions.version(version_index).print_err(p) {
+                let default = version.file().is_none() && !version_index.is_hidden();
+                name.push_str(if default { "@@" } else { "@" });
+                name.push_str(&lossy(version.name()));
+            }
+        }
+        name
+    }
+}
+
+fn print_relocations<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    data: &[u8],
+    sections: &SectionTable<Elf>,
+) {
+    let is_64 = elf.is_type_64();
+    let mut found = false;
+    for (index, section) in sections.enumerate() {
+        let sh_type = section.sh_type(endian);
+        if !matches!(sh_type, SHT_REL | SHT_RELA | SHT_RELR) {
+            continue;
+        }
+        found = true;
+        if !p
+            .options
+            .filter_section(index.0, sections.section_name(endian, section).ok())
+        {
+            continue;
+        }
+        let entries = if sh_type == SHT_RELR {
+            section
+                .relr(endian, data)
+                .print_err(p)
+                .flatten()
+                .map(|relocations| relocations.count().to_string())
+        } else {
+            let entsize = section.sh_entsize(endian).into();
+            (entsize != 0).then(|| (section.sh_size(endian).into() / entsize).to_string())
+        };
+        let name = section_name(p, endian, sections, section);
+        writeln!(
+            p.w,
+            "\nRelocation section '{}' at offset 0x{:x} contains {} entries:",
+            name,
+            section.sh_offset(endian).into(),
+            entries.as_deref().unwrap_or("<?>")
+        )
+        .unwrap();
+        if is_64 {
+            write!(p.w, "    Offset             Info             Type               Symbol's Value  Symbol's Name").unwrap();
+        } else {
+            write!(
+                p.w,
+                " Offset     Info    Type                Sym. Value  Symbol's Name"
+            )
+            .unwrap();
+        }
+        if sh_type == SHT_RELA {
+            write!(p.w, " + Addend").unwrap();
+        }
+        writeln!(p.w).unwrap();
+
+        match sh_type {
+            SHT_REL => {
+                if let Some(Some((relocations, link))) = section.rel(endian, data).print_err(p) {
+                    let symbols = sections
+                        .symbol_table_by_index(endian, data, link)
+                        .print_err(p)
+                        .map(|symbols| Symbols::new(p, endian, data, sections, symbols));
+                    for r in relocations {
+                        let r = Relocation {
+                            offset: r.r_offset(endian).into(),
+                            info: r.r_info(endian).into(),
+                            r_type: r.r_type(endian),
+                            r_sym: r.r_sym(endian),
+                        };
+                        print_relocation(p, endian, elf, sections, symbols.as_ref(), &r, None);
+                    }
+                }
+            }
+            SHT_RELA => {
+                if let Some(Some((relocations, link))) = section.rela(endian, data).print_err(p) {
+                    let symbols = sections
+                        .symbol_table_by_index(endian, data, link)
+                        .print_err(p)
+                        .map(|symbols| Symbols::new(p, endian, data, sections, symbols));
+                    let is_mips64el = elf.is_mips64el(endian);
+                    for r in relocations {
+                        let addend = r.r_addend(endian).into();
+                        let r = Relocation {
+                            offset: r.r_offset(endian).into(),
+                            info: r.r_info(endian, is_mips64el).into(),
+                            r_type: r.r_type(endian, is_mips64el),
+                            r_sym: r.r_sym(endian, is_mips64el),
+                        };
+                        print_relocation(
+                            p,
+                            endian,
+                            elf,
+                            sections,
+                            symbols.as_ref(),
+                            &r,
+                            Some(addend),
+                        );
+                    }
+                }
+            }
+            _ => {
+                if let Some(Some(relocations)) = section.relr(endian, data).print_err(p) {
+                    let r_type = relative_type(elf.e_machine(endian));
+                    for offset in relocations {
+                        let r = Relocation {
+                            offset: offset.into(),
+                            info: r_type.into(),
+                            r_type,
+                            r_sym: 0,
+                        };
+                        print_relocation(p, endian, elf, sections, None, &r, None);
+                    }
+                }
+            }
+        }
+    }
+    if !found {
+        writeln!(p.w, "\nThere are no relocations in this file.").unwrap();
+    }
+}
+
+struct Relocation {
+    offset: u64,
+    info: u64,
+    r_type: u32,
+    r_sym: u32,
+}
+
+fn print_relocation<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    sections: &SectionTable<Elf>,
+    symbols: Option<&Symbols<Elf>>,
+    r: &Relocation,
+    addend: Option<i64>,
+) {
+    let is_64 = elf.is_type_64();
+    let bias = if is_64 { 8 } else { 0 };
+    let width = if is_64 { 16 } else { 8 };
+    let type_name = |r_type: u32| match flag_name(r_type, &[rel_flag_type(endian, elf)]) {
+        Some(name) => name.into(),
+        None => r_type.to_string(),
+    };
+    let r_type = if elf.e_machine(endian) == EM_MIPS && is_64 {
+        // Match llvm-readelf, which prints all three MIPS64 relocation types.
+        let r_type = Mips64RelocationType::from_r_type(r.r_type);
+        format!(
+            "{}/{}/{}",
+            type_name(r_type.r_type.into()),
+            type_name(r_type.r_type2.into()),
+            type_name(r_type.r_type3.into())
+        )
+    } else {
+        type_name(r.r_type)
+    };
+    let mut value = String::new();
+    let mut name = String::new();
+    if r.r_sym != 0 {
+        if let Some(symbols) = symbols {
+            let index = SymbolIndex(r.r_sym as usize);
+            if let Some(symbol) = symbols.symbols.symbol(index).print_err(p) {
+                value = format!("{:01$x}", symbol.st_value(endian).into(), width);
+                name = symbols.name(p, endian, sections, index, symbol);
+            }
+        }
+    }
+    let mut line = Line::default();
+    line.field(0, &format!("{:01$x}", r.offset, width))
+        .field(10 + bias, &format!("{:01$x}", r.info, width))
+        .field(19 + 2 * bias, &r_type)
+        .field(42 + 2 * bias, &value)
+        .field(53 + 2 * bias, &name);
+    if let Some(addend) = addend {
+        if name.is_empty() {
+            line.0.push_str(&format!("{:x}", addend));
+        } else if addend < 0 {
+            line.0.push_str(&format!(" - {:x}", addend.unsigned_abs()));
+        } else {
+            line.0.push_str(&format!(" + {:x}", addend));
+        }
+    }
+    line.print(p);
+}
+
+/// Return the relocation type used for `SHT_RELR` relocations.
+fn relative_type(machine: u16) -> u32 {
+    match machine {
+        EM_386 => R_386_RELATIVE,
+        EM_X86_64 => R_X86_64_RELATIVE,
+        EM_AARCH64 => R_AARCH64_RELATIVE,
+        EM_ARM => R_ARM_RELATIVE,
+        EM_PPC => R_PPC_RELATIVE,
+        EM_PPC64 => R_PPC64_RELATIVE,
+        EM_RISCV => R_RISCV_RELATIVE,
+        EM_LOONGARCH => R_LARCH_RELATIVE,
+        EM_S390 => R_390_RELATIVE,
+        EM_SPARC | EM_SPARC32PLUS | EM_SPARCV9 => R_SPARC_RELATIVE,
+        EM_CSKY => R_CKCORE_RELATIVE,
+        _ => 0,
+    }
+}
+
+fn print_symbols<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    data: &[u8],
+    sections: &SectionTable<Elf>,
+    sh_type: u32,
+) {
+    let Some(symbols) = sections.symbols(endian, data, sh_type).print_err(p) else {
+        return;
+    };
+    if symbols.is_empty() {
+        return;
+    }
+    let Some(section) = sections.section(symbols.section()).print_err(p) else {
+        return;
+    };
+    let name = section_name(p, endian, sections, section);
+    writeln!(
+        p.w,
+        "\nSymbol table '{}' contains {} entries:",
+        name,
+        symbols.len()
+    )
+    .unwrap();
+    let symbols = Symbols::new(p, endian, data, sections, symbols);
+
+    let is_64 = elf.is_type_64();
+    let bias = if is_64 { 8 } else { 0 };
+    let width = if is_64 { 16 } else { 8 };
+    // Space for the non-visibility bits of `st_other`.
+    let other_bias = if symbols.symbols.iter().any(|s| s.st_other() & !0x3 != 0) {
+        13
+    } else {
+        0
+    };
+    if is_64 {
+        write!(p.w, "   Num:    Value          Size Type    Bind   Vis").unwrap();
+    } else {
+        write!(p.w, "   Num:    Value  Size Type    Bind   Vis").unwrap();
+    }
+    writeln!(p.w, "{:1$}       Ndx Name", "", other_bias).unwrap();
+
+    let os_abi = elf.e_ident().os_abi;
+    for (index, symbol) in symbols.symbols.enumerate() {
+        let raw_name = symbol.name(endian, symbols.symbols.strings());
+        if !p.options.filter_symbol(index.0, raw_name.ok()) {
+            continue;
+        }
+        let st_type = match symbol.st_type() {
+            STT_NOTYPE => "NOTYPE".into(),
+            STT_OBJECT => "OBJECT".into(),
+            STT_FUNC => "FUNC".into(),
+            STT_SECTION => "SECTION".into(),
+            STT_FILE => "FILE".into(),
+            STT_COMMON => "COMMON".into(),
+            STT_TLS => "TLS".into(),
+            STT_GNU_IFUNC if os_abi == ELFOSABI_GNU || os_abi == ELFOSABI_SYSV => "IFUNC".into(),
+            x => format!("{:x}", x),
+        };
+        let st_bind = match symbol.st_bind() {
+            STB_LOCAL => "LOCAL".into(),
+            STB_GLOBAL => "GLOBAL".into(),
+            STB_WEAK => "WEAK".into(),
+            STB_GNU_UNIQUE => "UNIQUE".into(),
+            x => format!("{:x}", x),
+        };
+        let other = symbol.st_other();
+        let mut visibility = match symbol.st_visibility() {
+            STV_DEFAULT => "DEFAULT",
+            STV_INTERNAL => "INTERNAL",
+            STV_HIDDEN => "HIDDEN",
+            _ => "PROTECTED",
+        }
+        .to_string();
+        if other & !0x3 != 0 {
+            visibility.push_str(&format!(" [<other: 0x{:x}>]", other));
+        }
+        let shndx = match symbol.st_shndx(endian) {
+            SHN_UNDEF => "UND".into(),
+            SHN_ABS => "ABS".into(),
+            SHN_COMMON => "COM".into(),
+            SHN_XINDEX => match symbols.symbols.shndx(endian, index) {
+                Some(shndx) => format!("{:3}", shndx),
+                None => "RSV[0xffff]".into(),
+            },
+            x if (SHN_LOPROC..=SHN_HIPROC).contains(&x) => format!("PRC[0x{:04x}]", x),
+            x if (SHN_LOOS..=SHN_HIOS).contains(&x) => format!("OS[0x{:04x}]", x),
+            x if x >= SHN_LORESERVE => format!("RSV[0x{:04x}]", x),
+            x => format!("{:3}", x),
+        };
+        let name = symbols.name(p, endian, sections, index, symbol);
+        Line::default()
+            .field(0, &format!("{:6}:", index.0))
+            .field(
+                8,
+                &format!("{:01$x}", symbol.st_value(endian).into(), width),
+            )
+            .field(17 + bias, &format!("{:5}", symbol.st_size(endian).into()))
+            .field(23 + bias, &st_type)
+            .field(31 + bias, &st_bind)
+            .field(38 + bias, &visibility)
+            .field(48 + bias + other_bias, &shndx)
+            .field(51 + bias + other_bias, &name)
+            .print(p);
+    }
+}
+
+fn print_notes<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    data: &[u8],
+    segments: &[Elf::ProgramHeader],
+    sections: &SectionTable<Elf>,
+) {
+    // Blank lines separate the note lists, but aren't printed before the first.
+    let mut first = true;
+    let mut separator = |p: &mut Printer<'_>| {
+        if !std::mem::replace(&mut first, false) {
+            writeln!(p.w).unwrap();
+        }
+    };
+    let e_type = elf.e_type(endian);
+    if e_type != ET_CORE && !sections.is_empty() {
+        for section in sections.iter() {
+            if let Some(Some(notes)) = section.notes(endian, data).print_err(p) {
+                separator(p);
+                let name = section_name(p, endian, sections, section);
+                writeln!(p.w, "Displaying notes found in: {}", name).unwrap();
+                print_note_list(p, endian, elf, notes);
+            }
+        }
+    } else {
+        for segment in segments {
+            if let Some(Some(notes)) = segment.notes(endian, data).print_err(p) {
+                separator(p);
+                writeln!(
+                    p.w,
+                    "Displaying notes found at file offset 0x{:08x} with length 0x{:08x}:",
+                    segment.p_offset(endian).into(),
+                    segment.p_filesz(endian).into()
+                )
+                .unwrap();
+                print_note_list(p, endian, elf, notes);
+            }
+        }
+    }
+}
+
+fn print_note_list<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    mut notes: NoteIterator<Elf>,
+) {
+    writeln!(p.w, "  Owner                Data size \tDescription").unwrap();
+    while let Some(Some(note)) = notes.next().print_err(p) {
+        let name = note.name();
+        let n_type = note.n_type(endian);
+        let desc = note.desc();
+        write!(p.w, "  {:<20} 0x{:08x}\t", lossy(name), desc.len()).unwrap();
+        match note_type_name(name, n_type, elf.e_type(endian)) {
+            Some(s) => writeln!(p.w, "{}", s).unwrap(),
+            None => writeln!(p.w, "Unknown note type: (0x{:08x})", n_type).unwrap(),
+        }
+        if name == ELF_NOTE_GNU && print_gnu_note(p, endian, elf, &note) {
+            continue;
+        }
+        if !desc.is_empty() {
+            write!(p.w, "   description data:").unwrap();
+            for byte in desc {
+                write!(p.w, " {:02x}", byte).unwrap();
+            }
+            writeln!(p.w).unwrap();
+        }
+    }
+}
+
+fn note_type_name(name: &[u8], n_type: u32, e_type: u16) -> Option<&'static str> {
+    let s = if name == ELF_NOTE_GNU {
+        match n_type {
+            NT_GNU_ABI_TAG => "NT_GNU_ABI_TAG (ABI version tag)",
+            NT_GNU_HWCAP => "NT_GNU_HWCAP (DSO-supplied software HWCAP info)",
+            NT_GNU_BUILD_ID => "NT_GNU_BUILD_ID (unique build ID bitstring)",
+            NT_GNU_GOLD_VERSION => "NT_GNU_GOLD_VERSION (gold version)",
+            NT_GNU_PROPERTY_TYPE_0 => "NT_GNU_PROPERTY_TYPE_0 (property note)",
+            _ => return None,
+        }
+    } else if e_type == ET_CORE {
+        match n_type {
+            NT_PRSTATUS => "NT_PRSTATUS (prstatus structure)",
+            NT_FPREGSET => "NT_FPREGSET (floating point registers)",
+            NT_PRPSINFO => "NT_PRPSINFO (prpsinfo structure)",
+            NT_TASKSTRUCT => "NT_TASKSTRUCT (task structure)",
+            NT_AUXV => "NT_AUXV (auxiliary vector)",
+            NT_PSTATUS => "NT_PSTATUS (pstatus structure)",
+            NT_PSINFO => "NT_PSINFO (psinfo structure)",
+            NT_LWPSTATUS => "NT_LWPSTATUS (lwpstatus_t structure)",
+            NT_LWPSINFO => "NT_LWPSINFO (lwpsinfo_t structure)",
+            NT_386_TLS => "NT_386_TLS (x86 TLS information)",
+            NT_386_IOPERM => "NT_386_IOPERM (x86 I/O permissions)",
+            NT_X86_XSTATE => "NT_X86_XSTATE (x86 XSAVE extended state)",
+            NT_ARM_VFP => "NT_ARM_VFP (arm VFP registers)",
+            NT_ARM_TLS => "NT_ARM_TLS (AArch TLS registers)",
+            NT_ARM_HW_BREAK => "NT_ARM_HW_BREAK (AArch hardware breakpoint registers)",
+            NT_ARM_HW_WATCH => "NT_ARM_HW_WATCH (AArch hardware watchpoint registers)",
+            NT_FILE => "NT_FILE (mapped files)",
+            NT_PRXFPREG => "NT_PRXFPREG (user_xfpregs structure)",
+            NT_SIGINFO => "NT_SIGINFO (siginfo_t data)",
+            _ => return None,
+        }
+    } else {
+        match n_type {
+            NT_VERSION => "NT_VERSION (version)",
+            NT_ARCH => "NT_ARCH (architecture)",
+            _ => return None,
+        }
+    };
+    Some(s)
+}
+
+/// Print the description of a GNU note.
+///
+/// Returns false if the note type is not handled.
+fn print_gnu_note<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    elf: &Elf,
+    note: &Note<Elf>,
+) -> bool {
+    let desc = note.desc();
+    match note.n_type(endian) {
+        NT_GNU_ABI_TAG => {
+            let mut words = desc
+                .chunks_exact(4)
+                .map(|x| endian.read_u32_bytes(x.try_into().unwrap()));
+            match (words.next(), words.next(), words.next(), words.next()) {
+                (Some(os), Some(major), Some(minor), Some(patch)) => {
+                    let os = [
+                        "Linux", "Hurd", "Solaris", "FreeBSD", "NetBSD", "Syllable", "NaCl",
+                    ]
+                    .get(os as usize)
+                    .unwrap_or(&"Unknown");
+                    write!(p.w, "    OS: {}, ABI: {}.{}.{}", os, major, minor, patch).unwrap();
+                }
+                _ => write!(p.w, "    <corrupt GNU_ABI_TAG>").unwrap(),
+            }
+        }
+        NT_GNU_BUILD_ID => {
+            write!(p.w, "    Build ID: ").unwrap();
+            for byte in desc {
+                write!(p.w, "{:02x}", byte).unwrap();
+            }
+        }
+        NT_GNU_GOLD_VERSION => {
+            let version = desc.split(|c| *c == 0).next().unwrap_or(desc);
+            write!(p.w, "    Version: {}", lossy(version)).unwrap();
+        }
+        NT_GNU_PROPERTY_TYPE_0 => {
+            write!(p.w, "    Properties:").unwrap();
+            if let Some(mut properties) = note.gnu_properties(endian) {
+                loop {
+                    match properties.next() {
+                        Ok(Some(property)) => {
+                            let s = gnu_property(endian, elf, &property);
+                            writeln!(p.w, "    {}", s).unwrap();
+                        }
+                        Ok(None) => break,
+                        Err(_) => {
+                            writeln!(p.w, "    <corrupted GNU_PROPERTY_TYPE_0>").unwrap();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        _ => return false,
+    }
+    writeln!(p.w).unwrap();
+    true
+}
+
+fn gnu_property<Elf: FileHeader>(
+    endian: Elf::Endian,
+    elf: &Elf,
+    property: &GnuProperty<'_>,
+) -> String {
+    fn bits(mut value: u32, names: &[(u32, &str)]) -> String {
+        if value == 0 {
+            return "<None>".into();
+        }
+        let mut s = Vec::new();
+        for (flag, name) in names {
+            if value & flag != 0 {
+                value &= !flag;
+                s.push(name.to_string());
+            }
+        }
+        if value != 0 {
+            s.push(format!("<unknown flags: 0x{:x}>", value));
+        }
+        s.join(", ")
+    }
+
+    let pr_type = property.pr_type();
+    let pr_data = property.pr_data();
+    let machine = elf.e_machine(endian);
+    let (prefix, names): (&str, &[(u32, &str)]) = match pr_type {
+        GNU_PROPERTY_STACK_SIZE => {
+            let size = if elf.is_type_64() {
+                pr_data.try_into().ok().map(|x| endian.read_u64_bytes(x))
+            } else {
+                pr_data
+                    .try_into()
+                    .ok()
+                    .map(|x| endian.read_u32_bytes(x).into())
+            };
+            return match size {
+                Some(size) => format!("stack size: 0x{:x}", size),
+                None => format!("stack size: <corrupt length: 0x{:x}>", pr_data.len()),
+            };
+        }
+        GNU_PROPERTY_NO_COPY_ON_PROTECTED => {
+            return if pr_data.is_empty() {
+                "no copy on protected".into()
+            } else {
+                format!(
+                    "no copy on protected <corrupt length: 0x{:x}>",
+                    pr_data.len()
+                )
+            };
+        }
+        GNU_PROPERTY_AARCH64_FEATURE_1_AND if machine == EM_AARCH64 => (
+            "aarch64 feature: ",
+            &[
+                (GNU_PROPERTY_AARCH64_FEATURE_1_BTI, "BTI"),
+                (GNU_PROPERTY_AARCH64_FEATURE_1_PAC, "PAC"),
+            ],
+        ),
+        GNU_PROPERTY_X86_FEATURE_1_AND => (
+            "x86 feature: ",
+            &[
+                (GNU_PROPERTY_X86_FEATURE_1_IBT, "IBT"),
+                (GNU_PROPERTY_X86_FEATURE_1_SHSTK, "SHSTK"),
+            ],
+        ),
+        GNU_PROPERTY_X86_FEATURE_2_NEEDED | GNU_PROPERTY_X86_FEATURE_2_USED => (
+            if pr_type == GNU_PROPERTY_X86_FEATURE_2_NEEDED {
+                "x86 feature needed: "
+            } else {
+                "x86 feature used: "
+            },
+            &[
+                (1 << 0, "x86"),
+                (1 << 1, "x87"),
+                (1 << 2, "MMX"),
+                (1 << 3, "XMM"),
+                (1 << 4, "YMM"),
+                (1 << 5, "ZMM"),
+                (1 << 6, "FXSR"),
+                (1 << 7, "XSAVE"),
+                (1 << 8, "XSAVEOPT"),
+                (1 << 9, "XSAVEC"),
+            ],
+        ),
+        GNU_PROPERTY_X86_ISA_1_NEEDED | GNU_PROPERTY_X86_ISA_1_USED => (
+            if pr_type == GNU_PROPERTY_X86_ISA_1_NEEDED {
+                "x86 ISA needed: "
+            } else {
+                "x86 ISA used: "
+            },
+            &[
+                (GNU_PROPERTY_X86_ISA_1_BASELINE, "x86-64-baseline"),
+                (GNU_PROPERTY_X86_ISA_1_V2, "x86-64-v2"),
+                (GNU_PROPERTY_X86_ISA_1_V3, "x86-64-v3"),
+                (GNU_PROPERTY_X86_ISA_1_V4, "x86-64-v4"),
+            ],
+        ),
+        _ => return format!("<application-specific type 0x{:x}>", pr_type),
+    };
+    match property.data_u32(endian) {
+        Ok(value) if pr_data.len() == 4 => format!("{}{}", prefix, bits(value, names)),
+        _ => format!("{}<corrupt length: 0x{:x}>", prefix, pr_data.len()),
+    }
+}
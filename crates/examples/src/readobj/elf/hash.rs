@@ -0,0 +1,309 @@
+//! Consistency checks for ELF hash tables.
+//!
+//! These check that the `.hash` and `.gnu.hash` contents agree with the
+//! symbol table that they index, so that the dynamic loader is able to
+//! find every symbol.
+
+use super::*;
+use object::{elf, Bytes, U32, U64};
+
+pub(super) fn check_hash<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    data: &[u8],
+    sections: &SectionTable<Elf>,
+    section: &Elf::SectionHeader,
+) {
+    let mut problems = Vec::new();
+    if let Some(symbols) = sections
+        .symbol_table_by_index(endian, data, SectionIndex(section.sh_link(endian) as usize))
+        .print_err(p)
+    {
+        if let Some(hash_data) = section.data(endian, data).print_err(p) {
+            check_sysv(endian, hash_data, &symbols, &mut problems);
+        }
+    }
+    print_problems(p, problems);
+}
+
+pub(super) fn check_gnu_hash<Elf: FileHeader>(
+    p: &mut Printer<'_>,
+    endian: Elf::Endian,
+    data: &[u8],
+    elf: &Elf,
+    sections: &SectionTable<Elf>,
+    section: &Elf::SectionHeader,
+) {
+    let mut problems = Vec::new();
+    if let Some(symbols) = sections
+        .symbol_table_by_index(endian, data, SectionIndex(section.sh_link(endian) as usize))
+        .print_err(p)
+    {
+        if let Some(hash_data) = section.data(endian, data).print_err(p) {
+            let is_64 = elf.is_type_64();
+            check_gnu(endian, is_64, hash_data, &symbols, &mut problems);
+        }
+    }
+    print_problems(p, problems);
+}
+
+fn print_problems(p: &mut Printer<'_>, problems: Vec<String>) {
+    p.group("HashCheck", |p| {
+        p.field("Problems", problems.len());
+        for problem in &problems {
+            p.field("Problem", problem);
+        }
+    });
+}
+
+/// Return the symbol names, with `None` for names that can't be read.
+fn symbol_names<'data, Elf: FileHeader>(
+    endian: Elf::Endian,
+    symbols: &SymbolTable<'data, Elf>,
+) -> Vec<Option<&'data [u8]>> {
+    symbols
+        .iter()
+        .map(|symbol| symbols.symbol_name(endian, symbol).ok())
+        .collect()
+}
+
+fn describe(index: usize, name: Option<&[u8]>) -> String {
+    match name {
+        Some(name) => format!("symbol {} \"{}\"", index, String::from_utf8_lossy(name)),
+        None => format!("symbol {} <invalid name>", index),
+    }
+}
+
+fn check_sysv<Elf: FileHeader>(
+    endian: Elf::Endian,
+    data: &[u8],
+    symbols: &SymbolTable<'_, Elf>,
+    problems: &mut Vec<String>,
+) {
+    let mut data = Bytes(data);
+    let Ok(header) = data.read::<HashHeader<Elf::Endian>>() else {
+        problems.push("hash header is truncated".into());
+        return;
+    };
+    let bucket_count = header.bucket_count.get(endian) as usize;
+    let chain_count = header.chain_count.get(endian) as usize;
+    let Ok(buckets) = data.read_slice::<U32<Elf::Endian>>(bucket_count) else {
+        problems.push("hash buckets are truncated".into());
+        return;
+    };
+    let Ok(chains) = data.read_slice::<U32<Elf::Endian>>(chain_count) else {
+        problems.push("hash chains are truncated".into());
+        return;
+    };
+    if bucket_count == 0 {
+        problems.push("hash table has no buckets".into());
+        return;
+    }
+    let names = symbol_names(endian, symbols);
+    if chain_count != names.len() {
+        problems.push(format!(
+            "chain count {} does not match symbol count {}",
+            chain_count,
+            names.len()
+        ));
+    }
+
+    // Walk each chain, checking that it terminates and that each symbol
+    // is in the bucket for its hash.
+    let mut found = vec![false; names.len()];
+    for (bucket_index, bucket) in buckets.iter().enumerate() {
+        let mut index = bucket.get(endian) as usize;
+        let mut length = 0;
+        while index != 0 {
+            if index >= chain_count {
+                problems.push(format!(
+                    "chain for bucket {} has out of range index {}",
+                    bucket_index, index
+                ));
+                break;
+            }
+            length += 1;
+            if length > chain_count {
+                problems.push(format!(
+                    "chain for bucket {} does not terminate",
+                    bucket_index
+                ));
+                break;
+            }
+            if let Some(name) = names.get(index) {
+                if let Some(name) = name {
+                    let expected = elf::hash(name) as usize % bucket_count;
+                    if expected != bucket_index {
+                        problems.push(format!(
+                            "{} is in bucket {} but hashes to bucket {}",
+                            describe(index, Some(name)),
+                            bucket_index,
+                            expected
+                        ));
+                    }
+                }
+                if found[index] {
+                    problems.push(format!(
+                        "{} is in more than one chain",
+                        describe(index, *name)
+                    ));
+                }
+                found[index] = true;
+            }
+            index = chains[index].get(endian) as usize;
+        }
+    }
+
+    // Every named symbol must be reachable.
+    for (index, name) in names.iter().enumerate().skip(1) {
+        if !found[index] && name.map(|name| !name.is_empty()).unwrap_or(true) {
+            problems.push(format!("{} is not in any chain", describe(index, *name)));
+        }
+    }
+}
+
+fn check_gnu<Elf: FileHeader>(
+    endian: Elf::Endian,
+    is_64: bool,
+    data: &[u8],
+    symbols: &SymbolTable<'_, Elf>,
+    problems: &mut Vec<String>,
+) {
+    let mut data = Bytes(data);
+    let Ok(header) = data.read::<GnuHashHeader<Elf::Endian>>() else {
+        problems.push("GNU hash header is truncated".into());
+        return;
+    };
+    let bucket_count = header.bucket_count.get(endian) as usize;
+    let symbol_base = header.symbol_base.get(endian) as usize;
+    let bloom_count = header.bloom_count.get(endian) as usize;
+    let bloom_shift = header.bloom_shift.get(endian);
+    let word_bits = if is_64 { 64 } else { 32 };
+    let bloom: Result<Vec<u64>, ()> = if is_64 {
+        data.read_slice::<U64<Elf::Endian>>(bloom_count)
+            .map(|bloom| bloom.iter().map(|word| word.get(endian)).collect())
+    } else {
+        data.read_slice::<U32<Elf::Endian>>(bloom_count)
+            .map(|bloom| bloom.iter().map(|word| word.get(endian).into()).collect())
+    };
+    let Ok(bloom) = bloom else {
+        problems.push("GNU hash bloom filter is truncated".into());
+        return;
+    };
+    let Ok(buckets) = data.read_slice::<U32<Elf::Endian>>(bucket_count) else {
+        problems.push("GNU hash buckets are truncated".into());
+        return;
+    };
+    let values = data
+        .read_slice::<U32<Elf::Endian>>(data.len() / 4)
+        .unwrap_or(&[]);
+
+    let names = symbol_names(endian, symbols);
+    if symbol_base > names.len() {
+        problems.push(format!(
+            "symbol base {} is larger than symbol count {}",
+            symbol_base,
+            names.len()
+        ));
+        return;
+    }
+    if bucket_count == 0 {
+        problems.push("GNU hash table has no buckets".into());
+        return;
+    }
+    let hashed = names.len() - symbol_base;
+    if values.len() < hashed {
+        problems.push(format!(
+            "GNU hash has {} chain values but {} hashed symbols",
+            values.len(),
+            hashed
+        ));
+    }
+    if !bloom_count.is_power_of_two() {
+        problems.push(format!(
+            "bloom filter size {} is not a power of 2",
+            bloom_count
+        ));
+    }
+
+    // Walk each chain, checking that it terminates and that each symbol
+    // has the correct hash value.
+    let mut found = vec![false; names.len()];
+    for (bucket_index, bucket) in buckets.iter().enumerate() {
+        let mut index = bucket.get(endian) as usize;
+        if index == 0 {
+            continue;
+        }
+        if index < symbol_base {
+            problems.push(format!(
+                "bucket {} index {} is less than symbol base {}",
+                bucket_index, index, symbol_base
+            ));
+            continue;
+        }
+        loop {
+            let (Some(name), Some(value)) = (names.get(index), values.get(index - symbol_base))
+            else {
+                problems.push(format!(
+                    "chain for bucket {} does not terminate",
+                    bucket_index
+                ));
+                break;
+            };
+            let value = value.get(endian);
+            if let Some(name) = name {
+                let hash = elf::gnu_hash(name);
+                if value | 1 != hash | 1 {
+                    problems.push(format!(
+                        "{} has hash value {:#x} but hashes to {:#x}",
+                        describe(index, Some(name)),
+                        value & !1,
+                        hash & !1
+                    ));
+                }
+                let expected = hash as usize % bucket_count;
+                if expected != bucket_index {
+                    problems.push(format!(
+                        "{} is in bucket {} but hashes to bucket {}",
+                        describe(index, Some(name)),
+                        bucket_index,
+                        expected
+                    ));
+                }
+            }
+            if found[index] {
+                problems.push(format!(
+                    "{} is in more than one chain",
+                    describe(index, *name)
+                ));
+            }
+            found[index] = true;
+            if value & 1 != 0 {
+                break;
+            }
+            index += 1;
+        }
+    }
+
+    for (index, name) in names.iter().enumerate().skip(symbol_base) {
+        if !found[index] {
+            problems.push(format!("{} is not in any chain", describe(index, *name)));
+        }
+        // Check that the bloom filter accepts the symbol.
+        let Some(name) = name else {
+            continue;
+        };
+        if !bloom_count.is_power_of_two() {
+            continue;
+        }
+        let hash = elf::gnu_hash(name);
+        let word = bloom[(hash / word_bits) as usize & (bloom_count - 1)];
+        let mask = (1 << (hash % word_bits)) | (1 << ((hash >> bloom_shift) % word_bits));
+        if word & mask != mask {
+            problems.push(format!(
+                "{} is rejected by the bloom filter",
+                describe(index, Some(name))
+            ));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! A minimal JSON document builder for the `--json` output of readobj.
+//!
+//! Each group is a JSON object. The fields and groups in an object are keyed by
+//! their names. Groups are always stored in an array, since a group name may be
+//! used multiple times (such as for each symbol). A field name that is used
+//! more than once in an object is also stored in an array.
+
+use std::fmt;
+use std::io::{self, Write};
+
+/// A JSON value.
+pub(super) enum Value {
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Object),
+}
+
+impl Value {
+    /// A value that is printed using `Display`.
+    ///
+    /// Integers are converted to JSON numbers, and everything else to strings.
+    pub(super) fn display<T: fmt::Display>(value: T) -> Self {
+        let s = value.to_string();
+        let digits = s.strip_prefix('-').unwrap_or(&s);
+        let is_integer = !digits.is_empty()
+            && digits.bytes().all(|c| c.is_ascii_digit())
+            && (digits == "0" || !digits.starts_with('0'));
+        if is_integer {
+            Value::Number(s)
+        } else {
+            Value::String(s)
+        }
+    }
+
+    /// A value that is printed in hexadecimal for text output.
+    pub(super) fn hex<T: fmt::UpperHex>(value: T) -> Self {
+        let s = format!("{:X}", value);
+        match u128::from_str_radix(&s, 16) {
+            Ok(n) => Value::Number(n.to_string()),
+            Err(_) => Value::String(format!("0x{}", s)),
+        }
+    }
+
+    /// A byte string.
+    ///
+    /// Invalid UTF-8 is stored as an array of bytes.
+    pub(super) fn string(s: &[u8]) -> Self {
+        match std::str::from_utf8(s) {
+            Ok(s) => Value::String(s.to_string()),
+            Err(_) => Value::bytes(s),
+        }
+    }
+
+    /// An array of bytes.
+    pub(super) fn bytes(s: &[u8]) -> Self {
+        Value::Array(s.iter().map(|b| Value::Number(b.to_string())).collect())
+    }
+
+    fn write(&self, w: &mut dyn Write, indent: usize) -> io::Result<()> {
+        match self {
+            Value::Number(n) => write!(w, "{}", n),
+            Value::String(s) => write_string(w, s),
+            Value::Array(values) => {
+                if values.is_empty() {
+                    return write!(w, "[]");
+                }
+                writeln!(w, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    write!(w, "{:1$}", "", (indent + 1) * 2)?;
+                    value.write(w, indent + 1)?;
+                    if i + 1 != values.len() {
+                        write!(w, ",")?;
+                    }
+                    writeln!(w)?;
+                }
+                write!(w, "{:1$}]", "", indent * 2)
+            }
+            Value::Object(object) => object.write(w, indent),
+        }
+    }
+}
+
+/// A JSON object.
+#[derive(Default)]
+pub(super) struct Object {
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    name: String,
+    value: Value,
+    group: bool,
+}
+
+impl Object {
+    /// Create an object from a list of fields.
+    pub(super) fn new(fields: Vec<(&str, Value)>) -> Self {
+        let mut object = Object::default();
+        for (name, value) in fields {
+            object.field(name, value);
+        }
+        object
+    }
+
+    /// Add a field.
+    pub(super) fn field(&mut self, name: &str, value: Value) {
+        self.entries.push(Entry {
+            name: name.to_string(),
+            value,
+            group: false,
+        });
+    }
+
+    /// Add flags to the most recently added field.
+    ///
+    /// The field value is converted to an object with a `Value` field
+    /// if needed, and the flags are appended to its `Flags` field.
+    pub(super) fn flags(&mut self, mut flags: Vec<Value>) {
+        let Some(entry) = self.entries.last_mut().filter(|entry| !entry.group) else {
+            self.field("Flags", Value::Array(flags));
+            return;
+        };
+        if !matches!(entry.value, Value::Object(_)) {
+            let value = std::mem::replace(&mut entry.value, Value::Array(Vec::new()));
+            entry.value = Value::Object(Object::new(vec![("Value", value)]));
+        }
+        let Value::Object(object) = &mut entry.value else {
+            unreachable!();
+        };
+        for entry in &mut object.entries {
+            if let (false, "Flags", Value::Array(values)) =
+                (entry.group, entry.name.as_str(), &mut entry.value)
+            {
+                values.append(&mut flags);
+                return;
+            }
+        }
+        object.field("Flags", Value::Array(flags));
+    }
+
+    fn write(&self, w: &mut dyn Write, indent: usize) -> io::Result<()> {
+        // Collect entries with the same name, in order of first use.
+        let mut names: Vec<(&str, Vec<&Entry>)> = Vec::new();
+        for entry in &self.entries {
+            match names.iter_mut().find(|(name, _)| *name == entry.name) {
+                Some((_, entries)) => entries.push(entry),
+                None => names.push((&entry.name, vec![entry])),
+            }
+        }
+        if names.is_empty() {
+            return write!(w, "{{}}");
+        }
+        writeln!(w, "{{")?;
+        for (i, (name, entries)) in names.iter().enumerate() {
+            write!(w, "{:1$}", "", (indent + 1) * 2)?;
+            write_string(w, name)?;
+            write!(w, ": ")?;
+            if entries.len() == 1 && !entries[0].group {
+                entries[0].value.write(w, indent + 1)?;
+            } else {
+                writeln!(w, "[")?;
+                for (j, entry) in entries.iter().enumerate() {
+                    write!(w, "{:1$}", "", (indent + 2) * 2)?;
+                    entry.value.write(w, indent + 2)?;
+                    if j + 1 != entries.len() {
+                        write!(w, ",")?;
+                    }
+                    writeln!(w)?;
+                }
+                write!(w, "{:1$}]", "", (indent + 1) * 2)?;
+            }
+            if i + 1 != names.len() {
+                write!(w, ",")?;
+            }
+            writeln!(w)?;
+        }
+        write!(w, "{:1$}}}", "", indent * 2)
+    }
+}
+
+/// Builds a JSON document from nested groups.
+#[derive(Default)]
+pub(super) struct Builder {
+    root: Object,
+    groups: Vec<(String, Object)>,
+}
+
+impl Builder {
+    /// The object for the current group.
+    pub(super) fn current(&mut self) -> &mut Object {
+        match self.groups.last_mut() {
+            Some((_, object)) => object,
+            None => &mut self.root,
+        }
+    }
+
+    pub(super) fn begin_group(&mut self, name: &str) {
+        self.groups.push((name.to_string(), Object::default()));
+    }
+
+    pub(super) fn end_group(&mut self) {
+        let (name, object) = self.groups.pop().unwrap();
+        self.current().entries.push(Entry {
+            name,
+            value: Value::Object(object),
+            group: true,
+        });
+    }
+
+    /// Write the document.
+    pub(super) fn write(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.root.write(w, 0)?;
+        writeln!(w)
+    }
+}
+
+fn write_string(w: &mut dyn Write, s: &str) -> io::Result<()> {
+    write!(w, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(w, "\\\"")?,
+            '\\' => write!(w, "\\\\")?,
+            '\n' => write!(w, "\\n")?,
+            '\r' => write!(w, "\\r")?,
+            '\t' => write!(w, "\\t")?,
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => write!(w, "{}", c)?,
+        }
+    }
+    write!(w, "\"")
+}
@@ -1,7 +1,7 @@
 use super::*;
 use object::macho::*;
 use object::read::macho::*;
-use object::BigEndian;
+use object::{BigEndian, Bytes, Endian, U16, U32, U64};
 
 pub(super) fn print_dyld_cache(p: &mut Printer<'_>, data: &[u8], subcache_data: &[&[u8]]) {
     if let Some(header) = DyldCacheHeader::<Endianness>::parse(data).print_err(p) {
@@ -115,7 +115,7 @@ pub(super) fn print_dyld_cache_images(p: &mut Printer<'_>, cache: &DyldCache) {
             if p.options.file {
                 p.blank();
             }
-            print_object_at(p, data, offset);
+            p.nested("Objects", |p| print_object_at(p, data, offset));
             p.blank();
         }
     }
@@ -123,7 +123,7 @@ pub(super) fn print_dyld_cache_images(p: &mut Printer<'_>, cache: &DyldCache) {
 
 pub(super) fn print_macho_fat32(p: &mut Printer<'_>, data: &[u8]) {
     if let Some(fat) = MachOFatFile32::parse(data).print_err(p) {
-        writeln!(p.w(), "Format: Mach-O Fat 32-bit").unwrap();
+        p.format(format_args!("Mach-O Fat 32-bit"));
         print_fat_header(p, fat.header());
         for arch in fat.arches() {
             print_fat_arch(p, arch);
@@ -131,7 +131,7 @@ pub(super) fn print_macho_fat32(p: &mut Printer<'_>, data: &[u8]) {
         for arch in fat.arches() {
             if let Some(data) = arch.data(data).print_err(p) {
                 p.blank();
-                print_object(p, data, &[]);
+                p.nested("Objects", |p| print_object(p, data, &[]));
             }
         }
     }
@@ -139,7 +139,7 @@ pub(super) fn print_macho_fat32(p: &mut Printer<'_>, data: &[u8]) {
 
 pub(super) fn print_macho_fat64(p: &mut Printer<'_>, data: &[u8]) {
     if let Some(fat) = MachOFatFile64::parse(data).print_err(p) {
-        writeln!(p.w(), "Format: Mach-O Fat 64-bit").unwrap();
+        p.format(format_args!("Mach-O Fat 64-bit"));
         print_fat_header(p, fat.header());
         for arch in fat.arches() {
             print_fat_arch(p, arch);
@@ -147,7 +147,7 @@ pub(super) fn print_macho_fat64(p: &mut Printer<'_>, data: &[u8]) {
         for arch in fat.arches() {
             if let Some(data) = arch.data(data).print_err(p) {
                 p.blank();
-                print_object(p, data, &[]);
+                p.nested("Objects", |p| print_object(p, data, &[]));
             }
         }
     }
@@ -177,14 +177,14 @@ pub(super) fn print_fat_arch<Arch: FatArch>(p: &mut Printer<'_>, arch: &Arch) {
 
 pub(super) fn print_macho32(p: &mut Printer<'_>, data: &[u8], offset: u64) {
     if let Some(header) = MachHeader32::parse(data, offset).print_err(p) {
-        writeln!(p.w(), "Format: Mach-O 32-bit").unwrap();
+        p.format(format_args!("Mach-O 32-bit"));
         print_macho(p, header, data, offset);
     }
 }
 
 pub(super) fn print_macho64(p: &mut Printer<'_>, data: &[u8], offset: u64) {
     if let Some(header) = MachHeader64::parse(data, offset).print_err(p) {
-        writeln!(p.w(), "Format: Mach-O 64-bit").unwrap();
+        p.format(format_args!("Mach-O 64-bit"));
         print_macho(p, header, data, offset);
     }
 }
@@ -192,9 +192,37 @@ pub(super) fn print_macho64(p: &mut Printer<'_>, data: &[u8], offset: u64) {
 #[derive(Default)]
 struct MachState<'a> {
     cputype: u32,
+    is_64: bool,
     symbols: Vec<Option<&'a [u8]>>,
     sections: Vec<Vec<u8>>,
     section_index: usize,
+    segments: Vec<MachSegment<'a>>,
+    dylibs: Vec<Option<&'a [u8]>>,
+}
+
+impl<'a> MachState<'a> {
+    fn pointer_size(&self) -> u64 {
+        if self.is_64 {
+            8
+        } else {
+            4
+        }
+    }
+
+    /// The address that export trie offsets are relative to.
+    fn base_address(&self) -> u64 {
+        self.segments
+            .iter()
+            .find(|segment| segment.name == b"__TEXT")
+            .map(|segment| segment.address)
+            .unwrap_or(0)
+    }
+}
+
+struct MachSegment<'a> {
+    name: &'a [u8],
+    address: u64,
+    file_offset: u64,
 }
 
 fn print_macho<Mach: MachHeader<Endian = Endianness>>(
@@ -206,12 +234,18 @@ fn print_macho<Mach: MachHeader<Endian = Endianness>>(
     if let Some(endian) = header.endian().print_err(p) {
         let mut state = MachState {
             cputype: header.cputype(endian),
+            is_64: header.is_type_64(),
             sections: vec![vec![]],
             ..MachState::default()
         };
         if let Ok(mut commands) = header.load_commands(endian, data, 0) {
             while let Ok(Some(command)) = commands.next() {
                 if let Ok(Some((segment, section_data))) = Mach::Segment::from_command(command) {
+                    state.segments.push(MachSegment {
+                        name: segment.name(),
+                        address: segment.vmaddr(endian).into(),
+                        file_offset: segment.fileoff(endian).into(),
+                    });
                     if let Ok(segment_sections) = segment.sections(endian, section_data) {
                         state
                             .sections
@@ -231,6 +265,10 @@ fn print_macho<Mach: MachHeader<Endian = Endianness>>(
                                 .map(|symbol| symbol.name(endian, symtab.strings()).ok()),
                         );
                     }
+                } else if let Ok(Some(dylib)) = command.dylib() {
+                    state
+                        .dylibs
+                        .push(command.string(endian, dylib.dylib.name).ok());
                 }
             }
         }
@@ -288,6 +326,12 @@ fn print_load_command<Mach: MachHeader>(
             LoadCommandVariant::Symtab(symtab) => {
                 print_symtab::<Mach>(p, endian, data, symtab, state);
             }
+            LoadCommandVariant::LinkeditData(x) => {
+                print_linkedit_data(p, endian, data, x, state);
+            }
+            LoadCommandVariant::DyldInfo(x) => {
+                print_dyld_info(p, endian, data, x, state);
+            }
             _ => {}
         }
         if !p.options.macho_load_commands {
@@ -296,7 +340,9 @@ fn print_load_command<Mach: MachHeader>(
         match variant {
             LoadCommandVariant::Segment32(..)
             | LoadCommandVariant::Segment64(..)
-            | LoadCommandVariant::Symtab(..) => {}
+            | LoadCommandVariant::Symtab(..)
+            | LoadCommandVariant::LinkeditData(..)
+            | LoadCommandVariant::DyldInfo(..) => {}
             LoadCommandVariant::Thread(x, _thread_data) => {
                 p.group("ThreadCommand", |p| {
                     p.field_enum("Cmd", x.cmd.get(endian), FLAGS_LC);
@@ -467,7 +513,7 @@ fn print_load_command<Mach: MachHeader>(
                 p.group("UuidCommand", |p| {
                     p.field_enum("Cmd", x.cmd.get(endian), FLAGS_LC);
                     p.field_hex("CmdSize", x.cmdsize.get(endian));
-                    p.field("Uuid", format!("{:X?}", x.uuid));
+                    p.field_bytes("Uuid", &x.uuid);
                 });
             }
             LoadCommandVariant::Rpath(x) => {
@@ -481,14 +527,6 @@ fn print_load_command<Mach: MachHeader>(
                     );
                 });
             }
-            LoadCommandVariant::LinkeditData(x) => {
-                p.group("LinkeditDataCommand", |p| {
-                    p.field_enum("Cmd", x.cmd.get(endian), FLAGS_LC);
-                    p.field_hex("CmdSize", x.cmdsize.get(endian));
-                    p.field_hex("DataOffset", x.dataoff.get(endian));
-                    p.field_hex("DataSize", x.datasize.get(endian));
-                });
-            }
             LoadCommandVariant::EncryptionInfo32(x) => {
                 p.group("EncryptionInfoCommand32", |p| {
                     p.field_enum("Cmd", x.cmd.get(endian), FLAGS_LC);
@@ -508,23 +546,6 @@ fn print_load_command<Mach: MachHeader>(
                     p.field_hex("Pad", x.pad.get(endian));
                 });
             }
-            LoadCommandVariant::DyldInfo(x) => {
-                p.group("DyldInfoCommand", |p| {
-                    p.field_enum("Cmd", x.cmd.get(endian), FLAGS_LC);
-                    p.field_hex("CmdSize", x.cmdsize.get(endian));
-                    // TODO: dump the tables these are all pointing to
-                    p.field_hex("RebaseOffset", x.rebase_off.get(endian));
-                    p.field_hex("RebaseSize", x.rebase_size.get(endian));
-                    p.field_hex("BindOffset", x.bind_off.get(endian));
-                    p.field_hex("BindSize", x.bind_size.get(endian));
-                    p.field_hex("WeakBindOffset", x.weak_bind_off.get(endian));
-                    p.field_hex("WeakBindSize", x.weak_bind_size.get(endian));
-                    p.field_hex("LazyBindOffset", x.lazy_bind_off.get(endian));
-                    p.field_hex("LazyBindSize", x.lazy_bind_size.get(endian));
-                    p.field_hex("ExportOffset", x.export_off.get(endian));
-                    p.field_hex("ExportSize", x.export_size.get(endian));
-                });
-            }
             LoadCommandVariant::VersionMin(x) => {
                 p.group("VersionMinCommand", |p| {
                     p.field_enum("Cmd", x.cmd.get(endian), FLAGS_LC);
@@ -561,7 +582,7 @@ fn print_load_command<Mach: MachHeader>(
                     p.field_enum("Cmd", x.cmd.get(endian), FLAGS_LC);
                     p.field_hex("CmdSize", x.cmdsize.get(endian));
                     // TODO: string?
-                    p.field("DataOwner", format!("{:X?}", x.data_owner));
+                    p.field_bytes("DataOwner", &x.data_owner);
                     p.field_hex("Offset", x.offset.get(endian));
                     p.field_hex("Size", x.size.get(endian));
                 });
@@ -657,6 +678,12 @@ fn print_section<S: Section>(
     if !p.options.sections && !(p.options.relocations && section.nreloc(endian) != 0) {
         return;
     }
+    if !p
+        .options
+        .filter_section(state.section_index, Some(section.name()))
+    {
+        return;
+    }
     p.group("Section", |p| {
         p.field("Index", state.section_index);
         p.field_inline_string("SectionName", section.name());
@@ -720,8 +747,9 @@ fn print_section_relocations<S: Section>(
                             .symbols
                             .get(info.r_symbolnum as usize)
                             .copied()
-                            .flatten();
-                        p.field_string_option("Symbol", info.r_symbolnum, name);
+                            .flatten()
+                            .map(|name| p.demangle(name));
+                        p.field_string_option("Symbol", info.r_symbolnum, name.as_deref());
                     } else {
                         let name = state
                             .sections
@@ -771,13 +799,14 @@ fn print_symtab_symbols<Mach: MachHeader>(
     }
     if let Some(symbols) = symtab.symbols::<Mach, _>(endian, data).print_err(p) {
         for (index, nlist) in symbols.iter().enumerate() {
+            let name = nlist.name(endian, symbols.strings());
+            if !p.options.filter_symbol(index, name.ok()) {
+                continue;
+            }
             p.group("Nlist", |p| {
                 p.field("Index", index);
-                p.field_string(
-                    "String",
-                    nlist.n_strx(endian),
-                    nlist.name(endian, symbols.strings()),
-                );
+                let name = name.map(|name| p.demangle(name));
+                p.field_string("String", nlist.n_strx(endian), name.as_deref());
                 let n_type = nlist.n_type();
                 if nlist.is_stab() {
                     p.field_enum("Type", n_type, FLAGS_N_STAB);
@@ -806,6 +835,828 @@ fn print_symtab_symbols<Mach: MachHeader>(
     }
 }
 
+fn print_linkedit_data<E: Endian>(
+    p: &mut Printer<'_>,
+    endian: E,
+    data: &[u8],
+    command: &LinkeditDataCommand<E>,
+    state: &MachState,
+) {
+    let cmd = command.cmd.get(endian);
+    let dyld_info =
+        p.options.macho_dyld_info && (cmd == LC_DYLD_EXPORTS_TRIE || cmd == LC_DYLD_CHAINED_FIXUPS);
+    if !p.options.macho_load_commands && !dyld_info {
+        return;
+    }
+    p.group("LinkeditDataCommand", |p| {
+        p.field_enum("Cmd", cmd, FLAGS_LC);
+        p.field_hex("CmdSize", command.cmdsize.get(endian));
+        p.field_hex("DataOffset", command.dataoff.get(endian));
+        p.field_hex("DataSize", command.datasize.get(endian));
+        if !dyld_info {
+            return;
+        }
+        let offset = command.dataoff.get(endian);
+        let size = command.datasize.get(endian);
+        if let Some(linkedit_data) = linkedit_data(data, offset, size).print_err(p) {
+            if cmd == LC_DYLD_EXPORTS_TRIE {
+                print_exports_trie(p, linkedit_data, state).print_err(p);
+            } else {
+                print_chained_fixups(p, endian, data, linkedit_data, state).print_err(p);
+            }
+        }
+    });
+}
+
+fn print_dyld_info<E: Endian>(
+    p: &mut Printer<'_>,
+    endian: E,
+    data: &[u8],
+    command: &DyldInfoCommand<E>,
+    state: &MachState,
+) {
+    if !p.options.macho_load_commands && !p.options.macho_dyld_info {
+        return;
+    }
+    p.group("DyldInfoCommand", |p| {
+        p.field_enum("Cmd", command.cmd.get(endian), FLAGS_LC);
+        p.field_hex("CmdSize", command.cmdsize.get(endian));
+        p.field_hex("RebaseOffset", command.rebase_off.get(endian));
+        p.field_hex("RebaseSize", command.rebase_size.get(endian));
+        p.field_hex("BindOffset", command.bind_off.get(endian));
+        p.field_hex("BindSize", command.bind_size.get(endian));
+        p.field_hex("WeakBindOffset", command.weak_bind_off.get(endian));
+        p.field_hex("WeakBindSize", command.weak_bind_size.get(endian));
+        p.field_hex("LazyBindOffset", command.lazy_bind_off.get(endian));
+        p.field_hex("LazyBindSize", command.lazy_bind_size.get(endian));
+        p.field_hex("ExportOffset", command.export_off.get(endian));
+        p.field_hex("ExportSize", command.export_size.get(endian));
+        if !p.options.macho_dyld_info {
+            return;
+        }
+        let rebase = linkedit_data(
+            data,
+            command.rebase_off.get(endian),
+            command.rebase_size.get(endian),
+        );
+        if let Some(rebase) = rebase.print_err(p) {
+            print_rebase_opcodes(p, rebase, state).print_err(p);
+        }
+        let binds = [
+            (
+                BindKind::Bind,
+                command.bind_off.get(endian),
+                command.bind_size.get(endian),
+            ),
+            (
+                BindKind::WeakBind,
+                command.weak_bind_off.get(endian),
+                command.weak_bind_size.get(endian),
+            ),
+            (
+                BindKind::LazyBind,
+                command.lazy_bind_off.get(endian),
+                command.lazy_bind_size.get(endian),
+            ),
+        ];
+        for (kind, offset, size) in binds {
+            if let Some(bind) = linkedit_data(data, offset, size).print_err(p) {
+                print_bind_opcodes(p, kind, bind, state).print_err(p);
+            }
+        }
+        let exports = linkedit_data(
+            data,
+            command.export_off.get(endian),
+            command.export_size.get(endian),
+        );
+        if let Some(exports) = exports.print_err(p) {
+            print_exports_trie(p, exports, state).print_err(p);
+        }
+    });
+}
+
+fn linkedit_data(data: &[u8], offset: u32, size: u32) -> Result<&[u8], &'static str> {
+    data.get(offset as usize..)
+        .and_then(|data| data.get(..size as usize))
+        .ok_or("Invalid dyld info offset or size")
+}
+
+fn read_uleb128(data: &mut Bytes<'_>) -> Result<u64, &'static str> {
+    data.read_uleb128()
+        .map_err(|()| "Invalid ULEB128 in dyld info")
+}
+
+fn read_sleb128(data: &mut Bytes<'_>) -> Result<i64, &'static str> {
+    data.read_sleb128()
+        .map_err(|()| "Invalid SLEB128 in dyld info")
+}
+
+fn read_string<'data>(data: &mut Bytes<'data>) -> Result<&'data [u8], &'static str> {
+    data.read_string()
+        .map_err(|()| "Invalid string in dyld info")
+}
+
+/// Print the segment and address of a rebase or bind.
+fn print_dyld_location(p: &mut Printer<'_>, state: &MachState, segment_index: u8, offset: u64) {
+    let segment = state.segments.get(segment_index as usize);
+    p.field_string_option("Segment", segment_index, segment.map(|s| s.name));
+    p.field_hex("SegmentOffset", offset);
+    if let Some(segment) = segment {
+        p.field_hex("Address", segment.address.wrapping_add(offset));
+    }
+}
+
+/// Print a library ordinal, which may be a special value.
+fn print_dylib_ordinal(p: &mut Printer<'_>, state: &MachState, ordinal: i64) {
+    if ordinal > 0 {
+        let name = state.dylibs.get(ordinal as usize - 1).copied().flatten();
+        p.field_string_option("Dylib", ordinal, name);
+    } else {
+        p.field_enum_display("Dylib", ordinal as i8, FLAGS_BIND_SPECIAL_DYLIB);
+    }
+}
+
+/// Decode the rebase opcodes and print the rebase table.
+fn print_rebase_opcodes(
+    p: &mut Printer<'_>,
+    data: &[u8],
+    state: &MachState,
+) -> Result<(), &'static str> {
+    let pointer_size = state.pointer_size();
+    let mut data = Bytes(data);
+    let mut rebase_type = 0;
+    let mut segment_index = 0;
+    let mut offset = 0u64;
+    let print_rebase = |p: &mut Printer<'_>, offset: u64, rebase_type, segment_index| {
+        p.group("Rebase", |p| {
+            print_dyld_location(p, state, segment_index, offset);
+            p.field_enum("Type", rebase_type, FLAGS_REBASE_TYPE);
+        });
+    };
+    while let Ok(byte) = data.read::<u8>() {
+        let immediate = byte & REBASE_IMMEDIATE_MASK;
+        match byte & REBASE_OPCODE_MASK {
+            REBASE_OPCODE_DONE => break,
+            REBASE_OPCODE_SET_TYPE_IMM => rebase_type = immediate,
+            REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                segment_index = immediate;
+                offset = read_uleb128(&mut data)?;
+            }
+            REBASE_OPCODE_ADD_ADDR_ULEB => {
+                offset = offset.wrapping_add(read_uleb128(&mut data)?);
+            }
+            REBASE_OPCODE_ADD_ADDR_IMM_SCALED => {
+                offset = offset.wrapping_add(u64::from(immediate) * pointer_size);
+            }
+            REBASE_OPCODE_DO_REBASE_IMM_TIMES => {
+                for _ in 0..immediate {
+                    print_rebase(p, offset, rebase_type, segment_index);
+                    offset = offset.wrapping_add(pointer_size);
+                }
+            }
+            REBASE_OPCODE_DO_REBASE_ULEB_TIMES => {
+                let count = read_uleb128(&mut data)?;
+                for _ in 0..count {
+                    print_rebase(p, offset, rebase_type, segment_index);
+                    offset = offset.wrapping_add(pointer_size);
+                }
+            }
+            REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB => {
+                print_rebase(p, offset, rebase_type, segment_index);
+                let skip = read_uleb128(&mut data)?;
+                offset = offset.wrapping_add(skip).wrapping_add(pointer_size);
+            }
+            REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = read_uleb128(&mut data)?;
+                let skip = read_uleb128(&mut data)?;
+                for _ in 0..count {
+                    print_rebase(p, offset, rebase_type, segment_index);
+                    offset = offset.wrapping_add(skip).wrapping_add(pointer_size);
+                }
+            }
+            _ => return Err("Invalid rebase opcode"),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BindKind {
+    Bind,
+    WeakBind,
+    LazyBind,
+}
+
+/// The current values of the bind table columns.
+#[derive(Default)]
+struct BindState<'data> {
+    segment_index: u8,
+    offset: u64,
+    bind_type: u8,
+    ordinal: i64,
+    name: &'data [u8],
+    flags: u8,
+    addend: i64,
+}
+
+/// Decode the bind opcodes and print the bind table.
+fn print_bind_opcodes(
+    p: &mut Printer<'_>,
+    kind: BindKind,
+    data: &[u8],
+    state: &MachState,
+) -> Result<(), &'static str> {
+    let pointer_size = state.pointer_size();
+    let mut data = Bytes(data);
+    let mut bind = BindState {
+        bind_type: BIND_TYPE_POINTER,
+        ..Default::default()
+    };
+    let print_bind = |p: &mut Printer<'_>, bind: &BindState<'_>| {
+        let name = match kind {
+            BindKind::Bind => "Bind",
+            BindKind::WeakBind => "WeakBind",
+            BindKind::LazyBind => "LazyBind",
+        };
+        p.group(name, |p| {
+            print_dyld_location(p, state, bind.segment_index, bind.offset);
+            p.field_enum("Type", bind.bind_type, FLAGS_BIND_TYPE);
+            if kind != BindKind::WeakBind {
+                print_dylib_ordinal(p, state, bind.ordinal);
+            }
+            let symbol = p.demangle(bind.name);
+            p.field_inline_string("Symbol", &symbol);
+            p.field_hex("Flags", bind.flags);
+            p.flags(bind.flags, 0, FLAGS_BIND_SYMBOL);
+            p.field("Addend", bind.addend);
+        });
+    };
+    while let Ok(byte) = data.read::<u8>() {
+        let immediate = byte & BIND_IMMEDIATE_MASK;
+        match byte & BIND_OPCODE_MASK {
+            // Lazy binds are separated by `BIND_OPCODE_DONE`.
+            BIND_OPCODE_DONE if kind == BindKind::LazyBind => {}
+            BIND_OPCODE_DONE => break,
+            BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => bind.ordinal = immediate.into(),
+            BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => bind.ordinal = read_uleb128(&mut data)? as i64,
+            BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => {
+                // Sign extend the immediate.
+                bind.ordinal = if immediate == 0 {
+                    0
+                } else {
+                    (BIND_OPCODE_MASK | immediate) as i8 as i64
+                };
+            }
+            BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                bind.name = read_string(&mut data)?;
+                bind.flags = immediate;
+            }
+            BIND_OPCODE_SET_TYPE_IMM => bind.bind_type = immediate,
+            BIND_OPCODE_SET_ADDEND_SLEB => bind.addend = read_sleb128(&mut data)?,
+            BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                bind.segment_index = immediate;
+                bind.offset = read_uleb128(&mut data)?;
+            }
+            BIND_OPCODE_ADD_ADDR_ULEB => {
+                bind.offset = bind.offset.wrapping_add(read_uleb128(&mut data)?);
+            }
+            BIND_OPCODE_DO_BIND => {
+                print_bind(p, &bind);
+                bind.offset = bind.offset.wrapping_add(pointer_size);
+            }
+            BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB => {
+                print_bind(p, &bind);
+                let skip = read_uleb128(&mut data)?;
+                bind.offset = bind.offset.wrapping_add(skip).wrapping_add(pointer_size);
+            }
+            BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED => {
+                print_bind(p, &bind);
+                let skip = u64::from(immediate) * pointer_size;
+                bind.offset = bind.offset.wrapping_add(skip).wrapping_add(pointer_size);
+            }
+            BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = read_uleb128(&mut data)?;
+                let skip = read_uleb128(&mut data)?;
+                for _ in 0..count {
+                    print_bind(p, &bind);
+                    bind.offset = bind.offset.wrapping_add(skip).wrapping_add(pointer_size);
+                }
+            }
+            _ => return Err("Unsupported bind opcode"),
+        }
+    }
+    Ok(())
+}
+
+/// Walk the exports trie and print each exported symbol.
+fn print_exports_trie(
+    p: &mut Printer<'_>,
+    data: &[u8],
+    state: &MachState,
+) -> Result<(), &'static str> {
+    if data.is_empty() {
+        return Ok(());
+    }
+    let base_address = state.base_address();
+    let mut nodes = vec![(0, Vec::new())];
+    let mut count = 0;
+    while let Some((offset, name)) = nodes.pop() {
+        // Every node is at least 2 bytes, so more nodes than this means there is a cycle.
+        count += 1;
+        if count > data.len() {
+            return Err("Invalid export trie");
+        }
+        let mut node = Bytes(data);
+        node.skip(offset)
+            .map_err(|()| "Invalid export trie node offset")?;
+        let terminal_size = read_uleb128(&mut node)?;
+        let mut children = node;
+        children
+            .skip(terminal_size as usize)
+            .map_err(|()| "Invalid export trie terminal size")?;
+        if terminal_size != 0 {
+            print_export(p, &mut node, &name, base_address, state)?;
+        }
+
+        let child_count = *children
+            .read::<u8>()
+            .map_err(|()| "Invalid export trie child count")?;
+        let first_child = nodes.len();
+        for _ in 0..child_count {
+            let edge = read_string(&mut children)?;
+            let child_offset = read_uleb128(&mut children)?;
+            let mut child_name = name.clone();
+            child_name.extend_from_slice(edge);
+            nodes.push((child_offset as usize, child_name));
+        }
+        // Visit the children in order.
+        nodes[first_child..].reverse();
+    }
+    Ok(())
+}
+
+fn print_export(
+    p: &mut Printer<'_>,
+    data: &mut Bytes<'_>,
+    name: &[u8],
+    base_address: u64,
+    state: &MachState,
+) -> Result<(), &'static str> {
+    let flags = read_uleb128(data)?;
+    let mut result = Ok(());
+    p.group("Export", |p| {
+        let name = p.demangle(name);
+        p.field_inline_string("Name", &name);
+        p.field_hex("Flags", flags);
+        p.flags(flags, EXPORT_SYMBOL_FLAGS_KIND_MASK, FLAGS_EXPORT_KIND);
+        p.flags(flags, 0, FLAGS_EXPORT_SYMBOL);
+        result = (|| {
+            if flags & u64::from(EXPORT_SYMBOL_FLAGS_REEXPORT) != 0 {
+                let ordinal = read_uleb128(data)?;
+                print_dylib_ordinal(p, state, ordinal as i64);
+                let import_name = read_string(data)?;
+                if !import_name.is_empty() {
+                    let import_name = p.demangle(import_name);
+                    p.field_inline_string("ImportName", &import_name);
+                }
+                return Ok(());
+            }
+            let mut address = read_uleb128(data)?;
+            if flags & u64::from(EXPORT_SYMBOL_FLAGS_KIND_MASK)
+                != u64::from(EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE)
+            {
+                address = address.wrapping_add(base_address);
+            }
+            p.field_hex("Address", address);
+            if flags & u64::from(EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER) != 0 {
+                let resolver = read_uleb128(data)?;
+                p.field_hex("Resolver", resolver.wrapping_add(base_address));
+            }
+            Ok(())
+        })();
+    });
+    result
+}
+
+/// Print the imports and the fixup chains in `LC_DYLD_CHAINED_FIXUPS` data.
+fn print_chained_fixups<E: Endian>(
+    p: &mut Printer<'_>,
+    endian: E,
+    data: &[u8],
+    fixups_data: &[u8],
+    state: &MachState,
+) -> Result<(), &'static str> {
+    let fixups = Bytes(fixups_data);
+    let header = fixups
+        .read_at::<DyldChainedFixupsHeader<E>>(0)
+        .map_err(|()| "Invalid chained fixups header")?;
+    let starts_offset = header.starts_offset.get(endian) as usize;
+    let imports_offset = header.imports_offset.get(endian) as usize;
+    let symbols_offset = header.symbols_offset.get(endian) as usize;
+    let imports_count = header.imports_count.get(endian);
+    let imports_format = header.imports_format.get(endian);
+    let symbols_format = header.symbols_format.get(endian);
+    p.group("ChainedFixupsHeader", |p| {
+        p.field("FixupsVersion", header.fixups_version.get(endian));
+        p.field_hex("StartsOffset", starts_offset);
+        p.field_hex("ImportsOffset", imports_offset);
+        p.field_hex("SymbolsOffset", symbols_offset);
+        p.field("ImportsCount", imports_count);
+        p.field_enum("ImportsFormat", imports_format, FLAGS_DYLD_CHAINED_IMPORT);
+        p.field("SymbolsFormat", symbols_format);
+    });
+
+    // Symbol names can't be printed if they are compressed.
+    let symbols = if symbols_format == 0 {
+        fixups_data.get(symbols_offset..).map(Bytes)
+    } else {
+        None
+    };
+    let mut imports = Vec::new();
+    let mut import_data = Bytes(fixups_data);
+    import_data
+        .skip(imports_offset)
+        .map_err(|()| "Invalid chained fixups imports offset")?;
+    for index in 0..imports_count {
+        let (ordinal, weak_import, name_offset, addend) = match imports_format {
+            DYLD_CHAINED_IMPORT => {
+                let import = import_data
+                    .read::<DyldChainedImport<E>>()
+                    .map_err(|()| "Invalid chained import")?;
+                let value = import.value.get(endian);
+                // The ordinal is a sign extended 8 bit value.
+                let ordinal = (value & 0xff) as i8 as i64;
+                (ordinal, value & 0x100 != 0, value >> 9, None)
+            }
+            DYLD_CHAINED_IMPORT_ADDEND => {
+                let import = import_data
+                    .read::<DyldChainedImportAddend<E>>()
+                    .map_err(|()| "Invalid chained import")?;
+                let value = import.value.get(endian);
+                let ordinal = (value & 0xff) as i8 as i64;
+                let addend = i64::from(import.addend.get(endian));
+                (ordinal, value & 0x100 != 0, value >> 9, Some(addend))
+            }
+            DYLD_CHAINED_IMPORT_ADDEND64 => {
+                let import = import_data
+                    .read::<DyldChainedImportAddend64<E>>()
+                    .map_err(|()| "Invalid chained import")?;
+                let value = import.value.get(endian);
+                // The ordinal is a sign extended 16 bit value.
+                let ordinal = (value & 0xffff) as i16 as i64;
+                let addend = import.addend.get(endian) as i64;
+                let name_offset = (value >> 32) as u32;
+                (ordinal, value & 0x1_0000 != 0, name_offset, Some(addend))
+            }
+            _ => return Err("Unsupported chained imports format"),
+        };
+        let name = symbols.and_then(|symbols| symbols.read_string_at(name_offset as usize).ok());
+        imports.push(name);
+        p.group("ChainedImport", |p| {
+            p.field("Index", index);
+            print_dylib_ordinal(p, state, ordinal);
+            p.field("WeakImport", if weak_import { "yes" } else { "no" });
+            let name = name.map(|name| p.demangle(name));
+            p.field_string_option("Name", name_offset, name.as_deref());
+            if let Some(addend) = addend {
+                p.field("Addend", addend);
+            }
+        });
+    }
+
+    let mut starts = Bytes(fixups_data);
+    starts
+        .skip(starts_offset)
+        .map_err(|()| "Invalid chained fixups starts offset")?;
+    let starts_in_image = starts;
+    let seg_count = starts
+        .read::<DyldChainedStartsInImage<E>>()
+        .map_err(|()| "Invalid chained starts in image")?
+        .seg_count
+        .get(endian);
+    let seg_info_offsets = starts
+        .read_slice::<U32<E>>(seg_count as usize)
+        .map_err(|()| "Invalid chained starts in image")?;
+    for (segment_index, seg_info_offset) in seg_info_offsets.iter().enumerate() {
+        let seg_info_offset = seg_info_offset.get(endian) as usize;
+        if seg_info_offset == 0 {
+            continue;
+        }
+        let mut seg_info = starts_in_image;
+        seg_info
+            .skip(seg_info_offset)
+            .map_err(|()| "Invalid chained starts in segment offset")?;
+        let starts_in_segment = seg_info
+            .read::<DyldChainedStartsInSegment<E>>()
+            .map_err(|()| "Invalid chained starts in segment")?;
+        let mut result = Ok(());
+        p.group("ChainedStartsInSegment", |p| {
+            result = print_chained_starts_in_segment(
+                p,
+                endian,
+                data,
+                segment_index,
+                starts_in_segment,
+                seg_info,
+                &imports,
+                state,
+            );
+        });
+        result?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn print_chained_starts_in_segment<E: Endian>(
+    p: &mut Printer<'_>,
+    endian: E,
+    data: &[u8],
+    segment_index: usize,
+    starts: &DyldChainedStartsInSegment<E>,
+    page_starts: Bytes<'_>,
+    imports: &[Option<&[u8]>],
+    state: &MachState,
+) -> Result<(), &'static str> {
+    let segment = state.segments.get(segment_index);
+    let page_size = starts.page_size.get(endian);
+    let pointer_format = starts.pointer_format.get(endian);
+    let segment_offset = starts.segment_offset.get(endian);
+    let page_count = starts.page_count.get(endian);
+    p.field_string_option("Segment", segment_index, segment.map(|s| s.name));
+    p.field_hex("Size", starts.size.get(endian));
+    p.field_hex("PageSize", page_size);
+    p.field_enum("PointerFormat", pointer_format, FLAGS_DYLD_CHAINED_PTR);
+    p.field_hex("SegmentOffset", segment_offset);
+    p.field_hex("MaxValidPointer", starts.max_valid_pointer.get(endian));
+    p.field("PageCount", page_count);
+
+    let page_start = |index: usize| {
+        page_starts
+            .read_at::<U16<E>>(index * 2)
+            .map(|start| start.get(endian))
+            .map_err(|()| "Invalid chained page start")
+    };
+    for page_index in 0..usize::from(page_count) {
+        let start = page_start(page_index)?;
+        if start == DYLD_CHAINED_PTR_START_NONE {
+            continue;
+        }
+        // Pages with multiple starts refer to a list of starts in the same array.
+        let mut chain_starts = Vec::new();
+        if start & DYLD_CHAINED_PTR_START_MULTI != 0 {
+            let mut index = usize::from(start & !DYLD_CHAINED_PTR_START_MULTI);
+            loop {
+                let start = page_start(index)?;
+                chain_starts.push(start & !DYLD_CHAINED_PTR_START_LAST);
+                if start & DYLD_CHAINED_PTR_START_LAST != 0 {
+                    break;
+                }
+                index += 1;
+            }
+        } else {
+            chain_starts.push(start);
+        }
+
+        let mut result = Ok(());
+        p.group("Page", |p| {
+            p.field("Index", page_index);
+            for start in &chain_starts {
+                p.field_hex("Start", *start);
+            }
+            let Some(segment) = segment else {
+                return;
+            };
+            for start in chain_starts {
+                let offset = page_index as u64 * u64::from(page_size) + u64::from(start);
+                result = print_fixup_chain(
+                    p,
+                    endian,
+                    data,
+                    pointer_format,
+                    segment,
+                    offset,
+                    page_size,
+                    imports,
+                );
+                if result.is_err() {
+                    break;
+                }
+            }
+        });
+        result?;
+    }
+    Ok(())
+}
+
+/// A decoded chained fixup pointer.
+enum ChainedFixup {
+    Rebase {
+        target: u64,
+        high8: u8,
+    },
+    Bind {
+        ordinal: u32,
+        addend: i64,
+    },
+    AuthRebase {
+        target: u64,
+        diversity: u16,
+        addr_div: bool,
+        key: u8,
+    },
+    AuthBind {
+        ordinal: u32,
+        diversity: u16,
+        addr_div: bool,
+        key: u8,
+    },
+}
+
+/// Follow a chain of fixups within a page and print each fixup.
+#[allow(clippy::too_many_arguments)]
+fn print_fixup_chain<E: Endian>(
+    p: &mut Printer<'_>,
+    endian: E,
+    data: &[u8],
+    pointer_format: u16,
+    segment: &MachSegment<'_>,
+    mut offset: u64,
+    page_size: u16,
+    imports: &[Option<&[u8]>],
+) -> Result<(), &'static str> {
+    let (stride, is_32) = match pointer_format {
+        DYLD_CHAINED_PTR_ARM64E
+        | DYLD_CHAINED_PTR_ARM64E_USERLAND
+        | DYLD_CHAINED_PTR_ARM64E_USERLAND24 => (8, false),
+        DYLD_CHAINED_PTR_64
+        | DYLD_CHAINED_PTR_64_OFFSET
+        | DYLD_CHAINED_PTR_ARM64E_KERNEL
+        | DYLD_CHAINED_PTR_ARM64E_FIRMWARE
+        | DYLD_CHAINED_PTR_64_KERNEL_CACHE => (4, false),
+        DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE => (1, false),
+        DYLD_CHAINED_PTR_32 => (4, true),
+        _ => return Err("Unsupported chained pointer format"),
+    };
+    let page_end = (offset / u64::from(page_size) + 1) * u64::from(page_size);
+    loop {
+        let file_offset = segment.file_offset.wrapping_add(offset) as usize;
+        let value = if is_32 {
+            Bytes(data)
+                .read_at::<U32<E>>(file_offset)
+                .map(|value| u64::from(value.get(endian)))
+        } else {
+            Bytes(data)
+                .read_at::<U64<E>>(file_offset)
+                .map(|value| value.get(endian))
+        }
+        .map_err(|()| "Invalid chained fixup offset")?;
+        let (next, fixup) = decode_chained_fixup(pointer_format, value);
+        p.group("ChainedFixup", |p| {
+            p.field_hex("SegmentOffset", offset);
+            p.field_hex("Address", segment.address.wrapping_add(offset));
+            p.field_hex("Value", value);
+            match fixup {
+                ChainedFixup::Rebase { target, high8 } => {
+                    p.field("Kind", "Rebase");
+                    p.field_hex("Target", target);
+                    p.field_hex("High8", high8);
+                }
+                ChainedFixup::Bind { ordinal, addend } => {
+                    p.field("Kind", "Bind");
+                    let name = imports.get(ordinal as usize).copied().flatten();
+                    let name = name.map(|name| p.demangle(name));
+                    p.field_string_option("Import", ordinal, name.as_deref());
+                    p.field("Addend", addend);
+                }
+                ChainedFixup::AuthRebase {
+                    target,
+                    diversity,
+                    addr_div,
+                    key,
+                } => {
+                    p.field("Kind", "AuthRebase");
+                    p.field_hex("Target", target);
+                    print_ptrauth(p, diversity, addr_div, key);
+                }
+                ChainedFixup::AuthBind {
+                    ordinal,
+                    diversity,
+                    addr_div,
+                    key,
+                } => {
+                    p.field("Kind", "AuthBind");
+                    let name = imports.get(ordinal as usize).copied().flatten();
+                    let name = name.map(|name| p.demangle(name));
+                    p.field_string_option("Import", ordinal, name.as_deref());
+                    print_ptrauth(p, diversity, addr_div, key);
+                }
+            }
+        });
+        if next == 0 {
+            return Ok(());
+        }
+        offset += next * stride;
+        if offset >= page_end {
+            return Err("Chained fixup is outside of page");
+        }
+    }
+}
+
+fn print_ptrauth(p: &mut Printer<'_>, diversity: u16, addr_div: bool, key: u8) {
+    p.field_hex("Diversity", diversity);
+    p.field("AddrDiv", if addr_div { "yes" } else { "no" });
+    let key = match key {
+        0 => PtrauthKey::IA,
+        1 => PtrauthKey::IB,
+        2 => PtrauthKey::DA,
+        _ => PtrauthKey::DB,
+    };
+    p.field("Key", format_args!("{:?}", key));
+}
+
+/// Decode a chained fixup pointer, and return the offset to the next fixup
+/// in units of the stride.
+fn decode_chained_fixup(pointer_format: u16, value: u64) -> (u64, ChainedFixup) {
+    let bits = |shift: u32, width: u32| (value >> shift) & ((1 << width) - 1);
+    match pointer_format {
+        DYLD_CHAINED_PTR_32 => {
+            let next = bits(26, 5);
+            if bits(31, 1) != 0 {
+                let ordinal = bits(0, 20) as u32;
+                let addend = bits(20, 6) as i64;
+                (next, ChainedFixup::Bind { ordinal, addend })
+            } else {
+                let target = bits(0, 26);
+                (next, ChainedFixup::Rebase { target, high8: 0 })
+            }
+        }
+        DYLD_CHAINED_PTR_64 | DYLD_CHAINED_PTR_64_OFFSET => {
+            let next = bits(51, 12);
+            if bits(63, 1) != 0 {
+                let ordinal = bits(0, 24) as u32;
+                let addend = bits(24, 8) as i64;
+                (next, ChainedFixup::Bind { ordinal, addend })
+            } else {
+                let target = bits(0, 36);
+                let high8 = bits(36, 8) as u8;
+                (next, ChainedFixup::Rebase { target, high8 })
+            }
+        }
+        DYLD_CHAINED_PTR_64_KERNEL_CACHE | DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE => {
+            let next = bits(51, 12);
+            let target = bits(0, 30);
+            if bits(63, 1) != 0 {
+                let fixup = ChainedFixup::AuthRebase {
+                    target,
+                    diversity: bits(32, 16) as u16,
+                    addr_div: bits(48, 1) != 0,
+                    key: bits(49, 2) as u8,
+                };
+                (next, fixup)
+            } else {
+                (next, ChainedFixup::Rebase { target, high8: 0 })
+            }
+        }
+        // The arm64e formats.
+        _ => {
+            let next = bits(51, 11);
+            let ordinal_width = if pointer_format == DYLD_CHAINED_PTR_ARM64E_USERLAND24 {
+                24
+            } else {
+                16
+            };
+            let ordinal = bits(0, ordinal_width) as u32;
+            let auth = bits(63, 1) != 0;
+            let bind = bits(62, 1) != 0;
+            let fixup = match (auth, bind) {
+                (false, false) => ChainedFixup::Rebase {
+                    target: bits(0, 43),
+                    high8: bits(43, 8) as u8,
+                },
+                (false, true) => {
+                    // The addend is a signed 19 bit value.
+                    let addend = ((bits(32, 19) << 45) as i64) >> 45;
+                    ChainedFixup::Bind { ordinal, addend }
+                }
+                (true, false) => ChainedFixup::AuthRebase {
+                    target: bits(0, 32),
+                    diversity: bits(32, 16) as u16,
+                    addr_div: bits(48, 1) != 0,
+                    key: bits(49, 2) as u8,
+                },
+                (true, true) => ChainedFixup::AuthBind {
+                    ordinal,
+                    diversity: bits(32, 16) as u16,
+                    addr_div: bits(48, 1) != 0,
+                    key: bits(49, 2) as u8,
+                },
+            };
+            (next, fixup)
+        }
+    }
+}
+
 fn print_cputype(p: &mut Printer<'_>, cputype: u32, cpusubtype: u32) {
     let proc = match cputype {
         CPU_TYPE_ANY => FLAGS_CPU_SUBTYPE_ANY,
@@ -1219,3 +2070,54 @@ const FLAGS_X86_64_RELOC: &[Flag<u8>] = &flags!(
     X86_64_RELOC_SIGNED_4,
     X86_64_RELOC_TLV,
 );
+const FLAGS_REBASE_TYPE: &[Flag<u8>] = &flags!(
+    REBASE_TYPE_POINTER,
+    REBASE_TYPE_TEXT_ABSOLUTE32,
+    REBASE_TYPE_TEXT_PCREL32,
+);
+const FLAGS_BIND_TYPE: &[Flag<u8>] = &flags!(
+    BIND_TYPE_POINTER,
+    BIND_TYPE_TEXT_ABSOLUTE32,
+    BIND_TYPE_TEXT_PCREL32,
+);
+const FLAGS_BIND_SPECIAL_DYLIB: &[Flag<i8>] = &flags!(
+    BIND_SPECIAL_DYLIB_SELF,
+    BIND_SPECIAL_DYLIB_MAIN_EXECUTABLE,
+    BIND_SPECIAL_DYLIB_FLAT_LOOKUP,
+    BIND_SPECIAL_DYLIB_WEAK_LOOKUP,
+);
+const FLAGS_BIND_SYMBOL: &[Flag<u8>] = &flags!(
+    BIND_SYMBOL_FLAGS_WEAK_IMPORT,
+    BIND_SYMBOL_FLAGS_NON_WEAK_DEFINITION,
+);
+const FLAGS_EXPORT_KIND: &[Flag<u32>] = &flags!(
+    EXPORT_SYMBOL_FLAGS_KIND_REGULAR,
+    EXPORT_SYMBOL_FLAGS_KIND_THREAD_LOCAL,
+    EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE,
+);
+const FLAGS_EXPORT_SYMBOL: &[Flag<u32>] = &flags!(
+    EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION,
+    EXPORT_SYMBOL_FLAGS_REEXPORT,
+    EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER,
+);
+const FLAGS_DYLD_CHAINED_IMPORT: &[Flag<u32>] = &flags!(
+    DYLD_CHAINED_IMPORT,
+    DYLD_CHAINED_IMPORT_ADDEND,
+    DYLD_CHAINED_IMPORT_ADDEND64,
+);
+const FLAGS_DYLD_CHAINED_PTR: &[Flag<u16>] = &flags!(
+    DYLD_CHAINED_PTR_ARM64E,
+    DYLD_CHAINED_PTR_64,
+    DYLD_CHAINED_PTR_32,
+    DYLD_CHAINED_PTR_32_CACHE,
+    DYLD_CHAINED_PTR_32_FIRMWARE,
+    DYLD_CHAINED_PTR_64_OFFSET,
+    DYLD_CHAINED_PTR_ARM64E_KERNEL,
+    DYLD_CHAINED_PTR_64_KERNEL_CACHE,
+    DYLD_CHAINED_PTR_ARM64E_USERLAND,
+    DYLD_CHAINED_PTR_ARM64E_FIRMWARE,
+    DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE,
+    DYLD_CHAINED_PTR_ARM64E_USERLAND24,
+    DYLD_CHAINED_PTR_ARM64E_SHARED_CACHE,
+    DYLD_CHAINED_PTR_ARM64E_SEGMENTED,
+);
@@ -1,11 +1,15 @@
+use std::borrow::Cow;
 use std::io::Write;
+use std::ops::Range;
 use std::{fmt, str};
 
 use object::read::archive::ArchiveFile;
 use object::read::macho::{FatArch, FatHeader};
 use object::Endianness;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::demangle;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PrintOptions {
     // Selectors
     pub file: bool,
@@ -20,9 +24,11 @@ pub struct PrintOptions {
     pub elf_notes: bool,
     pub elf_versions: bool,
     pub elf_attributes: bool,
+    pub elf_hash_check: bool,
 
     // Mach-O specific selectors
     pub macho_load_commands: bool,
+    pub macho_dyld_info: bool,
 
     // PE specific selectors
     pub pe_rich: bool,
@@ -31,8 +37,28 @@ pub struct PrintOptions {
     pub pe_exports: bool,
     pub pe_resources: bool,
 
+    // Filters
+    /// Only print the sections with these names or indices.
+    ///
+    /// All sections are printed if this is empty. For ELF files, this also
+    /// applies to the sections containing symbols and relocations.
+    pub section_names: Vec<String>,
+    /// Only print the symbols with these names.
+    ///
+    /// All symbols are printed if this is empty.
+    pub symbol_names: Vec<String>,
+    /// Only print the sections with an index in this range.
+    pub section_range: Option<Range<usize>>,
+    /// Only print the symbols with an index in this range.
+    pub symbol_range: Option<Range<usize>>,
+
     // Modifiers
     pub string_indices: bool,
+    pub json: bool,
+    /// Print ELF files in the style of `llvm-readelf`.
+    pub elf_gnu_style: bool,
+    /// Demangle symbol names.
+    pub demangle: bool,
 }
 
 impl PrintOptions {
@@ -49,13 +75,22 @@ impl PrintOptions {
             elf_notes: true,
             elf_versions: true,
             elf_attributes: true,
+            elf_hash_check: true,
             macho_load_commands: true,
+            macho_dyld_info: true,
             pe_rich: true,
             pe_base_relocs: true,
             pe_imports: true,
             pe_exports: true,
             pe_resources: true,
+            section_names: Vec::new(),
+            symbol_names: Vec::new(),
+            section_range: None,
+            symbol_range: None,
             string_indices: true,
+            json: false,
+            elf_gnu_style: false,
+            demangle: false,
         }
     }
 
@@ -72,14 +107,50 @@ impl PrintOptions {
             elf_notes: false,
             elf_versions: false,
             elf_attributes: false,
+            elf_hash_check: false,
             macho_load_commands: false,
+            macho_dyld_info: false,
             pe_rich: false,
             pe_base_relocs: false,
             pe_imports: false,
             pe_exports: false,
             pe_resources: false,
+            section_names: Vec::new(),
+            symbol_names: Vec::new(),
+            section_range: None,
+            symbol_range: None,
             string_indices: true,
+            json: false,
+            elf_gnu_style: false,
+            demangle: false,
+        }
+    }
+
+    /// Return true if the section with the given index and name passes the filters.
+    fn filter_section(&self, index: usize, name: Option<&[u8]>) -> bool {
+        if let Some(range) = &self.section_range {
+            if !range.contains(&index) {
+                return false;
+            }
         }
+        self.section_names.is_empty()
+            || self.section_names.iter().any(|filter| {
+                Some(filter.as_bytes()) == name || filter.parse::<usize>() == Ok(index)
+            })
+    }
+
+    /// Return true if the symbol with the given index and name passes the filters.
+    fn filter_symbol(&self, index: usize, name: Option<&[u8]>) -> bool {
+        if let Some(range) = &self.symbol_range {
+            if !range.contains(&index) {
+                return false;
+            }
+        }
+        self.symbol_names.is_empty()
+            || self
+                .symbol_names
+                .iter()
+                .any(|filter| Some(filter.as_bytes()) == name)
     }
 }
 
@@ -92,6 +163,7 @@ pub fn print(
 ) {
     let mut printer = Printer::new(w, e, options);
     print_object(&mut printer, file, extra_files);
+    printer.finish();
 }
 
 struct Printer<'a> {
@@ -99,6 +171,7 @@ struct Printer<'a> {
     e: &'a mut dyn Write,
     indent: usize,
     options: &'a PrintOptions,
+    json: Option<json::Builder>,
 }
 
 impl<'a> Printer<'a> {
@@ -108,15 +181,24 @@ impl<'a> Printer<'a> {
             e,
             indent: 0,
             options,
+            json: if options.json {
+                Some(json::Builder::default())
+            } else {
+                None
+            },
         }
     }
 
-    fn w(&mut self) -> &mut dyn Write {
-        self.w
+    fn finish(&mut self) {
+        if let Some(json) = &self.json {
+            json.write(self.w).unwrap();
+        }
     }
 
     fn blank(&mut self) {
-        writeln!(self.w).unwrap();
+        if self.json.is_none() {
+            writeln!(self.w).unwrap();
+        }
     }
 
     fn print_indent(&mut self) {
@@ -125,14 +207,6 @@ impl<'a> Printer<'a> {
         }
     }
 
-    fn print_string(&mut self, s: &[u8]) {
-        if let Ok(s) = str::from_utf8(s) {
-            write!(self.w, "\"{}\"", s).unwrap();
-        } else {
-            write!(self.w, "{:X?}", s).unwrap();
-        }
-    }
-
     fn indent<F: FnOnce(&mut Self)>(&mut self, f: F) {
         self.indent += 1;
         f(self);
@@ -140,6 +214,12 @@ impl<'a> Printer<'a> {
     }
 
     fn group<F: FnOnce(&mut Self)>(&mut self, name: &str, f: F) {
+        if let Some(json) = &mut self.json {
+            json.begin_group(name);
+            f(self);
+            self.json.as_mut().unwrap().end_group();
+            return;
+        }
         self.print_indent();
         writeln!(self.w, "{} {{", name).unwrap();
         self.indent(f);
@@ -147,36 +227,86 @@ impl<'a> Printer<'a> {
         writeln!(self.w, "}}").unwrap();
     }
 
-    fn field_name(&mut self, name: &str) {
+    /// Print a nested file.
+    ///
+    /// This is a group for JSON output, and has no effect on text output.
+    fn nested<F: FnOnce(&mut Self)>(&mut self, name: &str, f: F) {
+        if let Some(json) = &mut self.json {
+            json.begin_group(name);
+            f(self);
+            self.json.as_mut().unwrap().end_group();
+        } else {
+            f(self);
+        }
+    }
+
+    /// Print the file format.
+    fn format(&mut self, format: fmt::Arguments<'_>) {
+        self.field_raw("Format", format, || json::Value::display(format));
+    }
+
+    /// Print a field using the given text, or the given JSON value.
+    ///
+    /// An empty name is only used for list entries, so it has the JSON key "Entries".
+    fn field_raw<F: FnOnce() -> json::Value>(
+        &mut self,
+        name: &str,
+        text: fmt::Arguments<'_>,
+        value: F,
+    ) {
+        if let Some(json) = &mut self.json {
+            let name = if name.is_empty() { "Entries" } else { name };
+            json.current().field(name, value());
+            return;
+        }
         self.print_indent();
         if !name.is_empty() {
             write!(self.w, "{}: ", name).unwrap();
         }
+        writeln!(self.w, "{}", text).unwrap();
     }
 
     fn field<T: fmt::Display>(&mut self, name: &str, value: T) {
-        self.field_name(name);
-        writeln!(self.w, "{}", value).unwrap();
+        self.field_raw(name, format_args!("{}", value), || {
+            json::Value::display(&value)
+        });
     }
 
     fn field_hex<T: fmt::UpperHex>(&mut self, name: &str, value: T) {
-        self.field_name(name);
-        writeln!(self.w, "0x{:X}", value).unwrap();
+        self.field_raw(name, format_args!("0x{:X}", value), || {
+            json::Value::hex(&value)
+        });
     }
 
     fn field_bytes(&mut self, name: &str, value: &[u8]) {
-        self.field_name(name);
-        writeln!(self.w, "{:X?}", value).unwrap();
+        self.field_raw(name, format_args!("{:X?}", value), || {
+            json::Value::bytes(value)
+        });
+    }
+
+    /// Return the symbol name, demangled if enabled.
+    fn demangle<'data>(&self, name: &'data [u8]) -> Cow<'data, [u8]> {
+        if self.options.demangle {
+            if let Some(demangled) = demangle::demangle_bytes(name) {
+                return Cow::Owned(demangled.into_bytes());
+            }
+        }
+        Cow::Borrowed(name)
     }
 
     fn field_string_option<T: fmt::UpperHex>(&mut self, name: &str, value: T, s: Option<&[u8]>) {
         if let Some(s) = s {
-            self.field_name(name);
-            self.print_string(s);
+            let string = PrintString(s);
             if self.options.string_indices {
-                write!(self.w, " (0x{:X})", value).unwrap();
+                self.field_raw(name, format_args!("{} (0x{:X})", string, value), || {
+                    json::Value::Object(json::Object::new(vec![
+                        ("Value", json::Value::hex(&value)),
+                        ("String", json::Value::string(s)),
+                    ]))
+                });
+            } else {
+                self.field_raw(name, format_args!("{}", string), || json::Value::string(s));
             }
-            writeln!(self.w).unwrap();
         } else {
             self.field_hex(name, value);
         }
@@ -193,20 +323,13 @@ impl<'a> Printer<'a> {
     }
 
     fn field_inline_string(&mut self, name: &str, s: &[u8]) {
-        self.field_name(name);
-        self.print_string(s);
-        writeln!(self.w).unwrap();
+        self.field_raw(name, format_args!("{}", PrintString(s)), || {
+            json::Value::string(s)
+        });
     }
 
     fn field_enum<T: Eq + fmt::UpperHex>(&mut self, name: &str, value: T, flags: &[Flag<T>]) {
-        for flag in flags {
-            if value == flag.value {
-                self.field_name(name);
-                writeln!(self.w, "{} (0x{:X})", flag.name, value).unwrap();
-                return;
-            }
-        }
-        self.field_hex(name, value);
+        self.field_enums(name, value, &[flags]);
     }
 
     fn field_enum_display<T: Eq + fmt::Display>(
@@ -217,8 +340,12 @@ impl<'a> Printer<'a> {
     ) {
         for flag in flags {
             if value == flag.value {
-                self.field_name(name);
-                writeln!(self.w, "{} ({})", flag.name, value).unwrap();
+                self.field_raw(name, format_args!("{} ({})", flag.name, value), || {
+                    json::Value::Object(json::Object::new(vec![
+                        ("Value", json::Value::display(&value)),
+                        ("Name", json::Value::String(flag.name.to_string())),
+                    ]))
+                });
                 return;
             }
         }
@@ -229,8 +356,12 @@ impl<'a> Printer<'a> {
         for flags in enums {
             for flag in *flags {
                 if value == flag.value {
-                    self.field_name(name);
-                    writeln!(self.w, "{} (0x{:X})", flag.name, value).unwrap();
+                    self.field_raw(name, format_args!("{} (0x{:X})", flag.name, value), || {
+                        json::Value::Object(json::Object::new(vec![
+                            ("Value", json::Value::hex(&value)),
+                            ("Name", json::Value::String(flag.name.to_string())),
+                        ]))
+                    });
                     return;
                 }
             }
@@ -238,33 +369,98 @@ impl<'a> Printer<'a> {
         self.field_hex(name, value);
     }
 
+    /// Like `field_enums`, but unknown values within one of `ranges` are
+    /// printed relative to the start of the range.
+    fn field_enums_ranges<T>(
+        &mut self,
+        name: &str,
+        value: T,
+        enums: &[&[Flag<T>]],
+        ranges: &[FlagRange<T>],
+    ) where
+        T: Copy + Ord + fmt::UpperHex + std::ops::Sub<Output = T>,
+    {
+        let known = enums
+            .iter()
+            .flat_map(|flags| flags.iter())
+            .any(|flag| flag.value == value);
+        if !known {
+            if let Some(range) = ranges
+                .iter()
+                .find(|range| range.start <= value && value <= range.end)
+            {
+                let offset = value - range.start;
+                self.field_raw(
+                    name,
+                    format_args!("{}+0x{:X} (0x{:X})", range.name, offset, value),
+                    || {
+                        json::Value::Object(json::Object::new(vec![
+                            ("Value", json::Value::hex(value)),
+                            (
+                                "Name",
+                                json::Value::String(format!("{}+0x{:X}", range.name, offset)),
+                            ),
+                        ]))
+                    },
+                );
+                return;
+            }
+        }
+        self.field_enums(name, value, enums);
+    }
+
     fn flags<T: Into<u64>, U: Copy + Into<u64>>(&mut self, value: T, mask: U, flags: &[Flag<U>]) {
         let value = value.into();
         let mask = mask.into();
-        self.indent(|p| {
-            if mask != 0 {
-                for flag in flags {
-                    if value & mask == flag.value.into() {
-                        p.print_indent();
-                        writeln!(p.w, "{} (0x{:X})", flag.name, flag.value.into()).unwrap();
-                        return;
-                    }
+        let mut matches = Vec::new();
+        if mask != 0 {
+            match flags.iter().find(|flag| value & mask == flag.value.into()) {
+                Some(flag) => matches.push((flag.name, flag.value.into())),
+                None => matches.push(("<unknown>", value & mask)),
+            }
+        } else {
+            for flag in flags {
+                if value & flag.value.into() == flag.value.into() {
+                    matches.push((flag.name, flag.value.into()));
                 }
+            }
+            // TODO: display unknown flags (need to display all flags at once for this)
+        }
+        if let Some(json) = &mut self.json {
+            let flags = matches
+                .into_iter()
+                .map(|(name, value)| {
+                    json::Value::Object(json::Object::new(vec![
+                        ("Value", json::Value::hex(value)),
+                        ("Name", json::Value::String(name.to_string())),
+                    ]))
+                })
+                .collect();
+            json.current().flags(flags);
+            return;
+        }
+        self.indent(|p| {
+            for (name, value) in matches {
                 p.print_indent();
-                writeln!(p.w, "<unknown> (0x{:X})", value & mask).unwrap();
-            } else {
-                for flag in flags {
-                    if value & flag.value.into() == flag.value.into() {
-                        p.print_indent();
-                        writeln!(p.w, "{} (0x{:X})", flag.name, flag.value.into()).unwrap();
-                    }
-                }
-                // TODO: display unknown flags (need to display all flags at once for this)
+                writeln!(p.w, "{} (0x{:X})", name, value).unwrap();
             }
         });
     }
 }
 
+/// Display a string in quotes if it is valid UTF-8, or as bytes otherwise.
+struct PrintString<'a>(&'a [u8]);
+
+impl fmt::Display for PrintString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Ok(s) = str::from_utf8(self.0) {
+            write!(f, "\"{}\"", s)
+        } else {
+            write!(f, "{:X?}", self.0)
+        }
+    }
+}
+
 struct Flag<T> {
     value: T,
     name: &'static str,
@@ -274,6 +470,19 @@ macro_rules! flags {
     ($($name:ident),+ $(,)?) => ( [ $(Flag { value: $name, name: stringify!($name), }),+ ] )
 }
 
+/// A range of values that have a common meaning, such as OS-specific values.
+struct FlagRange<T> {
+    start: T,
+    end: T,
+    name: &'static str,
+}
+
+macro_rules! flag_ranges {
+    ($(($start:ident, $end:ident)),+ $(,)?) => (
+        [ $(FlagRange { start: $start, end: $end, name: stringify!($start) }),+ ]
+    )
+}
+
 fn print_object(p: &mut Printer<'_>, data: &[u8], extra_files: &[&[u8]]) {
     let kind = match object::FileKind::parse(data) {
         Ok(file) => file,
@@ -321,20 +530,19 @@ fn print_object_at(p: &mut Printer<'_>, data: &[u8], offset: u64) {
 
 fn print_archive(p: &mut Printer<'_>, data: &[u8]) {
     if let Some(archive) = ArchiveFile::parse(data).print_err(p) {
-        write!(p.w(), "Format: Archive ({:?})", archive.kind()).unwrap();
-        if archive.is_thin() {
-            write!(p.w(), " (thin)").unwrap();
-        }
-        p.blank();
+        let thin = if archive.is_thin() { " (thin)" } else { "" };
+        p.format(format_args!("Archive ({:?}){}", archive.kind(), thin));
         for member in archive.members() {
             if let Some(member) = member.print_err(p) {
                 p.blank();
-                p.field_inline_string("Member", member.name());
-                if member.is_thin() {
-                    p.field("Size", member.size());
-                } else if let Some(data) = member.data(data).print_err(p) {
-                    print_object(p, data, &[]);
-                }
+                p.nested("Members", |p| {
+                    p.field_inline_string("Member", member.name());
+                    if member.is_thin() {
+                        p.field("Size", member.size());
+                    } else if let Some(data) = member.data(data).print_err(p) {
+                        print_object(p, data, &[]);
+                    }
+                });
             }
         }
         if let Some(symbols) = archive.symbols().print_err(p).flatten() {
@@ -342,7 +550,8 @@ fn print_archive(p: &mut Printer<'_>, data: &[u8]) {
             for symbol in symbols {
                 if let Some(symbol) = symbol.print_err(p) {
                     p.group("Symbol", |p| {
-                        p.field_inline_string("Name", symbol.name());
+                        let name = p.demangle(symbol.name());
+                        p.field_inline_string("Name", &name);
                         let offset = symbol.offset();
                         if let Some(member) = archive.member(offset).print_err(p) {
                             p.field_inline_string("Member", member.name());
@@ -370,7 +579,11 @@ impl<T, E: fmt::Display> PrintErr<T> for Result<T, E> {
     }
 }
 
+mod diff;
+pub use diff::print_diff;
+
 mod elf;
+mod json;
 mod macho;
 mod pe;
 mod xcoff;
@@ -9,7 +9,7 @@ use object::{Bytes, U32Bytes, U64Bytes};
 pub(super) fn print_coff(p: &mut Printer<'_>, data: &[u8]) {
     let mut offset = 0;
     if let Some(header) = ImageFileHeader::parse(data, &mut offset).print_err(p) {
-        writeln!(p.w(), "Format: COFF").unwrap();
+        p.format(format_args!("COFF"));
         print_file(p, header);
         let sections = header.sections(data, offset).print_err(p);
         let symbols = header.symbols(data).print_err(p);
@@ -25,7 +25,7 @@ pub(super) fn print_coff(p: &mut Printer<'_>, data: &[u8]) {
 pub(super) fn print_coff_big(p: &mut Printer<'_>, data: &[u8]) {
     let mut offset = 0;
     if let Some(header) = AnonObjectHeaderBigobj::parse(data, &mut offset).print_err(p) {
-        writeln!(p.w(), "Format: COFF bigobj").unwrap();
+        p.format(format_args!("COFF bigobj"));
         print_bigobj(p, header);
         let sections = header.sections(data, offset).print_err(p);
         let symbols = header.symbols(data).print_err(p);
@@ -41,7 +41,7 @@ pub(super) fn print_coff_big(p: &mut Printer<'_>, data: &[u8]) {
 pub(super) fn print_coff_import(p: &mut Printer<'_>, data: &[u8]) {
     let mut offset = 0;
     if let Some(header) = ImportObjectHeader::parse(data, &mut offset).print_err(p) {
-        writeln!(p.w(), "Format: COFF import").unwrap();
+        p.format(format_args!("COFF import"));
         if !p.options.file {
             return;
         }
@@ -56,7 +56,8 @@ pub(super) fn print_coff_import(p: &mut Printer<'_>, data: &[u8]) {
             p.field_enum("ImportType", header.import_type(), FLAGS_IMAGE_OBJECT_TYPE);
             p.field_enum("NameType", header.name_type(), FLAGS_IMAGE_OBJECT_NAME);
             if let Some(data) = header.parse_data(data, &mut offset).print_err(p) {
-                p.field_inline_string("Symbol", data.symbol());
+                let symbol = p.demangle(data.symbol());
+                p.field_inline_string("Symbol", &symbol);
                 p.field_inline_string("Dll", data.dll());
                 if let Some(export) = data.export() {
                     p.field_inline_string("Export", export);
@@ -67,12 +68,12 @@ pub(super) fn print_coff_import(p: &mut Printer<'_>, data: &[u8]) {
 }
 
 pub(super) fn print_pe32(p: &mut Printer<'_>, data: &[u8]) {
-    writeln!(p.w(), "Format: PE 32-bit").unwrap();
+    p.format(format_args!("PE 32-bit"));
     print_pe::<ImageNtHeaders32>(p, data);
 }
 
 pub(super) fn print_pe64(p: &mut Printer<'_>, data: &[u8]) {
-    writeln!(p.w(), "Format: PE 64-bit").unwrap();
+    p.format(format_args!("PE 64-bit"));
     print_pe::<ImageNtHeaders64>(p, data);
 }
 
@@ -326,11 +327,9 @@ fn print_export_dir(
                 p.group("Export", |p| {
                     p.field("Ordinal", ordinal_base.wrapping_add(ordinal as u32));
                     if let Some(name_pointer) = names[ordinal] {
-                        p.field_string(
-                            "Name",
-                            name_pointer,
-                            export_table.name_from_pointer(name_pointer),
-                        );
+                        let name = export_table.name_from_pointer(name_pointer);
+                        let name = name.map(|name| p.demangle(name));
+                        p.field_string("Name", name_pointer, name.as_deref());
                     }
                     p.field_hex("Address", address.get(LE));
                     if let Some(target) = export_table
@@ -420,7 +419,8 @@ fn print_import_dir<Pe: ImageNtHeaders>(
                                 import_table.hint_name(thunk.address()).print_err(p)
                             {
                                 p.field("Hint", hint);
-                                p.field_inline_string("Name", name);
+                                let name = p.demangle(name);
+                                p.field_inline_string("Name", &name);
                             }
                         });
                     }
@@ -479,7 +479,8 @@ fn print_delay_load_dir<Pe: ImageNtHeaders>(
                                 import_table.hint_name(thunk.address()).print_err(p)
                             {
                                 p.field("Hint", hint);
-                                p.field_inline_string("Name", name);
+                                let name = p.demangle(name);
+                                p.field_inline_string("Name", &name);
                             }
                         });
                     }
@@ -532,8 +533,16 @@ fn print_resource_table(
                     ResourceNameOrId::Name(name) => {
                         let offset = entry.name_or_id.get(LE);
                         if let Some(name) = name.to_string_lossy(directory).print_err(p) {
-                            p.field_name("NameOrId");
-                            writeln!(p.w, "\"{}\" (0x{:X})", name, offset).unwrap();
+                            p.field_raw(
+                                "NameOrId",
+                                format_args!("\"{}\" (0x{:X})", name, offset),
+                                || {
+                                    json::Value::Object(json::Object::new(vec![
+                                        ("Value", json::Value::hex(offset)),
+                                        ("String", json::Value::String(name.clone())),
+                                    ]))
+                                },
+                            );
                         } else {
                             p.field_hex("NameOrId", offset);
                         }
@@ -586,6 +595,13 @@ fn print_sections<'data, Coff: CoffHeader>(
         {
             continue;
         }
+        let name = match symbols {
+            Some(symbols) => section.name(symbols.strings()).ok(),
+            None => Some(section.raw_name()),
+        };
+        if !p.options.filter_section(index + 1, name) {
+            continue;
+        }
         p.group("ImageSectionHeader", |p| {
             p.field("Index", index + 1);
             if let Some(name) =
@@ -647,7 +663,8 @@ fn print_relocations<'data, Coff: CoffHeader>(
                         .and_then(|symbol| symbol.name(symbols.strings()))
                         .print_err(p)
                 });
-                p.field_string_option("Symbol", index.0, name);
+                let name = name.map(|name| p.demangle(name));
+                p.field_string_option("Symbol", index.0, name.as_deref());
                 let proc = match machine {
                     IMAGE_FILE_MACHINE_I386 => FLAGS_IMAGE_REL_I386,
                     IMAGE_FILE_MACHINE_MIPS16
@@ -700,12 +717,19 @@ fn print_symbols<'data, Coff: CoffHeader>(
         return;
     }
     for (index, symbol) in symbols.iter() {
+        if !p
+            .options
+            .filter_symbol(index.0, symbol.name(symbols.strings()).ok())
+        {
+            continue;
+        }
         p.group("ImageSymbol", |p| {
             p.field("Index", index);
             if let Some(name) = symbol.name(symbols.strings()).print_err(p) {
-                p.field_inline_string("Name", name);
+                let name = p.demangle(name);
+                p.field_inline_string("Name", &name);
             } else {
-                p.field("Name", format!("{:X?}", symbol.raw_name()));
+                p.field_bytes("Name", symbol.raw_name());
             }
             p.field_hex("Value", symbol.value());
             if let Some(section_index) = symbol.section() {
@@ -747,7 +771,7 @@ fn print_symbols<'data, Coff: CoffHeader>(
                             "PointerToNextFunction",
                             aux.pointer_to_next_function.get(LE),
                         );
-                        p.field("Unused", format!("{:X?}", aux.unused));
+                        p.field_bytes("Unused", &aux.unused);
                     });
                 }
             } else if symbol.has_aux_section() {
@@ -5,7 +5,7 @@ use object::xcoff::*;
 pub(super) fn print_xcoff32(p: &mut Printer<'_>, data: &[u8]) {
     let mut offset = 0;
     if let Some(header) = FileHeader32::parse(data, &mut offset).print_err(p) {
-        writeln!(p.w(), "Format: XCOFF 32-bit").unwrap();
+        p.format(format_args!("XCOFF 32-bit"));
         print_xcoff(p, header, data, offset);
     }
 }
@@ -13,7 +13,7 @@ pub(super) fn print_xcoff32(p: &mut Printer<'_>, data: &[u8]) {
 pub(super) fn print_xcoff64(p: &mut Printer<'_>, data: &[u8]) {
     let mut offset = 0;
     if let Some(header) = FileHeader64::parse(data, &mut offset).print_err(p) {
-        writeln!(p.w(), "Format: XCOFF 64-bit").unwrap();
+        p.format(format_args!("XCOFF 64-bit"));
         print_xcoff(p, header, data, offset);
     }
 }
@@ -105,6 +105,9 @@ fn print_sections<'data, Xcoff: FileHeader>(
         return;
     }
     for (index, section) in sections.iter().enumerate() {
+        if !p.options.filter_section(index + 1, Some(section.name())) {
+            continue;
+        }
         p.group("SectionHeader", |p| {
             p.field("Index", index + 1);
             p.field_inline_string("Name", section.name());
@@ -132,7 +135,8 @@ fn print_sections<'data, Xcoff: FileHeader>(
                                 .and_then(|symbol| symbol.name(symbols.strings()))
                                 .print_err(p)
                         });
-                        p.field_string_option("Symbol", index.0, name);
+                        let name = name.map(|name| p.demangle(name));
+                        p.field_string_option("Symbol", index.0, name.as_deref());
                         p.field_hex("Size", relocation.r_rsize());
                         p.field_enum("Type", relocation.r_rtype(), FLAGS_R);
                     });
@@ -151,13 +155,17 @@ fn print_symbols<'data, Xcoff: FileHeader>(
         return;
     }
     for (index, symbol) in symbols.iter() {
+        let name = symbol.name(symbols.strings());
+        if !p.options.filter_symbol(index.0, name.ok()) {
+            continue;
+        }
         p.group("Symbol", |p| {
             p.field("Index", index.0);
-            let name = symbol.name(symbols.strings());
+            let name = name.map(|name| p.demangle(name));
             if let Some(offset) = symbol.name_offset() {
-                p.field_string("Name", offset, name);
+                p.field_string("Name", offset, name.as_deref());
             } else if let Ok(name) = name {
-                p.field_inline_string("Name", name);
+                p.field_inline_string("Name", &name);
             }
             p.field_hex("Value", symbol.n_value().into());
             if let Some(section_index) = symbol.section() {
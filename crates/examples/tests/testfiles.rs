@@ -62,19 +62,19 @@ fn testfiles() {
 
             if extension == "objdump" {
                 fail |= testfile(&in_path, &out_path, err_path, |out, err, data| {
-                    objdump::print(out, err, data, &[], vec![]).unwrap();
+                    objdump::print(out, err, data, &[], vec![], &Default::default()).unwrap();
                 });
             } else if extension == "objdump-shndx" {
                 // Special case for the large symtab shndx test.
                 fail |= testfile(&in_path, &out_path, err_path, |out, err, data| {
-                    objdump::print(out, err, data, &[], vec![]).unwrap();
+                    objdump::print(out, err, data, &[], vec![], &Default::default()).unwrap();
                     *out = filter_lines(&*out, |line| {
                         line.starts_with("6553") && line[5..].starts_with(": Symbol {")
                     });
                 });
             } else if extension == "objdump-comdat" {
                 fail |= testfile(&in_path, &out_path, err_path, |out, err, data| {
-                    objdump::print(out, err, data, &[], vec![]).unwrap();
+                    objdump::print(out, err, data, &[], vec![], &Default::default()).unwrap();
                     *out = filter_lines(&*out, |line| line.starts_with("Comdat "));
                 });
             } else if extension.starts_with("readobj") {
@@ -99,6 +99,10 @@ fn testfiles() {
                         pe_resources: true,
                         ..readobj::PrintOptions::none()
                     },
+                    "readobj-elf-gnu" => readobj::PrintOptions {
+                        elf_gnu_style: true,
+                        ..readobj::PrintOptions::all()
+                    },
                     _ => {
                         println!("Unknown test {}", out_path.display());
                         fail = true;
@@ -127,6 +131,452 @@ fn testfiles() {
     }
 }
 
+// The inputs for these tests are generated instead of being read from the
+// testfiles repository, so that the output doesn't depend on the toolchain
+// that produced them.
+#[cfg(feature = "write")]
+#[test]
+fn generated_elf_gnu() {
+    let out_testfiles = PathBuf::from("tests/generated");
+    let options = readobj::PrintOptions {
+        elf_gnu_style: true,
+        ..readobj::PrintOptions::all()
+    };
+
+    let mut fail = false;
+    for (name, in_data) in [
+        ("elf/relocatable.o", generated::elf_relocatable()),
+        ("elf/shared.so", generated::elf_shared()),
+    ] {
+        let mut out_path = out_testfiles.join(name).into_os_string();
+        out_path.push(".readobj-elf-gnu");
+        let out_path = PathBuf::from(out_path);
+        println!("Test {}", out_path.display());
+
+        let mut out_data = Vec::new();
+        let mut err_data = Vec::new();
+        readobj::print(&mut out_data, &mut err_data, &in_data, &[], &options);
+        fail |= check_output(&out_path, None, &out_data, &err_data);
+    }
+    if fail {
+        panic!("Tests failed; rerun with OBJECT_TESTFILES_UPDATE=1 to update tests");
+    }
+}
+
+#[cfg(feature = "write")]
+mod generated {
+    use object::write::elf::{FileHeader, ProgramHeader, Rel, SectionHeader, Sym, Writer};
+    use object::write::{Object, Relocation, StandardSection, Symbol, SymbolSection};
+    use object::{
+        elf, Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationFlags,
+        RelocationKind, SymbolFlags, SymbolKind, SymbolScope,
+    };
+
+    /// A relocatable object containing code, data, and relocations.
+    pub fn elf_relocatable() -> Vec<u8> {
+        let mut object = Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+        object.add_file_symbol(b"generated.c".to_vec());
+
+        let text = object.section_id(StandardSection::Text);
+        let data = object.section_id(StandardSection::Data);
+        let bss = object.section_id(StandardSection::UninitializedData);
+
+        let (main_offset, main_size) = (0, 16);
+        let main = object.add_symbol(Symbol {
+            name: b"main".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        object.add_symbol_data(main, text, &[0x90; 16], 16);
+        object.symbol_mut(main).size = main_size;
+        let helper = object.add_symbol(Symbol {
+            name: b"helper".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Compilation,
+            weak: false,
+            section: SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        object.add_symbol_data(helper, text, &[0xc3; 4], 16);
+        object.symbol_mut(helper).size = 4;
+
+        let value = object.add_symbol(Symbol {
+            name: b"value".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Data,
+            scope: SymbolScope::Linkage,
+            weak: true,
+            section: SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        object.add_symbol_data(value, data, &[0; 8], 8);
+        let counter = object.add_symbol(Symbol {
+            name: b"counter".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Data,
+            scope: SymbolScope::Dynamic,
+            weak: false,
+            section: SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        object.add_symbol_bss(counter, bss, 4, 4);
+        let external = object.add_symbol(Symbol {
+            name: b"external".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Dynamic,
+            weak: false,
+            section: SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+
+        object
+            .add_relocation(
+                text,
+                Relocation {
+                    offset: main_offset + 4,
+                    symbol: external,
+                    addend: -4,
+                    flags: RelocationFlags::Generic {
+                        kind: RelocationKind::PltRelative,
+                        encoding: RelocationEncoding::X86Branch,
+                        size: 32,
+                    },
+                },
+            )
+            .unwrap();
+        object
+            .add_relocation(
+                text,
+                Relocation {
+                    offset: main_offset + 10,
+                    symbol: counter,
+                    addend: -4,
+                    flags: RelocationFlags::Generic {
+                        kind: RelocationKind::Relative,
+                        encoding: RelocationEncoding::Generic,
+                        size: 32,
+                    },
+                },
+            )
+            .unwrap();
+        object
+            .add_relocation(
+                data,
+                Relocation {
+                    offset: 0,
+                    symbol: helper,
+                    addend: 0,
+                    flags: RelocationFlags::Generic {
+                        kind: RelocationKind::Absolute,
+                        encoding: RelocationEncoding::Generic,
+                        size: 64,
+                    },
+                },
+            )
+            .unwrap();
+
+        object.write().unwrap()
+    }
+
+    /// A shared object containing dynamic symbols, symbol versions, and dynamic relocations.
+    pub fn elf_shared() -> Vec<u8> {
+        const PAGE_SIZE: usize = 0x1000;
+        let text_data = [0x31, 0xc0, 0xc3, 0xcc];
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(Endianness::Little, true, &mut buffer);
+
+        let mut dynamic = object::write::elf::DynamicBuilder::new();
+        dynamic.add_needed(&mut writer, b"libc.so.6");
+        dynamic.add_soname(&mut writer, b"libgenerated.so");
+        let dt_symtab = dynamic.add(elf::DT_SYMTAB, 0);
+        let dt_strtab = dynamic.add(elf::DT_STRTAB, 0);
+        let dt_strsz = dynamic.add(elf::DT_STRSZ, 0);
+        dynamic.add(elf::DT_SYMENT, 24);
+        let dt_rela = dynamic.add(elf::DT_RELA, 0);
+        dynamic.add(elf::DT_RELASZ, 2 * 24);
+        dynamic.add(elf::DT_RELAENT, 24);
+        let dt_versym = dynamic.add(elf::DT_VERSYM, 0);
+        let dt_verdef = dynamic.add(elf::DT_VERDEF, 0);
+        dynamic.add(elf::DT_VERDEFNUM, 2);
+        let dt_verneed = dynamic.add(elf::DT_VERNEED, 0);
+        dynamic.add(elf::DT_VERNEEDNUM, 1);
+
+        writer.reserve_file_header();
+        writer.reserve_program_headers(5);
+
+        writer.reserve_null_section_index();
+        let dynsym_index = writer.reserve_dynsym_section_index();
+        writer.reserve_dynstr_section_index();
+        writer.reserve_gnu_versym_section_index();
+        writer.reserve_gnu_verdef_section_index();
+        writer.reserve_gnu_verneed_section_index();
+        let rela_dyn_name = writer.add_section_name(b".rela.dyn");
+        writer.reserve_section_index();
+        let text_name = writer.add_section_name(b".text");
+        let text_index = writer.reserve_section_index();
+        writer.reserve_dynamic_section_index();
+        let data_name = writer.add_section_name(b".data");
+        let data_index = writer.reserve_section_index();
+        writer.reserve_symtab_section_index();
+        writer.reserve_strtab_section_index();
+        writer.reserve_shstrtab_section_index();
+
+        let func_name = b"generated_func";
+        let value_name = b"generated_value";
+        let undefined_name = b"malloc";
+        let dyn_func_name = writer.add_dynamic_string(func_name);
+        let dyn_undefined_name = writer.add_dynamic_string(undefined_name);
+        writer.reserve_null_dynamic_symbol_index();
+        let dyn_undefined_index = writer.reserve_dynamic_symbol_index();
+        writer.reserve_dynamic_symbol_index();
+
+        let file_name = writer.add_string(b"generated.c");
+        let value_str = writer.add_string(value_name);
+        let func_str = writer.add_string(func_name);
+        let undefined_str = writer.add_string(undefined_name);
+        writer.reserve_null_symbol_index();
+        writer.reserve_symbol_index(None);
+        writer.reserve_symbol_index(Some(data_index));
+        writer.reserve_symbol_index(Some(text_index));
+        writer.reserve_symbol_index(None);
+
+        writer.add_gnu_version_definition(elf::VER_FLG_BASE, 1, &[b"libgenerated.so"]);
+        writer.add_gnu_version_definition(0, 2, &[b"GENERATED_1.0"]);
+        writer.add_gnu_version_dependency(b"libc.so.6", &[(0, 3, b"GLIBC_2.2.5")]);
+
+        let dynsym_offset = writer.reserve_dynsym();
+        let dynstr_offset = writer.reserve_dynstr();
+        let versym_offset = writer.reserve_gnu_versym();
+        let verdef_offset = writer.reserve_gnu_version_definitions();
+        let verneed_offset = writer.reserve_gnu_version_dependencies();
+        let rela_dyn_offset = writer.reserve_relocations(2, true);
+        let rodata_end = writer.reserved_len();
+
+        writer.reserve_until(PAGE_SIZE);
+        let text_offset = writer.reserve(text_data.len(), 16);
+
+        writer.reserve_until(2 * PAGE_SIZE);
+        let dynamic_offset = dynamic.reserve(&mut writer);
+        let data_offset = writer.reserve(16, 8);
+        let data_end = writer.reserved_len();
+
+        writer.reserve_symtab();
+        writer.reserve_strtab();
+        writer.reserve_shstrtab();
+        writer.reserve_section_headers();
+
+        // Addresses are the same as file offsets.
+        dynamic.set_value(dt_symtab, dynsym_offset as u64);
+        dynamic.set_value(dt_strtab, dynstr_offset as u64);
+        dynamic.set_value(dt_strsz, writer.dynstr_len() as u64);
+        dynamic.set_value(dt_rela, rela_dyn_offset as u64);
+        dynamic.set_value(dt_versym, versym_offset as u64);
+        dynamic.set_value(dt_verdef, verdef_offset as u64);
+        dynamic.set_value(dt_verneed, verneed_offset as u64);
+
+        writer
+            .write_file_header(&FileHeader {
+                os_abi: elf::ELFOSABI_NONE,
+                abi_version: 0,
+                e_type: elf::ET_DYN,
+                e_machine: elf::EM_X86_64,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .unwrap();
+
+        writer.write_align_program_headers();
+        let segment = |p_type, p_flags, offset: usize, end: usize, p_align| ProgramHeader {
+            p_type,
+            p_flags,
+            p_offset: offset as u64,
+            p_vaddr: offset as u64,
+            p_paddr: offset as u64,
+            p_filesz: (end - offset) as u64,
+            p_memsz: (end - offset) as u64,
+            p_align,
+        };
+        let page_size = PAGE_SIZE as u64;
+        writer.write_program_header(&segment(elf::PT_LOAD, elf::PF_R, 0, rodata_end, page_size));
+        writer.write_program_header(&segment(
+            elf::PT_LOAD,
+            elf::PF_R | elf::PF_X,
+            text_offset,
+            text_offset + text_data.len(),
+            page_size,
+        ));
+        writer.write_program_header(&segment(
+            elf::PT_LOAD,
+            elf::PF_R | elf::PF_W,
+            dynamic_offset,
+            data_end,
+            page_size,
+        ));
+        writer.write_program_header(&segment(
+            elf::PT_DYNAMIC,
+            elf::PF_R | elf::PF_W,
+            dynamic_offset,
+            data_offset,
+            8,
+        ));
+        writer.write_program_header(&segment(elf::PT_GNU_STACK, elf::PF_R | elf::PF_W, 0, 0, 16));
+
+        writer.write_null_dynamic_symbol();
+        writer.write_dynamic_symbol(&Sym {
+            name: Some(dyn_undefined_name),
+            section: None,
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: elf::STV_DEFAULT,
+            st_shndx: elf::SHN_UNDEF,
+            st_value: 0,
+            st_size: 0,
+        });
+        writer.write_dynamic_symbol(&Sym {
+            name: Some(dyn_func_name),
+            section: Some(text_index),
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: elf::STV_DEFAULT,
+            st_shndx: 0,
+            st_value: text_offset as u64,
+            st_size: text_data.len() as u64,
+        });
+        writer.write_dynstr();
+        writer.write_gnu_versyms(&[3, 2]);
+        writer.write_gnu_version_definitions();
+        writer.write_gnu_version_dependencies();
+
+        writer.write_align_relocation();
+        writer.write_relocation(
+            true,
+            &Rel {
+                r_offset: data_offset as u64,
+                r_sym: dyn_undefined_index.0,
+                r_type: elf::R_X86_64_GLOB_DAT,
+                r_addend: 0,
+            },
+        );
+        writer.write_relocation(
+            true,
+            &Rel {
+                r_offset: data_offset as u64 + 8,
+                r_sym: 0,
+                r_type: elf::R_X86_64_RELATIVE,
+                r_addend: text_offset as i64,
+            },
+        );
+
+        writer.pad_until(text_offset);
+        writer.write(&text_data);
+
+        writer.pad_until(dynamic_offset);
+        dynamic.write(&mut writer);
+        writer.write_align(8);
+        writer.write(&[0; 16]);
+
+        writer.write_null_symbol();
+        writer.write_symbol(&Sym {
+            name: Some(file_name),
+            section: None,
+            st_info: (elf::STB_LOCAL << 4) | elf::STT_FILE,
+            st_other: elf::STV_DEFAULT,
+            st_shndx: elf::SHN_ABS,
+            st_value: 0,
+            st_size: 0,
+        });
+        writer.write_symbol(&Sym {
+            name: Some(value_str),
+            section: Some(data_index),
+            st_info: (elf::STB_LOCAL << 4) | elf::STT_OBJECT,
+            st_other: elf::STV_HIDDEN,
+            st_shndx: 0,
+            st_value: data_offset as u64 + 8,
+            st_size: 8,
+        });
+        writer.write_symbol(&Sym {
+            name: Some(func_str),
+            section: Some(text_index),
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: elf::STV_DEFAULT,
+            st_shndx: 0,
+            st_value: text_offset as u64,
+            st_size: text_data.len() as u64,
+        });
+        writer.write_symbol(&Sym {
+            name: Some(undefined_str),
+            section: None,
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: elf::STV_DEFAULT,
+            st_shndx: elf::SHN_UNDEF,
+            st_value: 0,
+            st_size: 0,
+        });
+        writer.write_strtab();
+        writer.write_shstrtab();
+
+        writer.write_null_section_header();
+        writer.write_dynsym_section_header(dynsym_offset as u64, 1);
+        writer.write_dynstr_section_header(dynstr_offset as u64);
+        writer.write_gnu_versym_section_header(versym_offset as u64);
+        writer.write_gnu_verdef_section_header(verdef_offset as u64);
+        writer.write_gnu_verneed_section_header(verneed_offset as u64);
+        writer.write_section_header(&SectionHeader {
+            name: Some(rela_dyn_name),
+            sh_type: elf::SHT_RELA,
+            sh_flags: elf::SHF_ALLOC.into(),
+            sh_addr: rela_dyn_offset as u64,
+            sh_offset: rela_dyn_offset as u64,
+            sh_size: 2 * 24,
+            sh_link: dynsym_index.0,
+            sh_info: 0,
+            sh_addralign: 8,
+            sh_entsize: 24,
+        });
+        writer.write_section_header(&SectionHeader {
+            name: Some(text_name),
+            sh_type: elf::SHT_PROGBITS,
+            sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+            sh_addr: text_offset as u64,
+            sh_offset: text_offset as u64,
+            sh_size: text_data.len() as u64,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 16,
+            sh_entsize: 0,
+        });
+        writer.write_dynamic_section_header(dynamic_offset as u64);
+        writer.write_section_header(&SectionHeader {
+            name: Some(data_name),
+            sh_type: elf::SHT_PROGBITS,
+            sh_flags: (elf::SHF_ALLOC | elf::SHF_WRITE).into(),
+            sh_addr: data_offset as u64,
+            sh_offset: data_offset as u64,
+            sh_size: 16,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 8,
+            sh_entsize: 0,
+        });
+        writer.write_symtab_section_header(3);
+        writer.write_strtab_section_header();
+        writer.write_shstrtab_section_header();
+        assert_eq!(writer.reserved_len(), writer.len());
+
+        buffer
+    }
+}
+
 fn testfile<F>(in_path: &Path, out_path: &Path, err_path: Option<&Path>, f: F) -> bool
 where
     F: FnOnce(&mut Vec<u8>, &mut Vec<u8>, &[u8]),
@@ -143,13 +593,21 @@ where
     let mut out_data = Vec::new();
     let mut err_data = Vec::new();
     f(&mut out_data, &mut err_data, &in_data);
+    check_output(out_path, err_path, &out_data, &err_data)
+}
 
+fn check_output(
+    out_path: &Path,
+    err_path: Option<&Path>,
+    out_data: &[u8],
+    err_data: &[u8],
+) -> bool {
     let update = env::var_os("OBJECT_TESTFILES_UPDATE").is_some();
     let mut fail = false;
 
     // Check exact match of output.
     if update {
-        fs::write(out_path, &out_data).unwrap();
+        fs::write(out_path, out_data).unwrap();
     } else {
         let expect_out_data = fs::read(out_path).unwrap();
         if out_data != expect_out_data {
@@ -161,7 +619,7 @@ where
     // Check exact match of errors.
     if let Some(err_path) = err_path {
         if update {
-            fs::write(err_path, &err_data).unwrap();
+            fs::write(err_path, err_data).unwrap();
         } else {
             let expect_err_data = fs::read(err_path).unwrap();
             if err_data != expect_err_data {
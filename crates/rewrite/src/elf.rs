@@ -2,9 +2,11 @@ use std::collections::{HashMap, HashSet};
 
 #[cfg(feature = "logging")]
 use log::info;
-use object::{build, elf};
+use object::{build, elf, Endian, SectionFlags};
 
-use super::{Error, Result, Rewriter};
+use super::names::NameSet;
+use super::rewriter::Format;
+use super::{Error, NewSection, Result, Rewriter};
 
 /// Options for modifying an ELF file.
 ///
@@ -15,6 +17,26 @@ use super::{Error, Result, Rewriter};
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct ElfOptions {
+    /// Set the entry point address in the file header.
+    ///
+    /// See [`Rewriter::elf_set_entry`].
+    pub set_entry: Option<u64>,
+    /// Set the OS ABI in the file header.
+    ///
+    /// See [`Rewriter::elf_set_os_abi`].
+    pub set_os_abi: Option<u8>,
+    /// Set the ABI version in the file header.
+    ///
+    /// See [`Rewriter::elf_set_abi_version`].
+    pub set_abi_version: Option<u8>,
+    /// Set the processor-specific flags in the file header.
+    ///
+    /// See [`Rewriter::elf_set_flags`].
+    pub set_flags: Option<u32>,
+    /// Set the object file type in the file header.
+    ///
+    /// See [`Rewriter::elf_set_type`].
+    pub set_type: Option<u16>,
     /// Add a `DT_DEBUG` entry to the dynamic section.
     ///
     /// See [`Rewriter::elf_add_dynamic_debug`].
@@ -59,39 +81,112 @@ pub struct ElfOptions {
     ///
     /// See [`Rewriter::elf_set_interpreter`].
     pub set_interpreter: Option<Vec<u8>>,
+    /// Remove notes with the given name and optional type.
+    ///
+    /// See [`Rewriter::elf_remove_notes`].
+    pub remove_notes: Vec<(Vec<u8>, Option<u32>)>,
+    /// Add notes to note sections.
+    ///
+    /// See [`Rewriter::elf_add_notes`].
+    pub add_notes: Vec<ElfNote>,
+    /// Remove symbols that are not needed for relocation processing.
+    ///
+    /// See [`Rewriter::elf_strip_unneeded`].
+    pub strip_unneeded: bool,
+    /// Remove the contents of all sections except for debugging information.
+    ///
+    /// See [`Rewriter::elf_only_keep_debug`].
+    pub only_keep_debug: bool,
+    /// Add a `.gnu_debuglink` section with the given filename and CRC.
+    ///
+    /// See [`Rewriter::elf_add_gnu_debuglink`].
+    pub add_gnu_debuglink: Option<(Vec<u8>, u32)>,
+    /// Remove unused space between segments in the file.
+    ///
+    /// See [`Rewriter::elf_compact`].
+    pub compact: bool,
+    /// Delete data that is not part of any section, segment, or header.
+    ///
+    /// See [`Rewriter::elf_delete_extra_data`].
+    pub delete_extra_data: bool,
+}
+
+/// A note to add to an ELF file.
+///
+/// This is used in [`ElfOptions::add_notes`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ElfNote {
+    /// The name of the section to add the note to.
+    pub section: Vec<u8>,
+    /// The name of the note, without the null terminator.
+    pub name: Vec<u8>,
+    /// The type of the note.
+    pub n_type: u32,
+    /// The descriptor of the note.
+    pub desc: Vec<u8>,
+}
+
+impl ElfNote {
+    /// Create a new note to add to the given section.
+    pub fn new(section: Vec<u8>, name: Vec<u8>, n_type: u32, desc: Vec<u8>) -> Self {
+        ElfNote {
+            section,
+            name,
+            n_type,
+            desc,
+        }
+    }
 }
 
 impl Rewriter<'_> {
     /// Delete symbols from the symbol table.
-    pub fn elf_delete_symbols(&mut self, names: &HashSet<Vec<u8>>) {
-        for symbol in &mut self.builder.dynamic_symbols {
-            if names.contains(&*symbol.name) {
+    pub fn elf_delete_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        let matches = names.get_all(builder.symbols.iter().map(|s| &*s.name).collect());
+        for (symbol, matched) in builder.symbols.iter_mut().zip(matches) {
+            if matched.is_some() {
                 #[cfg(feature = "logging")]
                 info!("Deleting symbol {}", symbol.name);
                 symbol.delete = true;
                 self.modified = true;
             }
         }
+        Ok(())
     }
 
     /// Delete symbols from the dynamic symbol table.
-    pub fn elf_delete_dynamic_symbols(&mut self, names: &HashSet<Vec<u8>>) {
-        for symbol in &mut self.builder.symbols {
-            if names.contains(&*symbol.name) {
+    pub fn elf_delete_dynamic_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        let matches = names.get_all(builder.dynamic_symbols.iter().map(|s| &*s.name).collect());
+        for (symbol, matched) in builder.dynamic_symbols.iter_mut().zip(matches) {
+            if matched.is_some() {
                 #[cfg(feature = "logging")]
                 info!("Deleting dynamic symbol {}", symbol.name);
                 symbol.delete = true;
                 self.modified = true;
             }
         }
+        Ok(())
     }
 
     /// Rename symbols in the symbol table.
     ///
     /// The `names` map is from old names to new names.
-    pub fn elf_rename_symbols(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) {
-        for symbol in &mut self.builder.dynamic_symbols {
-            if let Some(name) = names.get(&*symbol.name) {
+    pub fn elf_rename_symbols(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let names = self.name_map(names)?;
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        let matches = names.get_all(builder.symbols.iter().map(|s| &*s.name).collect());
+        for (symbol, name) in builder.symbols.iter_mut().zip(matches) {
+            if let Some(name) = name {
                 let name = name.clone().into();
                 #[cfg(feature = "logging")]
                 info!("Renaming symbol {} to {}", symbol.name, name);
@@ -99,14 +194,20 @@ impl Rewriter<'_> {
                 self.modified = true;
             }
         }
+        Ok(())
     }
 
     /// Rename symbols in the dynamic symbol table.
     ///
     /// The `names` map is from old names to new names.
-    pub fn elf_rename_dynamic_symbols(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) {
-        for symbol in &mut self.builder.dynamic_symbols {
-            if let Some(name) = names.get(&*symbol.name) {
+    pub fn elf_rename_dynamic_symbols(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let names = self.name_map(names)?;
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        let matches = names.get_all(builder.dynamic_symbols.iter().map(|s| &*s.name).collect());
+        for (symbol, name) in builder.dynamic_symbols.iter_mut().zip(matches) {
+            if let Some(name) = name {
                 let name = name.clone().into();
                 #[cfg(feature = "logging")]
                 info!("Renaming dynamic symbol {} to {}", symbol.name, name);
@@ -114,11 +215,93 @@ impl Rewriter<'_> {
                 self.modified = true;
             }
         }
+        Ok(())
+    }
+
+    pub(crate) fn elf_localize_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        self.elf_change_symbol_binding(BindingChange::Localize(&names));
+        Ok(())
+    }
+
+    pub(crate) fn elf_keep_global_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        self.elf_change_symbol_binding(BindingChange::KeepGlobal(&names));
+        Ok(())
+    }
+
+    pub(crate) fn elf_globalize_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        self.elf_change_symbol_binding(BindingChange::Globalize(&names));
+        Ok(())
+    }
+
+    pub(crate) fn elf_weaken_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        self.elf_change_symbol_binding(BindingChange::Weaken(&names));
+        Ok(())
+    }
+
+    pub(crate) fn elf_prefix_symbols(&mut self, prefix: &[u8]) {
+        fn prefix_symbols<const DYNAMIC: bool>(
+            symbols: &mut build::elf::Symbols<'_, DYNAMIC>,
+            prefix: &[u8],
+        ) -> bool {
+            let mut modified = false;
+            for symbol in symbols {
+                if symbol.name.is_empty()
+                    || symbol.st_type() == elf::STT_SECTION
+                    || symbol.st_type() == elf::STT_FILE
+                {
+                    continue;
+                }
+                let mut name = prefix.to_vec();
+                name.extend_from_slice(&symbol.name);
+                let name = name.into();
+                #[cfg(feature = "logging")]
+                info!("Renaming symbol {} to {}", symbol.name, name);
+                symbol.name = name;
+                modified = true;
+            }
+            modified
+        }
+
+        let Some(builder) = self.format.elf_mut() else {
+            return;
+        };
+        if prefix_symbols(&mut builder.symbols, prefix) {
+            self.modified = true;
+        }
+        if prefix_symbols(&mut builder.dynamic_symbols, prefix) {
+            self.modified = true;
+        }
+    }
+
+    /// Change the binding of symbols in both the symbol table and the dynamic
+    /// symbol table.
+    fn elf_change_symbol_binding(&mut self, change: BindingChange<'_>) {
+        let Some(builder) = self.format.elf_mut() else {
+            return;
+        };
+        for symbol in &mut builder.symbols {
+            if change.apply(symbol) {
+                self.modified = true;
+            }
+        }
+        for symbol in &mut builder.dynamic_symbols {
+            if change.apply(symbol) {
+                self.modified = true;
+            }
+        }
     }
 
-    pub(crate) fn elf_delete_sections(&mut self, names: &HashSet<Vec<u8>>) {
-        for section in &mut self.builder.sections {
-            if names.contains(&*section.name) {
+    pub(crate) fn elf_delete_sections(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        for section in &mut builder.sections {
+            if names.contains(&section.name) {
                 #[cfg(feature = "logging")]
                 info!("Deleting section {}", section.name);
                 // Associated program header will be deleted by delete_orphan_segments.
@@ -126,11 +309,16 @@ impl Rewriter<'_> {
                 self.modified = true;
             }
         }
+        Ok(())
     }
 
-    pub(crate) fn elf_rename_sections(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) {
-        for section in &mut self.builder.sections {
-            if let Some(name) = names.get(&*section.name) {
+    pub(crate) fn elf_rename_sections(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let names = self.name_map(names)?;
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        for section in &mut builder.sections {
+            if let Some(name) = names.get(&section.name) {
                 let name = name.clone().into();
                 #[cfg(feature = "logging")]
                 info!("Renaming section {} to {}", section.name, name);
@@ -138,9 +326,182 @@ impl Rewriter<'_> {
                 self.modified = true;
             }
         }
+        Ok(())
+    }
+
+    pub(crate) fn elf_prefix_sections(&mut self, prefix: &[u8]) {
+        let Some(builder) = self.format.elf_mut() else {
+            return;
+        };
+        let mut names = Vec::new();
+        for section in &builder.sections {
+            match section.data {
+                build::elf::SectionData::Data(_)
+                | build::elf::SectionData::UninitializedData(_)
+                | build::elf::SectionData::Note(_)
+                | build::elf::SectionData::Attributes(_)
+                | build::elf::SectionData::Relocation(_) => {}
+                // Don't rename sections that are only needed for symbols
+                // or dynamic linking.
+                _ => continue,
+            }
+            if section.name.is_empty() {
+                continue;
+            }
+            let mut split = 0;
+            // Keep relocation section names matching the names of their target sections.
+            if let (build::elf::SectionData::Relocation(_), Some(target)) =
+                (&section.data, section.sh_info_section)
+            {
+                let target_name = &*builder.sections.get(target).name;
+                let rel_prefix: &[u8] = if section.sh_type == elf::SHT_RELA {
+                    b".rela"
+                } else {
+                    b".rel"
+                };
+                if section.name.strip_prefix(rel_prefix) == Some(target_name) {
+                    split = rel_prefix.len();
+                }
+            }
+            let mut name = section.name[..split].to_vec();
+            name.extend_from_slice(prefix);
+            name.extend_from_slice(&section.name[split..]);
+            names.push((section.id(), name));
+        }
+        for (id, name) in names {
+            let section = builder.sections.get_mut(id);
+            let name = name.into();
+            #[cfg(feature = "logging")]
+            info!("Renaming section {} to {}", section.name, name);
+            section.name = name;
+            self.modified = true;
+        }
+    }
+
+    pub(crate) fn elf_section_data(&self, name: &[u8]) -> Result<&[u8]> {
+        let section = self
+            .format
+            .elf()
+            .and_then(|builder| {
+                builder
+                    .sections
+                    .iter()
+                    .find(|section| section.name.as_slice() == name)
+            })
+            .ok_or_else(|| {
+                Error::modify(format!(
+                    "Section {} not found",
+                    String::from_utf8_lossy(name)
+                ))
+            })?;
+        match &section.data {
+            build::elf::SectionData::Data(data) | build::elf::SectionData::Note(data) => Ok(data),
+            build::elf::SectionData::UninitializedData(_) => Err(Error::modify(format!(
+                "Section {} has no contents",
+                section.name
+            ))),
+            _ => Err(Error::modify(format!(
+                "Unsupported contents for section {}",
+                section.name
+            ))),
+        }
+    }
+
+    pub(crate) fn elf_update_sections(
+        &mut self,
+        sections: &HashMap<Vec<u8>, Vec<u8>>,
+    ) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        let has_segments = !builder.segments.is_empty();
+        for (name, data) in sections {
+            let mut found = false;
+            for section in builder.sections.iter_mut() {
+                if section.name.as_slice() != name.as_slice() {
+                    continue;
+                }
+                found = true;
+                if has_segments && section.is_alloc() && data.len() as u64 > section.sh_size {
+                    return Err(Error::modify(format!(
+                        "Contents of allocated section {} can't be larger than 0x{:x} bytes",
+                        section.name, section.sh_size
+                    )));
+                }
+                match &mut section.data {
+                    build::elf::SectionData::Data(bytes) | build::elf::SectionData::Note(bytes) => {
+                        *bytes = data.clone().into();
+                    }
+                    _ => {
+                        return Err(Error::modify(format!(
+                            "Unsupported contents for section {}",
+                            section.name
+                        )));
+                    }
+                }
+                #[cfg(feature = "logging")]
+                info!("Updating section {}", section.name);
+                self.modified = true;
+            }
+            if !found {
+                return Err(Error::modify(format!(
+                    "Section {} not found",
+                    String::from_utf8_lossy(name)
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn elf_add_sections(&mut self, sections: &[NewSection]) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Ok(());
+        };
+        for new_section in sections {
+            if builder
+                .sections
+                .iter()
+                .any(|section| *section.name == *new_section.name)
+            {
+                return Err(Error::modify(format!(
+                    "Section {} already exists",
+                    String::from_utf8_lossy(&new_section.name)
+                )));
+            }
+            let sh_flags = match new_section.flags {
+                SectionFlags::None => 0,
+                SectionFlags::Elf { sh_flags } => sh_flags,
+                _ => return Err(Error::modify("Invalid section flags for ELF file")),
+            };
+            let section = builder.sections.add();
+            section.name = new_section.name.clone().into();
+            section.sh_type = elf::SHT_PROGBITS;
+            section.sh_flags = sh_flags;
+            section.sh_addralign = new_section.align.max(1);
+            section.data = build::elf::SectionData::Data(new_section.data.clone().into());
+            #[cfg(feature = "logging")]
+            info!("Adding section {}", section.name);
+            self.modified = true;
+        }
+        Ok(())
     }
 
     pub(crate) fn elf_modify(&mut self, options: ElfOptions) -> Result<()> {
+        if let Some(entry) = options.set_entry {
+            self.elf_set_entry(entry)?;
+        }
+        if let Some(os_abi) = options.set_os_abi {
+            self.elf_set_os_abi(os_abi)?;
+        }
+        if let Some(abi_version) = options.set_abi_version {
+            self.elf_set_abi_version(abi_version)?;
+        }
+        if let Some(flags) = options.set_flags {
+            self.elf_set_flags(flags)?;
+        }
+        if let Some(e_type) = options.set_type {
+            self.elf_set_type(e_type)?;
+        }
         if options.add_dynamic_debug {
             self.elf_add_dynamic_debug()?;
         }
@@ -174,14 +535,126 @@ impl Rewriter<'_> {
         if let Some(interpreter) = options.set_interpreter {
             self.elf_set_interpreter(interpreter)?;
         }
+        if !options.remove_notes.is_empty() {
+            self.elf_remove_notes(&options.remove_notes)?;
+        }
+        if !options.add_notes.is_empty() {
+            self.elf_add_notes(&options.add_notes)?;
+        }
+        if options.strip_unneeded {
+            self.elf_strip_unneeded()?;
+        }
+        if options.only_keep_debug {
+            self.elf_only_keep_debug()?;
+        }
+        if let Some((filename, crc)) = options.add_gnu_debuglink {
+            self.elf_add_gnu_debuglink(&filename, crc)?;
+        }
+        if options.compact {
+            self.elf_compact()?;
+        }
+        if options.delete_extra_data {
+            self.elf_delete_extra_data()?;
+        }
+        Ok(())
+    }
+
+    /// Set the entry point address in the file header.
+    pub fn elf_set_entry(&mut self, entry: u64) -> Result<()> {
+        let header = self
+            .elf_header_mut()
+            .ok_or_else(|| Error::modify("Not an ELF file; can't set entry point"))?;
+        #[cfg(feature = "logging")]
+        info!("Setting entry point to 0x{:x}", entry);
+        header.e_entry = entry;
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Set the OS ABI in the file header.
+    ///
+    /// This should be one of the `ELFOSABI_*` constants.
+    pub fn elf_set_os_abi(&mut self, os_abi: u8) -> Result<()> {
+        let header = self
+            .elf_header_mut()
+            .ok_or_else(|| Error::modify("Not an ELF file; can't set OS ABI"))?;
+        #[cfg(feature = "logging")]
+        info!("Setting OS ABI to {}", os_abi);
+        header.os_abi = os_abi;
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Set the ABI version in the file header.
+    pub fn elf_set_abi_version(&mut self, abi_version: u8) -> Result<()> {
+        let header = self
+            .elf_header_mut()
+            .ok_or_else(|| Error::modify("Not an ELF file; can't set ABI version"))?;
+        #[cfg(feature = "logging")]
+        info!("Setting ABI version to {}", abi_version);
+        header.abi_version = abi_version;
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Set the processor-specific flags in the file header.
+    pub fn elf_set_flags(&mut self, flags: u32) -> Result<()> {
+        let header = self
+            .elf_header_mut()
+            .ok_or_else(|| Error::modify("Not an ELF file; can't set flags"))?;
+        #[cfg(feature = "logging")]
+        info!("Setting flags to 0x{:x}", flags);
+        header.e_flags = flags;
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Set the object file type in the file header.
+    ///
+    /// Only changing between `ET_EXEC` and `ET_DYN` is supported. This does not
+    /// change the code or addresses, so the caller must ensure that the file is
+    /// valid for the new type. When changing to `ET_EXEC`, the `DF_1_PIE` flag
+    /// is cleared.
+    pub fn elf_set_type(&mut self, e_type: u16) -> Result<()> {
+        let builder = self
+            .format
+            .elf_mut()
+            .ok_or_else(|| Error::modify("Not an ELF file; can't set file type"))?;
+        let old_type = builder.header.e_type;
+        if old_type == e_type {
+            return Ok(());
+        }
+        if !matches!(old_type, elf::ET_EXEC | elf::ET_DYN)
+            || !matches!(e_type, elf::ET_EXEC | elf::ET_DYN)
+        {
+            return Err(Error::modify(format!(
+                "Can't change file type from {} to {}",
+                old_type, e_type
+            )));
+        }
+
+        #[cfg(feature = "logging")]
+        info!("Setting file type to {}", e_type);
+        builder.header.e_type = e_type;
+        if e_type == elf::ET_EXEC && builder.dynamic_section().is_some() {
+            builder
+                .update_dynamic_flags(elf::DT_FLAGS_1, 0, u64::from(elf::DF_1_PIE))
+                .map_err(|e| Error::modify(e.to_string()))?;
+        }
+        self.modified = true;
         Ok(())
     }
 
+    fn elf_header_mut(&mut self) -> Option<&mut build::elf::Header> {
+        self.format.elf_mut().map(|builder| &mut builder.header)
+    }
+
     /// Add a `DT_DEBUG` entry to the dynamic section.
     pub fn elf_add_dynamic_debug(&mut self) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't add debug entry"))?;
         if dynamic.iter().any(|entry| entry.tag() == elf::DT_DEBUG) {
             return Ok(());
@@ -199,7 +672,7 @@ impl Rewriter<'_> {
 
     /// Find the first `DT_RUNPATH` or `DT_RPATH` entry in the dynamic section.
     pub fn elf_runpath(&self) -> Option<&[u8]> {
-        let dynamic = self.builder.dynamic_data()?;
+        let dynamic = self.format.elf()?.dynamic_data()?;
         for entry in dynamic.iter() {
             let build::elf::Dynamic::String { tag, val } = entry else {
                 continue;
@@ -215,8 +688,9 @@ impl Rewriter<'_> {
     /// Delete any `DT_RUNPATH` or `DT_RPATH` entries in the dynamic section.
     pub fn elf_delete_runpath(&mut self) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't delete runpath"))?;
         let mut modified = false;
         dynamic.retain(|entry| {
@@ -246,8 +720,9 @@ impl Rewriter<'_> {
     /// Set the path for any `DT_RUNPATH` or `DT_RPATH` entry in the dynamic section.
     pub fn elf_set_runpath(&mut self, runpath: Vec<u8>) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't set runpath"))?;
         let mut found = false;
         for entry in dynamic.iter_mut() {
@@ -287,8 +762,9 @@ impl Rewriter<'_> {
     /// Add additional paths to any `DT_RUNPATH` or `DT_RPATH` entry in the dynamic section.
     pub fn elf_add_runpath(&mut self, runpaths: &[Vec<u8>]) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't add runpath"))?;
         let mut found = false;
         for entry in dynamic.iter_mut() {
@@ -333,8 +809,9 @@ impl Rewriter<'_> {
     /// Change any `DT_RPATH` entry in the dynamic section to `DT_RUNPATH`.
     pub fn elf_use_runpath(&mut self) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't change runpath"))?;
         for entry in dynamic.iter_mut() {
             let build::elf::Dynamic::String { tag, .. } = entry else {
@@ -355,8 +832,9 @@ impl Rewriter<'_> {
     /// Change any `DT_RUNPATH` entry in the dynamic section to `DT_RPATH`.
     pub fn elf_use_rpath(&mut self) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't change rpath"))?;
         for entry in dynamic.iter_mut() {
             let build::elf::Dynamic::String { tag, .. } = entry else {
@@ -376,7 +854,11 @@ impl Rewriter<'_> {
 
     /// Find the `DT_NEEDED` entries in the dynamic section.
     pub fn elf_needed(&self) -> impl Iterator<Item = &[u8]> {
-        let dynamic = self.builder.dynamic_data().unwrap_or(&[]);
+        let dynamic = self
+            .format
+            .elf()
+            .and_then(|builder| builder.dynamic_data())
+            .unwrap_or(&[]);
         dynamic.iter().filter_map(|entry| {
             if let build::elf::Dynamic::String { tag, val } = entry {
                 if *tag == elf::DT_NEEDED {
@@ -389,9 +871,13 @@ impl Rewriter<'_> {
 
     /// Delete `DT_NEEDED` entries from the dynamic section.
     pub fn elf_delete_needed(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
-        let dynamic = self.builder.dynamic_data_mut().ok_or_else(|| {
-            Error::modify("No dynamic section found; can't delete needed library")
-        })?;
+        let dynamic = self
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
+            .ok_or_else(|| {
+                Error::modify("No dynamic section found; can't delete needed library")
+            })?;
         let mut modified = false;
         dynamic.retain(|entry| {
             let build::elf::Dynamic::String { tag, val } = entry else {
@@ -414,9 +900,13 @@ impl Rewriter<'_> {
 
     /// Replace `DT_NEEDED` entries in the dynamic section.
     pub fn elf_replace_needed(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
-        let dynamic = self.builder.dynamic_data_mut().ok_or_else(|| {
-            Error::modify("No dynamic section found; can't replace needed library")
-        })?;
+        let dynamic = self
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
+            .ok_or_else(|| {
+                Error::modify("No dynamic section found; can't replace needed library")
+            })?;
         for entry in dynamic.iter_mut() {
             let build::elf::Dynamic::String { tag, val } = entry else {
                 continue;
@@ -442,8 +932,9 @@ impl Rewriter<'_> {
     /// This does not add a `DT_NEEDED` entry if the library is already listed.
     pub fn elf_add_needed(&mut self, names: &[Vec<u8>]) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't add needed library"))?;
         let mut found = HashSet::new();
         for entry in dynamic.iter() {
@@ -477,8 +968,9 @@ impl Rewriter<'_> {
 
     /// Find the `DT_SONAME` entry in the dynamic section.
     pub fn elf_soname(&self) -> Option<&[u8]> {
-        let id = self.builder.dynamic_section()?;
-        let section = self.builder.sections.get(id);
+        let builder = self.format.elf()?;
+        let id = builder.dynamic_section()?;
+        let section = builder.sections.get(id);
         let build::elf::SectionData::Dynamic(dynamic) = &section.data else {
             return None;
         };
@@ -498,8 +990,9 @@ impl Rewriter<'_> {
     /// Set the `DT_SONAME` entry in the dynamic section.
     pub fn elf_set_soname(&mut self, soname: Vec<u8>) -> Result<()> {
         let dynamic = self
-            .builder
-            .dynamic_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.dynamic_data_mut())
             .ok_or_else(|| Error::modify("No dynamic section found; can't set soname"))?;
         let mut found = false;
         for entry in dynamic.iter_mut() {
@@ -530,7 +1023,7 @@ impl Rewriter<'_> {
 
     /// Find the interpreter path in the `PT_INTERP` segment.
     pub fn elf_interpreter(&self) -> Option<&[u8]> {
-        self.builder.interp_data()
+        self.format.elf()?.interp_data()
     }
 
     /// Set the interpreter path in the `PT_INTERP` segment.
@@ -538,8 +1031,9 @@ impl Rewriter<'_> {
     /// The null terminator is automatically added if needed.
     pub fn elf_set_interpreter(&mut self, mut interpreter: Vec<u8>) -> Result<()> {
         let data = self
-            .builder
-            .interp_data_mut()
+            .format
+            .elf_mut()
+            .and_then(|builder| builder.interp_data_mut())
             .ok_or_else(|| Error::modify("No interp section found; can't set interpreter"))?;
         #[cfg(feature = "logging")]
         info!(
@@ -554,362 +1048,603 @@ impl Rewriter<'_> {
         Ok(())
     }
 
-    pub(crate) fn elf_finalize(&mut self) -> Result<()> {
-        if self.modified {
-            move_sections(&mut self.builder)?;
-        }
-        Ok(())
-    }
-}
-
-enum BlockKind {
-    FileHeader,
-    ProgramHeaders,
-    Segment,
-    Section(build::elf::SectionId),
-}
-
-struct Block<'a> {
-    #[allow(dead_code)]
-    name: build::ByteString<'a>,
-    kind: BlockKind,
-    address: u64,
-    size: u64,
-    // Higher means better to move. 0 means never move.
-    move_priority: u8,
-}
-
-/// Move sections between segments if needed, and assign file offsets to segments and sections.
-///
-/// Does not change the size of existing `PT_LOAD` segments, but may add new segments.
-// TODO: allow changing size of existing `PT_LOAD` segments
-fn move_sections(builder: &mut build::elf::Builder) -> Result<()> {
-    builder.delete_orphans();
-    builder.delete_unused_versions();
-    builder.set_section_sizes();
-
-    let mut added_p_flags = Vec::new();
-    let mut added_segments = 0;
-
-    // Loop until we reach a fixed point for the number of additional segments needed.
-    loop {
-        let mut move_sections = find_move_sections(builder, added_segments)?;
-        if move_sections.is_empty() {
-            return Ok(());
-        }
-
-        // Calculate the number of additional PT_LOAD segments needed.
-        added_p_flags.clear();
-        for id in &move_sections {
-            let section = builder.sections.get_mut(*id);
-            // Flag the section as needing to move.
-            section.sh_offset = 0;
-            // We need one PT_LOAD segment for each unique combination of p_flags.
-            let p_flags = section.p_flags();
-            if !added_p_flags.contains(&p_flags) {
-                added_p_flags.push(p_flags);
-            }
-        }
+    /// Remove notes from all note sections.
+    ///
+    /// Each entry contains the name of the note and an optional type. A note
+    /// is removed if its name matches and the type is either `None` or equal
+    /// to the type of the note. Names are matched according to
+    /// [`Options::name_matching`](super::Options::name_matching).
+    ///
+    /// Note sections that no longer contain any notes are deleted, along with
+    /// any program headers that only refer to them.
+    ///
+    /// Returns an error if notes would be removed from a section that has
+    /// relocations, since the relocation offsets would no longer be valid.
+    pub fn elf_remove_notes(&mut self, notes: &[(Vec<u8>, Option<u32>)]) -> Result<()> {
+        let matching = self.name_matching;
+        let notes = notes
+            .iter()
+            .map(|(name, n_type)| {
+                let name = NameSet::new(matching, core::iter::once((name.as_slice(), &())))?;
+                Ok((name, *n_type))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let Some(builder) = self.format.elf_mut() else {
+            return Err(Error::modify("Not an ELF file"));
+        };
+        let endian = builder.endian;
 
-        // If moving a section that is part of a non-PT_LOAD segment, then we may need to
-        // split the segment, which will require an additional segment.
-        let mut split_segments = 0;
-        for segment in &mut builder.segments {
-            if segment.p_type == elf::PT_LOAD {
+        let mut changed = Vec::new();
+        for section in &builder.sections {
+            let build::elf::SectionData::Note(data) = &section.data else {
                 continue;
-            }
-            let mut any = false;
-            let mut all = true;
-            for id in &segment.sections {
-                if move_sections.contains(id) {
-                    any = true;
+            };
+            let align = note_align(section.sh_addralign);
+            let mut new_data = Vec::new();
+            let mut removed = false;
+            for note in parse_notes(data, endian, align).ok_or_else(|| {
+                Error::modify(format!("Invalid notes in section {}", section.name))
+            })? {
+                if notes.iter().any(|(name, n_type)| {
+                    name.contains(note.name) && (n_type.is_none() || *n_type == Some(note.n_type))
+                }) {
+                    #[cfg(feature = "logging")]
+                    info!(
+                        "Removing note {} type 0x{:x} from section {}",
+                        build::ByteString::from(note.name),
+                        note.n_type,
+                        section.name
+                    );
+                    removed = true;
                 } else {
-                    all = false;
+                    new_data.extend_from_slice(&data[note.range]);
                 }
             }
-            if !any || all {
+            if !removed {
                 continue;
             }
-            split_segments += 1;
+            if builder.sections.iter().any(|relocation| {
+                matches!(relocation.data, build::elf::SectionData::Relocation(_))
+                    && relocation.sh_info_section == Some(section.id())
+            }) && !new_data.is_empty()
+            {
+                return Err(Error::modify(format!(
+                    "Can't remove notes from section {} with relocations",
+                    section.name
+                )));
+            }
+            changed.push((section.id(), new_data));
         }
 
-        // Check if we have reached a fixed point for the number of additional segments needed.
-        if added_segments < split_segments + added_p_flags.len() {
-            added_segments = split_segments + added_p_flags.len();
-            continue;
+        for (id, data) in changed {
+            if data.is_empty() {
+                let section = builder.sections.get_mut(id);
+                #[cfg(feature = "logging")]
+                info!("Deleting empty note section {}", section.name);
+                // Associated program header will be deleted by delete_orphan_segments.
+                section.delete = true;
+                for section in &mut builder.sections {
+                    if matches!(section.data, build::elf::SectionData::Relocation(_))
+                        && section.sh_info_section == Some(id)
+                    {
+                        section.delete = true;
+                    }
+                }
+            } else {
+                let section = builder.sections.get_mut(id);
+                section.sh_size = data.len() as u64;
+                section.data = build::elf::SectionData::Note(data.into());
+                let sections = &builder.sections;
+                for segment in &mut builder.segments {
+                    if segment.p_type != elf::PT_LOAD && segment.sections.contains(&id) {
+                        segment.recalculate_ranges(sections);
+                    }
+                }
+            }
+            self.modified = true;
         }
+        Ok(())
+    }
 
-        #[cfg(feature = "logging")]
-        info!(
-            "Moving {} sections, adding {} PT_LOAD segments, splitting {} segments",
-            move_sections.len(),
-            added_p_flags.len(),
-            split_segments,
-        );
-
-        // Add the PT_LOAD segments and append sections to them.
-        // Try to keep the same order of sections in the new segments.
-        move_sections.sort_by_key(|id| {
-            let section = builder.sections.get(*id);
-            (section.sh_addr, section.sh_size)
-        });
-        for p_flags in added_p_flags {
-            // TODO: reuse segments that only contain movable sections
-            let segment = builder
-                .segments
-                .add_load_segment(p_flags, builder.load_align);
-            for id in &move_sections {
-                let section = builder.sections.get_mut(*id);
-                if p_flags == section.p_flags() {
-                    segment.append_section(section);
-                    #[cfg(feature = "logging")]
-                    info!(
-                        "Moved {} to offset {:x}, addr {:x}",
-                        section.name, section.sh_offset, section.sh_addr
-                    );
+    /// Add notes to note sections.
+    ///
+    /// If a note section with the given name already exists, then the note is
+    /// appended to it. Otherwise, a new non-allocated `SHT_NOTE` section is added.
+    ///
+    /// Returns an error if a section with the given name exists but is not a
+    /// note section.
+    pub fn elf_add_notes(&mut self, notes: &[ElfNote]) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Err(Error::modify("Not an ELF file"));
+        };
+        let endian = builder.endian;
+        for note in notes {
+            let existing = builder
+                .sections
+                .iter()
+                .find(|section| section.name.as_slice() == note.section.as_slice())
+                .map(|section| section.id());
+            let section = match existing {
+                Some(id) => builder.sections.get_mut(id),
+                None => {
+                    let section = builder.sections.add();
+                    section.name = note.section.clone().into();
+                    section.sh_type = elf::SHT_NOTE;
+                    section.sh_addralign = 4;
+                    section.data = build::elf::SectionData::Note(Vec::new().into());
+                    section
                 }
-            }
+            };
+            let align = note_align(section.sh_addralign);
+            let build::elf::SectionData::Note(data) = &mut section.data else {
+                return Err(Error::modify(format!(
+                    "Section {} is not a note section",
+                    section.name
+                )));
+            };
             #[cfg(feature = "logging")]
             info!(
-                "Added PT_LOAD segment with p_flags {:x}, offset {:x}, addr {:x}, size {:x}",
-                p_flags, segment.p_offset, segment.p_vaddr, segment.p_memsz,
+                "Adding note {} type 0x{:x} to section {}",
+                build::ByteString::from(note.name.as_slice()),
+                note.n_type,
+                section.name
             );
+            let data = data.to_mut();
+            data.resize((data.len() + align - 1) & !(align - 1), 0);
+            let namesz = if note.name.is_empty() {
+                0
+            } else {
+                note.name.len() + 1
+            };
+            for value in [namesz, note.desc.len()] {
+                let value = u32::try_from(value)
+                    .map_err(|_| Error::modify("Note name or descriptor is too large"))?;
+                data.extend_from_slice(&u32_bytes(endian, value));
+            }
+            data.extend_from_slice(&u32_bytes(endian, note.n_type));
+            if namesz != 0 {
+                data.extend_from_slice(&note.name);
+                data.push(0);
+            }
+            data.resize((data.len() + align - 1) & !(align - 1), 0);
+            data.extend_from_slice(&note.desc);
+            data.resize((data.len() + align - 1) & !(align - 1), 0);
+            self.modified = true;
         }
+        Ok(())
+    }
 
-        // Split or move non-PT_LOAD segments that contain sections that have been moved.
-        let sections = &builder.sections;
-        let mut split_segments = Vec::new();
-        for segment in &mut builder.segments {
-            if segment.p_type == elf::PT_LOAD {
-                continue;
-            }
+    /// Remove symbols that are not needed for relocation processing.
+    ///
+    /// This matches the behaviour of `objcopy --strip-unneeded`. Symbols that
+    /// are referenced by a relocation or by an attributes section are always
+    /// kept. For relocatable files, global and weak symbols that are defined
+    /// or common are also kept. All other symbols are removed, including local
+    /// symbols, file symbols, and unreferenced undefined symbols.
+    ///
+    /// Relocations in debugging sections count as references, so symbols that
+    /// are needed for debugging information are kept.
+    ///
+    /// The dynamic symbol table is not changed.
+    pub fn elf_strip_unneeded(&mut self) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Err(Error::modify("Not an ELF file"));
+        };
 
-            let mut any = false;
-            let mut all = true;
-            for id in &segment.sections {
-                if move_sections.contains(id) {
-                    any = true;
-                } else {
-                    all = false;
+        let mut referenced = Vec::new();
+        for section in &builder.sections {
+            match &section.data {
+                build::elf::SectionData::Relocation(relocations) => {
+                    referenced.extend(relocations.iter().filter_map(|r| r.symbol));
                 }
+                build::elf::SectionData::Attributes(attributes) => {
+                    for subsection in &attributes.subsections {
+                        for subsubsection in &subsection.subsubsections {
+                            if let build::elf::AttributeTag::Symbol(symbols) = &subsubsection.tag {
+                                referenced.extend_from_slice(symbols);
+                            }
+                        }
+                    }
+                }
+                _ => {}
             }
-            if !any {
+        }
+
+        let relocatable = builder.header.e_type == elf::ET_REL;
+        for symbol in &mut builder.symbols {
+            if symbol.delete || referenced.contains(&symbol.id()) {
                 continue;
             }
-            if !all {
-                // Segment needs splitting.
-                // Remove all the sections that have been moved, and store them so
-                // that we can add the new segment later.
-                let mut split_sections = Vec::new();
-                segment.sections.retain(|id| {
-                    if move_sections.contains(id) {
-                        split_sections.push(*id);
-                        false
-                    } else {
-                        true
-                    }
-                });
-                split_segments.push((segment.id(), split_sections));
+            if relocatable
+                && matches!(
+                    symbol.st_bind(),
+                    elf::STB_GLOBAL | elf::STB_WEAK | elf::STB_GNU_UNIQUE
+                )
+                && !symbol.is_undefined()
+            {
+                continue;
             }
-
-            // The remaining sections have already been assigned an address.
-            // Recalcuate the file and address ranges for the segment.
-            // TODO: verify that the sections are contiguous. If not, try to slide the sections
-            // down in memory.
-            segment.recalculate_ranges(sections);
+            #[cfg(feature = "logging")]
+            info!("Stripping symbol {}", symbol.name);
+            symbol.delete = true;
+            self.modified = true;
         }
+        Ok(())
+    }
 
-        // Add new segments due to splitting.
-        for (segment_id, split_sections) in split_segments {
-            let segment = builder.segments.copy(segment_id);
-            for id in split_sections {
-                let section = builder.sections.get_mut(id);
-                segment.append_section(section);
+    /// Remove the contents of all sections except for debugging information.
+    ///
+    /// This is used to create a separate debug file. The contents of debug sections,
+    /// notes, and the symbol table are kept. All other sections are changed to
+    /// `SHT_NOBITS`, but their headers and sizes are preserved so that addresses
+    /// still match the original file.
+    ///
+    /// The dynamic symbols and symbol versions are also removed, since their
+    /// sections no longer have contents. Data that is not part of any section
+    /// is removed too.
+    pub fn elf_only_keep_debug(&mut self) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Err(Error::modify("Not an ELF file"));
+        };
+
+        let is_debug = |name: &[u8]| name.starts_with(b".debug") || name.starts_with(b".zdebug");
+        let debug_sections: Vec<build::elf::SectionId> = builder
+            .sections
+            .iter()
+            .filter(|section| is_debug(&section.name))
+            .map(|section| section.id())
+            .collect();
+        for section in &mut builder.sections {
+            match &section.data {
+                build::elf::SectionData::Data(_) | build::elf::SectionData::Attributes(_) => {
+                    if is_debug(&section.name) {
+                        continue;
+                    }
+                }
+                build::elf::SectionData::Relocation(_) => {
+                    if debug_sections
+                        .iter()
+                        .any(|id| section.sh_info_section == Some(*id))
+                    {
+                        continue;
+                    }
+                }
+                build::elf::SectionData::DynamicRelocation(_)
+                | build::elf::SectionData::Dynamic(_)
+                | build::elf::SectionData::DynamicSymbol
+                | build::elf::SectionData::DynamicString
+                | build::elf::SectionData::Hash
+                | build::elf::SectionData::GnuHash
+                | build::elf::SectionData::GnuVersym
+                | build::elf::SectionData::GnuVerdef
+                | build::elf::SectionData::GnuVerneed => {}
+                build::elf::SectionData::UninitializedData(_)
+                | build::elf::SectionData::Note(_)
+                | build::elf::SectionData::SectionString
+                | build::elf::SectionData::Symbol
+                | build::elf::SectionData::SymbolSectionIndex
+                | build::elf::SectionData::String => continue,
             }
             #[cfg(feature = "logging")]
-            info!(
-                "Split segment with type {:x}, offset {:x}, addr {:x}, size {:x}",
-                segment.p_type, segment.p_offset, segment.p_vaddr, segment.p_memsz,
-            );
+            info!("Removing contents of section {}", section.name);
+            section.sh_type = elf::SHT_NOBITS;
+            section.data = build::elf::SectionData::UninitializedData(section.sh_size);
+        }
+        for symbol in &mut builder.dynamic_symbols {
+            symbol.delete = true;
+        }
+        for version in &mut builder.versions {
+            version.delete = true;
         }
+        for file in &mut builder.version_files {
+            file.delete = true;
+        }
+        builder.version_base = None;
+        builder.padding.clear();
+        builder.overlay = Default::default();
+        self.modified = true;
+        Ok(())
+    }
 
-        // Update the PT_PHDR segment to include the new program headers.
-        let size = builder.program_headers_size() as u64;
-        for segment in &mut builder.segments {
-            if segment.p_type != elf::PT_PHDR {
-                continue;
-            }
-            segment.p_filesz = size;
-            segment.p_memsz = size;
+    /// Add a `.gnu_debuglink` section.
+    ///
+    /// `filename` is the name of the separate debug file, without any directory
+    /// components. `crc` is the CRC-32 of the contents of the debug file.
+    ///
+    /// Returns an error if the file already has a `.gnu_debuglink` section.
+    pub fn elf_add_gnu_debuglink(&mut self, filename: &[u8], crc: u32) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Err(Error::modify("Not an ELF file"));
+        };
+        if builder
+            .sections
+            .iter()
+            .any(|section| section.name.as_slice() == b".gnu_debuglink")
+        {
+            return Err(Error::modify("Section .gnu_debuglink already exists"));
         }
-        return Ok(());
+
+        let mut data = filename.to_vec();
+        data.push(0);
+        data.resize((data.len() + 3) & !3, 0);
+        data.extend_from_slice(&u32_bytes(builder.endian, crc));
+
+        let section = builder.sections.add();
+        section.name = b".gnu_debuglink"[..].into();
+        section.sh_type = elf::SHT_PROGBITS;
+        section.sh_addralign = 4;
+        section.data = build::elf::SectionData::Data(data.into());
+        #[cfg(feature = "logging")]
+        info!(
+            "Adding section .gnu_debuglink for {}",
+            String::from_utf8_lossy(filename)
+        );
+        self.modified = true;
+        Ok(())
     }
-}
 
-fn find_move_sections(
-    builder: &build::elf::Builder,
-    added_segments: usize,
-) -> Result<Vec<build::elf::SectionId>> {
-    use build::elf::SectionData;
-
-    let mut move_sections = Vec::new();
-    let mut blocks = Vec::new();
-    let file_header_size = builder.file_header_size() as u64;
-    let program_headers_size = (builder.program_headers_size()
-        + added_segments * builder.class().program_header_size())
-        as u64;
-    let interp = builder.interp_section();
-
-    if let Some(segment) = builder.segments.find_load_segment_from_offset(0) {
-        let address = segment.address_from_offset(0);
-        blocks.push(Block {
-            name: "file header".into(),
-            kind: BlockKind::FileHeader,
-            address,
-            size: file_header_size,
-            move_priority: 0,
-        });
+    /// Remove unused space between segments in the file.
+    ///
+    /// This places any sections that need it, and then moves `PT_LOAD` segments to
+    /// lower file offsets, while preserving the alignment of their offsets relative
+    /// to their addresses. See [`build::elf::Builder::compact_segments`].
+    ///
+    /// This should be called after any other modifications, since they may require
+    /// sections to be placed again.
+    pub fn elf_compact(&mut self) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Err(Error::modify("Not an ELF file"));
+        };
+        builder
+            .place_sections()
+            .map_err(|e| Error::modify(e.to_string()))?;
+        #[cfg(feature = "logging")]
+        info!("Compacting segments");
+        builder.compact_segments();
+        self.modified = true;
+        Ok(())
     }
-    if let Some(segment) = builder
-        .segments
-        .find_load_segment_from_offset(builder.header.e_phoff)
-    {
-        let address = segment.address_from_offset(builder.header.e_phoff);
-        blocks.push(Block {
-            name: "program headers".into(),
-            kind: BlockKind::ProgramHeaders,
-            address,
-            size: program_headers_size,
-            move_priority: 0,
-        });
+
+    /// Delete data that is not part of any section, segment, or header.
+    ///
+    /// By default, data within segments that is not part of any section, and
+    /// data that has been appended to the end of the file, are preserved.
+    /// This fills the space within segments with zeros instead, and removes the
+    /// appended data.
+    ///
+    /// See [`build::elf::Builder::padding`] and [`build::elf::Builder::overlay`].
+    pub fn elf_delete_extra_data(&mut self) -> Result<()> {
+        let Some(builder) = self.format.elf_mut() else {
+            return Err(Error::modify("Not an ELF file"));
+        };
+        #[cfg(feature = "logging")]
+        info!("Deleting data that is not part of any section");
+        builder.padding.clear();
+        builder.overlay = Default::default();
+        self.modified = true;
+        Ok(())
     }
-    for segment in &builder.segments {
-        if segment.p_type != elf::PT_LOAD {
-            continue;
+
+    /// Write the contents of the loadable sections as a flat binary.
+    ///
+    /// The ELF file is written first so that the sections have their final
+    /// layout and contents.
+    pub(crate) fn elf_write_binary<W: std::io::Write>(self, mut w: W) -> Result<()> {
+        let Format::Elf(builder) = self.format else {
+            return Err(Error::modify(
+                "Binary output is only supported for ELF files",
+            ));
+        };
+        let is_64 = builder.is_64;
+        let mut data = Vec::new();
+        builder.write(&mut data).map_err(Error::write)?;
+        let mut sections = if is_64 {
+            binary_sections::<elf::FileHeader64<object::Endianness>>(&data)
+        } else {
+            binary_sections::<elf::FileHeader32<object::Endianness>>(&data)
         }
-        // Add zero-sized blocks at the start and end of the segment
-        // to prevent changing the segment address or size.
-        blocks.push(Block {
-            name: "segment start".into(),
-            kind: BlockKind::Segment,
-            address: segment.p_vaddr,
-            size: 0,
-            move_priority: 0,
-        });
-        blocks.push(Block {
-            name: "segment end".into(),
-            kind: BlockKind::Segment,
-            address: segment.p_vaddr + segment.p_memsz,
-            size: 0,
-            move_priority: 0,
-        });
+        .map_err(|e| Error::write(e.into()))?;
+
+        sections.sort_by_key(|(address, _)| *address);
+        let Some(mut address) = sections.first().map(|(address, _)| *address) else {
+            return Ok(());
+        };
+        let zeros = [0; 4096];
+        for (section_address, section_data) in sections {
+            if section_address < address {
+                return Err(Error::modify(format!(
+                    "Section at load address 0x{:x} overlaps the previous section",
+                    section_address
+                )));
+            }
+            let mut gap = section_address - address;
+            while gap > 0 {
+                let len = gap.min(zeros.len() as u64) as usize;
+                w.write_all(&zeros[..len]).map_err(Error::io)?;
+                gap -= len as u64;
+            }
+            w.write_all(section_data).map_err(Error::io)?;
+            address = section_address + section_data.len() as u64;
+        }
+        Ok(())
     }
-    for section in &builder.sections {
-        if !section.is_alloc() {
-            continue;
+
+    pub(crate) fn elf_finalize(&mut self) -> Result<()> {
+        if let (true, Some(builder)) = (self.modified, self.format.elf_mut()) {
+            builder
+                .place_sections()
+                .map_err(|e| Error::modify(e.to_string()))?;
         }
-        if section.sh_offset == 0 {
-            // Newly added section that needs to be assigned to a segment,
-            // or a section that has already been flagged for moving.
-            move_sections.push(section.id());
+        Ok(())
+    }
+}
+
+/// Find the load address and contents of the sections to write for a flat binary.
+///
+/// These are the allocated sections that have contents in the file. The load
+/// address is calculated from the physical address of the containing `PT_LOAD`
+/// segment, or is the section address if there is no such segment.
+fn binary_sections<Elf: object::read::elf::FileHeader<Endian = object::Endianness>>(
+    data: &[u8],
+) -> object::read::Result<Vec<(u64, &[u8])>> {
+    use object::read::elf::{ProgramHeader, SectionHeader};
+
+    let header = Elf::parse(data)?;
+    let endian = header.endian()?;
+    let segments = header.program_headers(endian, data)?;
+    let sections = header.sections(endian, data)?;
+    let mut result = Vec::new();
+    for section in sections.iter() {
+        if section.sh_flags(endian).into() & u64::from(elf::SHF_ALLOC) == 0
+            || section.sh_type(endian) == elf::SHT_NOBITS
+        {
             continue;
         }
-        if section.sh_type == elf::SHT_NOBITS && section.sh_flags & u64::from(elf::SHF_TLS) != 0 {
-            // Uninitialized TLS sections are not part of the address space.
+        let section_data = section.data(endian, data)?;
+        if section_data.is_empty() {
             continue;
         }
-        let move_priority = match &section.data {
-            // Can't move sections whose address may referenced from
-            // a section that we can't rewrite.
-            SectionData::Data(_) => {
-                if Some(section.id()) == interp {
-                    1
-                } else {
-                    0
-                }
-            }
-            SectionData::UninitializedData(_) | SectionData::Dynamic(_) => 0,
-            // TODO: Can be referenced by dynamic entries, but we don't support that yet.
-            SectionData::DynamicRelocation(_) => 0,
-            // None of these can be referenced by address that I am aware of.
-            SectionData::Relocation(_)
-            | SectionData::Note(_)
-            | SectionData::Attributes(_)
-            | SectionData::SectionString
-            | SectionData::Symbol
-            | SectionData::SymbolSectionIndex
-            | SectionData::String
-            | SectionData::DynamicSymbol
-            | SectionData::DynamicString
-            | SectionData::Hash
-            | SectionData::GnuHash
-            | SectionData::GnuVersym
-            | SectionData::GnuVerdef
-            | SectionData::GnuVerneed => 2,
-        };
-        blocks.push(Block {
-            name: (*section.name).into(),
-            kind: BlockKind::Section(section.id()),
-            address: section.sh_addr,
-            size: section.sh_size,
-            move_priority,
+        let offset = section.sh_offset(endian).into();
+        let size = section_data.len() as u64;
+        let address = segments
+            .iter()
+            .find(|segment| {
+                let p_offset = segment.p_offset(endian).into();
+                segment.p_type(endian) == elf::PT_LOAD
+                    && offset >= p_offset
+                    && offset - p_offset + size <= segment.p_filesz(endian).into()
+            })
+            .map(|segment| {
+                segment.p_paddr(endian).into() + (offset - segment.p_offset(endian).into())
+            })
+            .unwrap_or_else(|| section.sh_addr(endian).into());
+        result.push((address, section_data));
+    }
+    Ok(result)
+}
+
+/// A note in the data of an ELF note section.
+struct Note<'a> {
+    name: &'a [u8],
+    n_type: u32,
+    /// The range of the note in the section data, including padding.
+    range: core::ops::Range<usize>,
+}
+
+/// Return the alignment of the notes in a section.
+fn note_align(sh_addralign: u64) -> usize {
+    if sh_addralign == 8 {
+        8
+    } else {
+        4
+    }
+}
+
+/// Parse the notes in the data for an ELF note section.
+///
+/// Returns `None` if the data is invalid.
+fn parse_notes(data: &[u8], endian: object::Endianness, align: usize) -> Option<Vec<Note<'_>>> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let bytes = data.get(offset..)?.get(..4)?;
+        Some(endian.read_u32_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+    let align_up = |offset: usize| offset.checked_add(align - 1).map(|x| x & !(align - 1));
+    let mut notes = Vec::new();
+    let mut offset = 0;
+    while offset + 12 <= data.len() {
+        let namesz = read_u32(offset)? as usize;
+        let descsz = read_u32(offset + 4)? as usize;
+        let n_type = read_u32(offset + 8)?;
+        let name_offset = offset + 12;
+        let name = data.get(name_offset..)?.get(..namesz)?;
+        let desc_offset = align_up(name_offset + namesz)?;
+        let desc_end = desc_offset.checked_add(descsz)?;
+        if desc_end > data.len() {
+            return None;
+        }
+        let end = align_up(desc_end)?.min(data.len());
+        notes.push(Note {
+            name: name.strip_suffix(&[0]).unwrap_or(name),
+            n_type,
+            range: offset..end,
         });
+        offset = end;
     }
-    blocks.sort_by_key(|block| (block.address, block.size));
+    Some(notes)
+}
 
-    // For each pair of overlapping blocks, decide which one to move.
-    let mut i = 0;
-    while i + 1 < blocks.len() {
-        let end_address = blocks[i].address + blocks[i].size;
-        if end_address <= blocks[i + 1].address {
-            i += 1;
-            continue;
+fn u32_bytes(endian: object::Endianness, value: u32) -> [u8; 4] {
+    match endian {
+        object::Endianness::Little => value.to_le_bytes(),
+        object::Endianness::Big => value.to_be_bytes(),
+    }
+}
+
+/// A change to the binding of ELF symbols.
+#[derive(Clone, Copy)]
+enum BindingChange<'a> {
+    /// Make the named symbols local.
+    Localize(&'a NameSet<'a>),
+    /// Make all symbols local except for the named symbols.
+    KeepGlobal(&'a NameSet<'a>),
+    /// Make the named symbols global.
+    Globalize(&'a NameSet<'a>),
+    /// Make the named symbols weak.
+    Weaken(&'a NameSet<'a>),
+}
+
+impl BindingChange<'_> {
+    /// Apply the change to a symbol.
+    ///
+    /// Returns true if the symbol was modified.
+    fn apply<const DYNAMIC: bool>(&self, symbol: &mut build::elf::Symbol<'_, DYNAMIC>) -> bool {
+        if symbol.name.is_empty()
+            || symbol.st_type() == elf::STT_SECTION
+            || symbol.st_type() == elf::STT_FILE
+        {
+            return false;
         }
-        // Prefer moving the earlier block, since it is the reason for the overlap.
-        if blocks[i].move_priority >= blocks[i + 1].move_priority {
-            if blocks[i].move_priority == 0 {
+        let bind = symbol.st_bind();
+        match *self {
+            BindingChange::Localize(names) => {
+                if symbol.is_undefined() || bind == elf::STB_LOCAL || !names.contains(&symbol.name)
+                {
+                    return false;
+                }
                 #[cfg(feature = "logging")]
-                info!(
-                    "Immovable {} (end address {:x}) overlaps immovable {} (start address {:x})",
-                    blocks[i].name,
-                    end_address,
-                    blocks[i + 1].name,
-                    blocks[i + 1].address,
-                );
-                return Err(Error::modify("Overlapping immovable sections"));
+                info!("Localizing symbol {}", symbol.name);
+                symbol.set_st_bind(elf::STB_LOCAL);
             }
-            #[cfg(feature = "logging")]
-            info!(
-                "Need to move {} (end address {:x}) because of {} (start address {:x})",
-                blocks[i].name,
-                end_address,
-                blocks[i + 1].name,
-                blocks[i + 1].address,
-            );
-            if let BlockKind::Section(section) = blocks[i].kind {
-                move_sections.push(section);
-                blocks.remove(i);
-            } else {
-                // Only sections can be moved.
-                unreachable!();
+            BindingChange::KeepGlobal(names) => {
+                if symbol.is_undefined() || bind == elf::STB_LOCAL || names.contains(&symbol.name) {
+                    return false;
+                }
+                #[cfg(feature = "logging")]
+                info!("Localizing symbol {}", symbol.name);
+                symbol.set_st_bind(elf::STB_LOCAL);
             }
-        } else {
-            #[cfg(feature = "logging")]
-            info!(
-                "Need to move {} (start address {:x}) because of {} (end address {:x})",
-                blocks[i + 1].name,
-                blocks[i + 1].address,
-                blocks[i].name,
-                end_address,
-            );
-            if let BlockKind::Section(section) = blocks[i + 1].kind {
-                move_sections.push(section);
-                blocks.remove(i + 1);
-            } else {
-                // Only sections can be moved.
-                unreachable!();
+            BindingChange::Globalize(names) => {
+                if symbol.is_undefined() || bind != elf::STB_LOCAL || !names.contains(&symbol.name)
+                {
+                    return false;
+                }
+                #[cfg(feature = "logging")]
+                info!("Globalizing symbol {}", symbol.name);
+                symbol.set_st_bind(elf::STB_GLOBAL);
+                // Hidden symbols would be made local again by the linker.
+                if matches!(symbol.st_visibility(), elf::STV_HIDDEN | elf::STV_INTERNAL) {
+                    symbol.set_st_visibility(elf::STV_DEFAULT);
+                }
+            }
+            BindingChange::Weaken(names) => {
+                if bind != elf::STB_GLOBAL || !names.contains(&symbol.name) {
+                    return false;
+                }
+                #[cfg(feature = "logging")]
+                info!("Weakening symbol {}", symbol.name);
+                symbol.set_st_bind(elf::STB_WEAK);
             }
         }
+        true
     }
-    Ok(move_sections)
 }
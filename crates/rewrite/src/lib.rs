@@ -4,8 +4,8 @@
 //! Modifications can be performed using methods on the [`Rewriter`] struct, or
 //! by passing an [`Options`] struct to the [`Rewriter::modify`] method.
 //!
-//! Currently, only ELF files are supported, and not many modifications are
-//! possible yet.
+//! Currently, only ELF, Mach-O and PE files are supported, and not many modifications
+//! are possible yet.
 //!
 //! # Example
 //! ```no_run
@@ -30,8 +30,20 @@
 mod error;
 pub use error::{Error, ErrorKind, Result};
 
+mod names;
+pub use names::NameMatching;
+
+mod report;
+pub use report::{Report, SectionChange, SectionLayout, SymbolChange, SymbolInfo};
+
 mod rewriter;
-pub use rewriter::{Options, Rewriter};
+pub use rewriter::{NewSection, Options, OutputFormat, Rewriter};
 
 mod elf;
-pub use elf::ElfOptions;
+pub use elf::{ElfNote, ElfOptions};
+
+mod macho;
+pub use macho::MachOptions;
+
+mod pe;
+pub use pe::PeOptions;
@@ -0,0 +1,1051 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+#[cfg(feature = "logging")]
+use log::info;
+use object::endian::{BigEndian as BE, Endianness, U32};
+use object::read::macho::{LoadCommandVariant, MachHeader, Nlist, Segment};
+use object::{build, macho, pod};
+
+use super::{Error, Result, Rewriter};
+
+/// Options for modifying a Mach-O file.
+///
+/// This struct contains options for modifying a Mach-O file. It is
+/// contained in the [`Options`](super::Options) struct.
+///
+/// Options are listed in the order they are processed.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct MachOptions {
+    /// Set the install name in the `LC_ID_DYLIB` load command.
+    ///
+    /// See [`Rewriter::macho_set_id`].
+    pub set_id: Option<Vec<u8>>,
+    /// Delete `LC_RPATH` load commands.
+    ///
+    /// See [`Rewriter::macho_delete_rpath`].
+    pub delete_rpath: HashSet<Vec<u8>>,
+    /// Replace the paths in `LC_RPATH` load commands.
+    ///
+    /// See [`Rewriter::macho_replace_rpath`].
+    pub replace_rpath: HashMap<Vec<u8>, Vec<u8>>,
+    /// Add `LC_RPATH` load commands.
+    ///
+    /// See [`Rewriter::macho_add_rpath`].
+    pub add_rpath: Vec<Vec<u8>>,
+    /// Replace the paths of dependent dylibs.
+    ///
+    /// See [`Rewriter::macho_replace_dylib`].
+    pub replace_dylib: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Rewriter<'_> {
+    pub(crate) fn macho_modify(&mut self, options: MachOptions) -> Result<()> {
+        if let Some(id) = options.set_id {
+            self.macho_set_id(id)?;
+        }
+        if !options.delete_rpath.is_empty() {
+            self.macho_delete_rpath(&options.delete_rpath)?;
+        }
+        if !options.replace_rpath.is_empty() {
+            self.macho_replace_rpath(&options.replace_rpath)?;
+        }
+        if !options.add_rpath.is_empty() {
+            self.macho_add_rpath(&options.add_rpath)?;
+        }
+        if !options.replace_dylib.is_empty() {
+            self.macho_replace_dylib(&options.replace_dylib)?;
+        }
+        Ok(())
+    }
+
+    /// Delete symbols from the symbol table.
+    ///
+    /// It is an error when writing the file if a deleted symbol is referenced
+    /// by a relocation or by the indirect symbol table.
+    ///
+    /// This does not modify the dyld binding information or export trie.
+    pub fn macho_delete_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        let Some(file) = self.format.macho_mut() else {
+            return Ok(());
+        };
+        for symbol in &mut file.symbols {
+            if !symbol.delete && names.contains(&symbol.name) {
+                #[cfg(feature = "logging")]
+                info!("Deleting symbol {}", symbol.name);
+                symbol.delete = true;
+                file.symbols_modified = true;
+                self.modified = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rename symbols in the symbol table.
+    ///
+    /// The `names` map is from old names to new names.
+    ///
+    /// This does not modify the dyld binding information or export trie.
+    pub fn macho_rename_symbols(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let names = self.name_map(names)?;
+        let Some(file) = self.format.macho_mut() else {
+            return Ok(());
+        };
+        for symbol in &mut file.symbols {
+            if let Some(name) = names.get(&symbol.name) {
+                let name = name.clone().into();
+                #[cfg(feature = "logging")]
+                info!("Renaming symbol {} to {}", symbol.name, name);
+                symbol.name = name;
+                file.symbols_modified = true;
+                self.modified = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the install name in the `LC_ID_DYLIB` load command.
+    pub fn macho_id(&self) -> Option<&[u8]> {
+        let file = self.format.macho()?;
+        file.commands.iter().find_map(|command| match command {
+            LoadCommand::Dylib { cmd, name, .. } if *cmd == macho::LC_ID_DYLIB => {
+                Some(name.as_slice())
+            }
+            _ => None,
+        })
+    }
+
+    /// Set the install name in the `LC_ID_DYLIB` load command.
+    pub fn macho_set_id(&mut self, id: Vec<u8>) -> Result<()> {
+        let file = self
+            .format
+            .macho_mut()
+            .ok_or_else(|| Error::modify("Not a Mach-O file; can't set install name"))?;
+        let name = file
+            .commands
+            .iter_mut()
+            .find_map(|command| match command {
+                LoadCommand::Dylib { cmd, name, .. } if *cmd == macho::LC_ID_DYLIB => Some(name),
+                _ => None,
+            })
+            .ok_or_else(|| Error::modify("No LC_ID_DYLIB command found; can't set install name"))?;
+        *name = id.into();
+        #[cfg(feature = "logging")]
+        info!("Setting install name to {}", name);
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Find the paths in the `LC_RPATH` load commands.
+    pub fn macho_rpaths(&self) -> impl Iterator<Item = &[u8]> {
+        let commands = self.format.macho().map(|file| &file.commands[..]);
+        commands
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|command| match command {
+                LoadCommand::Rpath { path } => Some(path.as_slice()),
+                _ => None,
+            })
+    }
+
+    /// Delete `LC_RPATH` load commands.
+    pub fn macho_delete_rpath(&mut self, paths: &HashSet<Vec<u8>>) -> Result<()> {
+        let file = self
+            .format
+            .macho_mut()
+            .ok_or_else(|| Error::modify("Not a Mach-O file; can't delete rpath"))?;
+        let mut modified = false;
+        file.commands.retain(|command| {
+            let LoadCommand::Rpath { path } = command else {
+                return true;
+            };
+            if !paths.contains(path.as_slice()) {
+                return true;
+            }
+
+            #[cfg(feature = "logging")]
+            info!("Deleting LC_RPATH command {}", path);
+            modified = true;
+            false
+        });
+        if modified {
+            self.modified = true;
+        }
+        Ok(())
+    }
+
+    /// Replace the paths in `LC_RPATH` load commands.
+    ///
+    /// The `paths` map is from old paths to new paths.
+    pub fn macho_replace_rpath(&mut self, paths: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let file = self
+            .format
+            .macho_mut()
+            .ok_or_else(|| Error::modify("Not a Mach-O file; can't replace rpath"))?;
+        for command in &mut file.commands {
+            let LoadCommand::Rpath { path } = command else {
+                continue;
+            };
+            let Some(new_path) = paths.get(path.as_slice()) else {
+                continue;
+            };
+
+            let new_path = new_path.clone().into();
+            #[cfg(feature = "logging")]
+            info!("Replacing LC_RPATH command {} with {}", path, new_path);
+            *path = new_path;
+            self.modified = true;
+        }
+        Ok(())
+    }
+
+    /// Add `LC_RPATH` load commands after any existing `LC_RPATH` load commands.
+    ///
+    /// This does not add a path if it is already present.
+    pub fn macho_add_rpath(&mut self, paths: &[Vec<u8>]) -> Result<()> {
+        let file = self
+            .format
+            .macho_mut()
+            .ok_or_else(|| Error::modify("Not a Mach-O file; can't add rpath"))?;
+        let mut found = HashSet::new();
+        let mut index = file.commands.len();
+        for (i, command) in file.commands.iter().enumerate() {
+            if let LoadCommand::Rpath { path } = command {
+                found.insert(path.clone());
+                index = i + 1;
+            }
+        }
+        for path in paths {
+            if !found.insert(path.clone().into()) {
+                continue;
+            }
+            let path = path.clone().into();
+            #[cfg(feature = "logging")]
+            info!("Adding LC_RPATH command {}", path);
+            file.commands.insert(index, LoadCommand::Rpath { path });
+            index += 1;
+            self.modified = true;
+        }
+        Ok(())
+    }
+
+    /// Find the paths of the dependent dylibs.
+    ///
+    /// This includes the paths in `LC_LOAD_DYLIB`, `LC_LOAD_WEAK_DYLIB`,
+    /// `LC_REEXPORT_DYLIB`, `LC_LAZY_LOAD_DYLIB` and `LC_LOAD_UPWARD_DYLIB`
+    /// load commands.
+    pub fn macho_dylibs(&self) -> impl Iterator<Item = &[u8]> {
+        let commands = self.format.macho().map(|file| &file.commands[..]);
+        commands
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|command| match command {
+                LoadCommand::Dylib { cmd, name, .. } if *cmd != macho::LC_ID_DYLIB => {
+                    Some(name.as_slice())
+                }
+                _ => None,
+            })
+    }
+
+    /// Replace the paths of dependent dylibs.
+    ///
+    /// The `names` map is from old paths to new paths.
+    pub fn macho_replace_dylib(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let file = self
+            .format
+            .macho_mut()
+            .ok_or_else(|| Error::modify("Not a Mach-O file; can't replace dylib"))?;
+        for command in &mut file.commands {
+            let LoadCommand::Dylib { cmd, name, .. } = command else {
+                continue;
+            };
+            if *cmd == macho::LC_ID_DYLIB {
+                continue;
+            }
+            let Some(new_name) = names.get(name.as_slice()) else {
+                continue;
+            };
+
+            let new_name = new_name.clone().into();
+            #[cfg(feature = "logging")]
+            info!("Replacing dylib {} with {}", name, new_name);
+            *name = new_name;
+            self.modified = true;
+        }
+        Ok(())
+    }
+}
+
+/// A Mach-O file that is being rewritten.
+///
+/// Only the load commands and the symbol table can be modified. All other
+/// data is copied from the original file.
+#[derive(Debug)]
+pub(crate) struct MachOFile<'data> {
+    data: &'data [u8],
+    endian: Endianness,
+    is_64: bool,
+    cputype: u32,
+    filetype: u32,
+    header_size: usize,
+    commands_size: usize,
+    commands: Vec<LoadCommand<'data>>,
+    symbols: Vec<Symbol<'data>>,
+    symbols_modified: bool,
+}
+
+#[derive(Debug)]
+enum LoadCommand<'data> {
+    /// `LC_ID_DYLIB` or a load command for a dependent dylib.
+    Dylib {
+        cmd: u32,
+        name: build::ByteString<'data>,
+        timestamp: u32,
+        current_version: u32,
+        compatibility_version: u32,
+    },
+    /// `LC_RPATH`
+    Rpath { path: build::ByteString<'data> },
+    /// Any other load command, including the command header.
+    Other { data: &'data [u8] },
+}
+
+#[derive(Debug)]
+struct Symbol<'data> {
+    name: build::ByteString<'data>,
+    delete: bool,
+}
+
+/// The fields of an existing code signature that are needed to replace it.
+#[derive(Debug)]
+struct CodeSignature {
+    identifier: Vec<u8>,
+    exec_seg_base: u64,
+    exec_seg_limit: u64,
+    exec_seg_flags: u64,
+}
+
+impl CodeSignature {
+    fn ad_hoc(&self) -> object::write::macho::CodeSignature<'_> {
+        object::write::macho::CodeSignature {
+            identifier: &self.identifier,
+            exec_seg_base: self.exec_seg_base,
+            exec_seg_limit: self.exec_seg_limit,
+            exec_seg_flags: self.exec_seg_flags,
+        }
+    }
+}
+
+impl<'data> MachOFile<'data> {
+    /// Read a Mach-O file.
+    pub(crate) fn read<Mach: MachHeader<Endian = Endianness>>(
+        data: &'data [u8],
+    ) -> build::Result<Self> {
+        let header = Mach::parse(data, 0)?;
+        let endian = header.endian()?;
+        let mut commands = Vec::new();
+        let mut symbols = Vec::new();
+        let mut iter = header.load_commands(endian, data, 0)?;
+        while let Some(command) = iter.next()? {
+            let cmd = command.cmd();
+            let command = match command.variant()? {
+                LoadCommandVariant::IdDylib(dylib) | LoadCommandVariant::Dylib(dylib) => {
+                    LoadCommand::Dylib {
+                        cmd,
+                        name: command.string(endian, dylib.dylib.name)?.into(),
+                        timestamp: dylib.dylib.timestamp.get(endian),
+                        current_version: dylib.dylib.current_version.get(endian),
+                        compatibility_version: dylib.dylib.compatibility_version.get(endian),
+                    }
+                }
+                LoadCommandVariant::Rpath(rpath) => LoadCommand::Rpath {
+                    path: command.string(endian, rpath.path)?.into(),
+                },
+                variant => {
+                    match variant {
+                        LoadCommandVariant::Symtab(symtab) => {
+                            let table = symtab.symbols::<Mach, _>(endian, data)?;
+                            let strings = table.strings();
+                            for nlist in table.iter() {
+                                symbols.push(Symbol {
+                                    name: nlist.name(endian, strings)?.into(),
+                                    delete: false,
+                                });
+                            }
+                        }
+                        // Check the section headers are valid so that they can be
+                        // modified later.
+                        LoadCommandVariant::Segment32(segment, section_data) => {
+                            segment.sections(endian, section_data)?;
+                        }
+                        LoadCommandVariant::Segment64(segment, section_data) => {
+                            segment.sections(endian, section_data)?;
+                        }
+                        _ => {}
+                    }
+                    LoadCommand::Other {
+                        data: command.raw_data(),
+                    }
+                }
+            };
+            commands.push(command);
+        }
+        Ok(MachOFile {
+            data,
+            endian,
+            is_64: header.is_type_64(),
+            cputype: header.cputype(endian),
+            filetype: header.filetype(endian),
+            header_size: mem::size_of::<Mach>(),
+            commands_size: header.sizeofcmds(endian) as usize,
+            commands,
+            symbols,
+            symbols_modified: false,
+        })
+    }
+
+    /// Return the original file data.
+    pub(crate) fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    fn pointer_align(&self) -> usize {
+        if self.is_64 {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn page_size(&self) -> u64 {
+        if self.cputype == macho::CPU_TYPE_ARM64 {
+            0x4000
+        } else {
+            0x1000
+        }
+    }
+
+    /// Write the modified file.
+    ///
+    /// The load commands are written in place of the original load commands.
+    /// If they no longer fit before the first section, then the file data is
+    /// moved for object files, and an error is returned for other files.
+    ///
+    /// If the symbol table was modified, then the string table is rewritten,
+    /// and is moved to the end of the `__LINKEDIT` segment if it no longer fits.
+    ///
+    /// If the file contains a code signature, then it is replaced with an
+    /// ad hoc signature.
+    pub(crate) fn write(&self) -> Result<Vec<u8>> {
+        let endian = self.endian;
+        let mut commands = self
+            .commands
+            .iter()
+            .map(|command| self.command_data(command))
+            .collect::<Vec<_>>();
+        let mut out = self.data.to_vec();
+
+        // The signature is recalculated after everything else,
+        // so remove the existing one.
+        let mut signature = None;
+        for (index, data) in commands.iter().enumerate() {
+            if command_cmd(endian, data) != macho::LC_CODE_SIGNATURE {
+                continue;
+            }
+            let (command, _) = pod::from_bytes::<macho::LinkeditDataCommand<Endianness>>(data)
+                .map_err(|()| Error::modify("Invalid LC_CODE_SIGNATURE command"))?;
+            let offset = command.dataoff.get(endian) as usize;
+            let size = command.datasize.get(endian) as usize;
+            signature = Some((index, self.code_signature(offset, size)?));
+            out.truncate(offset);
+        }
+
+        if self.symbols_modified {
+            self.write_symbols(&mut commands, &mut out)?;
+        }
+
+        // Find the space that is available for the load commands.
+        let commands_size = commands.iter().map(Vec::len).sum::<usize>();
+        let commands_end = self.header_size + commands_size;
+        let mut data_start = out.len();
+        for data in &mut commands {
+            visit_offsets(endian, data, &mut |offset| {
+                data_start = data_start.min(offset as usize);
+                offset
+            });
+        }
+        if commands_end > data_start {
+            if self.filetype != macho::MH_OBJECT {
+                return Err(Error::modify(format!(
+                    "Not enough space for load commands: need {:#x} bytes, but only {:#x} bytes are available",
+                    commands_size,
+                    data_start.saturating_sub(self.header_size)
+                )));
+            }
+            // Object files are not mapped, so all data can be moved.
+            let delta = align(
+                commands_end - data_start,
+                self.max_section_align(&mut commands),
+            );
+            #[cfg(feature = "logging")]
+            info!("Moving file data by {:#x} bytes for load commands", delta);
+            out.splice(data_start..data_start, vec![0; delta]);
+            for data in &mut commands {
+                visit_offsets(endian, data, &mut |offset| {
+                    if offset >= data_start as u64 {
+                        offset + delta as u64
+                    } else {
+                        offset
+                    }
+                });
+            }
+            data_start += delta;
+        }
+
+        // Reserve the space for the new signature at the end of the file.
+        if let Some((index, signature)) = &signature {
+            let code_limit = align(out.len(), 16);
+            let size = signature.ad_hoc().size(code_limit);
+            out.resize(code_limit + size, 0);
+            let (command, _) = pod::from_bytes_mut::<macho::LinkeditDataCommand<Endianness>>(
+                &mut commands[*index],
+            )
+            .map_err(|()| Error::modify("Invalid LC_CODE_SIGNATURE command"))?;
+            command.dataoff.set(endian, code_limit as u32);
+            command.datasize.set(endian, size as u32);
+            self.set_linkedit_end(&mut commands, out.len() as u64);
+        }
+
+        // Write the header and load commands.
+        out[16..20].copy_from_slice(pod::bytes_of(&U32::new(endian, commands.len() as u32)));
+        out[20..24].copy_from_slice(pod::bytes_of(&U32::new(endian, commands_size as u32)));
+        let mut offset = self.header_size;
+        for data in &commands {
+            out[offset..][..data.len()].copy_from_slice(data);
+            offset += data.len();
+        }
+        let old_commands_end = (self.header_size + self.commands_size).min(data_start);
+        if offset < old_commands_end {
+            out[offset..old_commands_end].fill(0);
+        }
+
+        if let Some((index, signature)) = &signature {
+            let (command, _) =
+                pod::from_bytes::<macho::LinkeditDataCommand<Endianness>>(&commands[*index])
+                    .map_err(|()| Error::modify("Invalid LC_CODE_SIGNATURE command"))?;
+            #[cfg(feature = "logging")]
+            info!("Replacing code signature with ad hoc signature");
+            signature
+                .ad_hoc()
+                .sign(&mut out, command.dataoff.get(endian) as usize)
+                .map_err(|e| Error::modify(e.to_string()))?;
+        }
+        Ok(out)
+    }
+
+    /// Return the data for a load command, including the command header.
+    fn command_data(&self, command: &LoadCommand<'_>) -> Vec<u8> {
+        let endian = self.endian;
+        let mut data = Vec::new();
+        match command {
+            LoadCommand::Dylib {
+                cmd,
+                name,
+                timestamp,
+                current_version,
+                compatibility_version,
+            } => {
+                let size = mem::size_of::<macho::DylibCommand<Endianness>>();
+                let cmdsize = align(size + name.len() + 1, self.pointer_align());
+                data.extend_from_slice(pod::bytes_of(&macho::DylibCommand {
+                    cmd: U32::new(endian, *cmd),
+                    cmdsize: U32::new(endian, cmdsize as u32),
+                    dylib: macho::Dylib {
+                        name: macho::LcStr {
+                            offset: U32::new(endian, size as u32),
+                        },
+                        timestamp: U32::new(endian, *timestamp),
+                        current_version: U32::new(endian, *current_version),
+                        compatibility_version: U32::new(endian, *compatibility_version),
+                    },
+                }));
+                data.extend_from_slice(name);
+                data.resize(cmdsize, 0);
+            }
+            LoadCommand::Rpath { path } => {
+                let size = mem::size_of::<macho::RpathCommand<Endianness>>();
+                let cmdsize = align(size + path.len() + 1, self.pointer_align());
+                data.extend_from_slice(pod::bytes_of(&macho::RpathCommand {
+                    cmd: U32::new(endian, macho::LC_RPATH),
+                    cmdsize: U32::new(endian, cmdsize as u32),
+                    path: macho::LcStr {
+                        offset: U32::new(endian, size as u32),
+                    },
+                }));
+                data.extend_from_slice(path);
+                data.resize(cmdsize, 0);
+            }
+            LoadCommand::Other { data: command } => data.extend_from_slice(command),
+        }
+        data
+    }
+
+    /// Read the fields of an existing code signature.
+    fn code_signature(&self, offset: usize, size: usize) -> Result<CodeSignature> {
+        let invalid = || Error::modify("Invalid or unsupported code signature");
+        let blob = self
+            .data
+            .get(offset..)
+            .and_then(|data| data.get(..size))
+            .ok_or_else(invalid)?;
+        let (superblob, indices) =
+            pod::from_bytes::<macho::CsSuperBlob>(blob).map_err(|()| invalid())?;
+        if superblob.magic.get(BE) != macho::CSMAGIC_EMBEDDED_SIGNATURE {
+            return Err(invalid());
+        }
+        let (indices, _) =
+            pod::slice_from_bytes::<macho::CsBlobIndex>(indices, superblob.count.get(BE) as usize)
+                .map_err(|()| invalid())?;
+        let index = indices
+            .iter()
+            .find(|index| index.typ.get(BE) == macho::CSSLOT_CODEDIRECTORY)
+            .ok_or_else(invalid)?;
+        let directory = blob
+            .get(index.offset.get(BE) as usize..)
+            .ok_or_else(invalid)?;
+        let (cd, _) =
+            pod::from_bytes::<macho::CsCodeDirectory>(directory).map_err(|()| invalid())?;
+        let identifier = directory
+            .get(cd.ident_offset.get(BE) as usize..)
+            .and_then(|data| data.split(|byte| *byte == 0).next())
+            .ok_or_else(invalid)?;
+        let mut signature = CodeSignature {
+            identifier: identifier.to_vec(),
+            exec_seg_base: 0,
+            exec_seg_limit: 0,
+            exec_seg_flags: 0,
+        };
+        if cd.version.get(BE) >= macho::CS_SUPPORTSEXECSEG {
+            signature.exec_seg_base = cd.exec_seg_base.get(BE);
+            signature.exec_seg_limit = cd.exec_seg_limit.get(BE);
+            signature.exec_seg_flags = cd.exec_seg_flags.get(BE);
+        }
+        Ok(signature)
+    }
+
+    /// Write the modified symbol table and string table.
+    ///
+    /// Symbol indices in the dynamic symbol table, indirect symbol table,
+    /// and relocations are updated for any deleted symbols.
+    fn write_symbols(&self, commands: &mut [Vec<u8>], out: &mut Vec<u8>) -> Result<()> {
+        let endian = self.endian;
+        let Some(symtab_index) = commands
+            .iter()
+            .position(|data| command_cmd(endian, data) == macho::LC_SYMTAB)
+        else {
+            return Ok(());
+        };
+        let (symtab, _) =
+            pod::from_bytes::<macho::SymtabCommand<Endianness>>(&commands[symtab_index])
+                .map_err(|()| Error::modify("Invalid LC_SYMTAB command"))?;
+        let symoff = symtab.symoff.get(endian) as usize;
+        let nsyms = symtab.nsyms.get(endian) as usize;
+        let stroff = symtab.stroff.get(endian) as usize;
+        let strsize = symtab.strsize.get(endian) as usize;
+        let nlist_size = if self.is_64 {
+            mem::size_of::<macho::Nlist64<Endianness>>()
+        } else {
+            mem::size_of::<macho::Nlist32<Endianness>>()
+        };
+
+        // Map from old symbol indices to new symbol indices.
+        let mut map = Vec::with_capacity(self.symbols.len());
+        let mut count = 0;
+        for symbol in &self.symbols {
+            if symbol.delete {
+                map.push(None);
+            } else {
+                map.push(Some(count));
+                count += 1;
+            }
+        }
+        if count != self.symbols.len() {
+            self.remap_symbols(&map, commands, out)?;
+        }
+
+        let mut symbol_data = Vec::with_capacity(count * nlist_size);
+        let mut string_data = vec![0];
+        for (index, symbol) in self.symbols.iter().enumerate() {
+            if symbol.delete {
+                continue;
+            }
+            let n_strx = if symbol.name.is_empty() {
+                0
+            } else {
+                let n_strx = string_data.len();
+                string_data.extend_from_slice(&symbol.name);
+                string_data.push(0);
+                n_strx
+            };
+            let start = symbol_data.len();
+            symbol_data.extend_from_slice(&self.data[symoff + index * nlist_size..][..nlist_size]);
+            // `n_strx` is the first field for both 32-bit and 64-bit.
+            symbol_data[start..start + 4]
+                .copy_from_slice(pod::bytes_of(&U32::new(endian, n_strx as u32)));
+        }
+        string_data.resize(align(string_data.len(), self.pointer_align()), 0);
+
+        // The symbol table can't grow, so write it in place.
+        out[symoff..][..nsyms * nlist_size].fill(0);
+        out[symoff..][..symbol_data.len()].copy_from_slice(&symbol_data);
+
+        let new_stroff = if string_data.len() <= strsize {
+            out[stroff..][..strsize].fill(0);
+            stroff
+        } else if self.is_last(commands, stroff) {
+            // The string table is at the end of the file, so it can grow.
+            out.truncate(stroff);
+            stroff
+        } else {
+            align(out.len(), self.pointer_align())
+        };
+        if out.len() < new_stroff + string_data.len() {
+            out.resize(new_stroff + string_data.len(), 0);
+            self.set_linkedit_end(commands, out.len() as u64);
+        }
+        out[new_stroff..][..string_data.len()].copy_from_slice(&string_data);
+
+        let (symtab, _) =
+            pod::from_bytes_mut::<macho::SymtabCommand<Endianness>>(&mut commands[symtab_index])
+                .map_err(|()| Error::modify("Invalid LC_SYMTAB command"))?;
+        symtab.nsyms.set(endian, count as u32);
+        symtab.stroff.set(endian, new_stroff as u32);
+        symtab.strsize.set(endian, string_data.len() as u32);
+        Ok(())
+    }
+
+    /// Return true if there is no data after the given file offset,
+    /// excluding the code signature.
+    fn is_last(&self, commands: &mut [Vec<u8>], offset: usize) -> bool {
+        let endian = self.endian;
+        let mut last = true;
+        for data in commands {
+            if command_cmd(endian, data) == macho::LC_CODE_SIGNATURE {
+                continue;
+            }
+            visit_offsets(endian, data, &mut |start| {
+                if start > offset as u64 {
+                    last = false;
+                }
+                start
+            });
+        }
+        last
+    }
+
+    /// Update symbol indices after deleting symbols.
+    fn remap_symbols(
+        &self,
+        map: &[Option<usize>],
+        commands: &mut [Vec<u8>],
+        out: &mut [u8],
+    ) -> Result<()> {
+        let endian = self.endian;
+        let remap = |index: u32| -> Result<u32> {
+            match map.get(index as usize) {
+                Some(Some(index)) => Ok(*index as u32),
+                Some(None) => Err(Error::modify(format!(
+                    "Can't delete symbol {} because it is still referenced",
+                    self.symbols[index as usize].name
+                ))),
+                None => Err(Error::modify(format!("Invalid symbol index {}", index))),
+            }
+        };
+
+        let mut relocations = Vec::new();
+        for data in commands.iter_mut() {
+            match command_cmd(endian, data) {
+                macho::LC_SEGMENT_64 => {
+                    let (_, sections) = segment_64(endian, data)?;
+                    for section in sections {
+                        relocations.push((section.reloff.get(endian), section.nreloc.get(endian)));
+                    }
+                }
+                macho::LC_SEGMENT => {
+                    let (_, sections) = segment_32(endian, data)?;
+                    for section in sections {
+                        relocations.push((section.reloff.get(endian), section.nreloc.get(endian)));
+                    }
+                }
+                macho::LC_DYSYMTAB => {
+                    let (dysymtab, _) =
+                        pod::from_bytes_mut::<macho::DysymtabCommand<Endianness>>(data)
+                            .map_err(|()| Error::modify("Invalid LC_DYSYMTAB command"))?;
+                    if dysymtab.ntoc.get(endian) != 0
+                        || dysymtab.nmodtab.get(endian) != 0
+                        || dysymtab.nextrefsyms.get(endian) != 0
+                    {
+                        return Err(Error::modify(
+                            "Unsupported LC_DYSYMTAB tables; can't delete symbols",
+                        ));
+                    }
+                    for (first, count) in [
+                        (&mut dysymtab.ilocalsym, &mut dysymtab.nlocalsym),
+                        (&mut dysymtab.iextdefsym, &mut dysymtab.nextdefsym),
+                        (&mut dysymtab.iundefsym, &mut dysymtab.nundefsym),
+                    ] {
+                        let start = (first.get(endian) as usize).min(map.len());
+                        let end = (start + count.get(endian) as usize).min(map.len());
+                        first.set(endian, map[..start].iter().flatten().count() as u32);
+                        count.set(endian, map[start..end].iter().flatten().count() as u32);
+                    }
+                    relocations
+                        .push((dysymtab.extreloff.get(endian), dysymtab.nextrel.get(endian)));
+                    relocations
+                        .push((dysymtab.locreloff.get(endian), dysymtab.nlocrel.get(endian)));
+
+                    let offset = dysymtab.indirectsymoff.get(endian) as usize;
+                    let count = dysymtab.nindirectsyms.get(endian) as usize;
+                    let (indirect, _) = out
+                        .get_mut(offset..)
+                        .and_then(|data| {
+                            pod::slice_from_bytes_mut::<U32<Endianness>>(data, count).ok()
+                        })
+                        .ok_or_else(|| Error::modify("Invalid indirect symbol table"))?;
+                    for entry in indirect {
+                        let index = entry.get(endian);
+                        if index & (macho::INDIRECT_SYMBOL_LOCAL | macho::INDIRECT_SYMBOL_ABS) == 0
+                        {
+                            entry.set(endian, remap(index)?);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (offset, count) in relocations {
+            if count == 0 {
+                continue;
+            }
+            let (relocations, _) = out
+                .get_mut(offset as usize..)
+                .and_then(|data| {
+                    pod::slice_from_bytes_mut::<macho::Relocation<Endianness>>(data, count as usize)
+                        .ok()
+                })
+                .ok_or_else(|| Error::modify("Invalid relocation offset or count"))?;
+            for relocation in relocations {
+                if relocation.r_scattered(endian, self.cputype) {
+                    continue;
+                }
+                let mut info = relocation.info(endian);
+                if info.r_extern {
+                    info.r_symbolnum = remap(info.r_symbolnum)?;
+                    *relocation = info.relocation(endian);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the maximum alignment of the sections in the file.
+    fn max_section_align(&self, commands: &mut [Vec<u8>]) -> usize {
+        let endian = self.endian;
+        let mut aligns = Vec::new();
+        for data in commands {
+            match command_cmd(endian, data) {
+                macho::LC_SEGMENT_64 => {
+                    if let Ok((_, sections)) = segment_64(endian, data) {
+                        aligns.extend(sections.iter().map(|section| section.align.get(endian)));
+                    }
+                }
+                macho::LC_SEGMENT => {
+                    if let Ok((_, sections)) = segment_32(endian, data) {
+                        aligns.extend(sections.iter().map(|section| section.align.get(endian)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        aligns
+            .into_iter()
+            .filter(|align| *align < 16)
+            .map(|align| 1 << align)
+            .fold(self.pointer_align(), usize::max)
+    }
+
+    /// Set the end of the `__LINKEDIT` segment, if there is one.
+    fn set_linkedit_end(&self, commands: &mut [Vec<u8>], end: u64) {
+        let endian = self.endian;
+        let page_size = self.page_size();
+        for data in commands {
+            match command_cmd(endian, data) {
+                macho::LC_SEGMENT_64 => {
+                    let Ok((segment, _)) = segment_64(endian, data) else {
+                        continue;
+                    };
+                    if !is_linkedit(&segment.segname) {
+                        continue;
+                    }
+                    let filesize = end.saturating_sub(segment.fileoff.get(endian));
+                    let vmsize = align64(filesize, page_size);
+                    segment.filesize.set(endian, filesize);
+                    if segment.vmsize.get(endian) < vmsize {
+                        segment.vmsize.set(endian, vmsize);
+                    }
+                }
+                macho::LC_SEGMENT => {
+                    let Ok((segment, _)) = segment_32(endian, data) else {
+                        continue;
+                    };
+                    if !is_linkedit(&segment.segname) {
+                        continue;
+                    }
+                    let filesize = end.saturating_sub(segment.fileoff.get(endian).into());
+                    let vmsize = align64(filesize, page_size);
+                    segment.filesize.set(endian, filesize as u32);
+                    if u64::from(segment.vmsize.get(endian)) < vmsize {
+                        segment.vmsize.set(endian, vmsize as u32);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn command_cmd(endian: Endianness, data: &[u8]) -> u32 {
+    pod::from_bytes::<macho::LoadCommand<Endianness>>(data)
+        .map(|(command, _)| command.cmd.get(endian))
+        .unwrap_or(0)
+}
+
+fn segment_64(
+    endian: Endianness,
+    data: &mut [u8],
+) -> Result<(
+    &mut macho::SegmentCommand64<Endianness>,
+    &mut [macho::Section64<Endianness>],
+)> {
+    let invalid = || Error::modify("Invalid LC_SEGMENT_64 command");
+    let (segment, data) =
+        pod::from_bytes_mut::<macho::SegmentCommand64<Endianness>>(data).map_err(|()| invalid())?;
+    let nsects = segment.nsects.get(endian) as usize;
+    let (sections, _) = pod::slice_from_bytes_mut::<macho::Section64<Endianness>>(data, nsects)
+        .map_err(|()| invalid())?;
+    Ok((segment, sections))
+}
+
+fn segment_32(
+    endian: Endianness,
+    data: &mut [u8],
+) -> Result<(
+    &mut macho::SegmentCommand32<Endianness>,
+    &mut [macho::Section32<Endianness>],
+)> {
+    let invalid = || Error::modify("Invalid LC_SEGMENT command");
+    let (segment, data) =
+        pod::from_bytes_mut::<macho::SegmentCommand32<Endianness>>(data).map_err(|()| invalid())?;
+    let nsects = segment.nsects.get(endian) as usize;
+    let (sections, _) = pod::slice_from_bytes_mut::<macho::Section32<Endianness>>(data, nsects)
+        .map_err(|()| invalid())?;
+    Ok((segment, sections))
+}
+
+fn is_linkedit(segname: &[u8; 16]) -> bool {
+    segname.split(|byte| *byte == 0).next() == Some(b"__LINKEDIT")
+}
+
+/// Call `f` for each non-zero file offset in a load command, and replace
+/// the offset with the result.
+fn visit_offsets(endian: Endianness, data: &mut [u8], f: &mut dyn FnMut(u64) -> u64) {
+    fn visit(endian: Endianness, offset: &mut U32<Endianness>, f: &mut dyn FnMut(u64) -> u64) {
+        let value = offset.get(endian);
+        if value != 0 {
+            offset.set(endian, f(value.into()) as u32);
+        }
+    }
+
+    match command_cmd(endian, data) {
+        macho::LC_SEGMENT_64 => {
+            let Ok((segment, sections)) = segment_64(endian, data) else {
+                return;
+            };
+            let fileoff = segment.fileoff.get(endian);
+            if fileoff != 0 && segment.filesize.get(endian) != 0 {
+                segment.fileoff.set(endian, f(fileoff));
+            }
+            for section in sections {
+                visit(endian, &mut section.offset, f);
+                visit(endian, &mut section.reloff, f);
+            }
+        }
+        macho::LC_SEGMENT => {
+            let Ok((segment, sections)) = segment_32(endian, data) else {
+                return;
+            };
+            if segment.filesize.get(endian) != 0 {
+                visit(endian, &mut segment.fileoff, f);
+            }
+            for section in sections {
+                visit(endian, &mut section.offset, f);
+                visit(endian, &mut section.reloff, f);
+            }
+        }
+        macho::LC_SYMTAB => {
+            if let Ok((symtab, _)) = pod::from_bytes_mut::<macho::SymtabCommand<Endianness>>(data) {
+                visit(endian, &mut symtab.symoff, f);
+                visit(endian, &mut symtab.stroff, f);
+            }
+        }
+        macho::LC_DYSYMTAB => {
+            if let Ok((dysymtab, _)) =
+                pod::from_bytes_mut::<macho::DysymtabCommand<Endianness>>(data)
+            {
+                visit(endian, &mut dysymtab.tocoff, f);
+                visit(endian, &mut dysymtab.modtaboff, f);
+                visit(endian, &mut dysymtab.extrefsymoff, f);
+                visit(endian, &mut dysymtab.indirectsymoff, f);
+                visit(endian, &mut dysymtab.extreloff, f);
+                visit(endian, &mut dysymtab.locreloff, f);
+            }
+        }
+        macho::LC_DYLD_INFO | macho::LC_DYLD_INFO_ONLY => {
+            if let Ok((dyld_info, _)) =
+                pod::from_bytes_mut::<macho::DyldInfoCommand<Endianness>>(data)
+            {
+                visit(endian, &mut dyld_info.rebase_off, f);
+                visit(endian, &mut dyld_info.bind_off, f);
+                visit(endian, &mut dyld_info.weak_bind_off, f);
+                visit(endian, &mut dyld_info.lazy_bind_off, f);
+                visit(endian, &mut dyld_info.export_off, f);
+            }
+        }
+        macho::LC_CODE_SIGNATURE
+        | macho::LC_SEGMENT_SPLIT_INFO
+        | macho::LC_FUNCTION_STARTS
+        | macho::LC_DATA_IN_CODE
+        | macho::LC_DYLIB_CODE_SIGN_DRS
+        | macho::LC_LINKER_OPTIMIZATION_HINT
+        | macho::LC_DYLD_EXPORTS_TRIE
+        | macho::LC_DYLD_CHAINED_FIXUPS => {
+            if let Ok((command, _)) =
+                pod::from_bytes_mut::<macho::LinkeditDataCommand<Endianness>>(data)
+            {
+                visit(endian, &mut command.dataoff, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn align(offset: usize, align: usize) -> usize {
+    (offset + (align - 1)) & !(align - 1)
+}
+
+fn align64(offset: u64, align: u64) -> u64 {
+    (offset + (align - 1)) & !(align - 1)
+}
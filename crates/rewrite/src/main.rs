@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fs;
 use std::io::{self, BufRead};
 use std::path::{Path, PathBuf};
@@ -15,11 +16,13 @@ fn main() -> Result<()> {
                 .required(true)
                 .value_parser(clap::value_parser!(PathBuf))
                 .help("The input file"),
-            // TODO: make output optional, and overwrite input file by default
             Arg::new("output")
                 .required(false)
                 .value_parser(clap::value_parser!(PathBuf))
-                .help("The output file. Required if any modification is requested"),
+                .help(
+                    "The output file. If a modification is requested without an output file, \
+                    then the input file is replaced",
+                ),
             Arg::new("delete-symbol")
                 .long("delete-symbol")
                 .value_name("symbol")
@@ -41,6 +44,71 @@ fn main() -> Result<()> {
                     "Read a list of symbol names from <file> and apply --rename-symbol for each. \
                     Each line contains two symbols separated by whitespace.",
                 ),
+            Arg::new("localize-symbol")
+                .long("localize-symbol")
+                .value_name("symbol")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Make the named symbol local"),
+            Arg::new("localize-symbols")
+                .long("localize-symbols")
+                .value_name("file")
+                .value_parser(clap::value_parser!(PathBuf))
+                .action(ArgAction::Append)
+                .help(
+                    "Read a list of symbol names from <file> and apply --localize-symbol for each. \
+                    Each line contains one symbol.",
+                ),
+            Arg::new("keep-global-symbol")
+                .long("keep-global-symbol")
+                .value_name("symbol")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Make all symbols local except for the named symbol"),
+            Arg::new("keep-global-symbols")
+                .long("keep-global-symbols")
+                .value_name("file")
+                .value_parser(clap::value_parser!(PathBuf))
+                .action(ArgAction::Append)
+                .help(
+                    "Read a list of symbol names from <file> and apply --keep-global-symbol for each. \
+                    Each line contains one symbol.",
+                ),
+            Arg::new("globalize-symbol")
+                .long("globalize-symbol")
+                .value_name("symbol")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Make the named symbol global"),
+            Arg::new("globalize-symbols")
+                .long("globalize-symbols")
+                .value_name("file")
+                .value_parser(clap::value_parser!(PathBuf))
+                .action(ArgAction::Append)
+                .help(
+                    "Read a list of symbol names from <file> and apply --globalize-symbol for each. \
+                    Each line contains one symbol.",
+                ),
+            Arg::new("weaken-symbol")
+                .long("weaken-symbol")
+                .value_name("symbol")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Make the named symbol weak"),
+            Arg::new("weaken-symbols")
+                .long("weaken-symbols")
+                .value_name("file")
+                .value_parser(clap::value_parser!(PathBuf))
+                .action(ArgAction::Append)
+                .help(
+                    "Read a list of symbol names from <file> and apply --weaken-symbol for each. \
+                    Each line contains one symbol.",
+                ),
+            Arg::new("prefix-symbols")
+                .long("prefix-symbols")
+                .value_name("prefix")
+                .value_parser(clap::value_parser!(String))
+                .help("Add <prefix> to the start of the names of all symbols"),
             Arg::new("delete-section")
                 .long("delete-section")
                 .value_name("section")
@@ -53,6 +121,54 @@ fn main() -> Result<()> {
                 .value_parser(clap::value_parser!(String))
                 .action(ArgAction::Append)
                 .help("Change the name of a section from <old> to <new>"),
+            Arg::new("update-section")
+                .long("update-section")
+                .value_name("section=file")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Replace the contents of the named section with the contents of <file>"),
+            Arg::new("dump-section")
+                .long("dump-section")
+                .value_name("section=file")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Write the contents of the named section to <file>"),
+            Arg::new("add-section")
+                .long("add-section")
+                .value_name("section=file")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Add a section named <section> containing the contents of <file>"),
+            Arg::new("prefix-sections")
+                .long("prefix-sections")
+                .value_name("prefix")
+                .value_parser(clap::value_parser!(String))
+                .help("Add <prefix> to the start of the names of all sections"),
+            Arg::new("elf-set-entry")
+                .long("elf-set-entry")
+                .value_name("address")
+                .value_parser(parse_u64)
+                .help("Set the entry point address in the file header"),
+            Arg::new("elf-set-os-abi")
+                .long("elf-set-os-abi")
+                .value_name("value")
+                .value_parser(parse_u8)
+                .help("Set the OS ABI in the file header"),
+            Arg::new("elf-set-abi-version")
+                .long("elf-set-abi-version")
+                .value_name("value")
+                .value_parser(parse_u8)
+                .help("Set the ABI version in the file header"),
+            Arg::new("elf-set-flags")
+                .long("elf-set-flags")
+                .value_name("flags")
+                .value_parser(parse_u32)
+                .help("Set the processor-specific flags in the file header"),
+            Arg::new("elf-set-type")
+                .long("elf-set-type")
+                .value_name("type")
+                .value_parser(["exec", "dyn"])
+                .help("Set the object file type in the file header"),
             Arg::new("elf-add-dynamic-debug")
                 .long("elf-add-dynamic-debug")
                 .action(ArgAction::SetTrue)
@@ -124,6 +240,117 @@ fn main() -> Result<()> {
                 .value_name("path")
                 .value_parser(clap::value_parser!(String))
                 .help("Set the interpreter path in the PT_INTERP segment"),
+            Arg::new("remove-note")
+                .long("remove-note")
+                .value_name("name[/type]")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Remove notes with the given name and optional type"),
+            Arg::new("add-note")
+                .long("add-note")
+                .value_name("section=name/type=file")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Add a note to <section> with the contents of <file> as the descriptor"),
+            Arg::new("strip-unneeded")
+                .long("strip-unneeded")
+                .action(ArgAction::SetTrue)
+                .help("Remove all symbols that are not needed for relocation processing"),
+            Arg::new("only-keep-debug")
+                .long("only-keep-debug")
+                .action(ArgAction::SetTrue)
+                .help("Remove the contents of all sections except for debugging information"),
+            Arg::new("add-gnu-debuglink")
+                .long("add-gnu-debuglink")
+                .value_name("file")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Add a .gnu_debuglink section referring to the debug file <file>"),
+            Arg::new("elf-compact")
+                .long("elf-compact")
+                .action(ArgAction::SetTrue)
+                .help("Remove unused space between segments in the file"),
+            Arg::new("elf-delete-extra-data")
+                .long("elf-delete-extra-data")
+                .action(ArgAction::SetTrue)
+                .help("Remove data that is not part of any section, segment, or header"),
+            Arg::new("macho-print-id")
+                .long("macho-print-id")
+                .action(ArgAction::SetTrue)
+                .help("Print the install name in the LC_ID_DYLIB load command"),
+            Arg::new("macho-set-id")
+                .long("macho-set-id")
+                .value_name("name")
+                .value_parser(clap::value_parser!(String))
+                .help("Set the install name in the LC_ID_DYLIB load command"),
+            Arg::new("macho-print-rpath")
+                .long("macho-print-rpath")
+                .action(ArgAction::SetTrue)
+                .help("Print the paths in the LC_RPATH load commands"),
+            Arg::new("macho-delete-rpath")
+                .long("macho-delete-rpath")
+                .value_name("path")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Delete the LC_RPATH load command with the given path"),
+            Arg::new("macho-replace-rpath")
+                .long("macho-replace-rpath")
+                .value_name("old=new")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Change the path of an LC_RPATH load command from <old> to <new>"),
+            Arg::new("macho-add-rpath")
+                .long("macho-add-rpath")
+                .value_name("path")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Add an LC_RPATH load command with the given path"),
+            Arg::new("macho-print-dylibs")
+                .long("macho-print-dylibs")
+                .action(ArgAction::SetTrue)
+                .help("Print the paths of the dependent dylibs"),
+            Arg::new("macho-replace-dylib")
+                .long("macho-replace-dylib")
+                .value_name("old=new")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Change the path of a dependent dylib from <old> to <new>"),
+            Arg::new("pe-print-dlls")
+                .long("pe-print-dlls")
+                .action(ArgAction::SetTrue)
+                .help("Print the names of the imported DLLs"),
+            Arg::new("pe-replace-dll")
+                .long("pe-replace-dll")
+                .value_name("old=new")
+                .value_parser(clap::value_parser!(String))
+                .action(ArgAction::Append)
+                .help("Change the name of an imported DLL from <old> to <new>"),
+            Arg::new("pe-strip-debug")
+                .long("pe-strip-debug")
+                .action(ArgAction::SetTrue)
+                .help("Remove the debug directory"),
+            Arg::new("pe-update-checksum")
+                .long("pe-update-checksum")
+                .action(ArgAction::SetTrue)
+                .help("Update the checksum in the optional header"),
+            Arg::new("output-format")
+                .short('O')
+                .long("output-format")
+                .value_name("format")
+                .value_parser(["binary"])
+                .help("Write the output in the given format instead of the input format"),
+            Arg::new("dry-run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help("Print the changes that would be made instead of writing the output file"),
+            Arg::new("exact-names")
+                .long("exact-names")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("regex")
+                .help("Match symbol and section names exactly, instead of as glob patterns"),
+            Arg::new("regex")
+                .long("regex")
+                .action(ArgAction::SetTrue)
+                .help("Match symbol and section names as regular expressions"),
             Arg::new("ignore-unknown-format")
                 .long("ignore-unknown-format")
                 .action(ArgAction::SetTrue)
@@ -140,8 +367,25 @@ fn main() -> Result<()> {
                     "delete-symbol",
                     "rename-symbol",
                     "rename-symbols",
+                    "localize-symbol",
+                    "localize-symbols",
+                    "keep-global-symbol",
+                    "keep-global-symbols",
+                    "globalize-symbol",
+                    "globalize-symbols",
+                    "weaken-symbol",
+                    "weaken-symbols",
+                    "prefix-symbols",
                     "delete-section",
                     "rename-section",
+                    "update-section",
+                    "add-section",
+                    "prefix-sections",
+                    "elf-set-entry",
+                    "elf-set-os-abi",
+                    "elf-set-abi-version",
+                    "elf-set-flags",
+                    "elf-set-type",
                     "elf-add-dynamic-debug",
                     "elf-delete-runpath",
                     "elf-set-runpath",
@@ -153,10 +397,25 @@ fn main() -> Result<()> {
                     "elf-add-needed",
                     "elf-set-soname",
                     "elf-set-interpreter",
+                    "remove-note",
+                    "add-note",
+                    "strip-unneeded",
+                    "only-keep-debug",
+                    "add-gnu-debuglink",
+                    "elf-compact",
+                    "elf-delete-extra-data",
+                    "macho-set-id",
+                    "macho-delete-rpath",
+                    "macho-replace-rpath",
+                    "macho-add-rpath",
+                    "macho-replace-dylib",
+                    "pe-replace-dll",
+                    "pe-strip-debug",
+                    "pe-update-checksum",
+                    "output-format",
                 ])
                 .multiple(true)
-                .required(false)
-                .requires("output"),
+                .required(false),
         )
         .get_matches();
 
@@ -179,7 +438,12 @@ fn main() -> Result<()> {
 
     if matches.get_flag("ignore-unknown-format") {
         match object::FileKind::parse(in_data) {
-            Ok(object::FileKind::Elf32) | Ok(object::FileKind::Elf64) => {}
+            Ok(object::FileKind::Elf32)
+            | Ok(object::FileKind::Elf64)
+            | Ok(object::FileKind::MachO32)
+            | Ok(object::FileKind::MachO64)
+            | Ok(object::FileKind::Pe32)
+            | Ok(object::FileKind::Pe64) => {}
             _ => {
                 info!(
                     "Ignoring input file '{}' with unknown format",
@@ -192,6 +456,21 @@ fn main() -> Result<()> {
     let mut rewriter = rewrite::Rewriter::read(in_data)
         .with_context(|| format!("Failed to parse input file '{}'", in_path.display()))?;
 
+    for arg in matches
+        .get_many::<String>("dump-section")
+        .unwrap_or_default()
+    {
+        let Some((name, filename)) = arg.split_once('=') else {
+            return Err(
+                anyhow!(
+                "Invalid dump section: `{}`. --dump-section expects argument of the form: <section>=<file>", arg)
+            );
+        };
+        let data = rewriter.section_data(name.as_bytes())?;
+        fs::write(filename, data)
+            .with_context(|| format!("Failed to write section file '{}'", filename))?;
+    }
+
     if matches.get_flag("elf-print-runpath") {
         if let Some(runpath) = rewriter.elf_runpath() {
             println!("{}", String::from_utf8_lossy(runpath));
@@ -213,13 +492,42 @@ fn main() -> Result<()> {
         }
     }
 
-    // TODO: allow replacing input file
-    let Some(out_path) = matches.get_one::<PathBuf>("output") else {
-        return Ok(());
+    if matches.get_flag("macho-print-id") {
+        if let Some(id) = rewriter.macho_id() {
+            println!("{}", String::from_utf8_lossy(id));
+        }
+    }
+    if matches.get_flag("macho-print-rpath") {
+        for rpath in rewriter.macho_rpaths() {
+            println!("{}", String::from_utf8_lossy(rpath));
+        }
+    }
+    if matches.get_flag("macho-print-dylibs") {
+        for dylib in rewriter.macho_dylibs() {
+            println!("{}", String::from_utf8_lossy(dylib));
+        }
+    }
+
+    if matches.get_flag("pe-print-dlls") {
+        for dll in rewriter.pe_dlls() {
+            println!("{}", String::from_utf8_lossy(dll));
+        }
+    }
+
+    let out_path = match matches.get_one::<PathBuf>("output") {
+        Some(out_path) => Some(out_path),
+        None if matches.contains_id("output-flags") || matches.get_flag("dry-run") => None,
+        None => return Ok(()),
     };
 
     let mut options = rewrite::Options::default();
 
+    if matches.get_flag("exact-names") {
+        options.name_matching = rewrite::NameMatching::Exact;
+    } else if matches.get_flag("regex") {
+        options.name_matching = rewrite::NameMatching::Regex;
+    }
+
     options.delete_symbols = matches
         .get_many::<String>("delete-symbol")
         .unwrap_or_default()
@@ -268,6 +576,53 @@ fn main() -> Result<()> {
             line.clear();
         }
     }
+    options.localize_symbols = matches
+        .get_many::<String>("localize-symbol")
+        .unwrap_or_default()
+        .map(|arg| arg.clone().into_bytes())
+        .collect();
+    for filename in matches
+        .get_many::<PathBuf>("localize-symbols")
+        .unwrap_or_default()
+    {
+        read_symbols_file(filename, &mut options.localize_symbols)?;
+    }
+    options.keep_global_symbols = matches
+        .get_many::<String>("keep-global-symbol")
+        .unwrap_or_default()
+        .map(|arg| arg.clone().into_bytes())
+        .collect();
+    for filename in matches
+        .get_many::<PathBuf>("keep-global-symbols")
+        .unwrap_or_default()
+    {
+        read_symbols_file(filename, &mut options.keep_global_symbols)?;
+    }
+    options.globalize_symbols = matches
+        .get_many::<String>("globalize-symbol")
+        .unwrap_or_default()
+        .map(|arg| arg.clone().into_bytes())
+        .collect();
+    for filename in matches
+        .get_many::<PathBuf>("globalize-symbols")
+        .unwrap_or_default()
+    {
+        read_symbols_file(filename, &mut options.globalize_symbols)?;
+    }
+    options.weaken_symbols = matches
+        .get_many::<String>("weaken-symbol")
+        .unwrap_or_default()
+        .map(|arg| arg.clone().into_bytes())
+        .collect();
+    for filename in matches
+        .get_many::<PathBuf>("weaken-symbols")
+        .unwrap_or_default()
+    {
+        read_symbols_file(filename, &mut options.weaken_symbols)?;
+    }
+    options.prefix_symbols = matches
+        .get_one::<String>("prefix-symbols")
+        .map(|arg| arg.clone().into_bytes());
     options.delete_sections = matches
         .get_many::<String>("delete-section")
         .unwrap_or_default()
@@ -288,6 +643,52 @@ fn main() -> Result<()> {
             .rename_sections
             .insert(names[0].to_vec(), names[1].to_vec());
     }
+    for arg in matches
+        .get_many::<String>("update-section")
+        .unwrap_or_default()
+    {
+        let Some((name, filename)) = arg.split_once('=') else {
+            return Err(
+                anyhow!(
+                "Invalid update section: `{}`. --update-section expects argument of the form: <section>=<file>", arg)
+            );
+        };
+        let data = fs::read(filename)
+            .with_context(|| format!("Failed to read section file '{}'", filename))?;
+        options
+            .update_sections
+            .insert(name.as_bytes().to_vec(), data);
+    }
+    for arg in matches
+        .get_many::<String>("add-section")
+        .unwrap_or_default()
+    {
+        let Some((name, filename)) = arg.split_once('=') else {
+            return Err(
+                anyhow!(
+                "Invalid add section: `{}`. --add-section expects argument of the form: <section>=<file>", arg)
+            );
+        };
+        let data = fs::read(filename)
+            .with_context(|| format!("Failed to read section file '{}'", filename))?;
+        options
+            .add_sections
+            .push(rewrite::NewSection::new(name.as_bytes().to_vec(), data));
+    }
+    options.prefix_sections = matches
+        .get_one::<String>("prefix-sections")
+        .map(|arg| arg.clone().into_bytes());
+    options.elf.set_entry = matches.get_one::<u64>("elf-set-entry").copied();
+    options.elf.set_os_abi = matches.get_one::<u8>("elf-set-os-abi").copied();
+    options.elf.set_abi_version = matches.get_one::<u8>("elf-set-abi-version").copied();
+    options.elf.set_flags = matches.get_one::<u32>("elf-set-flags").copied();
+    options.elf.set_type =
+        matches
+            .get_one::<String>("elf-set-type")
+            .map(|arg| match arg.as_str() {
+                "exec" => object::elf::ET_EXEC,
+                _ => object::elf::ET_DYN,
+            });
     options.elf.add_dynamic_debug = matches.get_flag("elf-add-dynamic-debug");
     options.elf.delete_runpath = matches.get_flag("elf-delete-runpath");
     options.elf.set_runpath = matches
@@ -332,9 +733,148 @@ fn main() -> Result<()> {
     options.elf.set_interpreter = matches
         .get_one::<String>("elf-set-interpreter")
         .map(|arg| arg.clone().into_bytes());
+    for arg in matches
+        .get_many::<String>("remove-note")
+        .unwrap_or_default()
+    {
+        let (name, n_type) = match arg.rsplit_once('/') {
+            Some((name, n_type)) => {
+                let n_type = parse_u32(n_type).with_context(|| {
+                    format!(
+                        "Invalid remove note: `{}`. --remove-note expects argument of the form: <name>[/<type>]",
+                        arg
+                    )
+                })?;
+                (name, Some(n_type))
+            }
+            None => (arg.as_str(), None),
+        };
+        options
+            .elf
+            .remove_notes
+            .push((name.as_bytes().to_vec(), n_type));
+    }
+    for arg in matches.get_many::<String>("add-note").unwrap_or_default() {
+        let invalid = || {
+            anyhow!(
+                "Invalid add note: `{}`. --add-note expects argument of the form: <section>=<name>/<type>=<file>",
+                arg
+            )
+        };
+        let (section, rest) = arg.split_once('=').ok_or_else(invalid)?;
+        let (note, filename) = rest.split_once('=').ok_or_else(invalid)?;
+        let (name, n_type) = note.rsplit_once('/').ok_or_else(invalid)?;
+        let n_type = parse_u32(n_type).map_err(|_| invalid())?;
+        let desc = fs::read(filename)
+            .with_context(|| format!("Failed to read note file '{}'", filename))?;
+        options.elf.add_notes.push(object_rewrite::ElfNote::new(
+            section.as_bytes().to_vec(),
+            name.as_bytes().to_vec(),
+            n_type,
+            desc,
+        ));
+    }
+    options.elf.strip_unneeded = matches.get_flag("strip-unneeded");
+    options.elf.only_keep_debug = matches.get_flag("only-keep-debug");
+    if let Some(path) = matches.get_one::<PathBuf>("add-gnu-debuglink") {
+        let data = fs::read(path)
+            .with_context(|| format!("Failed to read debug file '{}'", path.display()))?;
+        let Some(filename) = path.file_name() else {
+            return Err(anyhow!(
+                "Invalid debug file: `{}`. --add-gnu-debuglink expects a file name",
+                path.display()
+            ));
+        };
+        let crc = crc32fast::hash(&data);
+        options.elf.add_gnu_debuglink =
+            Some((filename.to_string_lossy().into_owned().into_bytes(), crc));
+    }
+    options.elf.compact = matches.get_flag("elf-compact");
+    options.elf.delete_extra_data = matches.get_flag("elf-delete-extra-data");
+    options.macho.set_id = matches
+        .get_one::<String>("macho-set-id")
+        .map(|arg| arg.clone().into_bytes());
+    options.macho.delete_rpath = matches
+        .get_many::<String>("macho-delete-rpath")
+        .unwrap_or_default()
+        .map(|arg| arg.clone().into_bytes())
+        .collect();
+    for arg in matches
+        .get_many::<String>("macho-replace-rpath")
+        .unwrap_or_default()
+    {
+        let names: Vec<&[u8]> = arg.as_bytes().splitn(2, |byte| *byte == b'=').collect();
+        if names.len() != 2 {
+            return Err(
+                anyhow!(
+                "Invalid replace rpath: `{}`. --macho-replace-rpath expects argument of the form: <old>=<new>", arg)
+            );
+        }
+        options
+            .macho
+            .replace_rpath
+            .insert(names[0].to_vec(), names[1].to_vec());
+    }
+    options.macho.add_rpath = matches
+        .get_many::<String>("macho-add-rpath")
+        .unwrap_or_default()
+        .map(|arg| arg.clone().into_bytes())
+        .collect();
+    for arg in matches
+        .get_many::<String>("macho-replace-dylib")
+        .unwrap_or_default()
+    {
+        let names: Vec<&[u8]> = arg.as_bytes().splitn(2, |byte| *byte == b'=').collect();
+        if names.len() != 2 {
+            return Err(
+                anyhow!(
+                "Invalid replace dylib: `{}`. --macho-replace-dylib expects argument of the form: <old>=<new>", arg)
+            );
+        }
+        options
+            .macho
+            .replace_dylib
+            .insert(names[0].to_vec(), names[1].to_vec());
+    }
+    for arg in matches
+        .get_many::<String>("pe-replace-dll")
+        .unwrap_or_default()
+    {
+        let names: Vec<&[u8]> = arg.as_bytes().splitn(2, |byte| *byte == b'=').collect();
+        if names.len() != 2 {
+            return Err(
+                anyhow!(
+                "Invalid replace DLL: `{}`. --pe-replace-dll expects argument of the form: <old>=<new>", arg)
+            );
+        }
+        options
+            .pe
+            .replace_dll
+            .insert(names[0].to_vec(), names[1].to_vec());
+    }
+    options.pe.strip_debug = matches.get_flag("pe-strip-debug");
+    options.pe.update_checksum = matches.get_flag("pe-update-checksum");
+    if let Some(format) = matches.get_one::<String>("output-format") {
+        options.output_format = match format.as_str() {
+            "binary" => rewrite::OutputFormat::Binary,
+            _ => unreachable!(),
+        };
+    }
 
     rewriter.modify(options)?;
 
+    if matches.get_flag("dry-run") {
+        let report = rewriter.dry_run()?;
+        print!("{}", report);
+        return Ok(());
+    }
+
+    let Some(out_path) = out_path else {
+        return rewriter
+            .write_to_path(in_path)
+            .with_context(|| format!("Failed to replace input file '{}'", in_path.display()));
+    };
+
     if out_path == Path::new("-") {
         rewriter
             .write(io::stdout().lock())
@@ -376,3 +916,42 @@ fn main() -> Result<()> {
     })?;
     Ok(())
 }
+
+/// Parse an integer that may be in hexadecimal with a `0x` prefix.
+fn parse_u64(arg: &str) -> Result<u64, std::num::ParseIntError> {
+    match arg.strip_prefix("0x").or_else(|| arg.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => arg.parse(),
+    }
+}
+
+fn parse_u32(arg: &str) -> Result<u32> {
+    Ok(u32::try_from(parse_u64(arg)?)?)
+}
+
+fn parse_u8(arg: &str) -> Result<u8> {
+    Ok(u8::try_from(parse_u64(arg)?)?)
+}
+
+/// Read a list of symbol names from a file, with one symbol per line.
+///
+/// Empty lines are ignored.
+fn read_symbols_file(filename: &Path, names: &mut HashSet<Vec<u8>>) -> Result<()> {
+    let file = fs::File::open(filename)
+        .with_context(|| format!("Failed to open symbol file '{}'", filename.display()))?;
+    let mut buf = io::BufReader::new(file);
+    let mut line = Vec::new();
+    while buf.read_until(b'\n', &mut line)? != 0 {
+        if line.ends_with(b"\n") {
+            line.pop();
+            if line.ends_with(b"\r") {
+                line.pop();
+            }
+        }
+        if !line.is_empty() {
+            names.insert(line.clone());
+        }
+        line.clear();
+    }
+    Ok(())
+}
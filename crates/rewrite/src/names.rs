@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use super::Result;
+
+/// How names in the options are matched against names in the file.
+///
+/// This applies to the options for deleting, renaming, and changing the
+/// binding of symbols, and for deleting and renaming sections.
+///
+/// The default is [`NameMatching::Glob`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NameMatching {
+    /// Names must match exactly.
+    Exact,
+    /// Names may contain glob wildcards.
+    ///
+    /// `*` matches any sequence of characters, `?` matches any single character,
+    /// and `[...]` matches any character in the set. A set may contain ranges
+    /// such as `a-z`, and is negated if it starts with `!` or `^`. A `\` matches
+    /// the following character exactly.
+    ///
+    /// Names that don't contain any of `*`, `?` or `[` must match exactly.
+    #[default]
+    Glob,
+    /// Names are regular expressions that must match the whole name.
+    ///
+    /// This requires the `regex` feature.
+    #[cfg(feature = "regex")]
+    Regex,
+}
+
+/// A map from names or patterns to values.
+pub(crate) struct NameMap<'a, V> {
+    names: HashMap<&'a [u8], &'a V>,
+    patterns: Vec<(Pattern<'a>, &'a V)>,
+}
+
+/// A set of names or patterns.
+pub(crate) type NameSet<'a> = NameMap<'a, ()>;
+
+enum Pattern<'a> {
+    Glob(&'a [u8]),
+    #[cfg(feature = "regex")]
+    Regex(regex::bytes::Regex),
+}
+
+impl<'a, V> NameMap<'a, V> {
+    pub(crate) fn new<I>(matching: NameMatching, entries: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (&'a [u8], &'a V)>,
+    {
+        let mut names = HashMap::new();
+        let mut patterns = Vec::new();
+        for (name, value) in entries {
+            match matching {
+                NameMatching::Exact => {
+                    names.insert(name, value);
+                }
+                NameMatching::Glob => {
+                    if name.iter().any(|c| matches!(c, b'*' | b'?' | b'[')) {
+                        patterns.push((Pattern::Glob(name), value));
+                    } else {
+                        names.insert(name, value);
+                    }
+                }
+                #[cfg(feature = "regex")]
+                NameMatching::Regex => {
+                    let regex = std::str::from_utf8(name)
+                        .map_err(|e| e.to_string())
+                        .and_then(|name| {
+                            regex::bytes::Regex::new(&format!("^(?:{})$", name))
+                                .map_err(|e| e.to_string())
+                        })
+                        .map_err(|e| {
+                            super::Error::modify(format!(
+                                "Invalid regular expression {}: {}",
+                                String::from_utf8_lossy(name),
+                                e
+                            ))
+                        })?;
+                    patterns.push((Pattern::Regex(regex), value));
+                }
+            }
+        }
+        Ok(NameMap { names, patterns })
+    }
+
+    /// Return the value for the first entry that matches the name.
+    ///
+    /// Exact names are checked before patterns.
+    pub(crate) fn get(&self, name: &[u8]) -> Option<&'a V> {
+        if let Some(value) = self.names.get(name) {
+            return Some(value);
+        }
+        self.patterns
+            .iter()
+            .find(|(pattern, _)| match pattern {
+                Pattern::Glob(pattern) => glob_match(pattern, name),
+                #[cfg(feature = "regex")]
+                Pattern::Regex(regex) => regex.is_match(name),
+            })
+            .map(|(_, value)| *value)
+    }
+
+    /// Return true if any entry matches the name.
+    pub(crate) fn contains(&self, name: &[u8]) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Return the value for the first entry that matches each name.
+    ///
+    /// With the `rayon` feature, the names are matched using multiple threads.
+    pub(crate) fn get_all(&self, names: Vec<&[u8]>) -> Vec<Option<&'a V>>
+    where
+        V: Sync,
+    {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            names.into_par_iter().map(|name| self.get(name)).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            names.into_iter().map(|name| self.get(name)).collect()
+        }
+    }
+}
+
+/// Return true if the name matches the glob pattern.
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    let mut p = 0;
+    let mut n = 0;
+    // The pattern and name positions to retry from after the most recent `*`.
+    let mut backtrack = None;
+    loop {
+        if pattern.get(p) == Some(&b'*') {
+            p += 1;
+            backtrack = Some((p, n));
+            continue;
+        }
+        if n == name.len() {
+            return p == pattern.len();
+        }
+        if p < pattern.len() {
+            if let Some(len) = match_element(&pattern[p..], name[n]) {
+                p += len;
+                n += 1;
+                continue;
+            }
+        }
+        // Let the most recent `*` match one more character.
+        match backtrack {
+            Some((backtrack_p, backtrack_n)) => {
+                p = backtrack_p;
+                n = backtrack_n + 1;
+                backtrack = Some((p, n));
+            }
+            None => return false,
+        }
+    }
+}
+
+/// If the first element of the glob pattern matches the character, then
+/// return the length of the element.
+fn match_element(pattern: &[u8], c: u8) -> Option<usize> {
+    match pattern[0] {
+        b'?' => Some(1),
+        b'\\' if pattern.len() > 1 => (pattern[1] == c).then_some(2),
+        b'[' => match set_end(pattern) {
+            Some(end) => match_set(&pattern[1..end], c).then_some(end + 1),
+            // Treat an unterminated set as a literal `[`.
+            None => (c == b'[').then_some(1),
+        },
+        x => (x == c).then_some(1),
+    }
+}
+
+/// Return the index of the `]` that terminates the set at the start of the pattern.
+fn set_end(pattern: &[u8]) -> Option<usize> {
+    let mut i = 1;
+    if matches!(pattern.get(i), Some(b'!' | b'^')) {
+        i += 1;
+    }
+    // A `]` at the start of the set is not a terminator.
+    if pattern.get(i) == Some(&b']') {
+        i += 1;
+    }
+    pattern[i.min(pattern.len())..]
+        .iter()
+        .position(|x| *x == b']')
+        .map(|pos| i + pos)
+}
+
+/// Return true if the character is in the set.
+fn match_set(mut set: &[u8], c: u8) -> bool {
+    let negate = matches!(set.first(), Some(b'!' | b'^'));
+    if negate {
+        set = &set[1..];
+    }
+    let mut matched = false;
+    while !set.is_empty() {
+        if set.len() >= 3 && set[1] == b'-' {
+            matched |= (set[0]..=set[2]).contains(&c);
+            set = &set[3..];
+        } else {
+            matched |= set[0] == c;
+            set = &set[1..];
+        }
+    }
+    matched != negate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        let tests: &[(&str, &str, bool)] = &[
+            ("", "", true),
+            ("", "a", false),
+            ("a", "a", true),
+            ("a", "b", false),
+            ("*", "", true),
+            ("*", "abc", true),
+            (".note.*", ".note.gnu.build-id", true),
+            (".note.*", ".note", false),
+            (".note*", ".note", true),
+            ("*.text", ".rela.text", true),
+            ("*.text", ".text.hot", false),
+            ("*a*b", "xaxbxab", true),
+            ("*a*b", "xaxbxa", false),
+            ("a?c", "abc", true),
+            ("a?c", "ac", false),
+            ("[abc]", "b", true),
+            ("[abc]", "d", false),
+            ("[a-c]x", "cx", true),
+            ("[!a-c]x", "cx", false),
+            ("[^a-c]x", "dx", true),
+            ("[]]", "]", true),
+            ("[!]]", "]", false),
+            ("[a-", "[a-", true),
+            ("\\*", "*", true),
+            ("\\*", "a", false),
+        ];
+        for (pattern, name, expected) in tests {
+            assert_eq!(
+                glob_match(pattern.as_bytes(), name.as_bytes()),
+                *expected,
+                "{:?} {:?}",
+                pattern,
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,894 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::ops::Range;
+
+#[cfg(feature = "logging")]
+use log::info;
+use object::read::pe::{ImageNtHeaders, ImageOptionalHeader, SectionTable};
+use object::{build, pe, pod, LittleEndian as LE, SectionFlags, U16, U32};
+
+use super::{Error, NewSection, Result, Rewriter};
+
+/// Options for modifying a PE file.
+///
+/// This struct contains options for modifying a PE file. It is
+/// contained in the [`Options`](super::Options) struct.
+///
+/// Options are listed in the order they are processed.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct PeOptions {
+    /// Replace the names of imported DLLs.
+    ///
+    /// See [`Rewriter::pe_replace_dll`].
+    pub replace_dll: HashMap<Vec<u8>, Vec<u8>>,
+    /// Remove the debug directory.
+    ///
+    /// See [`Rewriter::pe_strip_debug`].
+    pub strip_debug: bool,
+    /// Update the checksum in the optional header.
+    ///
+    /// See [`Rewriter::pe_update_checksum`].
+    pub update_checksum: bool,
+}
+
+impl Rewriter<'_> {
+    pub(crate) fn pe_modify(&mut self, options: PeOptions) -> Result<()> {
+        if !options.replace_dll.is_empty() {
+            self.pe_replace_dll(&options.replace_dll)?;
+        }
+        if options.strip_debug {
+            self.pe_strip_debug()?;
+        }
+        if options.update_checksum {
+            self.pe_update_checksum()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pe_delete_sections(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        let names = self.name_set(names)?;
+        let Some(file) = self.format.pe_mut() else {
+            return Ok(());
+        };
+        for section in &mut file.sections {
+            if !section.delete && names.contains(&section.name) {
+                #[cfg(feature = "logging")]
+                info!("Deleting section {}", section.name);
+                section.delete = true;
+                self.modified = true;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pe_rename_sections(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let names = self.name_map(names)?;
+        let Some(file) = self.format.pe_mut() else {
+            return Ok(());
+        };
+        for section in &mut file.sections {
+            if let Some(name) = names.get(&section.name) {
+                let name = name.clone().into();
+                #[cfg(feature = "logging")]
+                info!("Renaming section {} to {}", section.name, name);
+                section.name = name;
+                self.modified = true;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pe_section_data(&self, name: &[u8]) -> Result<&[u8]> {
+        let file = self
+            .format
+            .pe()
+            .ok_or_else(|| Error::modify("Not a PE file; can't get section data"))?;
+        let section = file
+            .sections
+            .iter()
+            .find(|section| !section.delete && section.name.as_slice() == name)
+            .ok_or_else(|| {
+                Error::modify(format!(
+                    "Section {} not found",
+                    String::from_utf8_lossy(name)
+                ))
+            })?;
+        if let Some(data) = &section.data {
+            return Ok(data);
+        }
+        let header = &section.header;
+        let mut range = file_range(header);
+        range.end = range.end.min(range.start + virtual_size(header) as usize);
+        file.data.get(range).ok_or_else(|| {
+            Error::modify(format!("Invalid file range for section {}", section.name))
+        })
+    }
+
+    pub(crate) fn pe_update_sections(
+        &mut self,
+        sections: &HashMap<Vec<u8>, Vec<u8>>,
+    ) -> Result<()> {
+        let Some(file) = self.format.pe_mut() else {
+            return Ok(());
+        };
+        for (name, data) in sections {
+            let section = file
+                .sections
+                .iter_mut()
+                .find(|section| !section.delete && section.name.as_slice() == name.as_slice())
+                .ok_or_else(|| {
+                    Error::modify(format!(
+                        "Section {} not found",
+                        String::from_utf8_lossy(name)
+                    ))
+                })?;
+            #[cfg(feature = "logging")]
+            info!("Updating section {}", section.name);
+            section.data = Some(data.clone());
+            self.modified = true;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pe_add_sections(&mut self, sections: &[NewSection]) -> Result<()> {
+        let Some(file) = self.format.pe_mut() else {
+            return Ok(());
+        };
+        for new_section in sections {
+            let name = String::from_utf8_lossy(&new_section.name);
+            let exists = file
+                .sections
+                .iter()
+                .filter(|section| !section.delete)
+                .map(|section| section.name.as_slice())
+                .chain(file.new_sections.iter().map(|section| {
+                    let len = section.name.iter().position(|byte| *byte == 0);
+                    &section.name[..len.unwrap_or(section.name.len())]
+                }))
+                .any(|section_name| section_name == new_section.name);
+            if exists {
+                return Err(Error::modify(format!("Section {} already exists", name)));
+            }
+            if new_section.name.len() > pe::IMAGE_SIZEOF_SHORT_NAME {
+                return Err(Error::modify(format!("Section name {} is too long", name)));
+            }
+            if new_section.align > u64::from(file.section_alignment) {
+                return Err(Error::modify(format!(
+                    "Alignment of section {} is greater than the section alignment",
+                    name
+                )));
+            }
+            let characteristics = match new_section.flags {
+                SectionFlags::None => pe::IMAGE_SCN_CNT_INITIALIZED_DATA | pe::IMAGE_SCN_MEM_READ,
+                SectionFlags::Coff { characteristics } => characteristics,
+                _ => return Err(Error::modify("Invalid section flags for PE file")),
+            };
+            let mut section_name = [0; pe::IMAGE_SIZEOF_SHORT_NAME];
+            section_name[..new_section.name.len()].copy_from_slice(&new_section.name);
+            #[cfg(feature = "logging")]
+            info!("Adding section {}", name);
+            file.new_sections.push(NewPeSection {
+                name: section_name,
+                data: new_section.data.clone(),
+                characteristics,
+            });
+            self.modified = true;
+        }
+        Ok(())
+    }
+
+    /// Find the names of the imported DLLs.
+    ///
+    /// This includes the DLLs in both the import table and the delay-load
+    /// import table.
+    pub fn pe_dlls(&self) -> impl Iterator<Item = &[u8]> {
+        let dlls = self.format.pe().map(|file| &file.dlls[..]);
+        dlls.unwrap_or(&[]).iter().map(|dll| dll.name.as_slice())
+    }
+
+    /// Replace the names of imported DLLs.
+    ///
+    /// The `names` map is from old names to new names. Names are compared
+    /// ignoring ASCII case.
+    ///
+    /// This applies to both the import table and the delay-load import table.
+    /// A new name that is longer than the old name is placed in a new `.dllname`
+    /// section, which requires space for an extra section header. Any bound
+    /// import directory is removed when writing the file.
+    pub fn pe_replace_dll(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let file = self
+            .format
+            .pe_mut()
+            .ok_or_else(|| Error::modify("Not a PE file; can't replace DLL"))?;
+        for dll in &mut file.dlls {
+            let Some(new_name) = names
+                .iter()
+                .find_map(|(old, new)| old.eq_ignore_ascii_case(&dll.name).then_some(new))
+            else {
+                continue;
+            };
+
+            let new_name = new_name.clone().into();
+            #[cfg(feature = "logging")]
+            info!("Replacing DLL {} with {}", dll.name, new_name);
+            dll.name = new_name;
+            self.modified = true;
+        }
+        Ok(())
+    }
+
+    /// Remove the debug directory.
+    ///
+    /// This clears the `IMAGE_DIRECTORY_ENTRY_DEBUG` data directory, and
+    /// zeroes the debug directory entries and the data that they refer to.
+    /// The sections containing the data are not removed.
+    pub fn pe_strip_debug(&mut self) -> Result<()> {
+        let file = self
+            .format
+            .pe_mut()
+            .ok_or_else(|| Error::modify("Not a PE file; can't strip debug directory"))?;
+        let Some(dir) = file
+            .data_directories
+            .get(pe::IMAGE_DIRECTORY_ENTRY_DEBUG)
+            .filter(|dir| dir.virtual_address.get(LE) != 0)
+        else {
+            return Ok(());
+        };
+
+        let (offset, size) = dir
+            .file_range(&file.section_table)
+            .map_err(|e| Error::modify(format!("Invalid PE debug directory: {}", e)))?;
+        let offset = offset as usize;
+        let size = size as usize;
+        let entries = file
+            .data
+            .get(offset..offset + size)
+            .and_then(|data| pod::slice_from_all_bytes::<pe::ImageDebugDirectory>(data).ok())
+            .ok_or_else(|| Error::modify("Invalid PE debug directory size"))?;
+        let mut ranges = Vec::new();
+        ranges.push(offset..offset + size);
+        for entry in entries {
+            let offset = entry.pointer_to_raw_data.get(LE) as usize;
+            let size = entry.size_of_data.get(LE) as usize;
+            if offset != 0 && offset + size <= file.data.len() {
+                ranges.push(offset..offset + size);
+            }
+        }
+        #[cfg(feature = "logging")]
+        info!("Stripping {} debug directory entries", entries.len());
+        file.debug_ranges = ranges;
+        file.strip_debug = true;
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Update the checksum in the optional header when writing the file.
+    ///
+    /// The checksum is always updated when writing a modified file if the
+    /// original checksum is non-zero.
+    pub fn pe_update_checksum(&mut self) -> Result<()> {
+        let file = self
+            .format
+            .pe_mut()
+            .ok_or_else(|| Error::modify("Not a PE file; can't update checksum"))?;
+        file.update_checksum = true;
+        self.modified = true;
+        Ok(())
+    }
+}
+
+// Offsets of fields in the file header.
+const FILE_NUMBER_OF_SECTIONS: usize = 2;
+const FILE_CHARACTERISTICS: usize = 18;
+// Offsets of fields in the optional header. These are the same for PE32 and PE32+.
+const OPTIONAL_SIZE_OF_CODE: usize = 4;
+const OPTIONAL_SIZE_OF_INITIALIZED_DATA: usize = 8;
+const OPTIONAL_SIZE_OF_UNINITIALIZED_DATA: usize = 12;
+const OPTIONAL_SIZE_OF_IMAGE: usize = 56;
+const OPTIONAL_CHECK_SUM: usize = 64;
+// Offset of the name field in the import descriptors.
+const IMPORT_TIME_DATE_STAMP: usize = 4;
+const IMPORT_NAME: usize = 12;
+const DELAYLOAD_DLL_NAME_RVA: usize = 4;
+
+/// A PE file that is being rewritten.
+///
+/// Only the section headers, data directories and DLL names can be modified.
+/// All other data is copied from the original file.
+#[derive(Debug)]
+pub(crate) struct PeFile<'data> {
+    data: &'data [u8],
+    file_header_offset: usize,
+    optional_header_offset: usize,
+    data_directories_offset: usize,
+    section_table_offset: usize,
+    /// The end of the space that is available for section headers.
+    section_table_end: usize,
+    section_table: SectionTable<'data>,
+    section_alignment: u32,
+    file_alignment: u32,
+    size_of_headers: u32,
+    check_sum: u32,
+    data_directories: Vec<pe::ImageDataDirectory>,
+    sections: Vec<Section<'data>>,
+    dlls: Vec<Dll<'data>>,
+    new_sections: Vec<NewPeSection>,
+    debug_ranges: Vec<Range<usize>>,
+    strip_debug: bool,
+    update_checksum: bool,
+}
+
+#[derive(Debug)]
+struct Section<'data> {
+    name: build::ByteString<'data>,
+    header: pe::ImageSectionHeader,
+    /// The updated contents of the section.
+    data: Option<Vec<u8>>,
+    delete: bool,
+}
+
+#[derive(Debug)]
+struct NewPeSection {
+    name: [u8; pe::IMAGE_SIZEOF_SHORT_NAME],
+    data: Vec<u8>,
+    characteristics: u32,
+}
+
+#[derive(Debug)]
+struct Dll<'data> {
+    /// The file offset of the import descriptor.
+    descriptor_offset: usize,
+    /// Whether the descriptor is in the delay-load import table.
+    delay_load: bool,
+    /// The file offset of the original name.
+    name_offset: usize,
+    original_name: &'data [u8],
+    name: build::ByteString<'data>,
+}
+
+impl<'data> PeFile<'data> {
+    /// Read a PE file.
+    pub(crate) fn read<Pe: ImageNtHeaders>(data: &'data [u8]) -> build::Result<Self> {
+        let file = object::read::pe::PeFile::<Pe>::parse(data)?;
+        let nt_headers = file.nt_headers();
+        let file_header = nt_headers.file_header();
+        let optional_header = nt_headers.optional_header();
+        let file_header_offset =
+            file.dos_header().nt_headers_offset() as usize + mem::size_of::<u32>();
+        let optional_header_offset = file_header_offset + mem::size_of::<pe::ImageFileHeader>();
+        let data_directories_offset =
+            optional_header_offset + mem::size_of::<Pe::ImageOptionalHeader>();
+        let section_table_offset =
+            optional_header_offset + usize::from(file_header.size_of_optional_header.get(LE));
+        let section_table = file.section_table();
+        let data_directories = file.data_directories();
+
+        let mut section_table_end = optional_header.size_of_headers() as usize;
+        let mut sections = Vec::new();
+        for header in section_table.iter() {
+            let pointer = header.pointer_to_raw_data.get(LE) as usize;
+            if pointer != 0 && header.size_of_raw_data.get(LE) != 0 {
+                section_table_end = section_table_end.min(pointer);
+            }
+            sections.push(Section {
+                name: header.raw_name().into(),
+                header: *header,
+                data: None,
+                delete: false,
+            });
+        }
+
+        let mut dlls = Vec::new();
+        if let Some(import_table) = data_directories.import_table(data, &section_table)? {
+            let dir = data_directories
+                .get(pe::IMAGE_DIRECTORY_ENTRY_IMPORT)
+                .unwrap();
+            let (offset, _) = dir.file_range(&section_table)?;
+            let mut descriptor_offset = offset as usize;
+            let mut descriptors = import_table.descriptors()?;
+            while let Some(descriptor) = descriptors.next()? {
+                let address = descriptor.name.get(LE);
+                let Some((name_offset, name)) = read_name(data, &section_table, address) else {
+                    import_table.name(address)?;
+                    continue;
+                };
+                dlls.push(Dll {
+                    descriptor_offset,
+                    delay_load: false,
+                    name_offset,
+                    original_name: name,
+                    name: name.into(),
+                });
+                descriptor_offset += mem::size_of::<pe::ImageImportDescriptor>();
+            }
+        }
+        if let Some(import_table) =
+            data_directories.delay_load_import_table(data, &section_table)?
+        {
+            let dir = data_directories
+                .get(pe::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT)
+                .unwrap();
+            let (offset, _) = dir.file_range(&section_table)?;
+            let mut descriptor_offset = offset as usize;
+            let mut descriptors = import_table.descriptors()?;
+            while let Some(descriptor) = descriptors.next()? {
+                let address = descriptor.dll_name_rva.get(LE);
+                let Some((name_offset, name)) = read_name(data, &section_table, address) else {
+                    import_table.name(address)?;
+                    continue;
+                };
+                dlls.push(Dll {
+                    descriptor_offset,
+                    delay_load: true,
+                    name_offset,
+                    original_name: name,
+                    name: name.into(),
+                });
+                descriptor_offset += mem::size_of::<pe::ImageDelayloadDescriptor>();
+            }
+        }
+
+        Ok(PeFile {
+            data,
+            file_header_offset,
+            optional_header_offset,
+            data_directories_offset,
+            section_table_offset,
+            section_table_end,
+            section_table,
+            section_alignment: optional_header.section_alignment().max(1),
+            file_alignment: optional_header.file_alignment().max(1),
+            size_of_headers: optional_header.size_of_headers(),
+            check_sum: optional_header.check_sum(),
+            data_directories: data_directories.iter().copied().collect(),
+            sections,
+            dlls,
+            new_sections: Vec::new(),
+            debug_ranges: Vec::new(),
+            strip_debug: false,
+            update_checksum: false,
+        })
+    }
+
+    /// Return the original file data.
+    pub(crate) fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Write the modified file.
+    ///
+    /// Any certificate table is removed, since it is no longer valid.
+    pub(crate) fn write(&self) -> Result<Vec<u8>> {
+        let mut data = self.data.to_vec();
+        let mut directories = self.data_directories.clone();
+        let optional_header = self.optional_header_offset;
+        let mut size_of_code = read_u32(&data, optional_header + OPTIONAL_SIZE_OF_CODE);
+        let mut size_of_initialized_data =
+            read_u32(&data, optional_header + OPTIONAL_SIZE_OF_INITIALIZED_DATA);
+        let mut size_of_uninitialized_data =
+            read_u32(&data, optional_header + OPTIONAL_SIZE_OF_UNINITIALIZED_DATA);
+        let mut size_of_image = read_u32(&data, optional_header + OPTIONAL_SIZE_OF_IMAGE);
+        let mut characteristics = read_u16(&data, self.file_header_offset + FILE_CHARACTERISTICS);
+
+        if self.strip_debug {
+            for range in &self.debug_ranges {
+                data[range.clone()].fill(0);
+            }
+            clear_directory(&mut directories, pe::IMAGE_DIRECTORY_ENTRY_DEBUG);
+        }
+
+        // Delete sections and the data directories that they contain.
+        if let Some(section) = self.sections.first().filter(|section| section.delete) {
+            return Err(Error::modify(format!(
+                "Can't delete first section {}",
+                section.name
+            )));
+        }
+        let mut truncate = Vec::new();
+        for section in self.sections.iter().filter(|section| section.delete) {
+            let header = &section.header;
+            let range = file_range(header);
+            if range.end <= data.len() {
+                data[range.clone()].fill(0);
+                truncate.push(range);
+            }
+            let size = header.size_of_raw_data.get(LE);
+            let flags = header.characteristics.get(LE);
+            if flags & pe::IMAGE_SCN_CNT_CODE != 0 {
+                size_of_code = size_of_code.saturating_sub(size);
+            }
+            if flags & pe::IMAGE_SCN_CNT_INITIALIZED_DATA != 0 {
+                size_of_initialized_data = size_of_initialized_data.saturating_sub(size);
+            }
+            if flags & pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0 {
+                size_of_uninitialized_data =
+                    size_of_uninitialized_data.saturating_sub(virtual_size(header));
+            }
+
+            let address = header.virtual_address.get(LE);
+            let address_range = address..address + virtual_size(header);
+            for (index, dir) in directories.iter_mut().enumerate() {
+                if index == pe::IMAGE_DIRECTORY_ENTRY_SECURITY
+                    || !address_range.contains(&dir.virtual_address.get(LE))
+                {
+                    continue;
+                }
+                #[cfg(feature = "logging")]
+                info!(
+                    "Removing data directory {} in section {}",
+                    index, section.name
+                );
+                *dir = empty_directory();
+                if index == pe::IMAGE_DIRECTORY_ENTRY_BASERELOC {
+                    characteristics |= pe::IMAGE_FILE_RELOCS_STRIPPED;
+                }
+            }
+        }
+
+        // Keep the sections contiguous in memory by extending the previous section
+        // to cover any gap.
+        let mut headers: Vec<pe::ImageSectionHeader> = Vec::new();
+        let mut updates = Vec::new();
+        let mut deleted = false;
+        for section in &self.sections {
+            if section.delete {
+                deleted = true;
+                continue;
+            }
+            if deleted {
+                let previous = headers.last_mut().unwrap();
+                let size =
+                    section.header.virtual_address.get(LE) - previous.virtual_address.get(LE);
+                previous.virtual_size.set(LE, size);
+                deleted = false;
+            }
+            let mut header = section.header;
+            if section.name.as_slice() != header.raw_name() {
+                if section.name.len() > pe::IMAGE_SIZEOF_SHORT_NAME {
+                    return Err(Error::modify(format!(
+                        "Section name {} is too long",
+                        section.name
+                    )));
+                }
+                header.name = [0; pe::IMAGE_SIZEOF_SHORT_NAME];
+                header.name[..section.name.len()].copy_from_slice(&section.name);
+            }
+            if let Some(contents) = &section.data {
+                // Move the contents to the end of the file if they don't fit.
+                let range = file_range(&header);
+                let moved = contents.len() > range.len() || range.end > data.len();
+                if moved && range.end <= data.len() {
+                    data[range.clone()].fill(0);
+                    truncate.push(range);
+                }
+                header.virtual_size.set(LE, contents.len() as u32);
+                updates.push((headers.len(), section, contents, moved));
+            }
+            headers.push(header);
+        }
+        for (index, section, contents, _) in &updates {
+            let header = &headers[*index];
+            if let Some(next) = headers.get(index + 1) {
+                let size = next.virtual_address.get(LE) - header.virtual_address.get(LE);
+                if contents.len() as u64 > u64::from(size) {
+                    return Err(Error::modify(format!(
+                        "Contents of section {} can't be larger than {:#x} bytes",
+                        section.name, size
+                    )));
+                }
+            }
+        }
+        if deleted || updates.iter().any(|update| update.0 == headers.len() - 1) {
+            let last = headers.last().unwrap();
+            let end = last.virtual_address.get(LE) + virtual_size(last);
+            size_of_image = align_u32(end, self.section_alignment);
+        }
+
+        // Remove the certificate table.
+        if let Some(dir) = clear_directory(&mut directories, pe::IMAGE_DIRECTORY_ENTRY_SECURITY) {
+            #[cfg(feature = "logging")]
+            info!("Removing certificate table");
+            let offset = dir.virtual_address.get(LE) as usize;
+            let end = offset + dir.size.get(LE) as usize;
+            if end <= data.len() {
+                data[offset..end].fill(0);
+                truncate.push(offset..end);
+            }
+        }
+
+        // Truncate any removed data at the end of the file.
+        truncate.sort_by_key(|range| range.start);
+        while let Some(range) = truncate.pop() {
+            if range.end >= data.len() && range.start < data.len() {
+                data.truncate(range.start);
+            }
+        }
+
+        // Write the updated section contents.
+        for (index, _, contents, moved) in updates {
+            let header = &mut headers[index];
+            if moved {
+                let old_size = header.size_of_raw_data.get(LE);
+                let size = align_u32(contents.len() as u32, self.file_alignment);
+                let offset = align(data.len(), self.file_alignment as usize);
+                data.resize(offset, 0);
+                data.extend_from_slice(contents);
+                data.resize(offset + size as usize, 0);
+                header.pointer_to_raw_data.set(LE, offset as u32);
+                header.size_of_raw_data.set(LE, size);
+                let flags = header.characteristics.get(LE);
+                if flags & pe::IMAGE_SCN_CNT_CODE != 0 {
+                    size_of_code = size_of_code.saturating_sub(old_size) + size;
+                }
+                if flags & pe::IMAGE_SCN_CNT_INITIALIZED_DATA != 0 {
+                    size_of_initialized_data =
+                        size_of_initialized_data.saturating_sub(old_size) + size;
+                }
+            } else {
+                let range = file_range(header);
+                data[range.clone()].fill(0);
+                data[range.start..][..contents.len()].copy_from_slice(contents);
+            }
+        }
+
+        // Replace DLL names, and place the names that don't fit in a new section.
+        let mut names = Vec::new();
+        let mut name_offsets = Vec::new();
+        let mut replaced = false;
+        for dll in &self.dlls {
+            if dll.name.as_slice() == dll.original_name {
+                continue;
+            }
+            replaced = true;
+            if dll.name.len() <= dll.original_name.len() {
+                let name = &mut data[dll.name_offset..][..dll.original_name.len()];
+                name.fill(0);
+                name[..dll.name.len()].copy_from_slice(&dll.name);
+            } else {
+                name_offsets.push((dll, names.len() as u32));
+                names.extend_from_slice(&dll.name);
+                names.push(0);
+                names.resize(align(names.len(), 2), 0);
+            }
+            if !dll.delay_load {
+                write_u32(&mut data, dll.descriptor_offset + IMPORT_TIME_DATE_STAMP, 0);
+            }
+        }
+        if replaced {
+            if let Some(dir) =
+                clear_directory(&mut directories, pe::IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT)
+            {
+                #[cfg(feature = "logging")]
+                info!("Removing bound import directory");
+                // The bound import directory is normally in the headers, where
+                // its address is also its file offset.
+                let offset = dir.virtual_address.get(LE) as usize;
+                let end = offset + dir.size.get(LE) as usize;
+                if end <= self.size_of_headers as usize {
+                    data[offset..end].fill(0);
+                }
+            }
+        }
+        // Append the new sections, starting with any DLL names that didn't fit.
+        let mut new_sections = Vec::new();
+        if !names.is_empty() {
+            new_sections.push((
+                *b".dllname",
+                &names[..],
+                pe::IMAGE_SCN_CNT_INITIALIZED_DATA | pe::IMAGE_SCN_MEM_READ,
+            ));
+        }
+        for section in &self.new_sections {
+            new_sections.push((section.name, &section.data[..], section.characteristics));
+        }
+        // The headers can reuse the space of deleted section headers, otherwise
+        // the space after the section table must be unused.
+        let count = headers.len() + new_sections.len();
+        if count > self.sections.len() {
+            let start =
+                self.section_table_offset + self.sections.len() * pe::IMAGE_SIZEOF_SECTION_HEADER;
+            let end = self.section_table_offset + count * pe::IMAGE_SIZEOF_SECTION_HEADER;
+            if end > self.section_table_end || data[start..end].iter().any(|byte| *byte != 0) {
+                return Err(Error::modify("Not enough space for section headers"));
+            }
+        }
+        let mut names_address = 0;
+        for (name, contents, characteristics) in new_sections {
+            let last = headers.last().unwrap();
+            let address = align_u32(
+                last.virtual_address.get(LE) + virtual_size(last),
+                self.section_alignment,
+            );
+            let size = contents.len() as u32;
+            let size_of_raw_data = align_u32(size, self.file_alignment);
+            let offset = if size != 0 {
+                let offset = align(data.len(), self.file_alignment as usize);
+                data.resize(offset, 0);
+                data.extend_from_slice(contents);
+                data.resize(offset + size_of_raw_data as usize, 0);
+                offset
+            } else {
+                0
+            };
+            #[cfg(feature = "logging")]
+            info!(
+                "Adding section {} at {:#x}",
+                String::from_utf8_lossy(&name).trim_end_matches('\0'),
+                address
+            );
+            headers.push(pe::ImageSectionHeader {
+                name,
+                virtual_size: U32::new(LE, size),
+                virtual_address: U32::new(LE, address),
+                size_of_raw_data: U32::new(LE, size_of_raw_data),
+                pointer_to_raw_data: U32::new(LE, offset as u32),
+                pointer_to_relocations: U32::new(LE, 0),
+                pointer_to_linenumbers: U32::new(LE, 0),
+                number_of_relocations: U16::new(LE, 0),
+                number_of_linenumbers: U16::new(LE, 0),
+                characteristics: U32::new(LE, characteristics),
+            });
+            size_of_image = align_u32(address + size.max(1), self.section_alignment);
+            if characteristics & pe::IMAGE_SCN_CNT_CODE != 0 {
+                size_of_code += size_of_raw_data;
+            }
+            if characteristics & pe::IMAGE_SCN_CNT_INITIALIZED_DATA != 0 {
+                size_of_initialized_data += size_of_raw_data;
+            }
+            if characteristics & pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0 {
+                size_of_uninitialized_data += size;
+            }
+            if names_address == 0 {
+                names_address = address;
+            }
+        }
+        for (dll, name_offset) in name_offsets {
+            let field = if dll.delay_load {
+                DELAYLOAD_DLL_NAME_RVA
+            } else {
+                IMPORT_NAME
+            };
+            write_u32(
+                &mut data,
+                dll.descriptor_offset + field,
+                names_address + name_offset,
+            );
+        }
+
+        // Write the headers.
+        let table_size = self.sections.len().max(headers.len()) * pe::IMAGE_SIZEOF_SECTION_HEADER;
+        let table = &mut data[self.section_table_offset..][..table_size];
+        table.fill(0);
+        for (header, bytes) in headers
+            .iter()
+            .zip(table.chunks_exact_mut(pe::IMAGE_SIZEOF_SECTION_HEADER))
+        {
+            bytes.copy_from_slice(pod::bytes_of(header));
+        }
+        let number_of_sections = self.file_header_offset + FILE_NUMBER_OF_SECTIONS;
+        data[number_of_sections..][..2].copy_from_slice(&(headers.len() as u16).to_le_bytes());
+        data[self.file_header_offset + FILE_CHARACTERISTICS..][..2]
+            .copy_from_slice(&characteristics.to_le_bytes());
+        let directories_size = directories.len() * mem::size_of::<pe::ImageDataDirectory>();
+        data[self.data_directories_offset..][..directories_size]
+            .copy_from_slice(pod::bytes_of_slice(&directories));
+        write_u32(
+            &mut data,
+            optional_header + OPTIONAL_SIZE_OF_CODE,
+            size_of_code,
+        );
+        write_u32(
+            &mut data,
+            optional_header + OPTIONAL_SIZE_OF_INITIALIZED_DATA,
+            size_of_initialized_data,
+        );
+        write_u32(
+            &mut data,
+            optional_header + OPTIONAL_SIZE_OF_UNINITIALIZED_DATA,
+            size_of_uninitialized_data,
+        );
+        write_u32(
+            &mut data,
+            optional_header + OPTIONAL_SIZE_OF_IMAGE,
+            size_of_image,
+        );
+
+        if self.update_checksum || self.check_sum != 0 {
+            let offset = optional_header + OPTIONAL_CHECK_SUM;
+            write_u32(&mut data, offset, 0);
+            let check_sum = check_sum(&data);
+            #[cfg(feature = "logging")]
+            info!("Setting checksum to {:#x}", check_sum);
+            write_u32(&mut data, offset, check_sum);
+        }
+        Ok(data)
+    }
+}
+
+/// Read a null-terminated DLL name and return its file offset.
+///
+/// Unlike the import table parser, this allows the name to be in a different
+/// section from the import descriptors, which is the case for names that were
+/// previously replaced.
+fn read_name<'data>(
+    data: &'data [u8],
+    section_table: &SectionTable<'data>,
+    address: u32,
+) -> Option<(usize, &'data [u8])> {
+    let (offset, size) = section_table.pe_file_range_at(address)?;
+    let offset = offset as usize;
+    let bytes = data.get(offset..)?.get(..size as usize)?;
+    let len = bytes.iter().position(|byte| *byte == 0)?;
+    Some((offset, &bytes[..len]))
+}
+
+/// Clear a data directory, and return its original value if it was present.
+fn clear_directory(
+    directories: &mut [pe::ImageDataDirectory],
+    index: usize,
+) -> Option<pe::ImageDataDirectory> {
+    let dir = directories.get_mut(index)?;
+    if dir.virtual_address.get(LE) == 0 {
+        return None;
+    }
+    Some(mem::replace(dir, empty_directory()))
+}
+
+fn empty_directory() -> pe::ImageDataDirectory {
+    pe::ImageDataDirectory {
+        virtual_address: U32::new(LE, 0),
+        size: U32::new(LE, 0),
+    }
+}
+
+fn file_range(header: &pe::ImageSectionHeader) -> Range<usize> {
+    let offset = header.pointer_to_raw_data.get(LE) as usize;
+    if offset == 0 {
+        return 0..0;
+    }
+    offset..offset + header.size_of_raw_data.get(LE) as usize
+}
+
+fn virtual_size(header: &pe::ImageSectionHeader) -> u32 {
+    match header.virtual_size.get(LE) {
+        0 => header.size_of_raw_data.get(LE),
+        size => size,
+    }
+}
+
+/// Calculate the checksum of a PE file.
+///
+/// The checksum field in the optional header must be zero.
+fn check_sum(data: &[u8]) -> u32 {
+    let mut sum: u32 = 0;
+    for word in data.chunks(2) {
+        let word = u32::from(word[0]) | u32::from(word.get(1).copied().unwrap_or(0)) << 8;
+        sum += word;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    sum = (sum & 0xffff) + (sum >> 16);
+    sum.wrapping_add(data.len() as u32)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[offset..][..4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..][..4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn align(offset: usize, align: usize) -> usize {
+    (offset + (align - 1)) & !(align - 1)
+}
+
+fn align_u32(offset: u32, align: u32) -> u32 {
+    (offset + (align - 1)) & !(align - 1)
+}
@@ -0,0 +1,488 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use object::{build, Object, ObjectSection, ObjectSymbol, SymbolKind, SymbolScope};
+
+use super::{Error, Result};
+
+/// A report of the changes made by a [`Rewriter`](super::Rewriter).
+///
+/// This is created by comparing the original file with the rewritten file.
+/// Sections and symbols are matched by name. A removed and an added item
+/// with the same properties are reported as a rename.
+///
+/// The [`fmt::Display`] implementation writes one change per line.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Report {
+    /// The size of the original file.
+    pub old_size: u64,
+    /// The size of the rewritten file.
+    pub new_size: u64,
+    /// The changes to sections.
+    pub sections: Vec<SectionChange>,
+    /// The changes to symbols.
+    pub symbols: Vec<SymbolChange>,
+}
+
+/// A change to a section.
+///
+/// This is used in [`Report::sections`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SectionChange {
+    /// A section was added.
+    Added {
+        /// The name of the section.
+        name: Vec<u8>,
+        /// The layout of the section.
+        layout: SectionLayout,
+    },
+    /// A section was removed.
+    Removed {
+        /// The name of the section.
+        name: Vec<u8>,
+        /// The layout of the section in the original file.
+        layout: SectionLayout,
+    },
+    /// A section was renamed.
+    Renamed {
+        /// The name of the section in the original file.
+        old_name: Vec<u8>,
+        /// The name of the section in the rewritten file.
+        new_name: Vec<u8>,
+    },
+    /// The layout or contents of a section changed.
+    Changed {
+        /// The name of the section.
+        name: Vec<u8>,
+        /// The layout of the section in the original file.
+        old: SectionLayout,
+        /// The layout of the section in the rewritten file.
+        new: SectionLayout,
+        /// Whether the contents of the section changed.
+        contents_changed: bool,
+    },
+}
+
+/// The layout of a section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SectionLayout {
+    /// The address of the section.
+    pub address: u64,
+    /// The size of the section in memory.
+    pub size: u64,
+    /// The offset and size of the section in the file, if any.
+    pub file_range: Option<(u64, u64)>,
+}
+
+/// A change to a symbol.
+///
+/// This is used in [`Report::symbols`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SymbolChange {
+    /// A symbol was added.
+    Added {
+        /// The name of the symbol.
+        name: Vec<u8>,
+        /// Whether the symbol is in the dynamic symbol table.
+        dynamic: bool,
+    },
+    /// A symbol was removed.
+    Removed {
+        /// The name of the symbol.
+        name: Vec<u8>,
+        /// Whether the symbol is in the dynamic symbol table.
+        dynamic: bool,
+    },
+    /// A symbol was renamed.
+    Renamed {
+        /// The name of the symbol in the original file.
+        old_name: Vec<u8>,
+        /// The name of the symbol in the rewritten file.
+        new_name: Vec<u8>,
+        /// Whether the symbol is in the dynamic symbol table.
+        dynamic: bool,
+    },
+    /// The properties of a symbol changed.
+    Changed {
+        /// The name of the symbol.
+        name: Vec<u8>,
+        /// Whether the symbol is in the dynamic symbol table.
+        dynamic: bool,
+        /// The properties of the symbol in the original file.
+        old: SymbolInfo,
+        /// The properties of the symbol in the rewritten file.
+        new: SymbolInfo,
+    },
+}
+
+/// The properties of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SymbolInfo {
+    /// The address of the symbol.
+    pub address: u64,
+    /// The size of the symbol.
+    pub size: u64,
+    /// The kind of the symbol.
+    pub kind: SymbolKind,
+    /// The scope of the symbol.
+    pub scope: SymbolScope,
+    /// Whether the symbol is weak.
+    pub weak: bool,
+    /// Whether the symbol is undefined.
+    pub undefined: bool,
+}
+
+impl Report {
+    /// Create a report of the changes between two files.
+    ///
+    /// If `new_data` is not an object file, such as for
+    /// [`OutputFormat::Binary`](super::OutputFormat::Binary), then only the
+    /// sizes are reported.
+    pub fn new(old_data: &[u8], new_data: &[u8]) -> Result<Report> {
+        let mut report = Report {
+            old_size: old_data.len() as u64,
+            new_size: new_data.len() as u64,
+            ..Default::default()
+        };
+        let old = object::File::parse(old_data).map_err(|e| Error::parse(e.into()))?;
+        let Ok(new) = object::File::parse(new_data) else {
+            return Ok(report);
+        };
+
+        let (old_sections, new_sections) = (sections(&old), sections(&new));
+        let changes = diff(&old_sections, &new_sections)
+            .into_iter()
+            .map(|(name, old, new)| match (old, new) {
+                (Some(old), None) => SectionChange::Removed {
+                    name,
+                    layout: old.0,
+                },
+                (None, Some(new)) => SectionChange::Added {
+                    name,
+                    layout: new.0,
+                },
+                (Some(old), Some(new)) => SectionChange::Changed {
+                    name,
+                    old: old.0,
+                    new: new.0,
+                    contents_changed: old.1 != new.1,
+                },
+                (None, None) => unreachable!(),
+            })
+            .collect();
+        // File offsets are likely to change for renamed sections, so ignore them.
+        let rename_key = |layout: &SectionLayout| {
+            (
+                layout.address,
+                layout.size,
+                layout.file_range.map(|(_, size)| size),
+            )
+        };
+        report.sections = pair_renames(changes, |change| match change {
+            SectionChange::Removed { name, layout } => Some((name, rename_key(layout), false)),
+            SectionChange::Added { name, layout } => Some((name, rename_key(layout), true)),
+            _ => None,
+        })
+        .into_iter()
+        .map(|change| match change {
+            Paired::Renamed(old_name, new_name) => SectionChange::Renamed { old_name, new_name },
+            Paired::Other(change) => change,
+        })
+        .collect();
+
+        for dynamic in [false, true] {
+            let (old_symbols, new_symbols) = if dynamic {
+                (
+                    symbols(old.dynamic_symbols()),
+                    symbols(new.dynamic_symbols()),
+                )
+            } else {
+                (symbols(old.symbols()), symbols(new.symbols()))
+            };
+            let changes = diff(&old_symbols, &new_symbols)
+                .into_iter()
+                .map(|(name, old, new)| match (old, new) {
+                    (Some(old), None) => (SymbolChange::Removed { name, dynamic }, *old),
+                    (None, Some(new)) => (SymbolChange::Added { name, dynamic }, *new),
+                    (Some(old), Some(new)) => (
+                        SymbolChange::Changed {
+                            name,
+                            dynamic,
+                            old: *old,
+                            new: *new,
+                        },
+                        *new,
+                    ),
+                    (None, None) => unreachable!(),
+                })
+                .collect();
+            report.symbols.extend(
+                pair_renames(changes, |(change, info)| match change {
+                    SymbolChange::Removed { name, .. } => Some((name, *info, false)),
+                    SymbolChange::Added { name, .. } => Some((name, *info, true)),
+                    _ => None,
+                })
+                .into_iter()
+                .map(|change| match change {
+                    Paired::Renamed(old_name, new_name) => SymbolChange::Renamed {
+                        old_name,
+                        new_name,
+                        dynamic,
+                    },
+                    Paired::Other((change, _)) => change,
+                }),
+            );
+        }
+        Ok(report)
+    }
+
+    /// Return true if the report contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.old_size == self.new_size && self.sections.is_empty() && self.symbols.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.sections {
+            match change {
+                SectionChange::Added { name, layout } => {
+                    writeln!(f, "Added section {} ({})", bytes(name), layout)?;
+                }
+                SectionChange::Removed { name, layout } => {
+                    writeln!(f, "Removed section {} ({})", bytes(name), layout)?;
+                }
+                SectionChange::Renamed { old_name, new_name } => {
+                    writeln!(
+                        f,
+                        "Renamed section {} to {}",
+                        bytes(old_name),
+                        bytes(new_name)
+                    )?;
+                }
+                SectionChange::Changed {
+                    name,
+                    old,
+                    new,
+                    contents_changed,
+                } => {
+                    write!(f, "Changed section {}", bytes(name))?;
+                    if old != new {
+                        write!(f, " ({} -> {})", old, new)?;
+                    }
+                    if *contents_changed {
+                        write!(f, " contents")?;
+                    }
+                    writeln!(f)?;
+                }
+            }
+        }
+        for change in &self.symbols {
+            let dynamic = |dynamic: bool| if dynamic { "dynamic symbol" } else { "symbol" };
+            match change {
+                SymbolChange::Added { name, dynamic: d } => {
+                    writeln!(f, "Added {} {}", dynamic(*d), bytes(name))?;
+                }
+                SymbolChange::Removed { name, dynamic: d } => {
+                    writeln!(f, "Removed {} {}", dynamic(*d), bytes(name))?;
+                }
+                SymbolChange::Renamed {
+                    old_name,
+                    new_name,
+                    dynamic: d,
+                } => {
+                    writeln!(
+                        f,
+                        "Renamed {} {} to {}",
+                        dynamic(*d),
+                        bytes(old_name),
+                        bytes(new_name)
+                    )?;
+                }
+                SymbolChange::Changed {
+                    name,
+                    dynamic: d,
+                    old,
+                    new,
+                } => {
+                    writeln!(
+                        f,
+                        "Changed {} {} ({} -> {})",
+                        dynamic(*d),
+                        bytes(name),
+                        old,
+                        new
+                    )?;
+                }
+            }
+        }
+        writeln!(
+            f,
+            "File size 0x{:x} -> 0x{:x} ({:+} bytes)",
+            self.old_size,
+            self.new_size,
+            self.new_size as i128 - self.old_size as i128
+        )
+    }
+}
+
+impl fmt::Display for SectionLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "address 0x{:x}, size 0x{:x}", self.address, self.size)?;
+        if let Some((offset, size)) = self.file_range {
+            write!(f, ", file range 0x{:x}+0x{:x}", offset, size)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SymbolInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "address 0x{:x}, size 0x{:x}, {:?}, {:?}",
+            self.address, self.size, self.kind, self.scope
+        )?;
+        if self.weak {
+            write!(f, ", weak")?;
+        }
+        if self.undefined {
+            write!(f, ", undefined")?;
+        }
+        Ok(())
+    }
+}
+
+fn bytes(name: &[u8]) -> build::ByteString<'_> {
+    build::ByteString::from(name)
+}
+
+/// The layout and contents of a section.
+type SectionInfo<'data> = (SectionLayout, &'data [u8]);
+
+/// The layout and contents of each named section.
+fn sections<'data>(file: &object::File<'data>) -> Vec<(Vec<u8>, SectionInfo<'data>)> {
+    file.sections()
+        .filter_map(|section| {
+            let name = section.name_bytes().ok()?;
+            if name.is_empty() {
+                return None;
+            }
+            let layout = SectionLayout {
+                address: section.address(),
+                size: section.size(),
+                file_range: section.file_range(),
+            };
+            Some((name.to_vec(), (layout, section.data().unwrap_or(&[]))))
+        })
+        .collect()
+}
+
+/// The properties of each named symbol.
+fn symbols<'data, I, S>(symbols: I) -> Vec<(Vec<u8>, SymbolInfo)>
+where
+    I: Iterator<Item = S>,
+    S: ObjectSymbol<'data>,
+{
+    symbols
+        .filter_map(|symbol| {
+            let name = symbol.name_bytes().ok()?;
+            if name.is_empty() || symbol.kind() == SymbolKind::Section {
+                return None;
+            }
+            let info = SymbolInfo {
+                address: symbol.address(),
+                size: symbol.size(),
+                kind: symbol.kind(),
+                scope: symbol.scope(),
+                weak: symbol.is_weak(),
+                undefined: symbol.is_undefined(),
+            };
+            Some((name.to_vec(), info))
+        })
+        .collect()
+}
+
+/// Match the old and new items by name, and return the items that differ.
+///
+/// Items with duplicate names are matched in order.
+fn diff<'a, T: PartialEq>(
+    old: &'a [(Vec<u8>, T)],
+    new: &'a [(Vec<u8>, T)],
+) -> Vec<(Vec<u8>, Option<&'a T>, Option<&'a T>)> {
+    let mut new_by_name = HashMap::<&[u8], VecDeque<usize>>::new();
+    for (i, (name, _)) in new.iter().enumerate() {
+        new_by_name.entry(name).or_default().push_back(i);
+    }
+    let mut matched = vec![false; new.len()];
+    let mut result = Vec::new();
+    for (name, old_value) in old {
+        let new_value = new_by_name
+            .get_mut(name.as_slice())
+            .and_then(VecDeque::pop_front)
+            .map(|i| {
+                matched[i] = true;
+                &new[i].1
+            });
+        if new_value != Some(old_value) {
+            result.push((name.clone(), Some(old_value), new_value));
+        }
+    }
+    for ((name, value), matched) in new.iter().zip(matched) {
+        if !matched {
+            result.push((name.clone(), None, Some(value)));
+        }
+    }
+    result
+}
+
+enum Paired<T> {
+    Renamed(Vec<u8>, Vec<u8>),
+    Other(T),
+}
+
+/// Pair removed items with added items that have the same properties.
+///
+/// The `key` function returns the name and properties of an item, and whether
+/// it was added, or `None` if the item was neither added nor removed.
+fn pair_renames<T, K: PartialEq>(
+    changes: Vec<T>,
+    key: impl Fn(&T) -> Option<(&[u8], K, bool)>,
+) -> Vec<Paired<T>> {
+    // For each removed item, the name of the matching added item.
+    let mut renames = vec![None; changes.len()];
+    let mut added = vec![false; changes.len()];
+    for (i, removed) in changes.iter().enumerate() {
+        let Some((_, removed_key, false)) = key(removed) else {
+            continue;
+        };
+        let matching = changes
+            .iter()
+            .enumerate()
+            .find_map(|(j, change)| match key(change) {
+                Some((name, added_key, true)) if !added[j] && added_key == removed_key => {
+                    Some((j, name.to_vec()))
+                }
+                _ => None,
+            });
+        if let Some((j, name)) = matching {
+            renames[i] = Some(name);
+            added[j] = true;
+        }
+    }
+    changes
+        .into_iter()
+        .zip(renames)
+        .zip(added)
+        .filter(|(_, added)| !added)
+        .map(|((change, rename), _)| match rename {
+            Some(new_name) => Paired::Renamed(key(&change).unwrap().0.to_vec(), new_name),
+            None => Paired::Other(change),
+        })
+        .collect()
+}
@@ -1,8 +1,14 @@
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-use object::build;
+use object::{build, macho, pe, Endianness, FileKind, SectionFlags};
 
-use super::{Error, Result};
+use super::macho::MachOFile;
+use super::names::{NameMap, NameSet};
+use super::pe::PeFile;
+use super::{Error, NameMatching, Report, Result};
 
 /// Options for modifying a file.
 ///
@@ -12,6 +18,10 @@ use super::{Error, Result};
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct Options {
+    /// How names in the other options are matched against names in the file.
+    ///
+    /// See [`Rewriter::set_name_matching`].
+    pub name_matching: NameMatching,
     /// Delete symbols from the symbol table.
     ///
     /// See [`Rewriter::delete_symbols`].
@@ -20,6 +30,26 @@ pub struct Options {
     ///
     /// See [`Rewriter::rename_symbols`].
     pub rename_symbols: HashMap<Vec<u8>, Vec<u8>>,
+    /// Make symbols local.
+    ///
+    /// See [`Rewriter::localize_symbols`].
+    pub localize_symbols: HashSet<Vec<u8>>,
+    /// Make all symbols local except for these symbols.
+    ///
+    /// See [`Rewriter::keep_global_symbols`].
+    pub keep_global_symbols: HashSet<Vec<u8>>,
+    /// Make symbols global.
+    ///
+    /// See [`Rewriter::globalize_symbols`].
+    pub globalize_symbols: HashSet<Vec<u8>>,
+    /// Make symbols weak.
+    ///
+    /// See [`Rewriter::weaken_symbols`].
+    pub weaken_symbols: HashSet<Vec<u8>>,
+    /// Add a prefix to the names of all symbols.
+    ///
+    /// See [`Rewriter::prefix_symbols`].
+    pub prefix_symbols: Option<Vec<u8>>,
     /// Delete sections from the file.
     ///
     /// See [`Rewriter::delete_sections`].
@@ -28,8 +58,134 @@ pub struct Options {
     ///
     /// See [`Rewriter::rename_sections`].
     pub rename_sections: HashMap<Vec<u8>, Vec<u8>>,
+    /// Replace the contents of sections.
+    ///
+    /// See [`Rewriter::update_sections`].
+    pub update_sections: HashMap<Vec<u8>, Vec<u8>>,
+    /// Add sections to the file.
+    ///
+    /// See [`Rewriter::add_sections`].
+    pub add_sections: Vec<NewSection>,
+    /// Add a prefix to the names of all sections.
+    ///
+    /// See [`Rewriter::prefix_sections`].
+    pub prefix_sections: Option<Vec<u8>>,
+    /// The format of the output file.
+    ///
+    /// See [`Rewriter::set_output_format`].
+    pub output_format: OutputFormat,
     /// Options that are specific to ELF files.
     pub elf: super::ElfOptions,
+    /// Options that are specific to Mach-O files.
+    pub macho: super::MachOptions,
+    /// Options that are specific to PE files.
+    pub pe: super::PeOptions,
+}
+
+/// A section to add to a file.
+///
+/// This is used in [`Options::add_sections`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct NewSection {
+    /// The name of the section.
+    pub name: Vec<u8>,
+    /// The contents of the section.
+    pub data: Vec<u8>,
+    /// The format-specific flags of the section.
+    ///
+    /// If this is [`SectionFlags::None`], then the section is a non-allocated
+    /// section for ELF, and read-only initialized data for PE.
+    pub flags: SectionFlags,
+    /// The required alignment of the section.
+    ///
+    /// For PE files, this must not be greater than the section alignment.
+    pub align: u64,
+}
+
+impl NewSection {
+    /// Create a new section with the given name and contents.
+    ///
+    /// The section has no flags and an alignment of 1.
+    pub fn new(name: Vec<u8>, data: Vec<u8>) -> Self {
+        NewSection {
+            name,
+            data,
+            flags: SectionFlags::None,
+            align: 1,
+        }
+    }
+}
+
+/// The format of the file written by a [`Rewriter`].
+///
+/// This is used in [`Options::output_format`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// The same format as the input file.
+    #[default]
+    Object,
+    /// A flat binary containing the contents of the loadable sections.
+    ///
+    /// The contents of each section are written at an offset given by its load
+    /// address, relative to the lowest load address. Gaps are filled with zeros.
+    /// This is similar to `objcopy -O binary`.
+    ///
+    /// This is only supported for ELF files.
+    Binary,
+}
+
+/// The file being rewritten.
+#[derive(Debug)]
+pub(crate) enum Format<'data> {
+    Elf(build::elf::Builder<'data>),
+    MachO(MachOFile<'data>),
+    Pe(PeFile<'data>),
+}
+
+impl<'data> Format<'data> {
+    pub(crate) fn elf(&self) -> Option<&build::elf::Builder<'data>> {
+        match self {
+            Format::Elf(builder) => Some(builder),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn elf_mut(&mut self) -> Option<&mut build::elf::Builder<'data>> {
+        match self {
+            Format::Elf(builder) => Some(builder),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn macho(&self) -> Option<&MachOFile<'data>> {
+        match self {
+            Format::MachO(file) => Some(file),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn macho_mut(&mut self) -> Option<&mut MachOFile<'data>> {
+        match self {
+            Format::MachO(file) => Some(file),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn pe(&self) -> Option<&PeFile<'data>> {
+        match self {
+            Format::Pe(file) => Some(file),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn pe_mut(&mut self) -> Option<&mut PeFile<'data>> {
+        match self {
+            Format::Pe(file) => Some(file),
+            _ => None,
+        }
+    }
 }
 
 /// A rewriter for object and executable files.
@@ -37,53 +193,232 @@ pub struct Options {
 /// This struct provides a way to read a file, modify it, and write it back.
 #[derive(Debug)]
 pub struct Rewriter<'data> {
-    pub(crate) builder: build::elf::Builder<'data>,
+    pub(crate) data: &'data [u8],
+    pub(crate) format: Format<'data>,
     pub(crate) modified: bool,
+    pub(crate) name_matching: NameMatching,
+    pub(crate) output_format: OutputFormat,
 }
 
 impl<'data> Rewriter<'data> {
     /// Read a file and create a new rewriter.
+    ///
+    /// ELF, Mach-O and PE files are supported.
     pub fn read(data: &'data [u8]) -> Result<Self> {
-        let builder = build::elf::Builder::read(data).map_err(Error::parse)?;
+        let format = match FileKind::parse(data) {
+            Ok(FileKind::MachO32) => Format::MachO(
+                MachOFile::read::<macho::MachHeader32<Endianness>>(data).map_err(Error::parse)?,
+            ),
+            Ok(FileKind::MachO64) => Format::MachO(
+                MachOFile::read::<macho::MachHeader64<Endianness>>(data).map_err(Error::parse)?,
+            ),
+            Ok(FileKind::Pe32) => {
+                Format::Pe(PeFile::read::<pe::ImageNtHeaders32>(data).map_err(Error::parse)?)
+            }
+            Ok(FileKind::Pe64) => {
+                Format::Pe(PeFile::read::<pe::ImageNtHeaders64>(data).map_err(Error::parse)?)
+            }
+            _ => Format::Elf(build::elf::Builder::read(data).map_err(Error::parse)?),
+        };
         Ok(Self {
-            builder,
+            data,
+            format,
             modified: false,
+            name_matching: NameMatching::default(),
+            output_format: OutputFormat::default(),
         })
     }
 
     /// Write the file to an output stream.
-    pub fn write<W: std::io::Write>(mut self, w: W) -> Result<()> {
+    pub fn write<W: std::io::Write>(mut self, mut w: W) -> Result<()> {
         self.elf_finalize()?;
-        let mut buffer = object::write::StreamingBuffer::new(w);
-        self.builder.write(&mut buffer).map_err(Error::write)?;
-        buffer.result().map_err(Error::io)
+        if self.output_format == OutputFormat::Binary {
+            return self.elf_write_binary(w);
+        }
+        match self.format {
+            Format::Elf(builder) => {
+                let mut buffer = object::write::StreamingBuffer::new(w);
+                builder.write(&mut buffer).map_err(Error::write)?;
+                buffer.result().map_err(Error::io)
+            }
+            Format::MachO(file) => {
+                if self.modified {
+                    w.write_all(&file.write()?).map_err(Error::io)
+                } else {
+                    w.write_all(file.data()).map_err(Error::io)
+                }
+            }
+            Format::Pe(file) => {
+                if self.modified {
+                    w.write_all(&file.write()?).map_err(Error::io)
+                } else {
+                    w.write_all(file.data()).map_err(Error::io)
+                }
+            }
+        }
+    }
+
+    /// Return a report of the changes that writing the file would make.
+    ///
+    /// The file is written to memory and compared with the original file,
+    /// so the report includes changes to the layout of sections that are
+    /// made when writing.
+    pub fn dry_run(self) -> Result<Report> {
+        let data = self.data;
+        let mut out = Vec::new();
+        self.write(&mut out)?;
+        Report::new(data, &out)
+    }
+
+    /// Write the file to the given path, replacing any existing file.
+    ///
+    /// The file is written to a temporary file in the same directory, which is
+    /// then synced and renamed over the destination. This means the destination
+    /// is never left partially written, and it may be the same file that the
+    /// input data was read from.
+    ///
+    /// If the destination is a symbolic link, then the file it refers to is
+    /// replaced. If the destination exists, then its permissions are preserved,
+    /// and on Unix its ownership is also preserved if possible.
+    pub fn write_to_path<P: AsRef<Path>>(self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let (path, metadata) = match fs::canonicalize(path) {
+            Ok(path) => {
+                let metadata = fs::metadata(&path).map_err(Error::io)?;
+                (path, Some(metadata))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (path.to_path_buf(), None),
+            Err(e) => return Err(Error::io(e)),
+        };
+
+        let (temp_path, temp_file) = create_temp_file(&path).map_err(Error::io)?;
+        let result = (|| {
+            let mut w = io::BufWriter::new(&temp_file);
+            self.write(&mut w)?;
+            w.flush().map_err(Error::io)?;
+            drop(w);
+            if let Some(metadata) = &metadata {
+                temp_file
+                    .set_permissions(metadata.permissions())
+                    .map_err(Error::io)?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::MetadataExt;
+                    // Changing the owner requires privileges, so ignore failures.
+                    std::os::unix::fs::fchown(
+                        &temp_file,
+                        Some(metadata.uid()),
+                        Some(metadata.gid()),
+                    )
+                    .ok();
+                }
+            }
+            temp_file.sync_all().map_err(Error::io)?;
+            fs::rename(&temp_path, &path).map_err(Error::io)
+        })();
+        if result.is_err() {
+            fs::remove_file(&temp_path).ok();
+        }
+        result
     }
 
     /// Modify the file according to the given options.
     pub fn modify(&mut self, options: Options) -> Result<()> {
+        self.set_name_matching(options.name_matching);
         if !options.delete_symbols.is_empty() {
-            self.delete_symbols(&options.delete_symbols);
+            self.delete_symbols(&options.delete_symbols)?;
         }
         if !options.rename_symbols.is_empty() {
-            self.rename_symbols(&options.rename_symbols);
+            self.rename_symbols(&options.rename_symbols)?;
+        }
+        if !options.localize_symbols.is_empty() {
+            self.localize_symbols(&options.localize_symbols)?;
+        }
+        if !options.keep_global_symbols.is_empty() {
+            self.keep_global_symbols(&options.keep_global_symbols)?;
+        }
+        if !options.globalize_symbols.is_empty() {
+            self.globalize_symbols(&options.globalize_symbols)?;
+        }
+        if !options.weaken_symbols.is_empty() {
+            self.weaken_symbols(&options.weaken_symbols)?;
+        }
+        if let Some(prefix) = &options.prefix_symbols {
+            self.prefix_symbols(prefix)?;
         }
         if !options.delete_sections.is_empty() {
-            self.delete_sections(&options.delete_sections);
+            self.delete_sections(&options.delete_sections)?;
         }
         if !options.rename_sections.is_empty() {
-            self.rename_sections(&options.rename_sections);
+            self.rename_sections(&options.rename_sections)?;
+        }
+        if !options.update_sections.is_empty() {
+            self.update_sections(&options.update_sections)?;
+        }
+        if !options.add_sections.is_empty() {
+            self.add_sections(&options.add_sections)?;
+        }
+        if let Some(prefix) = &options.prefix_sections {
+            self.prefix_sections(prefix)?;
         }
+        self.set_output_format(options.output_format)?;
         self.elf_modify(options.elf)?;
+        self.macho_modify(options.macho)?;
+        self.pe_modify(options.pe)?;
         Ok(())
     }
 
+    /// Set the format of the file that is written.
+    ///
+    /// The default is [`OutputFormat::Object`].
+    ///
+    /// Returns an error if the output format is not supported for the input file.
+    pub fn set_output_format(&mut self, output_format: OutputFormat) -> Result<()> {
+        match (output_format, &self.format) {
+            (OutputFormat::Object, _) | (OutputFormat::Binary, Format::Elf(_)) => {}
+            (OutputFormat::Binary, _) => {
+                return Err(Error::modify(
+                    "Binary output is only supported for ELF files",
+                ));
+            }
+        }
+        self.output_format = output_format;
+        Ok(())
+    }
+
+    /// Set how names are matched against names in the file.
+    ///
+    /// This is used by the methods that take sets or maps of symbol or section
+    /// names. The default is [`NameMatching::Glob`].
+    pub fn set_name_matching(&mut self, name_matching: NameMatching) {
+        self.name_matching = name_matching;
+    }
+
+    pub(crate) fn name_set<'a>(&self, names: &'a HashSet<Vec<u8>>) -> Result<NameSet<'a>> {
+        NameSet::new(
+            self.name_matching,
+            names.iter().map(|name| (name.as_slice(), &())),
+        )
+    }
+
+    pub(crate) fn name_map<'a>(
+        &self,
+        names: &'a HashMap<Vec<u8>, Vec<u8>>,
+    ) -> Result<NameMap<'a, Vec<u8>>> {
+        NameMap::new(
+            self.name_matching,
+            names.iter().map(|(name, value)| (name.as_slice(), value)),
+        )
+    }
+
     /// Delete symbols from the symbol table.
     ///
     /// For ELF files, this deletes symbols from both the symbol table and the
     /// dynamic symbol table.
-    pub fn delete_symbols(&mut self, names: &HashSet<Vec<u8>>) {
-        self.elf_delete_symbols(names);
-        self.elf_delete_dynamic_symbols(names);
+    pub fn delete_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        self.elf_delete_symbols(names)?;
+        self.elf_delete_dynamic_symbols(names)?;
+        self.macho_delete_symbols(names)
     }
 
     /// Rename symbols in the symbol table.
@@ -91,21 +426,235 @@ impl<'data> Rewriter<'data> {
     /// For ELF files, this renames symbols in both the symbol table and the
     /// dynamic symbol table.
     ///
-    /// The `names` map is from old names to new names.
-    pub fn rename_symbols(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) {
-        self.elf_rename_symbols(names);
-        self.elf_rename_dynamic_symbols(names);
+    /// The `names` map is from old names to new names. If a name matches
+    /// multiple patterns, then it is not specified which one is used.
+    pub fn rename_symbols(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        self.elf_rename_symbols(names)?;
+        self.elf_rename_dynamic_symbols(names)?;
+        self.macho_rename_symbols(names)
+    }
+
+    /// Make symbols local.
+    ///
+    /// This is only supported for ELF files.
+    ///
+    /// Undefined symbols are not changed. This applies to both the symbol
+    /// table and the dynamic symbol table.
+    pub fn localize_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => self.elf_localize_symbols(names),
+            Format::MachO(_) => Err(Error::modify(
+                "Localizing symbols is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => Err(Error::modify(
+                "Localizing symbols is not supported for PE files",
+            )),
+        }
+    }
+
+    /// Make all symbols local except for the given symbols.
+    ///
+    /// This is only supported for ELF files.
+    ///
+    /// Undefined symbols are not changed. This applies to both the symbol
+    /// table and the dynamic symbol table.
+    pub fn keep_global_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => self.elf_keep_global_symbols(names),
+            Format::MachO(_) => Err(Error::modify(
+                "Localizing symbols is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => Err(Error::modify(
+                "Localizing symbols is not supported for PE files",
+            )),
+        }
+    }
+
+    /// Make local symbols global.
+    ///
+    /// This is only supported for ELF files.
+    ///
+    /// Hidden and internal symbols are also given default visibility.
+    /// This applies to both the symbol table and the dynamic symbol table.
+    pub fn globalize_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => self.elf_globalize_symbols(names),
+            Format::MachO(_) => Err(Error::modify(
+                "Globalizing symbols is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => Err(Error::modify(
+                "Globalizing symbols is not supported for PE files",
+            )),
+        }
+    }
+
+    /// Make global symbols weak.
+    ///
+    /// This is only supported for ELF files.
+    ///
+    /// This applies to both the symbol table and the dynamic symbol table.
+    pub fn weaken_symbols(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => self.elf_weaken_symbols(names),
+            Format::MachO(_) => Err(Error::modify(
+                "Weakening symbols is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => Err(Error::modify(
+                "Weakening symbols is not supported for PE files",
+            )),
+        }
+    }
+
+    /// Add a prefix to the names of all symbols.
+    ///
+    /// This is only supported for ELF files.
+    ///
+    /// This applies to both the symbol table and the dynamic symbol table,
+    /// including undefined symbols. Relocations refer to symbols by index, so
+    /// they use the new names too. Section and file symbols are not changed.
+    pub fn prefix_symbols(&mut self, prefix: &[u8]) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => {
+                self.elf_prefix_symbols(prefix);
+                Ok(())
+            }
+            Format::MachO(_) => Err(Error::modify(
+                "Prefixing symbols is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => Err(Error::modify(
+                "Prefixing symbols is not supported for PE files",
+            )),
+        }
     }
 
     /// Delete sections from the file.
-    pub fn delete_sections(&mut self, names: &HashSet<Vec<u8>>) {
-        self.elf_delete_sections(names);
+    ///
+    /// This is only supported for ELF and PE files.
+    ///
+    /// For PE files, data directories contained in deleted sections are
+    /// removed, and the first section can't be deleted.
+    pub fn delete_sections(&mut self, names: &HashSet<Vec<u8>>) -> Result<()> {
+        self.elf_delete_sections(names)?;
+        self.pe_delete_sections(names)
     }
 
     /// Rename sections in the file.
     ///
-    /// The `names` map is from old names to new names.
-    pub fn rename_sections(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) {
-        self.elf_rename_sections(names);
+    /// This is only supported for ELF and PE files.
+    ///
+    /// For PE files, the new names must not be longer than 8 bytes.
+    ///
+    /// The `names` map is from old names to new names. If a name matches
+    /// multiple patterns, then it is not specified which one is used.
+    pub fn rename_sections(&mut self, names: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        self.elf_rename_sections(names)?;
+        self.pe_rename_sections(names)
+    }
+
+    /// Return the contents of the section with the given name.
+    ///
+    /// This is only supported for ELF and PE files. This includes any
+    /// modifications made by [`Self::update_sections`].
+    ///
+    /// For ELF files, it is an error if the contents of the section are
+    /// generated when writing the file, such as for symbol tables.
+    pub fn section_data(&self, name: &[u8]) -> Result<&[u8]> {
+        match &self.format {
+            Format::Elf(_) => self.elf_section_data(name),
+            Format::MachO(_) => Err(Error::modify(
+                "Section data is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => self.pe_section_data(name),
+        }
+    }
+
+    /// Replace the contents of sections.
+    ///
+    /// This is only supported for ELF and PE files.
+    ///
+    /// The `sections` map is from section names to the new contents.
+    /// It is an error if a section is not found.
+    ///
+    /// For ELF files, the contents of allocated sections in files with segments
+    /// can't be larger than the original contents, since the section addresses
+    /// can't be changed. For PE files, the contents of a section can't be larger
+    /// than the space before the next section in memory, and the contents are
+    /// moved to the end of the file if they are larger than the original contents.
+    pub fn update_sections(&mut self, sections: &HashMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => self.elf_update_sections(sections),
+            Format::MachO(_) => Err(Error::modify(
+                "Updating sections is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => self.pe_update_sections(sections),
+        }
+    }
+
+    /// Add sections to the file.
+    ///
+    /// This is only supported for ELF and PE files.
+    ///
+    /// It is an error if a section with the same name already exists.
+    ///
+    /// For ELF files, allocated sections are placed in new `PT_LOAD` segments
+    /// when writing the file. For PE files, the sections are appended to the
+    /// file, which requires space for the extra section headers.
+    pub fn add_sections(&mut self, sections: &[NewSection]) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => self.elf_add_sections(sections),
+            Format::MachO(_) => Err(Error::modify(
+                "Adding sections is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => self.pe_add_sections(sections),
+        }
+    }
+
+    /// Add a prefix to the names of all sections.
+    ///
+    /// This is only supported for ELF files.
+    ///
+    /// Sections containing symbol tables, string tables, or dynamic linking
+    /// information are not changed. Relocation sections named after their target
+    /// section keep the `.rel` or `.rela` prefix, so `.rela.text` becomes
+    /// `.rela<prefix>.text`.
+    pub fn prefix_sections(&mut self, prefix: &[u8]) -> Result<()> {
+        match self.format {
+            Format::Elf(_) => {
+                self.elf_prefix_sections(prefix);
+                Ok(())
+            }
+            Format::MachO(_) => Err(Error::modify(
+                "Prefixing sections is not supported for Mach-O files",
+            )),
+            Format::Pe(_) => Err(Error::modify(
+                "Prefixing sections is not supported for PE files",
+            )),
+        }
+    }
+}
+
+/// Create a new temporary file in the same directory as `path`.
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, fs::File)> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid output path '{}'", path.display()),
+        )
+    })?;
+    let mut index = 0;
+    loop {
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(".{}.{}.tmp", std::process::id(), index));
+        let temp_path = path.with_file_name(temp_name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && index < 100 => index += 1,
+            Err(e) => return Err(e),
+        }
     }
 }
@@ -0,0 +1,993 @@
+use object::{
+    elf, write, Endianness, Object, ObjectSection, ObjectSegment, ObjectSymbol, SectionFlags,
+};
+
+fn rewrite(data: &[u8], options: object_rewrite::Options) -> object_rewrite::Result<Vec<u8>> {
+    let mut rewriter = object_rewrite::Rewriter::read(data)?;
+    rewriter.modify(options)?;
+    let mut out = Vec::new();
+    rewriter.write(&mut out)?;
+    Ok(out)
+}
+
+#[test]
+fn rewrite_elf_add_sections() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 16);
+    let data = object.write().unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.add_sections.push(object_rewrite::NewSection::new(
+        b".note.version".to_vec(),
+        b"1.2.3".to_vec(),
+    ));
+    let mut section = object_rewrite::NewSection::new(b".rodata.config".to_vec(), vec![1; 12]);
+    section.flags = SectionFlags::Elf {
+        sh_flags: u64::from(elf::SHF_ALLOC),
+    };
+    section.align = 8;
+    options.add_sections.push(section);
+    let out = rewrite(&data, options).unwrap();
+
+    let file = object::File::parse(&*out).unwrap();
+    let section = file.section_by_name(".note.version").unwrap();
+    assert_eq!(section.data().unwrap(), b"1.2.3");
+    assert_eq!(section.flags(), SectionFlags::Elf { sh_flags: 0 });
+    let section = file.section_by_name(".rodata.config").unwrap();
+    assert_eq!(section.data().unwrap(), &[1; 12]);
+    assert_eq!(section.align(), 8);
+    assert_eq!(
+        section.flags(),
+        SectionFlags::Elf {
+            sh_flags: u64::from(elf::SHF_ALLOC)
+        }
+    );
+    assert_eq!(
+        file.section_by_name(".text").unwrap().data().unwrap(),
+        &[0xc3]
+    );
+
+    let mut options = object_rewrite::Options::default();
+    options.add_sections.push(object_rewrite::NewSection::new(
+        b".text".to_vec(),
+        Vec::new(),
+    ));
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Section .text already exists");
+}
+
+#[test]
+fn rewrite_elf_update_sections() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 16);
+    let comment = object.add_section(Vec::new(), b".comment".to_vec(), object::SectionKind::Other);
+    object.append_section_data(comment, b"old\0", 1);
+    let data = object.write().unwrap();
+
+    let rewriter = object_rewrite::Rewriter::read(&data).unwrap();
+    assert_eq!(rewriter.section_data(b".comment").unwrap(), b"old\0");
+    let err = rewriter.section_data(b".symtab").unwrap_err();
+    assert_eq!(err.to_string(), "Unsupported contents for section .symtab");
+    let err = rewriter.section_data(b".missing").unwrap_err();
+    assert_eq!(err.to_string(), "Section .missing not found");
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .update_sections
+        .insert(b".comment".to_vec(), b"a much longer comment\0".to_vec());
+    options
+        .update_sections
+        .insert(b".text".to_vec(), vec![0x90, 0x90, 0xc3]);
+    let out = rewrite(&data, options).unwrap();
+
+    let file = object::File::parse(&*out).unwrap();
+    let section = file.section_by_name(".comment").unwrap();
+    assert_eq!(section.data().unwrap(), b"a much longer comment\0");
+    let section = file.section_by_name(".text").unwrap();
+    assert_eq!(section.data().unwrap(), &[0x90, 0x90, 0xc3]);
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .update_sections
+        .insert(b".missing".to_vec(), Vec::new());
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Section .missing not found");
+}
+
+#[test]
+fn rewrite_elf_debuglink() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0x90, 0x90, 0xc3], 16);
+    let symbol = object.add_symbol(write::Symbol {
+        name: b"main".to_vec(),
+        value: 0,
+        size: 3,
+        kind: object::SymbolKind::Text,
+        scope: object::SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: object::SymbolFlags::None,
+    });
+    let debug_info = object.add_section(
+        Vec::new(),
+        b".debug_info".to_vec(),
+        object::SectionKind::Debug,
+    );
+    object.append_section_data(debug_info, &[1; 16], 1);
+    object
+        .add_relocation(
+            debug_info,
+            write::Relocation {
+                offset: 8,
+                symbol,
+                addend: 0,
+                flags: object::RelocationFlags::Generic {
+                    kind: object::RelocationKind::Absolute,
+                    encoding: object::RelocationEncoding::Generic,
+                    size: 64,
+                },
+            },
+        )
+        .unwrap();
+    let data = object.write().unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.elf.only_keep_debug = true;
+    let debug = rewrite(&data, options).unwrap();
+
+    let file = object::File::parse(&*debug).unwrap();
+    let section = file.section_by_name(".text").unwrap();
+    assert_eq!(section.kind(), object::SectionKind::UninitializedData);
+    assert_eq!(section.size(), 3);
+    assert_eq!(section.data().unwrap(), &[]);
+    let section = file.section_by_name(".debug_info").unwrap();
+    assert_eq!(section.data().unwrap(), &[1; 16]);
+    assert_eq!(section.relocations().count(), 1);
+    assert!(file.symbol_by_name("main").is_some());
+
+    let crc = crc32fast::hash(&debug);
+    let mut options = object_rewrite::Options::default();
+    options.elf.add_gnu_debuglink = Some((b"test.debug".to_vec(), crc));
+    let out = rewrite(&data, options).unwrap();
+
+    let file = object::File::parse(&*out).unwrap();
+    assert_eq!(
+        file.gnu_debuglink().unwrap(),
+        Some((&b"test.debug"[..], crc))
+    );
+    assert_eq!(
+        file.section_by_name(".text").unwrap().data().unwrap(),
+        &[0x90, 0x90, 0xc3]
+    );
+
+    let mut options = object_rewrite::Options::default();
+    options.elf.add_gnu_debuglink = Some((b"test.debug".to_vec(), crc));
+    let err = rewrite(&out, options).unwrap_err();
+    assert_eq!(err.to_string(), "Section .gnu_debuglink already exists");
+}
+
+#[test]
+fn rewrite_elf_symbol_binding() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    let mut add_symbol = |name: &[u8], scope, weak| {
+        let offset = object.append_section_data(text, &[0xc3], 1);
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value: offset,
+            size: 1,
+            kind: object::SymbolKind::Text,
+            scope,
+            weak,
+            section: write::SymbolSection::Section(text),
+            flags: object::SymbolFlags::None,
+        });
+    };
+    add_symbol(b"local", object::SymbolScope::Compilation, false);
+    add_symbol(b"hidden", object::SymbolScope::Linkage, false);
+    add_symbol(b"global1", object::SymbolScope::Dynamic, false);
+    add_symbol(b"global2", object::SymbolScope::Dynamic, false);
+    add_symbol(b"weak", object::SymbolScope::Dynamic, true);
+    object.add_symbol(write::Symbol {
+        name: b"undefined".to_vec(),
+        value: 0,
+        size: 0,
+        kind: object::SymbolKind::Text,
+        scope: object::SymbolScope::Dynamic,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: object::SymbolFlags::None,
+    });
+    let data = object.write().unwrap();
+
+    let binding = |data: &[u8], name: &str| {
+        let file = object::read::elf::ElfFile64::<Endianness>::parse(data).unwrap();
+        let symbol = file.symbol_by_name(name).unwrap();
+        let elf_symbol = symbol.elf_symbol();
+        (
+            elf_symbol.st_bind(),
+            elf_symbol.st_visibility(),
+            symbol.is_undefined(),
+        )
+    };
+
+    let names = |names: &[&str]| names.iter().map(|name| name.as_bytes().to_vec()).collect();
+    let mut options = object_rewrite::Options::default();
+    options.localize_symbols = names(&["global1", "undefined"]);
+    options.globalize_symbols = names(&["local", "hidden"]);
+    options.weaken_symbols = names(&["global2", "local"]);
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(
+        binding(&out, "global1"),
+        (elf::STB_LOCAL, elf::STV_DEFAULT, false)
+    );
+    assert_eq!(
+        binding(&out, "undefined"),
+        (elf::STB_GLOBAL, elf::STV_DEFAULT, true)
+    );
+    assert_eq!(
+        binding(&out, "local"),
+        (elf::STB_WEAK, elf::STV_DEFAULT, false)
+    );
+    assert_eq!(
+        binding(&out, "hidden"),
+        (elf::STB_GLOBAL, elf::STV_HIDDEN, false)
+    );
+    assert_eq!(
+        binding(&out, "global2"),
+        (elf::STB_WEAK, elf::STV_DEFAULT, false)
+    );
+
+    let mut options = object_rewrite::Options::default();
+    options.keep_global_symbols = names(&["global2"]);
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(
+        binding(&out, "global1"),
+        (elf::STB_LOCAL, elf::STV_DEFAULT, false)
+    );
+    assert_eq!(
+        binding(&out, "hidden"),
+        (elf::STB_LOCAL, elf::STV_HIDDEN, false)
+    );
+    assert_eq!(
+        binding(&out, "weak"),
+        (elf::STB_LOCAL, elf::STV_DEFAULT, false)
+    );
+    assert_eq!(
+        binding(&out, "global2"),
+        (elf::STB_GLOBAL, elf::STV_DEFAULT, false)
+    );
+    assert_eq!(
+        binding(&out, "undefined"),
+        (elf::STB_GLOBAL, elf::STV_DEFAULT, true)
+    );
+
+    let mut options = object_rewrite::Options::default();
+    options.globalize_symbols = names(&["hidden"]);
+    let out = rewrite(&out, options).unwrap();
+    assert_eq!(
+        binding(&out, "hidden"),
+        (elf::STB_GLOBAL, elf::STV_DEFAULT, false)
+    );
+}
+
+#[test]
+fn rewrite_elf_prefix() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xe8, 0, 0, 0, 0, 0xc3], 16);
+    object.add_symbol(write::Symbol {
+        name: b"main".to_vec(),
+        value: 0,
+        size: 6,
+        kind: object::SymbolKind::Text,
+        scope: object::SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: object::SymbolFlags::None,
+    });
+    let puts = object.add_symbol(write::Symbol {
+        name: b"puts".to_vec(),
+        value: 0,
+        size: 0,
+        kind: object::SymbolKind::Text,
+        scope: object::SymbolScope::Dynamic,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: object::SymbolFlags::None,
+    });
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 1,
+                symbol: puts,
+                addend: -4,
+                flags: object::RelocationFlags::Elf {
+                    r_type: elf::R_X86_64_PLT32,
+                },
+            },
+        )
+        .unwrap();
+    let data = object.write().unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.prefix_symbols = Some(b"lib_".to_vec());
+    options.prefix_sections = Some(b".lib".to_vec());
+    let out = rewrite(&data, options).unwrap();
+
+    let file = object::File::parse(&*out).unwrap();
+    assert!(file.section_by_name(".text").is_none());
+    let section = file.section_by_name(".lib.text").unwrap();
+    assert_eq!(section.data().unwrap(), &[0xe8, 0, 0, 0, 0, 0xc3]);
+    assert!(file.section_by_name(".rela.lib.text").is_some());
+    assert!(file.section_by_name(".symtab").is_some());
+    assert!(file.section_by_name(".strtab").is_some());
+    assert!(file.section_by_name(".shstrtab").is_some());
+
+    assert!(file.symbol_by_name("main").is_none());
+    let symbol = file.symbol_by_name("lib_main").unwrap();
+    assert_eq!(symbol.section_index(), Some(section.index()));
+    let (offset, relocation) = section.relocations().next().unwrap();
+    assert_eq!(offset, 1);
+    let object::RelocationTarget::Symbol(index) = relocation.target() else {
+        panic!("unexpected relocation target {:?}", relocation.target());
+    };
+    let symbol = file.symbol_by_index(index).unwrap();
+    assert_eq!(symbol.name(), Ok("lib_puts"));
+    assert!(symbol.is_undefined());
+}
+
+fn name_matching_object() -> Vec<u8> {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    for name in ["func1", "func2", "other"] {
+        let offset = object.append_section_data(text, &[0xc3], 1);
+        object.add_symbol(write::Symbol {
+            name: name.as_bytes().to_vec(),
+            value: offset,
+            size: 1,
+            kind: object::SymbolKind::Text,
+            scope: object::SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: object::SymbolFlags::None,
+        });
+    }
+    for name in [".note.one", ".note.two", ".comment"] {
+        let section = object.add_section(
+            Vec::new(),
+            name.as_bytes().to_vec(),
+            object::SectionKind::Other,
+        );
+        object.append_section_data(section, b"data", 1);
+    }
+    object.write().unwrap()
+}
+
+#[test]
+fn rewrite_elf_name_matching() {
+    let data = name_matching_object();
+
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".note.*".to_vec());
+    options
+        .rename_symbols
+        .insert(b"func[12]".to_vec(), b"renamed".to_vec());
+    let out = rewrite(&data, options).unwrap();
+    let file = object::File::parse(&*out).unwrap();
+    assert!(file.section_by_name(".note.one").is_none());
+    assert!(file.section_by_name(".note.two").is_none());
+    assert!(file.section_by_name(".comment").is_some());
+    let names: Vec<_> = file.symbols().filter_map(|s| s.name().ok()).collect();
+    assert_eq!(names, ["renamed", "renamed", "other"]);
+
+    let mut options = object_rewrite::Options::default();
+    options.name_matching = object_rewrite::NameMatching::Exact;
+    options.delete_sections.insert(b".note.*".to_vec());
+    let out = rewrite(&data, options).unwrap();
+    let file = object::File::parse(&*out).unwrap();
+    assert!(file.section_by_name(".note.one").is_some());
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn rewrite_elf_name_matching_regex() {
+    let data = name_matching_object();
+
+    let mut options = object_rewrite::Options::default();
+    options.name_matching = object_rewrite::NameMatching::Regex;
+    options
+        .delete_sections
+        .insert(br"\.note\.o.*|\.comment".to_vec());
+    options.delete_symbols.insert(b"func".to_vec());
+    let out = rewrite(&data, options).unwrap();
+    let file = object::File::parse(&*out).unwrap();
+    assert!(file.section_by_name(".note.one").is_none());
+    assert!(file.section_by_name(".note.two").is_some());
+    assert!(file.section_by_name(".comment").is_none());
+    assert_eq!(file.symbols().count(), 3);
+
+    let mut options = object_rewrite::Options::default();
+    options.name_matching = object_rewrite::NameMatching::Regex;
+    options.delete_sections.insert(b"(".to_vec());
+    let err = rewrite(&data, options).unwrap_err();
+    assert!(err.to_string().starts_with("Invalid regular expression ("));
+}
+
+#[test]
+fn rewrite_elf_write_to_path() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 16);
+    let data = object.write().unwrap();
+
+    let dir = std::env::temp_dir().join(format!("object-rewrite-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("test.o");
+    std::fs::write(&path, &data).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o751)).unwrap();
+    }
+
+    // Replace the file that the data was read from.
+    let input = std::fs::read(&path).unwrap();
+    let mut rewriter = object_rewrite::Rewriter::read(&input).unwrap();
+    let mut names = std::collections::HashMap::new();
+    names.insert(b".text".to_vec(), b".code".to_vec());
+    rewriter.rename_sections(&names).unwrap();
+    rewriter.write_to_path(&path).unwrap();
+
+    let out = std::fs::read(&path).unwrap();
+    let file = object::File::parse(&*out).unwrap();
+    assert!(file.section_by_name(".code").is_some());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o751);
+    }
+    // The temporary file must have been renamed.
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rewrite_elf_header() {
+    use object::read::elf::{ElfFile64, FileHeader};
+
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 16);
+    let data = object.write().unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.elf.set_entry = Some(0x1000);
+    options.elf.set_os_abi = Some(elf::ELFOSABI_FREEBSD);
+    options.elf.set_abi_version = Some(2);
+    options.elf.set_flags = Some(0x1234);
+    let out = rewrite(&data, options).unwrap();
+
+    let file = ElfFile64::<Endianness>::parse(&*out).unwrap();
+    let endian = file.endian();
+    let header = file.elf_header();
+    assert_eq!(header.e_entry(endian), 0x1000);
+    assert_eq!(header.e_ident().os_abi, elf::ELFOSABI_FREEBSD);
+    assert_eq!(header.e_ident().abi_version, 2);
+    assert_eq!(header.e_flags(endian), 0x1234);
+    assert_eq!(header.e_type(endian), elf::ET_REL);
+
+    // Only ET_EXEC and ET_DYN can be converted.
+    let mut options = object_rewrite::Options::default();
+    options.elf.set_type = Some(elf::ET_DYN);
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Can't change file type from 1 to 3");
+}
+
+fn note(name: &[u8], n_type: u32, desc: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(name.len() as u32 + 1).to_le_bytes());
+    data.extend_from_slice(&(desc.len() as u32).to_le_bytes());
+    data.extend_from_slice(&n_type.to_le_bytes());
+    data.extend_from_slice(name);
+    data.push(0);
+    data.resize((data.len() + 3) & !3, 0);
+    data.extend_from_slice(desc);
+    data.resize((data.len() + 3) & !3, 0);
+    data
+}
+
+fn section_notes(file: &object::read::elf::ElfFile64, name: &str) -> Vec<(Vec<u8>, u32, Vec<u8>)> {
+    use object::read::elf::SectionHeader;
+
+    let section = file.section_by_name(name).unwrap();
+    let mut notes = section
+        .elf_section_header()
+        .notes(file.endian(), file.data())
+        .unwrap()
+        .unwrap();
+    let mut result = Vec::new();
+    while let Some(note) = notes.next().unwrap() {
+        result.push((
+            note.name().to_vec(),
+            note.n_type(file.endian()),
+            note.desc().to_vec(),
+        ));
+    }
+    result
+}
+
+#[test]
+fn rewrite_elf_notes() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 16);
+    let abi_tag = object.add_section(
+        Vec::new(),
+        b".note.ABI-tag".to_vec(),
+        object::SectionKind::Note,
+    );
+    let mut data = note(b"GNU", elf::NT_GNU_ABI_TAG, &[0; 16]);
+    data.extend(note(b"GNU", elf::NT_GNU_PROPERTY_TYPE_0, &[1; 8]));
+    object.append_section_data(abi_tag, &data, 4);
+    let attributes = object.add_section(
+        Vec::new(),
+        b".gnu.build.attributes".to_vec(),
+        object::SectionKind::Note,
+    );
+    let mut data = note(b"GA$\x013p1", 0x100, &[]);
+    data.extend(note(b"GA*\x02", 0x100, &[]));
+    object.append_section_data(attributes, &data, 4);
+    let data = object.write().unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .elf
+        .remove_notes
+        .push((b"GNU".to_vec(), Some(elf::NT_GNU_PROPERTY_TYPE_0)));
+    options.elf.remove_notes.push((b"GA*".to_vec(), None));
+    options.elf.add_notes.push(object_rewrite::ElfNote::new(
+        b".note.package".to_vec(),
+        b"FDO".to_vec(),
+        0xcafe_1a7e,
+        b"{\"type\":\"rpm\"}\0".to_vec(),
+    ));
+    options.elf.add_notes.push(object_rewrite::ElfNote::new(
+        b".note.ABI-tag".to_vec(),
+        b"Go".to_vec(),
+        4,
+        b"abc".to_vec(),
+    ));
+    let out = rewrite(&data, options).unwrap();
+
+    let file = object::read::elf::ElfFile64::parse(&*out).unwrap();
+    assert_eq!(
+        section_notes(&file, ".note.ABI-tag"),
+        [
+            (b"GNU".to_vec(), elf::NT_GNU_ABI_TAG, vec![0; 16]),
+            (b"Go".to_vec(), 4, b"abc".to_vec()),
+        ]
+    );
+    assert_eq!(
+        section_notes(&file, ".note.package"),
+        [(
+            b"FDO".to_vec(),
+            0xcafe_1a7e,
+            b"{\"type\":\"rpm\"}\0".to_vec()
+        )]
+    );
+    assert!(file.section_by_name(".gnu.build.attributes").is_none());
+
+    let mut options = object_rewrite::Options::default();
+    options.elf.add_notes.push(object_rewrite::ElfNote::new(
+        b".text".to_vec(),
+        b"GNU".to_vec(),
+        1,
+        Vec::new(),
+    ));
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Section .text is not a note section");
+}
+
+#[test]
+fn rewrite_elf_output_binary() {
+    use object::build;
+
+    let mut builder = build::elf::Builder::new(Endianness::Little, true);
+    builder.header.e_type = elf::ET_EXEC;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+
+    let section = builder.sections.add();
+    section.name = b".shstrtab"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.data = build::elf::SectionData::SectionString;
+
+    // Load addresses differ from virtual addresses, and the segments are
+    // not contiguous in the file.
+    for (name, sh_type, data, address, paddr) in [
+        (
+            &b".text"[..],
+            elf::SHT_PROGBITS,
+            &[1, 2, 3, 4][..],
+            0x1000,
+            0x8000,
+        ),
+        (
+            &b".data"[..],
+            elf::SHT_PROGBITS,
+            &[5, 6][..],
+            0x2000,
+            0x8010,
+        ),
+        (&b".bss"[..], elf::SHT_NOBITS, &[][..], 0x2008, 0x8018),
+    ] {
+        let section = builder.sections.add();
+        section.name = name.into();
+        section.sh_type = sh_type;
+        section.sh_flags = u64::from(elf::SHF_ALLOC);
+        section.sh_addr = address;
+        section.sh_offset = address;
+        section.sh_addralign = 1;
+        if sh_type == elf::SHT_NOBITS {
+            section.sh_size = 8;
+            section.data = build::elf::SectionData::UninitializedData(8);
+        } else {
+            section.sh_size = data.len() as u64;
+            section.data = build::elf::SectionData::Data(data.into());
+        }
+        let id = section.id();
+        let segment = builder.segments.add();
+        segment.p_type = elf::PT_LOAD;
+        segment.p_flags = elf::PF_R;
+        segment.p_offset = address;
+        segment.p_vaddr = address;
+        segment.p_paddr = paddr;
+        segment.p_filesz = data.len() as u64;
+        segment.p_memsz = if sh_type == elf::SHT_NOBITS {
+            8
+        } else {
+            data.len() as u64
+        };
+        segment.p_align = 1;
+        segment.sections.push(id);
+    }
+    let mut data = Vec::new();
+    builder.write(&mut data).unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.output_format = object_rewrite::OutputFormat::Binary;
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(out, [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5, 6]);
+}
+
+#[test]
+fn rewrite_elf_dry_run() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3; 4], 16);
+    let comment = object.add_section(Vec::new(), b".comment".to_vec(), object::SectionKind::Other);
+    object.append_section_data(comment, b"comment\0", 1);
+    for (name, value) in [(&b"foo"[..], 0), (&b"bar"[..], 2)] {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value,
+            size: 2,
+            kind: object::SymbolKind::Text,
+            scope: object::SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: object::SymbolFlags::None,
+        });
+    }
+    let data = object.write().unwrap();
+
+    let mut rewriter = object_rewrite::Rewriter::read(&data).unwrap();
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".comment".to_vec());
+    options
+        .rename_symbols
+        .insert(b"foo".to_vec(), b"baz".to_vec());
+    options.weaken_symbols.insert(b"bar".to_vec());
+    rewriter.modify(options).unwrap();
+    let report = rewriter.dry_run().unwrap();
+
+    assert!(report.new_size < report.old_size);
+    assert!(matches!(
+        &report.sections[0],
+        object_rewrite::SectionChange::Removed { name, .. } if name == b".comment"
+    ));
+    // Later sections are moved.
+    assert!(report.sections[1..]
+        .iter()
+        .all(|change| matches!(change, object_rewrite::SectionChange::Changed { .. })));
+    assert!(matches!(
+        &report.symbols[..],
+        [
+            object_rewrite::SymbolChange::Renamed { old_name, new_name, dynamic: false },
+            object_rewrite::SymbolChange::Changed { name, old, new, .. },
+        ] if old_name == b"foo" && new_name == b"baz" && name == b"bar" && !old.weak && new.weak
+    ));
+    let text = report.to_string();
+    let lines: Vec<_> = text.lines().collect();
+    assert!(lines[0].starts_with("Removed section .comment ("));
+    assert!(lines.contains(&"Renamed symbol foo to baz"));
+    assert!(lines.last().unwrap().starts_with("File size 0x"));
+
+    // The file is unchanged if there are no modifications.
+    let rewriter = object_rewrite::Rewriter::read(&data).unwrap();
+    let report = rewriter.dry_run().unwrap();
+    assert!(report.sections.is_empty());
+    assert!(report.symbols.is_empty());
+}
+
+#[test]
+fn rewrite_elf_strip_unneeded() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::Elf,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    object.add_file_symbol(b"file.c".to_vec());
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0; 16], 16);
+    let mut symbols = Vec::new();
+    for (name, scope, section) in [
+        (
+            &b"local_unused"[..],
+            object::SymbolScope::Compilation,
+            write::SymbolSection::Section(text),
+        ),
+        (
+            b"local_used",
+            object::SymbolScope::Compilation,
+            write::SymbolSection::Section(text),
+        ),
+        (
+            b"global",
+            object::SymbolScope::Linkage,
+            write::SymbolSection::Section(text),
+        ),
+        (
+            b"undef_unused",
+            object::SymbolScope::Unknown,
+            write::SymbolSection::Undefined,
+        ),
+        (
+            b"undef_used",
+            object::SymbolScope::Unknown,
+            write::SymbolSection::Undefined,
+        ),
+    ] {
+        symbols.push(object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value: 0,
+            size: 0,
+            kind: object::SymbolKind::Text,
+            scope,
+            weak: false,
+            section,
+            flags: object::SymbolFlags::None,
+        }));
+    }
+    for (offset, symbol) in [(0, symbols[1]), (8, symbols[4])] {
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend: 0,
+                    flags: object::RelocationFlags::Elf {
+                        r_type: elf::R_X86_64_64,
+                    },
+                },
+            )
+            .unwrap();
+    }
+    let data = object.write().unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.elf.strip_unneeded = true;
+    let out = rewrite(&data, options).unwrap();
+
+    let file = object::File::parse(&*out).unwrap();
+    let mut names: Vec<_> = file
+        .symbols()
+        .filter(|symbol| symbol.kind() != object::SymbolKind::Section)
+        .map(|symbol| symbol.name().unwrap())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, ["global", "local_used", "undef_used"]);
+    let relocations: Vec<_> = file
+        .section_by_name(".text")
+        .unwrap()
+        .relocations()
+        .collect();
+    assert_eq!(relocations.len(), 2);
+}
+
+#[test]
+fn rewrite_elf_compact() {
+    use object::build;
+
+    let mut builder = build::elf::Builder::new(Endianness::Little, true);
+    builder.header.e_type = elf::ET_EXEC;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+
+    let section = builder.sections.add();
+    section.name = b".shstrtab"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.data = build::elf::SectionData::SectionString;
+
+    for (name, address) in [
+        (&b".text"[..], 0x1000),
+        (b".data1", 0x2000),
+        (b".data2", 0x3000),
+    ] {
+        let section = builder.sections.add();
+        section.name = name.into();
+        section.sh_type = elf::SHT_PROGBITS;
+        section.sh_flags = u64::from(elf::SHF_ALLOC);
+        section.sh_addr = address;
+        section.sh_offset = address;
+        section.sh_size = 4;
+        section.sh_addralign = 4;
+        section.data = build::elf::SectionData::Data(vec![1; 4].into());
+        let id = section.id();
+        let segment = builder.segments.add();
+        segment.p_type = elf::PT_LOAD;
+        segment.p_flags = elf::PF_R;
+        segment.p_offset = address;
+        segment.p_vaddr = address;
+        segment.p_paddr = address;
+        segment.p_filesz = 4;
+        segment.p_memsz = 4;
+        segment.p_align = 0x1000;
+        segment.sections.push(id);
+    }
+    let mut data = Vec::new();
+    builder.write(&mut data).unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".data1".to_vec());
+    let uncompacted = rewrite(&data, options).unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".data1".to_vec());
+    options.elf.compact = true;
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(out.len() + 0x1000, uncompacted.len());
+
+    let file = object::File::parse(&*out).unwrap();
+    let section = file.section_by_name(".data2").unwrap();
+    assert_eq!(section.address(), 0x3000);
+    assert_eq!(section.file_range(), Some((0x2000, 4)));
+    assert_eq!(section.data().unwrap(), &[1; 4]);
+    let segments: Vec<_> = file
+        .segments()
+        .map(|segment| (segment.address(), segment.file_range()))
+        .collect();
+    assert_eq!(segments, [(0x1000, (0x1000, 4)), (0x3000, (0x2000, 4))]);
+}
+
+#[test]
+fn rewrite_elf_extra_data() {
+    use object::build;
+
+    let mut builder = build::elf::Builder::new(Endianness::Little, true);
+    builder.header.e_type = elf::ET_EXEC;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+
+    let section = builder.sections.add();
+    section.name = b".shstrtab"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.data = build::elf::SectionData::SectionString;
+
+    for (name, address) in [
+        (&b".text"[..], 0x1000),
+        (b".data1", 0x2000),
+        (b".data2", 0x3000),
+    ] {
+        let section = builder.sections.add();
+        section.name = name.into();
+        section.sh_type = elf::SHT_PROGBITS;
+        section.sh_flags = u64::from(elf::SHF_ALLOC);
+        section.sh_addr = address;
+        section.sh_offset = address;
+        section.sh_size = 4;
+        section.sh_addralign = 4;
+        section.data = build::elf::SectionData::Data(vec![1; 4].into());
+        let id = section.id();
+        let segment = builder.segments.add();
+        segment.p_type = elf::PT_LOAD;
+        segment.p_flags = elf::PF_R;
+        segment.p_offset = address;
+        segment.p_vaddr = address;
+        segment.p_paddr = address;
+        segment.p_filesz = 8;
+        segment.p_memsz = 8;
+        segment.p_align = 0x1000;
+        segment.sections.push(id);
+        // The segment includes data after the section.
+        builder.padding.push(build::elf::Padding {
+            offset: address + 4,
+            data: vec![2; 4].into(),
+        });
+    }
+    builder.overlay = b"overlay"[..].into();
+    let mut data = Vec::new();
+    builder.write(&mut data).unwrap();
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .rename_sections
+        .insert(b".data2".to_vec(), b".data3".to_vec());
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(&out[0x1000..0x1008], &[1, 1, 1, 1, 2, 2, 2, 2]);
+    assert_eq!(&out[0x2000..0x2008], &[1, 1, 1, 1, 2, 2, 2, 2]);
+    assert!(out.ends_with(b"overlay"));
+
+    // Padding is moved with its segment when compacting.
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".data1".to_vec());
+    options.elf.compact = true;
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(&out[0x1000..0x1008], &[1, 1, 1, 1, 2, 2, 2, 2]);
+    assert_eq!(&out[0x2000..0x2008], &[1, 1, 1, 1, 2, 2, 2, 2]);
+    assert!(out.ends_with(b"overlay"));
+
+    let mut options = object_rewrite::Options::default();
+    options.elf.delete_extra_data = true;
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(&out[0x1000..0x1008], &[1, 1, 1, 1, 0, 0, 0, 0]);
+    assert_eq!(&out[0x3000..0x3008], &[1, 1, 1, 1, 0, 0, 0, 0]);
+    assert!(!out.ends_with(b"overlay"));
+}
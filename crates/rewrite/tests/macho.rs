@@ -0,0 +1,373 @@
+use object::read::macho::{MachHeader, Segment};
+use object::{macho, pod, write, Endianness, Object, ObjectSection, ObjectSymbol, U32};
+
+const LIBSYSTEM: &[u8] = b"/usr/lib/libSystem.B.dylib";
+
+fn string_command(cmd: u32, fixed: &[u8], string: &[u8]) -> Vec<u8> {
+    let endian = Endianness::Little;
+    let offset = 8 + fixed.len() + 4;
+    let cmdsize = (offset + string.len() + 8) & !7;
+    let mut data = Vec::new();
+    data.extend_from_slice(pod::bytes_of(&U32::new(endian, cmd)));
+    data.extend_from_slice(pod::bytes_of(&U32::new(endian, cmdsize as u32)));
+    data.extend_from_slice(pod::bytes_of(&U32::new(endian, offset as u32)));
+    data.extend_from_slice(fixed);
+    data.extend_from_slice(string);
+    data.resize(cmdsize, 0);
+    data
+}
+
+const SIGNATURE: write::macho::CodeSignature<'static> = write::macho::CodeSignature {
+    identifier: b"libfoo",
+    exec_seg_base: 0,
+    exec_seg_limit: 0x1000,
+    exec_seg_flags: 0,
+};
+
+/// Build a signed dylib, with optional padding after the load commands.
+fn build_dylib(padding: bool) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut writer = write::macho::Writer::new(Endianness::Little, true, &mut buffer);
+
+    let id = string_command(macho::LC_ID_DYLIB, &[0; 12], b"libfoo.dylib");
+    let dylib = string_command(macho::LC_LOAD_DYLIB, &[0; 12], LIBSYSTEM);
+    let rpath = string_command(macho::LC_RPATH, &[], b"@loader_path/../lib");
+
+    let name_foo = writer.add_string(b"_foo");
+    let name_baz = writer.add_string(b"_baz");
+    let name_bar = writer.add_string(b"_bar");
+    writer.reserve_symbol_index();
+    writer.reserve_symbol_index();
+    writer.reserve_symbol_index();
+
+    writer.reserve_mach_header();
+    writer.reserve_segment_command(1);
+    writer.reserve_segment_command(0);
+    writer.reserve_load_command(id.len());
+    writer.reserve_load_command(dylib.len());
+    writer.reserve_load_command(rpath.len());
+    writer.reserve_symtab_command();
+    writer.reserve_dysymtab_command();
+    writer.reserve_code_signature_command();
+    if padding {
+        writer.reserve_until(0x400);
+    }
+    let text_offset = writer.reserve(4, 16);
+    let linkedit_offset = writer.reserved_len();
+    writer.reserve_symtab();
+    writer.reserve_strtab();
+    writer.reserve_indirect_symtab(1);
+    let signature_offset = writer.reserve_code_signature(&SIGNATURE);
+    let file_size = writer.reserved_len();
+
+    writer
+        .write_mach_header(&write::macho::MachHeader {
+            cputype: macho::CPU_TYPE_X86_64,
+            cpusubtype: macho::CPU_SUBTYPE_X86_64_ALL,
+            filetype: macho::MH_DYLIB,
+            flags: 0,
+        })
+        .unwrap();
+    let mut text = [0; 16];
+    text[..6].copy_from_slice(b"__TEXT");
+    let mut linkedit = [0; 16];
+    linkedit[..10].copy_from_slice(b"__LINKEDIT");
+    let mut sectname = [0; 16];
+    sectname[..6].copy_from_slice(b"__text");
+    writer.write_segment_command(&write::macho::SegmentCommand {
+        segname: text,
+        vmaddr: 0,
+        vmsize: 0x1000,
+        fileoff: 0,
+        filesize: linkedit_offset as u64,
+        maxprot: macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+        initprot: macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+        nsects: 1,
+        flags: 0,
+    });
+    writer.write_section_header(&write::macho::SectionHeader {
+        sectname,
+        segname: text,
+        addr: text_offset as u64,
+        size: 4,
+        offset: text_offset as u32,
+        align: 4,
+        flags: macho::S_ATTR_PURE_INSTRUCTIONS,
+        ..Default::default()
+    });
+    writer.write_segment_command(&write::macho::SegmentCommand {
+        segname: linkedit,
+        vmaddr: 0x1000,
+        vmsize: 0x1000,
+        fileoff: linkedit_offset as u64,
+        filesize: (file_size - linkedit_offset) as u64,
+        maxprot: macho::VM_PROT_READ,
+        initprot: macho::VM_PROT_READ,
+        nsects: 0,
+        flags: 0,
+    });
+    writer.write(&id);
+    writer.write(&dylib);
+    writer.write(&rpath);
+    writer.write_symtab_command();
+    writer.write_dysymtab_command(&write::macho::Dysymtab {
+        ilocalsym: 0,
+        nlocalsym: 0,
+        iextdefsym: 0,
+        nextdefsym: 1,
+        iundefsym: 1,
+        nundefsym: 2,
+    });
+    writer.write_code_signature_command();
+    writer.pad_until(text_offset);
+    writer.write(&[0xc3; 4]);
+    writer.write_align_symtab();
+    writer.write_nlist(&write::macho::Nlist {
+        name: Some(name_foo),
+        n_type: macho::N_SECT | macho::N_EXT,
+        n_sect: 1,
+        n_desc: 0,
+        n_value: text_offset as u64,
+    });
+    for name in [name_baz, name_bar] {
+        writer.write_nlist(&write::macho::Nlist {
+            name: Some(name),
+            n_type: macho::N_UNDF | macho::N_EXT,
+            n_sect: 0,
+            n_desc: 0,
+            n_value: 0,
+        });
+    }
+    writer.write_strtab();
+    writer.write_indirect_symbol(2);
+    writer.write_code_signature();
+    SIGNATURE.sign(&mut buffer, signature_offset).unwrap();
+    buffer
+}
+
+fn rewrite(data: &[u8], options: object_rewrite::Options) -> object_rewrite::Result<Vec<u8>> {
+    let mut rewriter = object_rewrite::Rewriter::read(data)?;
+    rewriter.modify(options)?;
+    let mut out = Vec::new();
+    rewriter.write(&mut out)?;
+    Ok(out)
+}
+
+fn symbols(data: &[u8]) -> Vec<(String, bool)> {
+    let file = object::File::parse(data).unwrap();
+    file.symbols()
+        .map(|symbol| (symbol.name().unwrap().to_string(), symbol.is_undefined()))
+        .collect()
+}
+
+#[test]
+fn rewrite_macho_noop() {
+    let data = build_dylib(true);
+    let out = rewrite(&data, object_rewrite::Options::default()).unwrap();
+    assert_eq!(out, data);
+}
+
+#[test]
+fn rewrite_macho_load_commands() {
+    let data = build_dylib(true);
+    let rewriter = object_rewrite::Rewriter::read(&data).unwrap();
+    assert_eq!(rewriter.macho_id(), Some(&b"libfoo.dylib"[..]));
+    assert_eq!(
+        rewriter.macho_rpaths().collect::<Vec<_>>(),
+        [&b"@loader_path/../lib"[..]]
+    );
+    assert_eq!(rewriter.macho_dylibs().collect::<Vec<_>>(), [LIBSYSTEM]);
+
+    let mut options = object_rewrite::Options::default();
+    options.macho.set_id = Some(b"@rpath/libfoo.1.dylib".to_vec());
+    options.macho.replace_rpath.insert(
+        b"@loader_path/../lib".to_vec(),
+        b"@executable_path".to_vec(),
+    );
+    options.macho.add_rpath = vec![b"/opt/foo/lib".to_vec(), b"@executable_path".to_vec()];
+    options
+        .macho
+        .replace_dylib
+        .insert(LIBSYSTEM.to_vec(), b"/usr/lib/libSystem.dylib".to_vec());
+    let out = rewrite(&data, options).unwrap();
+
+    let rewriter = object_rewrite::Rewriter::read(&out).unwrap();
+    assert_eq!(rewriter.macho_id(), Some(&b"@rpath/libfoo.1.dylib"[..]));
+    assert_eq!(
+        rewriter.macho_rpaths().collect::<Vec<_>>(),
+        [&b"@executable_path"[..], b"/opt/foo/lib"]
+    );
+    assert_eq!(
+        rewriter.macho_dylibs().collect::<Vec<_>>(),
+        [&b"/usr/lib/libSystem.dylib"[..]]
+    );
+    let file = object::File::parse(&*out).unwrap();
+    let section = file.section_by_name("__text").unwrap();
+    assert_eq!(section.data().unwrap(), &[0xc3; 4]);
+    assert_eq!(symbols(&out), symbols(&data));
+    check_signature(&out);
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .macho
+        .delete_rpath
+        .insert(b"@executable_path".to_vec());
+    let out = rewrite(&out, options).unwrap();
+    let rewriter = object_rewrite::Rewriter::read(&out).unwrap();
+    assert_eq!(
+        rewriter.macho_rpaths().collect::<Vec<_>>(),
+        [&b"/opt/foo/lib"[..]]
+    );
+    check_signature(&out);
+
+    // There is no space for more load commands without the padding.
+    let data = build_dylib(false);
+    let mut options = object_rewrite::Options::default();
+    options.macho.add_rpath = vec![b"/opt/foo/lib".to_vec()];
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.kind(), object_rewrite::ErrorKind::Modify);
+}
+
+#[test]
+fn rewrite_macho_symbols() {
+    let data = build_dylib(false);
+
+    let mut options = object_rewrite::Options::default();
+    options.delete_symbols.insert(b"_baz".to_vec());
+    options
+        .rename_symbols
+        .insert(b"_foo".to_vec(), b"_foo_with_a_much_longer_name".to_vec());
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(
+        symbols(&out),
+        [
+            ("_foo_with_a_much_longer_name".to_string(), false),
+            ("_bar".to_string(), true)
+        ]
+    );
+
+    let header = macho::MachHeader64::<Endianness>::parse(&*out, 0).unwrap();
+    let endian = Endianness::Little;
+    let mut commands = header.load_commands(endian, &*out, 0).unwrap();
+    while let Some(command) = commands.next().unwrap() {
+        if let Some(dysymtab) = command.dysymtab().unwrap() {
+            assert_eq!(dysymtab.iundefsym.get(endian), 1);
+            assert_eq!(dysymtab.nundefsym.get(endian), 1);
+            // The indirect symbol for `_bar` must be updated.
+            let offset = dysymtab.indirectsymoff.get(endian) as usize;
+            assert_eq!(out[offset..][..4], [1, 0, 0, 0]);
+        }
+        if let Some((segment, _)) = command.segment_64().unwrap() {
+            if segment.name() == b"__LINKEDIT" {
+                let (offset, size) = segment.file_range(endian);
+                assert_eq!(offset + size, out.len() as u64);
+            }
+        }
+    }
+    check_signature(&out);
+
+    // Symbols that are referenced can't be deleted.
+    let mut options = object_rewrite::Options::default();
+    options.delete_symbols.insert(b"_bar".to_vec());
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.kind(), object_rewrite::ErrorKind::Modify);
+}
+
+#[test]
+fn rewrite_macho_object() {
+    let mut object = write::Object::new(
+        object::BinaryFormat::MachO,
+        object::Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xe8, 0, 0, 0, 0, 0xc3], 16);
+    object.add_symbol(write::Symbol {
+        name: b"main".to_vec(),
+        value: 0,
+        size: 6,
+        kind: object::SymbolKind::Text,
+        scope: object::SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: object::SymbolFlags::None,
+    });
+    // The symbol names are mangled with a leading underscore.
+    for name in [&b"unused"[..], b"printf"] {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value: 0,
+            size: 0,
+            kind: object::SymbolKind::Text,
+            scope: object::SymbolScope::Dynamic,
+            weak: false,
+            section: write::SymbolSection::Undefined,
+            flags: object::SymbolFlags::None,
+        });
+    }
+    let printf = object.symbol_id(b"printf").unwrap();
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 1,
+                symbol: printf,
+                addend: -4,
+                flags: object::RelocationFlags::MachO {
+                    r_type: macho::X86_64_RELOC_BRANCH,
+                    r_pcrel: true,
+                    r_length: 2,
+                },
+            },
+        )
+        .unwrap();
+    let data = object.write().unwrap();
+
+    // Object files have no padding, so adding a load command moves the data.
+    let mut options = object_rewrite::Options::default();
+    options.macho.add_rpath = vec![b"/opt/foo/lib".to_vec()];
+    options.delete_symbols.insert(b"_unused".to_vec());
+    options
+        .rename_symbols
+        .insert(b"_printf".to_vec(), b"_printf_renamed".to_vec());
+    let out = rewrite(&data, options).unwrap();
+
+    let file = object::File::parse(&*out).unwrap();
+    let section = file.section_by_name("__text").unwrap();
+    assert_eq!(section.data().unwrap(), &[0xe8, 0, 0, 0, 0, 0xc3]);
+    assert_eq!(section.file_range().unwrap().0 % 16, 0);
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 1);
+    let object::RelocationTarget::Symbol(symbol) = relocations[0].1.target() else {
+        panic!("unexpected relocation target");
+    };
+    let symbol = file.symbol_by_index(symbol).unwrap();
+    assert_eq!(symbol.name(), Ok("_printf_renamed"));
+    assert!(symbols(&out)
+        .iter()
+        .all(|(name, _)| name == "_main" || name == "_printf_renamed"));
+}
+
+/// Check that the code signature covers the file and is valid.
+fn check_signature(data: &[u8]) {
+    let endian = Endianness::Little;
+    let header = macho::MachHeader64::<Endianness>::parse(data, 0).unwrap();
+    let mut commands = header.load_commands(endian, data, 0).unwrap();
+    while let Some(command) = commands.next().unwrap() {
+        if command.cmd() != macho::LC_CODE_SIGNATURE {
+            continue;
+        }
+        let command = command
+            .data::<macho::LinkeditDataCommand<Endianness>>()
+            .unwrap();
+        let offset = command.dataoff.get(endian) as usize;
+        let size = command.datasize.get(endian) as usize;
+        assert_eq!(offset + size, data.len());
+        assert_eq!(size, SIGNATURE.size(offset));
+        let mut expect = data.to_vec();
+        SIGNATURE.sign(&mut expect, offset).unwrap();
+        assert!(expect == data);
+        return;
+    }
+    panic!("missing code signature");
+}
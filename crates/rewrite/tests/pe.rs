@@ -0,0 +1,373 @@
+use object::pe;
+use object::read::pe::{ImageNtHeaders, ImageOptionalHeader, PeFile64};
+use object::write::pe::{NtHeaders, Writer};
+use object::{pod, LittleEndian as LE, Object, ObjectSection, U32Bytes, U32};
+
+const CERTIFICATE: &[u8] = &[0xaa; 16];
+
+/// Build a PE file with imports, delay-load imports, debug directory and relocations.
+fn build_pe(certificate: bool) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(4);
+    let text = writer.reserve_text_section(4);
+    let idata = writer.reserve_idata_section(0x140);
+    writer.add_debug_codeview([0x11; 16], 1, b"C:\\build\\test.pdb");
+    writer.reserve_debug_section();
+    writer.add_reloc(text.virtual_address, pe::IMAGE_REL_BASED_DIR64);
+    writer.reserve_reloc_section();
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT,
+        idata.virtual_address + 0xc0,
+        0x40,
+    );
+    if certificate {
+        writer.reserve_certificate_table(CERTIFICATE.len() as u32);
+    }
+
+    // Import descriptors, followed by the lookup tables, names and delay-load descriptors.
+    let address = idata.virtual_address;
+    let mut data = vec![0; 0x140];
+    for (i, (name, thunk)) in [(0x100, 0x40), (0x110, 0x50)].iter().enumerate() {
+        let descriptor = pe::ImageImportDescriptor {
+            original_first_thunk: U32Bytes::new(LE, address + thunk),
+            time_date_stamp: U32Bytes::new(LE, 0),
+            forwarder_chain: U32Bytes::new(LE, 0),
+            name: U32Bytes::new(LE, address + name),
+            first_thunk: U32Bytes::new(LE, address + thunk + 0x20),
+        };
+        data[i * 20..][..20].copy_from_slice(pod::bytes_of(&descriptor));
+    }
+    for (thunk, hint_name) in [(0x40, 0x80), (0x50, 0x90)] {
+        let hint_name = u64::from(address) + hint_name;
+        data[thunk..][..8].copy_from_slice(&hint_name.to_le_bytes());
+        data[thunk + 0x20..][..8].copy_from_slice(&hint_name.to_le_bytes());
+    }
+    data[0x82..][..11].copy_from_slice(b"ExitProcess");
+    data[0x92..][..6].copy_from_slice(b"memcpy");
+    let descriptor = pe::ImageDelayloadDescriptor {
+        attributes: U32::new(LE, 1),
+        dll_name_rva: U32::new(LE, address + 0x128),
+        module_handle_rva: U32::new(LE, 0),
+        import_address_table_rva: U32::new(LE, 0),
+        import_name_table_rva: U32::new(LE, 0),
+        bound_import_address_table_rva: U32::new(LE, 0),
+        unload_information_table_rva: U32::new(LE, 0),
+        time_date_stamp: U32::new(LE, 0),
+    };
+    data[0xc0..][..32].copy_from_slice(pod::bytes_of(&descriptor));
+    data[0x100..][..12].copy_from_slice(b"KERNEL32.dll");
+    data[0x110..][..16].copy_from_slice(b"VCRUNTIME140.dll");
+    data[0x128..][..10].copy_from_slice(b"USER32.dll");
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(NtHeaders {
+        machine: pe::IMAGE_FILE_MACHINE_AMD64,
+        time_date_stamp: 0,
+        characteristics: pe::IMAGE_FILE_EXECUTABLE_IMAGE | pe::IMAGE_FILE_DLL,
+        major_linker_version: 14,
+        minor_linker_version: 0,
+        address_of_entry_point: 0,
+        image_base: 0x1_8000_0000,
+        major_operating_system_version: 6,
+        minor_operating_system_version: 0,
+        major_image_version: 0,
+        minor_image_version: 0,
+        major_subsystem_version: 6,
+        minor_subsystem_version: 0,
+        subsystem: pe::IMAGE_SUBSYSTEM_WINDOWS_CUI,
+        dll_characteristics: 0,
+        size_of_stack_reserve: 0x10_0000,
+        size_of_stack_commit: 0x1000,
+        size_of_heap_reserve: 0x10_0000,
+        size_of_heap_commit: 0x1000,
+    });
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xc3; 4]);
+    writer.write_section(idata.file_offset, &data);
+    writer.write_debug_section();
+    writer.write_reloc_section();
+    if certificate {
+        writer.write_certificate_table(CERTIFICATE);
+    }
+    buffer
+}
+
+fn rewrite(data: &[u8], options: object_rewrite::Options) -> object_rewrite::Result<Vec<u8>> {
+    let mut rewriter = object_rewrite::Rewriter::read(data)?;
+    rewriter.modify(options)?;
+    let mut out = Vec::new();
+    rewriter.write(&mut out)?;
+    Ok(out)
+}
+
+fn sections(data: &[u8]) -> Vec<(String, u32, u32)> {
+    let file = PeFile64::parse(data).unwrap();
+    file.section_table()
+        .iter()
+        .map(|section| {
+            (
+                String::from_utf8(section.raw_name().to_vec()).unwrap(),
+                section.virtual_address.get(LE),
+                section.virtual_size.get(LE),
+            )
+        })
+        .collect()
+}
+
+fn directory(data: &[u8], index: usize) -> u32 {
+    let file = PeFile64::parse(data).unwrap();
+    file.data_directory(index)
+        .map(|dir| dir.virtual_address.get(LE))
+        .unwrap_or(0)
+}
+
+/// Calculate the checksum of a PE file, skipping the checksum field.
+fn calculate_check_sum(data: &[u8], offset: usize) -> u32 {
+    let mut sum = 0u64;
+    for (i, word) in data.chunks(2).enumerate() {
+        if i * 2 == offset || i * 2 == offset + 2 {
+            continue;
+        }
+        sum += u64::from(word[0]) | u64::from(word.get(1).copied().unwrap_or(0)) << 8;
+    }
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    sum as u32 + data.len() as u32
+}
+
+#[test]
+fn rewrite_pe_noop() {
+    let data = build_pe(true);
+    let out = rewrite(&data, object_rewrite::Options::default()).unwrap();
+    assert_eq!(out, data);
+}
+
+#[test]
+fn rewrite_pe_dlls() {
+    let data = build_pe(false);
+    let rewriter = object_rewrite::Rewriter::read(&data).unwrap();
+    assert_eq!(
+        rewriter.pe_dlls().collect::<Vec<_>>(),
+        [&b"KERNEL32.dll"[..], b"VCRUNTIME140.dll", b"USER32.dll"]
+    );
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .pe
+        .replace_dll
+        .insert(b"kernel32.dll".to_vec(), b"k32.dll".to_vec());
+    options.pe.replace_dll.insert(
+        b"VCRUNTIME140.dll".to_vec(),
+        b"vcruntime140_custom.dll".to_vec(),
+    );
+    options
+        .pe
+        .replace_dll
+        .insert(b"USER32.dll".to_vec(), b"user32.dll".to_vec());
+    let out = rewrite(&data, options).unwrap();
+
+    let rewriter = object_rewrite::Rewriter::read(&out).unwrap();
+    assert_eq!(
+        rewriter.pe_dlls().collect::<Vec<_>>(),
+        [&b"k32.dll"[..], b"vcruntime140_custom.dll", b"user32.dll"]
+    );
+    let file = PeFile64::parse(&*out).unwrap();
+    let section = file.section_by_name(".dllname").unwrap();
+    assert_eq!(section.address(), 0x1_8000_5000);
+    assert_eq!(&section.data().unwrap()[..24], b"vcruntime140_custom.dll\0");
+    let import_table = file.import_table().unwrap().unwrap();
+    let mut descriptors = import_table.descriptors().unwrap();
+    let descriptor = descriptors.next().unwrap().unwrap();
+    assert_eq!(
+        import_table.name(descriptor.name.get(LE)).unwrap(),
+        b"k32.dll"
+    );
+    let descriptor = descriptors.next().unwrap().unwrap();
+    assert_eq!(descriptor.name.get(LE), 0x5000);
+    let optional_header = file.nt_headers().optional_header();
+    assert_eq!(optional_header.size_of_image(), 0x6000);
+    // The original checksum was zero, so it isn't updated.
+    assert_eq!(optional_header.check_sum(), 0);
+    // The existing sections are unchanged.
+    assert_eq!(&sections(&out)[..4], sections(&data));
+}
+
+#[test]
+fn rewrite_pe_sections() {
+    let data = build_pe(false);
+
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".debug".to_vec());
+    options
+        .rename_sections
+        .insert(b".text".to_vec(), b".code".to_vec());
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(
+        sections(&out),
+        [
+            (".code".to_string(), 0x1000, 4),
+            (".idata".to_string(), 0x2000, 0x2000),
+            (".reloc".to_string(), 0x4000, 12),
+        ]
+    );
+    assert_eq!(directory(&out, pe::IMAGE_DIRECTORY_ENTRY_DEBUG), 0);
+    assert_ne!(directory(&out, pe::IMAGE_DIRECTORY_ENTRY_BASERELOC), 0);
+    let file = PeFile64::parse(&*out).unwrap();
+    assert_eq!(file.nt_headers().optional_header().size_of_image(), 0x5000);
+    assert_eq!(file.imports().unwrap().len(), 2);
+
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".reloc".to_vec());
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(out.len(), data.len() - 0x200);
+    assert_eq!(directory(&out, pe::IMAGE_DIRECTORY_ENTRY_BASERELOC), 0);
+    let file = PeFile64::parse(&*out).unwrap();
+    let file_header = file.nt_headers().file_header();
+    assert_ne!(
+        file_header.characteristics.get(LE) & pe::IMAGE_FILE_RELOCS_STRIPPED,
+        0
+    );
+    assert_eq!(file.nt_headers().optional_header().size_of_image(), 0x4000);
+
+    let mut options = object_rewrite::Options::default();
+    options.delete_sections.insert(b".text".to_vec());
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Can't delete first section .text");
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .rename_sections
+        .insert(b".text".to_vec(), b".text.long".to_vec());
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Section name .text.long is too long");
+}
+
+#[test]
+fn rewrite_pe_strip_debug() {
+    let data = build_pe(true);
+    let debug = directory(&data, pe::IMAGE_DIRECTORY_ENTRY_DEBUG);
+    assert_ne!(debug, 0);
+
+    let mut options = object_rewrite::Options::default();
+    options.pe.strip_debug = true;
+    options.pe.update_checksum = true;
+    let out = rewrite(&data, options).unwrap();
+    assert_eq!(directory(&out, pe::IMAGE_DIRECTORY_ENTRY_DEBUG), 0);
+    assert_eq!(directory(&out, pe::IMAGE_DIRECTORY_ENTRY_SECURITY), 0);
+    assert_eq!(out.len(), data.len() - CERTIFICATE.len());
+
+    let file = PeFile64::parse(&*out).unwrap();
+    assert_eq!(file.pdb_info().unwrap(), None);
+    let section = file.section_by_name(".debug").unwrap();
+    assert!(section.data().unwrap().iter().all(|byte| *byte == 0));
+
+    let offset = file.dos_header().nt_headers_offset() as usize + 4 + 20 + 64;
+    let check_sum = file.nt_headers().optional_header().check_sum();
+    assert_ne!(check_sum, 0);
+    assert_eq!(check_sum, calculate_check_sum(&out, offset));
+
+    // The checksum is updated for later modifications.
+    let mut options = object_rewrite::Options::default();
+    options
+        .rename_sections
+        .insert(b".text".to_vec(), b".code".to_vec());
+    let out = rewrite(&out, options).unwrap();
+    let file = PeFile64::parse(&*out).unwrap();
+    let check_sum = file.nt_headers().optional_header().check_sum();
+    assert_eq!(check_sum, calculate_check_sum(&out, offset));
+}
+
+#[test]
+fn rewrite_pe_add_sections() {
+    let data = build_pe(true);
+
+    let mut options = object_rewrite::Options::default();
+    options.add_sections.push(object_rewrite::NewSection::new(
+        b".config".to_vec(),
+        vec![1; 0x300],
+    ));
+    let mut section = object_rewrite::NewSection::new(b".stub".to_vec(), vec![0xcc; 4]);
+    section.flags = object::SectionFlags::Coff {
+        characteristics: pe::IMAGE_SCN_CNT_CODE
+            | pe::IMAGE_SCN_MEM_EXECUTE
+            | pe::IMAGE_SCN_MEM_READ,
+    };
+    options.add_sections.push(section);
+    let out = rewrite(&data, options).unwrap();
+
+    // The certificate table is replaced by the new sections.
+    assert_eq!(directory(&out, pe::IMAGE_DIRECTORY_ENTRY_SECURITY), 0);
+    assert_eq!(
+        &sections(&out)[4..],
+        [
+            (".config".to_string(), 0x5000, 0x300),
+            (".stub".to_string(), 0x6000, 4),
+        ]
+    );
+    let file = PeFile64::parse(&*out).unwrap();
+    let section = file.section_by_name(".config").unwrap();
+    assert_eq!(section.data().unwrap(), &[1; 0x300]);
+    let section = file.section_by_name(".stub").unwrap();
+    assert_eq!(section.kind(), object::SectionKind::Text);
+    assert_eq!(section.data().unwrap(), &[0xcc; 4]);
+    let optional_header = file.nt_headers().optional_header();
+    assert_eq!(optional_header.size_of_image(), 0x7000);
+    assert_eq!(optional_header.size_of_code(), 0x400);
+
+    let mut options = object_rewrite::Options::default();
+    options.add_sections.push(object_rewrite::NewSection::new(
+        b".idata".to_vec(),
+        Vec::new(),
+    ));
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Section .idata already exists");
+
+    let mut options = object_rewrite::Options::default();
+    options.add_sections.push(object_rewrite::NewSection::new(
+        b".config.long".to_vec(),
+        Vec::new(),
+    ));
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(err.to_string(), "Section name .config.long is too long");
+}
+
+#[test]
+fn rewrite_pe_update_sections() {
+    let data = build_pe(true);
+    let rewriter = object_rewrite::Rewriter::read(&data).unwrap();
+    assert_eq!(rewriter.section_data(b".text").unwrap(), &[0xc3; 4]);
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .update_sections
+        .insert(b".text".to_vec(), vec![0x90, 0xc3]);
+    options
+        .update_sections
+        .insert(b".reloc".to_vec(), vec![0; 0x1800]);
+    let out = rewrite(&data, options).unwrap();
+
+    let rewriter = object_rewrite::Rewriter::read(&out).unwrap();
+    assert_eq!(rewriter.section_data(b".text").unwrap(), &[0x90, 0xc3]);
+    assert_eq!(rewriter.section_data(b".reloc").unwrap(), &[0; 0x1800]);
+    let file = PeFile64::parse(&*out).unwrap();
+    let text = file.section_by_name(".text").unwrap();
+    assert_eq!(text.file_range(), Some((0x400, 2)));
+    let reloc = file.section_by_name(".reloc").unwrap();
+    assert_eq!(reloc.file_range(), Some((0xa00, 0x1800)));
+    assert_eq!(out.len(), 0x2200);
+    assert_eq!(file.nt_headers().optional_header().size_of_image(), 0x6000);
+
+    let mut options = object_rewrite::Options::default();
+    options
+        .update_sections
+        .insert(b".text".to_vec(), vec![0; 0x1001]);
+    let err = rewrite(&data, options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Contents of section .text can't be larger than 0x1000 bytes"
+    );
+}
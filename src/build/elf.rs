@@ -1,5 +1,6 @@
 //! This module provides a [`Builder`] for reading, modifying, and then writing ELF files.
 use alloc::vec::Vec;
+use core::cmp;
 use core::convert::TryInto;
 use core::fmt;
 use core::marker::PhantomData;
@@ -8,8 +9,10 @@ use hashbrown::HashMap;
 #[cfg(feature = "std")]
 use std::collections::HashMap;
 
-use crate::build::{ByteString, Bytes, Error, Id, IdPrivate, Item, Result, Table};
+use crate::build::{hash, ByteString, Bytes, Error, Id, IdPrivate, Item, Result, Table};
 use crate::elf;
+use crate::endian::{Endian, U32};
+use crate::pod;
 use crate::read::elf::{Dyn, FileHeader, ProgramHeader, Rela, SectionHeader, Sym};
 use crate::read::{self, FileKind, ReadRef};
 use crate::write;
@@ -58,13 +61,37 @@ pub struct Builder<'data> {
     /// The filenames used in the GNU version definitions.
     pub version_files: VersionFiles<'data>,
     /// The bucket count parameter for the hash table.
+    ///
+    /// If this is zero, then the bucket count is calculated from the number
+    /// of symbols using [`write::elf::Class::hash_bucket_count`].
     pub hash_bucket_count: u32,
     /// The bloom shift parameter for the GNU hash table.
     pub gnu_hash_bloom_shift: u32,
     /// The bloom count parameter for the GNU hash table.
     pub gnu_hash_bloom_count: u32,
     /// The bucket count parameter for the GNU hash table.
+    ///
+    /// If this is zero, then all of the GNU hash parameters are calculated
+    /// from the number of symbols using [`write::elf::Class::gnu_hash_parameters`].
     pub gnu_hash_bucket_count: u32,
+    /// Data within the file range of the segments that is not part of any
+    /// section or header.
+    ///
+    /// This is read from the file if it has segments, and is written back at
+    /// the same file offsets. Parts of the data that overlap a section or a
+    /// header when writing are not written. Only data that is not all zeros
+    /// is stored.
+    ///
+    /// Clear this to fill the space with zeros instead.
+    pub padding: Vec<Padding<'data>>,
+    /// Data at the end of the file that is not part of any section, segment,
+    /// or header.
+    ///
+    /// This is typically data such as a signature or an archive that has been
+    /// appended to an executable. It is written after all other data.
+    ///
+    /// Set this to empty to remove the data.
+    pub overlay: Bytes<'data>,
     marker: PhantomData<()>,
 }
 
@@ -87,6 +114,8 @@ impl<'data> Builder<'data> {
             gnu_hash_bloom_shift: 0,
             gnu_hash_bloom_count: 0,
             gnu_hash_bucket_count: 0,
+            padding: Vec::new(),
+            overlay: Bytes::default(),
             marker: PhantomData,
         }
     }
@@ -149,6 +178,8 @@ impl<'data> Builder<'data> {
             gnu_hash_bloom_shift: 0,
             gnu_hash_bloom_count: 0,
             gnu_hash_bucket_count: 0,
+            padding: Vec::new(),
+            overlay: Bytes::default(),
             marker: PhantomData,
         };
 
@@ -380,10 +411,115 @@ impl<'data> Builder<'data> {
             builder.sections.len(),
         )?;
         builder.read_gnu_versions(endian, data, &sections, &dynamic_symbols)?;
+        builder.read_padding(header, endian, data, sections.len());
 
         Ok(builder)
     }
 
+    /// Read the data that isn't part of any section or header.
+    fn read_padding<Elf, R>(
+        &mut self,
+        header: &Elf,
+        endian: Elf::Endian,
+        data: R,
+        section_count: usize,
+    ) where
+        Elf: FileHeader<Endian = Endianness>,
+        R: ReadRef<'data>,
+    {
+        // The file range for the given offset and size, if it is valid.
+        let file_range = |offset: u64, size: u64| Some((offset, offset.checked_add(size)?));
+        let len = data.len().ok();
+
+        // The file ranges that contain headers or section data.
+        let mut ranges = Vec::new();
+        ranges.push((0, self.file_header_size() as u64));
+        let phnum = self.segments.count() as u64;
+        if phnum != 0 {
+            let phentsize = u64::from(header.e_phentsize(endian));
+            ranges.extend(
+                phnum
+                    .checked_mul(phentsize)
+                    .and_then(|size| file_range(self.header.e_phoff, size)),
+            );
+        }
+        let shoff: u64 = header.e_shoff(endian).into();
+        if shoff != 0 {
+            let shentsize = u64::from(header.e_shentsize(endian));
+            ranges.extend(
+                (section_count as u64)
+                    .checked_mul(shentsize)
+                    .and_then(|size| file_range(shoff, size)),
+            );
+        }
+        for section in &self.sections {
+            if section.sh_type != elf::SHT_NOBITS {
+                ranges.extend(file_range(section.sh_offset, section.sh_size));
+            }
+        }
+        let mut file_end = ranges.iter().map(|range| range.1).max().unwrap_or(0);
+
+        // Find the gaps within segments, split at the boundaries of PT_LOAD segments.
+        // Segments that are empty or extend past the end of the file are ignored.
+        let mut segment_end = 0;
+        let mut cuts = vec![0];
+        for segment in &self.segments {
+            if segment.p_filesz == 0 {
+                continue;
+            }
+            let Some((start, end)) = file_range(segment.p_offset, segment.p_filesz) else {
+                continue;
+            };
+            if len.map_or(true, |len| end > len) {
+                continue;
+            }
+            segment_end = cmp::max(segment_end, end);
+            if segment.p_type == elf::PT_LOAD {
+                cuts.push(start);
+                cuts.push(end);
+            }
+        }
+        file_end = cmp::max(file_end, segment_end);
+        for range in &ranges {
+            cuts.push(range.0);
+            cuts.push(range.1);
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+        ranges.sort_unstable();
+        let mut ranges = ranges.iter().peekable();
+        let mut covered_end = 0;
+        for cut in cuts.windows(2) {
+            let (start, end) = (cut[0], cut[1]);
+            if end > segment_end {
+                break;
+            }
+            while let Some(range) = ranges.next_if(|range| range.0 <= start) {
+                covered_end = cmp::max(covered_end, range.1);
+            }
+            if covered_end > start {
+                continue;
+            }
+            let Ok(bytes) = data.read_bytes_at(start, end - start) else {
+                continue;
+            };
+            if bytes.iter().any(|byte| *byte != 0) {
+                self.padding.push(Padding {
+                    offset: start,
+                    data: bytes.into(),
+                });
+            }
+        }
+
+        if let Some(len) = len {
+            if len > file_end {
+                if let Ok(bytes) = data.read_bytes_at(file_end, len - file_end) {
+                    self.overlay = bytes.into();
+                }
+            }
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn read_relocations<Elf, Rel, R>(
         index: read::SectionIndex,
@@ -433,10 +569,10 @@ impl<'data> Builder<'data> {
             )
             .map(SectionData::Relocation)
         } else {
-            return Err(Error(format!(
+            Err(Error(format!(
                 "Invalid sh_link {} in relocation section at index {}",
                 link.0, index,
-            )));
+            )))
         }
     }
 
@@ -918,9 +1054,7 @@ impl<'data> Builder<'data> {
                 if hash_id.is_some() {
                     hash = Some(elf::hash(&symbol.name));
                 }
-                if gnu_hash_id.is_some()
-                    && (symbol.section.is_some() || symbol.st_shndx != elf::SHN_UNDEF)
-                {
+                if gnu_hash_id.is_some() && !symbol.is_undefined() {
                     gnu_hash = Some(elf::gnu_hash(&symbol.name));
                 }
             }
@@ -937,20 +1071,17 @@ impl<'data> Builder<'data> {
             .count();
         // We must sort for GNU hash before allocating symbol indices.
         let mut gnu_hash_symbol_count = 0;
+        let mut gnu_hash = write::elf::GnuHashParameters::default();
         if gnu_hash_id.is_some() {
-            if self.gnu_hash_bucket_count == 0 {
-                return Err(Error::new(".gnu.hash bucket count is zero"));
-            }
-            // TODO: recalculate bucket_count?
+            gnu_hash_symbol_count = out_dynsyms[num_local_dynamic..]
+                .iter()
+                .filter(|sym| sym.gnu_hash.is_some())
+                .count() as u32;
+            gnu_hash = self.gnu_hash_parameters(gnu_hash_symbol_count);
             out_dynsyms[num_local_dynamic..].sort_by_key(|sym| match sym.gnu_hash {
                 None => (0, 0),
-                Some(hash) => (1, hash % self.gnu_hash_bucket_count),
+                Some(hash) => (1, hash % gnu_hash.bucket_count),
             });
-            gnu_hash_symbol_count = out_dynsyms
-                .iter()
-                .skip(num_local_dynamic)
-                .skip_while(|sym| sym.gnu_hash.is_none())
-                .count() as u32;
         }
         let mut out_dynsyms_index = vec![None; self.dynamic_symbols.len()];
         if dynsym_id.is_some() {
@@ -963,6 +1094,7 @@ impl<'data> Builder<'data> {
         // Hash parameters.
         let hash_index_base = 1; // Null symbol.
         let hash_chain_count = hash_index_base + out_dynsyms.len() as u32;
+        let hash_bucket_count = self.hash_bucket_count(out_dynsyms.len() as u32);
 
         // GNU hash parameters.
         let gnu_hash_index_base = if gnu_hash_symbol_count == 0 {
@@ -1209,13 +1341,13 @@ impl<'data> Builder<'data> {
                     }
                     SectionData::Hash => {
                         hash_addr = Some(section.sh_addr);
-                        writer.reserve_hash(self.hash_bucket_count, hash_chain_count)
+                        writer.reserve_hash(hash_bucket_count, hash_chain_count)
                     }
                     SectionData::GnuHash => {
                         gnu_hash_addr = Some(section.sh_addr);
                         writer.reserve_gnu_hash(
-                            self.gnu_hash_bloom_count,
-                            self.gnu_hash_bucket_count,
+                            gnu_hash.bloom_count,
+                            gnu_hash.bucket_count,
                             gnu_hash_symbol_count,
                         )
                     }
@@ -1247,6 +1379,35 @@ impl<'data> Builder<'data> {
             }
         }
 
+        // Reserve the rest of the segments, and any padding that extends past
+        // the alloc sections.
+        let mut padding_end = 0;
+        if !self.segments.is_empty() {
+            for segment in &self.segments {
+                if segment.p_filesz == 0 {
+                    continue;
+                }
+                let end: usize = segment
+                    .p_offset
+                    .checked_add(segment.p_filesz)
+                    .and_then(|end| end.try_into().ok())
+                    .ok_or_else(|| {
+                        Error(format!(
+                            "Invalid segment file range 0x{:x}+0x{:x}",
+                            segment.p_offset, segment.p_filesz
+                        ))
+                    })?;
+                padding_end = cmp::max(padding_end, end);
+            }
+            self.padding.sort_by_key(|padding| padding.offset);
+            for padding in &self.padding {
+                padding_end = cmp::max(padding_end, padding.offset as usize + padding.data.len());
+            }
+            if padding_end > writer.reserved_len() {
+                writer.reserve_until(padding_end);
+            }
+        }
+
         // Reserve non-alloc sections at any offset.
         for out_section in &mut out_sections {
             let section = self.sections.get(out_section.id);
@@ -1300,6 +1461,7 @@ impl<'data> Builder<'data> {
 
         writer.reserve_shstrtab();
         writer.reserve_section_headers();
+        writer.reserve(self.overlay.len(), 1);
 
         // Start writing.
         writer.write_file_header(&write::elf::FileHeader {
@@ -1337,7 +1499,7 @@ impl<'data> Builder<'data> {
                     continue;
                 }
 
-                writer.pad_until(out_section.offset);
+                Self::write_padding(&mut writer, &self.padding, out_section.offset);
                 match &section.data {
                     SectionData::Data(data) => {
                         writer.write(data);
@@ -1434,24 +1596,18 @@ impl<'data> Builder<'data> {
                         writer.write_dynstr();
                     }
                     SectionData::Hash => {
-                        if self.hash_bucket_count == 0 {
-                            return Err(Error::new(".hash bucket count is zero"));
-                        }
-                        writer.write_hash(self.hash_bucket_count, hash_chain_count, |index| {
+                        writer.write_hash(hash_bucket_count, hash_chain_count, |index| {
                             out_dynsyms
                                 .get(index.checked_sub(hash_index_base)? as usize)?
                                 .hash
                         });
                     }
                     SectionData::GnuHash => {
-                        if self.gnu_hash_bucket_count == 0 {
-                            return Err(Error::new(".gnu.hash bucket count is zero"));
-                        }
                         writer.write_gnu_hash(
                             gnu_hash_symbol_base,
-                            self.gnu_hash_bloom_shift,
-                            self.gnu_hash_bloom_count,
-                            self.gnu_hash_bucket_count,
+                            gnu_hash.bloom_shift,
+                            gnu_hash.bloom_count,
+                            gnu_hash.bucket_count,
                             gnu_hash_symbol_count,
                             |index| {
                                 out_dynsyms[(gnu_hash_index_base + index) as usize]
@@ -1540,6 +1696,9 @@ impl<'data> Builder<'data> {
                     }
                 }
             }
+            if padding_end > writer.len() {
+                Self::write_padding(&mut writer, &self.padding, padding_end);
+            }
         }
 
         // Write non-alloc sections.
@@ -1739,10 +1898,35 @@ impl<'data> Builder<'data> {
                 }
             }
         }
+        writer.write(&self.overlay);
         debug_assert_eq!(writer.reserved_len(), writer.len());
         Ok(())
     }
 
+    /// Write the padding that is before the given offset, and then pad with zeros
+    /// until the offset.
+    ///
+    /// Padding that has already been written over is skipped.
+    fn write_padding(writer: &mut write::elf::Writer<'_>, padding: &[Padding<'_>], offset: usize) {
+        for padding in padding {
+            let start = padding.offset as usize;
+            let end = start + padding.data.len();
+            if start >= offset {
+                break;
+            }
+            let len = writer.len();
+            if end <= len {
+                continue;
+            }
+            let start = cmp::max(start, len);
+            let end = cmp::min(end, offset);
+            writer.pad_until(start);
+            let data_offset = padding.offset as usize;
+            writer.write(&padding.data[start - data_offset..end - data_offset]);
+        }
+        writer.pad_until(offset);
+    }
+
     /// Delete segments, symbols, relocations, and dynamics that refer
     /// to deleted items.
     ///
@@ -1966,9 +2150,17 @@ impl<'data> Builder<'data> {
     /// To get an accurate result, you may need to first call
     /// [`Self::delete_orphan_symbols`].
     pub fn hash_size(&self) -> usize {
-        let chain_count = 1 + self.dynamic_symbols.count();
-        self.class()
-            .hash_size(self.hash_bucket_count, chain_count as u32)
+        let symbol_count = self.dynamic_symbols.count() as u32;
+        let bucket_count = self.hash_bucket_count(symbol_count);
+        self.class().hash_size(bucket_count, 1 + symbol_count)
+    }
+
+    fn hash_bucket_count(&self, symbol_count: u32) -> u32 {
+        if self.hash_bucket_count == 0 {
+            self.class().hash_bucket_count(symbol_count)
+        } else {
+            self.hash_bucket_count
+        }
     }
 
     /// Calculate the size of the GNU hash table.
@@ -1976,12 +2168,22 @@ impl<'data> Builder<'data> {
     /// To get an accurate result, you may need to first call
     /// [`Self::delete_orphan_symbols`].
     pub fn gnu_hash_size(&self) -> usize {
-        let symbol_count = self.dynamic_symbols.count_defined();
-        self.class().gnu_hash_size(
-            self.gnu_hash_bloom_count,
-            self.gnu_hash_bucket_count,
-            symbol_count as u32,
-        )
+        let symbol_count = self.dynamic_symbols.count_defined() as u32;
+        let gnu_hash = self.gnu_hash_parameters(symbol_count);
+        self.class()
+            .gnu_hash_size(gnu_hash.bloom_count, gnu_hash.bucket_count, symbol_count)
+    }
+
+    fn gnu_hash_parameters(&self, symbol_count: u32) -> write::elf::GnuHashParameters {
+        if self.gnu_hash_bucket_count == 0 {
+            self.class().gnu_hash_parameters(symbol_count)
+        } else {
+            write::elf::GnuHashParameters {
+                bloom_shift: self.gnu_hash_bloom_shift,
+                bloom_count: self.gnu_hash_bloom_count,
+                bucket_count: self.gnu_hash_bucket_count,
+            }
+        }
     }
 
     /// Calculate the size of the GNU symbol version section.
@@ -2083,6 +2285,505 @@ impl<'data> Builder<'data> {
         }
     }
 
+    /// Assign file offsets and addresses to allocated sections that need them.
+    ///
+    /// This is used after adding new allocated sections, or after modifying sections
+    /// so that they no longer fit at their original address.
+    ///
+    /// A new allocated section must be added to [`Self::sections`] with a `sh_offset`
+    /// of zero, which indicates that it has not been placed yet. The section must not
+    /// be added to any segment. Existing sections which overlap another section, or
+    /// the program headers, are also moved if the contents of the section cannot be
+    /// referenced by address from another section.
+    ///
+    /// The sections that need placing are appended to new `PT_LOAD` segments, one for
+    /// each unique combination of segment permission flags. Other segments containing
+    /// moved sections are updated, and split if needed.
+    ///
+    /// This can also be used to make room for program headers after adding segments.
+    /// The `PT_PHDR` segment is always updated to match the number of program headers,
+    /// including when segments have been deleted.
+    ///
+    /// This does not change the size of existing `PT_LOAD` segments.
+    ///
+    /// This calls [`Self::delete_orphans`], [`Self::delete_unused_versions`] and
+    /// [`Self::set_section_sizes`] first.
+    ///
+    /// Sections are not placed if there are no segments, such as for relocatable objects.
+    pub fn place_sections(&mut self) -> Result<()> {
+        self.delete_orphans();
+        self.delete_unused_versions();
+        self.set_section_sizes();
+        if self.segments.is_empty() {
+            return Ok(());
+        }
+
+        let mut added_p_flags = Vec::new();
+        let mut added_segments = 0;
+
+        // Loop until we reach a fixed point for the number of additional segments needed.
+        loop {
+            let mut move_sections = self.find_move_sections(added_segments)?;
+            if move_sections.is_empty() {
+                break;
+            }
+
+            // Calculate the number of additional PT_LOAD segments needed.
+            added_p_flags.clear();
+            for id in &move_sections {
+                let section = self.sections.get_mut(*id);
+                // Flag the section as needing to move.
+                section.sh_offset = 0;
+                // We need one PT_LOAD segment for each unique combination of p_flags.
+                let p_flags = section.p_flags();
+                if !added_p_flags.contains(&p_flags) {
+                    added_p_flags.push(p_flags);
+                }
+            }
+
+            // If moving a section that is part of a non-PT_LOAD segment, then we may need to
+            // split the segment, which will require an additional segment.
+            let mut split_segments = 0;
+            for segment in &self.segments {
+                if segment.p_type == elf::PT_LOAD {
+                    continue;
+                }
+                let mut any = false;
+                let mut all = true;
+                for id in &segment.sections {
+                    if move_sections.contains(id) {
+                        any = true;
+                    } else {
+                        all = false;
+                    }
+                }
+                if !any || all {
+                    continue;
+                }
+                split_segments += 1;
+            }
+
+            // Check if we have reached a fixed point for the number of additional segments needed.
+            if added_segments < split_segments + added_p_flags.len() {
+                added_segments = split_segments + added_p_flags.len();
+                continue;
+            }
+
+            // Add the PT_LOAD segments and append sections to them.
+            // Try to keep the same order of sections in the new segments.
+            move_sections.sort_by_key(|id| {
+                let section = self.sections.get(*id);
+                (section.sh_addr, section.sh_size)
+            });
+            for p_flags in added_p_flags {
+                // TODO: reuse segments that only contain movable sections
+                let segment = self.segments.add_load_segment(p_flags, self.load_align);
+                for id in &move_sections {
+                    let section = self.sections.get_mut(*id);
+                    if p_flags == section.p_flags() {
+                        segment.append_section(section);
+                    }
+                }
+            }
+
+            // Split or move non-PT_LOAD segments that contain sections that have been moved.
+            let sections = &self.sections;
+            let mut split_segments = Vec::new();
+            for segment in &mut self.segments {
+                if segment.p_type == elf::PT_LOAD {
+                    continue;
+                }
+
+                let mut any = false;
+                let mut all = true;
+                for id in &segment.sections {
+                    if move_sections.contains(id) {
+                        any = true;
+                    } else {
+                        all = false;
+                    }
+                }
+                if !any {
+                    continue;
+                }
+                if !all {
+                    // Segment needs splitting.
+                    // Remove all the sections that have been moved, and store them so
+                    // that we can add the new segment later.
+                    let mut split_sections = Vec::new();
+                    segment.sections.retain(|id| {
+                        if move_sections.contains(id) {
+                            split_sections.push(*id);
+                            false
+                        } else {
+                            true
+                        }
+                    });
+                    split_segments.push((segment.id(), split_sections));
+                }
+
+                // The remaining sections have already been assigned an address.
+                // Recalcuate the file and address ranges for the segment.
+                // TODO: verify that the sections are contiguous. If not, try to slide the sections
+                // down in memory.
+                segment.recalculate_ranges(sections);
+            }
+
+            // Add new segments due to splitting.
+            for (segment_id, split_sections) in split_segments {
+                let segment = self.segments.copy(segment_id);
+                for id in split_sections {
+                    let section = self.sections.get_mut(id);
+                    segment.append_section(section);
+                }
+            }
+
+            break;
+        }
+
+        // Update the PT_PHDR segment to match the program headers.
+        let size = self.program_headers_size() as u64;
+        for segment in &mut self.segments {
+            if segment.p_type != elf::PT_PHDR {
+                continue;
+            }
+            segment.p_filesz = size;
+            segment.p_memsz = size;
+        }
+        Ok(())
+    }
+
+    /// Append an allocated section to the end of a `PT_LOAD` segment.
+    ///
+    /// The file offset and address of the section are set to the end of the segment,
+    /// and the segment is extended to include the section. This uses the `sh_size`
+    /// field of the section, so [`Self::set_section_sizes`] may need to be called first.
+    ///
+    /// If the extended segment overlaps the file range of any later data, then the file
+    /// offsets of the later segments and sections are increased, while preserving their
+    /// addresses and alignment. Returns an error if the extended segment overlaps the
+    /// address range of another `PT_LOAD` segment, or if the segment contains
+    /// uninitialized data.
+    ///
+    /// Segments other than `PT_LOAD` that should include the section, such as
+    /// `PT_GNU_RELRO`, must be updated separately using [`Segment::append_section`].
+    pub fn append_load_section(&mut self, segment: SegmentId, section: SectionId) -> Result<()> {
+        let load = self.segments.get(segment);
+        if load.p_type != elf::PT_LOAD {
+            return Err(Error::new("Segment is not PT_LOAD"));
+        }
+        if load.p_filesz != load.p_memsz {
+            return Err(Error::new(
+                "Cannot append section to PT_LOAD segment containing uninitialized data",
+            ));
+        }
+        let section_ref = self.sections.get(section);
+        if !section_ref.is_alloc() {
+            return Err(Error(format!(
+                "Cannot append non-alloc section '{}' to PT_LOAD segment",
+                section_ref.name
+            )));
+        }
+        let old_offset_end = load.p_offset + load.p_filesz;
+        let old_address_end = load.p_vaddr + load.p_memsz;
+        let align = section_ref.sh_addralign.max(1);
+        let address = (old_address_end + (align - 1)) & !(align - 1);
+        let address_end = address + section_ref.sh_size;
+        let offset_end = if section_ref.sh_type == elf::SHT_NOBITS {
+            load.p_offset + (address - load.p_vaddr)
+        } else {
+            load.p_offset + (address_end - load.p_vaddr)
+        };
+
+        let mut next_offset = u64::MAX;
+        let mut shift_align = 1;
+        for other in &self.segments {
+            if other.id == segment || other.p_type != elf::PT_LOAD {
+                continue;
+            }
+            if other.p_vaddr < address_end && old_address_end < other.p_vaddr + other.p_memsz {
+                return Err(Error(format!(
+                    "Appending section '{}' would overlap PT_LOAD segment at address 0x{:x}",
+                    section_ref.name, other.p_vaddr
+                )));
+            }
+            if other.p_offset >= old_offset_end {
+                next_offset = next_offset.min(other.p_offset);
+                shift_align = shift_align.max(other.p_align);
+            }
+        }
+        for other in &self.sections {
+            if other.id == section || !other.is_alloc() || other.sh_type == elf::SHT_NOBITS {
+                continue;
+            }
+            if other.sh_offset >= old_offset_end && !load.sections.contains(&other.id) {
+                next_offset = next_offset.min(other.sh_offset);
+                shift_align = shift_align.max(other.sh_addralign);
+            }
+        }
+
+        // Move the file offsets of any later data.
+        if offset_end > next_offset {
+            let shift_align = shift_align.max(self.load_align).max(1);
+            let shift = (offset_end - next_offset + (shift_align - 1)) & !(shift_align - 1);
+            let load_sections = load.sections.clone();
+            for other in &mut self.segments {
+                if other.id != segment && other.p_offset >= old_offset_end {
+                    other.p_offset += shift;
+                }
+            }
+            for other in &mut self.sections {
+                if other.id != section
+                    && other.is_alloc()
+                    && other.sh_offset >= old_offset_end
+                    && !load_sections.contains(&other.id)
+                {
+                    other.sh_offset += shift;
+                }
+            }
+            for padding in &mut self.padding {
+                if padding.offset >= old_offset_end {
+                    padding.offset += shift;
+                }
+            }
+        }
+
+        let section = self.sections.get_mut(section);
+        self.segments.get_mut(segment).append_section(section);
+        Ok(())
+    }
+
+    /// Move `PT_LOAD` segments to lower file offsets to remove unused space between them.
+    ///
+    /// Each `PT_LOAD` segment, in order of file offset, is moved to the lowest offset
+    /// after the previous data in the file that preserves the alignment of the segment
+    /// and its sections relative to their addresses. The sections in the segment are
+    /// moved with it, and the ranges of other segments that contain these sections are
+    /// updated.
+    ///
+    /// Addresses are not changed, so unused space within a segment is not removed.
+    /// Segments that contain the file header or program headers are not moved.
+    /// Any [`Self::padding`] within a `PT_LOAD` segment is moved with the segment,
+    /// and other padding is deleted.
+    ///
+    /// This should be called after [`Self::place_sections`], if needed.
+    pub fn compact_segments(&mut self) {
+        let segments = &self.segments;
+        self.padding.retain(|padding| {
+            segments
+                .iter()
+                .any(|segment| segment.is_load() && segment.contains_offset(padding.offset))
+        });
+
+        // The segments to move, and the allocated sections that aren't in
+        // a PT_LOAD segment, in order of file offset.
+        let mut blocks = Vec::new();
+        for segment in &self.segments {
+            if segment.p_type == elf::PT_LOAD {
+                blocks.push((segment.p_offset, Some(segment.id)));
+            }
+        }
+        for section in &self.sections {
+            if section.is_alloc()
+                && section.sh_type != elf::SHT_NOBITS
+                && !self.segments.iter().any(|segment| {
+                    segment.p_type == elf::PT_LOAD && segment.sections.contains(&section.id)
+                })
+            {
+                blocks.push((section.sh_offset + section.sh_size, None));
+            }
+        }
+        blocks.sort_by_key(|(offset, _)| *offset);
+
+        let mut offset_end = (self.file_header_size() + self.program_headers_size()) as u64;
+        let mut moved = Vec::new();
+        for (offset, id) in blocks {
+            let Some(id) = id else {
+                offset_end = offset_end.max(offset);
+                continue;
+            };
+            let segment = self.segments.get(id);
+            if segment.p_offset > offset_end {
+                let mut align = segment.p_align.max(1);
+                for section in &segment.sections {
+                    align = align.max(self.sections.get(*section).sh_addralign);
+                }
+                let gap = segment.p_offset - offset_end;
+                let shift = gap - gap % align;
+                if shift != 0 {
+                    for section in &segment.sections {
+                        let section = self.sections.get_mut(*section);
+                        section.sh_offset = section.sh_offset.saturating_sub(shift);
+                        moved.push(section.id);
+                    }
+                    for padding in &mut self.padding {
+                        if segment.contains_offset(padding.offset) {
+                            padding.offset -= shift;
+                        }
+                    }
+                    self.segments.get_mut(id).p_offset -= shift;
+                }
+            }
+            let segment = self.segments.get(id);
+            offset_end = offset_end.max(segment.p_offset + segment.p_filesz);
+        }
+
+        let sections = &self.sections;
+        for segment in &mut self.segments {
+            if segment.p_type != elf::PT_LOAD
+                && segment.sections.iter().any(|id| moved.contains(id))
+            {
+                segment.recalculate_ranges(sections);
+            }
+        }
+    }
+
+    /// Find the sections that need to be placed by `place_sections`.
+    fn find_move_sections(&self, added_segments: usize) -> Result<Vec<SectionId>> {
+        enum BlockKind {
+            FileHeader,
+            ProgramHeaders,
+            Segment,
+            Section(SectionId),
+        }
+
+        struct Block {
+            kind: BlockKind,
+            address: u64,
+            size: u64,
+            // Higher means better to move. 0 means never move.
+            move_priority: u8,
+        }
+
+        let mut move_sections = Vec::new();
+        let mut blocks = Vec::new();
+        let file_header_size = self.file_header_size() as u64;
+        let program_headers_size = (self.program_headers_size()
+            + added_segments * self.class().program_header_size())
+            as u64;
+        let interp = self.interp_section();
+
+        if let Some(segment) = self.segments.find_load_segment_from_offset(0) {
+            let address = segment.address_from_offset(0);
+            blocks.push(Block {
+                kind: BlockKind::FileHeader,
+                address,
+                size: file_header_size,
+                move_priority: 0,
+            });
+        }
+        if let Some(segment) = self
+            .segments
+            .find_load_segment_from_offset(self.header.e_phoff)
+        {
+            let address = segment.address_from_offset(self.header.e_phoff);
+            blocks.push(Block {
+                kind: BlockKind::ProgramHeaders,
+                address,
+                size: program_headers_size,
+                move_priority: 0,
+            });
+        }
+        for segment in &self.segments {
+            if segment.p_type != elf::PT_LOAD {
+                continue;
+            }
+            // Add zero-sized blocks at the start and end of the segment
+            // to prevent changing the segment address or size.
+            blocks.push(Block {
+                kind: BlockKind::Segment,
+                address: segment.p_vaddr,
+                size: 0,
+                move_priority: 0,
+            });
+            blocks.push(Block {
+                kind: BlockKind::Segment,
+                address: segment.p_vaddr + segment.p_memsz,
+                size: 0,
+                move_priority: 0,
+            });
+        }
+        for section in &self.sections {
+            if !section.is_alloc() {
+                continue;
+            }
+            if section.sh_offset == 0 {
+                // Newly added section that needs to be assigned to a segment,
+                // or a section that has already been flagged for moving.
+                move_sections.push(section.id());
+                continue;
+            }
+            if section.sh_type == elf::SHT_NOBITS && section.sh_flags & u64::from(elf::SHF_TLS) != 0
+            {
+                // Uninitialized TLS sections are not part of the address space.
+                continue;
+            }
+            let move_priority = match &section.data {
+                // Can't move sections whose address may referenced from
+                // a section that we can't rewrite.
+                SectionData::Data(_) => {
+                    if Some(section.id()) == interp {
+                        1
+                    } else {
+                        0
+                    }
+                }
+                SectionData::UninitializedData(_) | SectionData::Dynamic(_) => 0,
+                // TODO: Can be referenced by dynamic entries, but we don't support that yet.
+                SectionData::DynamicRelocation(_) => 0,
+                // None of these can be referenced by address that I am aware of.
+                SectionData::Relocation(_)
+                | SectionData::Note(_)
+                | SectionData::Attributes(_)
+                | SectionData::SectionString
+                | SectionData::Symbol
+                | SectionData::SymbolSectionIndex
+                | SectionData::String
+                | SectionData::DynamicSymbol
+                | SectionData::DynamicString
+                | SectionData::Hash
+                | SectionData::GnuHash
+                | SectionData::GnuVersym
+                | SectionData::GnuVerdef
+                | SectionData::GnuVerneed => 2,
+            };
+            blocks.push(Block {
+                kind: BlockKind::Section(section.id()),
+                address: section.sh_addr,
+                size: section.sh_size,
+                move_priority,
+            });
+        }
+        blocks.sort_by_key(|block| (block.address, block.size));
+
+        // For each pair of overlapping blocks, decide which one to move.
+        let mut i = 0;
+        while i + 1 < blocks.len() {
+            let end_address = blocks[i].address + blocks[i].size;
+            if end_address <= blocks[i + 1].address {
+                i += 1;
+                continue;
+            }
+            // Prefer moving the earlier block, since it is the reason for the overlap.
+            let index = if blocks[i].move_priority >= blocks[i + 1].move_priority {
+                if blocks[i].move_priority == 0 {
+                    return Err(Error::new("Overlapping immovable sections"));
+                }
+                i
+            } else {
+                i + 1
+            };
+            if let BlockKind::Section(section) = blocks[index].kind {
+                move_sections.push(section);
+                blocks.remove(index);
+            } else {
+                // Only sections can be moved.
+                unreachable!();
+            }
+        }
+        Ok(move_sections)
+    }
+
     /// Find the section containing the dynamic table.
     ///
     /// This uses the `PT_DYNAMIC` program header to find the dynamic section.
@@ -2117,6 +2818,204 @@ impl<'data> Builder<'data> {
         }
     }
 
+    fn dynamic_data_mut_or_err(&mut self) -> Result<&mut Vec<Dynamic<'data>>> {
+        self.dynamic_data_mut()
+            .ok_or_else(|| Error::new("No dynamic section found"))
+    }
+
+    /// Return the first dynamic table entry with the given tag.
+    pub fn dynamic_entry(&self, tag: u32) -> Option<&Dynamic<'data>> {
+        self.dynamic_data()?
+            .iter()
+            .find(|dynamic| dynamic.tag() == tag)
+    }
+
+    /// Set the dynamic table entry for the tag of the given entry.
+    ///
+    /// The first entry with the same tag is replaced, and any other entries with the
+    /// same tag are removed. If there is no entry with the same tag, then the entry is
+    /// appended to the table.
+    ///
+    /// Returns an error if there is no dynamic section.
+    pub fn set_dynamic(&mut self, entry: Dynamic<'data>) -> Result<()> {
+        let dynamics = self.dynamic_data_mut_or_err()?;
+        let tag = entry.tag();
+        match dynamics.iter().position(|dynamic| dynamic.tag() == tag) {
+            Some(index) => {
+                dynamics[index] = entry;
+                let mut i = 0;
+                dynamics.retain(|dynamic| {
+                    i += 1;
+                    i <= index + 1 || dynamic.tag() != tag
+                });
+            }
+            None => dynamics.push(entry),
+        }
+        Ok(())
+    }
+
+    /// Insert an entry at the given index in the dynamic table.
+    ///
+    /// The index must not be greater than the number of entries. The `DT_NULL`
+    /// terminator is not included in the entries, and is always written last.
+    ///
+    /// Returns an error if there is no dynamic section.
+    pub fn insert_dynamic(&mut self, index: usize, entry: Dynamic<'data>) -> Result<()> {
+        let dynamics = self.dynamic_data_mut_or_err()?;
+        if index > dynamics.len() {
+            return Err(Error(format!("Invalid dynamic entry index {}", index)));
+        }
+        dynamics.insert(index, entry);
+        Ok(())
+    }
+
+    /// Remove all entries with the given tag from the dynamic table.
+    ///
+    /// Returns the number of entries that were removed, or an error if there is no
+    /// dynamic section.
+    pub fn remove_dynamic(&mut self, tag: u32) -> Result<usize> {
+        let dynamics = self.dynamic_data_mut_or_err()?;
+        let len = dynamics.len();
+        dynamics.retain(|dynamic| dynamic.tag() != tag);
+        Ok(len - dynamics.len())
+    }
+
+    /// Move the first entry with the given tag to the given index in the dynamic table.
+    ///
+    /// The index is the position of the entry after it has been moved, and must be
+    /// less than the number of entries.
+    ///
+    /// Returns an error if there is no dynamic section, or no entry with the given tag.
+    pub fn move_dynamic(&mut self, tag: u32, index: usize) -> Result<()> {
+        let dynamics = self.dynamic_data_mut_or_err()?;
+        if index >= dynamics.len() {
+            return Err(Error(format!("Invalid dynamic entry index {}", index)));
+        }
+        let from = dynamics
+            .iter()
+            .position(|dynamic| dynamic.tag() == tag)
+            .ok_or_else(|| Error(format!("Missing dynamic entry for tag 0x{:x}", tag)))?;
+        let entry = dynamics.remove(from);
+        dynamics.insert(index, entry);
+        Ok(())
+    }
+
+    /// Set and clear bits in the value of an integer entry in the dynamic table.
+    ///
+    /// This is intended for flag entries such as `DT_FLAGS` and `DT_FLAGS_1`.
+    /// The bits in `set` are set and then the bits in `clear` are cleared for the first
+    /// entry with the given tag. If there is no entry with the given tag and `set` is
+    /// not zero, then an entry is appended to the table.
+    ///
+    /// Returns an error if there is no dynamic section, or if the existing entry
+    /// is not an integer.
+    pub fn update_dynamic_flags(&mut self, tag: u32, set: u64, clear: u64) -> Result<()> {
+        let dynamics = self.dynamic_data_mut_or_err()?;
+        match dynamics.iter_mut().find(|dynamic| dynamic.tag() == tag) {
+            Some(Dynamic::Integer { val, .. }) => {
+                *val = (*val | set) & !clear;
+            }
+            Some(_) => {
+                return Err(Error(format!(
+                    "Dynamic entry for tag 0x{:x} is not an integer",
+                    tag
+                )));
+            }
+            None => {
+                if set != 0 {
+                    dynamics.push(Dynamic::Integer {
+                        tag,
+                        val: set & !clear,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the section containing the GNU build ID note.
+    pub fn build_id_section(&self) -> Option<SectionId> {
+        self.sections
+            .iter()
+            .find_map(|section| match &section.data {
+                SectionData::Note(data)
+                    if find_build_id(data, self.endian, note_align(section.sh_addralign))
+                        .is_some() =>
+                {
+                    Some(section.id)
+                }
+                _ => None,
+            })
+    }
+
+    /// Return the GNU build ID.
+    ///
+    /// This is the descriptor of the first [`elf::NT_GNU_BUILD_ID`] note.
+    pub fn build_id(&self) -> Option<&[u8]> {
+        let section = self.sections.get(self.build_id_section()?);
+        match &section.data {
+            SectionData::Note(data) => {
+                let (_, offset, size) =
+                    find_build_id(data, self.endian, note_align(section.sh_addralign))?;
+                Some(&data[offset..][..size])
+            }
+            _ => None,
+        }
+    }
+
+    /// Set the GNU build ID.
+    ///
+    /// This replaces the descriptor of the first [`elf::NT_GNU_BUILD_ID`] note.
+    /// The `sh_size` of the note section, and the ranges of any segments other than
+    /// `PT_LOAD` that contain it, are updated if the size of the build ID changes.
+    /// If the note section is now larger than the space available at its current
+    /// address, then [`Self::place_sections`] must be called before writing.
+    ///
+    /// To calculate the build ID from the contents of the file, set a build ID of
+    /// zeros with the desired size, and then call [`update_build_id`] after writing.
+    ///
+    /// Returns an error if there is no build ID note.
+    pub fn set_build_id(&mut self, build_id: &[u8]) -> Result<()> {
+        let endian = self.endian;
+        let id = self
+            .build_id_section()
+            .ok_or_else(|| Error::new("No GNU build ID note found"))?;
+        let section = self.sections.get_mut(id);
+        let align = note_align(section.sh_addralign);
+        let SectionData::Note(data) = &mut section.data else {
+            unreachable!();
+        };
+        let (note_offset, desc_offset, desc_size) = find_build_id(data, endian, align).unwrap();
+        if desc_size == build_id.len() {
+            data.to_mut()[desc_offset..][..desc_size].copy_from_slice(build_id);
+            return Ok(());
+        }
+
+        let desc_end = desc_offset + desc_size;
+        let old_end = cmp::min(
+            data.len(),
+            desc_end + (desc_end.wrapping_neg() & (align - 1)),
+        );
+        let mut desc = build_id.to_vec();
+        desc.resize(
+            build_id.len() + (build_id.len().wrapping_neg() & (align - 1)),
+            0,
+        );
+        let data = data.to_mut();
+        data.splice(desc_offset..old_end, desc);
+        let descsz = U32::new(endian, build_id.len() as u32);
+        data[note_offset + 4..][..4].copy_from_slice(pod::bytes_of(&descsz));
+        section.sh_size = data.len() as u64;
+
+        let sections = &self.sections;
+        for segment in &mut self.segments {
+            if segment.p_type != elf::PT_LOAD && segment.sections.contains(&id) {
+                segment.recalculate_ranges(sections);
+            }
+        }
+        Ok(())
+    }
+
     /// Find the section containing the interpreter path.
     ///
     /// This uses the `PT_INTERP` program header to find the interp section.
@@ -2152,6 +3051,17 @@ impl<'data> Builder<'data> {
     }
 }
 
+/// Data in a segment that is not part of any section or header.
+///
+/// This is used in [`Builder::padding`].
+#[derive(Debug)]
+pub struct Padding<'data> {
+    /// The file offset of the data.
+    pub offset: u64,
+    /// The data.
+    pub data: Bytes<'data>,
+}
+
 /// ELF file header.
 ///
 /// This corresponds to fields in [`elf::FileHeader32`] or [`elf::FileHeader64`].
@@ -2722,6 +3632,10 @@ pub struct Symbol<'data, const DYNAMIC: bool = false> {
     /// The `st_size` field in the ELF symbol.
     pub st_size: u64,
     /// GNU version for dynamic symbols.
+    ///
+    /// This is [`VersionId::local`] for new symbols. If the file has a `.gnu.version`
+    /// section, then new symbols that are not local should usually be set to
+    /// [`VersionId::global`] or a version in [`Builder::versions`].
     pub version: VersionId,
     /// Set the [`elf::VERSYM_HIDDEN`] flag for this symbol.
     pub version_hidden: bool,
@@ -2758,6 +3672,39 @@ impl<'data, const DYNAMIC: bool> Symbol<'data, DYNAMIC> {
     pub fn set_st_info(&mut self, st_bind: u8, st_type: u8) {
         self.st_info = (st_bind << 4) + (st_type & 0xf);
     }
+
+    /// Set the `st_bind` component of the `st_info` field.
+    #[inline]
+    pub fn set_st_bind(&mut self, st_bind: u8) {
+        self.set_st_info(st_bind, self.st_type());
+    }
+
+    /// Set the `st_type` component of the `st_info` field.
+    #[inline]
+    pub fn set_st_type(&mut self, st_type: u8) {
+        self.set_st_info(self.st_bind(), st_type);
+    }
+
+    /// Get the `st_visibility` component of the `st_other` field.
+    #[inline]
+    pub fn st_visibility(&self) -> u8 {
+        self.st_other & 0x3
+    }
+
+    /// Set the `st_visibility` component of the `st_other` field.
+    #[inline]
+    pub fn set_st_visibility(&mut self, st_visibility: u8) {
+        self.st_other = (self.st_other & !0x3) | (st_visibility & 0x3);
+    }
+
+    /// Return true if the symbol is undefined.
+    ///
+    /// A symbol is defined if it references a section, or if `st_shndx` is not
+    /// [`elf::SHN_UNDEF`].
+    #[inline]
+    pub fn is_undefined(&self) -> bool {
+        self.section.is_none() && self.st_shndx == elf::SHN_UNDEF
+    }
 }
 
 /// A symbol table.
@@ -2767,7 +3714,7 @@ impl<'data, const DYNAMIC: bool> Symbols<'data, DYNAMIC> {
     /// Number of defined symbols.
     pub fn count_defined(&self) -> usize {
         self.into_iter()
-            .filter(|symbol| symbol.st_shndx != elf::SHN_UNDEF)
+            .filter(|symbol| !symbol.is_undefined())
             .count()
     }
 
@@ -3128,3 +4075,122 @@ impl AttributeTag {
         }
     }
 }
+
+/// The hash function used by [`update_build_id`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildIdHash {
+    /// A 16 byte MD5 hash.
+    Md5,
+    /// A 20 byte SHA-1 hash.
+    Sha1,
+    /// An 8 byte 64-bit xxHash (XXH64), stored in little endian byte order.
+    XxHash64,
+}
+
+impl BuildIdHash {
+    /// The size of the hash in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            BuildIdHash::Md5 => 16,
+            BuildIdHash::Sha1 => 20,
+            BuildIdHash::XxHash64 => 8,
+        }
+    }
+
+    /// Calculate the hash of the given data.
+    pub fn hash(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            BuildIdHash::Md5 => hash::md5(data).to_vec(),
+            BuildIdHash::Sha1 => hash::sha1(data).to_vec(),
+            BuildIdHash::XxHash64 => hash::xxh64(data).to_le_bytes().to_vec(),
+        }
+    }
+}
+
+/// Calculate the GNU build ID for an ELF file, and store it in the file.
+///
+/// The build ID is the hash of the file contents with the build ID set to zero.
+/// The file must already contain a [`elf::NT_GNU_BUILD_ID`] note in a section,
+/// and the size of its descriptor must match the size of the hash. This is typically
+/// used after calling [`Builder::set_build_id`] and [`Builder::write`].
+///
+/// Returns the build ID.
+pub fn update_build_id(data: &mut [u8], hash: BuildIdHash) -> Result<Vec<u8>> {
+    let (offset, size) = match FileKind::parse(&*data)? {
+        FileKind::Elf32 => find_file_build_id::<elf::FileHeader32<Endianness>>(data)?,
+        FileKind::Elf64 => find_file_build_id::<elf::FileHeader64<Endianness>>(data)?,
+        #[allow(unreachable_patterns)]
+        _ => return Err(Error::new("Not an ELF file")),
+    };
+    if size != hash.size() {
+        return Err(Error(format!(
+            "GNU build ID size {} does not match hash size {}",
+            size,
+            hash.size()
+        )));
+    }
+    let build_id = &mut data[offset..][..size];
+    build_id.fill(0);
+    let build_id = hash.hash(data);
+    data[offset..][..size].copy_from_slice(&build_id);
+    Ok(build_id)
+}
+
+/// Return the file offset and size of the GNU build ID descriptor.
+fn find_file_build_id<Elf: FileHeader<Endian = Endianness>>(data: &[u8]) -> Result<(usize, usize)> {
+    let header = Elf::parse(data)?;
+    let endian = header.endian()?;
+    let sections = header.sections(endian, data)?;
+    for section in sections.iter() {
+        if section.sh_type(endian) != elf::SHT_NOTE {
+            continue;
+        }
+        let section_data = section.data(endian, data)?;
+        let align = note_align(section.sh_addralign(endian).into());
+        if let Some((_, offset, size)) = find_build_id(section_data, endian, align) {
+            let (section_offset, _) = section.file_range(endian).unwrap();
+            return Ok((section_offset as usize + offset, size));
+        }
+    }
+    Err(Error::new("No GNU build ID note found"))
+}
+
+fn note_align(sh_addralign: u64) -> usize {
+    if sh_addralign == 8 {
+        8
+    } else {
+        4
+    }
+}
+
+/// Find the first GNU build ID note in the data for a note section.
+///
+/// Returns the offsets of the note and its descriptor, and the size of the descriptor.
+fn find_build_id(data: &[u8], endian: Endianness, align: usize) -> Option<(usize, usize, usize)> {
+    let read_u32 = |offset: usize| -> Option<usize> {
+        let bytes = data.get(offset..)?.get(..4)?;
+        Some(endian.read_u32_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    };
+    let align_up = |offset: usize| offset.checked_add(align - 1).map(|x| x & !(align - 1));
+    let mut offset = 0;
+    while offset + 12 <= data.len() {
+        let namesz = read_u32(offset)?;
+        let descsz = read_u32(offset + 4)?;
+        let n_type = read_u32(offset + 8)?;
+        let name_offset = offset + 12;
+        let desc_offset = align_up(name_offset.checked_add(namesz)?)?;
+        let desc_end = desc_offset.checked_add(descsz)?;
+        if desc_end > data.len() {
+            return None;
+        }
+        let name = &data[name_offset..][..namesz];
+        if n_type == elf::NT_GNU_BUILD_ID as usize
+            && name.strip_suffix(&[0]).unwrap_or(name) == elf::ELF_NOTE_GNU
+        {
+            return Some((offset, desc_offset, descsz));
+        }
+        offset = align_up(desc_end)?;
+    }
+    None
+}
@@ -10,6 +10,9 @@ pub use error::{Error, Result};
 mod bytes;
 pub use bytes::{ByteString, Bytes};
 
+#[cfg(feature = "elf")]
+mod hash;
+
 mod table;
 use table::IdPrivate;
 pub use table::{Id, Item, Table};
@@ -470,6 +470,31 @@ pub enum FileFlags {
     },
 }
 
+/// Memory access permissions for a segment or section.
+///
+/// Returned by [`ObjectSegment::permissions`](crate::read::ObjectSegment::permissions)
+/// and [`ObjectSection::permissions`](crate::read::ObjectSection::permissions).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Permissions {
+    /// The memory is readable.
+    pub read: bool,
+    /// The memory is writable.
+    pub write: bool,
+    /// The memory is executable.
+    pub execute: bool,
+}
+
+impl Permissions {
+    /// Create permissions with the given read, write and execute bits.
+    pub const fn new(read: bool, write: bool, execute: bool) -> Self {
+        Permissions {
+            read,
+            write,
+            execute,
+        }
+    }
+}
+
 /// Segment flags that are specific to each file format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -478,6 +503,8 @@ pub enum SegmentFlags {
     None,
     /// ELF segment flags.
     Elf {
+        /// `p_type` field in the segment header.
+        p_type: u32,
         /// `p_flags` field in the segment header.
         p_flags: u32,
     },
@@ -550,6 +577,17 @@ pub enum SymbolFlags<Section, Symbol> {
         /// `Number` field in the auxiliary symbol for the section.
         associative_section: Option<Section>,
     },
+    /// COFF flags for a weak external symbol.
+    ///
+    /// The symbol must be undefined.
+    CoffWeakExternal {
+        /// The symbol to use if the weak external is not otherwise defined.
+        default_symbol: Symbol,
+        /// `Characteristics` field in the auxiliary symbol.
+        ///
+        /// One of the `IMAGE_WEAK_EXTERN_*` constants.
+        search_type: u32,
+    },
     /// XCOFF symbol flags.
     Xcoff {
         /// `n_sclass` field in the XCOFF symbol.
@@ -585,6 +623,9 @@ pub enum RelocationFlags {
     /// ELF relocation fields.
     Elf {
         /// `r_type` field in the ELF relocation.
+        ///
+        /// For MIPS64, this also contains the `r_type2`, `r_type3` and `r_ssym`
+        /// fields in bits 8 to 31.
         r_type: u32,
     },
     /// Mach-O relocation fields.
@@ -716,6 +716,8 @@ pub const SHT_RELR: u32 = 19;
 pub const SHT_LOOS: u32 = 0x6000_0000;
 /// LLVM-style dependent libraries.
 pub const SHT_LLVM_DEPENDENT_LIBRARIES: u32 = 0x6fff4c04;
+/// SFrame stack trace information.
+pub const SHT_GNU_SFRAME: u32 = 0x6fff_fff4;
 /// Object attributes.
 pub const SHT_GNU_ATTRIBUTES: u32 = 0x6fff_fff5;
 /// GNU-style hash table.
@@ -753,6 +755,203 @@ pub const SHT_LOUSER: u32 = 0x8000_0000;
 /// End of application-specific section types.
 pub const SHT_HIUSER: u32 = 0x8fff_ffff;
 
+// Solaris values for `SectionHeader*::sh_type`.
+//
+// Some of these overlap with the GNU values, so they are only valid
+// when `Ident::os_abi` is `ELFOSABI_SOLARIS`.
+/// Capabilities chain.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_capchain: u32 = 0x6fff_ffef;
+/// Symbol capabilities information.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_capinfo: u32 = 0x6fff_fff0;
+/// Sorted indices of symbols.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_symsort: u32 = 0x6fff_fff1;
+/// Sorted indices of thread-local symbols.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_tlssort: u32 = 0x6fff_fff2;
+/// Local dynamic symbol table.
+pub const SHT_SUNW_LDYNSYM: u32 = 0x6fff_fff3;
+/// DTrace object format.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_dof: u32 = 0x6fff_fff4;
+/// Hardware and software capabilities.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_cap: u32 = 0x6fff_fff5;
+/// Signature.
+pub const SHT_SUNW_SIGNATURE: u32 = 0x6fff_fff6;
+/// Annotation.
+pub const SHT_SUNW_ANNOTATE: u32 = 0x6fff_fff7;
+/// Debug string table.
+pub const SHT_SUNW_DEBUGSTR: u32 = 0x6fff_fff8;
+/// Debug information.
+pub const SHT_SUNW_DEBUG: u32 = 0x6fff_fff9;
+/// Version definition section.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_verdef: u32 = 0x6fff_fffd;
+/// Version needs section.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_verneed: u32 = 0x6fff_fffe;
+/// Version symbol table.
+#[allow(non_upper_case_globals)]
+pub const SHT_SUNW_versym: u32 = 0x6fff_ffff;
+
+macro_rules! constant_name {
+    ($value:expr, [$($name:ident),* $(,)?]) => {
+        match $value {
+            $($name => Some(stringify!($name)),)*
+            _ => None,
+        }
+    };
+}
+
+/// Return the name of the constant for a `SectionHeader*::sh_type` value.
+///
+/// `machine` is the value of `FileHeader*::e_machine` and is used for values
+/// in the processor-specific range.
+/// `os_abi` is the value of `Ident::os_abi` and is used for values
+/// in the OS-specific range.
+///
+/// Returns `None` if the value is unknown.
+#[allow(non_upper_case_globals)]
+pub fn name_for_sh_type(machine: u16, os_abi: u8, sh_type: u32) -> Option<&'static str> {
+    match sh_type {
+        SHT_LOOS..=SHT_HIOS => match os_abi {
+            ELFOSABI_SOLARIS => constant_name!(
+                sh_type,
+                [
+                    SHT_SUNW_capchain,
+                    SHT_SUNW_capinfo,
+                    SHT_SUNW_symsort,
+                    SHT_SUNW_tlssort,
+                    SHT_SUNW_LDYNSYM,
+                    SHT_SUNW_dof,
+                    SHT_SUNW_cap,
+                    SHT_SUNW_SIGNATURE,
+                    SHT_SUNW_ANNOTATE,
+                    SHT_SUNW_DEBUGSTR,
+                    SHT_SUNW_DEBUG,
+                    SHT_SUNW_move,
+                    SHT_SUNW_COMDAT,
+                    SHT_SUNW_syminfo,
+                    SHT_SUNW_verdef,
+                    SHT_SUNW_verneed,
+                    SHT_SUNW_versym,
+                ]
+            ),
+            _ => constant_name!(
+                sh_type,
+                [
+                    SHT_LLVM_DEPENDENT_LIBRARIES,
+                    SHT_GNU_SFRAME,
+                    SHT_GNU_ATTRIBUTES,
+                    SHT_GNU_HASH,
+                    SHT_GNU_LIBLIST,
+                    SHT_CHECKSUM,
+                    SHT_SUNW_move,
+                    SHT_SUNW_COMDAT,
+                    SHT_SUNW_syminfo,
+                    SHT_GNU_VERDEF,
+                    SHT_GNU_VERNEED,
+                    SHT_GNU_VERSYM,
+                ]
+            ),
+        },
+        SHT_LOPROC..=SHT_HIPROC => match machine {
+            EM_MIPS => constant_name!(
+                sh_type,
+                [
+                    SHT_MIPS_LIBLIST,
+                    SHT_MIPS_MSYM,
+                    SHT_MIPS_CONFLICT,
+                    SHT_MIPS_GPTAB,
+                    SHT_MIPS_UCODE,
+                    SHT_MIPS_DEBUG,
+                    SHT_MIPS_REGINFO,
+                    SHT_MIPS_PACKAGE,
+                    SHT_MIPS_PACKSYM,
+                    SHT_MIPS_RELD,
+                    SHT_MIPS_IFACE,
+                    SHT_MIPS_CONTENT,
+                    SHT_MIPS_OPTIONS,
+                    SHT_MIPS_SHDR,
+                    SHT_MIPS_FDESC,
+                    SHT_MIPS_EXTSYM,
+                    SHT_MIPS_DENSE,
+                    SHT_MIPS_PDESC,
+                    SHT_MIPS_LOCSYM,
+                    SHT_MIPS_AUXSYM,
+                    SHT_MIPS_OPTSYM,
+                    SHT_MIPS_LOCSTR,
+                    SHT_MIPS_LINE,
+                    SHT_MIPS_RFDESC,
+                    SHT_MIPS_DELTASYM,
+                    SHT_MIPS_DELTAINST,
+                    SHT_MIPS_DELTACLASS,
+                    SHT_MIPS_DWARF,
+                    SHT_MIPS_DELTADECL,
+                    SHT_MIPS_SYMBOL_LIB,
+                    SHT_MIPS_EVENTS,
+                    SHT_MIPS_TRANSLATE,
+                    SHT_MIPS_PIXIE,
+                    SHT_MIPS_XLATE,
+                    SHT_MIPS_XLATE_DEBUG,
+                    SHT_MIPS_WHIRL,
+                    SHT_MIPS_EH_REGION,
+                    SHT_MIPS_XLATE_OLD,
+                    SHT_MIPS_PDR_EXCEPTION,
+                    SHT_MIPS_ABIFLAGS,
+                ]
+            ),
+            EM_PARISC => {
+                constant_name!(sh_type, [SHT_PARISC_EXT, SHT_PARISC_UNWIND, SHT_PARISC_DOC])
+            }
+            EM_ALPHA => constant_name!(sh_type, [SHT_ALPHA_DEBUG, SHT_ALPHA_REGINFO]),
+            EM_ARM => constant_name!(
+                sh_type,
+                [
+                    SHT_ARM_EXIDX,
+                    SHT_ARM_PREEMPTMAP,
+                    SHT_ARM_ATTRIBUTES,
+                    SHT_ARM_DEBUGOVERLAY,
+                    SHT_ARM_OVERLAYSECTION,
+                ]
+            ),
+            EM_AARCH64 => constant_name!(sh_type, [SHT_AARCH64_ATTRIBUTES]),
+            EM_CSKY => constant_name!(sh_type, [SHT_CSKY_ATTRIBUTES]),
+            EM_IA_64 => constant_name!(sh_type, [SHT_IA_64_EXT, SHT_IA_64_UNWIND]),
+            EM_X86_64 => constant_name!(sh_type, [SHT_X86_64_UNWIND]),
+            EM_RISCV => constant_name!(sh_type, [SHT_RISCV_ATTRIBUTES]),
+            EM_SHARC => constant_name!(sh_type, [SHT_SHARC_ADI_ATTRIBUTES]),
+            _ => None,
+        },
+        _ => constant_name!(
+            sh_type,
+            [
+                SHT_NULL,
+                SHT_PROGBITS,
+                SHT_SYMTAB,
+                SHT_STRTAB,
+                SHT_RELA,
+                SHT_HASH,
+                SHT_DYNAMIC,
+                SHT_NOTE,
+                SHT_NOBITS,
+                SHT_REL,
+                SHT_SHLIB,
+                SHT_DYNSYM,
+                SHT_INIT_ARRAY,
+                SHT_FINI_ARRAY,
+                SHT_PREINIT_ARRAY,
+                SHT_GROUP,
+                SHT_SYMTAB_SHNDX,
+                SHT_RELR,
+            ]
+        ),
+    }
+}
+
 // Values for `SectionHeader*::sh_flags`.
 /// Section is writable.
 pub const SHF_WRITE: u32 = 1 << 0;
@@ -1029,6 +1228,44 @@ pub const STT_LOPROC: u8 = 13;
 /// End of processor-specific symbol types.
 pub const STT_HIPROC: u8 = 15;
 
+/// Return the name of the constant for a `Sym*::st_type` value.
+///
+/// `machine` is the value of `FileHeader*::e_machine` and is used for values
+/// in the processor-specific range.
+/// `os_abi` is the value of `Ident::os_abi` and is used for values
+/// in the OS-specific range.
+///
+/// Returns `None` if the value is unknown.
+pub fn name_for_st_type(machine: u16, os_abi: u8, st_type: u8) -> Option<&'static str> {
+    match st_type {
+        STT_LOOS..=STT_HIOS => match os_abi {
+            ELFOSABI_GNU | ELFOSABI_FREEBSD => constant_name!(st_type, [STT_GNU_IFUNC]),
+            ELFOSABI_HPUX => constant_name!(st_type, [STT_HP_OPAQUE, STT_HP_STUB]),
+            _ => None,
+        },
+        STT_LOPROC..=STT_HIPROC => match machine {
+            EM_SPARC | EM_SPARC32PLUS | EM_SPARCV9 => {
+                constant_name!(st_type, [STT_SPARC_REGISTER])
+            }
+            EM_PARISC => constant_name!(st_type, [STT_PARISC_MILLICODE]),
+            EM_ARM => constant_name!(st_type, [STT_ARM_TFUNC, STT_ARM_16BIT]),
+            _ => None,
+        },
+        _ => constant_name!(
+            st_type,
+            [
+                STT_NOTYPE,
+                STT_OBJECT,
+                STT_FUNC,
+                STT_SECTION,
+                STT_FILE,
+                STT_COMMON,
+                STT_TLS,
+            ]
+        ),
+    }
+}
+
 // Values for visibility component of `Symbol*::st_other`.
 /// Default symbol visibility rules.
 pub const STV_DEFAULT: u8 = 0;
@@ -1313,6 +1550,64 @@ pub const PT_LOPROC: u32 = 0x7000_0000;
 /// End of processor-specific segment types.
 pub const PT_HIPROC: u32 = 0x7fff_ffff;
 
+/// Return the name of the constant for a `ProgramHeader*::p_type` value.
+///
+/// `machine` is the value of `FileHeader*::e_machine` and is used for values
+/// in the processor-specific range.
+/// `os_abi` is the value of `Ident::os_abi` and is used for values
+/// in the OS-specific range.
+///
+/// Returns `None` if the value is unknown.
+pub fn name_for_p_type(machine: u16, os_abi: u8, p_type: u32) -> Option<&'static str> {
+    match p_type {
+        PT_LOOS..=PT_HIOS => match os_abi {
+            ELFOSABI_HPUX => constant_name!(
+                p_type,
+                [
+                    PT_HP_TLS,
+                    PT_HP_CORE_NONE,
+                    PT_HP_CORE_VERSION,
+                    PT_HP_CORE_KERNEL,
+                    PT_HP_CORE_COMM,
+                    PT_HP_CORE_PROC,
+                    PT_HP_CORE_LOADABLE,
+                    PT_HP_CORE_STACK,
+                    PT_HP_CORE_SHM,
+                    PT_HP_CORE_MMF,
+                    PT_HP_PARALLEL,
+                    PT_HP_FASTBIND,
+                    PT_HP_OPT_ANNOT,
+                    PT_HP_HSL_ANNOT,
+                    PT_HP_STACK,
+                ]
+            ),
+            _ => constant_name!(
+                p_type,
+                [PT_GNU_EH_FRAME, PT_GNU_STACK, PT_GNU_RELRO, PT_GNU_PROPERTY,]
+            ),
+        },
+        PT_LOPROC..=PT_HIPROC => match machine {
+            EM_MIPS => constant_name!(
+                p_type,
+                [
+                    PT_MIPS_REGINFO,
+                    PT_MIPS_RTPROC,
+                    PT_MIPS_OPTIONS,
+                    PT_MIPS_ABIFLAGS,
+                ]
+            ),
+            EM_PARISC => constant_name!(p_type, [PT_PARISC_ARCHEXT, PT_PARISC_UNWIND]),
+            EM_ARM => constant_name!(p_type, [PT_ARM_EXIDX]),
+            EM_IA_64 => constant_name!(p_type, [PT_IA_64_ARCHEXT, PT_IA_64_UNWIND]),
+            _ => None,
+        },
+        _ => constant_name!(
+            p_type,
+            [PT_NULL, PT_LOAD, PT_DYNAMIC, PT_INTERP, PT_NOTE, PT_SHLIB, PT_PHDR, PT_TLS,]
+        ),
+    }
+}
+
 // Values for `ProgramHeader*::p_flags`.
 /// Segment is executable.
 pub const PF_X: u32 = 1 << 0;
@@ -1561,6 +1856,12 @@ pub const DT_PREINIT_ARRAY: u32 = 32;
 pub const DT_PREINIT_ARRAYSZ: u32 = 33;
 /// Address of SYMTAB_SHNDX section
 pub const DT_SYMTAB_SHNDX: u32 = 34;
+/// Total size of RELR relative relocations
+pub const DT_RELRSZ: u32 = 35;
+/// Address of RELR relative relocations
+pub const DT_RELR: u32 = 36;
+/// Size of one RELR relative relocation
+pub const DT_RELRENT: u32 = 37;
 /// Start of OS-specific
 pub const DT_LOOS: u32 = 0x6000_000d;
 /// End of OS-specific
@@ -2639,6 +2940,8 @@ pub const SHT_MIPS_WHIRL: u32 = 0x7000_0026;
 pub const SHT_MIPS_EH_REGION: u32 = 0x7000_0027;
 pub const SHT_MIPS_XLATE_OLD: u32 = 0x7000_0028;
 pub const SHT_MIPS_PDR_EXCEPTION: u32 = 0x7000_0029;
+/// ABI related flags.
+pub const SHT_MIPS_ABIFLAGS: u32 = 0x7000_002a;
 
 // MIPS values for `SectionHeader32::sh_flags`.
 
@@ -2663,9 +2966,57 @@ pub const STB_MIPS_SPLIT_COMMON: u8 = 13;
 
 // Entries found in sections of type `SHT_MIPS_GPTAB`.
 
-// TODO: Elf32_gptab, Elf32_RegInfo, Elf_Options
+// TODO: Elf32_gptab
+
+/// Register usage information for 32-bit MIPS.
+///
+/// This is the contents of a section of type `SHT_MIPS_REGINFO`,
+/// or the descriptor of an `ODK_REGINFO` option in 32-bit files.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MipsRegInfo32<E: Endian> {
+    /// Mask of general purpose registers used.
+    pub ri_gprmask: U32<E>,
+    /// Masks of coprocessor registers used.
+    pub ri_cprmask: [U32<E>; 4],
+    /// The GP register value for this object file.
+    pub ri_gp_value: I32<E>,
+}
+
+/// Register usage information for 64-bit MIPS.
+///
+/// This is the descriptor of an `ODK_REGINFO` option in 64-bit files.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MipsRegInfo64<E: Endian> {
+    /// Mask of general purpose registers used.
+    pub ri_gprmask: U32<E>,
+    /// Padding.
+    pub ri_pad: U32<E>,
+    /// Masks of coprocessor registers used.
+    pub ri_cprmask: [U32<E>; 4],
+    /// The GP register value for this object file.
+    pub ri_gp_value: I64<E>,
+}
+
+/// Header of an entry in a section of type `SHT_MIPS_OPTIONS`.
+///
+/// This header is followed by a descriptor. The format of the descriptor
+/// depends on `kind`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MipsOptionHeader<E: Endian> {
+    /// Determines interpretation of the descriptor. One of the `ODK_*` constants.
+    pub kind: u8,
+    /// Size of the entry, including this header.
+    pub size: u8,
+    /// Index of the section affected, or 0 for global options.
+    pub section: U16<E>,
+    /// Kind-specific information.
+    pub info: U32<E>,
+}
 
-// Values for `Elf_Options::kind`.
+// Values for `MipsOptionHeader::kind`.
 
 /// Undefined.
 pub const ODK_NULL: u32 = 0;
@@ -2686,7 +3037,7 @@ pub const ODK_HWAND: u32 = 7;
 /// HW workarounds.  'OR' bits when merging.
 pub const ODK_HWOR: u32 = 8;
 
-// Values for `Elf_Options::info` for `ODK_EXCEPTIONS` entries.
+// Values for `MipsOptionHeader::info` for `ODK_EXCEPTIONS` entries.
 
 /// FPE's which MUST be enabled.
 pub const OEX_FPU_MIN: u32 = 0x1f;
@@ -2708,7 +3059,7 @@ pub const OEX_FPU_OFLO: u32 = 0x04;
 pub const OEX_FPU_UFLO: u32 = 0x02;
 pub const OEX_FPU_INEX: u32 = 0x01;
 
-// Masks for `Elf_Options::info` for an `ODK_HWPATCH` entry.  */
+// Masks for `MipsOptionHeader::info` for an `ODK_HWPATCH` entry.
 /// R4000 end-of-page patch.
 pub const OHW_R4KEOP: u32 = 0x1;
 /// may need R8000 prefetch patch.
@@ -2722,15 +3073,190 @@ pub const OPAD_PREFIX: u32 = 0x1;
 pub const OPAD_POSTFIX: u32 = 0x2;
 pub const OPAD_SYMBOL: u32 = 0x4;
 
-// Entries found in sections of type `SHT_MIPS_OPTIONS`.
-
-// TODO: Elf_Options_Hw
+/// Descriptor of `ODK_HWAND` and `ODK_HWOR` options.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MipsOptionHw<E: Endian> {
+    /// Extra flags.
+    pub hwp_flags1: U32<E>,
+    /// Extra flags.
+    pub hwp_flags2: U32<E>,
+}
 
-// Masks for `ElfOptions::info` for `ODK_HWAND` and `ODK_HWOR` entries.
+// Masks for `MipsOptionHw::hwp_flags1` for `ODK_HWAND` and `ODK_HWOR` entries.
 
 pub const OHWA0_R4KEOP_CHECKED: u32 = 0x0000_0001;
 pub const OHWA1_R4KEOP_CLEAN: u32 = 0x0000_0002;
 
+/// The contents of a section of type `SHT_MIPS_ABIFLAGS`.
+///
+/// This is also referenced by a segment of type `PT_MIPS_ABIFLAGS`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MipsAbiFlags<E: Endian> {
+    /// Version of this structure. Currently 0.
+    pub version: U16<E>,
+    /// The ISA level, such as 1 for MIPS I or 64 for MIPS64.
+    pub isa_level: u8,
+    /// The ISA revision, such as 2 for MIPS32 Release 2.
+    pub isa_rev: u8,
+    /// The size of general purpose registers. One of the `MIPS_AFL_REG_*` constants.
+    pub gpr_size: u8,
+    /// The size of coprocessor 1 registers. One of the `MIPS_AFL_REG_*` constants.
+    pub cpr1_size: u8,
+    /// The size of coprocessor 2 registers. One of the `MIPS_AFL_REG_*` constants.
+    pub cpr2_size: u8,
+    /// The floating point ABI. One of the `MIPS_ABI_FP_*` constants.
+    pub fp_abi: u8,
+    /// The processor-specific extension. One of the `MIPS_AFL_EXT_*` constants.
+    pub isa_ext: U32<E>,
+    /// The application-specific extensions. A combination of the `MIPS_AFL_ASE_*` flags.
+    pub ases: U32<E>,
+    /// A combination of the `MIPS_AFL_FLAGS1_*` flags.
+    pub flags1: U32<E>,
+    /// Reserved.
+    pub flags2: U32<E>,
+}
+
+// Values for `MipsAbiFlags::gpr_size`, `MipsAbiFlags::cpr1_size` and `MipsAbiFlags::cpr2_size`.
+/// No registers.
+pub const MIPS_AFL_REG_NONE: u8 = 0;
+/// 32-bit registers.
+pub const MIPS_AFL_REG_32: u8 = 1;
+/// 64-bit registers.
+pub const MIPS_AFL_REG_64: u8 = 2;
+/// 128-bit registers.
+pub const MIPS_AFL_REG_128: u8 = 3;
+
+// Values for `MipsAbiFlags::fp_abi`.
+/// Not tagged or not using any ABIs affected by the differences.
+pub const MIPS_ABI_FP_ANY: u8 = 0;
+/// Using hard-float -mdouble-float.
+pub const MIPS_ABI_FP_DOUBLE: u8 = 1;
+/// Using hard-float -msingle-float.
+pub const MIPS_ABI_FP_SINGLE: u8 = 2;
+/// Using soft-float.
+pub const MIPS_ABI_FP_SOFT: u8 = 3;
+/// Using -mips32r2 -mfp64.
+pub const MIPS_ABI_FP_OLD_64: u8 = 4;
+/// Using -mfpxx.
+pub const MIPS_ABI_FP_XX: u8 = 5;
+/// Using -mips32r2 -mfp64.
+pub const MIPS_ABI_FP_64: u8 = 6;
+/// Using -mips32r2 -mfp64 -mno-odd-spreg.
+pub const MIPS_ABI_FP_64A: u8 = 7;
+
+// Values for `MipsAbiFlags::isa_ext`.
+pub const MIPS_AFL_EXT_XLR: u32 = 1;
+pub const MIPS_AFL_EXT_OCTEON2: u32 = 2;
+pub const MIPS_AFL_EXT_OCTEONP: u32 = 3;
+pub const MIPS_AFL_EXT_LOONGSON_3A: u32 = 4;
+pub const MIPS_AFL_EXT_OCTEON: u32 = 5;
+pub const MIPS_AFL_EXT_5900: u32 = 6;
+pub const MIPS_AFL_EXT_4650: u32 = 7;
+pub const MIPS_AFL_EXT_4010: u32 = 8;
+pub const MIPS_AFL_EXT_4100: u32 = 9;
+pub const MIPS_AFL_EXT_3900: u32 = 10;
+pub const MIPS_AFL_EXT_10000: u32 = 11;
+pub const MIPS_AFL_EXT_SB1: u32 = 12;
+pub const MIPS_AFL_EXT_4111: u32 = 13;
+pub const MIPS_AFL_EXT_4120: u32 = 14;
+pub const MIPS_AFL_EXT_5400: u32 = 15;
+pub const MIPS_AFL_EXT_5500: u32 = 16;
+pub const MIPS_AFL_EXT_LOONGSON_2E: u32 = 17;
+pub const MIPS_AFL_EXT_LOONGSON_2F: u32 = 18;
+pub const MIPS_AFL_EXT_OCTEON3: u32 = 19;
+
+// Values for `MipsAbiFlags::ases`.
+/// DSP ASE.
+pub const MIPS_AFL_ASE_DSP: u32 = 0x0000_0001;
+/// DSP R2 ASE.
+pub const MIPS_AFL_ASE_DSPR2: u32 = 0x0000_0002;
+/// Enhanced VA Scheme.
+pub const MIPS_AFL_ASE_EVA: u32 = 0x0000_0004;
+/// MCU (MicroController) ASE.
+pub const MIPS_AFL_ASE_MCU: u32 = 0x0000_0008;
+/// MDMX ASE.
+pub const MIPS_AFL_ASE_MDMX: u32 = 0x0000_0010;
+/// MIPS-3D ASE.
+pub const MIPS_AFL_ASE_MIPS3D: u32 = 0x0000_0020;
+/// MT ASE.
+pub const MIPS_AFL_ASE_MT: u32 = 0x0000_0040;
+/// SmartMIPS ASE.
+pub const MIPS_AFL_ASE_SMARTMIPS: u32 = 0x0000_0080;
+/// VZ ASE.
+pub const MIPS_AFL_ASE_VIRT: u32 = 0x0000_0100;
+/// MSA ASE.
+pub const MIPS_AFL_ASE_MSA: u32 = 0x0000_0200;
+/// MIPS16 ASE.
+pub const MIPS_AFL_ASE_MIPS16: u32 = 0x0000_0400;
+/// MICROMIPS ASE.
+pub const MIPS_AFL_ASE_MICROMIPS: u32 = 0x0000_0800;
+/// XPA ASE.
+pub const MIPS_AFL_ASE_XPA: u32 = 0x0000_1000;
+
+// Values for `MipsAbiFlags::flags1`.
+/// Uses odd single-precision registers.
+pub const MIPS_AFL_FLAGS1_ODDSPREG: u32 = 1;
+
+/// The components of the `r_type` field of a MIPS64 relocation.
+///
+/// MIPS64 splits the 32-bit `r_type` field of `Rel64` and `Rela64` into three
+/// relocation types that are applied in sequence, and a special symbol that is
+/// used by the third relocation type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mips64RelocationType {
+    /// The special symbol for the third relocation type. One of the `RSS_*` constants.
+    pub r_ssym: u8,
+    /// The third relocation type.
+    pub r_type3: u8,
+    /// The second relocation type.
+    pub r_type2: u8,
+    /// The first relocation type.
+    pub r_type: u8,
+}
+
+impl Mips64RelocationType {
+    /// Split a MIPS64 `r_type` value into its components.
+    pub fn from_r_type(r_type: u32) -> Self {
+        Mips64RelocationType {
+            r_ssym: (r_type >> 24) as u8,
+            r_type3: (r_type >> 16) as u8,
+            r_type2: (r_type >> 8) as u8,
+            r_type: r_type as u8,
+        }
+    }
+
+    /// Combine the components into a MIPS64 `r_type` value.
+    pub fn to_r_type(self) -> u32 {
+        (u32::from(self.r_ssym) << 24)
+            | (u32::from(self.r_type3) << 16)
+            | (u32::from(self.r_type2) << 8)
+            | u32::from(self.r_type)
+    }
+
+    /// Return the relocation type if this is a single relocation.
+    ///
+    /// Returns `None` if the second or third relocation types are used.
+    pub fn single(self) -> Option<u32> {
+        if u32::from(self.r_type2) == R_MIPS_NONE && u32::from(self.r_type3) == R_MIPS_NONE {
+            Some(u32::from(self.r_type))
+        } else {
+            None
+        }
+    }
+}
+
+// Values for `Mips64RelocationType::r_ssym`.
+/// No special symbol.
+pub const RSS_UNDEF: u8 = 0;
+/// The value of GP.
+pub const RSS_GP: u8 = 1;
+/// The value of GP used to create the relocatable object.
+pub const RSS_GP0: u8 = 2;
+/// The address of the location being relocated.
+pub const RSS_LOC: u8 = 3;
+
 // MIPS values for `Rel*::r_type`.
 
 /// No reloc
@@ -3773,6 +4299,14 @@ pub const PPC64_OPT_LOCALENTRY: u32 = 4;
 pub const STO_PPC64_LOCAL_BIT: u8 = 5;
 pub const STO_PPC64_LOCAL_MASK: u8 = 7 << STO_PPC64_LOCAL_BIT;
 
+/// Decode the local entry point offset from `Sym64::st_other` for PowerPC64 ELFv2.
+///
+/// The local entry point of a function is the symbol value plus this offset.
+/// It skips the code in the function prologue that sets up the TOC pointer.
+pub const fn ppc64_local_entry_offset(st_other: u8) -> u64 {
+    ((1 << ((st_other & STO_PPC64_LOCAL_MASK) >> STO_PPC64_LOCAL_BIT)) >> 2) << 2
+}
+
 // ARM specific declarations.
 
 // ARM values for `FileHeader*::e_flags`.
@@ -3844,6 +4378,14 @@ pub const SHT_ARM_EXIDX: u32 = SHT_LOPROC + 1;
 pub const SHT_ARM_PREEMPTMAP: u32 = SHT_LOPROC + 2;
 /// ARM attributes section.
 pub const SHT_ARM_ATTRIBUTES: u32 = SHT_LOPROC + 3;
+/// Debug overlay information.
+pub const SHT_ARM_DEBUGOVERLAY: u32 = SHT_LOPROC + 4;
+/// Overlay section.
+pub const SHT_ARM_OVERLAYSECTION: u32 = SHT_LOPROC + 5;
+
+// ARM values for the second word of an `SHT_ARM_EXIDX` entry.
+/// The function cannot be unwound.
+pub const EXIDX_CANTUNWIND: u32 = 1;
 
 // AArch64 values for `SectionHeader*::sh_type`.
 /// AArch64 attributes section.
@@ -6487,4 +7029,9 @@ unsafe_impl_endian_pod!(
     NoteHeader64,
     HashHeader,
     GnuHashHeader,
+    MipsRegInfo32,
+    MipsRegInfo64,
+    MipsOptionHeader,
+    MipsOptionHw,
+    MipsAbiFlags,
 );
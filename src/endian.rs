@@ -152,6 +152,26 @@ pub trait Endian: Debug + Default + Clone + Copy + PartialEq + Eq + 'static {
         }
     }
 
+    /// Converts an unaligned unsigned 128 bit integer to native endian.
+    #[inline]
+    fn read_u128_bytes(self, n: [u8; 16]) -> u128 {
+        if self.is_big_endian() {
+            u128::from_be_bytes(n)
+        } else {
+            u128::from_le_bytes(n)
+        }
+    }
+
+    /// Converts an unaligned signed 128 bit integer to native endian.
+    #[inline]
+    fn read_i128_bytes(self, n: [u8; 16]) -> i128 {
+        if self.is_big_endian() {
+            i128::from_be_bytes(n)
+        } else {
+            i128::from_le_bytes(n)
+        }
+    }
+
     /// Converts an unsigned 16 bit integer from native endian.
     #[inline]
     fn write_u16(self, n: u16) -> u16 {
@@ -271,6 +291,26 @@ pub trait Endian: Debug + Default + Clone + Copy + PartialEq + Eq + 'static {
             i64::to_le_bytes(n)
         }
     }
+
+    /// Converts an unaligned unsigned 128 bit integer from native endian.
+    #[inline]
+    fn write_u128_bytes(self, n: u128) -> [u8; 16] {
+        if self.is_big_endian() {
+            u128::to_be_bytes(n)
+        } else {
+            u128::to_le_bytes(n)
+        }
+    }
+
+    /// Converts an unaligned signed 128 bit integer from native endian.
+    #[inline]
+    fn write_i128_bytes(self, n: i128) -> [u8; 16] {
+        if self.is_big_endian() {
+            i128::to_be_bytes(n)
+        } else {
+            i128::to_le_bytes(n)
+        }
+    }
 }
 
 /// An endianness that is selectable at run-time.
@@ -548,6 +588,60 @@ mod aligned {
         }
     }
 
+    /// An `f32` value with an externally specified endianness of type `E`.
+    ///
+    /// Comparisons and hashing use the bit representation of the value.
+    #[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct F32<E: Endian>(u32, PhantomData<E>);
+
+    impl<E: Endian> F32<E> {
+        /// Construct a new value given bytes that already have the required endianness.
+        pub const fn from_bytes(n: [u8; 4]) -> Self {
+            Self(u32::from_ne_bytes(n), PhantomData)
+        }
+
+        /// Construct a new value given a native endian value.
+        pub fn new(e: E, n: f32) -> Self {
+            Self(e.write_u32(n.to_bits()), PhantomData)
+        }
+        /// Return the value as a native endian value.
+        pub fn get(self, e: E) -> f32 {
+            f32::from_bits(e.read_u32(self.0))
+        }
+        /// Set the value given a native endian value.
+        pub fn set(&mut self, e: E, n: f32) {
+            self.0 = e.write_u32(n.to_bits());
+        }
+    }
+
+    /// An `f64` value with an externally specified endianness of type `E`.
+    ///
+    /// Comparisons and hashing use the bit representation of the value.
+    #[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct F64<E: Endian>(u64, PhantomData<E>);
+
+    impl<E: Endian> F64<E> {
+        /// Construct a new value given bytes that already have the required endianness.
+        pub const fn from_bytes(n: [u8; 8]) -> Self {
+            Self(u64::from_ne_bytes(n), PhantomData)
+        }
+
+        /// Construct a new value given a native endian value.
+        pub fn new(e: E, n: f64) -> Self {
+            Self(e.write_u64(n.to_bits()), PhantomData)
+        }
+        /// Return the value as a native endian value.
+        pub fn get(self, e: E) -> f64 {
+            f64::from_bits(e.read_u64(self.0))
+        }
+        /// Set the value given a native endian value.
+        pub fn set(&mut self, e: E, n: f64) {
+            self.0 = e.write_u64(n.to_bits());
+        }
+    }
+
     impl<E: Endian> fmt::Debug for U16<E> {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             write!(f, "U16({:x})", self.0)
@@ -584,7 +678,19 @@ mod aligned {
         }
     }
 
-    unsafe_impl_endian_pod!(U16, U32, U64, I16, I32, I64);
+    impl<E: Endian> fmt::Debug for F32<E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "F32({:x})", self.0)
+        }
+    }
+
+    impl<E: Endian> fmt::Debug for F64<E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "F64({:x})", self.0)
+        }
+    }
+
+    unsafe_impl_endian_pod!(U16, U32, U64, I16, I32, I64, F32, F64);
 }
 
 #[cfg(not(feature = "unaligned"))]
@@ -614,6 +720,24 @@ pub type I32<E> = I32Bytes<E>;
 #[cfg(feature = "unaligned")]
 pub type I64<E> = I64Bytes<E>;
 
+/// An `f32` value with an externally specified endianness of type `E`.
+#[cfg(feature = "unaligned")]
+pub type F32<E> = F32Bytes<E>;
+
+/// An `f64` value with an externally specified endianness of type `E`.
+#[cfg(feature = "unaligned")]
+pub type F64<E> = F64Bytes<E>;
+
+/// A `u128` value with an externally specified endianness of type `E`.
+///
+/// This is always unaligned, because the alignment of `u128` differs between targets.
+pub type U128<E> = U128Bytes<E>;
+
+/// An `i128` value with an externally specified endianness of type `E`.
+///
+/// This is always unaligned, because the alignment of `i128` differs between targets.
+pub type I128<E> = I128Bytes<E>;
+
 /// An unaligned `u16` value with an externally specified endianness of type `E`.
 #[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -776,6 +900,118 @@ impl<E: Endian> I64Bytes<E> {
     }
 }
 
+/// An unaligned `u128` value with an externally specified endianness of type `E`.
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct U128Bytes<E: Endian>([u8; 16], PhantomData<E>);
+
+impl<E: Endian> U128Bytes<E> {
+    /// Construct a new value given bytes that already have the required endianness.
+    pub const fn from_bytes(n: [u8; 16]) -> Self {
+        Self(n, PhantomData)
+    }
+
+    /// Construct a new value given a native endian value.
+    pub fn new(e: E, n: u128) -> Self {
+        Self(e.write_u128_bytes(n), PhantomData)
+    }
+
+    /// Return the value as a native endian value.
+    pub fn get(self, e: E) -> u128 {
+        e.read_u128_bytes(self.0)
+    }
+
+    /// Set the value given a native endian value.
+    pub fn set(&mut self, e: E, n: u128) {
+        self.0 = e.write_u128_bytes(n);
+    }
+}
+
+/// An unaligned `i128` value with an externally specified endianness of type `E`.
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct I128Bytes<E: Endian>([u8; 16], PhantomData<E>);
+
+impl<E: Endian> I128Bytes<E> {
+    /// Construct a new value given bytes that already have the required endianness.
+    pub const fn from_bytes(n: [u8; 16]) -> Self {
+        Self(n, PhantomData)
+    }
+
+    /// Construct a new value given a native endian value.
+    pub fn new(e: E, n: i128) -> Self {
+        Self(e.write_i128_bytes(n), PhantomData)
+    }
+
+    /// Return the value as a native endian value.
+    pub fn get(self, e: E) -> i128 {
+        e.read_i128_bytes(self.0)
+    }
+
+    /// Set the value given a native endian value.
+    pub fn set(&mut self, e: E, n: i128) {
+        self.0 = e.write_i128_bytes(n);
+    }
+}
+
+/// An unaligned `f32` value with an externally specified endianness of type `E`.
+///
+/// Comparisons and hashing use the bit representation of the value.
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct F32Bytes<E: Endian>([u8; 4], PhantomData<E>);
+
+impl<E: Endian> F32Bytes<E> {
+    /// Construct a new value given bytes that already have the required endianness.
+    pub const fn from_bytes(n: [u8; 4]) -> Self {
+        Self(n, PhantomData)
+    }
+
+    /// Construct a new value given a native endian value.
+    pub fn new(e: E, n: f32) -> Self {
+        Self(e.write_u32_bytes(n.to_bits()), PhantomData)
+    }
+
+    /// Return the value as a native endian value.
+    pub fn get(self, e: E) -> f32 {
+        f32::from_bits(e.read_u32_bytes(self.0))
+    }
+
+    /// Set the value given a native endian value.
+    pub fn set(&mut self, e: E, n: f32) {
+        self.0 = e.write_u32_bytes(n.to_bits());
+    }
+}
+
+/// An unaligned `f64` value with an externally specified endianness of type `E`.
+///
+/// Comparisons and hashing use the bit representation of the value.
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct F64Bytes<E: Endian>([u8; 8], PhantomData<E>);
+
+impl<E: Endian> F64Bytes<E> {
+    /// Construct a new value given bytes that already have the required endianness.
+    pub const fn from_bytes(n: [u8; 8]) -> Self {
+        Self(n, PhantomData)
+    }
+
+    /// Construct a new value given a native endian value.
+    pub fn new(e: E, n: f64) -> Self {
+        Self(e.write_u64_bytes(n.to_bits()), PhantomData)
+    }
+
+    /// Return the value as a native endian value.
+    pub fn get(self, e: E) -> f64 {
+        f64::from_bits(e.read_u64_bytes(self.0))
+    }
+
+    /// Set the value given a native endian value.
+    pub fn set(&mut self, e: E, n: f64) {
+        self.0 = e.write_u64_bytes(n.to_bits());
+    }
+}
+
 impl<E: Endian> fmt::Debug for U16Bytes<E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "U16({:x}, {:x})", self.0[0], self.0[1],)
@@ -828,4 +1064,88 @@ impl<E: Endian> fmt::Debug for I64Bytes<E> {
     }
 }
 
-unsafe_impl_endian_pod!(U16Bytes, U32Bytes, U64Bytes, I16Bytes, I32Bytes, I64Bytes);
+impl<E: Endian> fmt::Debug for U128Bytes<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_bytes(f, "U128", &self.0)
+    }
+}
+
+impl<E: Endian> fmt::Debug for I128Bytes<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_bytes(f, "I128", &self.0)
+    }
+}
+
+impl<E: Endian> fmt::Debug for F32Bytes<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_bytes(f, "F32", &self.0)
+    }
+}
+
+impl<E: Endian> fmt::Debug for F64Bytes<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_bytes(f, "F64", &self.0)
+    }
+}
+
+fn debug_bytes(f: &mut fmt::Formatter<'_>, name: &str, bytes: &[u8]) -> fmt::Result {
+    write!(f, "{}(", name)?;
+    for (i, byte) in bytes.iter().enumerate() {
+        if i != 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{:x}", byte)?;
+    }
+    write!(f, ")")
+}
+
+unsafe_impl_endian_pod!(
+    U16Bytes, U32Bytes, U64Bytes, U128Bytes, I16Bytes, I32Bytes, I64Bytes, I128Bytes, F32Bytes,
+    F64Bytes,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::bytes_of;
+
+    #[test]
+    fn wide_and_float() {
+        let n = 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10;
+        let value = U128::new(BigEndian, n);
+        assert_eq!(bytes_of(&value), &n.to_be_bytes());
+        assert_eq!(value.get(BigEndian), n);
+        let mut value = U128::new(LittleEndian, n);
+        assert_eq!(bytes_of(&value), &n.to_le_bytes());
+        value.set(LittleEndian, 1);
+        assert_eq!(value.get(LittleEndian), 1);
+
+        let value = I128::new(Endianness::Big, -2);
+        assert_eq!(value.get(Endianness::Big), -2);
+        assert_eq!(bytes_of(&value)[15], 0xfe);
+
+        let value = F32::new(BigEndian, 1.5);
+        assert_eq!(bytes_of(&value), &1.5f32.to_be_bytes());
+        assert_eq!(value.get(BigEndian), 1.5);
+        let value = F32Bytes::new(LittleEndian, -0.25);
+        assert_eq!(bytes_of(&value), &(-0.25f32).to_le_bytes());
+        assert_eq!(value.get(LittleEndian), -0.25);
+
+        let mut value = F64::new(BigEndian, 0.1);
+        assert_eq!(bytes_of(&value), &0.1f64.to_be_bytes());
+        value.set(BigEndian, f64::INFINITY);
+        assert_eq!(value.get(BigEndian), f64::INFINITY);
+        let value = F64Bytes::new(LittleEndian, 2.0);
+        assert_eq!(bytes_of(&value), &2.0f64.to_le_bytes());
+        assert_eq!(value.get(LittleEndian), 2.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn float_debug() {
+        assert_eq!(
+            format!("{:?}", F32Bytes::<BigEndian>::from_bytes([1, 2, 3, 4])),
+            "F32(1, 2, 3, 4)"
+        );
+    }
+}
@@ -91,6 +91,19 @@ pub mod read;
 #[cfg(feature = "read_core")]
 pub use read::*;
 
+#[cfg(all(
+    feature = "read_core",
+    any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "pe",
+        feature = "wasm",
+        feature = "xcoff"
+    )
+))]
+pub mod verify;
+
 #[cfg(feature = "write_core")]
 pub mod write;
 
@@ -7,7 +7,7 @@
 
 #![allow(missing_docs)]
 
-use crate::endian::{BigEndian, Endian, U64Bytes, U16, U32, U64};
+use crate::endian::{BigEndian, Endian, U64Bytes, I32, U16, U32, U64};
 use crate::pod::Pod;
 use core::fmt::{self, Debug};
 
@@ -597,6 +597,52 @@ pub struct DyldSubCacheEntryV2<E: Endian> {
     pub file_suffix: [u8; 32],
 }
 
+/// Corresponds to struct dyld_cache_local_symbols_info from dyld_cache_format.h.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldCacheLocalSymbolsInfo<E: Endian> {
+    /// offset into this chunk of nlist entries
+    pub nlist_offset: U32<E>,
+    /// count of nlist entries
+    pub nlist_count: U32<E>,
+    /// offset into this chunk of string pool
+    pub strings_offset: U32<E>,
+    /// byte count of string pool
+    pub strings_size: U32<E>,
+    /// offset into this chunk of array of dyld_cache_local_symbols_entry
+    pub entries_offset: U32<E>,
+    /// number of elements in dyld_cache_local_symbols_entry array
+    pub entries_count: U32<E>,
+}
+
+/// Corresponds to struct dyld_cache_local_symbols_entry from dyld_cache_format.h.
+///
+/// Used before dyld-940 (macOS 12).
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldCacheLocalSymbolsEntry<E: Endian> {
+    /// offset in cache file of start of dylib
+    pub dylib_offset: U32<E>,
+    /// start index of locals for this dylib
+    pub nlist_start_index: U32<E>,
+    /// number of local symbols for this dylib
+    pub nlist_count: U32<E>,
+}
+
+/// Corresponds to struct dyld_cache_local_symbols_entry_64 from dyld_cache_format.h.
+///
+/// Used since dyld-940 (macOS 12).
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldCacheLocalSymbolsEntry64<E: Endian> {
+    /// offset in cache buffer of start of dylib
+    pub dylib_offset: U64<E>,
+    /// start index of locals for this dylib
+    pub nlist_start_index: U32<E>,
+    /// number of local symbols for this dylib
+    pub nlist_count: U32<E>,
+}
+
 // Definitions from "/usr/include/mach-o/loader.h".
 
 /*
@@ -2543,6 +2589,127 @@ pub struct NoteCommand<E: Endian> {
     pub size: U64<E>,
 }
 
+// Definitions from "/usr/include/mach-o/fixup-chains.h".
+
+/// Header of the LC_DYLD_CHAINED_FIXUPS payload.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedFixupsHeader<E: Endian> {
+    /// 0
+    pub fixups_version: U32<E>,
+    /// offset of DyldChainedStartsInImage in chain_data
+    pub starts_offset: U32<E>,
+    /// offset of imports table in chain_data
+    pub imports_offset: U32<E>,
+    /// offset of symbol strings in chain_data
+    pub symbols_offset: U32<E>,
+    /// number of imported symbol names
+    pub imports_count: U32<E>,
+    /// DYLD_CHAINED_IMPORT*
+    pub imports_format: U32<E>,
+    /// 0 => uncompressed, 1 => zlib compressed
+    pub symbols_format: U32<E>,
+}
+
+/// This struct is embedded in the LC_DYLD_CHAINED_FIXUPS payload.
+///
+/// It is followed by `seg_count` entries of `U32<E>` containing the offset
+/// of each `DyldChainedStartsInSegment` relative to this struct.
+/// An offset of 0 means the segment has no fixups.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedStartsInImage<E: Endian> {
+    pub seg_count: U32<E>,
+}
+
+/// This struct is embedded in the LC_DYLD_CHAINED_FIXUPS payload.
+///
+/// It is followed by `page_count` entries of `U16<E>` containing the offset
+/// of the first fixup in each page, or `DYLD_CHAINED_PTR_START_NONE`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedStartsInSegment<E: Endian> {
+    /// size of this (amount kernel needs to copy)
+    pub size: U32<E>,
+    /// 0x1000 or 0x4000
+    pub page_size: U16<E>,
+    /// DYLD_CHAINED_PTR_*
+    pub pointer_format: U16<E>,
+    /// offset in memory to start of segment
+    pub segment_offset: U64<E>,
+    /// for 32-bit OS, any value beyond this is not a pointer
+    pub max_valid_pointer: U32<E>,
+    /// how many pages are in array
+    pub page_count: U16<E>,
+}
+
+// Values for `DyldChainedStartsInSegment::page_start`.
+/// used in page_start[] to denote a page with no fixups
+pub const DYLD_CHAINED_PTR_START_NONE: u16 = 0xFFFF;
+/// used in page_start[] to denote a page which has multiple starts
+pub const DYLD_CHAINED_PTR_START_MULTI: u16 = 0x8000;
+/// used in chain_starts[] to denote last start in list for page
+pub const DYLD_CHAINED_PTR_START_LAST: u16 = 0x8000;
+
+// Values for `DyldChainedStartsInSegment::pointer_format`.
+/// stride 8, unauth target is vmaddr
+pub const DYLD_CHAINED_PTR_ARM64E: u16 = 1;
+/// target is vmaddr
+pub const DYLD_CHAINED_PTR_64: u16 = 2;
+pub const DYLD_CHAINED_PTR_32: u16 = 3;
+pub const DYLD_CHAINED_PTR_32_CACHE: u16 = 4;
+pub const DYLD_CHAINED_PTR_32_FIRMWARE: u16 = 5;
+/// target is vm offset
+pub const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+/// stride 4, unauth target is vm offset
+pub const DYLD_CHAINED_PTR_ARM64E_KERNEL: u16 = 7;
+pub const DYLD_CHAINED_PTR_64_KERNEL_CACHE: u16 = 8;
+/// stride 8, unauth target is vm offset
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND: u16 = 9;
+/// stride 4, unauth target is vmaddr
+pub const DYLD_CHAINED_PTR_ARM64E_FIRMWARE: u16 = 10;
+/// stride 1, x86_64 kernel caches
+pub const DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE: u16 = 11;
+/// stride 8, unauth target is vm offset, 24-bit bind
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND24: u16 = 12;
+/// stride 8, regular/auth targets both vm offsets, only auth ptrs have high8
+pub const DYLD_CHAINED_PTR_ARM64E_SHARED_CACHE: u16 = 13;
+/// stride 4, rebase offsets use segIndex and segOffset
+pub const DYLD_CHAINED_PTR_ARM64E_SEGMENTED: u16 = 14;
+
+// Values for `DyldChainedFixupsHeader::imports_format`.
+pub const DYLD_CHAINED_IMPORT: u32 = 1;
+pub const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+pub const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+
+/// DYLD_CHAINED_IMPORT
+///
+/// Bitfield containing `lib_ordinal:8`, `weak_import:1`, `name_offset:23`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedImport<E: Endian> {
+    pub value: U32<E>,
+}
+
+/// DYLD_CHAINED_IMPORT_ADDEND
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedImportAddend<E: Endian> {
+    /// Bitfield containing `lib_ordinal:8`, `weak_import:1`, `name_offset:23`.
+    pub value: U32<E>,
+    pub addend: I32<E>,
+}
+
+/// DYLD_CHAINED_IMPORT_ADDEND64
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedImportAddend64<E: Endian> {
+    /// Bitfield containing `lib_ordinal:16`, `weak_import:1`, `reserved:15`,
+    /// `name_offset:32`.
+    pub value: U64<E>,
+    pub addend: U64<E>,
+}
+
 // Definitions from "/usr/include/mach-o/nlist.h".
 
 #[derive(Debug, Clone, Copy)]
@@ -3455,7 +3622,143 @@ pub const X86_64_RELOC_SIGNED_4: u8 = 8;
 /// for thread local variables
 pub const X86_64_RELOC_TLV: u8 = 9;
 
+// Definitions from "kern/cs_blobs.h".
+
+/// Magic number for an embedded signature (a [`CsSuperBlob`]).
+pub const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade_0cc0;
+/// Magic number for a [`CsCodeDirectory`].
+pub const CSMAGIC_CODEDIRECTORY: u32 = 0xfade_0c02;
+/// Magic number for a requirements vector.
+pub const CSMAGIC_REQUIREMENTS: u32 = 0xfade_0c01;
+/// Magic number for an embedded entitlements blob.
+pub const CSMAGIC_EMBEDDED_ENTITLEMENTS: u32 = 0xfade_7171;
+/// Magic number for a CMS signature blob.
+pub const CSMAGIC_BLOBWRAPPER: u32 = 0xfade_0b01;
+
+/// Slot index of the code directory.
+pub const CSSLOT_CODEDIRECTORY: u32 = 0;
+/// Slot index of the info plist.
+pub const CSSLOT_INFOSLOT: u32 = 1;
+/// Slot index of the requirements vector.
+pub const CSSLOT_REQUIREMENTS: u32 = 2;
+/// Slot index of the resource directory.
+pub const CSSLOT_RESOURCEDIR: u32 = 3;
+/// Slot index of the application specific slot.
+pub const CSSLOT_APPLICATION: u32 = 4;
+/// Slot index of the embedded entitlements.
+pub const CSSLOT_ENTITLEMENTS: u32 = 5;
+/// Slot index of the CMS signature.
+pub const CSSLOT_SIGNATURESLOT: u32 = 0x10000;
+
+/// Code directory version that supports scatter.
+pub const CS_SUPPORTSSCATTER: u32 = 0x20100;
+/// Code directory version that supports team ID.
+pub const CS_SUPPORTSTEAMID: u32 = 0x20200;
+/// Code directory version that supports 64-bit code limits.
+pub const CS_SUPPORTSCODELIMIT64: u32 = 0x20300;
+/// Code directory version that supports executable segments.
+pub const CS_SUPPORTSEXECSEG: u32 = 0x20400;
+
+/// Code directory flag for an ad hoc signature.
+pub const CS_ADHOC: u32 = 0x0000_0002;
+/// Code directory flag for hardened runtime.
+pub const CS_RUNTIME: u32 = 0x0001_0000;
+/// Code directory flag for a signature automatically created by a linker.
+pub const CS_LINKER_SIGNED: u32 = 0x0002_0000;
+
+/// Executable segment flag for the main binary.
+pub const CS_EXECSEG_MAIN_BINARY: u64 = 0x1;
+/// Executable segment flag to allow unsigned pages.
+pub const CS_EXECSEG_ALLOW_UNSIGNED: u64 = 0x10;
+
+/// SHA-1 hash type.
+pub const CS_HASHTYPE_SHA1: u8 = 1;
+/// SHA-256 hash type.
+pub const CS_HASHTYPE_SHA256: u8 = 2;
+/// SHA-256 truncated to 20 bytes hash type.
+pub const CS_HASHTYPE_SHA256_TRUNCATED: u8 = 3;
+/// SHA-384 hash type.
+pub const CS_HASHTYPE_SHA384: u8 = 4;
+
+/// Length of a SHA-1 hash.
+pub const CS_SHA1_LEN: u8 = 20;
+/// Length of a SHA-256 hash.
+pub const CS_SHA256_LEN: u8 = 32;
+
+/// An index entry in a [`CsSuperBlob`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CsBlobIndex {
+    /// type of entry (one of the `CSSLOT_*` constants)
+    pub typ: U32<BigEndian>,
+    /// offset of entry
+    pub offset: U32<BigEndian>,
+}
+
+/// The header of an embedded signature.
+///
+/// This is followed by `count` entries of [`CsBlobIndex`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CsSuperBlob {
+    /// `CSMAGIC_EMBEDDED_SIGNATURE`
+    pub magic: U32<BigEndian>,
+    /// total length of SuperBlob
+    pub length: U32<BigEndian>,
+    /// number of index entries following
+    pub count: U32<BigEndian>,
+}
+
+/// A code directory, including the fields up to version `CS_SUPPORTSEXECSEG`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CsCodeDirectory {
+    /// `CSMAGIC_CODEDIRECTORY`
+    pub magic: U32<BigEndian>,
+    /// total length of CodeDirectory blob
+    pub length: U32<BigEndian>,
+    /// compatibility version
+    pub version: U32<BigEndian>,
+    /// setup and mode flags
+    pub flags: U32<BigEndian>,
+    /// offset of hash slot element at index zero
+    pub hash_offset: U32<BigEndian>,
+    /// offset of identifier string
+    pub ident_offset: U32<BigEndian>,
+    /// number of special hash slots
+    pub n_special_slots: U32<BigEndian>,
+    /// number of ordinary (code) hash slots
+    pub n_code_slots: U32<BigEndian>,
+    /// limit to main image signature range
+    pub code_limit: U32<BigEndian>,
+    /// size of each hash in bytes
+    pub hash_size: u8,
+    /// type of hash (one of the `CS_HASHTYPE_*` constants)
+    pub hash_type: u8,
+    /// platform identifier; zero if not platform binary
+    pub platform: u8,
+    /// log2(page size in bytes); 0 => infinite
+    pub page_size: u8,
+    /// unused (must be zero)
+    pub spare2: U32<BigEndian>,
+    /// offset of optional scatter vector (version `CS_SUPPORTSSCATTER`)
+    pub scatter_offset: U32<BigEndian>,
+    /// offset of optional team identifier (version `CS_SUPPORTSTEAMID`)
+    pub team_offset: U32<BigEndian>,
+    /// unused (must be zero) (version `CS_SUPPORTSCODELIMIT64`)
+    pub spare3: U32<BigEndian>,
+    /// limit to main image signature range, 64 bits (version `CS_SUPPORTSCODELIMIT64`)
+    pub code_limit64: U64<BigEndian>,
+    /// offset of executable segment (version `CS_SUPPORTSEXECSEG`)
+    pub exec_seg_base: U64<BigEndian>,
+    /// limit of executable segment (version `CS_SUPPORTSEXECSEG`)
+    pub exec_seg_limit: U64<BigEndian>,
+    /// executable segment flags (version `CS_SUPPORTSEXECSEG`)
+    pub exec_seg_flags: U64<BigEndian>,
+}
+
 unsafe_impl_pod!(FatHeader, FatArch32, FatArch64,);
+unsafe_impl_pod!(CsBlobIndex, CsSuperBlob, CsCodeDirectory,);
 unsafe_impl_endian_pod!(
     DyldCacheHeader,
     DyldCacheMappingInfo,
@@ -3464,6 +3767,9 @@ unsafe_impl_endian_pod!(
     DyldCacheSlideInfo5,
     DyldSubCacheEntryV1,
     DyldSubCacheEntryV2,
+    DyldCacheLocalSymbolsInfo,
+    DyldCacheLocalSymbolsEntry,
+    DyldCacheLocalSymbolsEntry64,
     MachHeader32,
     MachHeader64,
     LoadCommand,
@@ -3513,6 +3819,12 @@ unsafe_impl_endian_pod!(
     DataInCodeEntry,
     //TlvDescriptor,
     NoteCommand,
+    DyldChainedFixupsHeader,
+    DyldChainedStartsInImage,
+    DyldChainedStartsInSegment,
+    DyldChainedImport,
+    DyldChainedImportAddend,
+    DyldChainedImportAddend64,
     Nlist32,
     Nlist64,
     Relocation,
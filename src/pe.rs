@@ -2141,6 +2141,78 @@ pub const RT_HTML: u16 = 23;
 /// ID for: Side-by-Side Assembly Manifest.
 pub const RT_MANIFEST: u16 = 24;
 
+/// Manifest resource ID used by the loader for executables.
+pub const CREATEPROCESS_MANIFEST_RESOURCE_ID: u16 = 1;
+/// Manifest resource ID used by the loader for DLLs.
+pub const ISOLATIONAWARE_MANIFEST_RESOURCE_ID: u16 = 2;
+/// Manifest resource ID used by the loader for DLLs that don't need binding.
+pub const ISOLATIONAWARE_NOSTATICIMPORT_MANIFEST_RESOURCE_ID: u16 = 3;
+
+//
+// Version information resource
+//
+
+/// Fixed information in a `VS_VERSIONINFO` resource.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VsFixedFileInfo {
+    /// `VS_FFI_SIGNATURE`
+    pub signature: U32<LE>,
+    /// `VS_FFI_STRUCVERSION`
+    pub struc_version: U32<LE>,
+    pub file_version_ms: U32<LE>,
+    pub file_version_ls: U32<LE>,
+    pub product_version_ms: U32<LE>,
+    pub product_version_ls: U32<LE>,
+    pub file_flags_mask: U32<LE>,
+    /// `VS_FF_*`
+    pub file_flags: U32<LE>,
+    /// `VOS_*`
+    pub file_os: U32<LE>,
+    /// `VFT_*`
+    pub file_type: U32<LE>,
+    /// `VFT2_*`
+    pub file_subtype: U32<LE>,
+    pub file_date_ms: U32<LE>,
+    pub file_date_ls: U32<LE>,
+}
+
+pub const VS_FFI_SIGNATURE: u32 = 0xFEEF_04BD;
+pub const VS_FFI_STRUCVERSION: u32 = 0x0001_0000;
+pub const VS_FFI_FILEFLAGSMASK: u32 = 0x0000_003F;
+
+pub const VS_FF_DEBUG: u32 = 0x0000_0001;
+pub const VS_FF_PRERELEASE: u32 = 0x0000_0002;
+pub const VS_FF_PATCHED: u32 = 0x0000_0004;
+pub const VS_FF_PRIVATEBUILD: u32 = 0x0000_0008;
+pub const VS_FF_INFOINFERRED: u32 = 0x0000_0010;
+pub const VS_FF_SPECIALBUILD: u32 = 0x0000_0020;
+
+pub const VOS_UNKNOWN: u32 = 0x0000_0000;
+pub const VOS_DOS: u32 = 0x0001_0000;
+pub const VOS_OS216: u32 = 0x0002_0000;
+pub const VOS_OS232: u32 = 0x0003_0000;
+pub const VOS_NT: u32 = 0x0004_0000;
+pub const VOS_WINCE: u32 = 0x0005_0000;
+pub const VOS__BASE: u32 = 0x0000_0000;
+pub const VOS__WINDOWS16: u32 = 0x0000_0001;
+pub const VOS__PM16: u32 = 0x0000_0002;
+pub const VOS__PM32: u32 = 0x0000_0003;
+pub const VOS__WINDOWS32: u32 = 0x0000_0004;
+pub const VOS_DOS_WINDOWS16: u32 = 0x0001_0001;
+pub const VOS_DOS_WINDOWS32: u32 = 0x0001_0004;
+pub const VOS_OS216_PM16: u32 = 0x0002_0002;
+pub const VOS_OS232_PM32: u32 = 0x0003_0003;
+pub const VOS_NT_WINDOWS32: u32 = 0x0004_0004;
+
+pub const VFT_UNKNOWN: u32 = 0x0000_0000;
+pub const VFT_APP: u32 = 0x0000_0001;
+pub const VFT_DLL: u32 = 0x0000_0002;
+pub const VFT_DRV: u32 = 0x0000_0003;
+pub const VFT_FONT: u32 = 0x0000_0004;
+pub const VFT_VXD: u32 = 0x0000_0005;
+pub const VFT_STATIC_LIB: u32 = 0x0000_0007;
+
 //
 // Code Integrity in loadconfig (CI)
 //
@@ -2973,6 +3045,206 @@ pub struct ImageCor20Header {
     pub managed_native_header: ImageDataDirectory,
 }
 
+//
+// CodeView debug information in COFF object files.
+//
+// These definitions are based on "cvinfo.h" from microsoft-pdb.
+//
+
+/// The signature at the start of `.debug$S` and `.debug$T` sections.
+pub const CV_SIGNATURE_C13: u32 = 4;
+
+/// The header of a subsection in a `.debug$S` section.
+///
+/// The subsection data follows the header, and is padded to a multiple of 4 bytes.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvDebugSubsectionHeader {
+    /// One of the `DEBUG_S_*` constants.
+    pub typ: U32Bytes<LE>,
+    /// The length of the subsection data, excluding this header and padding.
+    pub length: U32Bytes<LE>,
+}
+
+/// Flag in `CvDebugSubsectionHeader::typ` indicating that the subsection should be ignored.
+pub const DEBUG_S_IGNORE: u32 = 0x8000_0000;
+
+// Values for `CvDebugSubsectionHeader::typ`.
+pub const DEBUG_S_SYMBOLS: u32 = 0xf1;
+pub const DEBUG_S_LINES: u32 = 0xf2;
+pub const DEBUG_S_STRINGTABLE: u32 = 0xf3;
+pub const DEBUG_S_FILECHKSMS: u32 = 0xf4;
+pub const DEBUG_S_FRAMEDATA: u32 = 0xf5;
+pub const DEBUG_S_INLINEELINES: u32 = 0xf6;
+pub const DEBUG_S_CROSSSCOPEIMPORTS: u32 = 0xf7;
+pub const DEBUG_S_CROSSSCOPEEXPORTS: u32 = 0xf8;
+pub const DEBUG_S_IL_LINES: u32 = 0xf9;
+pub const DEBUG_S_FUNC_MDTOKEN_MAP: u32 = 0xfa;
+pub const DEBUG_S_TYPE_MDTOKEN_MAP: u32 = 0xfb;
+pub const DEBUG_S_MERGED_ASSEMBLYINPUT: u32 = 0xfc;
+pub const DEBUG_S_COFF_SYMBOL_RVA: u32 = 0xfd;
+
+/// The header of a symbol record or type record.
+///
+/// The record data follows the header.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvRecordHeader {
+    /// The length of the record, including `kind` but excluding this field.
+    pub length: U16Bytes<LE>,
+    /// The kind of the record.
+    ///
+    /// One of the `S_*` constants for symbol records, or `LF_*` constants
+    /// for type records.
+    pub kind: U16Bytes<LE>,
+}
+
+// Values for `CvRecordHeader::kind` in symbol records.
+pub const S_END: u16 = 0x0006;
+pub const S_FRAMEPROC: u16 = 0x1012;
+pub const S_OBJNAME: u16 = 0x1101;
+pub const S_THUNK32: u16 = 0x1102;
+pub const S_BLOCK32: u16 = 0x1103;
+pub const S_LABEL32: u16 = 0x1105;
+pub const S_CONSTANT: u16 = 0x1107;
+pub const S_UDT: u16 = 0x1108;
+pub const S_LDATA32: u16 = 0x110c;
+pub const S_GDATA32: u16 = 0x110d;
+pub const S_PUB32: u16 = 0x110e;
+pub const S_LPROC32: u16 = 0x110f;
+pub const S_GPROC32: u16 = 0x1110;
+pub const S_REGREL32: u16 = 0x1111;
+pub const S_LTHREAD32: u16 = 0x1112;
+pub const S_GTHREAD32: u16 = 0x1113;
+pub const S_COMPILE2: u16 = 0x1116;
+pub const S_SECTION: u16 = 0x1136;
+pub const S_COFFGROUP: u16 = 0x1137;
+pub const S_COMPILE3: u16 = 0x113c;
+pub const S_LOCAL: u16 = 0x113e;
+pub const S_LPROC32_ID: u16 = 0x1146;
+pub const S_GPROC32_ID: u16 = 0x1147;
+pub const S_BUILDINFO: u16 = 0x114c;
+pub const S_INLINESITE: u16 = 0x114d;
+pub const S_INLINESITE_END: u16 = 0x114e;
+pub const S_PROC_ID_END: u16 = 0x114f;
+
+/// The data of a `S_LPROC32`, `S_GPROC32`, `S_LPROC32_ID` or `S_GPROC32_ID` symbol record.
+///
+/// The null terminated name of the procedure follows this structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvProcSym32 {
+    pub parent: U32Bytes<LE>,
+    pub end: U32Bytes<LE>,
+    pub next: U32Bytes<LE>,
+    /// The length of the procedure in bytes.
+    pub len: U32Bytes<LE>,
+    pub dbg_start: U32Bytes<LE>,
+    pub dbg_end: U32Bytes<LE>,
+    /// The type index, or the item ID for `S_*PROC32_ID`.
+    pub typind: U32Bytes<LE>,
+    /// The section offset of the procedure.
+    ///
+    /// In object files, this is set by a `IMAGE_REL_*_SECREL` relocation.
+    pub off: U32Bytes<LE>,
+    /// The section number of the procedure.
+    ///
+    /// In object files, this is set by a `IMAGE_REL_*_SECTION` relocation.
+    pub seg: U16Bytes<LE>,
+    /// A combination of the `CV_PFLAG_*` flags.
+    pub flags: u8,
+}
+
+pub const CV_PFLAG_NOFPO: u8 = 0x01;
+pub const CV_PFLAG_INT: u8 = 0x02;
+pub const CV_PFLAG_FAR: u8 = 0x04;
+pub const CV_PFLAG_NEVER: u8 = 0x08;
+pub const CV_PFLAG_NOTREACHED: u8 = 0x10;
+pub const CV_PFLAG_CUST_CALL: u8 = 0x20;
+pub const CV_PFLAG_NOINLINE: u8 = 0x40;
+pub const CV_PFLAG_OPTDBGINFO: u8 = 0x80;
+
+/// The header of a `DEBUG_S_LINES` subsection.
+///
+/// A sequence of file blocks follows the header.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvDebugLinesHeader {
+    /// The section offset of the code.
+    pub off_con: U32Bytes<LE>,
+    /// The section number of the code.
+    pub seg_con: U16Bytes<LE>,
+    /// A combination of the `CV_LINES_*` flags.
+    pub flags: U16Bytes<LE>,
+    /// The length of the code.
+    pub cb_con: U32Bytes<LE>,
+}
+
+/// Flag in `CvDebugLinesHeader::flags` indicating that column information is present.
+pub const CV_LINES_HAVE_COLUMNS: u16 = 0x0001;
+
+/// The header of a file block in a `DEBUG_S_LINES` subsection.
+///
+/// This is followed by `num_lines` entries of `CvLine`, and then `num_lines`
+/// entries of `CvColumn` if `CV_LINES_HAVE_COLUMNS` is set.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvDebugLinesFileBlockHeader {
+    /// The offset of the file's entry in the `DEBUG_S_FILECHKSMS` subsection.
+    pub off_file: U32Bytes<LE>,
+    /// The number of line entries.
+    pub num_lines: U32Bytes<LE>,
+    /// The length of the block, including this header.
+    pub cb_block: U32Bytes<LE>,
+}
+
+/// A line entry in a `DEBUG_S_LINES` subsection.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvLine {
+    /// The offset of the code relative to `CvDebugLinesHeader::off_con`.
+    pub offset: U32Bytes<LE>,
+    /// A combination of the start line number, the line delta, and the statement flag.
+    pub flags: U32Bytes<LE>,
+}
+
+/// Mask for the start line number in `CvLine::flags`.
+pub const CV_LINE_NUMBER_START_MASK: u32 = 0x00ff_ffff;
+/// Shift for the delta to the end line number in `CvLine::flags`.
+pub const CV_LINE_DELTA_END_SHIFT: u32 = 24;
+/// Mask for the delta to the end line number in `CvLine::flags`.
+pub const CV_LINE_DELTA_END_MASK: u32 = 0x7f;
+/// Flag in `CvLine::flags` indicating that the line is a statement.
+pub const CV_LINE_STATEMENT: u32 = 0x8000_0000;
+
+/// A column entry in a `DEBUG_S_LINES` subsection.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvColumn {
+    pub off_column_start: U16Bytes<LE>,
+    pub off_column_end: U16Bytes<LE>,
+}
+
+/// The header of an entry in a `DEBUG_S_FILECHKSMS` subsection.
+///
+/// The checksum follows the header, and the entry is padded to a multiple of 4 bytes.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CvFileChecksumHeader {
+    /// The offset of the file name in the `DEBUG_S_STRINGTABLE` subsection.
+    pub off_file_name: U32Bytes<LE>,
+    /// The size of the checksum in bytes.
+    pub checksum_size: u8,
+    /// One of the `CHKSUM_TYPE_*` constants.
+    pub checksum_type: u8,
+}
+
+// Values for `CvFileChecksumHeader::checksum_type`.
+pub const CHKSUM_TYPE_NONE: u8 = 0;
+pub const CHKSUM_TYPE_MD5: u8 = 1;
+pub const CHKSUM_TYPE_SHA1: u8 = 2;
+pub const CHKSUM_TYPE_SHA_256: u8 = 3;
+
 unsafe_impl_pod!(
     ImageDosHeader,
     ImageOs2Header,
@@ -3019,6 +3291,7 @@ unsafe_impl_pod!(
     ImageResourceDirectoryString,
     ImageResourceDirStringU,
     ImageResourceDataEntry,
+    VsFixedFileInfo,
     ImageLoadConfigCodeIntegrity,
     ImageDynamicRelocationTable,
     ImageDynamicRelocation32,
@@ -3057,4 +3330,12 @@ unsafe_impl_pod!(
     ImportObjectHeader,
     ImageCor20Header,
     MaskedRichHeaderEntry,
+    CvDebugSubsectionHeader,
+    CvRecordHeader,
+    CvProcSym32,
+    CvDebugLinesHeader,
+    CvDebugLinesFileBlockHeader,
+    CvLine,
+    CvColumn,
+    CvFileChecksumHeader,
 );
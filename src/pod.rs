@@ -8,7 +8,7 @@
     allow(dead_code)
 )]
 
-use core::{mem, result, slice};
+use core::{fmt, mem, result, slice};
 
 type Result<T> = result::Result<T, ()>;
 
@@ -142,6 +142,113 @@ pub fn slice_from_all_bytes_mut<T: Pod>(data: &mut [u8]) -> Result<&mut [T]> {
     Ok(slice)
 }
 
+/// A wrapper for a `Pod` type that has an alignment of 1.
+///
+/// This allows casting data whose alignment can't be guaranteed, such as
+/// file members inside archives, independently of the `unaligned` feature.
+/// The value must be copied out using [`Self::get`] before it can be used.
+#[repr(C, packed)]
+pub struct Unaligned<T: Pod>(T);
+
+impl<T: Pod> Unaligned<T> {
+    /// Construct a new unaligned value.
+    #[inline]
+    pub fn new(val: T) -> Self {
+        Unaligned(val)
+    }
+
+    /// Return a copy of the value.
+    #[inline]
+    pub fn get(&self) -> T {
+        self.0
+    }
+
+    /// Set the value.
+    #[inline]
+    pub fn set(&mut self, val: T) {
+        self.0 = val;
+    }
+}
+
+impl<T: Pod> Clone for Unaligned<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Pod> Copy for Unaligned<T> {}
+
+impl<T: Pod + PartialEq> PartialEq for Unaligned<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Pod + Eq> Eq for Unaligned<T> {}
+
+impl<T: Pod + Default> Default for Unaligned<T> {
+    #[inline]
+    fn default() -> Self {
+        Unaligned(T::default())
+    }
+}
+
+impl<T: Pod + fmt::Debug> fmt::Debug for Unaligned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Unaligned").field(&self.get()).finish()
+    }
+}
+
+// Safety: `T` is `Pod`, and packing it can't add padding.
+unsafe impl<T: Pod> Pod for Unaligned<T> {}
+
+/// Cast the head of a byte slice to an [`Unaligned`] `Pod` type.
+///
+/// Returns the type and the tail of the byte slice.
+///
+/// Returns an error if the byte slice is too short. The alignment is never invalid.
+#[inline]
+pub fn from_bytes_unaligned<T: Pod>(data: &[u8]) -> Result<(&Unaligned<T>, &[u8])> {
+    from_bytes(data)
+}
+
+/// Cast the head of a byte slice to a slice of an [`Unaligned`] `Pod` type.
+///
+/// Returns the type slice and the tail of the byte slice.
+///
+/// Returns an error if the byte slice is too short. The alignment is never invalid.
+#[inline]
+pub fn slice_from_bytes_unaligned<T: Pod>(
+    data: &[u8],
+    count: usize,
+) -> Result<(&[Unaligned<T>], &[u8])> {
+    slice_from_bytes(data, count)
+}
+
+/// Cast all of a byte slice to a slice of an [`Unaligned`] `Pod` type.
+///
+/// Returns the type slice.
+///
+/// Returns an error if the size of the byte slice is not an exact multiple
+/// of the type size. The alignment is never invalid.
+#[inline]
+pub fn slice_from_all_bytes_unaligned<T: Pod>(data: &[u8]) -> Result<&[Unaligned<T>]> {
+    slice_from_all_bytes(data)
+}
+
+/// Cast all of a mutable byte slice to a slice of an [`Unaligned`] `Pod` type.
+///
+/// Returns the type slice.
+///
+/// Returns an error if the size of the byte slice is not an exact multiple
+/// of the type size. The alignment is never invalid.
+#[inline]
+pub fn slice_from_all_bytes_unaligned_mut<T: Pod>(data: &mut [u8]) -> Result<&mut [Unaligned<T>]> {
+    slice_from_all_bytes_mut(data)
+}
+
 /// Cast a `Pod` type to a byte slice.
 #[inline]
 pub fn bytes_of<T: Pod>(val: &T) -> &[u8] {
@@ -278,4 +385,40 @@ mod tests {
         assert_eq!(slice_from_bytes_mut::<u16>(&mut bytes_mut[2..], 4), Err(()));
         assert_eq!(slice_from_bytes_mut::<u16>(&mut bytes_mut[1..], 2), Err(()));
     }
+
+    #[test]
+    fn unaligned() {
+        let x = [
+            u16::to_be(0x0123),
+            u16::to_be(0x4567),
+            u16::to_be(0x89ab),
+            u16::to_be(0xcdef),
+        ];
+        let mut x_mut = x;
+        let bytes = bytes_of_slice(&x);
+        assert_eq!(mem::align_of::<Unaligned<u64>>(), 1);
+        assert_eq!(mem::size_of::<Unaligned<u64>>(), 8);
+
+        let (y, tail) = from_bytes_unaligned::<u16>(&bytes[1..]).unwrap();
+        assert_eq!(y.get(), u16::to_be(0x2345));
+        assert_eq!(tail, &bytes[3..]);
+
+        let (y, tail) = slice_from_bytes_unaligned::<u16>(&bytes[1..], 3).unwrap();
+        assert_eq!(y.len(), 3);
+        assert_eq!(u16::from_be(y[0].get()), 0x2345);
+        assert_eq!(u16::from_be(y[1].get()), 0x6789);
+        assert_eq!(u16::from_be(y[2].get()), 0xabcd);
+        assert_eq!(tail, &bytes[7..]);
+
+        let y = slice_from_all_bytes_unaligned::<u32>(&bytes[..8]).unwrap();
+        assert_eq!(y.len(), 2);
+        assert_eq!(u32::from_be(y[1].get()), 0x89ab_cdef);
+        assert_eq!(slice_from_all_bytes_unaligned::<u32>(&bytes[1..]), Err(()));
+        assert_eq!(slice_from_bytes_unaligned::<u16>(&bytes[1..], 4), Err(()));
+
+        let bytes_mut = bytes_of_slice_mut(&mut x_mut);
+        let y = slice_from_all_bytes_unaligned_mut::<u16>(&mut bytes_mut[1..7]).unwrap();
+        y[0].set(u16::to_be(0xfedc));
+        assert_eq!(bytes_mut, [0x01, 0xfe, 0xdc, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+    }
 }
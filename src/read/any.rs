@@ -18,10 +18,11 @@ use crate::read::wasm;
 use crate::read::xcoff;
 use crate::read::{
     self, Architecture, BinaryFormat, CodeView, ComdatKind, CompressedData, CompressedFileRange,
-    Error, Export, FileFlags, FileKind, Import, Object, ObjectComdat, ObjectKind, ObjectMap,
-    ObjectSection, ObjectSegment, ObjectSymbol, ObjectSymbolTable, ReadRef, Relocation,
-    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags, SubArchitecture,
-    SymbolFlags, SymbolIndex, SymbolKind, SymbolMap, SymbolMapName, SymbolScope, SymbolSection,
+    Error, Export, FileFlags, FileKind, GoBuildInfo, Import, Object, ObjectComdat, ObjectKind,
+    ObjectMap, ObjectSection, ObjectSegment, ObjectSymbol, ObjectSymbolTable, ParseOptions,
+    Permissions, ReadRef, Relocation, RelocationMap, Result, RustMetadata, SectionFlags,
+    SectionIndex, SectionKind, SegmentFlags, SourceFile, SubArchitecture, SymbolFlags, SymbolIndex,
+    SymbolKind, SymbolMap, SymbolMapName, SymbolScope, SymbolSection,
 };
 
 /// Evaluate an expression on the contents of a file format enum.
@@ -238,29 +239,39 @@ pub enum File<'data, R: ReadRef<'data> = &'data [u8]> {
 impl<'data, R: ReadRef<'data>> File<'data, R> {
     /// Parse the raw file data.
     pub fn parse(data: R) -> Result<Self> {
+        Self::parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw file data, using the given options.
+    ///
+    /// The options are ignored for Wasm files, except for `verify`.
+    pub fn parse_with(data: R, options: &ParseOptions) -> Result<Self> {
         Ok(match FileKind::parse(data)? {
             #[cfg(feature = "elf")]
-            FileKind::Elf32 => File::Elf32(elf::ElfFile32::parse(data)?),
+            FileKind::Elf32 => File::Elf32(elf::ElfFile32::parse_with(data, options)?),
             #[cfg(feature = "elf")]
-            FileKind::Elf64 => File::Elf64(elf::ElfFile64::parse(data)?),
+            FileKind::Elf64 => File::Elf64(elf::ElfFile64::parse_with(data, options)?),
             #[cfg(feature = "macho")]
-            FileKind::MachO32 => File::MachO32(macho::MachOFile32::parse(data)?),
+            FileKind::MachO32 => File::MachO32(macho::MachOFile32::parse_with(data, options)?),
             #[cfg(feature = "macho")]
-            FileKind::MachO64 => File::MachO64(macho::MachOFile64::parse(data)?),
+            FileKind::MachO64 => File::MachO64(macho::MachOFile64::parse_with(data, options)?),
             #[cfg(feature = "wasm")]
-            FileKind::Wasm => File::Wasm(wasm::WasmFile::parse(data)?),
+            FileKind::Wasm => {
+                options.verify_file(data)?;
+                File::Wasm(wasm::WasmFile::parse(data)?)
+            }
             #[cfg(feature = "pe")]
-            FileKind::Pe32 => File::Pe32(pe::PeFile32::parse(data)?),
+            FileKind::Pe32 => File::Pe32(pe::PeFile32::parse_with(data, options)?),
             #[cfg(feature = "pe")]
-            FileKind::Pe64 => File::Pe64(pe::PeFile64::parse(data)?),
+            FileKind::Pe64 => File::Pe64(pe::PeFile64::parse_with(data, options)?),
             #[cfg(feature = "coff")]
-            FileKind::Coff => File::Coff(coff::CoffFile::parse(data)?),
+            FileKind::Coff => File::Coff(coff::CoffFile::parse_with(data, options)?),
             #[cfg(feature = "coff")]
-            FileKind::CoffBig => File::CoffBig(coff::CoffBigFile::parse(data)?),
+            FileKind::CoffBig => File::CoffBig(coff::CoffBigFile::parse_with(data, options)?),
             #[cfg(feature = "xcoff")]
-            FileKind::Xcoff32 => File::Xcoff32(xcoff::XcoffFile32::parse(data)?),
+            FileKind::Xcoff32 => File::Xcoff32(xcoff::XcoffFile32::parse_with(data, options)?),
             #[cfg(feature = "xcoff")]
-            FileKind::Xcoff64 => File::Xcoff64(xcoff::XcoffFile64::parse(data)?),
+            FileKind::Xcoff64 => File::Xcoff64(xcoff::XcoffFile64::parse_with(data, options)?),
             #[allow(unreachable_patterns)]
             _ => return Err(Error("Unsupported file format")),
         })
@@ -301,6 +312,81 @@ impl<'data, R: ReadRef<'data>> File<'data, R> {
     }
 }
 
+#[cfg(feature = "compression")]
+impl<'data> File<'data, &'data [u8]> {
+    /// Parse the raw file data, decompressing it first if it is a compressed file.
+    ///
+    /// This supports files such as a `vmlinuz` kernel image or a `.gz` object
+    /// file that contain a single compressed object file. Gzip is supported if the
+    /// `compression` feature is enabled, and xz is supported if the `xz` feature
+    /// is enabled.
+    ///
+    /// If the file is compressed, then the decompressed data is stored in `buffer`
+    /// and the returned file refers to it. Otherwise, the file is parsed from `data`
+    /// and `buffer` is not used.
+    pub fn parse_decompressed(data: &'data [u8], buffer: &'data mut Vec<u8>) -> Result<Self> {
+        use crate::read::ReadError;
+        let decompressed = match FileKind::parse(data)? {
+            FileKind::Gzip => {
+                use std::io::Read;
+                buffer.clear();
+                flate2::read::MultiGzDecoder::new(data)
+                    .read_to_end(buffer)
+                    .ok()
+                    .read_error("Invalid gzip compressed data")?;
+                &buffer[..]
+            }
+            #[cfg(feature = "xz")]
+            FileKind::Xz => {
+                buffer.clear();
+                lzma_rs::xz_decompress(&mut &*data, buffer)
+                    .ok()
+                    .read_error("Invalid xz compressed data")?;
+                &buffer[..]
+            }
+            _ => return File::parse(data),
+        };
+        File::parse(decompressed)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'data, R: ReadRef<'data> + Sync> File<'data, R> {
+    /// Construct a map from addresses to symbol names, using multiple threads.
+    ///
+    /// This returns the same map as [`Object::symbol_map`], but reads and
+    /// classifies the symbols in parallel.
+    pub fn par_symbol_map(&self) -> SymbolMap<SymbolMapName<'data>> {
+        use rayon::prelude::*;
+
+        let symbols = match self.symbol_table().or_else(|| self.dynamic_symbol_table()) {
+            Some(table) => table
+                .symbols()
+                .collect::<Vec<_>>()
+                .par_iter()
+                .filter_map(read::symbol_map_entry)
+                .collect(),
+            None => Vec::new(),
+        };
+        read::symbol_map_from_entries(symbols)
+    }
+
+    /// Construct the relocation maps for all sections, using multiple threads.
+    ///
+    /// This is equivalent to calling [`ObjectSection::relocation_map`] for each
+    /// section that has relocations. The maps are returned in section order.
+    pub fn par_relocation_maps(&self) -> Result<Vec<(SectionIndex, RelocationMap)>> {
+        use rayon::prelude::*;
+
+        self.sections()
+            .filter(|section| section.relocations().next().is_some())
+            .collect::<Vec<_>>()
+            .par_iter()
+            .map(|section| Ok((section.index(), section.relocation_map()?)))
+            .collect()
+    }
+}
+
 impl<'data, R: ReadRef<'data>> read::private::Sealed for File<'data, R> {}
 
 impl<'data, R> Object<'data> for File<'data, R>
@@ -477,6 +563,10 @@ where
         with_inner!(self, File, |x| x.object_map())
     }
 
+    fn source_files(&self) -> Result<Vec<SourceFile<'data>>> {
+        with_inner!(self, File, |x| x.source_files())
+    }
+
     fn imports(&self) -> Result<Vec<Import<'data>>> {
         with_inner!(self, File, |x| x.imports())
     }
@@ -514,6 +604,22 @@ where
         with_inner!(self, File, |x| x.pdb_info())
     }
 
+    fn go_build_id(&self) -> Result<Option<&'data [u8]>> {
+        with_inner!(self, File, |x| x.go_build_id())
+    }
+
+    fn go_build_info(&self) -> Result<Option<GoBuildInfo<'data>>> {
+        with_inner!(self, File, |x| x.go_build_info())
+    }
+
+    fn rust_metadata(&self) -> Result<Option<RustMetadata<'data>>> {
+        with_inner!(self, File, |x| x.rust_metadata())
+    }
+
+    fn llvm_bitcode(&self) -> Result<Option<&'data [u8]>> {
+        with_inner!(self, File, |x| x.llvm_bitcode())
+    }
+
     fn relative_address_base(&self) -> u64 {
         with_inner!(self, File, |x| x.relative_address_base())
     }
@@ -525,6 +631,10 @@ where
     fn flags(&self) -> FileFlags {
         with_inner!(self, File, |x| x.flags())
     }
+
+    fn warnings(&self) -> &[Error] {
+        with_inner!(self, File, |x| x.warnings())
+    }
 }
 
 /// An iterator for the loadable segments in a [`File`].
@@ -658,6 +768,10 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSegment<'data> for Segment<'data, 'f
     fn flags(&self) -> SegmentFlags {
         with_inner!(self.inner, SegmentInternal, |x| x.flags())
     }
+
+    fn permissions(&self) -> Permissions {
+        with_inner!(self.inner, SegmentInternal, |x| x.permissions())
+    }
 }
 
 /// An iterator for the sections in a [`File`].
@@ -836,6 +950,10 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for Section<'data, 'f
     fn flags(&self) -> SectionFlags {
         with_inner!(self.inner, SectionInternal, |x| x.flags())
     }
+
+    fn permissions(&self) -> Permissions {
+        with_inner!(self.inner, SectionInternal, |x| x.permissions())
+    }
 }
 
 /// An iterator for the COMDAT section groups in a [`File`].
@@ -25,7 +25,9 @@ use core::slice;
 
 use crate::archive;
 use crate::endian::{BigEndian as BE, LittleEndian as LE, U16Bytes, U32Bytes, U64Bytes};
-use crate::read::{self, Bytes, Error, ReadError, ReadRef};
+use crate::read::{
+    self, Bytes, Error, ReadCache, ReadCacheOps, ReadCacheRange, ReadError, ReadRef,
+};
 
 /// The kind of archive format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -318,6 +320,50 @@ impl<'data, R: ReadRef<'data>> ArchiveFile<'data, R> {
     }
 }
 
+#[cfg(all(
+    feature = "rayon",
+    any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "pe",
+        feature = "wasm",
+        feature = "xcoff"
+    )
+))]
+impl<'data, R: ReadRef<'data>> ArchiveFile<'data, R> {
+    /// Parse the object files in all members of the archive, using multiple threads.
+    ///
+    /// The member headers are read sequentially, and then the member data is
+    /// parsed in parallel. The results are returned in member order. An error
+    /// for one member does not prevent the other members from being parsed.
+    ///
+    /// The members of thin archives are stored in separate files, so they will
+    /// fail to parse.
+    pub fn par_parse_members(
+        &self,
+    ) -> alloc::vec::Vec<read::Result<(ArchiveMember<'data>, read::File<'data, &'data [u8]>)>> {
+        use rayon::prelude::*;
+
+        let members: alloc::vec::Vec<_> = self
+            .members()
+            .map(|member| {
+                let member = member?;
+                let data = member.data(self.data)?;
+                Ok((member, data))
+            })
+            .collect();
+        members
+            .into_par_iter()
+            .map(|member| {
+                let (member, data) = member?;
+                let file = read::File::parse(data)?;
+                Ok((member, file))
+            })
+            .collect()
+    }
+}
+
 /// An iterator over the members of an archive.
 #[derive(Debug)]
 pub struct ArchiveMemberIterator<'data, R: ReadRef<'data> = &'data [u8]> {
@@ -591,6 +637,24 @@ impl<'data> ArchiveMember<'data> {
         data.read_bytes_at(self.offset, self.size)
             .read_error("Archive member size is too large")
     }
+
+    /// Return a [`ReadRef`] for the file data in an archive read using a [`ReadCache`].
+    ///
+    /// Unlike [`Self::data`], this does not read the file data. The returned
+    /// range can be used to parse the member, and only the parts that are
+    /// needed will be read from the stream.
+    ///
+    /// This is an empty range for thin members.
+    #[inline]
+    pub fn cache_range<'a, R: ReadCacheOps>(
+        &self,
+        cache: &'a ReadCache<R>,
+    ) -> ReadCacheRange<'a, R> {
+        if self.is_thin() {
+            return cache.range(0, 0);
+        }
+        cache.range(self.offset, self.size)
+    }
 }
 
 /// An offset of a member in an archive.
@@ -1130,4 +1194,82 @@ mod tests {
 
         assert!(members.next().is_none());
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn read_cache() {
+        use std::cell::Cell;
+        use std::io::{Cursor, Read, Seek, SeekFrom};
+        use std::rc::Rc;
+        use std::vec::Vec;
+
+        /// A reader that counts the number of bytes read.
+        struct CountingReader {
+            inner: Cursor<Vec<u8>>,
+            count: Rc<Cell<usize>>,
+        }
+
+        impl Read for CountingReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let len = Read::read(&mut self.inner, buf)?;
+                self.count.set(self.count.get() + len);
+                Ok(len)
+            }
+        }
+
+        impl Seek for CountingReader {
+            fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+                Seek::seek(&mut self.inner, pos)
+            }
+        }
+
+        let big_size = 1 << 20;
+        let mut data = b"\
+            !<arch>\n\
+            /                                               12        `\n\
+            \0\0\0\x01\0\0\0\x50foo\0\
+            big.o/          0           0     0     644     1048576   `\n"
+            .to_vec();
+        data.resize(data.len() + big_size, 0xcc);
+        data.extend_from_slice(
+            b"small/          0           0     0     644     4         `\nabcd",
+        );
+
+        let count = Rc::new(Cell::new(0));
+        let cache = ReadCache::new(CountingReader {
+            inner: Cursor::new(data),
+            count: count.clone(),
+        });
+        let archive = ArchiveFile::parse(&cache).unwrap();
+        assert_eq!(archive.kind(), ArchiveKind::Gnu);
+
+        let symbols = archive.symbols().unwrap().unwrap();
+        let symbols = symbols.collect::<read::Result<Vec<_>>>().unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name(), b"foo");
+        let member = archive.member(symbols[0].offset()).unwrap();
+        assert_eq!(member.name(), b"big.o");
+
+        let mut members = archive.members();
+        let big = members.next().unwrap().unwrap();
+        assert_eq!(big.name(), b"big.o");
+        assert_eq!(big.size(), big_size as u64);
+        let small = members.next().unwrap().unwrap();
+        assert_eq!(small.name(), b"small");
+        assert!(members.next().is_none());
+
+        let range = big.cache_range(&cache);
+        assert_eq!(range.len(), Ok(big_size as u64));
+        assert_eq!(
+            range.read_bytes_at(big_size as u64 - 4, 4),
+            Ok(&[0xcc; 4][..])
+        );
+        assert_eq!(
+            small.cache_range(&cache).read_bytes_at(0, 4),
+            Ok(&b"abcd"[..])
+        );
+
+        // Only the headers, symbol table, and requested data were read.
+        assert!(count.get() < 1024, "read {} bytes", count.get());
+    }
 }
@@ -0,0 +1,699 @@
+use core::mem;
+
+use crate::endian::{LittleEndian as LE, U32Bytes};
+use crate::pe;
+use crate::read::util::{self, StringTable};
+use crate::read::{self, Bytes, Error, ReadError};
+
+/// Check and skip the signature at the start of a CodeView section.
+fn parse_signature<'data>(data: &'data [u8], what: &'static str) -> read::Result<Bytes<'data>> {
+    let mut data = Bytes(data);
+    let signature = data.read::<U32Bytes<LE>>().read_error(what)?.get(LE);
+    if signature != pe::CV_SIGNATURE_C13 {
+        return Err(Error(what));
+    }
+    Ok(data)
+}
+
+/// The CodeView symbol information in a `.debug$S` section of a COFF file.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewSymbolSection<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewSymbolSection<'data> {
+    /// Parse the data of a `.debug$S` section.
+    ///
+    /// Returns an error if the signature is invalid.
+    pub fn parse(data: &'data [u8]) -> read::Result<Self> {
+        let data = parse_signature(data, "Invalid CodeView .debug$S signature")?;
+        Ok(CodeViewSymbolSection { data })
+    }
+
+    /// Iterate over the subsections.
+    pub fn subsections(&self) -> CodeViewSubsectionIterator<'data> {
+        CodeViewSubsectionIterator { data: self.data }
+    }
+
+    /// Find the first subsection with the given type.
+    ///
+    /// `typ` is one of the `DEBUG_S_*` constants.
+    pub fn subsection(&self, typ: u32) -> read::Result<Option<CodeViewSubsection<'data>>> {
+        let mut subsections = self.subsections();
+        while let Some(subsection) = subsections.next()? {
+            if subsection.typ() == typ {
+                return Ok(Some(subsection));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the string table subsection.
+    ///
+    /// Returns an empty string table if there is no `DEBUG_S_STRINGTABLE` subsection.
+    pub fn strings(&self) -> read::Result<StringTable<'data>> {
+        let data = match self.subsection(pe::DEBUG_S_STRINGTABLE)? {
+            Some(subsection) => subsection.data(),
+            None => &[],
+        };
+        Ok(StringTable::new(data, 0, data.len() as u64))
+    }
+
+    /// Return the file checksums subsection.
+    ///
+    /// Returns an empty table if there is no `DEBUG_S_FILECHKSMS` subsection.
+    pub fn file_checksums(&self) -> read::Result<CodeViewFileChecksums<'data>> {
+        let data = match self.subsection(pe::DEBUG_S_FILECHKSMS)? {
+            Some(subsection) => subsection.data(),
+            None => &[],
+        };
+        Ok(CodeViewFileChecksums { data: Bytes(data) })
+    }
+}
+
+/// An iterator over the subsections in a `.debug$S` section.
+///
+/// Returned by [`CodeViewSymbolSection::subsections`].
+#[derive(Debug, Clone)]
+pub struct CodeViewSubsectionIterator<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewSubsectionIterator<'data> {
+    /// Return the next subsection.
+    ///
+    /// Subsections with the `DEBUG_S_IGNORE` flag are skipped.
+    pub fn next(&mut self) -> read::Result<Option<CodeViewSubsection<'data>>> {
+        loop {
+            if self.data.is_empty() {
+                return Ok(None);
+            }
+            let result = self.parse();
+            if result.is_err() {
+                self.data = Bytes(&[]);
+            }
+            let subsection = result?;
+            if subsection.typ & pe::DEBUG_S_IGNORE == 0 {
+                return Ok(Some(subsection));
+            }
+        }
+    }
+
+    fn parse(&mut self) -> read::Result<CodeViewSubsection<'data>> {
+        let header = self
+            .data
+            .read::<pe::CvDebugSubsectionHeader>()
+            .read_error("Invalid CodeView subsection header")?;
+        let length = header.length.get(LE) as usize;
+        let data = self
+            .data
+            .read_bytes(length)
+            .read_error("Invalid CodeView subsection length")?
+            .0;
+        // The next subsection (if any) must be aligned.
+        let padding = util::align(length, 4) - length;
+        if self.data.skip(padding).is_err() {
+            self.data = Bytes(&[]);
+        }
+        Ok(CodeViewSubsection {
+            typ: header.typ.get(LE),
+            data,
+        })
+    }
+}
+
+impl<'data> Iterator for CodeViewSubsectionIterator<'data> {
+    type Item = read::Result<CodeViewSubsection<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// A subsection in a `.debug$S` section.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewSubsection<'data> {
+    typ: u32,
+    data: &'data [u8],
+}
+
+impl<'data> CodeViewSubsection<'data> {
+    /// The type of the subsection.
+    ///
+    /// This is one of the `DEBUG_S_*` constants.
+    #[inline]
+    pub fn typ(&self) -> u32 {
+        self.typ
+    }
+
+    /// The data of the subsection, excluding the header and padding.
+    #[inline]
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Iterate over the symbol records in a `DEBUG_S_SYMBOLS` subsection.
+    ///
+    /// Returns an error if this is a different type of subsection.
+    pub fn symbols(&self) -> read::Result<CodeViewRecordIterator<'data>> {
+        if self.typ != pe::DEBUG_S_SYMBOLS {
+            return Err(Error("CodeView subsection is not DEBUG_S_SYMBOLS"));
+        }
+        Ok(CodeViewRecordIterator {
+            data: Bytes(self.data),
+        })
+    }
+
+    /// Parse a `DEBUG_S_LINES` subsection.
+    ///
+    /// Returns an error if this is a different type of subsection.
+    pub fn lines(&self) -> read::Result<CodeViewLines<'data>> {
+        if self.typ != pe::DEBUG_S_LINES {
+            return Err(Error("CodeView subsection is not DEBUG_S_LINES"));
+        }
+        let mut data = Bytes(self.data);
+        let header = data
+            .read::<pe::CvDebugLinesHeader>()
+            .read_error("Invalid CodeView lines header")?;
+        Ok(CodeViewLines { header, data })
+    }
+}
+
+/// The CodeView type information in a `.debug$T` section of a COFF file.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewTypeSection<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewTypeSection<'data> {
+    /// Parse the data of a `.debug$T` section.
+    ///
+    /// Returns an error if the signature is invalid.
+    pub fn parse(data: &'data [u8]) -> read::Result<Self> {
+        let data = parse_signature(data, "Invalid CodeView .debug$T signature")?;
+        Ok(CodeViewTypeSection { data })
+    }
+
+    /// Iterate over the type records.
+    ///
+    /// The type index of the first record is 0x1000.
+    pub fn types(&self) -> CodeViewRecordIterator<'data> {
+        CodeViewRecordIterator { data: self.data }
+    }
+}
+
+/// An iterator over CodeView symbol records or type records.
+///
+/// Returned by [`CodeViewSubsection::symbols`] and [`CodeViewTypeSection::types`].
+#[derive(Debug, Clone)]
+pub struct CodeViewRecordIterator<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewRecordIterator<'data> {
+    /// Return the next record.
+    pub fn next(&mut self) -> read::Result<Option<CodeViewRecord<'data>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> read::Result<CodeViewRecord<'data>> {
+        let header = self
+            .data
+            .read::<pe::CvRecordHeader>()
+            .read_error("Invalid CodeView record header")?;
+        // The length includes the kind field.
+        let length = (header.length.get(LE) as usize)
+            .checked_sub(mem::size_of::<u16>())
+            .read_error("Invalid CodeView record length")?;
+        let data = self
+            .data
+            .read_bytes(length)
+            .read_error("Invalid CodeView record length")?
+            .0;
+        Ok(CodeViewRecord {
+            kind: header.kind.get(LE),
+            data,
+        })
+    }
+}
+
+impl<'data> Iterator for CodeViewRecordIterator<'data> {
+    type Item = read::Result<CodeViewRecord<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// A CodeView symbol record or type record.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewRecord<'data> {
+    kind: u16,
+    data: &'data [u8],
+}
+
+impl<'data> CodeViewRecord<'data> {
+    /// The kind of the record.
+    ///
+    /// This is one of the `S_*` constants for symbol records,
+    /// or one of the `LF_*` values for type records.
+    #[inline]
+    pub fn kind(&self) -> u16 {
+        self.kind
+    }
+
+    /// The data of the record, excluding the header.
+    #[inline]
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Parse a procedure symbol record.
+    ///
+    /// Returns the record and the procedure name.
+    /// Returns `Ok(None)` if the record is not a `S_LPROC32`, `S_GPROC32`,
+    /// `S_LPROC32_ID` or `S_GPROC32_ID` record.
+    pub fn proc(&self) -> read::Result<Option<(&'data pe::CvProcSym32, &'data [u8])>> {
+        match self.kind {
+            pe::S_LPROC32 | pe::S_GPROC32 | pe::S_LPROC32_ID | pe::S_GPROC32_ID => {}
+            _ => return Ok(None),
+        }
+        let mut data = Bytes(self.data);
+        let proc = data
+            .read::<pe::CvProcSym32>()
+            .read_error("Invalid CodeView procedure record")?;
+        let name = data
+            .read_string()
+            .read_error("Invalid CodeView procedure name")?;
+        Ok(Some((proc, name)))
+    }
+}
+
+/// A parsed `DEBUG_S_LINES` subsection.
+///
+/// Returned by [`CodeViewSubsection::lines`].
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewLines<'data> {
+    header: &'data pe::CvDebugLinesHeader,
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewLines<'data> {
+    /// The header of the subsection.
+    ///
+    /// The section offset and section number are usually set by relocations.
+    #[inline]
+    pub fn header(&self) -> &'data pe::CvDebugLinesHeader {
+        self.header
+    }
+
+    /// Return true if the line entries have column information.
+    #[inline]
+    pub fn has_columns(&self) -> bool {
+        self.header.flags.get(LE) & pe::CV_LINES_HAVE_COLUMNS != 0
+    }
+
+    /// Iterate over the file blocks.
+    pub fn blocks(&self) -> CodeViewLineBlockIterator<'data> {
+        CodeViewLineBlockIterator {
+            has_columns: self.has_columns(),
+            data: self.data,
+        }
+    }
+}
+
+/// An iterator over the file blocks in a `DEBUG_S_LINES` subsection.
+///
+/// Returned by [`CodeViewLines::blocks`].
+#[derive(Debug, Clone)]
+pub struct CodeViewLineBlockIterator<'data> {
+    has_columns: bool,
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewLineBlockIterator<'data> {
+    /// Return the next file block.
+    pub fn next(&mut self) -> read::Result<Option<CodeViewLineBlock<'data>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> read::Result<CodeViewLineBlock<'data>> {
+        let mut data = self.data;
+        let header = data
+            .read::<pe::CvDebugLinesFileBlockHeader>()
+            .read_error("Invalid CodeView line block header")?;
+        let count = header.num_lines.get(LE) as usize;
+        let lines = data
+            .read_slice::<pe::CvLine>(count)
+            .read_error("Invalid CodeView line block count")?;
+        let columns = if self.has_columns {
+            data.read_slice::<pe::CvColumn>(count)
+                .read_error("Invalid CodeView line block count")?
+        } else {
+            &[]
+        };
+        self.data
+            .skip(header.cb_block.get(LE) as usize)
+            .read_error("Invalid CodeView line block size")?;
+        Ok(CodeViewLineBlock {
+            header,
+            lines,
+            columns,
+        })
+    }
+}
+
+impl<'data> Iterator for CodeViewLineBlockIterator<'data> {
+    type Item = read::Result<CodeViewLineBlock<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// A file block in a `DEBUG_S_LINES` subsection.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewLineBlock<'data> {
+    header: &'data pe::CvDebugLinesFileBlockHeader,
+    lines: &'data [pe::CvLine],
+    columns: &'data [pe::CvColumn],
+}
+
+impl<'data> CodeViewLineBlock<'data> {
+    /// The offset of the file's entry in the file checksums subsection.
+    ///
+    /// Use [`CodeViewFileChecksums::get`] to find the entry.
+    #[inline]
+    pub fn file_offset(&self) -> u32 {
+        self.header.off_file.get(LE)
+    }
+
+    /// The line entries.
+    #[inline]
+    pub fn lines(&self) -> &'data [pe::CvLine] {
+        self.lines
+    }
+
+    /// The column entries.
+    ///
+    /// This is empty if the subsection does not have column information.
+    #[inline]
+    pub fn columns(&self) -> &'data [pe::CvColumn] {
+        self.columns
+    }
+}
+
+impl pe::CvLine {
+    /// The offset of the code relative to the `off_con` field of the lines header.
+    #[inline]
+    pub fn offset(&self) -> u32 {
+        self.offset.get(LE)
+    }
+
+    /// The start line number.
+    #[inline]
+    pub fn line_start(&self) -> u32 {
+        self.flags.get(LE) & pe::CV_LINE_NUMBER_START_MASK
+    }
+
+    /// The end line number.
+    #[inline]
+    pub fn line_end(&self) -> u32 {
+        let delta =
+            (self.flags.get(LE) >> pe::CV_LINE_DELTA_END_SHIFT) & pe::CV_LINE_DELTA_END_MASK;
+        self.line_start() + delta
+    }
+
+    /// Return true if the line is a statement.
+    #[inline]
+    pub fn is_statement(&self) -> bool {
+        self.flags.get(LE) & pe::CV_LINE_STATEMENT != 0
+    }
+}
+
+/// A parsed `DEBUG_S_FILECHKSMS` subsection.
+///
+/// Returned by [`CodeViewSymbolSection::file_checksums`].
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewFileChecksums<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewFileChecksums<'data> {
+    /// Parse the entry at the given offset in the subsection.
+    ///
+    /// The offset is usually from [`CodeViewLineBlock::file_offset`].
+    pub fn get(&self, offset: u32) -> read::Result<CodeViewFileChecksum<'data>> {
+        let mut data = self.data;
+        data.skip(offset as usize)
+            .read_error("Invalid CodeView file checksum offset")?;
+        CodeViewFileChecksumIterator { data }.parse()
+    }
+
+    /// Iterate over the entries.
+    pub fn iter(&self) -> CodeViewFileChecksumIterator<'data> {
+        CodeViewFileChecksumIterator { data: self.data }
+    }
+}
+
+/// An iterator over the entries in a `DEBUG_S_FILECHKSMS` subsection.
+///
+/// Returned by [`CodeViewFileChecksums::iter`].
+#[derive(Debug, Clone)]
+pub struct CodeViewFileChecksumIterator<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> CodeViewFileChecksumIterator<'data> {
+    /// Return the next entry.
+    pub fn next(&mut self) -> read::Result<Option<CodeViewFileChecksum<'data>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> read::Result<CodeViewFileChecksum<'data>> {
+        let header = self
+            .data
+            .read::<pe::CvFileChecksumHeader>()
+            .read_error("Invalid CodeView file checksum header")?;
+        let size = header.checksum_size as usize;
+        let checksum = self
+            .data
+            .read_bytes(size)
+            .read_error("Invalid CodeView file checksum size")?
+            .0;
+        // The next entry (if any) must be aligned.
+        let length = mem::size_of::<pe::CvFileChecksumHeader>() + size;
+        if self.data.skip(util::align(length, 4) - length).is_err() {
+            self.data = Bytes(&[]);
+        }
+        Ok(CodeViewFileChecksum { header, checksum })
+    }
+}
+
+impl<'data> Iterator for CodeViewFileChecksumIterator<'data> {
+    type Item = read::Result<CodeViewFileChecksum<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// An entry in a `DEBUG_S_FILECHKSMS` subsection.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeViewFileChecksum<'data> {
+    header: &'data pe::CvFileChecksumHeader,
+    checksum: &'data [u8],
+}
+
+impl<'data> CodeViewFileChecksum<'data> {
+    /// The offset of the file name in the string table subsection.
+    #[inline]
+    pub fn file_name_offset(&self) -> u32 {
+        self.header.off_file_name.get(LE)
+    }
+
+    /// Return the file name from the string table subsection.
+    ///
+    /// The string table is from [`CodeViewSymbolSection::strings`].
+    pub fn file_name(&self, strings: StringTable<'data>) -> read::Result<&'data [u8]> {
+        strings
+            .get(self.file_name_offset())
+            .read_error("Invalid CodeView file name offset")
+    }
+
+    /// The type of the checksum.
+    ///
+    /// This is one of the `CHKSUM_TYPE_*` constants.
+    #[inline]
+    pub fn checksum_type(&self) -> u8 {
+        self.header.checksum_type
+    }
+
+    /// The checksum bytes.
+    #[inline]
+    pub fn checksum(&self) -> &'data [u8] {
+        self.checksum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn subsection(data: &mut Vec<u8>, typ: u32, contents: &[u8]) {
+        data.extend_from_slice(&typ.to_le_bytes());
+        data.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+        data.extend_from_slice(contents);
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+    }
+
+    #[test]
+    fn debug_s() {
+        let mut symbols = Vec::new();
+        // S_GPROC32 "main" with length 0x20.
+        let mut proc = Vec::new();
+        proc.extend_from_slice(&pe::S_GPROC32.to_le_bytes());
+        proc.extend_from_slice(&[0; 12]);
+        proc.extend_from_slice(&0x20u32.to_le_bytes());
+        proc.extend_from_slice(&[0; 19]);
+        proc.extend_from_slice(b"main\0");
+        symbols.extend_from_slice(&(proc.len() as u16).to_le_bytes());
+        symbols.extend_from_slice(&proc);
+        symbols.extend_from_slice(&2u16.to_le_bytes());
+        symbols.extend_from_slice(&pe::S_PROC_ID_END.to_le_bytes());
+
+        let mut lines = Vec::new();
+        lines.extend_from_slice(&[0; 8]);
+        lines.extend_from_slice(&0x20u32.to_le_bytes());
+        // File block at checksum offset 0, with 2 lines.
+        lines.extend_from_slice(&0u32.to_le_bytes());
+        lines.extend_from_slice(&2u32.to_le_bytes());
+        lines.extend_from_slice(&(12u32 + 2 * 8).to_le_bytes());
+        lines.extend_from_slice(&0u32.to_le_bytes());
+        lines.extend_from_slice(&(pe::CV_LINE_STATEMENT | (1 << 24) | 3).to_le_bytes());
+        lines.extend_from_slice(&0x10u32.to_le_bytes());
+        lines.extend_from_slice(&5u32.to_le_bytes());
+
+        let mut checksums = Vec::new();
+        checksums.extend_from_slice(&1u32.to_le_bytes());
+        checksums.push(2);
+        checksums.push(pe::CHKSUM_TYPE_MD5);
+        checksums.extend_from_slice(&[0xaa, 0xbb]);
+
+        let mut data = pe::CV_SIGNATURE_C13.to_le_bytes().to_vec();
+        subsection(&mut data, pe::DEBUG_S_SYMBOLS, &symbols);
+        subsection(
+            &mut data,
+            pe::DEBUG_S_IGNORE | pe::DEBUG_S_LINES,
+            &[1, 2, 3],
+        );
+        subsection(&mut data, pe::DEBUG_S_LINES, &lines);
+        subsection(&mut data, pe::DEBUG_S_STRINGTABLE, b"\0main.c\0");
+        subsection(&mut data, pe::DEBUG_S_FILECHKSMS, &checksums);
+
+        let section = CodeViewSymbolSection::parse(&data).unwrap();
+        let types = section
+            .subsections()
+            .map(|subsection| subsection.unwrap().typ())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                pe::DEBUG_S_SYMBOLS,
+                pe::DEBUG_S_LINES,
+                pe::DEBUG_S_STRINGTABLE,
+                pe::DEBUG_S_FILECHKSMS
+            ]
+        );
+
+        let mut symbols = section
+            .subsection(pe::DEBUG_S_SYMBOLS)
+            .unwrap()
+            .unwrap()
+            .symbols()
+            .unwrap();
+        let record = symbols.next().unwrap().unwrap();
+        let (proc, name) = record.proc().unwrap().unwrap();
+        assert_eq!(proc.len.get(LE), 0x20);
+        assert_eq!(name, b"main");
+        let record = symbols.next().unwrap().unwrap();
+        assert_eq!(record.kind(), pe::S_PROC_ID_END);
+        assert!(record.proc().unwrap().is_none());
+        assert!(symbols.next().unwrap().is_none());
+
+        let lines = section
+            .subsection(pe::DEBUG_S_LINES)
+            .unwrap()
+            .unwrap()
+            .lines()
+            .unwrap();
+        assert_eq!(lines.header().cb_con.get(LE), 0x20);
+        assert!(!lines.has_columns());
+        let mut blocks = lines.blocks();
+        let block = blocks.next().unwrap().unwrap();
+        assert!(blocks.next().unwrap().is_none());
+        let lines = block.lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            (
+                lines[0].offset(),
+                lines[0].line_start(),
+                lines[0].line_end()
+            ),
+            (0, 3, 4)
+        );
+        assert!(lines[0].is_statement());
+        assert_eq!((lines[1].offset(), lines[1].line_start()), (0x10, 5));
+        assert!(!lines[1].is_statement());
+
+        let strings = section.strings().unwrap();
+        let file = section
+            .file_checksums()
+            .unwrap()
+            .get(block.file_offset())
+            .unwrap();
+        assert_eq!(file.file_name(strings), Ok(&b"main.c"[..]));
+        assert_eq!(file.checksum_type(), pe::CHKSUM_TYPE_MD5);
+        assert_eq!(file.checksum(), [0xaa, 0xbb]);
+        assert_eq!(section.file_checksums().unwrap().iter().count(), 1);
+    }
+
+    #[test]
+    fn debug_t() {
+        let mut data = pe::CV_SIGNATURE_C13.to_le_bytes().to_vec();
+        // LF_ARGLIST with no arguments.
+        data.extend_from_slice(&[6, 0, 0x01, 0x12, 0, 0, 0, 0]);
+        let section = CodeViewTypeSection::parse(&data).unwrap();
+        let mut types = section.types();
+        let record = types.next().unwrap().unwrap();
+        assert_eq!(record.kind(), 0x1201);
+        assert_eq!(record.data(), [0; 4]);
+        assert!(types.next().unwrap().is_none());
+
+        assert!(CodeViewTypeSection::parse(&[1, 0, 0, 0]).is_err());
+    }
+}
@@ -5,8 +5,9 @@ use crate::endian::LittleEndian as LE;
 use crate::pe;
 use crate::pod::Pod;
 use crate::read::{
-    self, Architecture, Export, FileFlags, Import, NoDynamicRelocationIterator, Object, ObjectKind,
-    ObjectSection, ReadError, ReadRef, Result, SectionIndex, SubArchitecture, SymbolIndex,
+    self, Architecture, Error, Export, FileFlags, Import, NoDynamicRelocationIterator, Object,
+    ObjectKind, ObjectSection, ParseOptions, ReadError, ReadRef, Result, SectionIndex,
+    SubArchitecture, SymbolIndex,
 };
 
 use super::{
@@ -43,15 +44,29 @@ pub struct CoffFile<'data, R: ReadRef<'data> = &'data [u8], Coff: CoffHeader = p
     pub(super) header: &'data Coff,
     pub(super) common: CoffCommon<'data, R, Coff>,
     pub(super) data: R,
+    pub(super) warnings: Vec<Error>,
 }
 
 impl<'data, R: ReadRef<'data>, Coff: CoffHeader> CoffFile<'data, R, Coff> {
     /// Parse the raw COFF file data.
     pub fn parse(data: R) -> Result<Self> {
+        Self::parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw COFF file data, using the given options.
+    ///
+    /// The `dynamic_symbols` and `relocations` options are ignored.
+    pub fn parse_with(data: R, options: &ParseOptions) -> Result<Self> {
+        options.verify_file(data)?;
         let mut offset = 0;
         let header = Coff::parse(data, &mut offset)?;
         let sections = header.sections(data, offset)?;
-        let symbols = header.symbols(data)?;
+        let mut warnings = Vec::new();
+        let symbols = if options.symbols {
+            options.recover(&mut warnings, header.symbols(data))?
+        } else {
+            SymbolTable::default()
+        };
 
         Ok(CoffFile {
             header,
@@ -61,6 +76,7 @@ impl<'data, R: ReadRef<'data>, Coff: CoffHeader> CoffFile<'data, R, Coff> {
                 image_base: 0,
             },
             data,
+            warnings,
         })
     }
 
@@ -78,6 +94,43 @@ impl<'data, R: ReadRef<'data>, Coff: CoffHeader> CoffFile<'data, R, Coff> {
     pub fn coff_symbol_table(&self) -> &SymbolTable<'data, R, Coff> {
         &self.common.symbols
     }
+
+    /// Returns the problems that were found while parsing the file.
+    ///
+    /// See [`Strictness::Permissive`](crate::read::Strictness::Permissive).
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    fn raw_section_by_name<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<CoffSection<'data, 'file, R, Coff>> {
+        self.sections()
+            .find(|section| section.name_bytes() == Ok(section_name))
+    }
+
+    #[cfg(feature = "compression")]
+    fn zdebug_section_by_name<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<CoffSection<'data, 'file, R, Coff>> {
+        if !section_name.starts_with(b".debug_") {
+            return None;
+        }
+        let mut name = Vec::with_capacity(section_name.len() + 1);
+        name.extend_from_slice(b".zdebug_");
+        name.extend_from_slice(&section_name[7..]);
+        self.raw_section_by_name(&name)
+    }
+
+    #[cfg(not(feature = "compression"))]
+    fn zdebug_section_by_name<'file>(
+        &'file self,
+        _section_name: &[u8],
+    ) -> Option<CoffSection<'data, 'file, R, Coff>> {
+        None
+    }
 }
 
 impl<'data, R: ReadRef<'data>, Coff: CoffHeader> read::private::Sealed
@@ -184,8 +237,8 @@ where
         &'file self,
         section_name: &[u8],
     ) -> Option<CoffSection<'data, 'file, R, Coff>> {
-        self.sections()
-            .find(|section| section.name_bytes() == Ok(section_name))
+        self.raw_section_by_name(section_name)
+            .or_else(|| self.zdebug_section_by_name(section_name))
     }
 
     fn section_by_index(&self, index: SectionIndex) -> Result<CoffSection<'data, '_, R, Coff>> {
@@ -270,6 +323,10 @@ where
             characteristics: self.header.characteristics(),
         }
     }
+
+    fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
 }
 
 /// Read the `class_id` field from a [`pe::AnonObjectHeader`].
@@ -13,6 +13,9 @@
 //! [`ImportFile`] allows reading COFF short imports that are used in import
 //! libraries. Currently these are not integrated with the unified read API.
 //!
+//! [`CodeViewSymbolSection`] and [`CodeViewTypeSection`] can be used to parse
+//! the CodeView debug information in `.debug$S` and `.debug$T` sections.
+//!
 //! ## Low level API
 //!
 //! The [`CoffHeader`] trait can be directly used to parse both COFF
@@ -64,3 +67,6 @@ pub use comdat::*;
 
 mod import;
 pub use import::*;
+
+mod codeview;
+pub use codeview::*;
@@ -5,8 +5,9 @@ use crate::endian::LittleEndian as LE;
 use crate::pe;
 use crate::read::util::StringTable;
 use crate::read::{
-    self, CompressedData, CompressedFileRange, Error, ObjectSection, ObjectSegment, ReadError,
-    ReadRef, RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+    self, gnu_compression, CompressedData, CompressedFileRange, Error, ObjectSection,
+    ObjectSegment, Permissions, ReadError, ReadRef, RelocationMap, Result, SectionFlags,
+    SectionIndex, SectionKind, SegmentFlags,
 };
 
 use super::{CoffFile, CoffHeader, CoffRelocationIterator};
@@ -242,6 +243,11 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> ObjectSegment<'data>
         let characteristics = self.section.characteristics.get(LE);
         SegmentFlags::Coff { characteristics }
     }
+
+    #[inline]
+    fn permissions(&self) -> Permissions {
+        self.section.permissions()
+    }
 }
 
 /// An iterator for the sections in a [`CoffBigFile`](super::CoffBigFile).
@@ -316,6 +322,21 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> CoffSection<'data, 'file
             .coff_data(self.file.data)
             .read_error("Invalid COFF section offset or size")
     }
+
+    // Try GNU-style "ZLIB" header decompression.
+    fn maybe_compressed_gnu(&self) -> Result<Option<CompressedFileRange>> {
+        if !self
+            .name()
+            .map_or(false, |name| name.starts_with(".zdebug_"))
+        {
+            return Ok(None);
+        }
+        let (section_offset, section_size) = self
+            .file_range()
+            .read_error("Invalid COFF GNU compressed section type")?;
+        gnu_compression::compressed_file_range(self.file.data, section_offset, section_size)
+            .map(Some)
+    }
 }
 
 impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> read::private::Sealed
@@ -368,14 +389,20 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> ObjectSection<'data>
         ))
     }
 
-    #[inline]
     fn compressed_file_range(&self) -> Result<CompressedFileRange> {
-        Ok(CompressedFileRange::none(self.file_range()))
+        Ok(if let Some(data) = self.maybe_compressed_gnu()? {
+            data
+        } else {
+            CompressedFileRange::none(self.file_range())
+        })
     }
 
-    #[inline]
     fn compressed_data(&self) -> Result<CompressedData<'data>> {
-        self.data().map(CompressedData::none)
+        if let Some(data) = self.maybe_compressed_gnu()? {
+            data.data(self.file.data)
+        } else {
+            self.data().map(CompressedData::none)
+        }
     }
 
     #[inline]
@@ -423,9 +450,23 @@ impl<'data, 'file, R: ReadRef<'data>, Coff: CoffHeader> ObjectSection<'data>
             characteristics: self.section.characteristics.get(LE),
         }
     }
+
+    #[inline]
+    fn permissions(&self) -> Permissions {
+        self.section.permissions()
+    }
 }
 
 impl pe::ImageSectionHeader {
+    pub(crate) fn permissions(&self) -> Permissions {
+        let characteristics = self.characteristics.get(LE);
+        Permissions::new(
+            characteristics & pe::IMAGE_SCN_MEM_READ != 0,
+            characteristics & pe::IMAGE_SCN_MEM_WRITE != 0,
+            characteristics & pe::IMAGE_SCN_MEM_EXECUTE != 0,
+        )
+    }
+
     pub(crate) fn kind(&self) -> SectionKind {
         let characteristics = self.characteristics.get(LE);
         if characteristics & (pe::IMAGE_SCN_CNT_CODE | pe::IMAGE_SCN_MEM_EXECUTE) != 0 {
@@ -0,0 +1,650 @@
+//! Object safe versions of the unified read API traits.
+//!
+//! The [`Object`], [`ObjectSection`] and [`ObjectComdat`] traits use
+//! associated types, so they can't be used as trait objects. The traits in this
+//! module are implemented for every type that implements the corresponding
+//! unified read trait, and return boxed iterators and trait objects instead.
+//! This allows a `Box<dyn DynObject<'data>>` to be stored in plugin systems and
+//! FFI layers.
+//!
+//! [`ObjectSegment`] and [`ObjectSymbol`] are already object safe, so they
+//! are used directly.
+//!
+//! These traits are not re-exported from the [`read`] module
+//! because their method names are the same as those of the unified read traits,
+//! so importing both would make method calls ambiguous.
+//!
+//! ## Example
+//! ```no_run
+//! use object::read::dynamic::DynObject;
+//! use std::error::Error;
+//! use std::fs;
+//!
+//! /// Reads a file and displays the name of each section.
+//! fn main() -> Result<(), Box<dyn Error>> {
+//! #   #[cfg(feature = "std")] {
+//!     let data = fs::read("path/to/binary")?;
+//!     let file = object::File::parse(&*data)?;
+//!     let file: Box<dyn DynObject> = Box::new(file);
+//!     for section in file.sections() {
+//!         println!("{}", section.name()?);
+//!     }
+//! #   }
+//!     Ok(())
+//! }
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::endian::Endianness;
+use crate::read::{
+    self, Architecture, CodeView, ComdatKind, CompressedData, CompressedFileRange, DebugFile,
+    Error, Export, FileFlags, GoBuildInfo, Import, Object, ObjectComdat, ObjectKind, ObjectMap,
+    ObjectSection, ObjectSegment, ObjectSymbol, Permissions, Relocation, RelocationMap, Result,
+    RustMetadata, SectionFlags, SectionIndex, SectionKind, SourceFile, SubArchitecture,
+    SymbolIndex, SymbolMap, SymbolMapName,
+};
+
+/// A boxed iterator.
+pub type DynIterator<'a, T> = Box<dyn Iterator<Item = T> + 'a>;
+
+/// An object safe version of [`Object`].
+///
+/// This is implemented for all types that implement [`Object`].
+pub trait DynObject<'data>: read::private::Sealed {
+    /// See [`Object::architecture`].
+    fn architecture(&self) -> Architecture;
+
+    /// See [`Object::sub_architecture`].
+    fn sub_architecture(&self) -> Option<SubArchitecture>;
+
+    /// See [`Object::endianness`].
+    fn endianness(&self) -> Endianness;
+
+    /// See [`Object::is_little_endian`].
+    fn is_little_endian(&self) -> bool;
+
+    /// See [`Object::is_64`].
+    fn is_64(&self) -> bool;
+
+    /// See [`Object::kind`].
+    fn kind(&self) -> ObjectKind;
+
+    /// See [`Object::segments`].
+    fn segments<'file>(&'file self) -> DynIterator<'file, Box<dyn ObjectSegment<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::section_by_name`].
+    fn section_by_name<'file>(
+        &'file self,
+        section_name: &str,
+    ) -> Option<Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::section_by_name_bytes`].
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::section_by_index`].
+    fn section_by_index<'file>(
+        &'file self,
+        index: SectionIndex,
+    ) -> Result<Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::sections`].
+    fn sections<'file>(&'file self) -> DynIterator<'file, Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::comdats`].
+    fn comdats<'file>(&'file self) -> DynIterator<'file, Box<dyn DynComdat<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::symbol_by_index`].
+    fn symbol_by_index<'file>(
+        &'file self,
+        index: SymbolIndex,
+    ) -> Result<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::symbols`].
+    fn symbols<'file>(&'file self) -> DynIterator<'file, Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::symbol_by_name`].
+    fn symbol_by_name<'file>(
+        &'file self,
+        symbol_name: &str,
+    ) -> Option<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::symbol_by_name_bytes`].
+    fn symbol_by_name_bytes<'file>(
+        &'file self,
+        symbol_name: &[u8],
+    ) -> Option<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::dynamic_symbols`].
+    fn dynamic_symbols<'file>(
+        &'file self,
+    ) -> DynIterator<'file, Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::all_symbols`].
+    fn all_symbols<'file>(&'file self) -> Vec<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file;
+
+    /// See [`Object::dynamic_relocations`].
+    fn dynamic_relocations<'file>(&'file self) -> Option<DynIterator<'file, (u64, Relocation)>>
+    where
+        'data: 'file;
+
+    /// See [`Object::symbol_map`].
+    fn symbol_map(&self) -> SymbolMap<SymbolMapName<'data>>;
+
+    /// See [`Object::object_map`].
+    fn object_map(&self) -> ObjectMap<'data>;
+
+    /// See [`Object::source_files`].
+    fn source_files(&self) -> Result<Vec<SourceFile<'data>>>;
+
+    /// See [`Object::imports`].
+    fn imports(&self) -> Result<Vec<Import<'data>>>;
+
+    /// See [`Object::exports`].
+    fn exports(&self) -> Result<Vec<Export<'data>>>;
+
+    /// See [`Object::has_debug_symbols`].
+    fn has_debug_symbols(&self) -> bool;
+
+    /// See [`Object::mach_uuid`].
+    fn mach_uuid(&self) -> Result<Option<[u8; 16]>>;
+
+    /// See [`Object::build_id`].
+    fn build_id(&self) -> Result<Option<&'data [u8]>>;
+
+    /// See [`Object::gnu_debuglink`].
+    fn gnu_debuglink(&self) -> Result<Option<(&'data [u8], u32)>>;
+
+    /// See [`Object::gnu_debugaltlink`].
+    fn gnu_debugaltlink(&self) -> Result<Option<(&'data [u8], &'data [u8])>>;
+
+    /// See [`Object::pdb_info`].
+    fn pdb_info(&self) -> Result<Option<CodeView<'_>>>;
+
+    /// See [`Object::go_build_id`].
+    fn go_build_id(&self) -> Result<Option<&'data [u8]>>;
+
+    /// See [`Object::go_build_info`].
+    fn go_build_info(&self) -> Result<Option<GoBuildInfo<'data>>>;
+
+    /// See [`Object::rust_metadata`].
+    fn rust_metadata(&self) -> Result<Option<RustMetadata<'data>>>;
+
+    /// See [`Object::llvm_bitcode`].
+    fn llvm_bitcode(&self) -> Result<Option<&'data [u8]>>;
+
+    /// See [`Object::debug_files`].
+    fn debug_files<'file>(&'file self) -> Result<Vec<DebugFile<'file>>>
+    where
+        'data: 'file;
+
+    /// See [`Object::relative_address_base`].
+    fn relative_address_base(&self) -> u64;
+
+    /// See [`Object::entry`].
+    fn entry(&self) -> u64;
+
+    /// See [`Object::flags`].
+    fn flags(&self) -> FileFlags;
+
+    /// See [`Object::warnings`].
+    fn warnings(&self) -> &[Error];
+}
+
+impl<'data, T: Object<'data>> DynObject<'data> for T {
+    fn architecture(&self) -> Architecture {
+        Object::architecture(self)
+    }
+
+    fn sub_architecture(&self) -> Option<SubArchitecture> {
+        Object::sub_architecture(self)
+    }
+
+    fn endianness(&self) -> Endianness {
+        Object::endianness(self)
+    }
+
+    fn is_little_endian(&self) -> bool {
+        Object::is_little_endian(self)
+    }
+
+    fn is_64(&self) -> bool {
+        Object::is_64(self)
+    }
+
+    fn kind(&self) -> ObjectKind {
+        Object::kind(self)
+    }
+
+    fn segments<'file>(&'file self) -> DynIterator<'file, Box<dyn ObjectSegment<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Box::new(Object::segments(self).map(|segment| Box::new(segment) as _))
+    }
+
+    fn section_by_name<'file>(
+        &'file self,
+        section_name: &str,
+    ) -> Option<Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Object::section_by_name(self, section_name).map(|section| Box::new(section) as _)
+    }
+
+    fn section_by_name_bytes<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Object::section_by_name_bytes(self, section_name).map(|section| Box::new(section) as _)
+    }
+
+    fn section_by_index<'file>(
+        &'file self,
+        index: SectionIndex,
+    ) -> Result<Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Object::section_by_index(self, index).map(|section| Box::new(section) as _)
+    }
+
+    fn sections<'file>(&'file self) -> DynIterator<'file, Box<dyn DynSection<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Box::new(Object::sections(self).map(|section| Box::new(section) as _))
+    }
+
+    fn comdats<'file>(&'file self) -> DynIterator<'file, Box<dyn DynComdat<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Box::new(Object::comdats(self).map(|comdat| Box::new(comdat) as _))
+    }
+
+    fn symbol_by_index<'file>(
+        &'file self,
+        index: SymbolIndex,
+    ) -> Result<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Object::symbol_by_index(self, index).map(|symbol| Box::new(symbol) as _)
+    }
+
+    fn symbols<'file>(&'file self) -> DynIterator<'file, Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Box::new(Object::symbols(self).map(|symbol| Box::new(symbol) as _))
+    }
+
+    fn symbol_by_name<'file>(
+        &'file self,
+        symbol_name: &str,
+    ) -> Option<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Object::symbol_by_name(self, symbol_name).map(|symbol| Box::new(symbol) as _)
+    }
+
+    fn symbol_by_name_bytes<'file>(
+        &'file self,
+        symbol_name: &[u8],
+    ) -> Option<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Object::symbol_by_name_bytes(self, symbol_name).map(|symbol| Box::new(symbol) as _)
+    }
+
+    fn dynamic_symbols<'file>(
+        &'file self,
+    ) -> DynIterator<'file, Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Box::new(Object::dynamic_symbols(self).map(|symbol| Box::new(symbol) as _))
+    }
+
+    fn all_symbols<'file>(&'file self) -> Vec<Box<dyn ObjectSymbol<'data> + 'file>>
+    where
+        'data: 'file,
+    {
+        Object::all_symbols(self)
+            .into_iter()
+            .map(|symbol| Box::new(symbol) as _)
+            .collect()
+    }
+
+    fn dynamic_relocations<'file>(&'file self) -> Option<DynIterator<'file, (u64, Relocation)>>
+    where
+        'data: 'file,
+    {
+        Object::dynamic_relocations(self).map(|relocations| Box::new(relocations) as _)
+    }
+
+    fn symbol_map(&self) -> SymbolMap<SymbolMapName<'data>> {
+        Object::symbol_map(self)
+    }
+
+    fn object_map(&self) -> ObjectMap<'data> {
+        Object::object_map(self)
+    }
+
+    fn source_files(&self) -> Result<Vec<SourceFile<'data>>> {
+        Object::source_files(self)
+    }
+
+    fn imports(&self) -> Result<Vec<Import<'data>>> {
+        Object::imports(self)
+    }
+
+    fn exports(&self) -> Result<Vec<Export<'data>>> {
+        Object::exports(self)
+    }
+
+    fn has_debug_symbols(&self) -> bool {
+        Object::has_debug_symbols(self)
+    }
+
+    fn mach_uuid(&self) -> Result<Option<[u8; 16]>> {
+        Object::mach_uuid(self)
+    }
+
+    fn build_id(&self) -> Result<Option<&'data [u8]>> {
+        Object::build_id(self)
+    }
+
+    fn gnu_debuglink(&self) -> Result<Option<(&'data [u8], u32)>> {
+        Object::gnu_debuglink(self)
+    }
+
+    fn gnu_debugaltlink(&self) -> Result<Option<(&'data [u8], &'data [u8])>> {
+        Object::gnu_debugaltlink(self)
+    }
+
+    fn pdb_info(&self) -> Result<Option<CodeView<'_>>> {
+        Object::pdb_info(self)
+    }
+
+    fn go_build_id(&self) -> Result<Option<&'data [u8]>> {
+        Object::go_build_id(self)
+    }
+
+    fn go_build_info(&self) -> Result<Option<GoBuildInfo<'data>>> {
+        Object::go_build_info(self)
+    }
+
+    fn rust_metadata(&self) -> Result<Option<RustMetadata<'data>>> {
+        Object::rust_metadata(self)
+    }
+
+    fn llvm_bitcode(&self) -> Result<Option<&'data [u8]>> {
+        Object::llvm_bitcode(self)
+    }
+
+    fn debug_files<'file>(&'file self) -> Result<Vec<DebugFile<'file>>>
+    where
+        'data: 'file,
+    {
+        Object::debug_files(self)
+    }
+
+    fn relative_address_base(&self) -> u64 {
+        Object::relative_address_base(self)
+    }
+
+    fn entry(&self) -> u64 {
+        Object::entry(self)
+    }
+
+    fn flags(&self) -> FileFlags {
+        Object::flags(self)
+    }
+
+    fn warnings(&self) -> &[Error] {
+        Object::warnings(self)
+    }
+}
+
+/// An object safe version of [`ObjectSection`].
+///
+/// This is implemented for all types that implement [`ObjectSection`].
+pub trait DynSection<'data>: read::private::Sealed {
+    /// See [`ObjectSection::index`].
+    fn index(&self) -> SectionIndex;
+
+    /// See [`ObjectSection::address`].
+    fn address(&self) -> u64;
+
+    /// See [`ObjectSection::size`].
+    fn size(&self) -> u64;
+
+    /// See [`ObjectSection::align`].
+    fn align(&self) -> u64;
+
+    /// See [`ObjectSection::file_range`].
+    fn file_range(&self) -> Option<(u64, u64)>;
+
+    /// See [`ObjectSection::data`].
+    fn data(&self) -> Result<&'data [u8]>;
+
+    /// See [`ObjectSection::data_range`].
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>>;
+
+    /// See [`ObjectSection::compressed_file_range`].
+    fn compressed_file_range(&self) -> Result<CompressedFileRange>;
+
+    /// See [`ObjectSection::compressed_data`].
+    fn compressed_data(&self) -> Result<CompressedData<'data>>;
+
+    /// See [`ObjectSection::data_file_range`].
+    fn data_file_range(&self) -> Result<Option<(u64, u64)>>;
+
+    /// See [`ObjectSection::uncompressed_data`].
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>>;
+
+    /// See [`ObjectSection::uncompressed_data_into`].
+    fn uncompressed_data_into(&self, buf: &mut Vec<u8>) -> Result<()>;
+
+    /// See [`ObjectSection::name_bytes`].
+    fn name_bytes(&self) -> Result<&'data [u8]>;
+
+    /// See [`ObjectSection::name`].
+    fn name(&self) -> Result<&'data str>;
+
+    /// See [`ObjectSection::segment_name_bytes`].
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>>;
+
+    /// See [`ObjectSection::segment_name`].
+    fn segment_name(&self) -> Result<Option<&str>>;
+
+    /// See [`ObjectSection::kind`].
+    fn kind(&self) -> SectionKind;
+
+    /// See [`ObjectSection::relocations`].
+    fn relocations<'file>(&'file self) -> DynIterator<'file, (u64, Relocation)>
+    where
+        'data: 'file;
+
+    /// See [`ObjectSection::relocation_map`].
+    fn relocation_map(&self) -> Result<RelocationMap>;
+
+    /// See [`ObjectSection::flags`].
+    fn flags(&self) -> SectionFlags;
+
+    /// See [`ObjectSection::permissions`].
+    fn permissions(&self) -> Permissions;
+}
+
+impl<'data, T: ObjectSection<'data>> DynSection<'data> for T {
+    fn index(&self) -> SectionIndex {
+        ObjectSection::index(self)
+    }
+
+    fn address(&self) -> u64 {
+        ObjectSection::address(self)
+    }
+
+    fn size(&self) -> u64 {
+        ObjectSection::size(self)
+    }
+
+    fn align(&self) -> u64 {
+        ObjectSection::align(self)
+    }
+
+    fn file_range(&self) -> Option<(u64, u64)> {
+        ObjectSection::file_range(self)
+    }
+
+    fn data(&self) -> Result<&'data [u8]> {
+        ObjectSection::data(self)
+    }
+
+    fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
+        ObjectSection::data_range(self, address, size)
+    }
+
+    fn compressed_file_range(&self) -> Result<CompressedFileRange> {
+        ObjectSection::compressed_file_range(self)
+    }
+
+    fn compressed_data(&self) -> Result<CompressedData<'data>> {
+        ObjectSection::compressed_data(self)
+    }
+
+    fn data_file_range(&self) -> Result<Option<(u64, u64)>> {
+        ObjectSection::data_file_range(self)
+    }
+
+    fn uncompressed_data(&self) -> Result<Cow<'data, [u8]>> {
+        ObjectSection::uncompressed_data(self)
+    }
+
+    fn uncompressed_data_into(&self, buf: &mut Vec<u8>) -> Result<()> {
+        ObjectSection::uncompressed_data_into(self, buf)
+    }
+
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        ObjectSection::name_bytes(self)
+    }
+
+    fn name(&self) -> Result<&'data str> {
+        ObjectSection::name(self)
+    }
+
+    fn segment_name_bytes(&self) -> Result<Option<&[u8]>> {
+        ObjectSection::segment_name_bytes(self)
+    }
+
+    fn segment_name(&self) -> Result<Option<&str>> {
+        ObjectSection::segment_name(self)
+    }
+
+    fn kind(&self) -> SectionKind {
+        ObjectSection::kind(self)
+    }
+
+    fn relocations<'file>(&'file self) -> DynIterator<'file, (u64, Relocation)>
+    where
+        'data: 'file,
+    {
+        Box::new(ObjectSection::relocations(self))
+    }
+
+    fn relocation_map(&self) -> Result<RelocationMap> {
+        ObjectSection::relocation_map(self)
+    }
+
+    fn flags(&self) -> SectionFlags {
+        ObjectSection::flags(self)
+    }
+
+    fn permissions(&self) -> Permissions {
+        ObjectSection::permissions(self)
+    }
+}
+
+/// An object safe version of [`ObjectComdat`].
+///
+/// This is implemented for all types that implement [`ObjectComdat`].
+pub trait DynComdat<'data>: read::private::Sealed {
+    /// See [`ObjectComdat::kind`].
+    fn kind(&self) -> ComdatKind;
+
+    /// See [`ObjectComdat::symbol`].
+    fn symbol(&self) -> SymbolIndex;
+
+    /// See [`ObjectComdat::name_bytes`].
+    fn name_bytes(&self) -> Result<&'data [u8]>;
+
+    /// See [`ObjectComdat::name`].
+    fn name(&self) -> Result<&'data str>;
+
+    /// See [`ObjectComdat::sections`].
+    fn sections<'file>(&'file self) -> DynIterator<'file, SectionIndex>
+    where
+        'data: 'file;
+}
+
+impl<'data, T: ObjectComdat<'data>> DynComdat<'data> for T {
+    fn kind(&self) -> ComdatKind {
+        ObjectComdat::kind(self)
+    }
+
+    fn symbol(&self) -> SymbolIndex {
+        ObjectComdat::symbol(self)
+    }
+
+    fn name_bytes(&self) -> Result<&'data [u8]> {
+        ObjectComdat::name_bytes(self)
+    }
+
+    fn name(&self) -> Result<&'data str> {
+        ObjectComdat::name(self)
+    }
+
+    fn sections<'file>(&'file self) -> DynIterator<'file, SectionIndex>
+    where
+        'data: 'file,
+    {
+        Box::new(ObjectComdat::sections(self))
+    }
+}
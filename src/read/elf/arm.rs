@@ -0,0 +1,285 @@
+use core::slice;
+
+use crate::elf;
+use crate::endian::U32;
+use crate::read::{self, Bytes, Error, ReadError};
+
+use super::FileHeader;
+
+/// Decode a 31-bit place-relative offset, and add it to `place`.
+fn prel31(place: u64, value: u32) -> u64 {
+    let offset = ((value << 1) as i32) >> 1;
+    u64::from((place as u32).wrapping_add(offset as u32))
+}
+
+/// An iterator over the entries in an ELF `SHT_ARM_EXIDX` section.
+///
+/// The addresses in the entries are only valid for linked files, since
+/// relocations are not applied.
+///
+/// Returned by [`SectionHeader::arm_exidx`](super::SectionHeader::arm_exidx).
+#[derive(Debug, Clone)]
+pub struct ArmExidxIterator<'data, Elf: FileHeader> {
+    endian: Elf::Endian,
+    address: u64,
+    data: Bytes<'data>,
+}
+
+impl<'data, Elf: FileHeader> ArmExidxIterator<'data, Elf> {
+    /// Construct a new iterator given the address and data of the section.
+    pub fn new(endian: Elf::Endian, address: u64, data: &'data [u8]) -> Self {
+        ArmExidxIterator {
+            endian,
+            address,
+            data: Bytes(data),
+        }
+    }
+
+    /// Returns the next entry.
+    pub fn next(&mut self) -> read::Result<Option<ArmExidxEntry>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+
+        let result = self
+            .data
+            .read::<[U32<Elf::Endian>; 2]>()
+            .read_error("Invalid ELF ARM exidx section size")
+            .map(|words| {
+                let function = prel31(self.address, words[0].get(self.endian));
+                let word = words[1].get(self.endian);
+                let unwind = if word == elf::EXIDX_CANTUNWIND {
+                    ArmExidxUnwind::CantUnwind
+                } else if word & 0x8000_0000 != 0 {
+                    ArmExidxUnwind::Inline(word)
+                } else {
+                    ArmExidxUnwind::Table(prel31(self.address + 4, word))
+                };
+                self.address += 8;
+                Some(ArmExidxEntry { function, unwind })
+            });
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+}
+
+impl<'data, Elf: FileHeader> Iterator for ArmExidxIterator<'data, Elf> {
+    type Item = read::Result<ArmExidxEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// An entry in an ELF `SHT_ARM_EXIDX` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArmExidxEntry {
+    /// The address of the start of the function.
+    ///
+    /// The function extends until the start of the function for the next entry.
+    pub function: u64,
+    /// The unwind information for the function.
+    pub unwind: ArmExidxUnwind,
+}
+
+/// The unwind information in an [`ArmExidxEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmExidxUnwind {
+    /// The function cannot be unwound.
+    CantUnwind,
+    /// The unwind instructions are stored inline in this word.
+    ///
+    /// Use [`ArmUnwind::inline`] to decode it.
+    Inline(u32),
+    /// The address of the unwind information in the `.ARM.extab` section.
+    ///
+    /// Use [`ArmExtab::unwind`] to decode it.
+    Table(u64),
+}
+
+/// The data in an ELF `.ARM.extab` section.
+///
+/// This contains the unwind information that is referenced by
+/// [`ArmExidxUnwind::Table`].
+#[derive(Debug, Clone, Copy)]
+pub struct ArmExtab<'data, Elf: FileHeader> {
+    endian: Elf::Endian,
+    address: u64,
+    data: Bytes<'data>,
+}
+
+impl<'data, Elf: FileHeader> ArmExtab<'data, Elf> {
+    /// Construct a new table given the address and data of the section.
+    pub fn new(endian: Elf::Endian, address: u64, data: &'data [u8]) -> Self {
+        ArmExtab {
+            endian,
+            address,
+            data: Bytes(data),
+        }
+    }
+
+    /// Return the unwind information for an [`ArmExidxUnwind`].
+    ///
+    /// Returns `Ok(None)` for [`ArmExidxUnwind::CantUnwind`].
+    /// Returns `Err` if the address is not within this section, or for invalid values.
+    pub fn unwind(&self, unwind: ArmExidxUnwind) -> read::Result<Option<ArmUnwind<'data, Elf>>> {
+        match unwind {
+            ArmExidxUnwind::CantUnwind => Ok(None),
+            ArmExidxUnwind::Inline(word) => ArmUnwind::inline(self.endian, word).map(Some),
+            ArmExidxUnwind::Table(address) => self.parse(address).map(Some),
+        }
+    }
+
+    fn parse(&self, address: u64) -> read::Result<ArmUnwind<'data, Elf>> {
+        let offset = address
+            .checked_sub(self.address)
+            .read_error("Invalid ELF ARM extab address")?;
+        let mut data = self.data;
+        data.skip(offset as usize)
+            .read_error("Invalid ELF ARM extab address")?;
+        let word = data
+            .read::<U32<Elf::Endian>>()
+            .read_error("Invalid ELF ARM extab entry")?
+            .get(self.endian);
+        if word & 0x8000_0000 == 0 {
+            // Generic model. The format of the data is defined by the personality routine.
+            return Ok(ArmUnwind {
+                personality: ArmPersonality::Generic(prel31(address, word)),
+                instructions: ArmUnwindInstructions::default(),
+                data: data.0,
+            });
+        }
+        let index = ((word >> 24) & 0xf) as u8;
+        let instructions = match index {
+            0 => ArmUnwindInstructions::new(word, 3, &[], self.endian),
+            1 | 2 => {
+                let count = ((word >> 16) & 0xff) as usize;
+                let words = data
+                    .read_slice::<U32<Elf::Endian>>(count)
+                    .read_error("Invalid ELF ARM extab instruction count")?;
+                ArmUnwindInstructions::new(word, 2, words, self.endian)
+            }
+            _ => return Err(Error("Unsupported ELF ARM extab personality index")),
+        };
+        Ok(ArmUnwind {
+            personality: ArmPersonality::Compact(index),
+            instructions,
+            data: data.0,
+        })
+    }
+}
+
+/// The personality routine for an [`ArmUnwind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmPersonality {
+    /// A compact model with the given personality routine index.
+    ///
+    /// Index 0 is `__aeabi_unwind_cpp_pr0`, 1 is `__aeabi_unwind_cpp_pr1`,
+    /// and 2 is `__aeabi_unwind_cpp_pr2`.
+    Compact(u8),
+    /// A generic model with the given personality routine address.
+    Generic(u64),
+}
+
+/// The unwind information for a function, as described by the ARM EHABI.
+#[derive(Debug, Clone)]
+pub struct ArmUnwind<'data, Elf: FileHeader> {
+    personality: ArmPersonality,
+    instructions: ArmUnwindInstructions<'data, Elf>,
+    data: &'data [u8],
+}
+
+impl<'data, Elf: FileHeader> ArmUnwind<'data, Elf> {
+    /// Decode unwind information that is stored inline in an exidx entry.
+    ///
+    /// Returns `Err` if the word does not use personality routine index 0.
+    pub fn inline(endian: Elf::Endian, word: u32) -> read::Result<Self> {
+        if word & 0xff00_0000 != 0x8000_0000 {
+            return Err(Error("Invalid ELF ARM exidx inline entry"));
+        }
+        Ok(ArmUnwind {
+            personality: ArmPersonality::Compact(0),
+            instructions: ArmUnwindInstructions::new(word, 3, &[], endian),
+            data: &[],
+        })
+    }
+
+    /// Return the personality routine.
+    pub fn personality(&self) -> ArmPersonality {
+        self.personality
+    }
+
+    /// Return an iterator over the unwind instruction bytes.
+    ///
+    /// This is empty for the generic model.
+    pub fn instructions(&self) -> ArmUnwindInstructions<'data, Elf> {
+        self.instructions.clone()
+    }
+
+    /// Return the data following the unwind instructions.
+    ///
+    /// For the generic model, this is all of the data following the personality
+    /// routine address. For the compact models, this is the data following the
+    /// unwind instructions, which is used by personality routines 1 and 2.
+    /// In both cases, the length of the data is not known, so this extends to the
+    /// end of the section.
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+/// An iterator over the unwind instruction bytes in an [`ArmUnwind`].
+///
+/// The instruction bytes are packed into words starting with the most
+/// significant byte.
+#[derive(Debug, Clone)]
+pub struct ArmUnwindInstructions<'data, Elf: FileHeader> {
+    endian: Elf::Endian,
+    word: u32,
+    remaining: u8,
+    words: slice::Iter<'data, U32<Elf::Endian>>,
+}
+
+impl<'data, Elf: FileHeader> Default for ArmUnwindInstructions<'data, Elf> {
+    fn default() -> Self {
+        ArmUnwindInstructions {
+            endian: Elf::Endian::default(),
+            word: 0,
+            remaining: 0,
+            words: [].iter(),
+        }
+    }
+}
+
+impl<'data, Elf: FileHeader> ArmUnwindInstructions<'data, Elf> {
+    fn new(
+        word: u32,
+        remaining: u8,
+        words: &'data [U32<Elf::Endian>],
+        endian: Elf::Endian,
+    ) -> Self {
+        ArmUnwindInstructions {
+            endian,
+            word: word << (8 * (4 - u32::from(remaining))),
+            remaining,
+            words: words.iter(),
+        }
+    }
+}
+
+impl<'data, Elf: FileHeader> Iterator for ArmUnwindInstructions<'data, Elf> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.remaining == 0 {
+            self.word = self.words.next()?.get(self.endian);
+            self.remaining = 4;
+        }
+        let byte = (self.word >> 24) as u8;
+        self.word <<= 8;
+        self.remaining -= 1;
+        Some(byte)
+    }
+}
@@ -0,0 +1,245 @@
+use alloc::vec::Vec;
+
+use crate::endian::{Endian, U32Bytes, U64Bytes};
+use crate::read::{self, Bytes, ReadError, ReadRef};
+
+use super::{ElfFile, FileHeader, SectionHeader};
+
+/// The `DW_SECT_INFO` section identifier in a DWARF package index.
+pub const DW_SECT_INFO: u32 = 1;
+/// The `DW_SECT_TYPES` section identifier in a version 2 DWARF package index.
+pub const DW_SECT_V2_TYPES: u32 = 2;
+/// The `DW_SECT_ABBREV` section identifier in a DWARF package index.
+pub const DW_SECT_ABBREV: u32 = 3;
+/// The `DW_SECT_LINE` section identifier in a DWARF package index.
+pub const DW_SECT_LINE: u32 = 4;
+/// The `DW_SECT_LOC` section identifier in a version 2 DWARF package index.
+pub const DW_SECT_V2_LOC: u32 = 5;
+/// The `DW_SECT_LOCLISTS` section identifier in a version 5 DWARF package index.
+pub const DW_SECT_LOCLISTS: u32 = 5;
+/// The `DW_SECT_STR_OFFSETS` section identifier in a DWARF package index.
+pub const DW_SECT_STR_OFFSETS: u32 = 6;
+/// The `DW_SECT_MACINFO` section identifier in a version 2 DWARF package index.
+pub const DW_SECT_V2_MACINFO: u32 = 7;
+/// The `DW_SECT_MACRO` section identifier in a version 5 DWARF package index.
+pub const DW_SECT_MACRO: u32 = 7;
+/// The `DW_SECT_MACRO` section identifier in a version 2 DWARF package index.
+pub const DW_SECT_V2_MACRO: u32 = 8;
+/// The `DW_SECT_RNGLISTS` section identifier in a version 5 DWARF package index.
+pub const DW_SECT_RNGLISTS: u32 = 8;
+
+/// A parsed `.debug_cu_index` or `.debug_tu_index` section in a DWARF package file.
+///
+/// The index maps the DWO id of a split unit to the ranges of the `.dwo`
+/// sections that contain the contributions of that unit.
+///
+/// Both version 2 (the GNU extension) and version 5 of the index are supported.
+#[derive(Debug, Clone, Copy)]
+pub struct DwarfPackageIndex<'data, E: Endian> {
+    endian: E,
+    version: u16,
+    unit_count: u32,
+    section_ids: &'data [U32Bytes<E>],
+    signatures: &'data [U64Bytes<E>],
+    indices: &'data [U32Bytes<E>],
+    offsets: &'data [U32Bytes<E>],
+    sizes: &'data [U32Bytes<E>],
+}
+
+/// A unit in a [`DwarfPackageIndex`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DwarfPackageUnit {
+    /// The DWO id of a compilation unit, or the type signature of a type unit.
+    pub signature: u64,
+    /// The contributions of the unit to each section.
+    pub sections: Vec<DwarfPackageSection>,
+}
+
+/// The contribution of a unit in a [`DwarfPackageIndex`] to a section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DwarfPackageSection {
+    /// The section identifier.
+    ///
+    /// One of the `DW_SECT_*` constants. The meaning of the value depends on
+    /// the version of the index.
+    pub id: u32,
+    /// The offset of the contribution within the section.
+    pub offset: u32,
+    /// The size of the contribution.
+    pub size: u32,
+}
+
+impl<'data, E: Endian> DwarfPackageIndex<'data, E> {
+    /// Parse the data of a `.debug_cu_index` or `.debug_tu_index` section.
+    pub fn parse(endian: E, data: &'data [u8]) -> read::Result<Self> {
+        let mut data = Bytes(data);
+        let header = data
+            .read_slice::<U32Bytes<E>>(4)
+            .read_error("Invalid DWARF package index header")?;
+        // Version 5 uses a 2 byte version followed by 2 bytes of padding.
+        let version = header[0].get(endian);
+        let version = if version == 2 {
+            2
+        } else if endian.is_little_endian() {
+            version as u16
+        } else {
+            (version >> 16) as u16
+        };
+        if version != 2 && version != 5 {
+            return Err(read::Error("Unsupported DWARF package index version"));
+        }
+        let section_count = header[1].get(endian) as usize;
+        let unit_count = header[2].get(endian);
+        let slot_count = header[3].get(endian) as usize;
+        let table_len = (unit_count as usize)
+            .checked_mul(section_count)
+            .read_error("Invalid DWARF package index size")?;
+
+        let signatures = data
+            .read_slice(slot_count)
+            .read_error("Invalid DWARF package index hash table")?;
+        let indices = data
+            .read_slice(slot_count)
+            .read_error("Invalid DWARF package index hash table")?;
+        let section_ids = data
+            .read_slice(section_count)
+            .read_error("Invalid DWARF package index section identifiers")?;
+        let offsets = data
+            .read_slice(table_len)
+            .read_error("Invalid DWARF package index offset table")?;
+        let sizes = data
+            .read_slice(table_len)
+            .read_error("Invalid DWARF package index size table")?;
+        Ok(DwarfPackageIndex {
+            endian,
+            version,
+            unit_count,
+            section_ids,
+            signatures,
+            indices,
+            offsets,
+            sizes,
+        })
+    }
+
+    /// Return the version of the index.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// Return the number of units in the index.
+    pub fn unit_count(&self) -> u32 {
+        self.unit_count
+    }
+
+    /// Return the section identifiers of the columns in the index.
+    pub fn section_ids(&self) -> impl Iterator<Item = u32> + 'data {
+        let endian = self.endian;
+        self.section_ids.iter().map(move |id| id.get(endian))
+    }
+
+    /// Return the name of the `.dwo` section for a section identifier in this index.
+    pub fn section_name(&self, id: u32) -> Option<&'static [u8]> {
+        Some(match (self.version, id) {
+            (_, DW_SECT_INFO) => b".debug_info.dwo",
+            (2, DW_SECT_V2_TYPES) => b".debug_types.dwo",
+            (_, DW_SECT_ABBREV) => b".debug_abbrev.dwo",
+            (_, DW_SECT_LINE) => b".debug_line.dwo",
+            (2, DW_SECT_V2_LOC) => b".debug_loc.dwo",
+            (_, DW_SECT_LOCLISTS) => b".debug_loclists.dwo",
+            (_, DW_SECT_STR_OFFSETS) => b".debug_str_offsets.dwo",
+            (2, DW_SECT_V2_MACINFO) => b".debug_macinfo.dwo",
+            (_, DW_SECT_MACRO) => b".debug_macro.dwo",
+            (2, DW_SECT_V2_MACRO) => b".debug_macro.dwo",
+            (_, DW_SECT_RNGLISTS) => b".debug_rnglists.dwo",
+            _ => return None,
+        })
+    }
+
+    /// Return all of the units in the index, in the order of the hash table.
+    pub fn units(&self) -> read::Result<Vec<DwarfPackageUnit>> {
+        let mut units = Vec::new();
+        for (signature, index) in self.signatures.iter().zip(self.indices) {
+            let index = index.get(self.endian);
+            if index != 0 {
+                units.push(self.unit(signature.get(self.endian), index)?);
+            }
+        }
+        Ok(units)
+    }
+
+    /// Find the unit with the given DWO id or type signature.
+    pub fn find(&self, signature: u64) -> read::Result<Option<DwarfPackageUnit>> {
+        let slot_count = self.signatures.len() as u64;
+        if slot_count == 0 {
+            return Ok(None);
+        }
+        // The hash table is an open addressing table with double hashing.
+        let mask = slot_count - 1;
+        let mut slot = signature & mask;
+        let step = ((signature >> 32) & mask) | 1;
+        for _ in 0..slot_count {
+            let index = self.indices[slot as usize].get(self.endian);
+            if index == 0 {
+                return Ok(None);
+            }
+            if self.signatures[slot as usize].get(self.endian) == signature {
+                return self.unit(signature, index).map(Some);
+            }
+            slot = (slot + step) & mask;
+        }
+        Ok(None)
+    }
+
+    fn unit(&self, signature: u64, index: u32) -> read::Result<DwarfPackageUnit> {
+        if index > self.unit_count {
+            return Err(read::Error("Invalid DWARF package index row"));
+        }
+        let section_count = self.section_ids.len();
+        let start = (index - 1) as usize * section_count;
+        let offsets = &self.offsets[start..][..section_count];
+        let sizes = &self.sizes[start..][..section_count];
+        let sections = self
+            .section_ids
+            .iter()
+            .zip(offsets.iter().zip(sizes))
+            .map(|(id, (offset, size))| DwarfPackageSection {
+                id: id.get(self.endian),
+                offset: offset.get(self.endian),
+                size: size.get(self.endian),
+            })
+            .collect();
+        Ok(DwarfPackageUnit {
+            signature,
+            sections,
+        })
+    }
+}
+
+impl<'data, Elf, R> ElfFile<'data, Elf, R>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    /// Parse the `.debug_cu_index` section of a DWARF package file, if present.
+    pub fn dwarf_cu_index(&self) -> read::Result<Option<DwarfPackageIndex<'data, Elf::Endian>>> {
+        self.dwarf_package_index(b".debug_cu_index")
+    }
+
+    /// Parse the `.debug_tu_index` section of a DWARF package file, if present.
+    pub fn dwarf_tu_index(&self) -> read::Result<Option<DwarfPackageIndex<'data, Elf::Endian>>> {
+        self.dwarf_package_index(b".debug_tu_index")
+    }
+
+    fn dwarf_package_index(
+        &self,
+        name: &[u8],
+    ) -> read::Result<Option<DwarfPackageIndex<'data, Elf::Endian>>> {
+        let endian = self.endian;
+        let Some((_, section)) = self.sections.section_by_name(endian, name) else {
+            return Ok(None);
+        };
+        let data = section.data(endian, self.data)?;
+        DwarfPackageIndex::parse(endian, data).map(Some)
+    }
+}
@@ -34,7 +34,10 @@ pub trait Dyn: Debug + Pod {
                 | elf::DT_RPATH
                 | elf::DT_RUNPATH
                 | elf::DT_AUXILIARY
-                | elf::DT_FILTER => true,
+                | elf::DT_FILTER
+                | elf::DT_CONFIG
+                | elf::DT_DEPAUDIT
+                | elf::DT_AUDIT => true,
                 _ => false,
             }
         } else {
@@ -74,6 +77,7 @@ pub trait Dyn: Debug + Pod {
                 | elf::DT_INIT_ARRAY
                 | elf::DT_PREINIT_ARRAY
                 | elf::DT_SYMTAB_SHNDX
+                | elf::DT_RELR
                 | elf::DT_VERDEF
                 | elf::DT_VERNEED
                 | elf::DT_VERSYM
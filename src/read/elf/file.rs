@@ -8,7 +8,7 @@ use crate::endian::{self, Endian, Endianness, U32};
 use crate::pod::Pod;
 use crate::read::{
     self, util, Architecture, ByteString, Bytes, Error, Export, FileFlags, Import, Object,
-    ObjectKind, ReadError, ReadRef, SectionIndex, StringTable, SymbolIndex,
+    ObjectKind, ParseOptions, ReadError, ReadRef, SectionIndex, StringTable, SymbolIndex,
 };
 
 use super::{
@@ -48,6 +48,7 @@ where
     pub(super) relocations: RelocationSections,
     pub(super) symbols: SymbolTable<'data, Elf, R>,
     pub(super) dynamic_symbols: SymbolTable<'data, Elf, R>,
+    pub(super) warnings: Vec<Error>,
 }
 
 impl<'data, Elf, R> ElfFile<'data, Elf, R>
@@ -57,15 +58,47 @@ where
 {
     /// Parse the raw ELF file data.
     pub fn parse(data: R) -> read::Result<Self> {
+        Self::parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw ELF file data, using the given options.
+    pub fn parse_with(data: R, options: &ParseOptions) -> read::Result<Self> {
+        options.verify_file(data)?;
         let header = Elf::parse(data)?;
         let endian = header.endian()?;
         let segments = header.program_headers(endian, data)?;
         let sections = header.sections(endian, data)?;
-        let symbols = sections.symbols(endian, data, elf::SHT_SYMTAB)?;
+        let mut warnings = Vec::new();
+        let symbols = if options.symbols {
+            options.recover(
+                &mut warnings,
+                sections.symbols(endian, data, elf::SHT_SYMTAB),
+            )?
+        } else {
+            SymbolTable::default()
+        };
         // TODO: get dynamic symbols from DT_SYMTAB if there are no sections
-        let dynamic_symbols = sections.symbols(endian, data, elf::SHT_DYNSYM)?;
+        let dynamic_symbols = if options.dynamic_symbols {
+            options.recover(
+                &mut warnings,
+                sections.symbols(endian, data, elf::SHT_DYNSYM),
+            )?
+        } else {
+            SymbolTable::default()
+        };
         // The API we provide requires a mapping from section to relocations, so build it now.
-        let relocations = sections.relocation_sections(endian, symbols.section())?;
+        let relocations = if options.relocations {
+            let symbol_section = sections
+                .enumerate()
+                .find(|(_, section)| section.sh_type(endian) == elf::SHT_SYMTAB)
+                .map_or(SectionIndex(0), |(index, _)| index);
+            options.recover(
+                &mut warnings,
+                sections.relocation_sections(endian, symbol_section),
+            )?
+        } else {
+            RelocationSections::default()
+        };
 
         Ok(ElfFile {
             endian,
@@ -76,6 +109,7 @@ where
             relocations,
             symbols,
             dynamic_symbols,
+            warnings,
         })
     }
 
@@ -89,6 +123,13 @@ where
         self.data
     }
 
+    /// Returns the problems that were found while parsing the file.
+    ///
+    /// See [`Strictness::Permissive`](crate::read::Strictness::Permissive).
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
     /// Returns the raw ELF file header.
     #[deprecated(note = "Use `elf_header` instead")]
     pub fn raw_header(&self) -> &'data Elf {
@@ -139,6 +180,70 @@ where
         &self.relocations
     }
 
+    /// Return the code address for a PowerPC64 ELFv1 function descriptor.
+    ///
+    /// In the ELFv1 ABI, the value of a function symbol is the address of a
+    /// function descriptor in the `.opd` section, instead of the address of the
+    /// code for the function. The first word of the descriptor is the code address.
+    ///
+    /// Relocations are not applied, so the result is only valid for linked files.
+    ///
+    /// Returns `Ok(None)` if the file does not use the ELFv1 ABI, or if the
+    /// address is not within the `.opd` section.
+    /// Returns `Err` for invalid values.
+    pub fn ppc64_opd_entry(&self, address: u64) -> read::Result<Option<u64>> {
+        if self.header.e_machine(self.endian) != elf::EM_PPC64
+            || !self.header.is_class_64()
+            || self.header.e_flags(self.endian) & elf::EF_PPC64_ABI == 2
+        {
+            return Ok(None);
+        }
+        let Some(opd) = self.raw_section_by_name(b".opd") else {
+            return Ok(None);
+        };
+        let opd_address = opd.section.sh_addr(self.endian).into();
+        let opd_size = opd.section.sh_size(self.endian).into();
+        let Some(offset) = address
+            .checked_sub(opd_address)
+            .filter(|offset| *offset < opd_size)
+        else {
+            return Ok(None);
+        };
+        let data = opd.section.data(self.endian, self.data)?;
+        let entry = Bytes(data)
+            .read_at::<endian::U64<Elf::Endian>>(offset as usize)
+            .read_error("Invalid ELF PowerPC64 function descriptor offset")?;
+        Ok(Some(entry.get(self.endian)))
+    }
+
+    /// Return the descriptor of the first note with the given name and type.
+    fn note_desc(&self, name: &[u8], n_type: u32) -> read::Result<Option<&'data [u8]>> {
+        let endian = self.endian;
+        // Use section headers if present, otherwise use program headers.
+        if !self.sections.is_empty() {
+            for section in self.sections.iter() {
+                if let Some(mut notes) = section.notes(endian, self.data)? {
+                    while let Some(note) = notes.next()? {
+                        if note.name() == name && note.n_type(endian) == n_type {
+                            return Ok(Some(note.desc()));
+                        }
+                    }
+                }
+            }
+        } else {
+            for segment in self.segments {
+                if let Some(mut notes) = segment.notes(endian, self.data)? {
+                    while let Some(note) = notes.next()? {
+                        if note.name() == name && note.n_type(endian) == n_type {
+                            return Ok(Some(note.desc()));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
     fn raw_section_by_name<'file>(
         &'file self,
         section_name: &[u8],
@@ -436,34 +541,11 @@ where
     }
 
     fn build_id(&self) -> read::Result<Option<&'data [u8]>> {
-        let endian = self.endian;
-        // Use section headers if present, otherwise use program headers.
-        if !self.sections.is_empty() {
-            for section in self.sections.iter() {
-                if let Some(mut notes) = section.notes(endian, self.data)? {
-                    while let Some(note) = notes.next()? {
-                        if note.name() == elf::ELF_NOTE_GNU
-                            && note.n_type(endian) == elf::NT_GNU_BUILD_ID
-                        {
-                            return Ok(Some(note.desc()));
-                        }
-                    }
-                }
-            }
-        } else {
-            for segment in self.segments {
-                if let Some(mut notes) = segment.notes(endian, self.data)? {
-                    while let Some(note) = notes.next()? {
-                        if note.name() == elf::ELF_NOTE_GNU
-                            && note.n_type(endian) == elf::NT_GNU_BUILD_ID
-                        {
-                            return Ok(Some(note.desc()));
-                        }
-                    }
-                }
-            }
-        }
-        Ok(None)
+        self.note_desc(elf::ELF_NOTE_GNU, elf::NT_GNU_BUILD_ID)
+    }
+
+    fn go_build_id(&self) -> read::Result<Option<&'data [u8]>> {
+        self.note_desc(elf::ELF_NOTE_GO, elf::NT_GO_BUILD_ID)
     }
 
     fn gnu_debuglink(&self) -> read::Result<Option<(&'data [u8], u32)>> {
@@ -519,6 +601,10 @@ where
             e_flags: self.header.e_flags(self.endian),
         }
     }
+
+    fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
 }
 
 /// A trait for generic access to [`elf::FileHeader32`] and [`elf::FileHeader64`].
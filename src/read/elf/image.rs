@@ -0,0 +1,187 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::elf;
+use crate::read::{self, Error, ReadError, ReadRef};
+
+use super::{ElfFile, FileHeader, ProgramHeader};
+
+/// The memory image of the loadable segments of an ELF file.
+///
+/// This contains the file data of the `PT_LOAD` segments, positioned at their
+/// physical addresses. This is the same as the output of `objcopy -O binary`.
+///
+/// Returned by [`ElfFile::load_image`].
+#[derive(Debug, Clone)]
+pub struct ElfLoadImage<'data> {
+    entry: u64,
+    segments: Vec<ElfLoadSegment<'data>>,
+}
+
+/// A loadable segment in an [`ElfLoadImage`].
+#[derive(Debug, Clone, Copy)]
+pub struct ElfLoadSegment<'data> {
+    /// The physical address of the segment.
+    ///
+    /// This is the address that the segment data is loaded at.
+    pub physical_address: u64,
+    /// The virtual address of the segment.
+    ///
+    /// This is the address of the segment during execution.
+    pub virtual_address: u64,
+    /// The file data of the segment.
+    ///
+    /// This does not include the zero initialized data at the end of the segment.
+    pub data: &'data [u8],
+    /// The size of the segment in memory.
+    pub memory_size: u64,
+}
+
+/// A contiguous range of memory in an [`ElfLoadImage`].
+///
+/// Returned by [`ElfLoadImage::chunks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfLoadChunk {
+    /// The physical address of the start of the chunk.
+    pub address: u64,
+    /// The data of the chunk.
+    ///
+    /// Gaps between segments within the chunk are filled with zeros.
+    pub data: Vec<u8>,
+}
+
+impl<'data> ElfLoadImage<'data> {
+    /// Return the entry point address from the file header.
+    ///
+    /// This is a virtual address.
+    pub fn entry(&self) -> u64 {
+        self.entry
+    }
+
+    /// Return the loadable segments that have file data, sorted by physical address.
+    pub fn segments(&self) -> &[ElfLoadSegment<'data>] {
+        &self.segments
+    }
+
+    /// Return the lowest physical address of the segments.
+    ///
+    /// This is the address of the start of the image, and is 0 if the image is empty.
+    pub fn load_address(&self) -> u64 {
+        self.segments
+            .first()
+            .map(|segment| segment.physical_address)
+            .unwrap_or(0)
+    }
+
+    /// Return the size of the image.
+    ///
+    /// This is the size of the range from the lowest physical address to the end
+    /// of the file data of the highest segment, including any gaps.
+    pub fn size(&self) -> u64 {
+        self.segments
+            .iter()
+            .map(segment_end)
+            .max()
+            .map(|end| end - self.load_address())
+            .unwrap_or(0)
+    }
+
+    /// Return the image as a single flat buffer, starting at [`Self::load_address`].
+    ///
+    /// Gaps between segments are filled with zeros. If segments overlap, the segment
+    /// with the higher physical address takes precedence.
+    ///
+    /// Use [`Self::chunks`] instead if the gaps may be large.
+    pub fn to_vec(&self) -> read::Result<Vec<u8>> {
+        Ok(self
+            .chunks(u64::MAX)?
+            .pop()
+            .map(|chunk| chunk.data)
+            .unwrap_or_default())
+    }
+
+    /// Return the image as a list of contiguous chunks, sorted by address.
+    ///
+    /// Segments are placed in the same chunk if the gap between them is not greater
+    /// than `max_gap`, and the gap is filled with zeros. Otherwise a new chunk is
+    /// started. Use a `max_gap` of 0 to only merge segments that are adjacent or
+    /// overlapping.
+    pub fn chunks(&self, max_gap: u64) -> read::Result<Vec<ElfLoadChunk>> {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let mut end = 0;
+        for (index, segment) in self.segments.iter().enumerate() {
+            if index > start && segment.physical_address.saturating_sub(end) > max_gap {
+                chunks.push(Self::chunk(&self.segments[start..index], end)?);
+                start = index;
+                end = 0;
+            }
+            end = end.max(segment_end(segment));
+        }
+        if start < self.segments.len() {
+            chunks.push(Self::chunk(&self.segments[start..], end)?);
+        }
+        Ok(chunks)
+    }
+
+    fn chunk(segments: &[ElfLoadSegment<'data>], end: u64) -> read::Result<ElfLoadChunk> {
+        let address = segments[0].physical_address;
+        let size = usize::try_from(end - address)
+            .ok()
+            .read_error("ELF load image is too large")?;
+        let mut data = Vec::new();
+        data.try_reserve_exact(size)
+            .map_err(|_| Error("ELF load image is too large"))?;
+        data.resize(size, 0);
+        for segment in segments {
+            let offset = (segment.physical_address - address) as usize;
+            data[offset..][..segment.data.len()].copy_from_slice(segment.data);
+        }
+        Ok(ElfLoadChunk { address, data })
+    }
+}
+
+fn segment_end(segment: &ElfLoadSegment<'_>) -> u64 {
+    segment.physical_address + segment.data.len() as u64
+}
+
+impl<'data, Elf, R> ElfFile<'data, Elf, R>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    /// Return the memory image of the loadable segments.
+    ///
+    /// This includes the file data of all `PT_LOAD` segments. Segments without
+    /// file data are omitted.
+    pub fn load_image(&self) -> read::Result<ElfLoadImage<'data>> {
+        let endian = self.endian;
+        let mut segments = Vec::new();
+        for segment in self.segments {
+            if segment.p_type(endian) != elf::PT_LOAD {
+                continue;
+            }
+            let data = segment
+                .data(endian, self.data)
+                .read_error("Invalid ELF segment size or offset")?;
+            if data.is_empty() {
+                continue;
+            }
+            let physical_address = segment.p_paddr(endian).into();
+            if physical_address.checked_add(data.len() as u64).is_none() {
+                return Err(Error("Invalid ELF segment address"));
+            }
+            segments.push(ElfLoadSegment {
+                physical_address,
+                virtual_address: segment.p_vaddr(endian).into(),
+                data,
+                memory_size: segment.p_memsz(endian).into(),
+            });
+        }
+        segments.sort_by_key(|segment| segment.physical_address);
+        Ok(ElfLoadImage {
+            entry: self.header.e_entry(endian).into(),
+            segments,
+        })
+    }
+}
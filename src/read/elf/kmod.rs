@@ -0,0 +1,230 @@
+use crate::endian::{U32, U64};
+use crate::read::{self, Bytes, ReadError, ReadRef};
+
+use super::{ElfFile, FileHeader, SectionHeader};
+
+/// The size of a module name field in a Linux kernel module, including the
+/// size of the preceding word.
+///
+/// This is `MODULE_NAME_LEN + sizeof(unsigned long)` in the kernel headers.
+const MODULE_NAME_FIELD: usize = 64;
+
+/// An iterator over the entries in the `.modinfo` section of a Linux kernel module.
+///
+/// Returned by [`ElfFile::modinfo`].
+#[derive(Debug, Clone)]
+pub struct ModInfoIterator<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> ModInfoIterator<'data> {
+    /// An iterator over the entries in the data of a `.modinfo` section.
+    pub fn new(data: &'data [u8]) -> Self {
+        ModInfoIterator { data: Bytes(data) }
+    }
+
+    /// Returns the next entry.
+    pub fn next(&mut self) -> read::Result<Option<ModInfo<'data>>> {
+        // Entries may be separated by padding.
+        while let [0, ..] = self.data.0 {
+            self.data.skip(1).ok();
+        }
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> read::Result<ModInfo<'data>> {
+        let entry = self
+            .data
+            .read_string()
+            .read_error("Missing ELF .modinfo entry null terminator")?;
+        let split = memchr::memchr(b'=', entry).read_error("Invalid ELF .modinfo entry")?;
+        Ok(ModInfo {
+            key: &entry[..split],
+            value: &entry[split + 1..],
+        })
+    }
+}
+
+impl<'data> Iterator for ModInfoIterator<'data> {
+    type Item = read::Result<ModInfo<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// An entry in the `.modinfo` section of a Linux kernel module.
+///
+/// Each entry is a `key=value` string, such as `license=GPL` or `depends=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModInfo<'data> {
+    /// The key of the entry.
+    pub key: &'data [u8],
+    /// The value of the entry.
+    pub value: &'data [u8],
+}
+
+/// An iterator over the entries in the `__versions` section of a Linux kernel module.
+///
+/// Returned by [`ElfFile::modversions`].
+#[derive(Debug, Clone)]
+pub struct ModVersionIterator<'data, Elf: FileHeader> {
+    endian: Elf::Endian,
+    is_64: bool,
+    data: Bytes<'data>,
+}
+
+impl<'data, Elf: FileHeader> ModVersionIterator<'data, Elf> {
+    /// An iterator over the entries in the data of a `__versions` section.
+    pub fn new(endian: Elf::Endian, is_64: bool, data: &'data [u8]) -> Self {
+        ModVersionIterator {
+            endian,
+            is_64,
+            data: Bytes(data),
+        }
+    }
+
+    /// Returns the next entry.
+    pub fn next(&mut self) -> read::Result<Option<ModVersion<'data>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> read::Result<ModVersion<'data>> {
+        let mut entry = self
+            .data
+            .read_bytes(MODULE_NAME_FIELD)
+            .read_error("Invalid ELF __versions section size")?;
+        let crc = if self.is_64 {
+            entry.read::<U64<Elf::Endian>>().map(|x| x.get(self.endian))
+        } else {
+            entry
+                .read::<U32<Elf::Endian>>()
+                .map(|x| x.get(self.endian).into())
+        }
+        .read_error("Invalid ELF __versions section size")?;
+        let name = entry
+            .read_string()
+            .read_error("Missing ELF __versions name null terminator")?;
+        Ok(ModVersion { crc, name })
+    }
+}
+
+impl<'data, Elf: FileHeader> Iterator for ModVersionIterator<'data, Elf> {
+    type Item = read::Result<ModVersion<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// An entry in the `__versions` section of a Linux kernel module.
+///
+/// This is a `struct modversion_info` in the kernel headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModVersion<'data> {
+    /// The CRC of the symbol's type signature.
+    pub crc: u64,
+    /// The name of the symbol.
+    pub name: &'data [u8],
+}
+
+impl<'data, Elf, R> ElfFile<'data, Elf, R>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    /// Return an iterator over the entries in the `.modinfo` section of a
+    /// Linux kernel module.
+    ///
+    /// Returns `Ok(None)` if there is no `.modinfo` section.
+    pub fn modinfo(&self) -> read::Result<Option<ModInfoIterator<'data>>> {
+        let endian = self.endian;
+        let Some((_, section)) = self.sections.section_by_name(endian, b".modinfo") else {
+            return Ok(None);
+        };
+        let data = section.data(endian, self.data)?;
+        Ok(Some(ModInfoIterator::new(data)))
+    }
+
+    /// Return the value of the first entry with the given key in the `.modinfo`
+    /// section of a Linux kernel module.
+    ///
+    /// Returns `Ok(None)` if there is no `.modinfo` section or no entry with the key.
+    pub fn modinfo_value(&self, key: &[u8]) -> read::Result<Option<&'data [u8]>> {
+        let Some(mut iter) = self.modinfo()? else {
+            return Ok(None);
+        };
+        while let Some(entry) = iter.next()? {
+            if entry.key == key {
+                return Ok(Some(entry.value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return an iterator over the symbol CRCs in the `__versions` section of a
+    /// Linux kernel module.
+    ///
+    /// Returns `Ok(None)` if there is no `__versions` section.
+    pub fn modversions(&self) -> read::Result<Option<ModVersionIterator<'data, Elf>>> {
+        let endian = self.endian;
+        let Some((_, section)) = self.sections.section_by_name(endian, b"__versions") else {
+            return Ok(None);
+        };
+        let data = section.data(endian, self.data)?;
+        Ok(Some(ModVersionIterator::new(
+            endian,
+            self.header.is_type_64(),
+            data,
+        )))
+    }
+
+    /// Return the module name stored in the `.gnu.linkonce.this_module` section
+    /// of a Linux kernel module.
+    ///
+    /// The section contains a `struct module`, and the name is read from the
+    /// `name` field, which follows the `state` and `list` fields.
+    ///
+    /// Returns `Ok(None)` if there is no `.gnu.linkonce.this_module` section.
+    pub fn module_name(&self) -> read::Result<Option<&'data [u8]>> {
+        let endian = self.endian;
+        let Some((_, section)) = self
+            .sections
+            .section_by_name(endian, b".gnu.linkonce.this_module")
+        else {
+            return Ok(None);
+        };
+        let data = section.data(endian, self.data)?;
+        // `enum module_state state` is padded to the word size,
+        // and `struct list_head list` is two pointers.
+        let (offset, size) = if self.header.is_type_64() {
+            (24, MODULE_NAME_FIELD - 8)
+        } else {
+            (12, MODULE_NAME_FIELD - 4)
+        };
+        let name = Bytes(data)
+            .read_bytes_at(offset, size)
+            .read_error("Invalid ELF .gnu.linkonce.this_module section size")?;
+        let name = match memchr::memchr(b'\0', name.0) {
+            Some(end) => &name.0[..end],
+            None => name.0,
+        };
+        Ok(Some(name))
+    }
+}
@@ -0,0 +1,148 @@
+use core::mem;
+
+use crate::elf;
+use crate::read::{self, Bytes, ReadError, SectionIndex};
+
+use super::FileHeader;
+
+/// An iterator over the entries in an ELF `SHT_MIPS_OPTIONS` section.
+///
+/// Returned by [`SectionHeader::mips_options`](super::SectionHeader::mips_options).
+#[derive(Debug)]
+pub struct MipsOptionIterator<'data, Elf: FileHeader> {
+    endian: Elf::Endian,
+    data: Bytes<'data>,
+}
+
+impl<'data, Elf: FileHeader> MipsOptionIterator<'data, Elf> {
+    /// An iterator over the entries in an ELF `SHT_MIPS_OPTIONS` section.
+    pub fn new(endian: Elf::Endian, data: &'data [u8]) -> Self {
+        MipsOptionIterator {
+            endian,
+            data: Bytes(data),
+        }
+    }
+
+    /// Returns the next option.
+    pub fn next(&mut self) -> read::Result<Option<MipsOption<'data, Elf>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> read::Result<MipsOption<'data, Elf>> {
+        let header = self
+            .data
+            .read_at::<elf::MipsOptionHeader<Elf::Endian>>(0)
+            .read_error("ELF MIPS option is too short")?;
+        let offset = mem::size_of::<elf::MipsOptionHeader<Elf::Endian>>();
+        let size = usize::from(header.size);
+        let desc = size
+            .checked_sub(offset)
+            .and_then(|descsz| self.data.read_bytes_at(offset, descsz).ok())
+            .read_error("Invalid ELF MIPS option size")?
+            .0;
+        self.data
+            .skip(size)
+            .read_error("Invalid ELF MIPS option size")?;
+        Ok(MipsOption {
+            endian: self.endian,
+            header,
+            desc,
+        })
+    }
+}
+
+impl<'data, Elf: FileHeader> Iterator for MipsOptionIterator<'data, Elf> {
+    type Item = read::Result<MipsOption<'data, Elf>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next().transpose()
+    }
+}
+
+/// An entry in an ELF `SHT_MIPS_OPTIONS` section.
+#[derive(Debug)]
+pub struct MipsOption<'data, Elf: FileHeader> {
+    endian: Elf::Endian,
+    header: &'data elf::MipsOptionHeader<Elf::Endian>,
+    desc: &'data [u8],
+}
+
+impl<'data, Elf: FileHeader> MipsOption<'data, Elf> {
+    /// Return the header of the option.
+    pub fn header(&self) -> &'data elf::MipsOptionHeader<Elf::Endian> {
+        self.header
+    }
+
+    /// Return the `kind` field of the header.
+    ///
+    /// This is one of the `ODK_*` constants.
+    pub fn kind(&self) -> u8 {
+        self.header.kind
+    }
+
+    /// Return the `section` field of the header.
+    ///
+    /// This is the index of the section that the option applies to,
+    /// or 0 for options that apply to the whole file.
+    pub fn section(&self) -> SectionIndex {
+        SectionIndex(self.header.section.get(self.endian).into())
+    }
+
+    /// Return the `info` field of the header.
+    pub fn info(&self) -> u32 {
+        self.header.info.get(self.endian)
+    }
+
+    /// Return the descriptor following the header.
+    pub fn desc(&self) -> &'data [u8] {
+        self.desc
+    }
+
+    /// Return the descriptor of an `ODK_REGINFO` option in a 32-bit file.
+    ///
+    /// Returns `Ok(None)` if the kind is not `ODK_REGINFO`.
+    pub fn reginfo32(&self) -> read::Result<Option<&'data elf::MipsRegInfo32<Elf::Endian>>> {
+        if u32::from(self.kind()) != elf::ODK_REGINFO {
+            return Ok(None);
+        }
+        Bytes(self.desc)
+            .read()
+            .read_error("Invalid ELF MIPS reginfo option size")
+            .map(Some)
+    }
+
+    /// Return the descriptor of an `ODK_REGINFO` option in a 64-bit file.
+    ///
+    /// Returns `Ok(None)` if the kind is not `ODK_REGINFO`.
+    pub fn reginfo64(&self) -> read::Result<Option<&'data elf::MipsRegInfo64<Elf::Endian>>> {
+        if u32::from(self.kind()) != elf::ODK_REGINFO {
+            return Ok(None);
+        }
+        Bytes(self.desc)
+            .read()
+            .read_error("Invalid ELF MIPS reginfo option size")
+            .map(Some)
+    }
+
+    /// Return the descriptor of an `ODK_HWAND` or `ODK_HWOR` option.
+    ///
+    /// Returns `Ok(None)` if the kind is not `ODK_HWAND` or `ODK_HWOR`.
+    pub fn hw(&self) -> read::Result<Option<&'data elf::MipsOptionHw<Elf::Endian>>> {
+        let kind = u32::from(self.kind());
+        if kind != elf::ODK_HWAND && kind != elf::ODK_HWOR {
+            return Ok(None);
+        }
+        Bytes(self.desc)
+            .read()
+            .read_error("Invalid ELF MIPS hardware option size")
+            .map(Some)
+    }
+}
@@ -76,3 +76,21 @@ pub use version::*;
 
 mod attributes;
 pub use attributes::*;
+
+mod arm;
+pub use arm::*;
+
+mod mips;
+pub use mips::*;
+
+mod plt;
+pub use plt::*;
+
+mod image;
+pub use image::*;
+
+mod dwp;
+pub use dwp::*;
+
+mod kmod;
+pub use kmod::*;
@@ -0,0 +1,319 @@
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use crate::elf;
+use crate::endian::Endian;
+use crate::read::{self, ReadRef, SymbolIndex};
+
+use super::{ElfFile, FileHeader, Rela, SectionHeader};
+
+/// An entry in the procedure linkage table of an ELF file.
+///
+/// Returned by [`ElfFile::plt_entries`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfPltEntry {
+    /// The address of the PLT entry.
+    pub address: u64,
+    /// The address of the GOT entry that the PLT entry jumps through.
+    pub got_address: u64,
+    /// The index of the dynamic symbol that the GOT entry is relocated against.
+    ///
+    /// This is `None` for `IRELATIVE` relocations.
+    pub symbol: Option<SymbolIndex>,
+    /// The type of the dynamic relocation for the GOT entry.
+    ///
+    /// This is usually a `JUMP_SLOT`, `GLOB_DAT` or `IRELATIVE` relocation.
+    pub r_type: u32,
+    /// The addend of the dynamic relocation for the GOT entry.
+    ///
+    /// For `IRELATIVE` relocations, this is the address of the ifunc resolver.
+    /// For relocations without an explicit addend, this is read from the GOT entry.
+    pub addend: i64,
+}
+
+impl<'data, Elf, R> ElfFile<'data, Elf, R>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    /// Return the entries in the procedure linkage table.
+    ///
+    /// This decodes the instructions in the `.plt`, `.plt.sec`, `.plt.got` and `.iplt`
+    /// sections to find the GOT entry that each PLT entry jumps through, and then
+    /// finds the dynamic relocation for that GOT entry. PLT entries that don't
+    /// have a dynamic relocation are omitted, such as the first entry in `.plt`
+    /// which calls the dynamic linker.
+    ///
+    /// This is supported for x86, x86-64, AArch64, ARM and RISC-V. An empty list
+    /// is returned for other architectures.
+    ///
+    /// The entries are sorted by address.
+    pub fn plt_entries(&self) -> read::Result<Vec<ElfPltEntry>> {
+        let endian = self.endian;
+        let machine = self.header.e_machine(endian);
+        let decode: fn(u64, &[u8], u64, &mut Vec<(u64, u64)>) = match machine {
+            elf::EM_X86_64 => plt_x86_64,
+            elf::EM_386 => plt_i386,
+            elf::EM_AARCH64 => plt_aarch64,
+            elf::EM_ARM => plt_arm,
+            elf::EM_RISCV => plt_riscv,
+            _ => return Ok(Vec::new()),
+        };
+
+        // The i386 PIC PLT is relative to the address of the `.got.plt` section.
+        let got_plt = self
+            .sections
+            .section_by_name(endian, b".got.plt")
+            .map(|(_, section)| section.sh_addr(endian).into())
+            .unwrap_or(0);
+
+        let mut plt = Vec::new();
+        for section in self.sections.iter() {
+            let name = self.sections.section_name(endian, section)?;
+            if !matches!(name, b".plt" | b".plt.sec" | b".plt.got" | b".iplt") {
+                continue;
+            }
+            let data = section.data(endian, self.data)?;
+            decode(section.sh_addr(endian).into(), data, got_plt, &mut plt);
+        }
+        if plt.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let is_mips64el = self.header.is_mips64el(endian);
+        let mut got = Vec::new();
+        for section in self.sections.iter() {
+            if section.sh_flags(endian).into() & u64::from(elf::SHF_ALLOC) == 0 {
+                continue;
+            }
+            if let Some((relocations, _)) = section.rela(endian, self.data)? {
+                for rela in relocations {
+                    got.push((
+                        rela.r_offset(endian).into(),
+                        rela.symbol(endian, is_mips64el),
+                        rela.r_type(endian, is_mips64el),
+                        Some(rela.r_addend(endian).into()),
+                    ));
+                }
+            } else if let Some((relocations, _)) = section.rel(endian, self.data)? {
+                for rel in relocations {
+                    let rela = Elf::Rela::from(*rel);
+                    got.push((
+                        rela.r_offset(endian).into(),
+                        rela.symbol(endian, is_mips64el),
+                        rela.r_type(endian, is_mips64el),
+                        None,
+                    ));
+                }
+            }
+        }
+        got.sort_by_key(|entry| entry.0);
+
+        let mut entries = Vec::new();
+        for (address, got_address) in plt {
+            let Ok(index) = got.binary_search_by_key(&got_address, |entry| entry.0) else {
+                continue;
+            };
+            let (_, symbol, r_type, addend) = got[index];
+            let addend = match addend {
+                Some(addend) => addend,
+                None => self.read_word(got_address)?.unwrap_or(0) as i64,
+            };
+            entries.push(ElfPltEntry {
+                address,
+                got_address,
+                symbol,
+                r_type,
+                addend,
+            });
+        }
+        entries.sort_by_key(|entry| entry.address);
+        Ok(entries)
+    }
+
+    /// Read an address sized word from the allocated section data at the given address.
+    fn read_word(&self, address: u64) -> read::Result<Option<u64>> {
+        let endian = self.endian;
+        for section in self.sections.iter() {
+            if section.sh_flags(endian).into() & u64::from(elf::SHF_ALLOC) == 0 {
+                continue;
+            }
+            let section_address = section.sh_addr(endian).into();
+            let Some(offset) = address.checked_sub(section_address) else {
+                continue;
+            };
+            if offset >= section.sh_size(endian).into() {
+                continue;
+            }
+            let data = section.data(endian, self.data)?;
+            let Ok(offset) = offset.try_into() else {
+                return Ok(None);
+            };
+            return Ok(if self.header.is_type_64() {
+                data.get(offset..)
+                    .and_then(|data| data.get(..8))
+                    .map(|bytes| endian.read_u64_bytes(bytes.try_into().unwrap()))
+            } else {
+                data.get(offset..)
+                    .and_then(|data| data.get(..4))
+                    .map(|bytes| endian.read_u32_bytes(bytes.try_into().unwrap()).into())
+            });
+        }
+        Ok(None)
+    }
+}
+
+fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Find `jmp *disp32(%rip)` instructions.
+fn plt_x86_64(address: u64, data: &[u8], _got_plt: u64, plt: &mut Vec<(u64, u64)>) {
+    let mut offset = 0;
+    while offset + 6 <= data.len() {
+        if data[offset] != 0xff || data[offset + 1] != 0x25 {
+            offset += 1;
+            continue;
+        }
+        let disp = read_u32_le(data, offset + 2).unwrap() as i32;
+        let got_address = address
+            .wrapping_add(offset as u64 + 6)
+            .wrapping_add(disp as i64 as u64);
+        let entry = address.wrapping_add(x86_entry_start(data, offset) as u64);
+        plt.push((entry, got_address));
+        offset += 6;
+    }
+}
+
+/// Find `jmp *abs32` and `jmp *disp32(%ebx)` instructions.
+fn plt_i386(address: u64, data: &[u8], got_plt: u64, plt: &mut Vec<(u64, u64)>) {
+    let mut offset = 0;
+    while offset + 6 <= data.len() {
+        let got_address = match (data[offset], data[offset + 1]) {
+            (0xff, 0x25) => u64::from(read_u32_le(data, offset + 2).unwrap()),
+            (0xff, 0xa3) => {
+                let disp = read_u32_le(data, offset + 2).unwrap();
+                u64::from((got_plt as u32).wrapping_add(disp))
+            }
+            _ => {
+                offset += 1;
+                continue;
+            }
+        };
+        let entry = address.wrapping_add(x86_entry_start(data, offset) as u64);
+        plt.push((entry, got_address));
+        offset += 6;
+    }
+}
+
+/// Include any `endbr` and `bnd` prefixes in the entry.
+fn x86_entry_start(data: &[u8], mut offset: usize) -> usize {
+    if offset >= 1 && data[offset - 1] == 0xf2 {
+        offset -= 1;
+    }
+    if offset >= 4 && matches!(data[offset - 4..offset], [0xf3, 0x0f, 0x1e, 0xfa | 0xfb]) {
+        offset -= 4;
+    }
+    offset
+}
+
+/// Find `adrp x16, page` followed by `ldr x17, [x16, offset]`.
+fn plt_aarch64(address: u64, data: &[u8], _got_plt: u64, plt: &mut Vec<(u64, u64)>) {
+    let mut offset = 0;
+    while let Some(mut insn) = read_u32_le(data, offset) {
+        let start = offset;
+        let mut next = offset + 4;
+        // Skip `bti c`.
+        if insn == 0xd503_245f {
+            match read_u32_le(data, next) {
+                Some(value) => insn = value,
+                None => break,
+            }
+            next += 4;
+        }
+        offset += 4;
+        if insn & 0x9f00_0000 != 0x9000_0000 {
+            continue;
+        }
+        let Some(ldr) = read_u32_le(data, next) else {
+            break;
+        };
+        if ldr >> 22 != 0x3e5 {
+            continue;
+        }
+        let pc = address.wrapping_add((next - 4) as u64);
+        let immlo = u64::from((insn >> 29) & 0x3);
+        let immhi = u64::from((insn >> 5) & 0x7_ffff);
+        // Sign extend the 21-bit page offset.
+        let page_offset = ((((immhi << 2) | immlo) << 43) as i64 >> 31) as u64;
+        let page = (pc & !0xfff).wrapping_add(page_offset);
+        let got_address = page.wrapping_add(u64::from((ldr >> 10) & 0xfff) << 3);
+        plt.push((address.wrapping_add(start as u64), got_address));
+        offset = next + 4;
+    }
+}
+
+/// Find `add ip, pc, #imm` followed by `add ip, ip, #imm` and `ldr pc, [ip, #imm]!`.
+fn plt_arm(address: u64, data: &[u8], _got_plt: u64, plt: &mut Vec<(u64, u64)>) {
+    fn arm_imm(insn: u32) -> u32 {
+        (insn & 0xff).rotate_right(2 * ((insn >> 8) & 0xf))
+    }
+
+    let mut offset = 0;
+    while let Some(insn) = read_u32_le(data, offset) {
+        let start = offset;
+        offset += 4;
+        if insn & 0xffff_f000 != 0xe28f_c000 {
+            continue;
+        }
+        let (Some(add), Some(ldr)) = (
+            read_u32_le(data, offset),
+            offset
+                .checked_add(4)
+                .and_then(|offset| read_u32_le(data, offset)),
+        ) else {
+            break;
+        };
+        if add & 0xffff_f000 != 0xe28c_c000 || ldr & 0xffff_f000 != 0xe5bc_f000 {
+            continue;
+        }
+        let pc = (address as u32).wrapping_add(start as u32).wrapping_add(8);
+        let got_address = pc
+            .wrapping_add(arm_imm(insn))
+            .wrapping_add(arm_imm(add))
+            .wrapping_add(ldr & 0xfff);
+        plt.push((address.wrapping_add(start as u64), got_address.into()));
+        offset += 8;
+    }
+}
+
+/// Find `auipc t3, hi` followed by `ld t3, lo(t3)` or `lw t3, lo(t3)`.
+fn plt_riscv(address: u64, data: &[u8], _got_plt: u64, plt: &mut Vec<(u64, u64)>) {
+    // `auipc t3, 0`
+    const AUIPC_T3: u32 = 0x0000_0e17;
+    // `ld t3, 0(t3)` and `lw t3, 0(t3)`
+    const LD_T3: u32 = 0x000e_3e03;
+    const LW_T3: u32 = 0x000e_2e03;
+
+    let mut offset = 0;
+    while let Some(insn) = read_u32_le(data, offset) {
+        let start = offset;
+        offset += 4;
+        if insn & 0xfff != AUIPC_T3 {
+            continue;
+        }
+        let Some(load) = read_u32_le(data, offset) else {
+            break;
+        };
+        if load & 0xf_ffff != LD_T3 && load & 0xf_ffff != LW_T3 {
+            continue;
+        }
+        let hi = i64::from((insn & 0xffff_f000) as i32);
+        let lo = i64::from((load as i32) >> 20);
+        let entry = address.wrapping_add(start as u64);
+        let got_address = entry.wrapping_add(hi.wrapping_add(lo) as u64);
+        plt.push((entry, got_address));
+        offset += 4;
+    }
+}
@@ -361,12 +361,21 @@ fn parse_relocation<Elf: FileHeader>(
             elf::R_68K_PLT8 => (K::PltRelative, g, 8),
             _ => unknown,
         },
-        elf::EM_MIPS => match r_type {
-            elf::R_MIPS_16 => (K::Absolute, g, 16),
-            elf::R_MIPS_32 => (K::Absolute, g, 32),
-            elf::R_MIPS_64 => (K::Absolute, g, 64),
-            _ => unknown,
-        },
+        elf::EM_MIPS => {
+            // MIPS64 stores up to three relocation types in `r_type`.
+            // We only support relocations that use a single type.
+            let r_type = if header.is_type_64() {
+                elf::Mips64RelocationType::from_r_type(r_type).single()
+            } else {
+                Some(r_type)
+            };
+            match r_type {
+                Some(elf::R_MIPS_16) => (K::Absolute, g, 16),
+                Some(elf::R_MIPS_32) => (K::Absolute, g, 32),
+                Some(elf::R_MIPS_64) => (K::Absolute, g, 64),
+                _ => unknown,
+            }
+        }
         elf::EM_MSP430 => match r_type {
             elf::R_MSP430_32 => (K::Absolute, g, 32),
             elf::R_MSP430_16_BYTE => (K::Absolute, g, 16),
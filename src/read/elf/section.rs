@@ -5,15 +5,15 @@ use crate::elf;
 use crate::endian::{self, Endianness, U32Bytes};
 use crate::pod::{self, Pod};
 use crate::read::{
-    self, gnu_compression, CompressedData, CompressedFileRange, CompressionFormat, Error,
-    ObjectSection, ReadError, ReadRef, RelocationMap, SectionFlags, SectionIndex, SectionKind,
-    StringTable,
+    self, gnu_compression, Bytes, CompressedData, CompressedFileRange, CompressionFormat, Error,
+    ObjectSection, Permissions, ReadError, ReadRef, RelocationMap, SectionFlags, SectionIndex,
+    SectionKind, StringTable,
 };
 
 use super::{
-    AttributesSection, CompressionHeader, ElfFile, ElfSectionRelocationIterator, FileHeader,
-    GnuHashTable, HashTable, NoteIterator, RelocationSections, RelrIterator, SymbolTable,
-    VerdefIterator, VerneedIterator, VersionTable,
+    ArmExidxIterator, AttributesSection, CompressionHeader, ElfFile, ElfSectionRelocationIterator,
+    FileHeader, GnuHashTable, HashTable, MipsOptionIterator, NoteIterator, RelocationSections,
+    RelrIterator, SymbolTable, VerdefIterator, VerneedIterator, VersionTable,
 };
 
 /// The table of section headers in an ELF file.
@@ -509,6 +509,12 @@ impl<'data, 'file, Elf: FileHeader, R: ReadRef<'data>> ElfSection<'data, 'file,
         }
     }
 
+    fn is_debug(&self) -> bool {
+        self.name_bytes().map_or(false, |name| {
+            name.starts_with(b".debug_") || name.starts_with(b".zdebug_")
+        })
+    }
+
     // Try GNU-style "ZLIB" header decompression.
     fn maybe_compressed_gnu(&self) -> read::Result<Option<CompressedFileRange>> {
         if !self
@@ -632,6 +638,13 @@ where
                     } else {
                         SectionKind::ReadOnlyData
                     }
+                } else if self.is_debug() {
+                    // This includes the `.dwo` sections and package indices of split DWARF.
+                    if flags & u64::from(elf::SHF_STRINGS) != 0 {
+                        SectionKind::DebugString
+                    } else {
+                        SectionKind::Debug
+                    }
                 } else if flags & u64::from(elf::SHF_STRINGS) != 0 {
                     SectionKind::OtherString
                 } else {
@@ -678,6 +691,18 @@ where
             sh_flags: self.section.sh_flags(self.file.endian).into(),
         }
     }
+
+    fn permissions(&self) -> Permissions {
+        let sh_flags = self.section.sh_flags(self.file.endian).into();
+        if sh_flags & u64::from(elf::SHF_ALLOC) == 0 {
+            return Permissions::default();
+        }
+        Permissions::new(
+            true,
+            sh_flags & u64::from(elf::SHF_WRITE) != 0,
+            sh_flags & u64::from(elf::SHF_EXECINSTR) != 0,
+        )
+    }
 }
 
 /// A trait for generic access to [`elf::SectionHeader32`] and [`elf::SectionHeader64`].
@@ -1099,6 +1124,86 @@ pub trait SectionHeader: Debug + Pod {
         self.attributes(endian, data).map(Some)
     }
 
+    /// Return an iterator over the entries of a `SHT_ARM_EXIDX` section.
+    ///
+    /// The caller should check that `e_machine` is `EM_ARM`.
+    ///
+    /// Returns `Ok(None)` if the section type is not `SHT_ARM_EXIDX`.
+    /// Returns `Err` for invalid values.
+    fn arm_exidx<'data, R: ReadRef<'data>>(
+        &self,
+        endian: Self::Endian,
+        data: R,
+    ) -> read::Result<Option<ArmExidxIterator<'data, Self::Elf>>> {
+        if self.sh_type(endian) != elf::SHT_ARM_EXIDX {
+            return Ok(None);
+        }
+        let address = self.sh_addr(endian).into();
+        let data = self.data(endian, data)?;
+        Ok(Some(ArmExidxIterator::new(endian, address, data)))
+    }
+
+    /// Return the contents of a `SHT_MIPS_REGINFO` section.
+    ///
+    /// This section type is only used by 32-bit MIPS files.
+    /// The caller should check that `e_machine` is `EM_MIPS`.
+    ///
+    /// Returns `Ok(None)` if the section type is not `SHT_MIPS_REGINFO`.
+    /// Returns `Err` for invalid values.
+    fn mips_reginfo<'data, R: ReadRef<'data>>(
+        &self,
+        endian: Self::Endian,
+        data: R,
+    ) -> read::Result<Option<&'data elf::MipsRegInfo32<Self::Endian>>> {
+        if self.sh_type(endian) != elf::SHT_MIPS_REGINFO {
+            return Ok(None);
+        }
+        let data = self.data(endian, data)?;
+        Bytes(data)
+            .read()
+            .read_error("Invalid ELF MIPS reginfo section size")
+            .map(Some)
+    }
+
+    /// Return an iterator over the entries of a `SHT_MIPS_OPTIONS` section.
+    ///
+    /// The caller should check that `e_machine` is `EM_MIPS`.
+    ///
+    /// Returns `Ok(None)` if the section type is not `SHT_MIPS_OPTIONS`.
+    /// Returns `Err` for invalid values.
+    fn mips_options<'data, R: ReadRef<'data>>(
+        &self,
+        endian: Self::Endian,
+        data: R,
+    ) -> read::Result<Option<MipsOptionIterator<'data, Self::Elf>>> {
+        if self.sh_type(endian) != elf::SHT_MIPS_OPTIONS {
+            return Ok(None);
+        }
+        let data = self.data(endian, data)?;
+        Ok(Some(MipsOptionIterator::new(endian, data)))
+    }
+
+    /// Return the contents of a `SHT_MIPS_ABIFLAGS` section.
+    ///
+    /// The caller should check that `e_machine` is `EM_MIPS`.
+    ///
+    /// Returns `Ok(None)` if the section type is not `SHT_MIPS_ABIFLAGS`.
+    /// Returns `Err` for invalid values.
+    fn mips_abiflags<'data, R: ReadRef<'data>>(
+        &self,
+        endian: Self::Endian,
+        data: R,
+    ) -> read::Result<Option<&'data elf::MipsAbiFlags<Self::Endian>>> {
+        if self.sh_type(endian) != elf::SHT_MIPS_ABIFLAGS {
+            return Ok(None);
+        }
+        let data = self.data(endian, data)?;
+        Bytes(data)
+            .read()
+            .read_error("Invalid ELF MIPS abiflags section size")
+            .map(Some)
+    }
+
     /// Parse the contents of the section as attributes.
     ///
     /// This function does not check whether section type corresponds
@@ -4,7 +4,7 @@ use core::{slice, str};
 use crate::elf;
 use crate::endian::{self, Endianness};
 use crate::pod::{self, Pod};
-use crate::read::{self, ObjectSegment, ReadError, ReadRef, SegmentFlags};
+use crate::read::{self, ObjectSegment, Permissions, ReadError, ReadRef, SegmentFlags};
 
 use super::{ElfFile, FileHeader, NoteIterator};
 
@@ -132,18 +132,34 @@ where
 
     #[inline]
     fn name_bytes(&self) -> read::Result<Option<&[u8]>> {
-        Ok(None)
+        Ok(self.name()?.map(str::as_bytes))
     }
 
-    #[inline]
     fn name(&self) -> read::Result<Option<&str>> {
-        Ok(None)
+        let endian = self.file.endian;
+        let header = self.file.header;
+        let name = elf::name_for_p_type(
+            header.e_machine(endian),
+            header.e_ident().os_abi,
+            self.segment.p_type(endian),
+        );
+        Ok(name.map(|name| name.trim_start_matches("PT_")))
     }
 
     #[inline]
     fn flags(&self) -> SegmentFlags {
+        let p_type = self.segment.p_type(self.file.endian);
+        let p_flags = self.segment.p_flags(self.file.endian);
+        SegmentFlags::Elf { p_type, p_flags }
+    }
+
+    fn permissions(&self) -> Permissions {
         let p_flags = self.segment.p_flags(self.file.endian);
-        SegmentFlags::Elf { p_flags }
+        Permissions::new(
+            p_flags & elf::PF_R != 0,
+            p_flags & elf::PF_W != 0,
+            p_flags & elf::PF_X != 0,
+        )
     }
 }
 
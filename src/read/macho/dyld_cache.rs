@@ -1,11 +1,17 @@
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::convert::TryInto;
 use core::fmt::{self, Debug};
 use core::{mem, slice};
 
 use crate::endian::{Endian, Endianness, U16, U32, U64};
 use crate::macho;
-use crate::read::{Architecture, Error, File, ReadError, ReadRef, Result};
+use crate::pod::Pod;
+use crate::read::{
+    AddressSize, Architecture, Error, File, ReadError, ReadRef, Result, StringTable,
+};
+
+use super::{MachHeader, Nlist, Segment, SymbolTable};
 
 /// A parsed representation of the dyld shared cache.
 #[derive(Debug)]
@@ -18,6 +24,8 @@ where
     data: R,
     /// The first entry is the main cache file, and the rest are subcaches.
     files: Vec<DyldFile<'data, E, R>>,
+    /// The data of the `.symbols` subcache, if present.
+    symbols_data: Option<R>,
     images: &'data [macho::DyldCacheImageInfo<E>],
     arch: Architecture,
 }
@@ -51,6 +59,10 @@ const MIN_HEADER_SIZE_SUBCACHES_V1: u32 = 0x1c8;
 // This is the offset of the end of the cache_sub_type field.
 const MIN_HEADER_SIZE_SUBCACHES_V2: u32 = 0x1d0;
 
+// This is the offset of the symbol_file_uuid field.
+// Caches with this field use `DyldCacheLocalSymbolsEntry64`.
+const MIN_HEADER_SIZE_LOCAL_SYMBOLS_64: u32 = 0x190;
+
 impl<'data, E, R> DyldCache<'data, E, R>
 where
     E: Endian,
@@ -140,15 +152,14 @@ where
         }
 
         // Read the .symbols SubCache, if present.
-        // Other than the UUID verification, the symbols SubCache is currently unused.
-        let _symbols_subcache = match symbols_subcache_data_and_uuid {
+        // This is only used for the local symbols.
+        let symbols_data = match symbols_subcache_data_and_uuid {
             Some((data, uuid)) => {
                 let header = macho::DyldCacheHeader::<E>::parse(data)?;
                 if header.uuid != uuid {
                     return Err(Error("Unexpected .symbols SubCache UUID"));
                 }
-                let mappings = header.mappings(endian, data)?;
-                Some(DyldFile { data, mappings })
+                Some(data)
             }
             None => None,
         };
@@ -158,6 +169,7 @@ where
             endian,
             data,
             files,
+            symbols_data,
             images,
             arch,
         })
@@ -214,6 +226,52 @@ where
         }
         None
     }
+
+    /// Find the mapping that contains the given address.
+    ///
+    /// This searches the mappings of the main cache file and all subcaches.
+    pub fn mapping_for_address(&self, address: u64) -> Option<DyldCacheMapping<'data, E, R>> {
+        self.mappings()
+            .find(|mapping| mapping.contains_address(address))
+    }
+
+    /// Read the bytes at the given address.
+    ///
+    /// The bytes are read from the cache or subcache data containing the mapping
+    /// for the address. The range must be contained within a single mapping.
+    pub fn read_bytes_at_address(&self, address: u64, size: u64) -> Result<&'data [u8]> {
+        self.mapping_for_address(address)
+            .read_error("Address not found in any mapping")?
+            .read_bytes_at_address(address, size)
+    }
+
+    /// Read a value of type `T` at the given address.
+    ///
+    /// The value must be contained within a single mapping.
+    pub fn read_at_address<T: Pod>(&self, address: u64) -> Result<&'data T> {
+        let bytes = self.read_bytes_at_address(address, mem::size_of::<T>() as u64)?;
+        crate::pod::from_bytes(bytes)
+            .map(|(value, _)| value)
+            .read_error("Invalid dyld cache data alignment")
+    }
+
+    /// Find the image that contains the given address.
+    ///
+    /// An image contains an address if the address is within one of its segments.
+    /// The `__LINKEDIT` segment is ignored, since it is shared by all images.
+    ///
+    /// This parses the load commands of each image until a match is found.
+    pub fn image_containing_address<'cache>(
+        &'cache self,
+        address: u64,
+    ) -> Result<Option<DyldCacheImage<'data, 'cache, E, R>>> {
+        for image in self.images() {
+            if image.contains_address(address)? {
+                return Ok(Some(image));
+            }
+        }
+        Ok(None)
+    }
 }
 
 /// An iterator over all the images (dylibs) in the dyld shared cache.
@@ -285,6 +343,420 @@ where
     pub fn parse_object(&self) -> Result<File<'data, R>> {
         File::parse_dyld_cache_image(self)
     }
+
+    /// Return true if the given address is within one of the segments of this image.
+    ///
+    /// The `__LINKEDIT` segment is ignored, since it is shared by all images.
+    pub fn contains_address(&self, address: u64) -> Result<bool> {
+        match self.cache.architecture().address_size() {
+            Some(AddressSize::U64) => self.segments_contain::<macho::MachHeader64<E>>(address),
+            Some(AddressSize::U32) => self.segments_contain::<macho::MachHeader32<E>>(address),
+            _ => Err(Error("Unsupported dyld cache architecture")),
+        }
+    }
+
+    /// Return the local symbols for this image.
+    ///
+    /// The local symbols are removed from the symbol table of each image
+    /// when the cache is built, and are stored separately in either the main
+    /// cache file or the `.symbols` subcache.
+    ///
+    /// Returns `Ok(None)` if the cache does not contain local symbols for this image.
+    pub fn local_symbols<Mach: MachHeader<Endian = E>>(
+        &self,
+    ) -> Result<Option<SymbolTable<'data, Mach, R>>> {
+        let endian = self.cache.endian;
+        let data = self.cache.symbols_data.unwrap_or(self.cache.data);
+        let header = macho::DyldCacheHeader::<E>::parse(data)?;
+        let info_offset = header.local_symbols_offset.get(endian);
+        if info_offset == 0 {
+            return Ok(None);
+        }
+        let info = data
+            .read_at::<macho::DyldCacheLocalSymbolsInfo<E>>(info_offset)
+            .read_error("Invalid dyld cache local symbols info")?;
+
+        // The entries identify each image by its offset from the start of the cache.
+        let base_address = self
+            .cache
+            .mappings()
+            .next()
+            .read_error("Missing dyld cache mappings")?
+            .address();
+        let dylib_offset = self
+            .image_info
+            .address
+            .get(endian)
+            .wrapping_sub(base_address);
+        let entries_offset = info_offset + u64::from(info.entries_offset.get(endian));
+        let entries_count = info.entries_count.get(endian) as usize;
+        let main_header = macho::DyldCacheHeader::<E>::parse(self.cache.data)?;
+        let entry = if main_header.mapping_offset.get(endian) >= MIN_HEADER_SIZE_LOCAL_SYMBOLS_64 {
+            data.read_slice_at::<macho::DyldCacheLocalSymbolsEntry64<E>>(
+                entries_offset,
+                entries_count,
+            )
+            .read_error("Invalid dyld cache local symbols entries")?
+            .iter()
+            .find(|entry| entry.dylib_offset.get(endian) == dylib_offset)
+            .map(|entry| (entry.nlist_start_index, entry.nlist_count))
+        } else {
+            data.read_slice_at::<macho::DyldCacheLocalSymbolsEntry<E>>(
+                entries_offset,
+                entries_count,
+            )
+            .read_error("Invalid dyld cache local symbols entries")?
+            .iter()
+            .find(|entry| u64::from(entry.dylib_offset.get(endian)) == dylib_offset)
+            .map(|entry| (entry.nlist_start_index, entry.nlist_count))
+        };
+        let Some((start, count)) = entry else {
+            return Ok(None);
+        };
+
+        let nlist_offset = info_offset
+            + u64::from(info.nlist_offset.get(endian))
+            + u64::from(start.get(endian)) * mem::size_of::<Mach::Nlist>() as u64;
+        let symbols = data
+            .read_slice_at(nlist_offset, count.get(endian) as usize)
+            .read_error("Invalid dyld cache local symbols nlist size or alignment")?;
+        let strings_offset = info_offset + u64::from(info.strings_offset.get(endian));
+        let strings_end = strings_offset + u64::from(info.strings_size.get(endian));
+        let strings = StringTable::new(data, strings_offset, strings_end);
+        Ok(Some(SymbolTable::new(symbols, strings)))
+    }
+
+    /// Reconstruct a standalone Mach-O file for this image.
+    ///
+    /// The segments of the image are copied from the cache and laid out
+    /// contiguously in the new file, and the file offsets in the segment and
+    /// section headers are updated to match.
+    ///
+    /// A new `__LINKEDIT` segment is built that contains only the data
+    /// referenced by the load commands of this image. The symbol table
+    /// includes the local symbols from the cache if they are available.
+    ///
+    /// Pointers in the segment data are not modified, so they may still be
+    /// in the cache's slid or chained format.
+    pub fn extract(&self) -> Result<Vec<u8>> {
+        match self.cache.architecture().address_size() {
+            Some(AddressSize::U64) => self.extract_image::<macho::MachHeader64<E>>(),
+            Some(AddressSize::U32) => self.extract_image::<macho::MachHeader32<E>>(),
+            _ => Err(Error("Unsupported dyld cache architecture")),
+        }
+    }
+
+    fn extract_image<Mach: MachHeader<Endian = E>>(&self) -> Result<Vec<u8>> {
+        let cache = self.cache;
+        let endian = cache.endian;
+        let (data, header_offset) = self.image_data_and_offset()?;
+        let header = Mach::parse(data, header_offset)?;
+        let is_64 = header.is_type_64();
+        let page_size = match cache.architecture() {
+            Architecture::Aarch64 | Architecture::Aarch64_Ilp32 => 0x4000,
+            _ => 0x1000,
+        };
+
+        // Copy the segment data, and remember the load commands that need updating.
+        let mut out = Vec::new();
+        let mut segments = Vec::new();
+        let mut linkedit = None;
+        let mut symtab = None;
+        let mut dysymtab = None;
+        let mut dyld_info = None;
+        let mut linkedit_data = Vec::new();
+        let mut command_offset = mem::size_of::<Mach>();
+        let mut commands = header.load_commands(endian, data, header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let Some((segment, _)) = Mach::Segment::from_command(command)? {
+                if segment.name() == macho::SEG_LINKEDIT.as_bytes() {
+                    linkedit = Some((command_offset, segment));
+                } else {
+                    let file_offset = align(out.len(), page_size);
+                    let file_size = segment.filesize(endian).into();
+                    if file_size != 0 {
+                        let bytes = cache
+                            .read_bytes_at_address(segment.vmaddr(endian).into(), file_size)?;
+                        out.resize(file_offset, 0);
+                        out.extend_from_slice(bytes);
+                    }
+                    segments.push((command_offset, file_offset));
+                }
+            } else {
+                match command.cmd() {
+                    macho::LC_SYMTAB => symtab = Some((command_offset, command.data()?)),
+                    macho::LC_DYSYMTAB => dysymtab = Some((command_offset, command.data()?)),
+                    macho::LC_DYLD_INFO | macho::LC_DYLD_INFO_ONLY => {
+                        dyld_info = Some((command_offset, command.data()?))
+                    }
+                    macho::LC_CODE_SIGNATURE
+                    | macho::LC_SEGMENT_SPLIT_INFO
+                    | macho::LC_FUNCTION_STARTS
+                    | macho::LC_DATA_IN_CODE
+                    | macho::LC_DYLIB_CODE_SIGN_DRS
+                    | macho::LC_LINKER_OPTIMIZATION_HINT
+                    | macho::LC_DYLD_EXPORTS_TRIE
+                    | macho::LC_DYLD_CHAINED_FIXUPS => {
+                        linkedit_data.push((command_offset, command.data()?))
+                    }
+                    _ => {}
+                }
+            }
+            command_offset += command.cmdsize() as usize;
+        }
+        if segments.first().map(|&(_, file_offset)| file_offset) != Some(0) {
+            return Err(Error("Missing dyld cache image header segment"));
+        }
+        if out.len() < command_offset {
+            return Err(Error("Invalid dyld cache image header segment size"));
+        }
+
+        // Clear the flag that indicates the image is in the cache.
+        let flags = header.flags(endian) & !macho::MH_DYLIB_IN_CACHE;
+        data_mut::<U32<E>>(&mut out, 24)?.0.set(endian, flags);
+
+        // Update the file offsets in the segments and sections.
+        for &(offset, file_offset) in &segments {
+            if is_64 {
+                let (segment, section_data) =
+                    data_mut::<macho::SegmentCommand64<E>>(&mut out, offset)?;
+                let vmaddr = segment.vmaddr.get(endian);
+                segment.fileoff.set(endian, file_offset as u64);
+                let nsects = segment.nsects.get(endian) as usize;
+                let sections =
+                    crate::pod::slice_from_bytes_mut::<macho::Section64<E>>(section_data, nsects)
+                        .ok()
+                        .read_error("Invalid Mach-O number of sections")?
+                        .0;
+                for section in sections {
+                    if section.offset.get(endian) != 0 {
+                        let offset = section.addr.get(endian) - vmaddr + file_offset as u64;
+                        section.offset.set(endian, offset_u32(offset)?);
+                    }
+                }
+            } else {
+                let (segment, section_data) =
+                    data_mut::<macho::SegmentCommand32<E>>(&mut out, offset)?;
+                let vmaddr = segment.vmaddr.get(endian);
+                segment.fileoff.set(endian, offset_u32(file_offset as u64)?);
+                let nsects = segment.nsects.get(endian) as usize;
+                let sections =
+                    crate::pod::slice_from_bytes_mut::<macho::Section32<E>>(section_data, nsects)
+                        .ok()
+                        .read_error("Invalid Mach-O number of sections")?
+                        .0;
+                for section in sections {
+                    if section.offset.get(endian) != 0 {
+                        let offset = section.addr.get(endian) - vmaddr + file_offset as u32;
+                        section.offset.set(endian, offset);
+                    }
+                }
+            }
+        }
+
+        // Build the new __LINKEDIT segment.
+        let Some((linkedit_offset, linkedit_segment)) = linkedit else {
+            return Ok(out);
+        };
+        let (linkedit_source, _) = cache
+            .data_and_offset_for_address(linkedit_segment.vmaddr(endian).into())
+            .read_error("Could not find __LINKEDIT data in dyld shared cache")?;
+        let linkedit_start = align(out.len(), page_size);
+        out.resize(linkedit_start, 0);
+        let copy_linkedit = |out: &mut Vec<u8>, offset: &mut U32<E>, size: u32| -> Result<()> {
+            if size == 0 {
+                offset.set(endian, 0);
+                return Ok(());
+            }
+            let bytes = linkedit_source
+                .read_bytes_at(offset.get(endian).into(), size.into())
+                .read_error("Invalid dyld cache __LINKEDIT data")?;
+            out.resize(align(out.len(), 8), 0);
+            offset.set(endian, offset_u32(out.len() as u64)?);
+            out.extend_from_slice(bytes);
+            Ok(())
+        };
+
+        if let Some((command_offset, command)) = dyld_info {
+            let mut command: macho::DyldInfoCommand<E> = *command;
+            let sizes = [
+                command.rebase_size.get(endian),
+                command.bind_size.get(endian),
+                command.weak_bind_size.get(endian),
+                command.lazy_bind_size.get(endian),
+                command.export_size.get(endian),
+            ];
+            copy_linkedit(&mut out, &mut command.rebase_off, sizes[0])?;
+            copy_linkedit(&mut out, &mut command.bind_off, sizes[1])?;
+            copy_linkedit(&mut out, &mut command.weak_bind_off, sizes[2])?;
+            copy_linkedit(&mut out, &mut command.lazy_bind_off, sizes[3])?;
+            copy_linkedit(&mut out, &mut command.export_off, sizes[4])?;
+            *data_mut(&mut out, command_offset)?.0 = command;
+        }
+
+        for (command_offset, command) in linkedit_data {
+            let mut command: macho::LinkeditDataCommand<E> = *command;
+            let size = command.datasize.get(endian);
+            copy_linkedit(&mut out, &mut command.dataoff, size)?;
+            *data_mut(&mut out, command_offset)?.0 = command;
+        }
+
+        // The local symbols are placed before the symbols from the image's symbol table,
+        // so the symbol indices in the dynamic symbol table must be adjusted.
+        let mut local_count = 0;
+        if let Some((command_offset, command)) = symtab {
+            let mut command: macho::SymtabCommand<E> = *command;
+            let image_symbols = command.symbols::<Mach, _>(endian, linkedit_source)?;
+            let local_symbols = self.local_symbols::<Mach>()?;
+
+            let mut strings = vec![0];
+            let mut symbols = Vec::new();
+            let tables = local_symbols.iter().chain(Some(&image_symbols));
+            for table in tables {
+                for symbol in table.iter() {
+                    let mut symbol = *symbol;
+                    let strx = symbol.n_strx(endian);
+                    if strx != 0 {
+                        let name = table
+                            .strings()
+                            .get(strx)
+                            .read_error("Invalid Mach-O symbol name offset")?;
+                        let new_strx = offset_u32(strings.len() as u64)?;
+                        strings.extend_from_slice(name);
+                        strings.push(0);
+                        data_mut::<U32<E>>(crate::pod::bytes_of_mut(&mut symbol), 0)?
+                            .0
+                            .set(endian, new_strx);
+                    }
+                    symbols.push(symbol);
+                }
+            }
+            local_count = local_symbols.map_or(0, |symbols| symbols.len() as u32);
+
+            out.resize(align(out.len(), 8), 0);
+            command.symoff.set(endian, offset_u32(out.len() as u64)?);
+            command.nsyms.set(endian, offset_u32(symbols.len() as u64)?);
+            out.extend_from_slice(crate::pod::bytes_of_slice(&symbols));
+            out.resize(align(out.len(), 8), 0);
+            command.stroff.set(endian, offset_u32(out.len() as u64)?);
+            command
+                .strsize
+                .set(endian, offset_u32(strings.len() as u64)?);
+            out.extend_from_slice(&strings);
+            *data_mut(&mut out, command_offset)?.0 = command;
+        }
+
+        if let Some((command_offset, command)) = dysymtab {
+            let mut command: macho::DysymtabCommand<E> = *command;
+            if command.nlocalsym.get(endian) == 0 {
+                command.ilocalsym.set(endian, 0);
+            }
+            command
+                .nlocalsym
+                .set(endian, command.nlocalsym.get(endian) + local_count);
+            command
+                .iextdefsym
+                .set(endian, command.iextdefsym.get(endian) + local_count);
+            command
+                .iundefsym
+                .set(endian, command.iundefsym.get(endian) + local_count);
+
+            let indirect_count = command.nindirectsyms.get(endian);
+            let indirect = linkedit_source
+                .read_slice_at::<U32<E>>(
+                    command.indirectsymoff.get(endian).into(),
+                    indirect_count as usize,
+                )
+                .read_error("Invalid Mach-O indirect symbol table")?;
+            out.resize(align(out.len(), 8), 0);
+            command.indirectsymoff.set(
+                endian,
+                if indirect_count == 0 {
+                    0
+                } else {
+                    offset_u32(out.len() as u64)?
+                },
+            );
+            for index in indirect {
+                let mut index = index.get(endian);
+                if index & (macho::INDIRECT_SYMBOL_LOCAL | macho::INDIRECT_SYMBOL_ABS) == 0 {
+                    index += local_count;
+                }
+                out.extend_from_slice(crate::pod::bytes_of(&U32::new(endian, index)));
+            }
+
+            // These tables are not used for images in the cache.
+            for (offset, count) in [
+                (&mut command.tocoff, &mut command.ntoc),
+                (&mut command.modtaboff, &mut command.nmodtab),
+                (&mut command.extrefsymoff, &mut command.nextrefsyms),
+                (&mut command.extreloff, &mut command.nextrel),
+                (&mut command.locreloff, &mut command.nlocrel),
+            ] {
+                offset.set(endian, 0);
+                count.set(endian, 0);
+            }
+            *data_mut(&mut out, command_offset)?.0 = command;
+        }
+
+        let linkedit_size = (out.len() - linkedit_start) as u64;
+        if is_64 {
+            let segment = data_mut::<macho::SegmentCommand64<E>>(&mut out, linkedit_offset)?.0;
+            segment.fileoff.set(endian, linkedit_start as u64);
+            segment.filesize.set(endian, linkedit_size);
+            segment
+                .vmsize
+                .set(endian, align(linkedit_size as usize, page_size) as u64);
+        } else {
+            let segment = data_mut::<macho::SegmentCommand32<E>>(&mut out, linkedit_offset)?.0;
+            segment
+                .fileoff
+                .set(endian, offset_u32(linkedit_start as u64)?);
+            segment.filesize.set(endian, offset_u32(linkedit_size)?);
+            segment.vmsize.set(
+                endian,
+                offset_u32(align(linkedit_size as usize, page_size) as u64)?,
+            );
+        }
+        Ok(out)
+    }
+
+    fn segments_contain<Mach: MachHeader<Endian = E>>(&self, address: u64) -> Result<bool> {
+        let (data, header_offset) = self.image_data_and_offset()?;
+        let header = Mach::parse(data, header_offset)?;
+        let endian = self.cache.endian;
+        let mut commands = header.load_commands(endian, data, header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let Some((segment, _)) = Mach::Segment::from_command(command)? {
+                if segment.name() == macho::SEG_LINKEDIT.as_bytes() {
+                    continue;
+                }
+                let vmaddr = segment.vmaddr(endian).into();
+                let vmsize = segment.vmsize(endian).into();
+                if address >= vmaddr && address - vmaddr < vmsize {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+fn align(offset: usize, size: usize) -> usize {
+    (offset + (size - 1)) & !(size - 1)
+}
+
+fn offset_u32(offset: u64) -> Result<u32> {
+    offset
+        .try_into()
+        .ok()
+        .read_error("Extracted dyld cache image is too large")
+}
+
+fn data_mut<T: Pod>(data: &mut [u8], offset: usize) -> Result<(&mut T, &mut [u8])> {
+    data.get_mut(offset..)
+        .and_then(|data| crate::pod::from_bytes_mut(data).ok())
+        .read_error("Invalid dyld cache image load command")
 }
 
 /// An enum of arrays containing dyld cache mappings
@@ -338,14 +810,8 @@ where
 
     /// Find the file offset of the image by looking up its address in the mappings.
     pub fn address_to_file_offset(&self, address: u64) -> Option<u64> {
-        for mapping in self.iter() {
-            let mapping_address = mapping.address();
-            if address >= mapping_address && address < mapping_address.wrapping_add(mapping.size())
-            {
-                return Some(address - mapping_address + mapping.file_offset());
-            }
-        }
-        None
+        self.iter()
+            .find_map(|mapping| mapping.address_to_file_offset(address))
     }
 }
 
@@ -533,6 +999,40 @@ where
         }
     }
 
+    /// Return true if the mapping contains the given address.
+    pub fn contains_address(&self, address: u64) -> bool {
+        let mapping_address = self.address();
+        address >= mapping_address && address - mapping_address < self.size()
+    }
+
+    /// Return the file offset of the given address in the cache or subcache data
+    /// for this mapping.
+    ///
+    /// Returns `None` if the mapping does not contain the address.
+    pub fn address_to_file_offset(&self, address: u64) -> Option<u64> {
+        if !self.contains_address(address) {
+            return None;
+        }
+        Some(address - self.address() + self.file_offset())
+    }
+
+    /// Read the bytes at the given address in this mapping.
+    ///
+    /// The range must be contained within the mapping.
+    pub fn read_bytes_at_address(&self, address: u64, size: u64) -> Result<&'data [u8]> {
+        let offset = self
+            .address_to_file_offset(address)
+            .read_error("Address not found in dyld cache mapping")?;
+        if size > self.address() + self.size() - address {
+            return Err(Error("Invalid dyld cache mapping address range"));
+        }
+        let data = match self {
+            Self::V1 { data, .. } | Self::V2 { data, .. } => *data,
+        };
+        data.read_bytes_at(offset, size)
+            .read_error("Failed to read bytes for mapping")
+    }
+
     /// The mapping data
     pub fn data(&self) -> Result<&'data [u8]> {
         match self {
@@ -8,7 +8,8 @@ use crate::pod::Pod;
 use crate::read::{
     self, Architecture, ByteString, ComdatKind, Error, Export, FileFlags, Import,
     NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ObjectMap, ObjectSection,
-    ReadError, ReadRef, Result, SectionIndex, SubArchitecture, SymbolIndex,
+    ParseOptions, ReadError, ReadRef, Result, SectionIndex, SourceFile, SubArchitecture,
+    SymbolIndex,
 };
 
 use super::{
@@ -46,6 +47,7 @@ where
     pub(super) segments: Vec<MachOSegmentInternal<'data, Mach, R>>,
     pub(super) sections: Vec<MachOSectionInternal<'data, Mach, R>>,
     pub(super) symbols: SymbolTable<'data, Mach, R>,
+    pub(super) warnings: Vec<Error>,
 }
 
 impl<'data, Mach, R> MachOFile<'data, Mach, R>
@@ -55,6 +57,14 @@ where
 {
     /// Parse the raw Mach-O file data.
     pub fn parse(data: R) -> Result<Self> {
+        Self::parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw Mach-O file data, using the given options.
+    ///
+    /// The `dynamic_symbols` and `relocations` options are ignored.
+    pub fn parse_with(data: R, options: &ParseOptions) -> Result<Self> {
+        options.verify_file(data)?;
         let header = Mach::parse(data, 0)?;
         let endian = header.endian()?;
 
@@ -62,8 +72,11 @@ where
         let mut segments = Vec::new();
         let mut sections = Vec::new();
         let mut symbols = SymbolTable::default();
+        let mut warnings = Vec::new();
         if let Ok(mut commands) = header.load_commands(endian, data, 0) {
-            while let Ok(Some(command)) = commands.next() {
+            // Malformed trailing load commands are ignored, but are recorded as a warning
+            // in permissive mode.
+            while let Ok(Some(command)) = options.recover(&mut warnings, commands.next()) {
                 if let Some((segment, section_data)) = Mach::Segment::from_command(command)? {
                     segments.push(MachOSegmentInternal { segment, data });
                     for section in segment.sections(endian, section_data)? {
@@ -71,7 +84,9 @@ where
                         sections.push(MachOSectionInternal::parse(index, section, data));
                     }
                 } else if let Some(symtab) = command.symtab()? {
-                    symbols = symtab.symbols(endian, data)?;
+                    if options.symbols {
+                        symbols = options.recover(&mut warnings, symtab.symbols(endian, data))?;
+                    }
                 }
             }
         }
@@ -84,6 +99,7 @@ where
             segments,
             sections,
             symbols,
+            warnings,
         })
     }
 
@@ -145,6 +161,7 @@ where
             segments,
             sections,
             symbols,
+            warnings: Vec::new(),
         })
     }
 
@@ -171,6 +188,13 @@ where
         self.data
     }
 
+    /// Returns the problems that were found while parsing the file.
+    ///
+    /// See [`Strictness::Permissive`](crate::read::Strictness::Permissive).
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
     /// Returns the raw Mach-O file header.
     #[deprecated(note = "Use `macho_header` instead")]
     pub fn raw_header(&self) -> &'data Mach {
@@ -401,6 +425,10 @@ where
         self.symbols.object_map(self.endian)
     }
 
+    fn source_files(&self) -> Result<Vec<SourceFile<'data>>> {
+        self.symbols.source_files(self.endian)
+    }
+
     fn imports(&self) -> Result<Vec<Import<'data>>> {
         let mut dysymtab = None;
         let mut libraries = Vec::new();
@@ -511,6 +539,10 @@ where
             flags: self.header.flags(self.endian),
         }
     }
+
+    fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
 }
 
 /// An iterator for the COMDAT section groups in a [`MachOFile64`].
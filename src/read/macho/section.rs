@@ -5,11 +5,11 @@ use crate::endian::{self, Endianness};
 use crate::macho;
 use crate::pod::Pod;
 use crate::read::{
-    self, gnu_compression, CompressedData, CompressedFileRange, ObjectSection, ReadError, ReadRef,
-    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind,
+    self, gnu_compression, CompressedData, CompressedFileRange, ObjectSection, Permissions,
+    ReadError, ReadRef, RelocationMap, Result, SectionFlags, SectionIndex, SectionKind,
 };
 
-use super::{MachHeader, MachOFile, MachORelocationIterator};
+use super::{vm_prot_permissions, MachHeader, MachOFile, MachORelocationIterator, Segment};
 
 /// An iterator for the sections in a [`MachOFile32`](super::MachOFile32).
 pub type MachOSectionIterator32<'data, 'file, Endian = Endianness, R = &'data [u8]> =
@@ -235,6 +235,24 @@ where
             flags: self.internal.section.flags(self.file.endian),
         }
     }
+
+    fn permissions(&self) -> Permissions {
+        let segment_name = self.internal.section.segment_name();
+        let segments = &self.file.segments;
+        // Sections in object files are contained in a single segment with no name.
+        let segment = segments
+            .iter()
+            .find(|segment| segment.segment.name() == segment_name)
+            .or_else(|| {
+                segments
+                    .iter()
+                    .find(|segment| segment.segment.name().is_empty())
+            });
+        match segment {
+            Some(segment) => vm_prot_permissions(segment.segment.initprot(self.file.endian)),
+            None => Permissions::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
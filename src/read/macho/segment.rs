@@ -4,7 +4,7 @@ use core::{result, slice, str};
 use crate::endian::{self, Endianness};
 use crate::macho;
 use crate::pod::Pod;
-use crate::read::{self, ObjectSegment, ReadError, ReadRef, Result, SegmentFlags};
+use crate::read::{self, ObjectSegment, Permissions, ReadError, ReadRef, Result, SegmentFlags};
 
 use super::{LoadCommandData, MachHeader, MachOFile, Section};
 
@@ -155,6 +155,20 @@ where
             initprot,
         }
     }
+
+    #[inline]
+    fn permissions(&self) -> Permissions {
+        vm_prot_permissions(self.internal.segment.initprot(self.file.endian))
+    }
+}
+
+/// Convert Mach-O `VM_PROT_*` protection flags to permissions.
+pub(super) fn vm_prot_permissions(prot: u32) -> Permissions {
+    Permissions::new(
+        prot & macho::VM_PROT_READ != 0,
+        prot & macho::VM_PROT_WRITE != 0,
+        prot & macho::VM_PROT_EXECUTE != 0,
+    )
 }
 
 #[derive(Debug, Clone, Copy)]
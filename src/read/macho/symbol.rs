@@ -7,9 +7,9 @@ use crate::macho;
 use crate::pod::Pod;
 use crate::read::util::StringTable;
 use crate::read::{
-    self, ObjectMap, ObjectMapEntry, ObjectMapFile, ObjectSymbol, ObjectSymbolTable, ReadError,
-    ReadRef, Result, SectionIndex, SectionKind, SymbolFlags, SymbolIndex, SymbolKind, SymbolMap,
-    SymbolMapEntry, SymbolScope, SymbolSection,
+    self, ByteString, ObjectMap, ObjectMapEntry, ObjectMapFile, ObjectSymbol, ObjectSymbolTable,
+    ReadError, ReadRef, Result, SectionIndex, SectionKind, SourceFile, SymbolFlags, SymbolIndex,
+    SymbolKind, SymbolMap, SymbolMapEntry, SymbolScope, SymbolSection,
 };
 
 use super::{MachHeader, MachOFile};
@@ -91,6 +91,34 @@ impl<'data, Mach: MachHeader, R: ReadRef<'data>> SymbolTable<'data, Mach, R> {
         SymbolMap::new(symbols)
     }
 
+    /// Return the source files named by the `N_SO` STAB entries.
+    ///
+    /// A source file is named by either a single `N_SO` entry containing the path,
+    /// or an `N_SO` entry for the directory followed by one for the file name.
+    pub fn source_files(&self, endian: Mach::Endian) -> Result<Vec<SourceFile<'data>>> {
+        let mut files = Vec::new();
+        let mut directory: &[u8] = &[];
+        for nlist in self.symbols {
+            if nlist.n_type() != macho::N_SO {
+                continue;
+            }
+            let name = nlist.name(endian, self.strings)?;
+            if name.is_empty() {
+                // End of the module.
+                directory = &[];
+            } else if name.ends_with(b"/") {
+                directory = name;
+            } else {
+                files.push(SourceFile {
+                    directory: ByteString(directory),
+                    name: ByteString(name),
+                });
+                directory = &[];
+            }
+        }
+        Ok(files)
+    }
+
     /// Construct a map from addresses to symbol names and object file names.
     pub fn object_map(&self, endian: Mach::Endian) -> ObjectMap<'data> {
         let mut symbols = Vec::new();
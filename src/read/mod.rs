@@ -43,6 +43,7 @@
 
 use alloc::borrow::Cow;
 use alloc::vec::Vec;
+use core::convert::TryInto;
 use core::{fmt, result};
 
 #[cfg(not(feature = "std"))]
@@ -61,7 +62,7 @@ pub use read_cache::*;
 mod util;
 pub use util::*;
 
-#[cfg(any(feature = "elf", feature = "macho"))]
+#[cfg(any(feature = "coff", feature = "elf", feature = "macho", feature = "pe"))]
 mod gnu_compression;
 
 #[cfg(any(
@@ -107,6 +108,8 @@ pub mod xcoff;
 mod traits;
 pub use traits::*;
 
+pub mod dynamic;
+
 mod private {
     pub trait Sealed {}
 }
@@ -278,6 +281,16 @@ pub enum FileKind {
     /// See [`xcoff::XcoffFile64`].
     #[cfg(feature = "xcoff")]
     Xcoff64,
+    /// A gzip compressed file.
+    ///
+    /// See [`File::parse_decompressed`].
+    #[cfg(feature = "compression")]
+    Gzip,
+    /// An xz compressed file.
+    ///
+    /// See [`File::parse_decompressed`].
+    #[cfg(feature = "xz")]
+    Xz,
 }
 
 impl FileKind {
@@ -356,12 +369,139 @@ impl FileKind {
             [0x01, 0xdf, ..] => FileKind::Xcoff32,
             #[cfg(feature = "xcoff")]
             [0x01, 0xf7, ..] => FileKind::Xcoff64,
+            #[cfg(feature = "compression")]
+            [0x1f, 0x8b, 0x08, ..] => FileKind::Gzip,
+            #[cfg(feature = "xz")]
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => FileKind::Xz,
             _ => return Err(Error("Unknown file magic")),
         };
         Ok(kind)
     }
 }
 
+/// Options that control which parts of a file are parsed.
+///
+/// This is used by [`File::parse_with`] and the `parse_with` methods of the
+/// file format types, such as [`elf::ElfFile::parse_with`].
+///
+/// The parts of a file that are not parsed are treated as empty. For example,
+/// if `symbols` is false then [`Object::symbols`] returns an empty iterator.
+///
+/// Options that do not apply to a file format are ignored for that format.
+/// In particular, `dynamic_symbols` and `relocations` only affect ELF files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Parse the symbol table.
+    ///
+    /// Defaults to true.
+    pub symbols: bool,
+    /// Parse the dynamic symbol table.
+    ///
+    /// This only affects ELF. Defaults to true.
+    pub dynamic_symbols: bool,
+    /// Build the mapping from sections to relocation sections.
+    ///
+    /// If false, [`ObjectSection::relocations`] returns an empty iterator.
+    /// This only affects ELF. Defaults to true.
+    pub relocations: bool,
+    /// Check the file for structural consistency before parsing it.
+    ///
+    /// Parsing fails if [`verify`](crate::verify::verify) finds any problems,
+    /// or if the file contains a checksum that does not match its contents.
+    /// Defaults to false.
+    pub verify: bool,
+    /// How to handle malformed parts of a file.
+    ///
+    /// Defaults to [`Strictness::Normal`].
+    pub strictness: Strictness,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            symbols: true,
+            dynamic_symbols: true,
+            relocations: true,
+            verify: false,
+            strictness: Strictness::Normal,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Options that only parse the file header and the section and segment tables.
+    ///
+    /// This is useful if only the section data is needed.
+    pub fn minimal() -> Self {
+        ParseOptions {
+            symbols: false,
+            dynamic_symbols: false,
+            relocations: false,
+            verify: false,
+            strictness: Strictness::Normal,
+        }
+    }
+
+    /// Check the file if `verify` is true.
+    #[cfg(any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "pe",
+        feature = "wasm",
+        feature = "xcoff"
+    ))]
+    pub(crate) fn verify_file<'data, R: ReadRef<'data>>(&self, data: R) -> Result<()> {
+        if self.verify && !crate::verify::verify(data)?.is_empty() {
+            return Err(Error("File failed structural verification"));
+        }
+        Ok(())
+    }
+
+    /// Handle the result of parsing an optional part of a file.
+    ///
+    /// If the strictness is [`Strictness::Permissive`], then an error is
+    /// recorded as a warning and the default value is returned instead.
+    #[cfg(any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "pe",
+        feature = "xcoff"
+    ))]
+    pub(crate) fn recover<T: Default>(
+        &self,
+        warnings: &mut Vec<Error>,
+        result: Result<T>,
+    ) -> Result<T> {
+        match result {
+            Err(e) if self.strictness == Strictness::Permissive => {
+                warnings.push(e);
+                Ok(T::default())
+            }
+            result => result,
+        }
+    }
+}
+
+/// How to handle malformed parts of a file.
+///
+/// Used in [`ParseOptions::strictness`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Strictness {
+    /// Fail if any of the parsed parts of the file are malformed.
+    Normal,
+    /// Continue parsing if a part of the file that is not essential is malformed.
+    ///
+    /// Parts of the file that failed to parse are treated as empty, and the
+    /// errors are recorded as warnings that can be retrieved with
+    /// [`Object::warnings`]. Files with an invalid checksum are also recorded
+    /// as a warning, unless [`ParseOptions::verify`] is true.
+    Permissive,
+}
+
 /// An object kind.
 ///
 /// Returned by [`Object::kind`].
@@ -617,6 +757,61 @@ impl<'data> ObjectMapFile<'data> {
     }
 }
 
+/// A map from section names to section indices.
+///
+/// Returned by [`Object::section_name_index`].
+#[derive(Debug, Default, Clone)]
+pub struct SectionNameIndex<'data> {
+    sections: Vec<(&'data [u8], SectionIndex)>,
+}
+
+impl<'data> SectionNameIndex<'data> {
+    /// Construct a new section name index.
+    ///
+    /// This function will sort the sections by name. If multiple sections
+    /// have the same name, then the first of these in the given order is used.
+    pub fn new(mut sections: Vec<(&'data [u8], SectionIndex)>) -> Self {
+        sections.sort_by_key(|(name, _)| *name);
+        sections.dedup_by_key(|(name, _)| *name);
+        SectionNameIndex { sections }
+    }
+
+    /// Get the index of the section with the given name.
+    pub fn get(&self, name: &[u8]) -> Option<SectionIndex> {
+        let index = self
+            .sections
+            .binary_search_by_key(&name, |(name, _)| *name)
+            .ok()?;
+        Some(self.sections[index].1)
+    }
+}
+
+/// The name of a source file.
+///
+/// Returned by [`Object::source_files`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceFile<'data> {
+    directory: ByteString<'data>,
+    name: ByteString<'data>,
+}
+
+impl<'data> SourceFile<'data> {
+    /// The directory of the source file.
+    ///
+    /// This is empty if the file format does not store the directory separately.
+    /// The directory may also be included in the name.
+    #[inline]
+    pub fn directory(&self) -> &'data [u8] {
+        self.directory.0
+    }
+
+    /// The name of the source file.
+    #[inline]
+    pub fn name(&self) -> &'data [u8] {
+        self.name.0
+    }
+}
+
 /// An imported symbol.
 ///
 /// Returned by [`Object::imports`].
@@ -693,6 +888,166 @@ impl<'data> CodeView<'data> {
     }
 }
 
+/// The build information embedded in a Go binary.
+///
+/// Returned by [`Object::go_build_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoBuildInfo<'data> {
+    version: ByteString<'data>,
+    mod_info: ByteString<'data>,
+}
+
+impl<'data> GoBuildInfo<'data> {
+    /// The version of the Go toolchain that built the binary, such as `go1.21.0`.
+    #[inline]
+    pub fn version(&self) -> &'data [u8] {
+        self.version.0
+    }
+
+    /// The module information, as printed by `go version -m`.
+    ///
+    /// This is empty if the binary was not built with module support.
+    #[inline]
+    pub fn mod_info(&self) -> &'data [u8] {
+        self.mod_info.0
+    }
+}
+
+/// The metadata that rustc embeds in a Rust library.
+///
+/// Returned by [`Object::rust_metadata`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RustMetadata<'data> {
+    version: u8,
+    compressed: bool,
+    data: &'data [u8],
+}
+
+impl<'data> RustMetadata<'data> {
+    /// The magic bytes at the start of the metadata, before the version byte.
+    const MAGIC: &'static [u8] = b"rust\0\0\0";
+
+    /// The stream identifier chunk of the snappy frame format.
+    const SNAPPY_MAGIC: &'static [u8] = b"\xff\x06\x00\x00sNaPpY";
+
+    /// Parse the data of a `.rustc` or `.rmeta` section.
+    ///
+    /// The `.rustc` section in a dylib contains a header, the length of the
+    /// metadata, and the metadata itself. Older versions of rustc omit the length,
+    /// and compress the metadata using the snappy frame format.
+    ///
+    /// The `.rmeta` section in an rlib contains the metadata only.
+    pub fn parse(data: &'data [u8]) -> Result<Self> {
+        if data.len() < 8 || !data.starts_with(Self::MAGIC) {
+            return Err(Error("Invalid Rust metadata header"));
+        }
+        let version = data[7];
+        let rest = &data[8..];
+        let (compressed, data) = if rest.starts_with(Self::SNAPPY_MAGIC) {
+            (true, rest)
+        } else if rest.len() >= 8 && rest[8..].starts_with(Self::SNAPPY_MAGIC) {
+            (true, &rest[8..])
+        } else if rest.len() >= 8 && rest[8..].starts_with(Self::MAGIC) {
+            let len = u64::from_le_bytes(rest[..8].try_into().unwrap());
+            let data = len
+                .try_into()
+                .ok()
+                .and_then(|len: usize| rest[8..].get(..len))
+                .read_error("Invalid Rust metadata length")?;
+            (false, data)
+        } else {
+            (false, data)
+        };
+        Ok(RustMetadata {
+            version,
+            compressed,
+            data,
+        })
+    }
+
+    /// The version of the metadata format in the header.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Return true if the metadata is compressed.
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// The raw metadata.
+    ///
+    /// If [`Self::is_compressed`] is true, then this is a snappy frame stream.
+    /// Otherwise, this is the metadata blob that rustc reads, including its own header.
+    #[inline]
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Return the metadata blob, decompressing it if required.
+    #[cfg(feature = "snappy")]
+    pub fn decompress(&self) -> Result<Cow<'data, [u8]>> {
+        use std::io::Read;
+        if !self.compressed {
+            return Ok(Cow::Borrowed(self.data));
+        }
+        let mut decompressed = Vec::new();
+        snap::read::FrameDecoder::new(self.data)
+            .read_to_end(&mut decompressed)
+            .ok()
+            .read_error("Invalid snappy compressed Rust metadata")?;
+        Ok(Cow::Owned(decompressed))
+    }
+}
+
+/// A reference to an external file that contains debug information.
+///
+/// Returned by [`Object::debug_files`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DebugFile<'data> {
+    /// The PDB information from the PE CodeView section.
+    ///
+    /// See [`Object::pdb_info`].
+    Pdb(CodeView<'data>),
+    /// The UUID from a Mach-O `LC_UUID` load command.
+    ///
+    /// This is used to find the matching dSYM bundle.
+    ///
+    /// See [`Object::mach_uuid`].
+    MachUuid([u8; 16]),
+    /// The build ID from an ELF `NT_GNU_BUILD_ID` note.
+    ///
+    /// This is used to find the debug file in a `.build-id` directory
+    /// or from a debuginfod server.
+    ///
+    /// See [`Object::build_id`].
+    BuildId(&'data [u8]),
+    /// The filename and CRC from a `.gnu_debuglink` section.
+    ///
+    /// See [`Object::gnu_debuglink`].
+    GnuDebugLink {
+        /// The filename of the debug file.
+        filename: &'data [u8],
+        /// The CRC-32 of the debug file.
+        crc: u32,
+    },
+    /// The filename and build ID from a `.gnu_debugaltlink` section.
+    ///
+    /// This is the supplementary debug file that is shared by multiple
+    /// debug files, such as one created by `dwz`.
+    ///
+    /// See [`Object::gnu_debugaltlink`].
+    GnuDebugAltLink {
+        /// The filename of the supplementary debug file.
+        filename: &'data [u8],
+        /// The build ID of the supplementary debug file.
+        build_id: &'data [u8],
+    },
+}
+
 /// The target referenced by a [`Relocation`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -746,6 +1101,50 @@ impl Relocation {
         self.target
     }
 
+    /// Get the symbol that is the target of the relocation.
+    ///
+    /// This is for relocations returned by [`ObjectSection::relocations`].
+    /// Use [`Self::target_dynamic_symbol`] for relocations returned by
+    /// [`Object::dynamic_relocations`].
+    ///
+    /// Returns `Ok(None)` if the target is not a symbol.
+    /// Returns an error if the symbol index is invalid.
+    pub fn target_symbol<'data: 'file, 'file, T>(
+        &self,
+        file: &'file T,
+    ) -> Result<Option<T::Symbol<'file>>>
+    where
+        T: Object<'data>,
+    {
+        match self.target {
+            RelocationTarget::Symbol(index) => file.symbol_by_index(index).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Get the dynamic symbol that is the target of the relocation.
+    ///
+    /// This is for relocations returned by [`Object::dynamic_relocations`].
+    ///
+    /// Returns `Ok(None)` if the target is not a symbol.
+    /// Returns an error if the symbol index is invalid.
+    pub fn target_dynamic_symbol<'data: 'file, 'file, T>(
+        &self,
+        file: &'file T,
+    ) -> Result<Option<T::Symbol<'file>>>
+    where
+        T: Object<'data>,
+    {
+        match self.target {
+            RelocationTarget::Symbol(index) => file
+                .dynamic_symbol_table()
+                .read_error("Missing dynamic symbol table")?
+                .symbol_by_index(index)
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+
     /// The addend to use in the relocation calculation.
     #[inline]
     pub fn addend(&self) -> i64 {
@@ -769,6 +1168,11 @@ impl Relocation {
     ///
     /// The values returned by `kind`, `encoding` and `size` are derived
     /// from these flags.
+    ///
+    /// For relocations that are read from a file, these are always the raw
+    /// format specific fields, even if the relocation maps to a known
+    /// [`RelocationKind`]. They can be used to display the exact relocation
+    /// type, or to write the relocation to another file of the same format.
     #[inline]
     pub fn flags(&self) -> RelocationFlags {
         self.flags
@@ -966,8 +1370,33 @@ impl<'data> CompressedData<'data> {
     /// This includes if the data is compressed but the `compression` feature
     /// for this crate is disabled.
     pub fn decompress(self) -> Result<Cow<'data, [u8]>> {
+        if self.format == CompressionFormat::None {
+            return Ok(Cow::Borrowed(self.data));
+        }
+        let mut decompressed = Vec::new();
+        self.decompress_into(&mut decompressed)?;
+        Ok(Cow::Owned(decompressed))
+    }
+
+    /// Write the uncompressed data into the given buffer.
+    ///
+    /// The buffer is cleared first. This allows the buffer's allocation to be
+    /// reused when decompressing many sections. Uncompressed data is copied
+    /// into the buffer; use [`Self::decompress`] to avoid the copy.
+    ///
+    /// Returns an error for invalid data or unsupported compression.
+    /// This includes if the data is compressed but the `compression` feature
+    /// for this crate is disabled.
+    pub fn decompress_into(self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.clear();
         match self.format {
-            CompressionFormat::None => Ok(Cow::Borrowed(self.data)),
+            CompressionFormat::None => {
+                buf.try_reserve_exact(self.data.len())
+                    .ok()
+                    .read_error("Uncompressed data allocation failed")?;
+                buf.extend_from_slice(self.data);
+                Ok(())
+            }
             #[cfg(feature = "compression")]
             CompressionFormat::Zlib | CompressionFormat::Zstandard => {
                 use core::convert::TryInto;
@@ -977,9 +1406,7 @@ impl<'data> CompressedData<'data> {
                     .try_into()
                     .ok()
                     .read_error("Uncompressed data size is too large.")?;
-                let mut decompressed = Vec::new();
-                decompressed
-                    .try_reserve_exact(size)
+                buf.try_reserve_exact(size)
                     .ok()
                     .read_error("Uncompressed data allocation failed")?;
 
@@ -987,11 +1414,7 @@ impl<'data> CompressedData<'data> {
                     CompressionFormat::Zlib => {
                         let mut decompress = flate2::Decompress::new(true);
                         decompress
-                            .decompress_vec(
-                                self.data,
-                                &mut decompressed,
-                                flate2::FlushDecompress::Finish,
-                            )
+                            .decompress_vec(self.data, buf, flate2::FlushDecompress::Finish)
                             .ok()
                             .read_error("Invalid zlib compressed data")?;
                     }
@@ -1016,20 +1439,19 @@ impl<'data> CompressedData<'data> {
                                 x => x.ok().read_error("Invalid zstd compressed data")?,
                             };
                             decoder
-                                .read_to_end(&mut decompressed)
+                                .read_to_end(buf)
                                 .ok()
                                 .read_error("Invalid zstd compressed data")?;
                         }
                     }
                     _ => unreachable!(),
                 }
-                if size != decompressed.len() {
+                if size != buf.len() {
                     return Err(Error(
                         "Uncompressed data size does not match compression header",
                     ));
                 }
-
-                Ok(Cow::Owned(decompressed))
+                Ok(())
             }
             _ => Err(Error("Unsupported compressed data.")),
         }
@@ -0,0 +1,73 @@
+use crate::endian::LittleEndian as LE;
+use crate::pe;
+use crate::read::{ReadRef, Result};
+
+use super::SectionTable;
+
+impl pe::ImageCor20Header {
+    /// Return the `COMIMAGE_FLAGS_*` flags.
+    pub fn flags(&self) -> u32 {
+        self.flags.get(LE)
+    }
+
+    /// Return true if the image has a strong name signature.
+    ///
+    /// This checks `COMIMAGE_FLAGS_STRONGNAMESIGNED`. Note that this flag is
+    /// set by signing tools once the signature has been computed, so it may be
+    /// clear even if space has been reserved for the signature.
+    pub fn is_strong_name_signed(&self) -> bool {
+        self.flags() & pe::COMIMAGE_FLAGS_STRONGNAMESIGNED != 0
+    }
+
+    /// Return the metadata token of the managed entry point.
+    ///
+    /// Returns `None` if the image has a native entry point, or if the token is zero.
+    pub fn entry_point_token(&self) -> Option<u32> {
+        if self.flags() & pe::COMIMAGE_FLAGS_NATIVE_ENTRYPOINT != 0 {
+            return None;
+        }
+        Some(self.entry_point_token_or_rva.get(LE)).filter(|&token| token != 0)
+    }
+
+    /// Return the relative virtual address of the native entry point.
+    ///
+    /// Returns `None` if the image does not have a native entry point.
+    pub fn native_entry_point(&self) -> Option<u32> {
+        if self.flags() & pe::COMIMAGE_FLAGS_NATIVE_ENTRYPOINT == 0 {
+            return None;
+        }
+        Some(self.entry_point_token_or_rva.get(LE))
+    }
+
+    /// Return the file offset and size of the strong name signature blob.
+    ///
+    /// This is the range that signing tools must exclude from the hash and then
+    /// overwrite with the signature.
+    ///
+    /// Returns `Ok(None)` if there is no space reserved for a signature.
+    pub fn strong_name_signature_range(
+        &self,
+        sections: &SectionTable<'_>,
+    ) -> Result<Option<(u32, u32)>> {
+        if self.strong_name_signature.virtual_address.get(LE) == 0 {
+            return Ok(None);
+        }
+        self.strong_name_signature.file_range(sections).map(Some)
+    }
+
+    /// Return the strong name signature blob.
+    ///
+    /// `data` must be the entire file data.
+    ///
+    /// Returns `Ok(None)` if there is no space reserved for a signature.
+    pub fn strong_name_signature<'data, R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<&'data [u8]>> {
+        if self.strong_name_signature.virtual_address.get(LE) == 0 {
+            return Ok(None);
+        }
+        self.strong_name_signature.data(data, sections).map(Some)
+    }
+}
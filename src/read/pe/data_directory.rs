@@ -165,6 +165,27 @@ impl<'data> DataDirectories<'data> {
         let rsrc_data = data_dir.data(data, sections)?;
         Ok(Some(ResourceDirectory::new(rsrc_data)))
     }
+
+    /// Returns the CLR runtime header.
+    ///
+    /// This is only present in .NET images.
+    ///
+    /// `data` must be the entire file data.
+    pub fn cor20_header<R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<&'data pe::ImageCor20Header>> {
+        let data_dir = match self.get(pe::IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR) {
+            Some(data_dir) => data_dir,
+            None => return Ok(None),
+        };
+        let cor20_data = data_dir.data(data, sections)?;
+        cor20_data
+            .read_at(0)
+            .read_error("Invalid CLR runtime header size")
+            .map(Some)
+    }
 }
 
 impl pe::ImageDataDirectory {
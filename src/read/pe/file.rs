@@ -10,8 +10,8 @@ use crate::pod::{self, Pod};
 use crate::read::coff::{CoffCommon, CoffSymbol, CoffSymbolIterator, CoffSymbolTable, SymbolTable};
 use crate::read::{
     self, Architecture, ByteString, Bytes, CodeView, ComdatKind, Error, Export, FileFlags, Import,
-    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result,
-    SectionIndex, SubArchitecture, SymbolIndex,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ParseOptions, ReadError,
+    ReadRef, Result, SectionIndex, Strictness, SubArchitecture, SymbolIndex,
 };
 
 use super::{
@@ -44,6 +44,7 @@ where
     pub(super) data_directories: DataDirectories<'data>,
     pub(super) common: CoffCommon<'data, R>,
     pub(super) data: R,
+    pub(super) warnings: Vec<Error>,
 }
 
 impl<'data, Pe, R> PeFile<'data, Pe, R>
@@ -53,25 +54,56 @@ where
 {
     /// Parse the raw PE file data.
     pub fn parse(data: R) -> Result<Self> {
+        Self::parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw PE file data, using the given options.
+    ///
+    /// The `dynamic_symbols` and `relocations` options are ignored.
+    pub fn parse_with(data: R, options: &ParseOptions) -> Result<Self> {
+        options.verify_file(data)?;
         let dos_header = pe::ImageDosHeader::parse(data)?;
         let mut offset = dos_header.nt_headers_offset().into();
         let (nt_headers, data_directories) = Pe::parse(data, &mut offset)?;
         let sections = nt_headers.sections(data, offset)?;
-        let coff_symbols = nt_headers.symbols(data);
+        let mut warnings = Vec::new();
+        // The PE file format deprecates the COFF symbol table (https://docs.microsoft.com/en-us/windows/win32/debug/pe-format#coff-file-header-object-and-image)
+        // We do not want to prevent parsing the rest of the PE file for a corrupt COFF header, but rather return an empty symbol table
+        let coff_symbols = if options.symbols {
+            nt_headers.symbols(data).unwrap_or_else(|e| {
+                if options.strictness == Strictness::Permissive {
+                    warnings.push(e);
+                }
+                SymbolTable::default()
+            })
+        } else {
+            SymbolTable::default()
+        };
         let image_base = nt_headers.optional_header().image_base();
 
+        let check_sum = nt_headers.optional_header().check_sum();
+        if check_sum != 0 && (options.verify || options.strictness == Strictness::Permissive) {
+            let offset = dos_header.nt_headers_offset().into();
+            if compute_checksum(data, offset)? != check_sum {
+                let error = Error("Invalid PE checksum");
+                if options.verify {
+                    return Err(error);
+                }
+                warnings.push(error);
+            }
+        }
+
         Ok(PeFile {
             dos_header,
             nt_headers,
             data_directories,
             common: CoffCommon {
                 sections,
-                // The PE file format deprecates the COFF symbol table (https://docs.microsoft.com/en-us/windows/win32/debug/pe-format#coff-file-header-object-and-image)
-                // We do not want to prevent parsing the rest of the PE file for a corrupt COFF header, but rather return an empty symbol table
-                symbols: coff_symbols.unwrap_or_default(),
+                symbols: coff_symbols,
                 image_base,
             },
             data,
+            warnings,
         })
     }
 
@@ -80,6 +112,20 @@ where
         self.data
     }
 
+    /// Returns the problems that were found while parsing the file.
+    ///
+    /// See [`Strictness::Permissive`].
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// Compute the checksum of this file.
+    ///
+    /// This is the value that is expected in the `CheckSum` field of the optional header.
+    pub fn compute_checksum(&self) -> Result<u32> {
+        compute_checksum(self.data, self.dos_header.nt_headers_offset().into())
+    }
+
     /// Return the DOS header of this file.
     pub fn dos_header(&self) -> &'data pe::ImageDosHeader {
         self.dos_header
@@ -118,6 +164,14 @@ where
             .export_table(self.data, &self.common.sections)
     }
 
+    /// Returns the CLR runtime header of this file.
+    ///
+    /// This is only present in .NET images.
+    pub fn cor20_header(&self) -> Result<Option<&'data pe::ImageCor20Header>> {
+        self.data_directories
+            .cor20_header(self.data, &self.common.sections)
+    }
+
     /// Returns the import table of this file.
     ///
     /// The import table is located using the data directory.
@@ -129,6 +183,42 @@ where
     pub(super) fn section_alignment(&self) -> u64 {
         u64::from(self.nt_headers.optional_header().section_alignment())
     }
+
+    fn raw_section_by_name<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<PeSection<'data, 'file, Pe, R>> {
+        self.common
+            .sections
+            .section_by_name(self.common.symbols.strings(), section_name)
+            .map(|(index, section)| PeSection {
+                file: self,
+                index,
+                section,
+            })
+    }
+
+    #[cfg(feature = "compression")]
+    fn zdebug_section_by_name<'file>(
+        &'file self,
+        section_name: &[u8],
+    ) -> Option<PeSection<'data, 'file, Pe, R>> {
+        if !section_name.starts_with(b".debug_") {
+            return None;
+        }
+        let mut name = Vec::with_capacity(section_name.len() + 1);
+        name.extend_from_slice(b".zdebug_");
+        name.extend_from_slice(&section_name[7..]);
+        self.raw_section_by_name(&name)
+    }
+
+    #[cfg(not(feature = "compression"))]
+    fn zdebug_section_by_name<'file>(
+        &'file self,
+        _section_name: &[u8],
+    ) -> Option<PeSection<'data, 'file, Pe, R>> {
+        None
+    }
 }
 
 impl<'data, Pe, R> read::private::Sealed for PeFile<'data, Pe, R>
@@ -244,14 +334,8 @@ where
         &'file self,
         section_name: &[u8],
     ) -> Option<PeSection<'data, 'file, Pe, R>> {
-        self.common
-            .sections
-            .section_by_name(self.common.symbols.strings(), section_name)
-            .map(|(index, section)| PeSection {
-                file: self,
-                index,
-                section,
-            })
+        self.raw_section_by_name(section_name)
+            .or_else(|| self.zdebug_section_by_name(section_name))
     }
 
     fn section_by_index(&self, index: SectionIndex) -> Result<PeSection<'data, '_, Pe, R>> {
@@ -416,6 +500,42 @@ where
             characteristics: self.nt_headers.file_header().characteristics.get(LE),
         }
     }
+
+    fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+}
+
+/// Compute the checksum of a PE file.
+///
+/// `nt_headers_offset` is used to locate the `CheckSum` field, which is
+/// excluded from the checksum.
+fn compute_checksum<'data, R: ReadRef<'data>>(data: R, nt_headers_offset: u64) -> Result<u32> {
+    let len = data.len().read_error("Unknown PE file length")?;
+    let bytes = data
+        .read_bytes_at(0, len)
+        .read_error("Invalid PE file length")?;
+    // The field is at the same offset in both PE32 and PE32+ optional headers.
+    let check_sum_offset = nt_headers_offset
+        + mem::size_of::<U32<LE>>() as u64
+        + mem::size_of::<pe::ImageFileHeader>() as u64
+        + 64;
+
+    let mut sum = 0u32;
+    for (index, chunk) in bytes.chunks(2).enumerate() {
+        let offset = index as u64 * 2;
+        if offset >= check_sum_offset && offset < check_sum_offset + 4 {
+            continue;
+        }
+        let word = match *chunk {
+            [lo, hi] => u16::from_le_bytes([lo, hi]),
+            [lo] => u16::from(lo),
+            _ => unreachable!(),
+        };
+        sum += u32::from(word);
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    Ok(sum.wrapping_add(len as u32))
 }
 
 /// An iterator for the COMDAT section groups in a [`PeFile32`].
@@ -65,4 +65,6 @@ pub use resource::*;
 mod rich;
 pub use rich::*;
 
+mod clr;
+
 pub use super::coff::{SectionTable, SymbolTable};
@@ -5,8 +5,9 @@ use crate::endian::LittleEndian as LE;
 use crate::pe;
 use crate::pe::ImageSectionHeader;
 use crate::read::{
-    self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
-    Relocation, RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+    self, gnu_compression, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment,
+    Permissions, ReadError, ReadRef, Relocation, RelocationMap, Result, SectionFlags, SectionIndex,
+    SectionKind, SegmentFlags,
 };
 
 use super::{ImageNtHeaders, PeFile, SectionTable};
@@ -148,6 +149,11 @@ where
         let characteristics = self.section.characteristics.get(LE);
         SegmentFlags::Coff { characteristics }
     }
+
+    #[inline]
+    fn permissions(&self) -> Permissions {
+        self.section.permissions()
+    }
 }
 
 /// An iterator for the sections in a [`PeFile32`](super::PeFile32).
@@ -219,6 +225,21 @@ where
     pub fn pe_section(&self) -> &'data pe::ImageSectionHeader {
         self.section
     }
+
+    // Try GNU-style "ZLIB" header decompression.
+    fn maybe_compressed_gnu(&self) -> Result<Option<CompressedFileRange>> {
+        if !self
+            .name()
+            .map_or(false, |name| name.starts_with(".zdebug_"))
+        {
+            return Ok(None);
+        }
+        let (section_offset, section_size) = self
+            .file_range()
+            .read_error("Invalid PE GNU compressed section type")?;
+        gnu_compression::compressed_file_range(self.file.data, section_offset, section_size)
+            .map(Some)
+    }
 }
 
 impl<'data, 'file, Pe, R> read::private::Sealed for PeSection<'data, 'file, Pe, R>
@@ -278,14 +299,20 @@ where
         ))
     }
 
-    #[inline]
     fn compressed_file_range(&self) -> Result<CompressedFileRange> {
-        Ok(CompressedFileRange::none(self.file_range()))
+        Ok(if let Some(data) = self.maybe_compressed_gnu()? {
+            data
+        } else {
+            CompressedFileRange::none(self.file_range())
+        })
     }
 
-    #[inline]
     fn compressed_data(&self) -> Result<CompressedData<'data>> {
-        self.data().map(CompressedData::none)
+        if let Some(data) = self.maybe_compressed_gnu()? {
+            data.data(self.file.data)
+        } else {
+            self.data().map(CompressedData::none)
+        }
     }
 
     #[inline]
@@ -329,6 +356,11 @@ where
             characteristics: self.section.characteristics.get(LE),
         }
     }
+
+    #[inline]
+    fn permissions(&self) -> Permissions {
+        self.section.permissions()
+    }
 }
 
 impl<'data> SectionTable<'data> {
@@ -1,12 +1,15 @@
 use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
 use alloc::vec::Vec;
 
-use crate::endian::Endianness;
+use crate::endian::{Endian, Endianness};
 use crate::read::{
-    self, Architecture, CodeView, ComdatKind, CompressedData, CompressedFileRange, Export,
-    FileFlags, Import, ObjectKind, ObjectMap, Relocation, RelocationMap, Result, SectionFlags,
-    SectionIndex, SectionKind, SegmentFlags, SubArchitecture, SymbolFlags, SymbolIndex, SymbolKind,
-    SymbolMap, SymbolMapName, SymbolScope, SymbolSection,
+    self, Architecture, ByteString, Bytes, CodeView, ComdatKind, CompressedData,
+    CompressedFileRange, CompressionFormat, DebugFile, Error, Export, FileFlags, GoBuildInfo,
+    Import, ObjectKind, ObjectMap, Permissions, ReadError, Relocation, RelocationMap, Result,
+    RustMetadata, SectionFlags, SectionIndex, SectionKind, SectionNameIndex, SegmentFlags,
+    SourceFile, SubArchitecture, SymbolFlags, SymbolIndex, SymbolKind, SymbolMap, SymbolMapName,
+    SymbolScope, SymbolSection,
 };
 
 /// An object file.
@@ -154,6 +157,23 @@ pub trait Object<'data>: read::private::Sealed {
     /// Returns an error if the index is invalid.
     fn section_by_index(&self, index: SectionIndex) -> Result<Self::Section<'_>>;
 
+    /// Construct a map from section names to section indices.
+    ///
+    /// This is useful when looking up many sections by name, since
+    /// [`Self::section_by_name`] may need to iterate through all sections for each call.
+    ///
+    /// Names are compared exactly. Unlike [`Self::section_by_name`], the conventions
+    /// specific to the object file format are not used for system section names.
+    ///
+    /// Sections with invalid names are skipped.
+    fn section_name_index(&self) -> SectionNameIndex<'data> {
+        SectionNameIndex::new(
+            self.sections()
+                .filter_map(|section| Some((section.name_bytes().ok()?, section.index())))
+                .collect(),
+        )
+    }
+
     /// Get an iterator for the sections in the file.
     fn sections(&self) -> Self::SectionIterator<'_>;
 
@@ -203,6 +223,30 @@ pub trait Object<'data>: read::private::Sealed {
     /// Consider using [`Self::exports`] or [`Self::imports`] instead.
     fn dynamic_symbols(&self) -> Self::SymbolIterator<'_>;
 
+    /// Get the symbols from both the symbol table and the dynamic linking symbol table.
+    ///
+    /// The symbols in the symbol table are returned first, followed by the dynamic
+    /// linking symbols that are not also in the symbol table. A dynamic linking symbol
+    /// is considered to be in the symbol table if a symbol with the same name and
+    /// address exists there.
+    ///
+    /// The symbol index of each symbol is relative to the table that it came from.
+    fn all_symbols(&self) -> Vec<Self::Symbol<'_>> {
+        let mut symbols: Vec<_> = self.symbols().collect();
+        let names: BTreeSet<_> = symbols
+            .iter()
+            .filter_map(|symbol| Some((symbol.name_bytes().ok()?, symbol.address())))
+            .collect();
+        symbols.extend(
+            self.dynamic_symbols()
+                .filter(|symbol| match symbol.name_bytes() {
+                    Ok(name) => !names.contains(&(name, symbol.address())),
+                    Err(_) => true,
+                }),
+        );
+        symbols
+    }
+
     /// Get the dynamic relocations for this file.
     ///
     /// Symbol indices in these relocations refer to the dynamic symbol table.
@@ -215,62 +259,14 @@ pub trait Object<'data>: read::private::Sealed {
     /// The map will only contain defined text and data symbols.
     /// The dynamic symbol table will only be used if there are no debugging symbols.
     fn symbol_map(&self) -> SymbolMap<SymbolMapName<'data>> {
-        let mut symbols = Vec::new();
-        if let Some(table) = self.symbol_table().or_else(|| self.dynamic_symbol_table()) {
-            // Sometimes symbols share addresses. Collect them all then choose the "best".
-            let mut all_symbols = Vec::new();
-            for symbol in table.symbols() {
-                // Must have an address.
-                if !symbol.is_definition() {
-                    continue;
-                }
-                // Must have a name.
-                let name = match symbol.name() {
-                    Ok(name) => name,
-                    _ => continue,
-                };
-                if name.is_empty() {
-                    continue;
-                }
-
-                // Lower is better.
-                let mut priority = 0u32;
-
-                // Prefer known kind.
-                match symbol.kind() {
-                    SymbolKind::Text | SymbolKind::Data => {}
-                    SymbolKind::Unknown => priority += 1,
-                    _ => continue,
-                }
-                priority *= 2;
-
-                // Prefer global visibility.
-                priority += match symbol.scope() {
-                    SymbolScope::Unknown => 3,
-                    SymbolScope::Compilation => 2,
-                    SymbolScope::Linkage => 1,
-                    SymbolScope::Dynamic => 0,
-                };
-                priority *= 4;
-
-                // Prefer later entries (earlier symbol is likely to be less specific).
-                let index = !0 - symbol.index().0;
-
-                // Tuple is ordered for sort.
-                all_symbols.push((symbol.address(), priority, index, name));
-            }
-            // Unstable sort is okay because tuple includes index.
-            all_symbols.sort_unstable();
-
-            let mut previous_address = !0;
-            for (address, _priority, _index, name) in all_symbols {
-                if address != previous_address {
-                    symbols.push(SymbolMapName::new(address, name));
-                    previous_address = address;
-                }
-            }
-        }
-        SymbolMap::new(symbols)
+        let symbols = match self.symbol_table().or_else(|| self.dynamic_symbol_table()) {
+            Some(table) => table
+                .symbols()
+                .filter_map(|s| symbol_map_entry(&s))
+                .collect(),
+            None => Vec::new(),
+        };
+        symbol_map_from_entries(symbols)
     }
 
     /// Construct a map from addresses to symbol names and object file names.
@@ -280,6 +276,31 @@ pub trait Object<'data>: read::private::Sealed {
         ObjectMap::default()
     }
 
+    /// Get the names of the source files of the compilation units in the file.
+    ///
+    /// This uses the symbol table, and does not require parsing debug information.
+    /// For ELF, COFF and XCOFF, this uses the file symbols. For Mach-O, this uses
+    /// the `N_SO` STAB entries.
+    ///
+    /// The files are returned in the order of the symbol table, and may contain
+    /// duplicates.
+    fn source_files(&self) -> Result<Vec<SourceFile<'data>>> {
+        let mut files = Vec::new();
+        for symbol in self.symbols() {
+            if symbol.kind() != SymbolKind::File {
+                continue;
+            }
+            let name = symbol.name_bytes()?;
+            if !name.is_empty() {
+                files.push(SourceFile {
+                    directory: ByteString(&[]),
+                    name: ByteString(name),
+                });
+            }
+        }
+        Ok(files)
+    }
+
     /// Get the imported symbols.
     fn imports(&self) -> Result<Vec<Import<'data>>>;
 
@@ -322,6 +343,112 @@ pub trait Object<'data>: read::private::Sealed {
         Ok(None)
     }
 
+    /// The build ID of a Go binary.
+    ///
+    /// For ELF, this is read from a [`NT_GO_BUILD_ID`](crate::elf::NT_GO_BUILD_ID) note.
+    /// For other file formats, this is read from the marker that the Go linker
+    /// places at the start of the text section.
+    ///
+    /// This is unrelated to the GNU build ID returned by [`Self::build_id`], which
+    /// may also be present in Go binaries.
+    fn go_build_id(&self) -> Result<Option<&'data [u8]>> {
+        let Some(section) = self.sections().find(|s| s.kind() == SectionKind::Text) else {
+            return Ok(None);
+        };
+        let data = section.data()?;
+        Ok(go_build_id_marker(&data[..data.len().min(32 * 1024)]))
+    }
+
+    /// The build information of a Go binary.
+    ///
+    /// This is read from the `.go.buildinfo` section in ELF, or the `__go_buildinfo`
+    /// section in Mach-O. For other file formats, the data sections are searched
+    /// for the build information header.
+    ///
+    /// Both the inline format used since Go 1.18 and the older format that
+    /// refers to the strings by address are supported.
+    fn go_build_info(&self) -> Result<Option<GoBuildInfo<'data>>> {
+        for section in self.sections() {
+            if let Ok(b".go.buildinfo" | b"__go_buildinfo") = section.name_bytes() {
+                return parse_go_build_info(self, section.data()?).map(Some);
+            }
+        }
+        for section in self.sections() {
+            if section.kind() != SectionKind::Data {
+                continue;
+            }
+            let data = section.data()?;
+            let mut offset = 0;
+            while let Some(buildinfo) = data.get(offset..) {
+                if buildinfo.len() < 32 {
+                    break;
+                }
+                if buildinfo.starts_with(GO_BUILD_INFO_MAGIC) {
+                    return parse_go_build_info(self, buildinfo).map(Some);
+                }
+                // The header is aligned to 16 bytes.
+                offset += 16;
+            }
+        }
+        Ok(None)
+    }
+
+    /// The metadata that rustc embeds in a Rust library.
+    ///
+    /// This is read from the `.rustc` section in a dylib or proc-macro, or from
+    /// the `.rmeta` section in the `lib.rmeta` member of an rlib.
+    fn rust_metadata(&self) -> Result<Option<RustMetadata<'data>>> {
+        for section in self.sections() {
+            if let Ok(b".rustc" | b".rmeta") = section.name_bytes() {
+                return RustMetadata::parse(section.data()?).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// The LLVM bitcode that is embedded in the file.
+    ///
+    /// This is read from the `.llvmbc` section, or the `__LLVM,__bitcode` section
+    /// in Mach-O. rustc emits this section when using `-C embed-bitcode`.
+    fn llvm_bitcode(&self) -> Result<Option<&'data [u8]>> {
+        for section in self.sections() {
+            if let Ok(b".llvmbc" | b"__bitcode") = section.name_bytes() {
+                return section.data().map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the references to external files that contain debug information.
+    ///
+    /// This combines the results of [`Self::pdb_info`], [`Self::mach_uuid`],
+    /// [`Self::build_id`], [`Self::gnu_debuglink`] and [`Self::gnu_debugaltlink`],
+    /// so that the debug files for any file format can be found using the same API.
+    ///
+    /// Returns an error if any of these fail.
+    fn debug_files<'file>(&'file self) -> Result<Vec<DebugFile<'file>>>
+    where
+        'data: 'file,
+    {
+        let mut files = Vec::new();
+        if let Some(info) = self.pdb_info()? {
+            files.push(DebugFile::Pdb(info));
+        }
+        if let Some(uuid) = self.mach_uuid()? {
+            files.push(DebugFile::MachUuid(uuid));
+        }
+        if let Some(build_id) = self.build_id()? {
+            files.push(DebugFile::BuildId(build_id));
+        }
+        if let Some((filename, crc)) = self.gnu_debuglink()? {
+            files.push(DebugFile::GnuDebugLink { filename, crc });
+        }
+        if let Some((filename, build_id)) = self.gnu_debugaltlink()? {
+            files.push(DebugFile::GnuDebugAltLink { filename, build_id });
+        }
+        Ok(files)
+    }
+
     /// Get the base address used for relative virtual addresses.
     ///
     /// Currently this is only non-zero for PE.
@@ -332,6 +459,169 @@ pub trait Object<'data>: read::private::Sealed {
 
     /// File flags that are specific to each file format.
     fn flags(&self) -> FileFlags;
+
+    /// Get the problems that were found while parsing the file.
+    ///
+    /// This is only non-empty if the file was parsed with
+    /// [`Strictness::Permissive`](crate::read::Strictness::Permissive).
+    fn warnings(&self) -> &[Error] {
+        &[]
+    }
+}
+
+const GO_BUILD_ID_PREFIX: &[u8] = b"\xff Go build ID: \"";
+const GO_BUILD_ID_SUFFIX: &[u8] = b"\"\n \xff";
+const GO_BUILD_INFO_MAGIC: &[u8] = b"\xff Go buildinf:";
+
+/// Find the Go build ID marker in the given data, and return the build ID.
+fn go_build_id_marker(data: &[u8]) -> Option<&[u8]> {
+    let start = memchr::memmem::find(data, GO_BUILD_ID_PREFIX)? + GO_BUILD_ID_PREFIX.len();
+    let data = &data[start..];
+    let end = memchr::memmem::find(data, GO_BUILD_ID_SUFFIX)?;
+    Some(&data[..end])
+}
+
+/// Parse the Go build information that starts with [`GO_BUILD_INFO_MAGIC`].
+fn parse_go_build_info<'data, O: Object<'data> + ?Sized>(
+    object: &O,
+    data: &'data [u8],
+) -> Result<GoBuildInfo<'data>> {
+    let mut data = Bytes(data);
+    let header = data
+        .read_bytes(32)
+        .read_error("Invalid Go build info header size")?
+        .0;
+    if !header.starts_with(GO_BUILD_INFO_MAGIC) {
+        return Err(Error("Invalid Go build info magic"));
+    }
+    let pointer_size = header[14];
+    let flags = header[15];
+    let (version, mod_info) = if flags & 2 != 0 {
+        // The strings follow the header, each prefixed by a varint length.
+        let mut read_string = || -> Result<&'data [u8]> {
+            let len = data
+                .read_uleb128()
+                .read_error("Invalid Go build info string length")?;
+            data.read_bytes(len as usize)
+                .read_error("Invalid Go build info string length")
+                .map(|x| x.0)
+        };
+        (read_string()?, read_string()?)
+    } else {
+        // The header contains pointers to Go string headers.
+        if pointer_size != 4 && pointer_size != 8 {
+            return Err(Error("Invalid Go build info pointer size"));
+        }
+        let endian = if flags & 1 != 0 {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        };
+        let read_pointer = |data: &[u8]| -> Result<u64> {
+            match (pointer_size, data) {
+                (4, [a, b, c, d, ..]) => Ok(endian.read_u32_bytes([*a, *b, *c, *d]).into()),
+                (8, [a, b, c, d, e, f, g, h, ..]) => {
+                    Ok(endian.read_u64_bytes([*a, *b, *c, *d, *e, *f, *g, *h]))
+                }
+                _ => Err(Error("Invalid Go build info pointer")),
+            }
+        };
+        let read_data = |address: u64, size: u64| -> Result<&'data [u8]> {
+            for section in object.sections() {
+                if let Some(data) = section.data_range(address, size)? {
+                    return Ok(data);
+                }
+            }
+            Err(Error("Invalid Go build info address"))
+        };
+        let read_string = |pointer: &[u8]| -> Result<&'data [u8]> {
+            let address = read_pointer(pointer)?;
+            let string = read_data(address, u64::from(pointer_size) * 2)?;
+            let address = read_pointer(string)?;
+            let size = read_pointer(&string[usize::from(pointer_size)..])?;
+            read_data(address, size)
+        };
+        let pointers = &header[16..];
+        (
+            read_string(pointers)?,
+            read_string(&pointers[usize::from(pointer_size)..])?,
+        )
+    };
+    // The module information is surrounded by 16 byte sentinels.
+    let mod_info = if mod_info.len() >= 33 && mod_info[mod_info.len() - 17] == b'\n' {
+        &mod_info[16..mod_info.len() - 16]
+    } else {
+        &[]
+    };
+    Ok(GoBuildInfo {
+        version: ByteString(version),
+        mod_info: ByteString(mod_info),
+    })
+}
+
+/// An entry for sorting the candidates for a [`SymbolMap`].
+///
+/// The tuple is ordered so that sorting places the "best" symbol for each address first.
+pub(crate) type SymbolMapCandidate<'data> = (u64, u32, usize, &'data str);
+
+/// Return the [`SymbolMap`] sort key for a symbol, or `None` if it should not be in the map.
+pub(crate) fn symbol_map_entry<'data, S: ObjectSymbol<'data>>(
+    symbol: &S,
+) -> Option<SymbolMapCandidate<'data>> {
+    // Must have an address.
+    if !symbol.is_definition() {
+        return None;
+    }
+    // Must have a name.
+    let name = symbol.name().ok()?;
+    if name.is_empty() {
+        return None;
+    }
+
+    // Lower is better.
+    let mut priority = 0u32;
+
+    // Prefer known kind.
+    match symbol.kind() {
+        SymbolKind::Text | SymbolKind::Data => {}
+        SymbolKind::Unknown => priority += 1,
+        _ => return None,
+    }
+    priority *= 2;
+
+    // Prefer global visibility.
+    priority += match symbol.scope() {
+        SymbolScope::Unknown => 3,
+        SymbolScope::Compilation => 2,
+        SymbolScope::Linkage => 1,
+        SymbolScope::Dynamic => 0,
+    };
+    priority *= 4;
+
+    // Prefer later entries (earlier symbol is likely to be less specific).
+    let index = !0 - symbol.index().0;
+
+    Some((symbol.address(), priority, index, name))
+}
+
+/// Construct a [`SymbolMap`] from the candidates returned by [`symbol_map_entry`].
+///
+/// Sometimes symbols share addresses, so only the "best" candidate is kept for each address.
+pub(crate) fn symbol_map_from_entries(
+    mut all_symbols: Vec<SymbolMapCandidate<'_>>,
+) -> SymbolMap<SymbolMapName<'_>> {
+    // Unstable sort is okay because tuple includes index.
+    all_symbols.sort_unstable();
+
+    let mut symbols = Vec::new();
+    let mut previous_address = !0;
+    for (address, _priority, _index, name) in all_symbols {
+        if address != previous_address {
+            symbols.push(SymbolMapName::new(address, name));
+            previous_address = address;
+        }
+    }
+    SymbolMap::new(symbols)
 }
 
 /// A loadable segment in an [`Object`].
@@ -362,15 +652,29 @@ pub trait ObjectSegment<'data>: read::private::Sealed {
     fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>>;
 
     /// Returns the name of the segment.
+    ///
+    /// For Mach-O, this is the segment name in the load command.
+    /// For PE, this is the name of the section.
+    /// For ELF, this is the name of the segment type without the `PT_` prefix,
+    /// which is `LOAD` for the segments returned by [`Object::segments`].
+    ///
+    /// Returns `Ok(None)` if the segment does not have a name.
     fn name_bytes(&self) -> Result<Option<&[u8]>>;
 
     /// Returns the name of the segment.
     ///
+    /// See [`Self::name_bytes`].
+    ///
     /// Returns an error if the name is not UTF-8.
     fn name(&self) -> Result<Option<&str>>;
 
     /// Return the flags of segment.
     fn flags(&self) -> SegmentFlags;
+
+    /// Return the memory access permissions of the segment.
+    ///
+    /// These are the permissions that the loader initially applies to the segment.
+    fn permissions(&self) -> Permissions;
 }
 
 /// A section in an [`Object`].
@@ -421,6 +725,25 @@ pub trait ObjectSection<'data>: read::private::Sealed {
     /// along with information about the compression.
     fn compressed_data(&self) -> Result<CompressedData<'data>>;
 
+    /// Returns the file range of the section data, if the data is stored
+    /// uncompressed in the file.
+    ///
+    /// This range can be used to read or memory map the section data directly,
+    /// such as for lazy loading of DWARF sections.
+    ///
+    /// Returns `Ok(None)` if the section has no data in the file,
+    /// or if the data is compressed.
+    fn data_file_range(&self) -> Result<Option<(u64, u64)>> {
+        if self.file_range().is_none() {
+            return Ok(None);
+        }
+        let range = self.compressed_file_range()?;
+        if range.format != CompressionFormat::None {
+            return Ok(None);
+        }
+        Ok(Some((range.offset, range.compressed_size)))
+    }
+
     /// Returns the uncompressed contents of the section.
     ///
     /// The length of this data may be different from the size of the
@@ -432,6 +755,16 @@ pub trait ObjectSection<'data>: read::private::Sealed {
         self.compressed_data()?.decompress()
     }
 
+    /// Write the uncompressed contents of the section into the given buffer.
+    ///
+    /// The buffer is cleared first, and its allocation is reused if possible.
+    /// This avoids an allocation for each section when processing many sections.
+    ///
+    /// Returns `Err` if decompression fails.
+    fn uncompressed_data_into(&self, buf: &mut Vec<u8>) -> Result<()> {
+        self.compressed_data()?.decompress_into(buf)
+    }
+
     /// Returns the name of the section.
     fn name_bytes(&self) -> Result<&'data [u8]>;
 
@@ -459,6 +792,13 @@ pub trait ObjectSection<'data>: read::private::Sealed {
 
     /// Section flags that are specific to each file format.
     fn flags(&self) -> SectionFlags;
+
+    /// Return the memory access permissions of the section when it is loaded.
+    ///
+    /// Returns no permissions for sections that are not loaded into memory.
+    ///
+    /// For Mach-O, this is the initial protection of the segment containing the section.
+    fn permissions(&self) -> Permissions;
 }
 
 /// A COMDAT section group in an [`Object`].
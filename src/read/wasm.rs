@@ -11,9 +11,9 @@ use wasmparser as wp;
 use crate::read::{
     self, Architecture, ComdatKind, CompressedData, CompressedFileRange, Error, Export, FileFlags,
     Import, NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ObjectSection,
-    ObjectSegment, ObjectSymbol, ObjectSymbolTable, ReadError, ReadRef, Relocation, RelocationMap,
-    Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags, SymbolFlags, SymbolIndex,
-    SymbolKind, SymbolScope, SymbolSection,
+    ObjectSegment, ObjectSymbol, ObjectSymbolTable, Permissions, ReadError, ReadRef, Relocation,
+    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags, SymbolFlags,
+    SymbolIndex, SymbolKind, SymbolScope, SymbolSection,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -622,6 +622,11 @@ impl<'data, 'file, R> ObjectSegment<'data> for WasmSegment<'data, 'file, R> {
     fn flags(&self) -> SegmentFlags {
         unreachable!()
     }
+
+    #[inline]
+    fn permissions(&self) -> Permissions {
+        unreachable!()
+    }
 }
 
 /// An iterator for the sections in a [`WasmFile`].
@@ -780,6 +785,16 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for WasmSection<'data
     fn flags(&self) -> SectionFlags {
         SectionFlags::None
     }
+
+    fn permissions(&self) -> Permissions {
+        // Code can only be executed, and data segments are copied to linear memory,
+        // which can be read and written. Other sections are not loaded.
+        match self.section.id {
+            SectionId::Code => Permissions::new(false, false, true),
+            SectionId::Data => Permissions::new(true, true, false),
+            _ => Permissions::default(),
+        }
+    }
 }
 
 /// An iterator for the COMDAT section groups in a [`WasmFile`].
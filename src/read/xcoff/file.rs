@@ -7,7 +7,7 @@ use crate::endian::BigEndian as BE;
 use crate::pod::Pod;
 use crate::read::{
     self, Architecture, Error, Export, FileFlags, Import, NoDynamicRelocationIterator, Object,
-    ObjectKind, ObjectSection, ReadError, ReadRef, Result, SectionIndex, SymbolIndex,
+    ObjectKind, ObjectSection, ParseOptions, ReadError, ReadRef, Result, SectionIndex, SymbolIndex,
 };
 use crate::xcoff;
 
@@ -42,6 +42,7 @@ where
     pub(super) aux_header: Option<&'data Xcoff::AuxHeader>,
     pub(super) sections: SectionTable<'data, Xcoff>,
     pub(super) symbols: SymbolTable<'data, Xcoff, R>,
+    pub(super) warnings: Vec<Error>,
 }
 
 impl<'data, Xcoff, R> XcoffFile<'data, Xcoff, R>
@@ -51,11 +52,24 @@ where
 {
     /// Parse the raw XCOFF file data.
     pub fn parse(data: R) -> Result<Self> {
+        Self::parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw XCOFF file data, using the given options.
+    ///
+    /// The `dynamic_symbols` and `relocations` options are ignored.
+    pub fn parse_with(data: R, options: &ParseOptions) -> Result<Self> {
+        options.verify_file(data)?;
         let mut offset = 0;
         let header = Xcoff::parse(data, &mut offset)?;
         let aux_header = header.aux_header(data, &mut offset)?;
         let sections = header.sections(data, &mut offset)?;
-        let symbols = header.symbols(data)?;
+        let mut warnings = Vec::new();
+        let symbols = if options.symbols {
+            options.recover(&mut warnings, header.symbols(data))?
+        } else {
+            SymbolTable::default()
+        };
 
         Ok(XcoffFile {
             data,
@@ -63,6 +77,7 @@ where
             aux_header,
             sections,
             symbols,
+            warnings,
         })
     }
 
@@ -71,6 +86,13 @@ where
         self.data
     }
 
+    /// Returns the problems that were found while parsing the file.
+    ///
+    /// See [`Strictness::Permissive`](crate::read::Strictness::Permissive).
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
     /// Returns the raw XCOFF file header.
     #[deprecated(note = "Use `xcoff_header` instead")]
     pub fn raw_header(&self) -> &'data Xcoff {
@@ -299,6 +321,10 @@ where
             f_flags: self.header.f_flags(),
         }
     }
+
+    fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
 }
 
 /// A trait for generic access to [`xcoff::FileHeader32`] and [`xcoff::FileHeader64`].
@@ -4,8 +4,8 @@ use core::{iter, result, slice, str};
 use crate::endian::BigEndian as BE;
 use crate::pod::Pod;
 use crate::read::{
-    self, CompressedData, CompressedFileRange, Error, ObjectSection, ReadError, ReadRef,
-    RelocationMap, Result, SectionFlags, SectionIndex, SectionKind,
+    self, CompressedData, CompressedFileRange, Error, ObjectSection, Permissions, ReadError,
+    ReadRef, RelocationMap, Result, SectionFlags, SectionIndex, SectionKind,
 };
 use crate::xcoff;
 
@@ -216,6 +216,20 @@ where
         }
     }
 
+    fn permissions(&self) -> Permissions {
+        let section_type = self.section.s_flags() as u16;
+        if section_type & xcoff::STYP_TEXT != 0 {
+            Permissions::new(true, false, true)
+        } else if section_type
+            & (xcoff::STYP_DATA | xcoff::STYP_BSS | xcoff::STYP_TDATA | xcoff::STYP_TBSS)
+            != 0
+        {
+            Permissions::new(true, true, false)
+        } else {
+            Permissions::default()
+        }
+    }
+
     fn uncompressed_data(&self) -> Result<alloc::borrow::Cow<'data, [u8]>> {
         self.compressed_data()?.decompress()
     }
@@ -0,0 +1,130 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::elf;
+use crate::read::elf::{FileHeader, ProgramHeader, SectionHeader, SectionTable};
+use crate::read::{self, ReadRef};
+
+use super::{DiagnosticKind, Diagnostics, Range};
+
+pub(super) fn verify<'data, Elf: FileHeader, R: ReadRef<'data>>(
+    d: &mut Diagnostics,
+    data: R,
+) -> read::Result<()> {
+    let header = Elf::parse(data)?;
+    let endian = header.endian()?;
+
+    match header.program_headers(endian, data) {
+        Ok(segments) => verify_segments::<Elf>(d, endian, segments),
+        Err(e) => d.parse_error("program headers", e),
+    }
+
+    let sections = match header.sections(endian, data) {
+        Ok(sections) => sections,
+        Err(e) => {
+            d.parse_error("section headers", e);
+            return Ok(());
+        }
+    };
+    verify_sections(d, endian, &sections);
+    for (sh_type, table) in [
+        (elf::SHT_SYMTAB, "symbol"),
+        (elf::SHT_DYNSYM, "dynamic symbol"),
+    ] {
+        match sections.symbols(endian, data, sh_type) {
+            Ok(symbols) => {
+                for (index, symbol) in symbols.enumerate() {
+                    match symbols.symbol_section(endian, symbol, index) {
+                        Ok(Some(section)) if section.0 >= sections.len() => d.push(
+                            DiagnosticKind::InvalidReference,
+                            format!(
+                                "{} {} has invalid section index {}",
+                                table, index.0, section.0
+                            ),
+                        ),
+                        Ok(_) => {}
+                        Err(e) => d.push(
+                            DiagnosticKind::InvalidReference,
+                            format!("{} {}: {}", table, index.0, e),
+                        ),
+                    }
+                }
+            }
+            Err(e) => d.parse_error(&format!("{} table", table), e),
+        }
+    }
+    Ok(())
+}
+
+fn verify_segments<Elf: FileHeader>(
+    d: &mut Diagnostics,
+    endian: Elf::Endian,
+    segments: &[Elf::ProgramHeader],
+) {
+    let mut addresses = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let name = format!("segment {}", index);
+        let (offset, size) = segment.file_range(endian);
+        if size != 0 {
+            d.check_file_range(&name, offset, size);
+        }
+        if segment.p_type(endian) == elf::PT_LOAD {
+            let memsz = segment.p_memsz(endian).into();
+            if size > memsz {
+                d.push(
+                    DiagnosticKind::InvalidSize,
+                    format!(
+                        "{} file size {:#x} is larger than memory size {:#x}",
+                        name, size, memsz
+                    ),
+                );
+            }
+            addresses.push(Range::new(name, segment.p_vaddr(endian).into(), memsz));
+        }
+    }
+    d.check_overlaps("address", addresses);
+}
+
+fn verify_sections<'data, Elf: FileHeader, R: ReadRef<'data>>(
+    d: &mut Diagnostics,
+    endian: Elf::Endian,
+    sections: &SectionTable<'data, Elf, R>,
+) {
+    let mut ranges = Vec::new();
+    for (index, section) in sections.enumerate().skip(1) {
+        let name = format!(
+            "section {} ({})",
+            index.0,
+            String::from_utf8_lossy(
+                sections
+                    .section_name(endian, section)
+                    .unwrap_or(b"<invalid>")
+            )
+        );
+        if let Some((offset, size)) = section.file_range(endian) {
+            if size != 0 && d.check_file_range(&name, offset, size) {
+                ranges.push(Range::new(name.clone(), offset, size));
+            }
+        }
+
+        let link = section.sh_link(endian) as usize;
+        if link >= sections.len() {
+            d.push(
+                DiagnosticKind::InvalidReference,
+                format!("{} has invalid sh_link {}", name, link),
+            );
+        }
+        let sh_type = section.sh_type(endian);
+        let info_link = sh_type == elf::SHT_REL
+            || sh_type == elf::SHT_RELA
+            || section.sh_flags(endian).into() & u64::from(elf::SHF_INFO_LINK) != 0;
+        let info = section.sh_info(endian) as usize;
+        if info_link && info >= sections.len() {
+            d.push(
+                DiagnosticKind::InvalidReference,
+                format!("{} has invalid sh_info {}", name, info),
+            );
+        }
+    }
+    d.check_overlaps("file", ranges);
+}
@@ -0,0 +1,160 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::macho;
+use crate::read::macho::{MachHeader, Nlist, Section, Segment};
+use crate::read::{self, ReadRef};
+
+use super::{DiagnosticKind, Diagnostics, Range};
+
+pub(super) fn verify<'data, Mach: MachHeader, R: ReadRef<'data>>(
+    d: &mut Diagnostics,
+    data: R,
+) -> read::Result<()> {
+    let header = Mach::parse(data, 0)?;
+    let endian = header.endian()?;
+    let mut commands = match header.load_commands(endian, data, 0) {
+        Ok(commands) => commands,
+        Err(e) => {
+            d.parse_error("load commands", e);
+            return Ok(());
+        }
+    };
+
+    let command_align = if header.is_type_64() { 8 } else { 4 };
+    let mut commands_size = 0;
+    let mut section_count = 0;
+    let mut symtab = None;
+    let mut addresses = Vec::new();
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    loop {
+        let command = match commands.next() {
+            Ok(Some(command)) => command,
+            Ok(None) => break,
+            Err(e) => {
+                d.parse_error(&format!("load command {}", index), e);
+                break;
+            }
+        };
+        let cmdsize = command.cmdsize();
+        if cmdsize % command_align != 0 {
+            d.push(
+                DiagnosticKind::InvalidSize,
+                format!(
+                    "load command {} size {:#x} is not a multiple of {}",
+                    index, cmdsize, command_align
+                ),
+            );
+        }
+        commands_size += u64::from(cmdsize);
+
+        match Mach::Segment::from_command(command) {
+            Ok(Some((segment, section_data))) => {
+                let name = format!("segment {}", String::from_utf8_lossy(segment.name()));
+                let (offset, size) = segment.file_range(endian);
+                if size != 0 {
+                    d.check_file_range(&name, offset, size);
+                }
+                let address = segment.vmaddr(endian).into();
+                let vmsize = segment.vmsize(endian).into();
+                addresses.push(Range::new(name.clone(), address, vmsize));
+                match segment.sections(endian, section_data) {
+                    Ok(sections) => {
+                        for section in sections {
+                            section_count += 1;
+                            verify_section::<Mach>(
+                                d,
+                                endian,
+                                section,
+                                &name,
+                                Range::new(String::new(), address, vmsize),
+                                &mut ranges,
+                            );
+                        }
+                    }
+                    Err(e) => d.parse_error(&format!("{} sections", name), e),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => d.parse_error(&format!("load command {}", index), e),
+        }
+        if let Ok(Some(command)) = command.symtab() {
+            symtab = Some(command);
+        }
+        index += 1;
+    }
+    let sizeofcmds = u64::from(header.sizeofcmds(endian));
+    if commands_size != sizeofcmds {
+        d.push(
+            DiagnosticKind::InvalidSize,
+            format!(
+                "load command sizes total {:#x} but sizeofcmds is {:#x}",
+                commands_size, sizeofcmds
+            ),
+        );
+    }
+    d.check_overlaps("address", addresses);
+    d.check_overlaps("file", ranges);
+
+    if let Some(symtab) = symtab {
+        match symtab.symbols::<Mach, _>(endian, data) {
+            Ok(symbols) => {
+                for (index, symbol) in symbols.iter().enumerate() {
+                    let n_type = symbol.n_type();
+                    if n_type & macho::N_STAB != 0 || n_type & macho::N_TYPE != macho::N_SECT {
+                        continue;
+                    }
+                    let n_sect = symbol.n_sect();
+                    if n_sect == 0 || usize::from(n_sect) > section_count {
+                        d.push(
+                            DiagnosticKind::InvalidReference,
+                            format!("symbol {} has invalid section number {}", index, n_sect),
+                        );
+                    }
+                }
+            }
+            Err(e) => d.parse_error("symbol table", e),
+        }
+    }
+    Ok(())
+}
+
+fn verify_section<Mach: MachHeader>(
+    d: &mut Diagnostics,
+    endian: Mach::Endian,
+    section: &Mach::Section,
+    segment_name: &str,
+    segment: Range,
+    ranges: &mut Vec<Range>,
+) {
+    let name = format!(
+        "section {},{}",
+        String::from_utf8_lossy(section.segment_name()),
+        String::from_utf8_lossy(section.name())
+    );
+    let size = section.size(endian).into();
+    if let Some((offset, size)) = section.file_range(endian) {
+        if size != 0 && d.check_file_range(&name, offset, size) {
+            ranges.push(Range::new(name.clone(), offset, size));
+        }
+    }
+    let address = section.addr(endian).into();
+    if size != 0 {
+        let end = address.checked_add(size);
+        if address < segment.start || end.map_or(true, |end| end > segment.end) {
+            d.push(
+                DiagnosticKind::OutOfBounds,
+                format!(
+                    "{} address range {:#x}..{:#x} is outside {} {:#x}..{:#x}",
+                    name,
+                    address,
+                    address.wrapping_add(size),
+                    segment_name,
+                    segment.start,
+                    segment.end
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Structural verification of object files.
+//!
+//! The parsers in the [`read`] module only check the parts of a
+//! file that are needed for each operation, and tolerate many inconsistencies.
+//! [`verify`] instead checks the whole file for structural consistency, such as
+//! overlapping sections, ranges that extend outside the file, and invalid
+//! section references. It returns a list of [`Diagnostic`]s.
+//!
+//! ELF, Mach-O and PE files are checked in detail. For other formats, only the
+//! section file ranges are checked.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::read::{self, FileKind, Object, ObjectSection, ReadRef};
+
+#[cfg(feature = "elf")]
+mod elf;
+
+#[cfg(feature = "macho")]
+mod macho;
+
+#[cfg(feature = "pe")]
+mod pe;
+
+/// The kind of problem found by [`verify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// A header or table could not be parsed.
+    Parse,
+    /// A range extends outside the file or the containing range.
+    OutOfBounds,
+    /// Ranges that must be disjoint overlap.
+    Overlap,
+    /// A reference to a section or other table entry is invalid.
+    InvalidReference,
+    /// A size field is inconsistent with other fields.
+    InvalidSize,
+}
+
+/// A problem found by [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The kind of problem.
+    pub kind: DiagnosticKind,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Check the given file data for structural consistency.
+///
+/// Returns an error if the file format is unknown or the file header can't
+/// be parsed. All other problems are returned as diagnostics, and an empty
+/// list means that no problems were found.
+pub fn verify<'data, R: ReadRef<'data>>(data: R) -> read::Result<Vec<Diagnostic>> {
+    let file_len = data
+        .len()
+        .map_err(|()| read::Error("Unknown file length"))?;
+    let mut diagnostics = Diagnostics {
+        list: Vec::new(),
+        file_len,
+    };
+    match FileKind::parse(data)? {
+        #[cfg(feature = "elf")]
+        FileKind::Elf32 => {
+            elf::verify::<crate::elf::FileHeader32<crate::Endianness>, R>(&mut diagnostics, data)?
+        }
+        #[cfg(feature = "elf")]
+        FileKind::Elf64 => {
+            elf::verify::<crate::elf::FileHeader64<crate::Endianness>, R>(&mut diagnostics, data)?
+        }
+        #[cfg(feature = "macho")]
+        FileKind::MachO32 => macho::verify::<crate::macho::MachHeader32<crate::Endianness>, R>(
+            &mut diagnostics,
+            data,
+        )?,
+        #[cfg(feature = "macho")]
+        FileKind::MachO64 => macho::verify::<crate::macho::MachHeader64<crate::Endianness>, R>(
+            &mut diagnostics,
+            data,
+        )?,
+        #[cfg(feature = "pe")]
+        FileKind::Pe32 => pe::verify::<crate::pe::ImageNtHeaders32, R>(&mut diagnostics, data)?,
+        #[cfg(feature = "pe")]
+        FileKind::Pe64 => pe::verify::<crate::pe::ImageNtHeaders64, R>(&mut diagnostics, data)?,
+        #[allow(unreachable_patterns)]
+        _ => verify_sections(&mut diagnostics, data)?,
+    }
+    Ok(diagnostics.list)
+}
+
+/// Check the section file ranges using the unified read API.
+fn verify_sections<'data, R: ReadRef<'data>>(d: &mut Diagnostics, data: R) -> read::Result<()> {
+    let file = read::File::parse(data)?;
+    let mut ranges = Vec::new();
+    for section in file.sections() {
+        let name = format!(
+            "section {} ({})",
+            section.index().0,
+            String::from_utf8_lossy(section.name_bytes().unwrap_or(b"<invalid>"))
+        );
+        if let Some((offset, size)) = section.file_range() {
+            if size != 0 && d.check_file_range(&name, offset, size) {
+                ranges.push(Range::new(name, offset, size));
+            }
+        }
+    }
+    d.check_overlaps("file", ranges);
+    Ok(())
+}
+
+/// A named range, used for overlap checks.
+struct Range {
+    name: String,
+    start: u64,
+    end: u64,
+}
+
+impl Range {
+    fn new(name: String, start: u64, size: u64) -> Self {
+        Range {
+            name,
+            start,
+            end: start.saturating_add(size),
+        }
+    }
+}
+
+struct Diagnostics {
+    list: Vec<Diagnostic>,
+    file_len: u64,
+}
+
+impl Diagnostics {
+    fn push(&mut self, kind: DiagnosticKind, message: String) {
+        self.list.push(Diagnostic { kind, message });
+    }
+
+    #[cfg(any(feature = "elf", feature = "macho", feature = "pe"))]
+    fn parse_error(&mut self, what: &str, error: read::Error) {
+        self.push(
+            DiagnosticKind::Parse,
+            format!("failed to parse {}: {}", what, error),
+        );
+    }
+
+    /// Check that a range is within the file.
+    ///
+    /// Returns true if the range is valid.
+    fn check_file_range(&mut self, name: &str, offset: u64, size: u64) -> bool {
+        match offset.checked_add(size) {
+            Some(end) if end <= self.file_len => true,
+            _ => {
+                self.push(
+                    DiagnosticKind::OutOfBounds,
+                    format!(
+                        "{} file range {:#x}..{:#x} is outside the file (size {:#x})",
+                        name,
+                        offset,
+                        offset.wrapping_add(size),
+                        self.file_len
+                    ),
+                );
+                false
+            }
+        }
+    }
+
+    /// Report each pair of overlapping ranges.
+    ///
+    /// `space` describes the kind of range, such as "file" or "address".
+    fn check_overlaps(&mut self, space: &str, mut ranges: Vec<Range>) {
+        ranges.sort_by_key(|range| (range.start, range.end));
+        // The range with the largest end so far.
+        let mut previous: Option<&Range> = None;
+        for range in &ranges {
+            if range.start == range.end {
+                continue;
+            }
+            if let Some(previous) = previous {
+                if range.start < previous.end {
+                    self.push(
+                        DiagnosticKind::Overlap,
+                        format!(
+                            "{} {} range {:#x}..{:#x} overlaps {} {:#x}..{:#x}",
+                            range.name,
+                            space,
+                            range.start,
+                            range.end,
+                            previous.name,
+                            previous.start,
+                            previous.end
+                        ),
+                    );
+                }
+            }
+            if previous.map_or(true, |previous| range.end > previous.end) {
+                previous = Some(range);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::pe;
+use crate::read::pe::{ImageNtHeaders, ImageOptionalHeader};
+use crate::read::{self, ReadRef};
+
+use super::{DiagnosticKind, Diagnostics, Range};
+
+pub(super) fn verify<'data, Pe: ImageNtHeaders, R: ReadRef<'data>>(
+    d: &mut Diagnostics,
+    data: R,
+) -> read::Result<()> {
+    let dos_header = pe::ImageDosHeader::parse(data)?;
+    let mut offset = dos_header.nt_headers_offset().into();
+    let (nt_headers, data_directories) = Pe::parse(data, &mut offset)?;
+    let size_of_image = u64::from(nt_headers.optional_header().size_of_image());
+
+    match nt_headers.sections(data, offset) {
+        Ok(sections) => {
+            let mut ranges = Vec::new();
+            let mut addresses = Vec::new();
+            for (index, section) in sections.iter().enumerate() {
+                let name = format!(
+                    "section {} ({})",
+                    index + 1,
+                    String::from_utf8_lossy(section.raw_name())
+                );
+                let offset = u64::from(section.pointer_to_raw_data.get(crate::LittleEndian));
+                let size = u64::from(section.size_of_raw_data.get(crate::LittleEndian));
+                if size != 0 && d.check_file_range(&name, offset, size) {
+                    ranges.push(Range::new(name.clone(), offset, size));
+                }
+                let (address, size) = section.pe_address_range();
+                let (address, size) = (u64::from(address), u64::from(size));
+                check_image_range(d, &name, address, size, size_of_image);
+                addresses.push(Range::new(name, address, size));
+            }
+            d.check_overlaps("file", ranges);
+            d.check_overlaps("address", addresses);
+        }
+        Err(e) => d.parse_error("section headers", e),
+    }
+
+    for (index, directory) in data_directories.enumerate() {
+        let (address, size) = directory.address_range();
+        if address == 0 && size == 0 {
+            continue;
+        }
+        let name = format!("data directory {}", index);
+        if index == pe::IMAGE_DIRECTORY_ENTRY_SECURITY {
+            // The security directory uses a file offset instead of an address.
+            d.check_file_range(&name, address.into(), size.into());
+        } else {
+            check_image_range(d, &name, address.into(), size.into(), size_of_image);
+        }
+    }
+    Ok(())
+}
+
+fn check_image_range(d: &mut Diagnostics, name: &str, address: u64, size: u64, size_of_image: u64) {
+    if address + size > size_of_image {
+        d.push(
+            DiagnosticKind::OutOfBounds,
+            format!(
+                "{} address range {:#x}..{:#x} is outside the image (size {:#x})",
+                name,
+                address,
+                address + size,
+                size_of_image
+            ),
+        );
+    }
+}
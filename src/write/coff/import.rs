@@ -0,0 +1,521 @@
+//! Helper for writing Windows import libraries.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::mem;
+
+use crate::endian::{LittleEndian as LE, U16, U32};
+use crate::pe;
+use crate::pod::bytes_of;
+use crate::write::coff::{FileHeader, Relocation, SectionHeader, Symbol, Writer};
+use crate::write::{Error, Result, WritableBuffer};
+
+/// The format of an import library written by [`write_import_library`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ImportLibraryFormat {
+    /// The format used by the MSVC toolchain.
+    ///
+    /// The archive contains both COFF linker members and a long names member.
+    Msvc,
+    /// The format used by the MinGW toolchain.
+    ///
+    /// The archive uses the GNU format, which contains a single symbol table.
+    Gnu,
+}
+
+/// A symbol exported by a DLL, for use with [`write_import_library`].
+#[derive(Debug, Clone, Copy)]
+pub struct ImportLibraryExport<'a> {
+    /// The name of the symbol that is defined by the import library.
+    ///
+    /// This must include any decoration that is used by the target, such as
+    /// the leading underscore for I386.
+    ///
+    /// The import library also defines this name with an `__imp_` prefix,
+    /// which is the address of the import address table entry.
+    pub name: &'a [u8],
+    /// The name of the export in the DLL.
+    ///
+    /// This must be set if and only if `name_type` is [`pe::IMPORT_OBJECT_NAME_EXPORTAS`].
+    pub export_name: Option<&'a [u8]>,
+    /// The ordinal of the export if `name_type` is [`pe::IMPORT_OBJECT_ORDINAL`],
+    /// otherwise the hint for the export name.
+    pub ordinal_or_hint: u16,
+    /// The import type.
+    ///
+    /// One of the `IMPORT_OBJECT_CODE`, `IMPORT_OBJECT_DATA` or `IMPORT_OBJECT_CONST`
+    /// constants in [`pe`].
+    pub import_type: u16,
+    /// The way that the import name is determined from the symbol name.
+    ///
+    /// One of the `IMPORT_OBJECT_ORDINAL` or `IMPORT_OBJECT_NAME*` constants in [`pe`].
+    pub name_type: u16,
+}
+
+/// Write an import library for a DLL.
+///
+/// This writes an archive containing the objects that define the import directory
+/// entry for the DLL, followed by a short import member for each export.
+///
+/// `dll_name` is the file name of the DLL, such as `kernel32.dll`.
+/// `machine` is one of the `IMAGE_FILE_MACHINE_*` constants in [`pe`].
+pub fn write_import_library(
+    buffer: &mut dyn WritableBuffer,
+    dll_name: &[u8],
+    machine: u16,
+    format: ImportLibraryFormat,
+    exports: &[ImportLibraryExport<'_>],
+) -> Result<()> {
+    let (reloc_type, pointer_size) = match machine {
+        pe::IMAGE_FILE_MACHINE_I386 => (pe::IMAGE_REL_I386_DIR32NB, 4),
+        pe::IMAGE_FILE_MACHINE_ARMNT => (pe::IMAGE_REL_ARM_ADDR32NB, 4),
+        pe::IMAGE_FILE_MACHINE_AMD64 => (pe::IMAGE_REL_AMD64_ADDR32NB, 8),
+        pe::IMAGE_FILE_MACHINE_ARM64
+        | pe::IMAGE_FILE_MACHINE_ARM64EC
+        | pe::IMAGE_FILE_MACHINE_ARM64X => (pe::IMAGE_REL_ARM64_ADDR32NB, 8),
+        _ => {
+            return Err(Error(format!(
+                "unimplemented machine {:#x} for import library",
+                machine
+            )));
+        }
+    };
+
+    // The library name is the DLL name without the extension.
+    let library = match dll_name.iter().rposition(|&b| b == b'.') {
+        Some(index) => &dll_name[..index],
+        None => dll_name,
+    };
+    let descriptor_name = [b"__IMPORT_DESCRIPTOR_", library].concat();
+    let null_descriptor_name = b"__NULL_IMPORT_DESCRIPTOR".to_vec();
+    let null_thunk_name = [b"\x7f", library, b"_NULL_THUNK_DATA"].concat();
+
+    let mut members = Vec::new();
+    members.push(ImportMember {
+        data: import_descriptor(
+            machine,
+            reloc_type,
+            dll_name,
+            &descriptor_name,
+            &null_descriptor_name,
+            &null_thunk_name,
+        )?,
+        symbols: vec![descriptor_name.clone()],
+    });
+    members.push(ImportMember {
+        data: null_import_descriptor(machine, &null_descriptor_name)?,
+        symbols: vec![null_descriptor_name.clone()],
+    });
+    members.push(ImportMember {
+        data: null_thunk(machine, pointer_size, &null_thunk_name)?,
+        symbols: vec![null_thunk_name.clone()],
+    });
+    for export in exports {
+        let mut symbols = vec![[b"__imp_", export.name].concat()];
+        if export.import_type != pe::IMPORT_OBJECT_DATA {
+            symbols.push(export.name.to_vec());
+        }
+        members.push(ImportMember {
+            data: short_import(machine, dll_name, export)?,
+            symbols,
+        });
+    }
+
+    let data = write_archive(dll_name, format, &members)?;
+    buffer
+        .reserve(data.len())
+        .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+    buffer.write_bytes(&data);
+    Ok(())
+}
+
+struct ImportMember {
+    data: Vec<u8>,
+    symbols: Vec<Vec<u8>>,
+}
+
+fn file_characteristics(machine: u16) -> u16 {
+    match machine {
+        pe::IMAGE_FILE_MACHINE_I386 | pe::IMAGE_FILE_MACHINE_ARMNT => pe::IMAGE_FILE_32BIT_MACHINE,
+        _ => 0,
+    }
+}
+
+/// Write the object containing the import directory entry for the DLL.
+///
+/// The entry references the import lookup table in `.idata$4` and the import
+/// address table in `.idata$5`, which the linker creates from the `.idata$4` and
+/// `.idata$5` sections of the other members.
+fn import_descriptor(
+    machine: u16,
+    reloc_type: u16,
+    dll_name: &[u8],
+    descriptor_name: &[u8],
+    null_descriptor_name: &[u8],
+    null_thunk_name: &[u8],
+) -> Result<Vec<u8>> {
+    const DIRECTORY_SIZE: usize = mem::size_of::<pe::ImageImportDescriptor>();
+    let mut dll_name_data = dll_name.to_vec();
+    dll_name_data.push(0);
+    if dll_name_data.len() % 2 != 0 {
+        dll_name_data.push(0);
+    }
+    let relocations = [
+        // `Name`
+        (12, 2),
+        // `OriginalFirstThunk`
+        (0, 3),
+        // `FirstThunk`
+        (16, 4),
+    ];
+
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(&mut buffer);
+    let idata2_name = writer.add_name(b".idata$2");
+    let idata6_name = writer.add_name(b".idata$6");
+    let descriptor_name = writer.add_name(descriptor_name);
+    let null_descriptor_name = writer.add_name(null_descriptor_name);
+    let null_thunk_name = writer.add_name(null_thunk_name);
+
+    writer.reserve_file_header();
+    writer.reserve_section_headers(2);
+    let idata2_offset = writer.reserve_section(DIRECTORY_SIZE);
+    let reloc_offset = writer.reserve_relocations(relocations.len());
+    let idata6_offset = writer.reserve_section(dll_name_data.len());
+    writer.reserve_symbol_indices(7);
+    writer.reserve_symtab_strtab();
+
+    writer.write_file_header(FileHeader {
+        machine,
+        time_date_stamp: 0,
+        characteristics: file_characteristics(machine),
+    })?;
+    writer.write_section_header(SectionHeader {
+        name: idata2_name,
+        size_of_raw_data: DIRECTORY_SIZE as u32,
+        pointer_to_raw_data: idata2_offset,
+        pointer_to_relocations: reloc_offset,
+        number_of_relocations: relocations.len() as u32,
+        characteristics: pe::IMAGE_SCN_ALIGN_4BYTES
+            | pe::IMAGE_SCN_CNT_INITIALIZED_DATA
+            | pe::IMAGE_SCN_MEM_READ
+            | pe::IMAGE_SCN_MEM_WRITE,
+        ..Default::default()
+    });
+    writer.write_section_header(SectionHeader {
+        name: idata6_name,
+        size_of_raw_data: dll_name_data.len() as u32,
+        pointer_to_raw_data: idata6_offset,
+        characteristics: pe::IMAGE_SCN_ALIGN_2BYTES
+            | pe::IMAGE_SCN_CNT_INITIALIZED_DATA
+            | pe::IMAGE_SCN_MEM_READ
+            | pe::IMAGE_SCN_MEM_WRITE,
+        ..Default::default()
+    });
+    writer.write_section_zeroes(DIRECTORY_SIZE);
+    for (virtual_address, symbol) in relocations {
+        writer.write_relocation(Relocation {
+            virtual_address,
+            symbol,
+            typ: reloc_type,
+        });
+    }
+    writer.write_section(&dll_name_data);
+
+    let symbols = [
+        (descriptor_name, 1, pe::IMAGE_SYM_CLASS_EXTERNAL),
+        (idata2_name, 1, pe::IMAGE_SYM_CLASS_SECTION),
+        (idata6_name, 2, pe::IMAGE_SYM_CLASS_STATIC),
+        (b".idata$4".into(), 0, pe::IMAGE_SYM_CLASS_SECTION),
+        (b".idata$5".into(), 0, pe::IMAGE_SYM_CLASS_SECTION),
+        (null_descriptor_name, 0, pe::IMAGE_SYM_CLASS_EXTERNAL),
+        (null_thunk_name, 0, pe::IMAGE_SYM_CLASS_EXTERNAL),
+    ];
+    for (name, section_number, storage_class) in symbols {
+        writer.write_symbol(Symbol {
+            name,
+            section_number,
+            storage_class,
+            ..Default::default()
+        });
+    }
+    writer.write_strtab();
+    debug_assert_eq!(writer.reserved_len(), writer.len());
+    Ok(buffer)
+}
+
+/// Write the object containing the null import directory entry that terminates
+/// the import directory.
+fn null_import_descriptor(machine: u16, null_descriptor_name: &[u8]) -> Result<Vec<u8>> {
+    const DIRECTORY_SIZE: usize = mem::size_of::<pe::ImageImportDescriptor>();
+
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(&mut buffer);
+    let null_descriptor_name = writer.add_name(null_descriptor_name);
+
+    writer.reserve_file_header();
+    writer.reserve_section_headers(1);
+    let idata3_offset = writer.reserve_section(DIRECTORY_SIZE);
+    writer.reserve_symbol_indices(1);
+    writer.reserve_symtab_strtab();
+
+    writer.write_file_header(FileHeader {
+        machine,
+        time_date_stamp: 0,
+        characteristics: file_characteristics(machine),
+    })?;
+    writer.write_section_header(SectionHeader {
+        name: b".idata$3".into(),
+        size_of_raw_data: DIRECTORY_SIZE as u32,
+        pointer_to_raw_data: idata3_offset,
+        characteristics: pe::IMAGE_SCN_ALIGN_4BYTES
+            | pe::IMAGE_SCN_CNT_INITIALIZED_DATA
+            | pe::IMAGE_SCN_MEM_READ
+            | pe::IMAGE_SCN_MEM_WRITE,
+        ..Default::default()
+    });
+    writer.write_section_zeroes(DIRECTORY_SIZE);
+    writer.write_symbol(Symbol {
+        name: null_descriptor_name,
+        section_number: 1,
+        storage_class: pe::IMAGE_SYM_CLASS_EXTERNAL,
+        ..Default::default()
+    });
+    writer.write_strtab();
+    debug_assert_eq!(writer.reserved_len(), writer.len());
+    Ok(buffer)
+}
+
+/// Write the object containing the null entries that terminate the import lookup
+/// table and import address table for the DLL.
+fn null_thunk(machine: u16, pointer_size: usize, null_thunk_name: &[u8]) -> Result<Vec<u8>> {
+    let align = if pointer_size == 8 {
+        pe::IMAGE_SCN_ALIGN_8BYTES
+    } else {
+        pe::IMAGE_SCN_ALIGN_4BYTES
+    };
+    let characteristics = align
+        | pe::IMAGE_SCN_CNT_INITIALIZED_DATA
+        | pe::IMAGE_SCN_MEM_READ
+        | pe::IMAGE_SCN_MEM_WRITE;
+
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(&mut buffer);
+    let null_thunk_name = writer.add_name(null_thunk_name);
+
+    writer.reserve_file_header();
+    writer.reserve_section_headers(2);
+    let idata5_offset = writer.reserve_section(pointer_size);
+    let idata4_offset = writer.reserve_section(pointer_size);
+    writer.reserve_symbol_indices(1);
+    writer.reserve_symtab_strtab();
+
+    writer.write_file_header(FileHeader {
+        machine,
+        time_date_stamp: 0,
+        characteristics: file_characteristics(machine),
+    })?;
+    writer.write_section_header(SectionHeader {
+        name: b".idata$5".into(),
+        size_of_raw_data: pointer_size as u32,
+        pointer_to_raw_data: idata5_offset,
+        characteristics,
+        ..Default::default()
+    });
+    writer.write_section_header(SectionHeader {
+        name: b".idata$4".into(),
+        size_of_raw_data: pointer_size as u32,
+        pointer_to_raw_data: idata4_offset,
+        characteristics,
+        ..Default::default()
+    });
+    writer.write_section_zeroes(pointer_size);
+    writer.write_section_zeroes(pointer_size);
+    writer.write_symbol(Symbol {
+        name: null_thunk_name,
+        section_number: 1,
+        storage_class: pe::IMAGE_SYM_CLASS_EXTERNAL,
+        ..Default::default()
+    });
+    writer.write_strtab();
+    debug_assert_eq!(writer.reserved_len(), writer.len());
+    Ok(buffer)
+}
+
+/// Write a short import member, which is an [`pe::ImportObjectHeader`] followed
+/// by the symbol name, the DLL name, and the export name if required.
+fn short_import(
+    machine: u16,
+    dll_name: &[u8],
+    export: &ImportLibraryExport<'_>,
+) -> Result<Vec<u8>> {
+    if export.import_type & !pe::IMPORT_OBJECT_TYPE_MASK != 0
+        || export.name_type & !pe::IMPORT_OBJECT_NAME_MASK != 0
+    {
+        return Err(Error(String::from("Invalid import type or name type")));
+    }
+    if export.export_name.is_some() != (export.name_type == pe::IMPORT_OBJECT_NAME_EXPORTAS) {
+        return Err(Error(String::from(
+            "Export name must be set if and only if the name type is IMPORT_OBJECT_NAME_EXPORTAS",
+        )));
+    }
+
+    let mut data = Vec::new();
+    data.extend_from_slice(export.name);
+    data.push(0);
+    data.extend_from_slice(dll_name);
+    data.push(0);
+    if let Some(export_name) = export.export_name {
+        data.extend_from_slice(export_name);
+        data.push(0);
+    }
+
+    let header = pe::ImportObjectHeader {
+        sig1: U16::new(LE, pe::IMAGE_FILE_MACHINE_UNKNOWN),
+        sig2: U16::new(LE, pe::IMPORT_OBJECT_HDR_SIG2),
+        version: U16::new(LE, 0),
+        machine: U16::new(LE, machine),
+        time_date_stamp: U32::new(LE, 0),
+        size_of_data: U32::new(LE, data.len() as u32),
+        ordinal_or_hint: U16::new(LE, export.ordinal_or_hint),
+        name_type: U16::new(
+            LE,
+            (export.name_type << pe::IMPORT_OBJECT_NAME_SHIFT)
+                | (export.import_type << pe::IMPORT_OBJECT_TYPE_SHIFT),
+        ),
+    };
+    let mut buffer = Vec::with_capacity(mem::size_of_val(&header) + data.len());
+    buffer.extend_from_slice(bytes_of(&header));
+    buffer.extend_from_slice(&data);
+    Ok(buffer)
+}
+
+const MEMBER_HEADER_SIZE: usize = 60;
+
+fn member_size(data_len: usize) -> usize {
+    MEMBER_HEADER_SIZE + data_len + (data_len & 1)
+}
+
+/// Write the archive header and data for a member, including the padding.
+fn write_member(out: &mut Vec<u8>, name: &[u8], mode: &[u8], data: &[u8]) {
+    fn field(out: &mut Vec<u8>, value: &[u8], len: usize) {
+        debug_assert!(value.len() <= len);
+        out.extend_from_slice(value);
+        out.resize(out.len() + len - value.len(), b' ');
+    }
+
+    field(out, name, 16);
+    // Use fixed values for the date, uid and gid so that the output is deterministic.
+    field(out, b"0", 12);
+    field(out, b"0", 6);
+    field(out, b"0", 6);
+    field(out, mode, 8);
+    field(out, format!("{}", data.len()).as_bytes(), 10);
+    out.extend_from_slice(b"`\n");
+    out.extend_from_slice(data);
+    if data.len() % 2 != 0 {
+        out.push(b'\n');
+    }
+}
+
+fn write_archive(
+    dll_name: &[u8],
+    format: ImportLibraryFormat,
+    members: &[ImportMember],
+) -> Result<Vec<u8>> {
+    let msvc = format == ImportLibraryFormat::Msvc;
+    if members.len() > usize::from(u16::MAX) {
+        return Err(Error(String::from("Too many import library members")));
+    }
+
+    // All members are named after the DLL.
+    let mut names = Vec::new();
+    let member_name = if dll_name.len() < 16 {
+        [dll_name, b"/"].concat()
+    } else {
+        names.extend_from_slice(dll_name);
+        names.extend_from_slice(if msvc { b"\0" } else { b"/\n" });
+        b"/0".to_vec()
+    };
+
+    let symbol_count = members.iter().map(|m| m.symbols.len()).sum::<usize>();
+    let symbol_names_len = members
+        .iter()
+        .flat_map(|m| &m.symbols)
+        .map(|s| s.len() + 1)
+        .sum::<usize>();
+    let first_linker_len = 4 + 4 * symbol_count + symbol_names_len;
+    let second_linker_len = 4 + 4 * members.len() + 4 + 2 * symbol_count + symbol_names_len;
+
+    let mut offset = 8 + member_size(first_linker_len);
+    if msvc {
+        offset += member_size(second_linker_len);
+    }
+    if msvc || !names.is_empty() {
+        offset += member_size(names.len());
+    }
+    let mut member_offsets = Vec::with_capacity(members.len());
+    for member in members {
+        member_offsets.push(
+            u32::try_from(offset)
+                .map_err(|_| Error(String::from("Import library is too large")))?,
+        );
+        offset += member_size(member.data.len());
+    }
+
+    let mut out = Vec::with_capacity(offset);
+    out.extend_from_slice(b"!<arch>\n");
+
+    // The first linker member (or GNU symbol table) lists the symbols in member order,
+    // using big-endian offsets.
+    let mut data = Vec::with_capacity(first_linker_len);
+    data.extend_from_slice(&(symbol_count as u32).to_be_bytes());
+    for (member, offset) in members.iter().zip(&member_offsets) {
+        for _ in &member.symbols {
+            data.extend_from_slice(&offset.to_be_bytes());
+        }
+    }
+    for symbol in members.iter().flat_map(|m| &m.symbols) {
+        data.extend_from_slice(symbol);
+        data.push(0);
+    }
+    write_member(&mut out, b"/", b"0", &data);
+
+    // The second linker member lists the symbols in lexical order, using
+    // little-endian indices into a table of member offsets.
+    if msvc {
+        let mut symbols = Vec::with_capacity(symbol_count);
+        for (index, member) in members.iter().enumerate() {
+            for symbol in &member.symbols {
+                symbols.push((symbol, index as u16 + 1));
+            }
+        }
+        symbols.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut data = Vec::with_capacity(second_linker_len);
+        data.extend_from_slice(&(members.len() as u32).to_le_bytes());
+        for offset in &member_offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(&(symbol_count as u32).to_le_bytes());
+        for (_, index) in &symbols {
+            data.extend_from_slice(&index.to_le_bytes());
+        }
+        for (symbol, _) in &symbols {
+            data.extend_from_slice(symbol);
+            data.push(0);
+        }
+        write_member(&mut out, b"/", b"0", &data);
+    }
+
+    if msvc || !names.is_empty() {
+        write_member(&mut out, b"//", b"0", &names);
+    }
+
+    for member in members {
+        write_member(&mut out, &member_name, b"644", &member.data);
+    }
+    debug_assert_eq!(out.len(), offset);
+    Ok(out)
+}
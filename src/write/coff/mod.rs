@@ -2,6 +2,11 @@
 //!
 //! Provides [`Writer`] for low level writing of COFF files.
 //! This is also used to provide COFF support for [`write::Object`](crate::write::Object).
+//!
+//! Provides [`write_import_library`] for writing Windows import libraries.
+
+mod import;
+pub use import::*;
 
 mod object;
 pub use self::object::*;
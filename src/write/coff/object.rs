@@ -32,6 +32,14 @@ pub enum CoffExportStyle {
 }
 
 impl<'a> Object<'a> {
+    /// Specify whether to write the COFF bigobj format.
+    ///
+    /// The bigobj format is always used if there are more than
+    /// [`coff::IMAGE_SYM_SECTION_MAX`] sections.
+    pub fn set_coff_bigobj(&mut self, bigobj: bool) {
+        self.coff_bigobj = bigobj;
+    }
+
     pub(crate) fn coff_section_info(
         &self,
         section: StandardSection,
@@ -338,8 +346,16 @@ impl<'a> Object<'a> {
         self.append_section_data(drectve, &directives, 1);
     }
 
-    pub(crate) fn coff_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
-        let mut writer = writer::Writer::new(buffer);
+    pub(crate) fn coff_write(&self, buffer: &mut dyn WritableBuffer) -> Result<SymbolIndices> {
+        if self.sections.len() > coff::IMAGE_SYM_SECTION_MAX_EX as usize {
+            return Err(Error(format!("too many sections: {}", self.sections.len())));
+        }
+        let mut writer =
+            if self.coff_bigobj || self.sections.len() > coff::IMAGE_SYM_SECTION_MAX as usize {
+                writer::Writer::new_bigobj(buffer)
+            } else {
+                writer::Writer::new(buffer)
+            };
 
         // Add section strings to strtab.
         let mut section_offsets = vec![SectionOffsets::default(); self.sections.len()];
@@ -404,14 +420,18 @@ impl<'a> Object<'a> {
                 SymbolKind::Section if symbol.section.id().is_some() => {
                     symbol_offsets[index].aux_count = writer.reserve_aux_section();
                 }
-                _ => {}
+                _ => {
+                    if let SymbolFlags::CoffWeakExternal { .. } = symbol.flags {
+                        symbol_offsets[index].aux_count = writer.reserve_aux_weak_external();
+                    }
+                }
             };
             symbol_offsets[index].name = writer.add_name(name);
         }
 
         // Reserve file ranges.
         writer.reserve_file_header();
-        writer.reserve_section_headers(self.sections.len() as u16);
+        writer.reserve_section_headers(self.sections.len() as u32);
         for (index, section) in self.sections.iter().enumerate() {
             section_offsets[index].offset = writer.reserve_section(section.data.len());
             section_offsets[index].reloc_offset =
@@ -565,12 +585,12 @@ impl<'a> Object<'a> {
             let section_number = match symbol.section {
                 SymbolSection::None => {
                     debug_assert_eq!(symbol.kind, SymbolKind::File);
-                    coff::IMAGE_SYM_DEBUG as u16
+                    coff::IMAGE_SYM_DEBUG
                 }
-                SymbolSection::Undefined => coff::IMAGE_SYM_UNDEFINED as u16,
-                SymbolSection::Absolute => coff::IMAGE_SYM_ABSOLUTE as u16,
-                SymbolSection::Common => coff::IMAGE_SYM_UNDEFINED as u16,
-                SymbolSection::Section(id) => id.0 as u16 + 1,
+                SymbolSection::Undefined => coff::IMAGE_SYM_UNDEFINED,
+                SymbolSection::Absolute => coff::IMAGE_SYM_ABSOLUTE,
+                SymbolSection::Common => coff::IMAGE_SYM_UNDEFINED,
+                SymbolSection::Section(id) => id.0 as i32 + 1,
             };
             let typ = if symbol.kind == SymbolKind::Text {
                 coff::IMAGE_SYM_DTYPE_FUNCTION << coff::IMAGE_SYM_DTYPE_SHIFT
@@ -595,9 +615,14 @@ impl<'a> Object<'a> {
                                 symbol.name().unwrap_or("")
                             )));
                         }
-                        SymbolSection::Undefined | SymbolSection::Common => {
-                            coff::IMAGE_SYM_CLASS_EXTERNAL
+                        SymbolSection::Undefined => {
+                            if let SymbolFlags::CoffWeakExternal { .. } = symbol.flags {
+                                coff::IMAGE_SYM_CLASS_WEAK_EXTERNAL
+                            } else {
+                                coff::IMAGE_SYM_CLASS_EXTERNAL
+                            }
                         }
+                        SymbolSection::Common => coff::IMAGE_SYM_CLASS_EXTERNAL,
                         SymbolSection::Absolute | SymbolSection::Section(_) => {
                             match symbol.scope {
                                 // TODO: does this need aux symbol records too?
@@ -663,7 +688,19 @@ impl<'a> Object<'a> {
                     });
                 }
                 _ => {
-                    debug_assert_eq!(number_of_aux_symbols, 0);
+                    if let SymbolFlags::CoffWeakExternal {
+                        default_symbol,
+                        search_type,
+                    } = symbol.flags
+                    {
+                        debug_assert_eq!(number_of_aux_symbols, 1);
+                        writer.write_aux_weak_external(writer::AuxSymbolWeak {
+                            weak_default_sym_index: symbol_offsets[default_symbol.0].index,
+                            weak_search_type: search_type,
+                        });
+                    } else {
+                        debug_assert_eq!(number_of_aux_symbols, 0);
+                    }
                 }
             }
         }
@@ -672,7 +709,12 @@ impl<'a> Object<'a> {
 
         debug_assert_eq!(writer.reserved_len(), writer.len());
 
-        Ok(())
+        Ok(SymbolIndices(
+            symbol_offsets
+                .iter()
+                .map(|offsets| Some(offsets.index as usize))
+                .collect(),
+        ))
     }
 }
 
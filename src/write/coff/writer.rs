@@ -3,7 +3,7 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use core::mem;
 
-use crate::endian::{LittleEndian as LE, U16Bytes, U32Bytes, U16, U32};
+use crate::endian::{I32Bytes, LittleEndian as LE, U16Bytes, U32Bytes, U16, U32};
 use crate::pe;
 use crate::write::string::{StringId, StringTable};
 use crate::write::util;
@@ -25,12 +25,16 @@ use crate::write::{Error, Result, WritableBuffer};
 /// The second phase writes everything out in order. Thus the caller must ensure writing
 /// is in the same order that file ranges were reserved. There are debug asserts to assist
 /// with checking this.
+///
+/// Use [`Self::new_bigobj`] to write objects in the bigobj format, which supports
+/// more than 65279 sections.
 #[allow(missing_debug_implementations)]
 pub struct Writer<'a> {
     buffer: &'a mut dyn WritableBuffer,
     len: usize,
+    bigobj: bool,
 
-    section_num: u16,
+    section_num: u32,
 
     symtab_offset: u32,
     symtab_num: u32,
@@ -44,9 +48,22 @@ pub struct Writer<'a> {
 impl<'a> Writer<'a> {
     /// Create a new `Writer`.
     pub fn new(buffer: &'a mut dyn WritableBuffer) -> Self {
+        Self::new_impl(buffer, false)
+    }
+
+    /// Create a new `Writer` for the bigobj format.
+    ///
+    /// This format uses [`pe::AnonObjectHeaderBigobj`] for the file header,
+    /// and [`pe::ImageSymbolEx`] for symbols.
+    pub fn new_bigobj(buffer: &'a mut dyn WritableBuffer) -> Self {
+        Self::new_impl(buffer, true)
+    }
+
+    fn new_impl(buffer: &'a mut dyn WritableBuffer, bigobj: bool) -> Self {
         Writer {
             buffer,
             len: 0,
+            bigobj,
 
             section_num: 0,
 
@@ -60,6 +77,20 @@ impl<'a> Writer<'a> {
         }
     }
 
+    /// Return true if writing the bigobj format.
+    pub fn is_bigobj(&self) -> bool {
+        self.bigobj
+    }
+
+    /// Return the size of a symbol table entry.
+    fn symbol_size(&self) -> usize {
+        if self.bigobj {
+            pe::IMAGE_SIZEOF_SYMBOL_EX
+        } else {
+            pe::IMAGE_SIZEOF_SYMBOL
+        }
+    }
+
     /// Return the current file length that has been reserved.
     pub fn reserved_len(&self) -> usize {
         self.len
@@ -105,8 +136,9 @@ impl<'a> Writer<'a> {
 
     /// Write padding up to the given file offset.
     pub fn pad_until(&mut self, offset: usize) {
-        debug_assert!(self.buffer.len() <= offset);
-        self.buffer.resize(offset);
+        let len = self.buffer.len();
+        debug_assert!(len <= offset);
+        self.buffer.write_zeros(offset.saturating_sub(len));
     }
 
     /// Reserve the range for the file header.
@@ -114,7 +146,11 @@ impl<'a> Writer<'a> {
     /// This must be at the start of the file.
     pub fn reserve_file_header(&mut self) {
         debug_assert_eq!(self.len, 0);
-        self.reserve(mem::size_of::<pe::ImageFileHeader>(), 1);
+        if self.bigobj {
+            self.reserve(mem::size_of::<pe::AnonObjectHeaderBigobj>(), 1);
+        } else {
+            self.reserve(mem::size_of::<pe::ImageFileHeader>(), 1);
+        }
     }
 
     /// Write the file header.
@@ -122,6 +158,8 @@ impl<'a> Writer<'a> {
     /// This must be at the start of the file.
     ///
     /// Fields that can be derived from known information are automatically set by this function.
+    ///
+    /// The characteristics are ignored for the bigobj format.
     pub fn write_file_header(&mut self, header: FileHeader) -> Result<()> {
         debug_assert_eq!(self.buffer.len(), 0);
 
@@ -130,10 +168,30 @@ impl<'a> Writer<'a> {
             .reserve(self.len)
             .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
 
+        if self.bigobj {
+            let header = pe::AnonObjectHeaderBigobj {
+                sig1: U16::new(LE, pe::IMAGE_FILE_MACHINE_UNKNOWN),
+                sig2: U16::new(LE, 0xffff),
+                version: U16::new(LE, 2),
+                machine: U16::new(LE, header.machine),
+                time_date_stamp: U32::new(LE, header.time_date_stamp),
+                class_id: pe::ANON_OBJECT_HEADER_BIGOBJ_CLASS_ID,
+                size_of_data: U32::default(),
+                flags: U32::default(),
+                meta_data_size: U32::default(),
+                meta_data_offset: U32::default(),
+                number_of_sections: U32::new(LE, self.section_num),
+                pointer_to_symbol_table: U32::new(LE, self.symtab_offset),
+                number_of_symbols: U32::new(LE, self.symtab_num),
+            };
+            self.buffer.write(&header);
+            return Ok(());
+        }
+
         // Write file header.
         let header = pe::ImageFileHeader {
             machine: U16::new(LE, header.machine),
-            number_of_sections: U16::new(LE, self.section_num),
+            number_of_sections: U16::new(LE, self.section_num as u16),
             time_date_stamp: U32::new(LE, header.time_date_stamp),
             pointer_to_symbol_table: U32::new(LE, self.symtab_offset),
             number_of_symbols: U32::new(LE, self.symtab_num),
@@ -146,8 +204,19 @@ impl<'a> Writer<'a> {
     }
 
     /// Reserve the range for the section headers.
-    pub fn reserve_section_headers(&mut self, section_num: u16) {
+    ///
+    /// The number of sections must not exceed [`pe::IMAGE_SYM_SECTION_MAX`],
+    /// or [`pe::IMAGE_SYM_SECTION_MAX_EX`] for the bigobj format.
+    pub fn reserve_section_headers(&mut self, section_num: u32) {
         debug_assert_eq!(self.section_num, 0);
+        debug_assert!(
+            section_num
+                <= if self.bigobj {
+                    pe::IMAGE_SYM_SECTION_MAX_EX
+                } else {
+                    pe::IMAGE_SYM_SECTION_MAX.into()
+                }
+        );
         self.section_num = section_num;
         self.reserve(
             section_num as usize * mem::size_of::<pe::ImageSectionHeader>(),
@@ -258,7 +327,7 @@ impl<'a> Writer<'a> {
             return;
         }
         self.write_section_align();
-        self.buffer.resize(self.buffer.len() + len);
+        self.buffer.write_zeros(len);
     }
 
     /// Reserve a file range for the given number of relocations.
@@ -319,22 +388,35 @@ impl<'a> Writer<'a> {
 
     /// Write a symbol table entry.
     pub fn write_symbol(&mut self, symbol: Symbol) {
-        let mut coff_symbol = pe::ImageSymbol {
-            name: [0; 8],
-            value: U32Bytes::new(LE, symbol.value),
-            section_number: U16Bytes::new(LE, symbol.section_number),
-            typ: U16Bytes::new(LE, symbol.typ),
-            storage_class: symbol.storage_class,
-            number_of_aux_symbols: symbol.number_of_aux_symbols,
-        };
+        let mut name = [0; 8];
         match symbol.name {
-            Name::Short(name) => coff_symbol.name = name,
+            Name::Short(short_name) => name = short_name,
             Name::Long(str_id) => {
                 let str_offset = self.strtab.get_offset(str_id);
-                coff_symbol.name[4..8].copy_from_slice(&u32::to_le_bytes(str_offset as u32));
+                name[4..8].copy_from_slice(&u32::to_le_bytes(str_offset as u32));
             }
         }
-        self.buffer.write(&coff_symbol);
+        if self.bigobj {
+            let coff_symbol = pe::ImageSymbolEx {
+                name,
+                value: U32Bytes::new(LE, symbol.value),
+                section_number: I32Bytes::new(LE, symbol.section_number),
+                typ: U16Bytes::new(LE, symbol.typ),
+                storage_class: symbol.storage_class,
+                number_of_aux_symbols: symbol.number_of_aux_symbols,
+            };
+            self.buffer.write(&coff_symbol);
+        } else {
+            let coff_symbol = pe::ImageSymbol {
+                name,
+                value: U32Bytes::new(LE, symbol.value),
+                section_number: U16Bytes::new(LE, symbol.section_number as u16),
+                typ: U16Bytes::new(LE, symbol.typ),
+                storage_class: symbol.storage_class,
+                number_of_aux_symbols: symbol.number_of_aux_symbols,
+            };
+            self.buffer.write(&coff_symbol);
+        }
     }
 
     /// Reserve auxiliary symbols for a file name.
@@ -344,18 +426,18 @@ impl<'a> Writer<'a> {
     /// This must be called before [`Self::reserve_symtab_strtab`].
     pub fn reserve_aux_file_name(&mut self, name: &[u8]) -> u8 {
         debug_assert_eq!(self.symtab_offset, 0);
-        let aux_count = (name.len() + pe::IMAGE_SIZEOF_SYMBOL - 1) / pe::IMAGE_SIZEOF_SYMBOL;
+        let symbol_size = self.symbol_size();
+        let aux_count = (name.len() + symbol_size - 1) / symbol_size;
         self.symtab_num += aux_count as u32;
         aux_count as u8
     }
 
     /// Write auxiliary symbols for a file name.
     pub fn write_aux_file_name(&mut self, name: &[u8], aux_count: u8) {
-        let aux_len = aux_count as usize * pe::IMAGE_SIZEOF_SYMBOL;
+        let aux_len = aux_count as usize * self.symbol_size();
         debug_assert!(aux_len >= name.len());
-        let old_len = self.buffer.len();
         self.buffer.write_bytes(name);
-        self.buffer.resize(old_len + aux_len);
+        self.buffer.write_zeros(aux_len - name.len());
     }
 
     /// Reserve an auxiliary symbol for a section.
@@ -386,6 +468,62 @@ impl<'a> Writer<'a> {
             high_number: U16Bytes::new(LE, (section.number >> 16) as u16),
         };
         self.buffer.write(&aux);
+        if self.bigobj {
+            // Auxiliary records are padded to the size of a symbol.
+            self.buffer
+                .write_zeros(pe::IMAGE_SIZEOF_SYMBOL_EX - pe::IMAGE_SIZEOF_SYMBOL);
+        }
+    }
+
+    /// Reserve an auxiliary symbol for a function definition.
+    ///
+    /// Returns the number of auxiliary symbols required.
+    ///
+    /// This must be called before [`Self::reserve_symtab_strtab`].
+    pub fn reserve_aux_function(&mut self) -> u8 {
+        debug_assert_eq!(self.symtab_offset, 0);
+        self.symtab_num += 1;
+        1
+    }
+
+    /// Write an auxiliary symbol for a function definition.
+    pub fn write_aux_function(&mut self, function: AuxSymbolFunction) {
+        let aux = pe::ImageAuxSymbolFunction {
+            tag_index: U32Bytes::new(LE, function.tag_index),
+            total_size: U32Bytes::new(LE, function.total_size),
+            pointer_to_linenumber: U32Bytes::new(LE, function.pointer_to_linenumber),
+            pointer_to_next_function: U32Bytes::new(LE, function.pointer_to_next_function),
+            unused: [0; 2],
+        };
+        self.buffer.write(&aux);
+        if self.bigobj {
+            // Auxiliary records are padded to the size of a symbol.
+            self.buffer
+                .write_zeros(pe::IMAGE_SIZEOF_SYMBOL_EX - pe::IMAGE_SIZEOF_SYMBOL);
+        }
+    }
+
+    /// Reserve an auxiliary symbol for a weak external.
+    ///
+    /// Returns the number of auxiliary symbols required.
+    ///
+    /// This must be called before [`Self::reserve_symtab_strtab`].
+    pub fn reserve_aux_weak_external(&mut self) -> u8 {
+        debug_assert_eq!(self.symtab_offset, 0);
+        self.symtab_num += 1;
+        1
+    }
+
+    /// Write an auxiliary symbol for a weak external.
+    pub fn write_aux_weak_external(&mut self, weak: AuxSymbolWeak) {
+        let aux = pe::ImageAuxSymbolWeak {
+            weak_default_sym_index: U32Bytes::new(LE, weak.weak_default_sym_index),
+            weak_search_type: U32Bytes::new(LE, weak.weak_search_type),
+        };
+        self.buffer.write(&aux);
+        // Auxiliary records are padded to the size of a symbol.
+        self.buffer
+            .write_zeros(self.symbol_size() - mem::size_of::<pe::ImageAuxSymbolWeak>());
     }
 
     /// Return the number of reserved symbol table entries.
@@ -420,7 +558,7 @@ impl<'a> Writer<'a> {
     /// indices or add strings.
     pub fn reserve_symtab_strtab(&mut self) {
         debug_assert_eq!(self.symtab_offset, 0);
-        self.symtab_offset = self.reserve(self.symtab_num as usize * pe::IMAGE_SIZEOF_SYMBOL, 1);
+        self.symtab_offset = self.reserve(self.symtab_num as usize * self.symbol_size(), 1);
 
         debug_assert_eq!(self.strtab_offset, 0);
         // First 4 bytes of strtab are the length.
@@ -439,6 +577,8 @@ impl<'a> Writer<'a> {
 }
 
 /// Shortened and native endian version of [`pe::ImageFileHeader`].
+///
+/// This is also used for [`pe::AnonObjectHeaderBigobj`].
 #[allow(missing_docs)]
 #[derive(Debug, Default, Clone)]
 pub struct FileHeader {
@@ -485,13 +625,14 @@ pub struct SectionHeader {
     pub characteristics: u32,
 }
 
-/// Native endian version of [`pe::ImageSymbol`].
+/// Native endian version of [`pe::ImageSymbol`] and [`pe::ImageSymbolEx`].
 #[allow(missing_docs)]
 #[derive(Debug, Default, Clone)]
 pub struct Symbol {
     pub name: Name,
     pub value: u32,
-    pub section_number: u16,
+    /// This is truncated to 16 bits if not writing the bigobj format.
+    pub section_number: i32,
     pub typ: u16,
     pub storage_class: u8,
     pub number_of_aux_symbols: u8,
@@ -510,6 +651,24 @@ pub struct AuxSymbolSection {
     pub selection: u8,
 }
 
+/// Native endian version of [`pe::ImageAuxSymbolFunction`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone)]
+pub struct AuxSymbolFunction {
+    pub tag_index: u32,
+    pub total_size: u32,
+    pub pointer_to_linenumber: u32,
+    pub pointer_to_next_function: u32,
+}
+
+/// Native endian version of [`pe::ImageAuxSymbolWeak`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone)]
+pub struct AuxSymbolWeak {
+    pub weak_default_sym_index: u32,
+    pub weak_search_type: u32,
+}
+
 /// Native endian version of [`pe::ImageRelocation`].
 #[allow(missing_docs)]
 #[derive(Debug, Default, Clone)]
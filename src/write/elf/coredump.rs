@@ -0,0 +1,390 @@
+//! Helpers for writing core files.
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::elf;
+use crate::endian::*;
+use crate::pod;
+use crate::write::elf::{FileHeader, ProgramHeader, Writer};
+use crate::write::util;
+use crate::write::{Error, Result, WritableBuffer};
+
+/// The alignment of `PT_LOAD` segments in a core file.
+const CORE_PAGE_SIZE: u64 = 0x1000;
+
+/// Return the number of general purpose registers in `NT_PRSTATUS` for a machine.
+///
+/// This is the value of `ELF_NGREG` in the Linux kernel.
+/// Returns `None` if the machine is not supported.
+pub fn core_register_count(e_machine: u16, is_64: bool) -> Option<usize> {
+    match (e_machine, is_64) {
+        (elf::EM_X86_64, true) => Some(27),
+        (elf::EM_386, false) => Some(17),
+        (elf::EM_AARCH64, true) => Some(34),
+        (elf::EM_ARM, false) => Some(18),
+        (elf::EM_RISCV, _) => Some(32),
+        (elf::EM_PPC64, true) | (elf::EM_PPC, false) => Some(48),
+        (elf::EM_LOONGARCH, true) => Some(45),
+        _ => None,
+    }
+}
+
+/// Information for writing a `NT_PRSTATUS` note.
+///
+/// This note contains the state of a thread.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PrStatus<'a> {
+    /// The signal that caused the core dump, or 0.
+    pub signal: u16,
+    /// The thread ID.
+    pub pid: u32,
+    /// The parent process ID.
+    pub ppid: u32,
+    /// The process group ID.
+    pub pgrp: u32,
+    /// The session ID.
+    pub sid: u32,
+    /// The general purpose registers, in the order used by `elf_gregset_t`.
+    ///
+    /// The number of registers must match [`core_register_count`].
+    /// The values are truncated to 32 bits for 32-bit files.
+    pub registers: &'a [u64],
+    /// Whether a `NT_PRFPREG` note is present for this thread.
+    pub fp_valid: bool,
+}
+
+/// Information for writing a `NT_PRPSINFO` note.
+///
+/// This note contains information about the process.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PrPsInfo<'a> {
+    /// The numeric process state.
+    pub state: u8,
+    /// The character for the process state, such as `b'R'`.
+    pub sname: u8,
+    /// Whether the process is a zombie.
+    pub zombie: bool,
+    /// The nice value of the process.
+    pub nice: i8,
+    /// The process flags.
+    pub flags: u64,
+    /// The user ID.
+    pub uid: u32,
+    /// The group ID.
+    pub gid: u32,
+    /// The process ID.
+    pub pid: u32,
+    /// The parent process ID.
+    pub ppid: u32,
+    /// The process group ID.
+    pub pgrp: u32,
+    /// The session ID.
+    pub sid: u32,
+    /// The file name of the executable.
+    ///
+    /// This is truncated to 16 bytes.
+    pub fname: &'a [u8],
+    /// The initial part of the argument list.
+    ///
+    /// This is truncated to 80 bytes.
+    pub psargs: &'a [u8],
+}
+
+/// A file mapping for a `NT_FILE` note.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CoreFileMapping<'a> {
+    /// The start address of the mapping.
+    pub start: u64,
+    /// The end address of the mapping.
+    pub end: u64,
+    /// The offset of the mapping within the file, in bytes.
+    ///
+    /// This must be a multiple of the page size.
+    pub offset: u64,
+    /// The name of the file.
+    pub name: &'a [u8],
+}
+
+/// A memory image for a `PT_LOAD` segment in a core file.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CoreSegment<'a> {
+    /// The segment flags, such as `PF_R`.
+    pub p_flags: u32,
+    /// The address of the memory.
+    pub address: u64,
+    /// The size of the memory.
+    ///
+    /// This may be larger than the size of `data` for memory that was not dumped.
+    pub mem_size: u64,
+    /// The contents of the memory.
+    pub data: &'a [u8],
+}
+
+/// A helper for building the contents of the `PT_NOTE` segment of a core file.
+///
+/// The notes use the layouts of the Linux kernel. The notes for a thread
+/// should start with a `NT_PRSTATUS` note, and may be followed by other
+/// notes for that thread, such as `NT_PRFPREG`.
+#[derive(Debug)]
+pub struct CoreNotes {
+    endian: Endianness,
+    is_64: bool,
+    e_machine: u16,
+    data: Vec<u8>,
+}
+
+impl CoreNotes {
+    /// Create a new set of notes for the given file class and machine.
+    pub fn new(endian: Endianness, is_64: bool, e_machine: u16) -> Self {
+        CoreNotes {
+            endian,
+            is_64,
+            e_machine,
+            data: Vec::new(),
+        }
+    }
+
+    /// Return the contents of the notes.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Add a note with the given name, type and descriptor.
+    ///
+    /// The name is usually [`elf::ELF_NOTE_CORE`] or [`elf::ELF_NOTE_LINUX`].
+    pub fn add_note(&mut self, name: &[u8], n_type: u32, desc: &[u8]) {
+        self.data
+            .extend_from_slice(pod::bytes_of(&elf::NoteHeader32 {
+                n_namesz: U32::new(self.endian, name.len() as u32 + 1),
+                n_descsz: U32::new(self.endian, desc.len() as u32),
+                n_type: U32::new(self.endian, n_type),
+            }));
+        self.data.extend_from_slice(name);
+        self.data.push(0);
+        util::write_align(&mut self.data, 4);
+        self.data.extend_from_slice(desc);
+        util::write_align(&mut self.data, 4);
+    }
+
+    /// Add a `NT_PRSTATUS` note.
+    ///
+    /// Returns an error if the machine is not supported, or if the number of
+    /// registers is incorrect.
+    pub fn add_prstatus(&mut self, prstatus: &PrStatus<'_>) -> Result<()> {
+        let register_count = core_register_count(self.e_machine, self.is_64).ok_or_else(|| {
+            Error(format!(
+                "unsupported machine {} for NT_PRSTATUS",
+                self.e_machine
+            ))
+        })?;
+        if prstatus.registers.len() != register_count {
+            return Err(Error(format!(
+                "invalid NT_PRSTATUS register count {}, expected {}",
+                prstatus.registers.len(),
+                register_count
+            )));
+        }
+
+        let mut desc = Vec::new();
+        // pr_info: si_signo, si_code, si_errno
+        self.push_u32(&mut desc, prstatus.signal.into());
+        self.push_u32(&mut desc, 0);
+        self.push_u32(&mut desc, 0);
+        // pr_cursig
+        desc.extend_from_slice(pod::bytes_of(&U16::new(self.endian, prstatus.signal)));
+        util::write_align(&mut desc, self.word_size());
+        // pr_sigpend, pr_sighold
+        self.push_word(&mut desc, 0);
+        self.push_word(&mut desc, 0);
+        self.push_u32(&mut desc, prstatus.pid);
+        self.push_u32(&mut desc, prstatus.ppid);
+        self.push_u32(&mut desc, prstatus.pgrp);
+        self.push_u32(&mut desc, prstatus.sid);
+        // pr_utime, pr_stime, pr_cutime, pr_cstime
+        for _ in 0..8 {
+            self.push_word(&mut desc, 0);
+        }
+        for register in prstatus.registers {
+            self.push_word(&mut desc, *register);
+        }
+        self.push_u32(&mut desc, prstatus.fp_valid.into());
+        util::write_align(&mut desc, self.word_size());
+
+        self.add_note(elf::ELF_NOTE_CORE, elf::NT_PRSTATUS, &desc);
+        Ok(())
+    }
+
+    /// Add a `NT_PRPSINFO` note.
+    pub fn add_prpsinfo(&mut self, prpsinfo: &PrPsInfo<'_>) {
+        let mut desc = vec![
+            prpsinfo.state,
+            prpsinfo.sname,
+            prpsinfo.zombie.into(),
+            prpsinfo.nice as u8,
+        ];
+        util::write_align(&mut desc, self.word_size());
+        self.push_word(&mut desc, prpsinfo.flags);
+        // The 32-bit ABIs for these machines use 16-bit IDs.
+        if !self.is_64 && matches!(self.e_machine, elf::EM_386 | elf::EM_ARM) {
+            desc.extend_from_slice(pod::bytes_of(&U16::new(self.endian, prpsinfo.uid as u16)));
+            desc.extend_from_slice(pod::bytes_of(&U16::new(self.endian, prpsinfo.gid as u16)));
+        } else {
+            self.push_u32(&mut desc, prpsinfo.uid);
+            self.push_u32(&mut desc, prpsinfo.gid);
+        }
+        self.push_u32(&mut desc, prpsinfo.pid);
+        self.push_u32(&mut desc, prpsinfo.ppid);
+        self.push_u32(&mut desc, prpsinfo.pgrp);
+        self.push_u32(&mut desc, prpsinfo.sid);
+        push_fixed(&mut desc, prpsinfo.fname, 16);
+        push_fixed(&mut desc, prpsinfo.psargs, 80);
+        util::write_align(&mut desc, self.word_size());
+
+        self.add_note(elf::ELF_NOTE_CORE, elf::NT_PRPSINFO, &desc);
+    }
+
+    /// Add a `NT_AUXV` note.
+    ///
+    /// `auxv` contains the type and value of each auxiliary vector entry.
+    /// A terminating `AT_NULL` entry is added if it is not present.
+    pub fn add_auxv(&mut self, auxv: &[(u64, u64)]) {
+        let mut desc = Vec::new();
+        for (a_type, a_val) in auxv {
+            self.push_word(&mut desc, *a_type);
+            self.push_word(&mut desc, *a_val);
+        }
+        if auxv.last().map_or(true, |(a_type, _)| *a_type != 0) {
+            self.push_word(&mut desc, 0);
+            self.push_word(&mut desc, 0);
+        }
+
+        self.add_note(elf::ELF_NOTE_CORE, elf::NT_AUXV, &desc);
+    }
+
+    /// Add a `NT_FILE` note.
+    ///
+    /// Returns an error if an offset is not a multiple of `page_size`.
+    pub fn add_file(&mut self, page_size: u64, files: &[CoreFileMapping<'_>]) -> Result<()> {
+        if page_size == 0 {
+            return Err(Error(String::from("invalid NT_FILE page size")));
+        }
+        let mut desc = Vec::new();
+        self.push_word(&mut desc, files.len() as u64);
+        self.push_word(&mut desc, page_size);
+        for file in files {
+            if file.offset % page_size != 0 {
+                return Err(Error(format!(
+                    "unaligned NT_FILE offset {:#x}",
+                    file.offset
+                )));
+            }
+            self.push_word(&mut desc, file.start);
+            self.push_word(&mut desc, file.end);
+            self.push_word(&mut desc, file.offset / page_size);
+        }
+        for file in files {
+            desc.extend_from_slice(file.name);
+            desc.push(0);
+        }
+
+        self.add_note(elf::ELF_NOTE_CORE, elf::NT_FILE, &desc);
+        Ok(())
+    }
+
+    fn word_size(&self) -> usize {
+        if self.is_64 {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn push_u32(&self, data: &mut Vec<u8>, value: u32) {
+        data.extend_from_slice(pod::bytes_of(&U32::new(self.endian, value)));
+    }
+
+    fn push_word(&self, data: &mut Vec<u8>, value: u64) {
+        if self.is_64 {
+            data.extend_from_slice(pod::bytes_of(&U64::new(self.endian, value)));
+        } else {
+            self.push_u32(data, value as u32);
+        }
+    }
+}
+
+/// Append a fixed size, zero padded string.
+fn push_fixed(data: &mut Vec<u8>, value: &[u8], size: usize) {
+    let len = core::cmp::min(value.len(), size);
+    data.extend_from_slice(&value[..len]);
+    data.resize(data.len() + size - len, 0);
+}
+
+/// Write a core file containing the given notes and memory images.
+///
+/// The file contains a `PT_NOTE` segment for the notes, followed by a
+/// `PT_LOAD` segment for each memory image. There are no sections.
+pub fn write_core(
+    buffer: &mut dyn WritableBuffer,
+    notes: &CoreNotes,
+    segments: &[CoreSegment<'_>],
+) -> Result<()> {
+    let mut writer = Writer::new(notes.endian, notes.is_64, buffer);
+
+    // Calculate offsets and layout.
+    writer.reserve_file_header();
+    writer.reserve_program_headers(1 + segments.len() as u32);
+    let notes_offset = writer.reserve(notes.data.len(), 4);
+    let mut segment_offsets = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if (segment.data.len() as u64) > segment.mem_size {
+            return Err(Error(format!(
+                "core segment at {:#x} has more data than memory",
+                segment.address
+            )));
+        }
+        segment_offsets.push(writer.reserve(segment.data.len(), CORE_PAGE_SIZE as usize));
+    }
+
+    // Start writing.
+    writer.write_file_header(&FileHeader {
+        os_abi: elf::ELFOSABI_NONE,
+        abi_version: 0,
+        e_type: elf::ET_CORE,
+        e_machine: notes.e_machine,
+        e_entry: 0,
+        e_flags: 0,
+    })?;
+    writer.write_align_program_headers();
+    writer.write_program_header(&ProgramHeader {
+        p_type: elf::PT_NOTE,
+        p_flags: 0,
+        p_offset: notes_offset as u64,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: notes.data.len() as u64,
+        p_memsz: 0,
+        p_align: 4,
+    });
+    for (segment, offset) in segments.iter().zip(segment_offsets.iter()) {
+        writer.write_program_header(&ProgramHeader {
+            p_type: elf::PT_LOAD,
+            p_flags: segment.p_flags,
+            p_offset: *offset as u64,
+            p_vaddr: segment.address,
+            p_paddr: 0,
+            p_filesz: segment.data.len() as u64,
+            p_memsz: segment.mem_size,
+            p_align: CORE_PAGE_SIZE,
+        });
+    }
+
+    writer.write_align(4);
+    writer.write(&notes.data);
+    for segment in segments {
+        writer.write_align(CORE_PAGE_SIZE as usize);
+        writer.write(segment.data);
+    }
+    debug_assert_eq!(writer.reserved_len(), writer.len());
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Helper for building the dynamic table.
+use alloc::vec::Vec;
+
+use crate::elf;
+use crate::write::elf::Writer;
+use crate::write::string::StringId;
+
+#[derive(Debug, Clone, Copy)]
+enum DynamicValue {
+    Value(u64),
+    String(StringId),
+}
+
+/// A helper for writing the `.dynamic` section using a [`Writer`].
+///
+/// Entries are written in the order that they are added, followed by a
+/// terminating `DT_NULL` entry.
+///
+/// Entries with string values, such as `DT_NEEDED` and `DT_SONAME`, add the string
+/// to `.dynstr` when they are added, so they must be added before
+/// [`Writer::reserve_dynstr`]. Entries with values that are not known until later,
+/// such as the addresses of other sections, can be added with a placeholder value
+/// and updated using [`Self::set_value`] at any time before [`Self::write`].
+#[derive(Debug, Default)]
+pub struct DynamicBuilder {
+    entries: Vec<(u32, DynamicValue)>,
+}
+
+impl DynamicBuilder {
+    /// Create a new dynamic table builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry with an integer or address value.
+    ///
+    /// Returns the index of the entry, which can be passed to [`Self::set_value`].
+    pub fn add(&mut self, d_tag: u32, d_val: u64) -> usize {
+        debug_assert_ne!(d_tag, elf::DT_NULL);
+        self.entries.push((d_tag, DynamicValue::Value(d_val)));
+        self.entries.len() - 1
+    }
+
+    /// Add an entry with a string value.
+    ///
+    /// The string is added to the dynamic string table.
+    ///
+    /// This must be called before [`Writer::reserve_dynstr`].
+    pub fn add_string<'a>(&mut self, writer: &mut Writer<'a>, d_tag: u32, value: &'a [u8]) {
+        let id = writer.add_dynamic_string(value);
+        self.entries.push((d_tag, DynamicValue::String(id)));
+    }
+
+    /// Add a `DT_NEEDED` entry for the given library name.
+    ///
+    /// This must be called before [`Writer::reserve_dynstr`].
+    pub fn add_needed<'a>(&mut self, writer: &mut Writer<'a>, name: &'a [u8]) {
+        self.add_string(writer, elf::DT_NEEDED, name);
+    }
+
+    /// Add a `DT_SONAME` entry.
+    ///
+    /// This must be called before [`Writer::reserve_dynstr`].
+    pub fn add_soname<'a>(&mut self, writer: &mut Writer<'a>, name: &'a [u8]) {
+        self.add_string(writer, elf::DT_SONAME, name);
+    }
+
+    /// Set the value of an entry that was added by [`Self::add`].
+    pub fn set_value(&mut self, index: usize, d_val: u64) {
+        let entry = &mut self.entries[index];
+        debug_assert!(matches!(entry.1, DynamicValue::Value(_)));
+        entry.1 = DynamicValue::Value(d_val);
+    }
+
+    /// Return the number of entries, including the terminating `DT_NULL`.
+    pub fn len(&self) -> usize {
+        self.entries.len() + 1
+    }
+
+    /// Return true if no entries have been added.
+    ///
+    /// The terminating `DT_NULL` entry is still written in this case.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Reserve the file range for the dynamic table.
+    ///
+    /// This range is used for a section named `.dynamic`. The section index must
+    /// be reserved separately using [`Writer::reserve_dynamic_section_index`].
+    ///
+    /// Returns the offset of the range.
+    pub fn reserve(&self, writer: &mut Writer<'_>) -> usize {
+        writer.reserve_dynamic(self.len())
+    }
+
+    /// Write the dynamic table, including the alignment padding before it.
+    pub fn write(&self, writer: &mut Writer<'_>) {
+        writer.write_align_dynamic();
+        for &(d_tag, value) in &self.entries {
+            match value {
+                DynamicValue::Value(d_val) => writer.write_dynamic(d_tag, d_val),
+                DynamicValue::String(id) => writer.write_dynamic_string(d_tag, id),
+            }
+        }
+        writer.write_dynamic(elf::DT_NULL, 0);
+    }
+}
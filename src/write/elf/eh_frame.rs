@@ -0,0 +1,358 @@
+//! Helpers for writing `.eh_frame_hdr` sections.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::endian::*;
+use crate::pod::bytes_of;
+use crate::write::{Error, Result};
+
+const DW_EH_PE_ABSPTR: u8 = 0x00;
+const DW_EH_PE_ULEB128: u8 = 0x01;
+const DW_EH_PE_UDATA2: u8 = 0x02;
+const DW_EH_PE_UDATA4: u8 = 0x03;
+const DW_EH_PE_UDATA8: u8 = 0x04;
+const DW_EH_PE_SLEB128: u8 = 0x09;
+const DW_EH_PE_SDATA2: u8 = 0x0a;
+const DW_EH_PE_SDATA4: u8 = 0x0b;
+const DW_EH_PE_SDATA8: u8 = 0x0c;
+const DW_EH_PE_PCREL: u8 = 0x10;
+const DW_EH_PE_DATAREL: u8 = 0x30;
+const DW_EH_PE_OMIT: u8 = 0xff;
+
+/// An entry in the binary search table of a `.eh_frame_hdr` section.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EhFrameHdrEntry {
+    /// The initial location of the code covered by the FDE.
+    pub address: u64,
+    /// The address of the FDE within the `.eh_frame` section.
+    pub fde_address: u64,
+}
+
+/// Return the size of a `.eh_frame_hdr` section with the given number of FDEs.
+pub fn eh_frame_hdr_size(fde_count: usize) -> usize {
+    12 + fde_count * 8
+}
+
+/// Find the FDEs in a `.eh_frame` section.
+///
+/// `eh_frame_address` is the address of the `.eh_frame` section, which is
+/// needed to decode PC relative initial locations. The returned entries are
+/// in the order of the FDEs in the section, and can be passed to
+/// [`write_eh_frame_hdr`].
+///
+/// Returns an error if the section is invalid or if an FDE uses a pointer
+/// encoding that cannot be decoded without relocations.
+pub fn eh_frame_entries(
+    eh_frame: &[u8],
+    eh_frame_address: u64,
+    endian: Endianness,
+    is_64: bool,
+) -> Result<Vec<EhFrameHdrEntry>> {
+    let reader = EhFrameReader {
+        data: eh_frame,
+        address: eh_frame_address,
+        endian,
+        is_64,
+    };
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while offset < eh_frame.len() {
+        let (id_offset, end) = reader.entry(offset)?;
+        if id_offset == end {
+            // Zero terminator.
+            break;
+        }
+        let id = reader.u32(id_offset)?;
+        if id != 0 {
+            let cie_offset = id_offset
+                .checked_sub(id as usize)
+                .ok_or_else(|| Error(format!("invalid CIE pointer in FDE at {:#x}", offset)))?;
+            let encoding = reader.cie_fde_encoding(cie_offset)?;
+            let mut pc_offset = id_offset + 4;
+            let address = reader.pointer(&mut pc_offset, encoding)?;
+            entries.push(EhFrameHdrEntry {
+                address,
+                fde_address: eh_frame_address.wrapping_add(offset as u64),
+            });
+        }
+        offset = end;
+    }
+    Ok(entries)
+}
+
+/// Write the contents of a `.eh_frame_hdr` section.
+///
+/// The contents are appended to `data`, which can then be written to the
+/// file using [`Writer::write`](super::Writer::write).
+///
+/// The section contains a pointer to the `.eh_frame` section, and a binary
+/// search table of FDEs, sorted by initial location. All values are encoded
+/// relative to the `.eh_frame_hdr` section, so no relocations are needed.
+///
+/// `entries` is typically obtained using [`eh_frame_entries`], and does
+/// not need to be sorted.
+///
+/// Returns an error if an address is out of range of the encoding.
+pub fn write_eh_frame_hdr(
+    data: &mut Vec<u8>,
+    endian: Endianness,
+    eh_frame_hdr_address: u64,
+    eh_frame_address: u64,
+    entries: &[EhFrameHdrEntry],
+) -> Result<()> {
+    let mut entries = entries.to_vec();
+    entries.sort_by_key(|entry| entry.address);
+
+    let relative = |address: u64, base: u64| {
+        i32::try_from(address.wrapping_sub(base) as i64)
+            .map(|value| I32::new(endian, value))
+            .map_err(|_| {
+                Error(format!(
+                    "address {:#x} is out of range for .eh_frame_hdr",
+                    address
+                ))
+            })
+    };
+
+    data.extend_from_slice(&[
+        1,
+        DW_EH_PE_PCREL | DW_EH_PE_SDATA4,
+        DW_EH_PE_UDATA4,
+        DW_EH_PE_DATAREL | DW_EH_PE_SDATA4,
+    ]);
+    data.extend_from_slice(bytes_of(&relative(
+        eh_frame_address,
+        eh_frame_hdr_address.wrapping_add(4),
+    )?));
+    let fde_count = u32::try_from(entries.len())
+        .map_err(|_| Error(String::from("too many FDEs for .eh_frame_hdr")))?;
+    data.extend_from_slice(bytes_of(&U32::new(endian, fde_count)));
+    for entry in &entries {
+        data.extend_from_slice(bytes_of(&relative(entry.address, eh_frame_hdr_address)?));
+        data.extend_from_slice(bytes_of(&relative(
+            entry.fde_address,
+            eh_frame_hdr_address,
+        )?));
+    }
+    Ok(())
+}
+
+struct EhFrameReader<'data> {
+    data: &'data [u8],
+    address: u64,
+    endian: Endianness,
+    is_64: bool,
+}
+
+impl<'data> EhFrameReader<'data> {
+    /// Read the length of the entry at the given offset.
+    ///
+    /// Returns the offset of the CIE ID or CIE pointer, and the offset of the end of the entry.
+    fn entry(&self, offset: usize) -> Result<(usize, usize)> {
+        let length = self.u32(offset)?;
+        let (length, id_offset) = if length == 0xffff_ffff {
+            let length = self.u64_bytes(self.bytes(offset + 4, 8)?);
+            (length, offset + 12)
+        } else {
+            (u64::from(length), offset + 4)
+        };
+        let end = usize::try_from(length)
+            .ok()
+            .and_then(|length| id_offset.checked_add(length))
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error(format!("invalid .eh_frame entry length at {:#x}", offset)))?;
+        if end != id_offset && end < id_offset + 4 {
+            return Err(Error(format!(
+                "invalid .eh_frame entry length at {:#x}",
+                offset
+            )));
+        }
+        Ok((id_offset, end))
+    }
+
+    /// Return the pointer encoding for FDEs that use the CIE at the given offset.
+    fn cie_fde_encoding(&self, offset: usize) -> Result<u8> {
+        let invalid = || Error(format!("invalid .eh_frame CIE at {:#x}", offset));
+        let (id_offset, end) = self.entry(offset)?;
+        if id_offset == end || self.u32(id_offset)? != 0 {
+            return Err(invalid());
+        }
+        let mut cursor = id_offset + 4;
+        let version = self.u8(&mut cursor)?;
+        if version != 1 && version != 3 {
+            return Err(Error(format!(
+                "unsupported .eh_frame CIE version {} at {:#x}",
+                version, offset
+            )));
+        }
+        let augmentation_start = cursor;
+        let augmentation_len = self
+            .data
+            .get(cursor..end)
+            .and_then(|data| data.iter().position(|c| *c == 0))
+            .ok_or_else(invalid)?;
+        let augmentation = &self.data[augmentation_start..][..augmentation_len];
+        cursor += augmentation_len + 1;
+        if augmentation.is_empty() {
+            return Ok(DW_EH_PE_ABSPTR);
+        }
+        if augmentation[0] != b'z' {
+            return Err(Error(format!(
+                "unsupported .eh_frame CIE augmentation at {:#x}",
+                offset
+            )));
+        }
+        // Code alignment factor, data alignment factor and return address register.
+        self.uleb128(&mut cursor)?;
+        self.uleb128(&mut cursor)?;
+        if version == 1 {
+            self.u8(&mut cursor)?;
+        } else {
+            self.uleb128(&mut cursor)?;
+        }
+        // Augmentation data length.
+        self.uleb128(&mut cursor)?;
+        for c in &augmentation[1..] {
+            match *c {
+                b'R' => return self.u8(&mut cursor),
+                b'L' => {
+                    self.u8(&mut cursor)?;
+                }
+                b'P' => {
+                    let encoding = self.u8(&mut cursor)?;
+                    self.value(&mut cursor, encoding)?;
+                }
+                b'S' | b'B' | b'G' => {}
+                _ => {
+                    return Err(Error(format!(
+                        "unsupported .eh_frame CIE augmentation at {:#x}",
+                        offset
+                    )));
+                }
+            }
+        }
+        Ok(DW_EH_PE_ABSPTR)
+    }
+
+    /// Read an encoded pointer.
+    fn pointer(&self, offset: &mut usize, encoding: u8) -> Result<u64> {
+        let field_address = self.address.wrapping_add(*offset as u64);
+        if encoding == DW_EH_PE_OMIT {
+            return Err(Error(format!(
+                "missing .eh_frame pointer at {:#x}",
+                *offset
+            )));
+        }
+        let value = self.value(offset, encoding)?;
+        let pointer = match encoding & 0xf0 {
+            0 => value,
+            DW_EH_PE_PCREL => field_address.wrapping_add(value),
+            _ => {
+                return Err(Error(format!(
+                    "unsupported .eh_frame pointer encoding {:#x}",
+                    encoding
+                )));
+            }
+        };
+        if self.is_64 {
+            Ok(pointer)
+        } else {
+            Ok(pointer & 0xffff_ffff)
+        }
+    }
+
+    /// Read the value of an encoded pointer, without applying the base address.
+    fn value(&self, offset: &mut usize, encoding: u8) -> Result<u64> {
+        let start = *offset;
+        let read = |offset: &mut usize, len: usize| -> Result<&'data [u8]> {
+            let bytes = self.bytes(*offset, len)?;
+            *offset += len;
+            Ok(bytes)
+        };
+        let value = match encoding & 0x0f {
+            DW_EH_PE_ABSPTR if self.is_64 => self.u64_bytes(read(offset, 8)?),
+            DW_EH_PE_ABSPTR => self.u32_bytes(read(offset, 4)?).into(),
+            DW_EH_PE_ULEB128 => self.uleb128(offset)?,
+            DW_EH_PE_UDATA2 => self.u16_bytes(read(offset, 2)?).into(),
+            DW_EH_PE_UDATA4 => self.u32_bytes(read(offset, 4)?).into(),
+            DW_EH_PE_UDATA8 => self.u64_bytes(read(offset, 8)?),
+            DW_EH_PE_SLEB128 => self.sleb128(offset)? as u64,
+            DW_EH_PE_SDATA2 => self.u16_bytes(read(offset, 2)?) as i16 as u64,
+            DW_EH_PE_SDATA4 => self.u32_bytes(read(offset, 4)?) as i32 as u64,
+            DW_EH_PE_SDATA8 => self.u64_bytes(read(offset, 8)?),
+            _ => {
+                return Err(Error(format!(
+                    "unsupported .eh_frame pointer encoding {:#x} at {:#x}",
+                    encoding, start
+                )));
+            }
+        };
+        Ok(value)
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'data [u8]> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| Error(format!("unexpected end of .eh_frame at {:#x}", offset)))
+    }
+
+    fn u8(&self, offset: &mut usize) -> Result<u8> {
+        let value = self.bytes(*offset, 1)?[0];
+        *offset += 1;
+        Ok(value)
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        Ok(self.u32_bytes(self.bytes(offset, 4)?))
+    }
+
+    fn u16_bytes(&self, bytes: &[u8]) -> u16 {
+        self.endian.read_u16_bytes([bytes[0], bytes[1]])
+    }
+
+    fn u32_bytes(&self, bytes: &[u8]) -> u32 {
+        self.endian
+            .read_u32_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn u64_bytes(&self, bytes: &[u8]) -> u64 {
+        self.endian.read_u64_bytes([
+            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+        ])
+    }
+
+    fn uleb128(&self, offset: &mut usize) -> Result<u64> {
+        let mut result = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8(offset)?;
+            if shift < 64 {
+                result |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+    }
+
+    fn sleb128(&self, offset: &mut usize) -> Result<i64> {
+        let mut result = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8(offset)?;
+            if shift < 64 {
+                result |= i64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && (byte & 0x40) != 0 {
+                    result |= !0 << shift;
+                }
+                return Ok(result);
+            }
+        }
+    }
+}
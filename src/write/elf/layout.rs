@@ -0,0 +1,363 @@
+//! Helper for calculating the layout of segments.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp;
+
+use crate::elf;
+use crate::write::elf::{Class, ProgramHeader};
+use crate::write::{Error, Result};
+
+/// A section that is placed by [`SegmentLayout`].
+#[derive(Debug, Default, Clone)]
+pub struct LayoutSection {
+    /// The size of the section.
+    pub size: u64,
+    /// The required alignment of the section.
+    pub align: u64,
+    /// Whether the section occupies no space in the file, such as `SHT_NOBITS`.
+    pub is_nobits: bool,
+    /// The file offset of the section.
+    ///
+    /// This is set by [`SegmentLayout::calculate`].
+    pub offset: u64,
+    /// The address of the section.
+    ///
+    /// This is set by [`SegmentLayout::calculate`].
+    pub address: u64,
+    load_segment: usize,
+}
+
+#[derive(Debug, Clone)]
+struct LayoutSegment {
+    p_type: u32,
+    p_flags: u32,
+    address: Option<u64>,
+    sections: Vec<usize>,
+}
+
+/// A helper for calculating the file offsets and addresses of the sections and
+/// segments in an executable or shared object.
+///
+/// Sections are placed in the order that they are added to `PT_LOAD` segments,
+/// and `PT_LOAD` segments are placed in the order that they are added. The file
+/// offset and address of each section are congruent modulo the page size, so that
+/// the segments can be mapped directly from the file. The first `PT_LOAD` segment
+/// also contains the file header and program headers.
+///
+/// Other segments, such as `PT_INTERP`, `PT_DYNAMIC` and `PT_NOTE`, cover a range
+/// of consecutive sections within a single `PT_LOAD` segment. A `PT_PHDR` segment
+/// covers the program headers.
+///
+/// The program headers are in the order that the segments are added.
+/// After calling [`Self::calculate`], the file offset of each section can be passed
+/// to [`Writer::reserve_until`](super::Writer::reserve_until) before reserving the
+/// section, and the program headers can be written using
+/// [`Self::program_headers`].
+#[derive(Debug)]
+pub struct SegmentLayout {
+    class: Class,
+    base_address: u64,
+    page_size: u64,
+    sections: Vec<LayoutSection>,
+    segments: Vec<LayoutSegment>,
+    program_headers: Vec<ProgramHeader>,
+    file_size: u64,
+}
+
+impl SegmentLayout {
+    /// Create a new layout.
+    ///
+    /// `base_address` is the address of the first `PT_LOAD` segment, and is
+    /// typically 0 for shared objects. `page_size` must be a power of two.
+    pub fn new(class: Class, base_address: u64, page_size: u64) -> Self {
+        debug_assert!(page_size.is_power_of_two());
+        SegmentLayout {
+            class,
+            base_address,
+            page_size,
+            sections: Vec::new(),
+            segments: Vec::new(),
+            program_headers: Vec::new(),
+            file_size: 0,
+        }
+    }
+
+    /// Add a segment.
+    ///
+    /// Segments other than `PT_LOAD`, `PT_PHDR` and `PT_GNU_STACK` must contain
+    /// at least one section. For example, a `PT_GNU_RELRO` segment should contain
+    /// the sections that are read-only after relocation.
+    ///
+    /// A `PT_GNU_STACK` segment without sections is written with a size of zero,
+    /// and only its flags are significant: they determine whether the stack is
+    /// executable.
+    ///
+    /// Returns the index of the segment, which is also the index of its program header.
+    pub fn add_segment(&mut self, p_type: u32, p_flags: u32) -> usize {
+        self.segments.push(LayoutSegment {
+            p_type,
+            p_flags,
+            address: None,
+            sections: Vec::new(),
+        });
+        self.segments.len() - 1
+    }
+
+    /// Set the address of a `PT_LOAD` segment.
+    ///
+    /// By default, each `PT_LOAD` segment starts on the page following the end of
+    /// the previous segment. The given address must not be lower than this.
+    pub fn set_segment_address(&mut self, segment: usize, address: u64) {
+        debug_assert_eq!(self.segments[segment].p_type, elf::PT_LOAD);
+        self.segments[segment].address = Some(address);
+    }
+
+    /// Add a section to the end of a `PT_LOAD` segment.
+    ///
+    /// Sections that occupy no space in the file must be after all other sections
+    /// in the segment.
+    ///
+    /// Returns the index of the section within the layout.
+    pub fn add_section(&mut self, segment: usize, size: u64, align: u64, is_nobits: bool) -> usize {
+        debug_assert_eq!(self.segments[segment].p_type, elf::PT_LOAD);
+        let index = self.sections.len();
+        self.sections.push(LayoutSection {
+            size,
+            align,
+            is_nobits,
+            offset: 0,
+            address: 0,
+            load_segment: segment,
+        });
+        self.segments[segment].sections.push(index);
+        index
+    }
+
+    /// Add a section to the end of a segment other than `PT_LOAD`.
+    ///
+    /// The section must have already been added to a `PT_LOAD` segment using
+    /// [`Self::add_section`].
+    pub fn append_section(&mut self, segment: usize, section: usize) {
+        debug_assert_ne!(self.segments[segment].p_type, elf::PT_LOAD);
+        self.segments[segment].sections.push(section);
+    }
+
+    /// Return the size of the file header and program headers.
+    pub fn headers_size(&self) -> u64 {
+        (self.class.file_header_size() + self.segments.len() * self.class.program_header_size())
+            as u64
+    }
+
+    /// Calculate the file offsets and addresses.
+    ///
+    /// Returns an error if segments overlap, if the sections of a segment other than
+    /// `PT_LOAD` are not consecutive in a single `PT_LOAD` segment, or if the
+    /// addresses are too large.
+    pub fn calculate(&mut self) -> Result<()> {
+        let headers_size = self.headers_size();
+        let page_size = self.page_size;
+        let mut load_headers = Vec::new();
+        let mut first_load_address = None;
+        let mut offset = 0;
+        let mut address = 0;
+        for (index, segment) in self.segments.iter().enumerate() {
+            if segment.p_type != elf::PT_LOAD {
+                continue;
+            }
+
+            let mut segment_offset;
+            let mut segment_address;
+            let is_first = first_load_address.is_none();
+            if is_first {
+                segment_offset = 0;
+                segment_address = segment.address.unwrap_or(self.base_address);
+                first_load_address = Some(segment_address);
+                if segment_address % page_size != 0 {
+                    return Err(Error(format!(
+                        "unaligned address {:#x} for first PT_LOAD segment",
+                        segment_address
+                    )));
+                }
+                offset = headers_size;
+                address = add(segment_address, headers_size)?;
+            } else {
+                let min_address = add(align(address, page_size)?, offset % page_size)?;
+                match segment.address {
+                    Some(segment_address) if segment_address < address => {
+                        return Err(Error(format!(
+                            "PT_LOAD segment {} at address {:#x} overlaps previous segment ending at {:#x}",
+                            index, segment_address, address
+                        )));
+                    }
+                    Some(segment_address) => {
+                        // Pad the file so that the offset is congruent to the address.
+                        offset += segment_address.wrapping_sub(offset) % page_size;
+                        address = segment_address;
+                    }
+                    None => address = min_address,
+                }
+                segment_offset = offset;
+                segment_address = address;
+            }
+
+            let mut nobits = false;
+            for (i, &section_index) in segment.sections.iter().enumerate() {
+                let section = &mut self.sections[section_index];
+                let aligned = align(address, cmp::max(section.align, 1))?;
+                if !section.is_nobits {
+                    offset += aligned - address;
+                }
+                address = aligned;
+                if section.is_nobits {
+                    nobits = true;
+                } else if nobits {
+                    return Err(Error(format!(
+                        "section {} in PT_LOAD segment {} follows a section with no file data",
+                        section_index, index
+                    )));
+                }
+                section.offset = offset;
+                section.address = address;
+                if i == 0 && !is_first {
+                    // Start the segment at its first section instead of including the padding.
+                    segment_offset = offset;
+                    segment_address = address;
+                }
+                if !section.is_nobits {
+                    offset += section.size;
+                }
+                address = add(address, section.size)?;
+            }
+
+            load_headers.push(ProgramHeader {
+                p_type: segment.p_type,
+                p_flags: segment.p_flags,
+                p_offset: segment_offset,
+                p_vaddr: segment_address,
+                p_paddr: segment_address,
+                p_filesz: offset - segment_offset,
+                p_memsz: address - segment_address,
+                p_align: page_size,
+            });
+        }
+        if !self.class.is_64 && address > u64::from(u32::MAX) {
+            return Err(Error(format!(
+                "address {:#x} is too large for 32-bit ELF",
+                address
+            )));
+        }
+        self.file_size = offset;
+
+        // Calculate the segments other than PT_LOAD, keeping the original order.
+        let mut load_headers = load_headers.into_iter();
+        let mut program_headers = Vec::with_capacity(self.segments.len());
+        for (index, segment) in self.segments.iter().enumerate() {
+            let header = if segment.p_type == elf::PT_LOAD {
+                load_headers.next().unwrap()
+            } else if segment.p_type == elf::PT_PHDR {
+                let first_load_address = first_load_address.ok_or_else(|| {
+                    Error(String::from("PT_PHDR segment requires a PT_LOAD segment"))
+                })?;
+                let p_offset = self.class.file_header_size() as u64;
+                let size = headers_size - p_offset;
+                ProgramHeader {
+                    p_type: segment.p_type,
+                    p_flags: segment.p_flags,
+                    p_offset,
+                    p_vaddr: first_load_address + p_offset,
+                    p_paddr: first_load_address + p_offset,
+                    p_filesz: size,
+                    p_memsz: size,
+                    p_align: self.class.align() as u64,
+                }
+            } else if segment.p_type == elf::PT_GNU_STACK && segment.sections.is_empty() {
+                ProgramHeader {
+                    p_type: segment.p_type,
+                    p_flags: segment.p_flags,
+                    p_offset: 0,
+                    p_vaddr: 0,
+                    p_paddr: 0,
+                    p_filesz: 0,
+                    p_memsz: 0,
+                    p_align: 0,
+                }
+            } else {
+                self.segment_header(index, segment)?
+            };
+            program_headers.push(header);
+        }
+        self.program_headers = program_headers;
+        Ok(())
+    }
+
+    fn segment_header(&self, index: usize, segment: &LayoutSegment) -> Result<ProgramHeader> {
+        let (first, rest) = match segment.sections.split_first() {
+            Some(sections) => sections,
+            None => {
+                return Err(Error(format!("segment {} contains no sections", index)));
+            }
+        };
+        let first_section = &self.sections[*first];
+        let load_sections = &self.segments[first_section.load_segment].sections;
+        let mut position = load_sections.iter().position(|s| s == first).unwrap();
+        let mut align = cmp::max(first_section.align, 1);
+        let mut last_section = first_section;
+        for section_index in rest {
+            position += 1;
+            if load_sections.get(position) != Some(section_index) {
+                return Err(Error(format!(
+                    "sections of segment {} are not consecutive in a PT_LOAD segment",
+                    index
+                )));
+            }
+            last_section = &self.sections[*section_index];
+            align = cmp::max(align, last_section.align);
+        }
+        let file_end = if last_section.is_nobits {
+            last_section.offset
+        } else {
+            last_section.offset + last_section.size
+        };
+        Ok(ProgramHeader {
+            p_type: segment.p_type,
+            p_flags: segment.p_flags,
+            p_offset: first_section.offset,
+            p_vaddr: first_section.address,
+            p_paddr: first_section.address,
+            p_filesz: file_end - first_section.offset,
+            p_memsz: last_section.address + last_section.size - first_section.address,
+            p_align: align,
+        })
+    }
+
+    /// Return the section with the given index.
+    ///
+    /// The file offset and address are valid after calling [`Self::calculate`].
+    pub fn section(&self, index: usize) -> &LayoutSection {
+        &self.sections[index]
+    }
+
+    /// Return the program headers.
+    ///
+    /// This is valid after calling [`Self::calculate`].
+    pub fn program_headers(&self) -> &[ProgramHeader] {
+        &self.program_headers
+    }
+
+    /// Return the file offset of the end of the last section in a segment.
+    ///
+    /// This is valid after calling [`Self::calculate`].
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+}
+
+fn add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b)
+        .ok_or_else(|| Error(String::from("segment address is too large")))
+}
+
+fn align(address: u64, align: u64) -> Result<u64> {
+    debug_assert!(align.is_power_of_two());
+    Ok(add(address, align - 1)? & !(align - 1))
+}
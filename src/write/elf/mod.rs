@@ -1,9 +1,29 @@
 //! Support for writing ELF files.
 //!
-//! Provides [`Writer`] for low level writing of ELF files.
+//! Provides [`Writer`] for low level writing of ELF files, and [`SegmentLayout`]
+//! for calculating the layout of segments in executables and shared objects.
+//! Core files can be written using [`CoreNotes`] and [`write_core`], and
+//! `.eh_frame_hdr` sections can be written using [`write_eh_frame_hdr`].
+//! [`SymbolTableBuilder`] and [`DynamicBuilder`] can be used with [`Writer`] to write
+//! the symbol table and dynamic table.
 //! This is also used to provide ELF support for [`write::Object`](crate::write::Object).
 
+mod coredump;
+pub use coredump::*;
+
+mod dynamic;
+pub use dynamic::*;
+
+mod eh_frame;
+pub use eh_frame::*;
+
+mod layout;
+pub use layout::*;
+
 mod object;
 
+mod symtab;
+pub use symtab::*;
+
 mod writer;
 pub use writer::*;
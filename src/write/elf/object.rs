@@ -56,6 +56,36 @@ impl<'a> Object<'a> {
         let section = self.section_id(StandardSection::GnuProperty);
         self.append_section_data(section, &data, align as u64);
     }
+
+    /// Add the ELF ".note.GNU-stack" section, which specifies whether the code
+    /// in this object requires an executable stack.
+    ///
+    /// Linkers use this section to determine the flags of the `PT_GNU_STACK`
+    /// segment. If the section is missing, linkers may assume that an executable
+    /// stack is required.
+    ///
+    /// If the section has already been added, this updates its flags.
+    ///
+    /// Requires `feature = "elf"`.
+    pub fn set_elf_gnu_stack(&mut self, executable: bool) {
+        if self.format != BinaryFormat::Elf {
+            return;
+        }
+
+        let name = &b".note.GNU-stack"[..];
+        let section = match self
+            .sections
+            .iter()
+            .position(|section| section.name == name)
+        {
+            Some(index) => SectionId(index),
+            None => self.add_section(Vec::new(), name.to_vec(), SectionKind::Other),
+        };
+        let sh_flags = if executable { elf::SHF_EXECINSTR } else { 0 };
+        self.sections[section.0].flags = SectionFlags::Elf {
+            sh_flags: sh_flags.into(),
+        };
+    }
 }
 
 // Private methods.
@@ -443,7 +473,7 @@ impl<'a> Object<'a> {
         }
     }
 
-    pub(crate) fn elf_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+    pub(crate) fn elf_write(&self, buffer: &mut dyn WritableBuffer) -> Result<SymbolIndices> {
         // Create reloc section header names so we can reference them.
         let is_rela = self.elf_has_relocation_addend()?;
         let reloc_names: Vec<_> = self
@@ -485,8 +515,13 @@ impl<'a> Object<'a> {
             let str_id = writer.add_section_name(b".group");
             comdat_offsets.push(ComdatOffsets { offset, str_id });
         }
+        // Sections are written in order of their keys, but are indexed by `SectionId`.
+        let mut section_order: Vec<usize> = (0..self.sections.len()).collect();
+        section_order.sort_by_key(|index| self.sections[*index].order);
         let mut section_offsets = Vec::with_capacity(self.sections.len());
-        for (section, reloc_name) in self.sections.iter().zip(reloc_names.iter()) {
+        for &section_index in &section_order {
+            let section = &self.sections[section_index];
+            let reloc_name = &reloc_names[section_index];
             let index = writer.reserve_section_index();
             let offset = writer.reserve(section.data.len(), section.align as usize);
             let str_id = writer.add_section_name(&section.name);
@@ -495,31 +530,54 @@ impl<'a> Object<'a> {
                 writer.reserve_section_index();
                 reloc_str_id = Some(writer.add_section_name(reloc_name));
             }
-            section_offsets.push(SectionOffsets {
-                index,
-                offset,
-                str_id,
-                // Relocation data is reserved later.
-                reloc_offset: 0,
-                reloc_str_id,
-            });
+            section_offsets.push((
+                section_index,
+                SectionOffsets {
+                    index,
+                    offset,
+                    str_id,
+                    // Relocation data is reserved later.
+                    reloc_offset: 0,
+                    reloc_str_id,
+                },
+            ));
         }
+        section_offsets.sort_by_key(|(section_index, _)| *section_index);
+        let mut section_offsets: Vec<_> = section_offsets
+            .into_iter()
+            .map(|(_, offsets)| offsets)
+            .collect();
 
         // Calculate index of symbols and add symbol strings to strtab.
         let mut symbol_offsets = vec![SymbolOffsets::default(); self.symbols.len()];
         writer.reserve_null_symbol_index();
         // Local symbols must come before global.
-        for (index, symbol) in self.symbols.iter().enumerate() {
-            if symbol.is_local() {
-                let section_index = symbol.section.id().map(|s| section_offsets[s.0].index);
-                symbol_offsets[index].index = writer.reserve_symbol_index(section_index);
+        let mut symbol_order: Vec<usize> = (0..self.symbols.len()).collect();
+        match self.symbol_order {
+            SymbolOrder::Format => {
+                symbol_order.sort_by_key(|index| !self.symbols[*index].is_local());
+            }
+            SymbolOrder::Insertion => {
+                if let Some(pair) = self
+                    .symbols
+                    .windows(2)
+                    .find(|pair| !pair[0].is_local() && pair[1].is_local())
+                {
+                    return Err(Error(format!(
+                        "local symbol `{}` added after global symbol `{}`",
+                        pair[1].name().unwrap_or(""),
+                        pair[0].name().unwrap_or(""),
+                    )));
+                }
             }
         }
-        let symtab_num_local = writer.symbol_count();
-        for (index, symbol) in self.symbols.iter().enumerate() {
-            if !symbol.is_local() {
-                let section_index = symbol.section.id().map(|s| section_offsets[s.0].index);
-                symbol_offsets[index].index = writer.reserve_symbol_index(section_index);
+        let mut symtab_num_local = writer.symbol_count();
+        for &index in &symbol_order {
+            let symbol = &self.symbols[index];
+            let section_index = symbol.section.id().map(|s| section_offsets[s.0].index);
+            symbol_offsets[index].index = writer.reserve_symbol_index(section_index);
+            if symbol.is_local() {
+                symtab_num_local = writer.symbol_count();
             }
         }
         for (index, symbol) in self.symbols.iter().enumerate() {
@@ -621,7 +679,8 @@ impl<'a> Object<'a> {
                 writer.write_comdat_entry(section_offsets[section.0].index);
             }
         }
-        for (index, section) in self.sections.iter().enumerate() {
+        for &index in &section_order {
+            let section = &self.sections[index];
             writer.write_align(section.align as usize);
             debug_assert_eq!(section_offsets[index].offset, writer.len());
             writer.write(&section.data);
@@ -705,15 +764,8 @@ impl<'a> Object<'a> {
             });
             Ok(())
         };
-        for (index, symbol) in self.symbols.iter().enumerate() {
-            if symbol.is_local() {
-                write_symbol(index, symbol)?;
-            }
-        }
-        for (index, symbol) in self.symbols.iter().enumerate() {
-            if !symbol.is_local() {
-                write_symbol(index, symbol)?;
-            }
+        for &index in &symbol_order {
+            write_symbol(index, &self.symbols[index])?;
         }
         writer.write_symtab_shndx();
         writer.write_strtab();
@@ -758,7 +810,8 @@ impl<'a> Object<'a> {
                 comdat.sections.len(),
             );
         }
-        for (index, section) in self.sections.iter().enumerate() {
+        for &index in &section_order {
+            let section = &self.sections[index];
             let sh_type = match section.kind {
                 SectionKind::UninitializedData | SectionKind::UninitializedTls => elf::SHT_NOBITS,
                 SectionKind::Note => elf::SHT_NOTE,
@@ -836,6 +889,11 @@ impl<'a> Object<'a> {
 
         debug_assert_eq!(writer.reserved_len(), writer.len());
 
-        Ok(())
+        Ok(SymbolIndices(
+            symbol_offsets
+                .iter()
+                .map(|offsets| Some(offsets.index.0 as usize))
+                .collect(),
+        ))
     }
 }
@@ -0,0 +1,179 @@
+//! Helper for building the symbol table.
+use alloc::vec::Vec;
+
+use crate::elf;
+use crate::write::elf::{SectionIndex, Sym, SymbolIndex, Writer};
+use crate::write::string::StringId;
+
+/// A symbol that is added to a [`SymbolTableBuilder`].
+///
+/// This is the same as [`Sym`], except that the name is not yet in the string table.
+#[allow(missing_docs)]
+#[derive(Debug, Clone)]
+pub struct SymbolTableEntry<'a> {
+    pub name: Option<&'a [u8]>,
+    pub section: Option<SectionIndex>,
+    pub st_info: u8,
+    pub st_other: u8,
+    pub st_shndx: u16,
+    pub st_value: u64,
+    pub st_size: u64,
+}
+
+impl<'a> SymbolTableEntry<'a> {
+    fn is_local(&self) -> bool {
+        self.st_info >> 4 == elf::STB_LOCAL
+    }
+}
+
+/// A helper for writing the `.symtab`, `.symtab_shndx` and `.strtab` sections
+/// using a [`Writer`].
+///
+/// Symbols may be added in any order. The builder places the local symbols before
+/// the other symbols, adds the symbol names to the string table, determines whether
+/// `.symtab_shndx` is required, and sets `sh_info` of the symbol table to the index
+/// of the first non-local symbol.
+///
+/// The methods of this builder replace the corresponding symbol table and string
+/// table methods of the `Writer`, and must be called in the following order:
+/// - [`Self::reserve_indices`] after reserving the section indices that the symbols
+///   reference, and before using [`Self::symbol_index`]
+/// - [`Self::reserve`] where the sections should be placed in the file
+/// - [`Self::write`] in the same position relative to other sections
+/// - [`Self::write_section_headers`] in the same position relative to other section
+///   headers
+#[derive(Debug, Default)]
+pub struct SymbolTableBuilder<'a> {
+    symbols: Vec<SymbolTableEntry<'a>>,
+    indices: Vec<SymbolIndex>,
+    names: Vec<Option<StringId>>,
+    order: Vec<usize>,
+    num_local: u32,
+}
+
+impl<'a> SymbolTableBuilder<'a> {
+    /// Create a new symbol table builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a symbol.
+    ///
+    /// The null symbol is added automatically and must not be added by this method.
+    ///
+    /// Returns the position of the symbol within the builder. This is not the
+    /// symbol index; use [`Self::symbol_index`] to obtain that.
+    pub fn add_symbol(&mut self, symbol: SymbolTableEntry<'a>) -> usize {
+        debug_assert!(self.order.is_empty());
+        self.symbols.push(symbol);
+        self.symbols.len() - 1
+    }
+
+    /// Return the number of symbols that have been added.
+    ///
+    /// Does not include the null symbol.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Return true if no symbols have been added.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Reserve the symbol indices and add the symbol names to the string table.
+    ///
+    /// This also reserves the null symbol, so the symbol table is always written
+    /// even if no symbols were added.
+    ///
+    /// This must be called before [`Writer::reserve_strtab`], and no other
+    /// symbol table entries may be reserved in the writer.
+    pub fn reserve_indices(&mut self, writer: &mut Writer<'a>) {
+        debug_assert!(self.order.is_empty());
+        writer.reserve_null_symbol_index();
+        self.indices = vec![SymbolIndex(0); self.symbols.len()];
+        self.order.reserve(self.symbols.len());
+        // Local symbols must come before global.
+        for is_local in [true, false] {
+            for (index, symbol) in self.symbols.iter().enumerate() {
+                if symbol.is_local() == is_local {
+                    self.indices[index] = writer.reserve_symbol_index(symbol.section);
+                    self.order.push(index);
+                }
+            }
+            if is_local {
+                self.num_local = writer.symbol_count();
+            }
+        }
+        self.names = self
+            .symbols
+            .iter()
+            .map(|symbol| match symbol.name {
+                Some(name) if !name.is_empty() => Some(writer.add_string(name)),
+                _ => None,
+            })
+            .collect();
+    }
+
+    /// Return the index of the symbol at the given position within the builder.
+    ///
+    /// This must be called after [`Self::reserve_indices`].
+    pub fn symbol_index(&self, symbol: usize) -> SymbolIndex {
+        self.indices[symbol]
+    }
+
+    /// Return the value of `sh_info` for the symbol table.
+    ///
+    /// This is one greater than the symbol index of the last local symbol.
+    ///
+    /// This must be called after [`Self::reserve_indices`].
+    pub fn num_local(&self) -> u32 {
+        self.num_local
+    }
+
+    /// Reserve the section indices and file ranges for the symbol table, the
+    /// extended section indices (if needed), and the string table.
+    ///
+    /// The section indices are reserved in the same order, which is the order
+    /// that [`Self::write_section_headers`] writes them.
+    ///
+    /// This must be called after [`Self::reserve_indices`].
+    pub fn reserve(&mut self, writer: &mut Writer<'a>) {
+        writer.reserve_symtab_section_index();
+        writer.reserve_symtab();
+        if writer.symtab_shndx_needed() {
+            writer.reserve_symtab_shndx_section_index();
+        }
+        writer.reserve_symtab_shndx();
+        writer.reserve_strtab_section_index();
+        writer.reserve_strtab();
+    }
+
+    /// Write the symbol table, the extended section indices (if needed),
+    /// and the string table.
+    pub fn write(&self, writer: &mut Writer<'a>) {
+        writer.write_null_symbol();
+        for &index in &self.order {
+            let symbol = &self.symbols[index];
+            writer.write_symbol(&Sym {
+                name: self.names[index],
+                section: symbol.section,
+                st_info: symbol.st_info,
+                st_other: symbol.st_other,
+                st_shndx: symbol.st_shndx,
+                st_value: symbol.st_value,
+                st_size: symbol.st_size,
+            });
+        }
+        writer.write_symtab_shndx();
+        writer.write_strtab();
+    }
+
+    /// Write the section headers for the symbol table, the extended section
+    /// indices (if needed), and the string table.
+    pub fn write_section_headers(&self, writer: &mut Writer<'a>) {
+        writer.write_symtab_section_header(self.num_local);
+        writer.write_symtab_shndx_section_header();
+        writer.write_strtab_section_header();
+    }
+}
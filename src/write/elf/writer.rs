@@ -1,7 +1,7 @@
 //! Helper for writing ELF files.
 use alloc::string::String;
 use alloc::vec::Vec;
-use core::mem;
+use core::{cmp, mem};
 
 use crate::elf;
 use crate::endian::*;
@@ -40,7 +40,8 @@ pub struct SymbolIndex(pub u32);
 ///
 /// The second phase writes everything out in order. Thus the caller must ensure writing
 /// is in the same order that file ranges were reserved. There are debug asserts to assist
-/// with checking this.
+/// with checking this. Alternatively, [`Self::set_validation`] can be used to enable
+/// checks that report these errors via [`Self::validate`] instead.
 #[allow(missing_debug_implementations)]
 pub struct Writer<'a> {
     endian: Endianness,
@@ -51,6 +52,10 @@ pub struct Writer<'a> {
     buffer: &'a mut dyn WritableBuffer,
     len: usize,
 
+    validate: bool,
+    validation_error: Option<Error>,
+    validation_last: &'static str,
+
     segment_offset: usize,
     segment_num: u32,
 
@@ -96,6 +101,10 @@ pub struct Writer<'a> {
     dynamic_offset: usize,
     dynamic_num: usize,
 
+    relr_str_id: Option<StringId>,
+    relr_offset: usize,
+    relr_data: Vec<u64>,
+
     hash_str_id: Option<StringId>,
     hash_offset: usize,
     hash_size: usize,
@@ -113,6 +122,7 @@ pub struct Writer<'a> {
     gnu_verdef_count: u16,
     gnu_verdef_remaining: u16,
     gnu_verdaux_remaining: u16,
+    gnu_verdefs: Vec<(Verdef, Vec<StringId>)>,
 
     gnu_verneed_str_id: Option<StringId>,
     gnu_verneed_offset: usize,
@@ -120,6 +130,7 @@ pub struct Writer<'a> {
     gnu_verneed_count: u16,
     gnu_verneed_remaining: u16,
     gnu_vernaux_remaining: u16,
+    gnu_verneeds: Vec<(Verneed, Vec<Vernaux>)>,
 
     gnu_attributes_str_id: Option<StringId>,
     gnu_attributes_offset: usize,
@@ -140,6 +151,10 @@ impl<'a> Writer<'a> {
             buffer,
             len: 0,
 
+            validate: false,
+            validation_error: None,
+            validation_last: "start of file",
+
             segment_offset: 0,
             segment_num: 0,
 
@@ -185,6 +200,10 @@ impl<'a> Writer<'a> {
             dynamic_offset: 0,
             dynamic_num: 0,
 
+            relr_str_id: None,
+            relr_offset: 0,
+            relr_data: Vec::new(),
+
             hash_str_id: None,
             hash_offset: 0,
             hash_size: 0,
@@ -202,6 +221,7 @@ impl<'a> Writer<'a> {
             gnu_verdef_count: 0,
             gnu_verdef_remaining: 0,
             gnu_verdaux_remaining: 0,
+            gnu_verdefs: Vec::new(),
 
             gnu_verneed_str_id: None,
             gnu_verneed_offset: 0,
@@ -209,6 +229,7 @@ impl<'a> Writer<'a> {
             gnu_verneed_count: 0,
             gnu_verneed_remaining: 0,
             gnu_vernaux_remaining: 0,
+            gnu_verneeds: Vec::new(),
 
             gnu_attributes_str_id: None,
             gnu_attributes_offset: 0,
@@ -221,6 +242,67 @@ impl<'a> Writer<'a> {
         Class { is_64: self.is_64 }
     }
 
+    /// Enable or disable validation of the writes.
+    ///
+    /// By default, debug asserts are used to check that headers and sections are
+    /// written in the order that they were reserved, at the reserved offsets, and
+    /// with the reserved sizes.
+    ///
+    /// When validation is enabled, these checks are performed in all builds, and
+    /// instead of panicking, the first failure is recorded and returned by
+    /// [`Self::validate`].
+    pub fn set_validation(&mut self, validate: bool) {
+        self.validate = validate;
+    }
+
+    /// Return the first error found by validation.
+    ///
+    /// This also checks that the length that has been written matches the length
+    /// that was reserved, so it should be called after everything has been written.
+    ///
+    /// See [`Self::set_validation`].
+    pub fn validate(&self) -> Result<()> {
+        if let Some(error) = &self.validation_error {
+            return Err(error.clone());
+        }
+        if self.buffer.len() != self.len {
+            return Err(Error(format!(
+                "wrote {:#x} bytes after {}, but reserved {:#x} bytes",
+                self.buffer.len(),
+                self.validation_last,
+                self.len
+            )));
+        }
+        Ok(())
+    }
+
+    /// Record a validation failure, or panic if validation is disabled and debug
+    /// assertions are enabled.
+    fn check_failed<F: FnOnce() -> String>(&mut self, message: F) {
+        if self.validate {
+            if self.validation_error.is_none() {
+                self.validation_error = Some(Error(message()));
+            }
+        } else if cfg!(debug_assertions) {
+            panic!("{}", message());
+        }
+    }
+
+    /// Check that the range for `name` is being written at its reserved offset.
+    fn check_offset(&mut self, name: &'static str, offset: usize) {
+        let len = self.buffer.len();
+        if len != offset {
+            let last = self.validation_last;
+            self.check_failed(|| {
+                format!(
+                    "write of {} at offset {:#x} after {}, but it was reserved at offset {:#x}",
+                    name, len, last, offset
+                )
+            });
+        }
+        self.validation_last = name;
+    }
+
     /// Return the current file length that has been reserved.
     pub fn reserved_len(&self) -> usize {
         self.len
@@ -268,8 +350,17 @@ impl<'a> Writer<'a> {
 
     /// Write padding up to the given file offset.
     pub fn pad_until(&mut self, offset: usize) {
-        debug_assert!(self.buffer.len() <= offset);
-        self.buffer.resize(offset);
+        let len = self.buffer.len();
+        if len > offset {
+            self.check_failed(|| {
+                format!(
+                    "padding until offset {:#x}, but already written until offset {:#x}",
+                    offset, len
+                )
+            });
+        } else {
+            self.buffer.write_zeros(offset - len);
+        }
     }
 
     /// Reserve the range for the file header.
@@ -286,7 +377,7 @@ impl<'a> Writer<'a> {
     ///
     /// Fields that can be derived from known information are automatically set by this function.
     pub fn write_file_header(&mut self, header: &FileHeader) -> Result<()> {
-        debug_assert_eq!(self.buffer.len(), 0);
+        self.check_offset("file header", 0);
 
         self.is_mips64el =
             self.is_64 && self.endian.is_little_endian() && header.e_machine == elf::EM_MIPS;
@@ -404,7 +495,7 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, self.elf_align);
-        debug_assert_eq!(self.segment_offset, self.buffer.len());
+        self.check_offset("program headers", self.segment_offset);
     }
 
     /// Write a program header.
@@ -491,7 +582,7 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, self.elf_align);
-        debug_assert_eq!(self.section_offset, self.buffer.len());
+        self.check_offset("section headers", self.section_offset);
         self.write_section_header(&SectionHeader {
             name: None,
             sh_type: 0,
@@ -589,7 +680,7 @@ impl<'a> Writer<'a> {
         if self.shstrtab_offset == 0 {
             return;
         }
-        debug_assert_eq!(self.shstrtab_offset, self.buffer.len());
+        self.check_offset(".shstrtab", self.shstrtab_offset);
         self.buffer.write_bytes(&self.shstrtab_data);
     }
 
@@ -678,7 +769,7 @@ impl<'a> Writer<'a> {
         if self.strtab_offset == 0 {
             return;
         }
-        debug_assert_eq!(self.strtab_offset, self.buffer.len());
+        self.check_offset(".strtab", self.strtab_offset);
         self.buffer.write_bytes(&self.strtab_data);
     }
 
@@ -805,7 +896,7 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, self.elf_align);
-        debug_assert_eq!(self.symtab_offset, self.buffer.len());
+        self.check_offset(".symtab", self.symtab_offset);
         if self.is_64 {
             self.buffer.write(&elf::Sym64::<Endianness>::default());
         } else {
@@ -942,8 +1033,16 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, ALIGN_SYMTAB_SHNDX);
-        debug_assert_eq!(self.symtab_shndx_offset, self.buffer.len());
-        debug_assert_eq!(self.symtab_num as usize * 4, self.symtab_shndx_data.len());
+        self.check_offset(".symtab_shndx", self.symtab_shndx_offset);
+        if self.symtab_num as usize * 4 != self.symtab_shndx_data.len() {
+            let (reserved, written) = (self.symtab_num, self.symtab_shndx_data.len() / 4);
+            self.check_failed(|| {
+                format!(
+                    "wrote {} of {} reserved symbols before .symtab_shndx",
+                    written, reserved
+                )
+            });
+        }
         self.buffer.write_bytes(&self.symtab_shndx_data);
     }
 
@@ -1056,7 +1155,7 @@ impl<'a> Writer<'a> {
         if self.dynstr_offset == 0 {
             return;
         }
-        debug_assert_eq!(self.dynstr_offset, self.buffer.len());
+        self.check_offset(".dynstr", self.dynstr_offset);
         self.buffer.write_bytes(&self.dynstr_data);
     }
 
@@ -1177,7 +1276,7 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, self.elf_align);
-        debug_assert_eq!(self.dynsym_offset, self.buffer.len());
+        self.check_offset(".dynsym", self.dynsym_offset);
         if self.is_64 {
             self.buffer.write(&elf::Sym64::<Endianness>::default());
         } else {
@@ -1293,7 +1392,7 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, self.elf_align);
-        debug_assert_eq!(self.dynamic_offset, self.buffer.len());
+        self.check_offset(".dynamic", self.dynamic_offset);
     }
 
     /// Reserve a file range for the given number of dynamic entries.
@@ -1358,6 +1457,8 @@ impl<'a> Writer<'a> {
     ///
     /// `symbol_count` is the number of symbols in the hash,
     /// not the total number of symbols.
+    ///
+    /// [`Class::hash_bucket_count`] can be used to calculate the bucket count.
     pub fn reserve_hash(&mut self, bucket_count: u32, chain_count: u32) -> usize {
         self.hash_size = self.class().hash_size(bucket_count, chain_count);
         self.hash_offset = self.reserve(self.hash_size, ALIGN_HASH);
@@ -1383,7 +1484,7 @@ impl<'a> Writer<'a> {
         }
 
         util::write_align(self.buffer, ALIGN_HASH);
-        debug_assert_eq!(self.hash_offset, self.buffer.len());
+        self.check_offset(".hash", self.hash_offset);
         self.buffer.write(&elf::HashHeader {
             bucket_count: U32::new(self.endian, bucket_count),
             chain_count: U32::new(self.endian, chain_count),
@@ -1429,6 +1530,9 @@ impl<'a> Writer<'a> {
     ///
     /// `symbol_count` is the number of symbols in the hash,
     /// not the total number of symbols.
+    ///
+    /// [`Class::gnu_hash_parameters`] can be used to calculate the bloom
+    /// and bucket counts.
     pub fn reserve_gnu_hash(
         &mut self,
         bloom_count: u32,
@@ -1447,7 +1551,8 @@ impl<'a> Writer<'a> {
     /// `symbol_count` is the number of symbols in the hash.
     /// The argument to `hash` will be in the range `0..symbol_count`.
     ///
-    /// This requires that symbols are already sorted by bucket.
+    /// This requires that symbols are already sorted by bucket,
+    /// where the bucket of a symbol is its hash modulo `bucket_count`.
     pub fn write_gnu_hash<F>(
         &mut self,
         symbol_base: u32,
@@ -1460,7 +1565,7 @@ impl<'a> Writer<'a> {
         F: Fn(u32) -> u32,
     {
         util::write_align(self.buffer, self.elf_align);
-        debug_assert_eq!(self.gnu_hash_offset, self.buffer.len());
+        self.check_offset(".gnu.hash", self.gnu_hash_offset);
         self.buffer.write(&elf::GnuHashHeader {
             bucket_count: U32::new(self.endian, bucket_count),
             symbol_base: U32::new(self.endian, symbol_base),
@@ -1577,7 +1682,7 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, ALIGN_GNU_VERSYM);
-        debug_assert_eq!(self.gnu_versym_offset, self.buffer.len());
+        self.check_offset(".gnu.version", self.gnu_versym_offset);
         self.write_gnu_versym(0);
     }
 
@@ -1586,6 +1691,31 @@ impl<'a> Writer<'a> {
         self.buffer.write(&U16::new(self.endian, versym));
     }
 
+    /// Write the `.gnu.version` section.
+    ///
+    /// This writes the null symbol version entry, followed by one entry
+    /// for each of the given versions. `versyms` must contain an entry for
+    /// each reserved dynamic symbol, excluding the null symbol.
+    /// This function does nothing if no dynamic symbols were reserved.
+    pub fn write_gnu_versyms(&mut self, versyms: &[u16]) {
+        if self.dynsym_num == 0 {
+            return;
+        }
+        if versyms.len() + 1 != self.dynsym_num as usize {
+            let (reserved, count) = (self.dynsym_num - 1, versyms.len());
+            self.check_failed(|| {
+                format!(
+                    "{} symbol versions given for {} reserved dynamic symbols",
+                    count, reserved
+                )
+            });
+        }
+        self.write_null_gnu_versym();
+        for versym in versyms {
+            self.write_gnu_versym(*versym);
+        }
+    }
+
     /// Reserve the section index for the `.gnu.version` section.
     pub fn reserve_gnu_versym_section_index(&mut self) -> SectionIndex {
         self.reserve_gnu_versym_section_index_with_name(&b".gnu.version"[..])
@@ -1619,6 +1749,59 @@ impl<'a> Writer<'a> {
         });
     }
 
+    /// Add a version definition to the `.gnu.version_d` section.
+    ///
+    /// `names` contains the name of the version, followed by the names of
+    /// any parent versions. It must not be empty.
+    ///
+    /// The names are added to the dynamic string table, so this must be called
+    /// before [`Self::reserve_dynstr`]. The definitions are written in the order
+    /// they are added by [`Self::write_gnu_version_definitions`].
+    pub fn add_gnu_version_definition(&mut self, flags: u16, index: u16, names: &[&'a [u8]]) {
+        debug_assert_eq!(self.gnu_verdef_offset, 0);
+        debug_assert!(!names.is_empty());
+        let names: Vec<_> = names
+            .iter()
+            .map(|name| self.add_dynamic_string(name))
+            .collect();
+        let verdef = Verdef {
+            version: elf::VER_DEF_CURRENT,
+            flags,
+            index,
+            aux_count: names.len() as u16,
+            name: names[0],
+        };
+        self.gnu_verdefs.push((verdef, names));
+    }
+
+    /// Reserve the range for the `.gnu.version_d` section using the version
+    /// definitions that were added by [`Self::add_gnu_version_definition`].
+    ///
+    /// This function does nothing if no version definitions were added.
+    pub fn reserve_gnu_version_definitions(&mut self) -> usize {
+        let verdaux_count = self.gnu_verdefs.iter().map(|(_, names)| names.len()).sum();
+        self.reserve_gnu_verdef(self.gnu_verdefs.len(), verdaux_count)
+    }
+
+    /// Write the `.gnu.version_d` section using the version definitions
+    /// that were added by [`Self::add_gnu_version_definition`].
+    ///
+    /// This function does nothing if no version definitions were added.
+    pub fn write_gnu_version_definitions(&mut self) {
+        if self.gnu_verdefs.is_empty() {
+            return;
+        }
+        self.write_align_gnu_verdef();
+        let verdefs = mem::take(&mut self.gnu_verdefs);
+        for (verdef, names) in &verdefs {
+            self.write_gnu_verdef(verdef);
+            for name in &names[1..] {
+                self.write_gnu_verdaux(*name);
+            }
+        }
+        self.gnu_verdefs = verdefs;
+    }
+
     /// Reserve the range for the `.gnu.version_d` section.
     pub fn reserve_gnu_verdef(&mut self, verdef_count: usize, verdaux_count: usize) -> usize {
         debug_assert_eq!(self.gnu_verdef_offset, 0);
@@ -1638,13 +1821,15 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, ALIGN_GNU_VERDEF);
-        debug_assert_eq!(self.gnu_verdef_offset, self.buffer.len());
+        self.check_offset(".gnu.version_d", self.gnu_verdef_offset);
     }
 
     /// Write a version definition entry.
     pub fn write_gnu_verdef(&mut self, verdef: &Verdef) {
-        debug_assert_ne!(self.gnu_verdef_remaining, 0);
-        self.gnu_verdef_remaining -= 1;
+        if self.gnu_verdef_remaining == 0 {
+            self.check_failed(|| String::from("too many version definitions written"));
+        }
+        self.gnu_verdef_remaining = self.gnu_verdef_remaining.saturating_sub(1);
         let vd_next = if self.gnu_verdef_remaining == 0 {
             0
         } else {
@@ -1673,9 +1858,15 @@ impl<'a> Writer<'a> {
     /// This is typically useful when there are only two versions (including the base)
     /// and they have the same name.
     pub fn write_gnu_verdef_shared(&mut self, verdef: &Verdef) {
-        debug_assert_ne!(self.gnu_verdef_remaining, 0);
-        self.gnu_verdef_remaining -= 1;
-        debug_assert_ne!(self.gnu_verdef_remaining, 0);
+        if self.gnu_verdef_remaining == 0 {
+            self.check_failed(|| String::from("too many version definitions written"));
+        }
+        self.gnu_verdef_remaining = self.gnu_verdef_remaining.saturating_sub(1);
+        if self.gnu_verdef_remaining == 0 {
+            self.check_failed(|| {
+                String::from("shared version definition must be followed by another definition")
+            });
+        }
         let vd_next = mem::size_of::<elf::Verdef<Endianness>>() as u32;
 
         debug_assert_ne!(verdef.aux_count, 0);
@@ -1695,8 +1886,12 @@ impl<'a> Writer<'a> {
 
     /// Write a version definition auxiliary entry.
     pub fn write_gnu_verdaux(&mut self, name: StringId) {
-        debug_assert_ne!(self.gnu_verdaux_remaining, 0);
-        self.gnu_verdaux_remaining -= 1;
+        if self.gnu_verdaux_remaining == 0 {
+            self.check_failed(|| {
+                String::from("too many version definition auxiliary entries written")
+            });
+        }
+        self.gnu_verdaux_remaining = self.gnu_verdaux_remaining.saturating_sub(1);
         let vda_next = if self.gnu_verdaux_remaining == 0 {
             0
         } else {
@@ -1741,6 +1936,68 @@ impl<'a> Writer<'a> {
         });
     }
 
+    /// Add a version dependency to the `.gnu.version_r` section.
+    ///
+    /// `file` is the name of the file containing the versions, and `versions`
+    /// contains the flags, index and name of each version that is needed.
+    ///
+    /// The names are added to the dynamic string table, so this must be called
+    /// before [`Self::reserve_dynstr`]. The dependencies are written in the order
+    /// they are added by [`Self::write_gnu_version_dependencies`].
+    pub fn add_gnu_version_dependency(
+        &mut self,
+        file: &'a [u8],
+        versions: &[(u16, u16, &'a [u8])],
+    ) {
+        debug_assert_eq!(self.gnu_verneed_offset, 0);
+        let verneed = Verneed {
+            version: elf::VER_NEED_CURRENT,
+            aux_count: versions.len() as u16,
+            file: self.add_dynamic_string(file),
+        };
+        let vernauxs = versions
+            .iter()
+            .map(|(flags, index, name)| Vernaux {
+                flags: *flags,
+                index: *index,
+                name: self.add_dynamic_string(name),
+            })
+            .collect();
+        self.gnu_verneeds.push((verneed, vernauxs));
+    }
+
+    /// Reserve the range for the `.gnu.version_r` section using the version
+    /// dependencies that were added by [`Self::add_gnu_version_dependency`].
+    ///
+    /// This function does nothing if no version dependencies were added.
+    pub fn reserve_gnu_version_dependencies(&mut self) -> usize {
+        let vernaux_count = self
+            .gnu_verneeds
+            .iter()
+            .map(|(_, vernauxs)| vernauxs.len())
+            .sum();
+        self.reserve_gnu_verneed(self.gnu_verneeds.len(), vernaux_count)
+    }
+
+    /// Write the `.gnu.version_r` section using the version dependencies
+    /// that were added by [`Self::add_gnu_version_dependency`].
+    ///
+    /// This function does nothing if no version dependencies were added.
+    pub fn write_gnu_version_dependencies(&mut self) {
+        if self.gnu_verneeds.is_empty() {
+            return;
+        }
+        self.write_align_gnu_verneed();
+        let verneeds = mem::take(&mut self.gnu_verneeds);
+        for (verneed, vernauxs) in &verneeds {
+            self.write_gnu_verneed(verneed);
+            for vernaux in vernauxs {
+                self.write_gnu_vernaux(vernaux);
+            }
+        }
+        self.gnu_verneeds = verneeds;
+    }
+
     /// Reserve the range for the `.gnu.version_r` section.
     pub fn reserve_gnu_verneed(&mut self, verneed_count: usize, vernaux_count: usize) -> usize {
         debug_assert_eq!(self.gnu_verneed_offset, 0);
@@ -1760,13 +2017,15 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, ALIGN_GNU_VERNEED);
-        debug_assert_eq!(self.gnu_verneed_offset, self.buffer.len());
+        self.check_offset(".gnu.version_r", self.gnu_verneed_offset);
     }
 
     /// Write a version need entry.
     pub fn write_gnu_verneed(&mut self, verneed: &Verneed) {
-        debug_assert_ne!(self.gnu_verneed_remaining, 0);
-        self.gnu_verneed_remaining -= 1;
+        if self.gnu_verneed_remaining == 0 {
+            self.check_failed(|| String::from("too many version needs written"));
+        }
+        self.gnu_verneed_remaining = self.gnu_verneed_remaining.saturating_sub(1);
         let vn_next = if self.gnu_verneed_remaining == 0 {
             0
         } else {
@@ -1792,8 +2051,10 @@ impl<'a> Writer<'a> {
 
     /// Write a version need auxiliary entry.
     pub fn write_gnu_vernaux(&mut self, vernaux: &Vernaux) {
-        debug_assert_ne!(self.gnu_vernaux_remaining, 0);
-        self.gnu_vernaux_remaining -= 1;
+        if self.gnu_vernaux_remaining == 0 {
+            self.check_failed(|| String::from("too many version need auxiliary entries written"));
+        }
+        self.gnu_vernaux_remaining = self.gnu_vernaux_remaining.saturating_sub(1);
         let vna_next = if self.gnu_vernaux_remaining == 0 {
             0
         } else {
@@ -1894,7 +2155,7 @@ impl<'a> Writer<'a> {
             return;
         }
         util::write_align(self.buffer, self.elf_align);
-        debug_assert_eq!(self.gnu_attributes_offset, self.buffer.len());
+        self.check_offset(".gnu.attributes", self.gnu_attributes_offset);
         self.buffer.write_bytes(data);
     }
 
@@ -1990,7 +2251,7 @@ impl<'a> Writer<'a> {
     ) {
         self.write_section_header(&SectionHeader {
             name: Some(name),
-            sh_type: elf::SHT_RELA,
+            sh_type: elf::SHT_RELR,
             sh_flags: 0,
             sh_addr: 0,
             sh_offset: offset as u64,
@@ -2002,6 +2263,149 @@ impl<'a> Writer<'a> {
         });
     }
 
+    /// Reserve a file range for a `.relr.dyn` section.
+    ///
+    /// `offsets` are the addresses of the relative relocations. They must be
+    /// sorted, unique, and aligned to the word size of the file.
+    /// The relocations are encoded using the bitmap encoding of `SHT_RELR`.
+    ///
+    /// This function does nothing if `offsets` is empty.
+    ///
+    /// Returns the offset of the range.
+    pub fn reserve_relr(&mut self, offsets: &[u64]) -> Result<usize> {
+        debug_assert_eq!(self.relr_offset, 0);
+        if offsets.is_empty() {
+            return Ok(0);
+        }
+        let word_size = self.class().relr_size() as u64;
+        let mut prev = None;
+        for &offset in offsets {
+            if offset % word_size != 0 {
+                return Err(Error(format!(
+                    "unaligned relative relocation offset {:#x}",
+                    offset
+                )));
+            }
+            if !self.is_64 && offset > u64::from(u32::MAX) {
+                return Err(Error(format!(
+                    "relative relocation offset {:#x} is too large",
+                    offset
+                )));
+            }
+            if prev.map_or(false, |prev| offset <= prev) {
+                return Err(Error(String::from(
+                    "relative relocation offsets are not sorted",
+                )));
+            }
+            prev = Some(offset);
+        }
+
+        // Each bitmap entry covers the words following the previous entry,
+        // using all bits except the lowest bit, which marks the bitmap.
+        let bitmap_bits = word_size * 8 - 1;
+        let mut data = Vec::new();
+        let mut i = 0;
+        while i < offsets.len() {
+            data.push(offsets[i]);
+            let mut base = offsets[i] + word_size;
+            i += 1;
+            loop {
+                let mut bitmap = 0;
+                while i < offsets.len() {
+                    let delta = (offsets[i] - base) / word_size;
+                    if delta >= bitmap_bits {
+                        break;
+                    }
+                    bitmap |= 1 << delta;
+                    i += 1;
+                }
+                if bitmap == 0 {
+                    break;
+                }
+                data.push((bitmap << 1) | 1);
+                base += bitmap_bits * word_size;
+            }
+        }
+
+        self.relr_offset = self.reserve(data.len() * word_size as usize, self.elf_align);
+        self.relr_data = data;
+        Ok(self.relr_offset)
+    }
+
+    /// Return the size of the `.relr.dyn` section.
+    ///
+    /// This is only valid after calling [`Self::reserve_relr`].
+    pub fn relr_size(&self) -> usize {
+        self.relr_data.len() * self.class().relr_size()
+    }
+
+    /// Write the `.relr.dyn` section.
+    ///
+    /// This function does nothing if the section was not reserved.
+    pub fn write_relr(&mut self) {
+        if self.relr_offset == 0 {
+            return;
+        }
+        util::write_align(self.buffer, self.elf_align);
+        self.check_offset(".relr.dyn", self.relr_offset);
+        for entry in &self.relr_data {
+            if self.is_64 {
+                self.buffer.write(&U64::new(self.endian, *entry));
+            } else {
+                self.buffer.write(&U32::new(self.endian, *entry as u32));
+            }
+        }
+    }
+
+    /// Write the `DT_RELR`, `DT_RELRSZ` and `DT_RELRENT` dynamic entries
+    /// for the `.relr.dyn` section.
+    ///
+    /// `address` is the virtual address of the section.
+    ///
+    /// This function does nothing if the section was not reserved.
+    /// Otherwise, three dynamic entries must have been reserved for this.
+    pub fn write_relr_dynamics(&mut self, address: u64) {
+        if self.relr_offset == 0 {
+            return;
+        }
+        self.write_dynamic(elf::DT_RELR, address);
+        self.write_dynamic(elf::DT_RELRSZ, self.relr_size() as u64);
+        self.write_dynamic(elf::DT_RELRENT, self.class().relr_size() as u64);
+    }
+
+    /// Reserve the section index for the `.relr.dyn` section.
+    pub fn reserve_relr_section_index(&mut self) -> SectionIndex {
+        self.reserve_relr_section_index_with_name(&b".relr.dyn"[..])
+    }
+
+    /// Reserve the section index for the `.relr.dyn` section.
+    pub fn reserve_relr_section_index_with_name(&mut self, name: &'a [u8]) -> SectionIndex {
+        debug_assert!(self.relr_str_id.is_none());
+        self.relr_str_id = Some(self.add_section_name(name));
+        self.reserve_section_index()
+    }
+
+    /// Write the section header for the `.relr.dyn` section.
+    ///
+    /// This function does nothing if the section index was not reserved.
+    pub fn write_relr_section_header(&mut self, sh_addr: u64) {
+        if self.relr_str_id.is_none() {
+            return;
+        }
+        self.write_section_header(&SectionHeader {
+            name: self.relr_str_id,
+            sh_type: elf::SHT_RELR,
+            sh_flags: elf::SHF_ALLOC.into(),
+            sh_addr,
+            sh_offset: self.relr_offset as u64,
+            sh_size: self.relr_size() as u64,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: self.elf_align as u64,
+            sh_entsize: self.class().relr_size() as u64,
+        });
+    }
+
     /// Reserve a file range for a COMDAT section.
     ///
     /// `count` is the number of sections in the COMDAT group.
@@ -2271,6 +2675,25 @@ impl Class {
             + chain_count as usize * 4
     }
 
+    /// Calculate the bucket count for a SysV hash table.
+    ///
+    /// `symbol_count` is the number of symbols in the hash.
+    ///
+    /// This uses the same table of bucket counts as `ld`.
+    pub fn hash_bucket_count(self, symbol_count: u32) -> u32 {
+        const BUCKETS: [u32; 16] = [
+            1, 3, 17, 37, 67, 97, 131, 197, 263, 521, 1031, 2053, 4099, 8209, 16411, 32771,
+        ];
+        let mut bucket_count = BUCKETS[0];
+        for &count in &BUCKETS[1..] {
+            if symbol_count < count {
+                break;
+            }
+            bucket_count = count;
+        }
+        bucket_count
+    }
+
     /// Return the size of a GNU hash table.
     pub fn gnu_hash_size(self, bloom_count: u32, bucket_count: u32, symbol_count: u32) -> usize {
         let bloom_size = if self.is_64 { 8 } else { 4 };
@@ -2280,6 +2703,46 @@ impl Class {
             + symbol_count as usize * 4
     }
 
+    /// Calculate the parameters for a GNU hash table.
+    ///
+    /// `symbol_count` is the number of symbols in the hash,
+    /// not the total number of symbols.
+    ///
+    /// This uses the same heuristics as `ld --hash-style=gnu`.
+    pub fn gnu_hash_parameters(self, symbol_count: u32) -> GnuHashParameters {
+        if symbol_count == 0 {
+            return GnuHashParameters {
+                bloom_shift: 0,
+                bloom_count: 1,
+                bucket_count: 1,
+            };
+        }
+
+        let bucket_count = cmp::max(self.hash_bucket_count(symbol_count), 2);
+
+        // Choose the bloom filter size in the same way as `ld`.
+        let mut bloom_log2 = 32 - (symbol_count - 1).leading_zeros() + 1;
+        if bloom_log2 < 3 {
+            bloom_log2 = 5;
+        } else if (1 << (bloom_log2 - 2)) & symbol_count != 0 {
+            bloom_log2 += 3;
+        } else {
+            bloom_log2 += 2;
+        }
+        let word_log2 = if self.is_64 {
+            bloom_log2 = cmp::max(bloom_log2, 6);
+            6
+        } else {
+            5
+        };
+
+        GnuHashParameters {
+            bloom_shift: bloom_log2,
+            bloom_count: 1 << (bloom_log2 - word_log2),
+            bucket_count,
+        }
+    }
+
     /// Return the size of a GNU symbol version section.
     pub fn gnu_versym_size(self, symbol_count: usize) -> usize {
         symbol_count * 2
@@ -2298,6 +2761,20 @@ impl Class {
     }
 }
 
+/// The parameters for a GNU hash table.
+///
+/// These are passed to [`Writer::reserve_gnu_hash`] and [`Writer::write_gnu_hash`],
+/// and can be calculated using [`Class::gnu_hash_parameters`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GnuHashParameters {
+    /// The shift count for the second bloom filter hash.
+    pub bloom_shift: u32,
+    /// The number of words in the bloom filter. Must be a power of 2.
+    pub bloom_count: u32,
+    /// The number of hash buckets.
+    pub bucket_count: u32,
+}
+
 /// Native endian version of [`elf::FileHeader64`].
 #[allow(missing_docs)]
 #[derive(Debug, Clone)]
@@ -0,0 +1,151 @@
+//! Helper for writing Mach-O universal binaries.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::endian::{BigEndian as BE, U32, U64};
+use crate::macho;
+use crate::write::util;
+use crate::write::{Error, Result, WritableBuffer};
+
+/// The maximum alignment of a slice in a universal binary, as a power of 2.
+const MAX_FAT_ALIGN: u32 = 15;
+
+/// A slice to be written to a Mach-O universal binary by [`write_fat`].
+#[derive(Debug, Clone, Copy)]
+pub struct FatArch<'a> {
+    /// The CPU type of the slice.
+    pub cputype: u32,
+    /// The CPU subtype of the slice.
+    pub cpusubtype: u32,
+    /// The alignment of the slice within the file, as a power of 2.
+    pub align: u32,
+    /// The contents of the slice.
+    pub data: &'a [u8],
+}
+
+impl<'a> FatArch<'a> {
+    /// Create a slice using the default alignment for the CPU type.
+    ///
+    /// See [`fat_align`].
+    pub fn new(cputype: u32, cpusubtype: u32, data: &'a [u8]) -> Self {
+        FatArch {
+            cputype,
+            cpusubtype,
+            align: fat_align(cputype),
+            data,
+        }
+    }
+}
+
+/// Return the default alignment of a slice in a universal binary, as a power of 2.
+///
+/// This is the page size for the CPU type, which is 16KB for ARM and 4KB otherwise.
+pub fn fat_align(cputype: u32) -> u32 {
+    match cputype {
+        macho::CPU_TYPE_ARM | macho::CPU_TYPE_ARM64 | macho::CPU_TYPE_ARM64_32 => 14,
+        _ => 12,
+    }
+}
+
+/// Write a Mach-O universal binary containing the given slices.
+///
+/// The slices are written in the given order, each aligned as specified.
+/// If `is_64` is true then [`macho::FatArch64`] is used for the slice headers,
+/// otherwise [`macho::FatArch32`] is used, and it is an error if the offset or
+/// size of a slice does not fit in 32 bits.
+///
+/// Returns an error if two slices have the same CPU type and subtype.
+pub fn write_fat(
+    buffer: &mut dyn WritableBuffer,
+    arches: &[FatArch<'_>],
+    is_64: bool,
+) -> Result<()> {
+    for (i, arch) in arches.iter().enumerate() {
+        if arch.align > MAX_FAT_ALIGN {
+            return Err(Error(format!(
+                "fat arch alignment 2^{} is too large",
+                arch.align
+            )));
+        }
+        let cpusubtype = arch.cpusubtype & !macho::CPU_SUBTYPE_MASK;
+        if arches[..i].iter().any(|other| {
+            other.cputype == arch.cputype
+                && other.cpusubtype & !macho::CPU_SUBTYPE_MASK == cpusubtype
+        }) {
+            return Err(Error(format!(
+                "duplicate fat arch with cputype {:#x} and cpusubtype {:#x}",
+                arch.cputype, arch.cpusubtype
+            )));
+        }
+    }
+
+    // Calculate offsets of the slices.
+    let header_len = mem::size_of::<macho::FatHeader>();
+    let arch_len = if is_64 {
+        mem::size_of::<macho::FatArch64>()
+    } else {
+        mem::size_of::<macho::FatArch32>()
+    };
+    let mut offset = (header_len + arches.len() * arch_len) as u64;
+    let mut offsets = Vec::with_capacity(arches.len());
+    for arch in arches {
+        offset = util::align_u64(offset, 1 << arch.align);
+        offsets.push(offset);
+        offset += arch.data.len() as u64;
+    }
+    if !is_64 && offset > u64::from(u32::MAX) {
+        return Err(Error(String::from(
+            "fat file is too large for 32-bit fat arches",
+        )));
+    }
+    let len = offset as usize;
+    if len as u64 != offset {
+        return Err(Error(String::from("fat file is too large")));
+    }
+
+    // Start writing.
+    debug_assert_eq!(buffer.len(), 0);
+    buffer
+        .reserve(len)
+        .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+
+    buffer.write(&macho::FatHeader {
+        magic: U32::new(
+            BE,
+            if is_64 {
+                macho::FAT_MAGIC_64
+            } else {
+                macho::FAT_MAGIC
+            },
+        ),
+        nfat_arch: U32::new(BE, arches.len() as u32),
+    });
+    for (arch, offset) in arches.iter().zip(offsets.iter()) {
+        if is_64 {
+            buffer.write(&macho::FatArch64 {
+                cputype: U32::new(BE, arch.cputype),
+                cpusubtype: U32::new(BE, arch.cpusubtype),
+                offset: U64::new(BE, *offset),
+                size: U64::new(BE, arch.data.len() as u64),
+                align: U32::new(BE, arch.align),
+                reserved: U32::default(),
+            });
+        } else {
+            buffer.write(&macho::FatArch32 {
+                cputype: U32::new(BE, arch.cputype),
+                cpusubtype: U32::new(BE, arch.cpusubtype),
+                offset: U32::new(BE, *offset as u32),
+                size: U32::new(BE, arch.data.len() as u32),
+                align: U32::new(BE, arch.align),
+            });
+        }
+    }
+    for (arch, offset) in arches.iter().zip(offsets.iter()) {
+        buffer.write_zeros(*offset as usize - buffer.len());
+        buffer.write_bytes(arch.data);
+    }
+    debug_assert_eq!(buffer.len(), len);
+
+    Ok(())
+}
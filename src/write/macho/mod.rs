@@ -0,0 +1,17 @@
+//! Support for writing Mach-O files.
+//!
+//! Provides [`Writer`] for low level writing of Mach-O files,
+//! [`CodeSignature`] for ad hoc code signatures,
+//! and [`write_fat`] for writing universal binaries.
+//! This is also used to provide Mach-O support for [`write::Object`](crate::write::Object).
+
+mod object;
+
+mod writer;
+pub use writer::*;
+
+mod fat;
+pub use fat::*;
+
+mod signature;
+pub use signature::*;
@@ -1,7 +1,5 @@
-use core::mem;
-
-use crate::endian::*;
 use crate::macho;
+use crate::write::macho::writer;
 use crate::write::string::*;
 use crate::write::util::*;
 use crate::write::*;
@@ -17,34 +15,10 @@ struct SectionOffsets {
 
 #[derive(Default, Clone, Copy)]
 struct SymbolOffsets {
-    index: usize,
+    index: u32,
     str_id: Option<StringId>,
 }
 
-/// The customizable portion of a [`macho::BuildVersionCommand`].
-#[derive(Debug, Default, Clone, Copy)]
-#[non_exhaustive] // May want to add the tool list?
-pub struct MachOBuildVersion {
-    /// One of the `PLATFORM_` constants (for example,
-    /// [`object::macho::PLATFORM_MACOS`](macho::PLATFORM_MACOS)).
-    pub platform: u32,
-    /// The minimum OS version, where `X.Y.Z` is encoded in nibbles as
-    /// `xxxx.yy.zz`.
-    pub minos: u32,
-    /// The SDK version as `X.Y.Z`, where `X.Y.Z` is encoded in nibbles as
-    /// `xxxx.yy.zz`.
-    pub sdk: u32,
-}
-
-impl MachOBuildVersion {
-    fn cmdsize(&self) -> u32 {
-        // Same size for both endianness, and we don't have `ntools`.
-        let sz = mem::size_of::<macho::BuildVersionCommand<Endianness>>();
-        debug_assert!(sz <= u32::MAX as usize);
-        sz as u32
-    }
-}
-
 // Public methods.
 impl<'a> Object<'a> {
     /// Specify the Mach-O CPU subtype.
@@ -175,7 +149,7 @@ impl<'a> Object<'a> {
     ///
     /// If `symbol_id` is not for a TLS variable, then it is returned unchanged.
     pub(crate) fn macho_add_thread_var(&mut self, symbol_id: SymbolId) -> SymbolId {
-        let symbol = self.symbol_mut(symbol_id);
+        let symbol = &self.symbols[symbol_id.0];
         if symbol.kind != SymbolKind::Tls {
             return symbol_id;
         }
@@ -236,7 +210,7 @@ impl<'a> Object<'a> {
         .unwrap();
 
         // Update the symbol to point to the tlv.
-        let symbol = self.symbol_mut(symbol_id);
+        let symbol = &mut self.symbols[symbol_id.0];
         symbol.value = offset;
         symbol.size = size;
         symbol.section = SymbolSection::Section(section);
@@ -355,58 +329,26 @@ impl<'a> Object<'a> {
         }
     }
 
-    pub(crate) fn macho_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+    pub(crate) fn macho_write(&self, buffer: &mut dyn WritableBuffer) -> Result<SymbolIndices> {
         let address_size = self.architecture.address_size().unwrap();
-        let endian = self.endian;
-        let macho32 = MachO32 { endian };
-        let macho64 = MachO64 { endian };
-        let macho: &dyn MachO = match address_size {
-            AddressSize::U8 | AddressSize::U16 | AddressSize::U32 => &macho32,
-            AddressSize::U64 => &macho64,
+        let is_64 = match address_size {
+            AddressSize::U8 | AddressSize::U16 | AddressSize::U32 => false,
+            AddressSize::U64 => true,
         };
-        let pointer_align = address_size.bytes() as usize;
+        let mut writer = writer::Writer::new(self.endian, is_64, buffer);
 
-        // Calculate offsets of everything, and build strtab.
-        let mut offset = 0;
-
-        // Calculate size of Mach-O header.
-        offset += macho.mach_header_size();
-
-        // Calculate size of commands.
-        let mut ncmds = 0;
-        let command_offset = offset;
-
-        // Calculate size of segment command and section headers.
-        let segment_command_offset = offset;
-        let segment_command_len =
-            macho.segment_command_size() + self.sections.len() * macho.section_header_size();
-        offset += segment_command_len;
-        ncmds += 1;
-
-        // Calculate size of build version.
-        let build_version_offset = offset;
-        if let Some(version) = &self.macho_build_version {
-            offset += version.cmdsize() as usize;
-            ncmds += 1;
+        // Reserve the Mach-O header and load commands.
+        writer.reserve_mach_header();
+        writer.reserve_segment_command(self.sections.len() as u32);
+        if self.macho_build_version.is_some() {
+            writer.reserve_build_version_command();
         }
-
-        // Calculate size of symtab command.
-        let symtab_command_offset = offset;
-        let symtab_command_len = mem::size_of::<macho::SymtabCommand<Endianness>>();
-        offset += symtab_command_len;
-        ncmds += 1;
-
-        // Calculate size of dysymtab command.
-        let dysymtab_command_offset = offset;
-        let dysymtab_command_len = mem::size_of::<macho::DysymtabCommand<Endianness>>();
-        offset += dysymtab_command_len;
-        ncmds += 1;
-
-        let sizeofcmds = offset - command_offset;
+        writer.reserve_symtab_command();
+        writer.reserve_dysymtab_command();
 
         // Calculate size of section data.
         // Section data can immediately follow the load commands without any alignment padding.
-        let segment_file_offset = offset;
+        let segment_file_offset = writer.reserved_len();
         let mut section_offsets = vec![SectionOffsets::default(); self.sections.len()];
         let mut address = 0;
         for (index, section) in self.sections.iter().enumerate() {
@@ -419,7 +361,7 @@ impl<'a> Object<'a> {
             }
         }
         let segment_file_size = address as usize;
-        offset += address as usize;
+        writer.reserve_until(segment_file_offset + segment_file_size);
         for (index, section) in self.sections.iter().enumerate() {
             if section.is_bss() {
                 debug_assert!(section.data.is_empty());
@@ -430,7 +372,6 @@ impl<'a> Object<'a> {
         }
 
         // Partition symbols and add symbol strings to strtab.
-        let mut strtab = StringTable::default();
         let mut symbol_offsets = vec![SymbolOffsets::default(); self.symbols.len()];
         let mut local_symbols = vec![];
         let mut external_symbols = vec![];
@@ -452,7 +393,7 @@ impl<'a> Object<'a> {
                 }
             }
             if !symbol.name.is_empty() {
-                symbol_offsets[index].str_id = Some(strtab.add(&symbol.name));
+                symbol_offsets[index].str_id = Some(writer.add_string(&symbol.name));
             }
             if symbol.is_undefined() {
                 undefined_symbols.push(index);
@@ -463,55 +404,37 @@ impl<'a> Object<'a> {
             }
         }
 
-        external_symbols.sort_by_key(|index| &*self.symbols[*index].name);
-        undefined_symbols.sort_by_key(|index| &*self.symbols[*index].name);
+        if self.symbol_order == SymbolOrder::Format {
+            external_symbols.sort_by_key(|index| &*self.symbols[*index].name);
+            undefined_symbols.sort_by_key(|index| &*self.symbols[*index].name);
+        }
 
-        // Count symbols.
-        let mut nsyms = 0;
+        // Reserve symbol indices.
+        let mut symbol_indices = vec![None; self.symbols.len()];
         for index in local_symbols
             .iter()
             .copied()
             .chain(external_symbols.iter().copied())
             .chain(undefined_symbols.iter().copied())
         {
-            symbol_offsets[index].index = nsyms;
-            nsyms += 1;
+            symbol_offsets[index].index = writer.reserve_symbol_index();
+            symbol_indices[index] = Some(symbol_offsets[index].index as usize);
         }
 
-        // Calculate size of relocations.
+        // Reserve relocations.
         for (index, section) in self.sections.iter().enumerate() {
             let count: usize = section
                 .relocations
                 .iter()
                 .map(|reloc| 1 + usize::from(reloc.addend != 0))
                 .sum();
-            if count != 0 {
-                offset = align(offset, pointer_align);
-                section_offsets[index].reloc_offset = offset;
-                section_offsets[index].reloc_count = count;
-                let len = count * mem::size_of::<macho::Relocation<Endianness>>();
-                offset += len;
-            }
+            section_offsets[index].reloc_offset = writer.reserve_relocations(count);
+            section_offsets[index].reloc_count = count;
         }
 
-        // Calculate size of symtab.
-        offset = align(offset, pointer_align);
-        let symtab_offset = offset;
-        let symtab_len = nsyms * macho.nlist_size();
-        offset += symtab_len;
-
-        // Calculate size of strtab.
-        let strtab_offset = offset;
-        // Start with null name.
-        let mut strtab_data = vec![0];
-        strtab.write(1, &mut strtab_data);
-        write_align(&mut strtab_data, pointer_align);
-        offset += strtab_data.len();
-
-        // Start writing.
-        buffer
-            .reserve(offset)
-            .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+        // Reserve symtab and strtab.
+        writer.reserve_symtab();
+        writer.reserve_strtab();
 
         // Write file header.
         let (cputype, mut cpusubtype) = match (self.architecture, self.sub_architecture) {
@@ -550,35 +473,25 @@ impl<'a> Object<'a> {
         if self.macho_subsections_via_symbols {
             flags |= macho::MH_SUBSECTIONS_VIA_SYMBOLS;
         }
-        macho.write_mach_header(
-            buffer,
-            MachHeader {
-                cputype,
-                cpusubtype,
-                filetype: macho::MH_OBJECT,
-                ncmds,
-                sizeofcmds: sizeofcmds as u32,
-                flags,
-            },
-        );
+        writer.write_mach_header(&writer::MachHeader {
+            cputype,
+            cpusubtype,
+            filetype: macho::MH_OBJECT,
+            flags,
+        })?;
 
         // Write segment command.
-        debug_assert_eq!(segment_command_offset, buffer.len());
-        macho.write_segment_command(
-            buffer,
-            SegmentCommand {
-                cmdsize: segment_command_len as u32,
-                segname: [0; 16],
-                vmaddr: 0,
-                vmsize: address,
-                fileoff: segment_file_offset as u64,
-                filesize: segment_file_size as u64,
-                maxprot: macho::VM_PROT_READ | macho::VM_PROT_WRITE | macho::VM_PROT_EXECUTE,
-                initprot: macho::VM_PROT_READ | macho::VM_PROT_WRITE | macho::VM_PROT_EXECUTE,
-                nsects: self.sections.len() as u32,
-                flags: 0,
-            },
-        );
+        writer.write_segment_command(&writer::SegmentCommand {
+            segname: [0; 16],
+            vmaddr: 0,
+            vmsize: address,
+            fileoff: segment_file_offset as u64,
+            filesize: segment_file_size as u64,
+            maxprot: macho::VM_PROT_READ | macho::VM_PROT_WRITE | macho::VM_PROT_EXECUTE,
+            initprot: macho::VM_PROT_READ | macho::VM_PROT_WRITE | macho::VM_PROT_EXECUTE,
+            nsects: self.sections.len() as u32,
+            flags: 0,
+        });
 
         // Write section headers.
         for (index, section) in self.sections.iter().enumerate() {
@@ -628,90 +541,55 @@ impl<'a> Object<'a> {
                     }
                 }
             };
-            macho.write_section(
-                buffer,
-                SectionHeader {
-                    sectname,
-                    segname,
-                    addr: section_offsets[index].address,
-                    size: section.size,
-                    offset: section_offsets[index].offset as u32,
-                    align: section.align.trailing_zeros(),
-                    reloff: section_offsets[index].reloc_offset as u32,
-                    nreloc: section_offsets[index].reloc_count as u32,
-                    flags,
-                },
-            );
+            writer.write_section_header(&writer::SectionHeader {
+                sectname,
+                segname,
+                addr: section_offsets[index].address,
+                size: section.size,
+                offset: section_offsets[index].offset as u32,
+                align: section.align.trailing_zeros(),
+                reloff: section_offsets[index].reloc_offset as u32,
+                nreloc: section_offsets[index].reloc_count as u32,
+                flags,
+                reserved1: 0,
+                reserved2: 0,
+            });
         }
 
         // Write build version.
         if let Some(version) = &self.macho_build_version {
-            debug_assert_eq!(build_version_offset, buffer.len());
-            buffer.write(&macho::BuildVersionCommand {
-                cmd: U32::new(endian, macho::LC_BUILD_VERSION),
-                cmdsize: U32::new(endian, version.cmdsize()),
-                platform: U32::new(endian, version.platform),
-                minos: U32::new(endian, version.minos),
-                sdk: U32::new(endian, version.sdk),
-                ntools: U32::new(endian, 0),
-            });
+            writer.write_build_version_command(version);
         }
 
         // Write symtab command.
-        debug_assert_eq!(symtab_command_offset, buffer.len());
-        let symtab_command = macho::SymtabCommand {
-            cmd: U32::new(endian, macho::LC_SYMTAB),
-            cmdsize: U32::new(endian, symtab_command_len as u32),
-            symoff: U32::new(endian, symtab_offset as u32),
-            nsyms: U32::new(endian, nsyms as u32),
-            stroff: U32::new(endian, strtab_offset as u32),
-            strsize: U32::new(endian, strtab_data.len() as u32),
-        };
-        buffer.write(&symtab_command);
+        writer.write_symtab_command();
 
         // Write dysymtab command.
-        debug_assert_eq!(dysymtab_command_offset, buffer.len());
-        let dysymtab_command = macho::DysymtabCommand {
-            cmd: U32::new(endian, macho::LC_DYSYMTAB),
-            cmdsize: U32::new(endian, dysymtab_command_len as u32),
-            ilocalsym: U32::new(endian, 0),
-            nlocalsym: U32::new(endian, local_symbols.len() as u32),
-            iextdefsym: U32::new(endian, local_symbols.len() as u32),
-            nextdefsym: U32::new(endian, external_symbols.len() as u32),
-            iundefsym: U32::new(
-                endian,
-                local_symbols.len() as u32 + external_symbols.len() as u32,
-            ),
-            nundefsym: U32::new(endian, undefined_symbols.len() as u32),
-            tocoff: U32::default(),
-            ntoc: U32::default(),
-            modtaboff: U32::default(),
-            nmodtab: U32::default(),
-            extrefsymoff: U32::default(),
-            nextrefsyms: U32::default(),
-            indirectsymoff: U32::default(),
-            nindirectsyms: U32::default(),
-            extreloff: U32::default(),
-            nextrel: U32::default(),
-            locreloff: U32::default(),
-            nlocrel: U32::default(),
-        };
-        buffer.write(&dysymtab_command);
+        let nlocalsym = local_symbols.len() as u32;
+        let nextdefsym = external_symbols.len() as u32;
+        writer.write_dysymtab_command(&writer::Dysymtab {
+            ilocalsym: 0,
+            nlocalsym,
+            iextdefsym: nlocalsym,
+            nextdefsym,
+            iundefsym: nlocalsym + nextdefsym,
+            nundefsym: undefined_symbols.len() as u32,
+        });
 
         // Write section data.
         for (index, section) in self.sections.iter().enumerate() {
             if !section.is_bss() {
-                buffer.resize(section_offsets[index].offset);
-                buffer.write_bytes(&section.data);
+                writer.pad_until(section_offsets[index].offset);
+                writer.write(&section.data);
             }
         }
-        debug_assert_eq!(segment_file_offset + segment_file_size, buffer.len());
+        debug_assert_eq!(segment_file_offset + segment_file_size, writer.len());
 
         // Write relocations.
         for (index, section) in self.sections.iter().enumerate() {
             if !section.relocations.is_empty() {
-                write_align(buffer, pointer_align);
-                debug_assert_eq!(section_offsets[index].reloc_offset, buffer.len());
+                writer.write_align_relocations();
+                debug_assert_eq!(section_offsets[index].reloc_offset, writer.len());
 
                 let mut write_reloc = |reloc: &Relocation| {
                     let (r_type, r_pcrel, r_length) = if let RelocationFlags::MachO {
@@ -736,15 +614,14 @@ impl<'a> Object<'a> {
                             }
                         };
 
-                        let reloc_info = macho::RelocationInfo {
+                        writer.write_relocation(&macho::RelocationInfo {
                             r_address: reloc.offset as u32,
                             r_symbolnum: reloc.addend as u32,
                             r_pcrel: false,
                             r_length,
                             r_extern: false,
                             r_type,
-                        };
-                        buffer.write(&reloc_info.relocation(endian));
+                        });
                     }
 
                     let r_extern;
@@ -754,19 +631,18 @@ impl<'a> Object<'a> {
                         r_symbolnum = section_offsets[symbol.section.id().unwrap().0].index as u32;
                         r_extern = false;
                     } else {
-                        r_symbolnum = symbol_offsets[reloc.symbol.0].index as u32;
+                        r_symbolnum = symbol_offsets[reloc.symbol.0].index;
                         r_extern = true;
                     }
 
-                    let reloc_info = macho::RelocationInfo {
+                    writer.write_relocation(&macho::RelocationInfo {
                         r_address: reloc.offset as u32,
                         r_symbolnum,
                         r_pcrel,
                         r_length,
                         r_extern,
                         r_type,
-                    };
-                    buffer.write(&reloc_info.relocation(endian));
+                    });
                     Ok(())
                 };
 
@@ -795,8 +671,7 @@ impl<'a> Object<'a> {
         }
 
         // Write symtab.
-        write_align(buffer, pointer_align);
-        debug_assert_eq!(symtab_offset, buffer.len());
+        writer.write_align_symtab();
         for index in local_symbols
             .iter()
             .copied()
@@ -846,262 +721,20 @@ impl<'a> Object<'a> {
                 None => symbol.value,
             };
 
-            let n_strx = symbol_offsets[index]
-                .str_id
-                .map(|id| strtab.get_offset(id))
-                .unwrap_or(0);
-
-            macho.write_nlist(
-                buffer,
-                Nlist {
-                    n_strx: n_strx as u32,
-                    n_type,
-                    n_sect: n_sect as u8,
-                    n_desc,
-                    n_value,
-                },
-            );
+            writer.write_nlist(&writer::Nlist {
+                name: symbol_offsets[index].str_id,
+                n_type,
+                n_sect: n_sect as u8,
+                n_desc,
+                n_value,
+            });
         }
 
         // Write strtab.
-        debug_assert_eq!(strtab_offset, buffer.len());
-        buffer.write_bytes(&strtab_data);
-
-        debug_assert_eq!(offset, buffer.len());
+        writer.write_strtab();
 
-        Ok(())
-    }
-}
-
-struct MachHeader {
-    cputype: u32,
-    cpusubtype: u32,
-    filetype: u32,
-    ncmds: u32,
-    sizeofcmds: u32,
-    flags: u32,
-}
-
-struct SegmentCommand {
-    cmdsize: u32,
-    segname: [u8; 16],
-    vmaddr: u64,
-    vmsize: u64,
-    fileoff: u64,
-    filesize: u64,
-    maxprot: u32,
-    initprot: u32,
-    nsects: u32,
-    flags: u32,
-}
-
-pub struct SectionHeader {
-    sectname: [u8; 16],
-    segname: [u8; 16],
-    addr: u64,
-    size: u64,
-    offset: u32,
-    align: u32,
-    reloff: u32,
-    nreloc: u32,
-    flags: u32,
-}
+        debug_assert_eq!(writer.reserved_len(), writer.len());
 
-struct Nlist {
-    n_strx: u32,
-    n_type: u8,
-    n_sect: u8,
-    n_desc: u16,
-    n_value: u64,
-}
-
-trait MachO {
-    fn mach_header_size(&self) -> usize;
-    fn segment_command_size(&self) -> usize;
-    fn section_header_size(&self) -> usize;
-    fn nlist_size(&self) -> usize;
-    fn write_mach_header(&self, buffer: &mut dyn WritableBuffer, section: MachHeader);
-    fn write_segment_command(&self, buffer: &mut dyn WritableBuffer, segment: SegmentCommand);
-    fn write_section(&self, buffer: &mut dyn WritableBuffer, section: SectionHeader);
-    fn write_nlist(&self, buffer: &mut dyn WritableBuffer, nlist: Nlist);
-}
-
-struct MachO32<E> {
-    endian: E,
-}
-
-impl<E: Endian> MachO for MachO32<E> {
-    fn mach_header_size(&self) -> usize {
-        mem::size_of::<macho::MachHeader32<E>>()
-    }
-
-    fn segment_command_size(&self) -> usize {
-        mem::size_of::<macho::SegmentCommand32<E>>()
-    }
-
-    fn section_header_size(&self) -> usize {
-        mem::size_of::<macho::Section32<E>>()
-    }
-
-    fn nlist_size(&self) -> usize {
-        mem::size_of::<macho::Nlist32<E>>()
-    }
-
-    fn write_mach_header(&self, buffer: &mut dyn WritableBuffer, header: MachHeader) {
-        let endian = self.endian;
-        let magic = if endian.is_big_endian() {
-            macho::MH_MAGIC
-        } else {
-            macho::MH_CIGAM
-        };
-        let header = macho::MachHeader32 {
-            magic: U32::new(BigEndian, magic),
-            cputype: U32::new(endian, header.cputype),
-            cpusubtype: U32::new(endian, header.cpusubtype),
-            filetype: U32::new(endian, header.filetype),
-            ncmds: U32::new(endian, header.ncmds),
-            sizeofcmds: U32::new(endian, header.sizeofcmds),
-            flags: U32::new(endian, header.flags),
-        };
-        buffer.write(&header);
-    }
-
-    fn write_segment_command(&self, buffer: &mut dyn WritableBuffer, segment: SegmentCommand) {
-        let endian = self.endian;
-        let segment = macho::SegmentCommand32 {
-            cmd: U32::new(endian, macho::LC_SEGMENT),
-            cmdsize: U32::new(endian, segment.cmdsize),
-            segname: segment.segname,
-            vmaddr: U32::new(endian, segment.vmaddr as u32),
-            vmsize: U32::new(endian, segment.vmsize as u32),
-            fileoff: U32::new(endian, segment.fileoff as u32),
-            filesize: U32::new(endian, segment.filesize as u32),
-            maxprot: U32::new(endian, segment.maxprot),
-            initprot: U32::new(endian, segment.initprot),
-            nsects: U32::new(endian, segment.nsects),
-            flags: U32::new(endian, segment.flags),
-        };
-        buffer.write(&segment);
-    }
-
-    fn write_section(&self, buffer: &mut dyn WritableBuffer, section: SectionHeader) {
-        let endian = self.endian;
-        let section = macho::Section32 {
-            sectname: section.sectname,
-            segname: section.segname,
-            addr: U32::new(endian, section.addr as u32),
-            size: U32::new(endian, section.size as u32),
-            offset: U32::new(endian, section.offset),
-            align: U32::new(endian, section.align),
-            reloff: U32::new(endian, section.reloff),
-            nreloc: U32::new(endian, section.nreloc),
-            flags: U32::new(endian, section.flags),
-            reserved1: U32::default(),
-            reserved2: U32::default(),
-        };
-        buffer.write(&section);
-    }
-
-    fn write_nlist(&self, buffer: &mut dyn WritableBuffer, nlist: Nlist) {
-        let endian = self.endian;
-        let nlist = macho::Nlist32 {
-            n_strx: U32::new(endian, nlist.n_strx),
-            n_type: nlist.n_type,
-            n_sect: nlist.n_sect,
-            n_desc: U16::new(endian, nlist.n_desc),
-            n_value: U32::new(endian, nlist.n_value as u32),
-        };
-        buffer.write(&nlist);
-    }
-}
-
-struct MachO64<E> {
-    endian: E,
-}
-
-impl<E: Endian> MachO for MachO64<E> {
-    fn mach_header_size(&self) -> usize {
-        mem::size_of::<macho::MachHeader64<E>>()
-    }
-
-    fn segment_command_size(&self) -> usize {
-        mem::size_of::<macho::SegmentCommand64<E>>()
-    }
-
-    fn section_header_size(&self) -> usize {
-        mem::size_of::<macho::Section64<E>>()
-    }
-
-    fn nlist_size(&self) -> usize {
-        mem::size_of::<macho::Nlist64<E>>()
-    }
-
-    fn write_mach_header(&self, buffer: &mut dyn WritableBuffer, header: MachHeader) {
-        let endian = self.endian;
-        let magic = if endian.is_big_endian() {
-            macho::MH_MAGIC_64
-        } else {
-            macho::MH_CIGAM_64
-        };
-        let header = macho::MachHeader64 {
-            magic: U32::new(BigEndian, magic),
-            cputype: U32::new(endian, header.cputype),
-            cpusubtype: U32::new(endian, header.cpusubtype),
-            filetype: U32::new(endian, header.filetype),
-            ncmds: U32::new(endian, header.ncmds),
-            sizeofcmds: U32::new(endian, header.sizeofcmds),
-            flags: U32::new(endian, header.flags),
-            reserved: U32::default(),
-        };
-        buffer.write(&header);
-    }
-
-    fn write_segment_command(&self, buffer: &mut dyn WritableBuffer, segment: SegmentCommand) {
-        let endian = self.endian;
-        let segment = macho::SegmentCommand64 {
-            cmd: U32::new(endian, macho::LC_SEGMENT_64),
-            cmdsize: U32::new(endian, segment.cmdsize),
-            segname: segment.segname,
-            vmaddr: U64::new(endian, segment.vmaddr),
-            vmsize: U64::new(endian, segment.vmsize),
-            fileoff: U64::new(endian, segment.fileoff),
-            filesize: U64::new(endian, segment.filesize),
-            maxprot: U32::new(endian, segment.maxprot),
-            initprot: U32::new(endian, segment.initprot),
-            nsects: U32::new(endian, segment.nsects),
-            flags: U32::new(endian, segment.flags),
-        };
-        buffer.write(&segment);
-    }
-
-    fn write_section(&self, buffer: &mut dyn WritableBuffer, section: SectionHeader) {
-        let endian = self.endian;
-        let section = macho::Section64 {
-            sectname: section.sectname,
-            segname: section.segname,
-            addr: U64::new(endian, section.addr),
-            size: U64::new(endian, section.size),
-            offset: U32::new(endian, section.offset),
-            align: U32::new(endian, section.align),
-            reloff: U32::new(endian, section.reloff),
-            nreloc: U32::new(endian, section.nreloc),
-            flags: U32::new(endian, section.flags),
-            reserved1: U32::default(),
-            reserved2: U32::default(),
-            reserved3: U32::default(),
-        };
-        buffer.write(&section);
-    }
-
-    fn write_nlist(&self, buffer: &mut dyn WritableBuffer, nlist: Nlist) {
-        let endian = self.endian;
-        let nlist = macho::Nlist64 {
-            n_strx: U32::new(endian, nlist.n_strx),
-            n_type: nlist.n_type,
-            n_sect: nlist.n_sect,
-            n_desc: U16::new(endian, nlist.n_desc),
-            n_value: U64Bytes::new(endian, nlist.n_value),
-        };
-        buffer.write(&nlist);
+        Ok(SymbolIndices(symbol_indices))
     }
 }
@@ -0,0 +1,208 @@
+//! Helper for writing ad hoc code signatures.
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::mem;
+
+use crate::endian::{BigEndian as BE, U32, U64};
+use crate::macho;
+use crate::pod::bytes_of;
+use crate::write::{util, Error, Result};
+
+/// The log2 of the page size used for code signature hashes.
+const PAGE_SIZE_SHIFT: u8 = 12;
+const PAGE_SIZE: usize = 1 << PAGE_SIZE_SHIFT;
+
+/// The alignment of the code signature within the file.
+pub(crate) const CODE_SIGNATURE_ALIGN: usize = 16;
+
+/// An ad hoc code signature.
+///
+/// This is the same form of signature that linkers create for executables and dylibs
+/// on Apple Silicon, which will not run without a signature. It contains a single
+/// code directory with a SHA-256 hash of each page of the file.
+///
+/// The file range for the signature is reserved by
+/// [`Writer::reserve_code_signature`](super::Writer::reserve_code_signature),
+/// and the signature is calculated by [`Self::sign`] after the rest of the file
+/// has been written.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CodeSignature<'a> {
+    /// The identifier of the signed code, typically the file name.
+    pub identifier: &'a [u8],
+    /// The file offset of the executable segment (usually `__TEXT`).
+    pub exec_seg_base: u64,
+    /// The file size of the executable segment.
+    pub exec_seg_limit: u64,
+    /// The executable segment flags.
+    ///
+    /// This should be `CS_EXECSEG_MAIN_BINARY` for executables, and 0 otherwise.
+    pub exec_seg_flags: u64,
+}
+
+impl<'a> CodeSignature<'a> {
+    /// Return the offset of the hashes within the code directory.
+    fn hash_offset(&self) -> usize {
+        mem::size_of::<macho::CsCodeDirectory>() + self.identifier.len() + 1
+    }
+
+    /// Return the size of the signature for a file with the given code limit.
+    ///
+    /// The code limit is the offset of the signature within the file.
+    /// The returned size includes padding to the required alignment.
+    pub fn size(&self, code_limit: usize) -> usize {
+        let code_slots = (code_limit + PAGE_SIZE - 1) / PAGE_SIZE;
+        let len = mem::size_of::<macho::CsSuperBlob>()
+            + mem::size_of::<macho::CsBlobIndex>()
+            + self.hash_offset()
+            + code_slots * usize::from(macho::CS_SHA256_LEN);
+        util::align(len, CODE_SIGNATURE_ALIGN)
+    }
+
+    /// Calculate the signature and store it in the file.
+    ///
+    /// `file` must contain the complete file, with the signature at `code_limit`.
+    /// All data prior to `code_limit` is hashed.
+    pub fn sign(&self, file: &mut [u8], code_limit: usize) -> Result<()> {
+        let size = self.size(code_limit);
+        if file.len() < code_limit || file.len() - code_limit < size {
+            return Err(Error(String::from("file is too small for code signature")));
+        }
+        let code_limit32 = u32::try_from(code_limit)
+            .map_err(|_| Error(String::from("code limit is too large for code signature")))?;
+        let (code, signature) = file.split_at_mut(code_limit);
+        let signature = &mut signature[..size];
+
+        let hash_len = usize::from(macho::CS_SHA256_LEN);
+        let code_slots = (code_limit + PAGE_SIZE - 1) / PAGE_SIZE;
+        let hash_offset = self.hash_offset();
+        let directory_offset =
+            mem::size_of::<macho::CsSuperBlob>() + mem::size_of::<macho::CsBlobIndex>();
+        let directory_len = hash_offset + code_slots * hash_len;
+
+        let mut offset = 0;
+        let mut write = |data: &[u8]| {
+            signature[offset..][..data.len()].copy_from_slice(data);
+            offset += data.len();
+        };
+        write(bytes_of(&macho::CsSuperBlob {
+            magic: U32::new(BE, macho::CSMAGIC_EMBEDDED_SIGNATURE),
+            length: U32::new(BE, (directory_offset + directory_len) as u32),
+            count: U32::new(BE, 1),
+        }));
+        write(bytes_of(&macho::CsBlobIndex {
+            typ: U32::new(BE, macho::CSSLOT_CODEDIRECTORY),
+            offset: U32::new(BE, directory_offset as u32),
+        }));
+        write(bytes_of(&macho::CsCodeDirectory {
+            magic: U32::new(BE, macho::CSMAGIC_CODEDIRECTORY),
+            length: U32::new(BE, directory_len as u32),
+            version: U32::new(BE, macho::CS_SUPPORTSEXECSEG),
+            flags: U32::new(BE, macho::CS_ADHOC | macho::CS_LINKER_SIGNED),
+            hash_offset: U32::new(BE, hash_offset as u32),
+            ident_offset: U32::new(BE, mem::size_of::<macho::CsCodeDirectory>() as u32),
+            n_special_slots: U32::new(BE, 0),
+            n_code_slots: U32::new(BE, code_slots as u32),
+            code_limit: U32::new(BE, code_limit32),
+            hash_size: macho::CS_SHA256_LEN,
+            hash_type: macho::CS_HASHTYPE_SHA256,
+            platform: 0,
+            page_size: PAGE_SIZE_SHIFT,
+            spare2: U32::default(),
+            scatter_offset: U32::default(),
+            team_offset: U32::default(),
+            spare3: U32::default(),
+            code_limit64: U64::default(),
+            exec_seg_base: U64::new(BE, self.exec_seg_base),
+            exec_seg_limit: U64::new(BE, self.exec_seg_limit),
+            exec_seg_flags: U64::new(BE, self.exec_seg_flags),
+        }));
+        write(self.identifier);
+        write(&[0]);
+        for page in code.chunks(PAGE_SIZE) {
+            write(&sha256(page));
+        }
+        // Clear any padding.
+        signature[offset..].fill(0);
+        Ok(())
+    }
+}
+
+/// Calculate the SHA-256 hash of the given data.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let mut compress = |block: &[u8]| {
+        let mut w = [0u32; 64];
+        for (w, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (s, x) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(x);
+        }
+    };
+
+    let mut blocks = data.chunks_exact(64);
+    for block in &mut blocks {
+        compress(block);
+    }
+    let remainder = blocks.remainder();
+    let mut last = [0u8; 128];
+    last[..remainder.len()].copy_from_slice(remainder);
+    last[remainder.len()] = 0x80;
+    let last_len = if remainder.len() < 56 { 64 } else { 128 };
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    last[last_len - 8..last_len].copy_from_slice(&bit_len.to_be_bytes());
+    for block in last[..last_len].chunks_exact(64) {
+        compress(block);
+    }
+
+    let mut hash = [0; 32];
+    for (bytes, s) in hash.chunks_exact_mut(4).zip(state.iter()) {
+        bytes.copy_from_slice(&s.to_be_bytes());
+    }
+    hash
+}
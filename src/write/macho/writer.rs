@@ -0,0 +1,685 @@
+//! Helper for writing Mach-O files.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::endian::*;
+use crate::macho;
+use crate::write::macho::signature::{CodeSignature, CODE_SIGNATURE_ALIGN};
+use crate::write::string::{StringId, StringTable};
+use crate::write::util;
+use crate::write::{Error, Result, WritableBuffer};
+
+/// A helper for writing Mach-O files.
+///
+/// Writing uses a two phase approach. The first phase builds up all of the information
+/// that may need to be known ahead of time:
+/// - build string table
+/// - reserve symbol indices
+/// - reserve load commands
+/// - reserve file ranges for headers, segments and symbol tables
+///
+/// Some of the information has ordering requirements. For example, strings must be added
+/// to the string table before reserving the file range for the string table. Load commands
+/// must be reserved immediately after the Mach-O header. There are debug asserts to check
+/// some of these requirements.
+///
+/// The second phase writes everything out in order. Thus the caller must ensure writing
+/// is in the same order that file ranges were reserved. There are debug asserts to assist
+/// with checking this.
+#[allow(missing_debug_implementations)]
+pub struct Writer<'a> {
+    endian: Endianness,
+    is_64: bool,
+    pointer_align: usize,
+
+    buffer: &'a mut dyn WritableBuffer,
+    len: usize,
+
+    command_offset: usize,
+    command_num: u32,
+    command_size: usize,
+
+    symtab_offset: usize,
+    symtab_num: u32,
+
+    strtab: StringTable<'a>,
+    strtab_offset: usize,
+    strtab_data: Vec<u8>,
+
+    indirect_symtab_offset: usize,
+    indirect_symtab_num: u32,
+
+    code_signature_offset: usize,
+    code_signature_size: usize,
+}
+
+impl<'a> Writer<'a> {
+    /// Create a new `Writer` for the given endianness and address size.
+    pub fn new(endian: Endianness, is_64: bool, buffer: &'a mut dyn WritableBuffer) -> Self {
+        let pointer_align = if is_64 { 8 } else { 4 };
+        Writer {
+            endian,
+            is_64,
+            pointer_align,
+
+            buffer,
+            len: 0,
+
+            command_offset: 0,
+            command_num: 0,
+            command_size: 0,
+
+            symtab_offset: 0,
+            symtab_num: 0,
+
+            strtab: StringTable::default(),
+            strtab_offset: 0,
+            strtab_data: Vec::new(),
+
+            indirect_symtab_offset: 0,
+            indirect_symtab_num: 0,
+
+            code_signature_offset: 0,
+            code_signature_size: 0,
+        }
+    }
+
+    /// Return the current file length that has been reserved.
+    pub fn reserved_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the current file length that has been written.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Reserve a file range with the given size and starting alignment.
+    ///
+    /// Returns the aligned offset of the start of the range.
+    ///
+    /// `align_start` must be a power of two.
+    pub fn reserve(&mut self, len: usize, align_start: usize) -> usize {
+        if align_start > 1 {
+            self.len = util::align(self.len, align_start);
+        }
+        let offset = self.len;
+        self.len += len;
+        offset
+    }
+
+    /// Write alignment padding bytes.
+    pub fn write_align(&mut self, align_start: usize) {
+        if align_start > 1 {
+            util::write_align(self.buffer, align_start);
+        }
+    }
+
+    /// Write data.
+    ///
+    /// This is typically used to write section data.
+    pub fn write(&mut self, data: &[u8]) {
+        self.buffer.write_bytes(data);
+    }
+
+    /// Reserve the file range up to the given file offset.
+    pub fn reserve_until(&mut self, offset: usize) {
+        debug_assert!(self.len <= offset);
+        self.len = offset;
+    }
+
+    /// Write padding up to the given file offset.
+    pub fn pad_until(&mut self, offset: usize) {
+        let len = self.buffer.len();
+        debug_assert!(len <= offset);
+        self.buffer.write_zeros(offset.saturating_sub(len));
+    }
+
+    /// Reserve the range for the Mach-O header.
+    ///
+    /// This must be at the start of the file.
+    pub fn reserve_mach_header(&mut self) {
+        debug_assert_eq!(self.len, 0);
+        let size = if self.is_64 {
+            mem::size_of::<macho::MachHeader64<Endianness>>()
+        } else {
+            mem::size_of::<macho::MachHeader32<Endianness>>()
+        };
+        self.reserve(size, 1);
+        self.command_offset = self.len;
+    }
+
+    /// Write the Mach-O header.
+    ///
+    /// This must be at the start of the file.
+    ///
+    /// Fields that can be derived from known information are automatically set by this function.
+    pub fn write_mach_header(&mut self, header: &MachHeader) -> Result<()> {
+        debug_assert_eq!(self.buffer.len(), 0);
+
+        // Start writing.
+        self.buffer
+            .reserve(self.len)
+            .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+
+        let endian = self.endian;
+        if self.is_64 {
+            let magic = if endian.is_big_endian() {
+                macho::MH_MAGIC_64
+            } else {
+                macho::MH_CIGAM_64
+            };
+            let header = macho::MachHeader64 {
+                magic: U32::new(BigEndian, magic),
+                cputype: U32::new(endian, header.cputype),
+                cpusubtype: U32::new(endian, header.cpusubtype),
+                filetype: U32::new(endian, header.filetype),
+                ncmds: U32::new(endian, self.command_num),
+                sizeofcmds: U32::new(endian, self.command_size as u32),
+                flags: U32::new(endian, header.flags),
+                reserved: U32::default(),
+            };
+            self.buffer.write(&header);
+        } else {
+            let magic = if endian.is_big_endian() {
+                macho::MH_MAGIC
+            } else {
+                macho::MH_CIGAM
+            };
+            let header = macho::MachHeader32 {
+                magic: U32::new(BigEndian, magic),
+                cputype: U32::new(endian, header.cputype),
+                cpusubtype: U32::new(endian, header.cpusubtype),
+                filetype: U32::new(endian, header.filetype),
+                ncmds: U32::new(endian, self.command_num),
+                sizeofcmds: U32::new(endian, self.command_size as u32),
+                flags: U32::new(endian, header.flags),
+            };
+            self.buffer.write(&header);
+        }
+
+        Ok(())
+    }
+
+    /// Reserve the range for a load command.
+    ///
+    /// This is used by the functions that reserve specific load commands, but can also
+    /// be used for load commands that this writer does not otherwise support.
+    /// The command must then be written using [`Self::write`].
+    ///
+    /// Load commands must be reserved immediately after [`Self::reserve_mach_header`],
+    /// and before any other file ranges.
+    ///
+    /// Returns the offset of the command.
+    pub fn reserve_load_command(&mut self, cmdsize: usize) -> usize {
+        debug_assert_ne!(self.command_offset, 0);
+        debug_assert_eq!(self.len, self.command_offset + self.command_size);
+        debug_assert_eq!(cmdsize % self.pointer_align, 0);
+        self.command_num += 1;
+        self.command_size += cmdsize;
+        self.reserve(cmdsize, 1)
+    }
+
+    /// Return the size of a segment load command, including its section headers.
+    fn segment_command_size(&self, nsects: u32) -> usize {
+        let (segment_size, section_size) = if self.is_64 {
+            (
+                mem::size_of::<macho::SegmentCommand64<Endianness>>(),
+                mem::size_of::<macho::Section64<Endianness>>(),
+            )
+        } else {
+            (
+                mem::size_of::<macho::SegmentCommand32<Endianness>>(),
+                mem::size_of::<macho::Section32<Endianness>>(),
+            )
+        };
+        segment_size + nsects as usize * section_size
+    }
+
+    /// Reserve the range for a segment load command and its section headers.
+    ///
+    /// Returns the offset of the command.
+    pub fn reserve_segment_command(&mut self, nsects: u32) -> usize {
+        self.reserve_load_command(self.segment_command_size(nsects))
+    }
+
+    /// Write a segment load command.
+    ///
+    /// This must be followed by `segment.nsects` calls to [`Self::write_section_header`].
+    pub fn write_segment_command(&mut self, segment: &SegmentCommand) {
+        let endian = self.endian;
+        let cmdsize = self.segment_command_size(segment.nsects) as u32;
+        if self.is_64 {
+            let segment = macho::SegmentCommand64 {
+                cmd: U32::new(endian, macho::LC_SEGMENT_64),
+                cmdsize: U32::new(endian, cmdsize),
+                segname: segment.segname,
+                vmaddr: U64::new(endian, segment.vmaddr),
+                vmsize: U64::new(endian, segment.vmsize),
+                fileoff: U64::new(endian, segment.fileoff),
+                filesize: U64::new(endian, segment.filesize),
+                maxprot: U32::new(endian, segment.maxprot),
+                initprot: U32::new(endian, segment.initprot),
+                nsects: U32::new(endian, segment.nsects),
+                flags: U32::new(endian, segment.flags),
+            };
+            self.buffer.write(&segment);
+        } else {
+            let segment = macho::SegmentCommand32 {
+                cmd: U32::new(endian, macho::LC_SEGMENT),
+                cmdsize: U32::new(endian, cmdsize),
+                segname: segment.segname,
+                vmaddr: U32::new(endian, segment.vmaddr as u32),
+                vmsize: U32::new(endian, segment.vmsize as u32),
+                fileoff: U32::new(endian, segment.fileoff as u32),
+                filesize: U32::new(endian, segment.filesize as u32),
+                maxprot: U32::new(endian, segment.maxprot),
+                initprot: U32::new(endian, segment.initprot),
+                nsects: U32::new(endian, segment.nsects),
+                flags: U32::new(endian, segment.flags),
+            };
+            self.buffer.write(&segment);
+        }
+    }
+
+    /// Write a section header.
+    ///
+    /// Section headers must immediately follow the segment load command
+    /// that contains them.
+    pub fn write_section_header(&mut self, section: &SectionHeader) {
+        let endian = self.endian;
+        if self.is_64 {
+            let section = macho::Section64 {
+                sectname: section.sectname,
+                segname: section.segname,
+                addr: U64::new(endian, section.addr),
+                size: U64::new(endian, section.size),
+                offset: U32::new(endian, section.offset),
+                align: U32::new(endian, section.align),
+                reloff: U32::new(endian, section.reloff),
+                nreloc: U32::new(endian, section.nreloc),
+                flags: U32::new(endian, section.flags),
+                reserved1: U32::new(endian, section.reserved1),
+                reserved2: U32::new(endian, section.reserved2),
+                reserved3: U32::default(),
+            };
+            self.buffer.write(&section);
+        } else {
+            let section = macho::Section32 {
+                sectname: section.sectname,
+                segname: section.segname,
+                addr: U32::new(endian, section.addr as u32),
+                size: U32::new(endian, section.size as u32),
+                offset: U32::new(endian, section.offset),
+                align: U32::new(endian, section.align),
+                reloff: U32::new(endian, section.reloff),
+                nreloc: U32::new(endian, section.nreloc),
+                flags: U32::new(endian, section.flags),
+                reserved1: U32::new(endian, section.reserved1),
+                reserved2: U32::new(endian, section.reserved2),
+            };
+            self.buffer.write(&section);
+        }
+    }
+
+    /// Reserve the range for a `LC_BUILD_VERSION` load command.
+    ///
+    /// Returns the offset of the command.
+    pub fn reserve_build_version_command(&mut self) -> usize {
+        self.reserve_load_command(mem::size_of::<macho::BuildVersionCommand<Endianness>>())
+    }
+
+    /// Write a `LC_BUILD_VERSION` load command.
+    pub fn write_build_version_command(&mut self, version: &MachOBuildVersion) {
+        let endian = self.endian;
+        self.buffer.write(&macho::BuildVersionCommand {
+            cmd: U32::new(endian, macho::LC_BUILD_VERSION),
+            cmdsize: U32::new(
+                endian,
+                mem::size_of::<macho::BuildVersionCommand<Endianness>>() as u32,
+            ),
+            platform: U32::new(endian, version.platform),
+            minos: U32::new(endian, version.minos),
+            sdk: U32::new(endian, version.sdk),
+            ntools: U32::new(endian, 0),
+        });
+    }
+
+    /// Reserve the range for a `LC_SYMTAB` load command.
+    ///
+    /// Returns the offset of the command.
+    pub fn reserve_symtab_command(&mut self) -> usize {
+        self.reserve_load_command(mem::size_of::<macho::SymtabCommand<Endianness>>())
+    }
+
+    /// Write a `LC_SYMTAB` load command.
+    ///
+    /// The symbol table and string table ranges must have been reserved.
+    pub fn write_symtab_command(&mut self) {
+        let endian = self.endian;
+        self.buffer.write(&macho::SymtabCommand {
+            cmd: U32::new(endian, macho::LC_SYMTAB),
+            cmdsize: U32::new(
+                endian,
+                mem::size_of::<macho::SymtabCommand<Endianness>>() as u32,
+            ),
+            symoff: U32::new(endian, self.symtab_offset as u32),
+            nsyms: U32::new(endian, self.symtab_num),
+            stroff: U32::new(endian, self.strtab_offset as u32),
+            strsize: U32::new(endian, self.strtab_data.len() as u32),
+        });
+    }
+
+    /// Reserve the range for a `LC_DYSYMTAB` load command.
+    ///
+    /// Returns the offset of the command.
+    pub fn reserve_dysymtab_command(&mut self) -> usize {
+        self.reserve_load_command(mem::size_of::<macho::DysymtabCommand<Endianness>>())
+    }
+
+    /// Write a `LC_DYSYMTAB` load command.
+    ///
+    /// The range of the indirect symbol table is automatically set if it was reserved.
+    pub fn write_dysymtab_command(&mut self, dysymtab: &Dysymtab) {
+        let endian = self.endian;
+        self.buffer.write(&macho::DysymtabCommand {
+            cmd: U32::new(endian, macho::LC_DYSYMTAB),
+            cmdsize: U32::new(
+                endian,
+                mem::size_of::<macho::DysymtabCommand<Endianness>>() as u32,
+            ),
+            ilocalsym: U32::new(endian, dysymtab.ilocalsym),
+            nlocalsym: U32::new(endian, dysymtab.nlocalsym),
+            iextdefsym: U32::new(endian, dysymtab.iextdefsym),
+            nextdefsym: U32::new(endian, dysymtab.nextdefsym),
+            iundefsym: U32::new(endian, dysymtab.iundefsym),
+            nundefsym: U32::new(endian, dysymtab.nundefsym),
+            tocoff: U32::default(),
+            ntoc: U32::default(),
+            modtaboff: U32::default(),
+            nmodtab: U32::default(),
+            extrefsymoff: U32::default(),
+            nextrefsyms: U32::default(),
+            indirectsymoff: U32::new(endian, self.indirect_symtab_offset as u32),
+            nindirectsyms: U32::new(endian, self.indirect_symtab_num),
+            extreloff: U32::default(),
+            nextrel: U32::default(),
+            locreloff: U32::default(),
+            nlocrel: U32::default(),
+        });
+    }
+
+    /// Reserve a file range for the given number of relocations.
+    ///
+    /// Returns the offset of the range.
+    /// Does nothing and returns 0 if the count is zero.
+    pub fn reserve_relocations(&mut self, count: usize) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        self.reserve(
+            count * mem::size_of::<macho::Relocation<Endianness>>(),
+            self.pointer_align,
+        )
+    }
+
+    /// Write alignment padding bytes prior to the relocations for a section.
+    pub fn write_align_relocations(&mut self) {
+        util::write_align(self.buffer, self.pointer_align);
+    }
+
+    /// Write a relocation.
+    pub fn write_relocation(&mut self, reloc: &macho::RelocationInfo) {
+        self.buffer.write(&reloc.relocation(self.endian));
+    }
+
+    /// Reserve a symbol table entry.
+    ///
+    /// Callers may assume that the returned indices will be sequential
+    /// starting at 0.
+    ///
+    /// This must be called before [`Self::reserve_symtab`].
+    pub fn reserve_symbol_index(&mut self) -> u32 {
+        debug_assert_eq!(self.symtab_offset, 0);
+        let index = self.symtab_num;
+        self.symtab_num += 1;
+        index
+    }
+
+    /// Return the number of reserved symbol table entries.
+    pub fn symbol_count(&self) -> u32 {
+        self.symtab_num
+    }
+
+    /// Reserve the range for the symbol table.
+    ///
+    /// This must be called after [`Self::reserve_symbol_index`].
+    pub fn reserve_symtab(&mut self) {
+        debug_assert_eq!(self.symtab_offset, 0);
+        let nlist_size = if self.is_64 {
+            mem::size_of::<macho::Nlist64<Endianness>>()
+        } else {
+            mem::size_of::<macho::Nlist32<Endianness>>()
+        };
+        self.symtab_offset =
+            self.reserve(self.symtab_num as usize * nlist_size, self.pointer_align);
+    }
+
+    /// Write alignment padding bytes prior to the symbol table.
+    pub fn write_align_symtab(&mut self) {
+        util::write_align(self.buffer, self.pointer_align);
+        debug_assert_eq!(self.symtab_offset, self.buffer.len());
+    }
+
+    /// Write a symbol table entry.
+    pub fn write_nlist(&mut self, nlist: &Nlist) {
+        let n_strx = if let Some(name) = nlist.name {
+            self.strtab.get_offset(name) as u32
+        } else {
+            0
+        };
+        let endian = self.endian;
+        if self.is_64 {
+            let nlist = macho::Nlist64 {
+                n_strx: U32::new(endian, n_strx),
+                n_type: nlist.n_type,
+                n_sect: nlist.n_sect,
+                n_desc: U16::new(endian, nlist.n_desc),
+                n_value: U64Bytes::new(endian, nlist.n_value),
+            };
+            self.buffer.write(&nlist);
+        } else {
+            let nlist = macho::Nlist32 {
+                n_strx: U32::new(endian, n_strx),
+                n_type: nlist.n_type,
+                n_sect: nlist.n_sect,
+                n_desc: U16::new(endian, nlist.n_desc),
+                n_value: U32::new(endian, nlist.n_value as u32),
+            };
+            self.buffer.write(&nlist);
+        }
+    }
+
+    /// Add a string to the string table.
+    ///
+    /// This must be called before [`Self::reserve_strtab`].
+    pub fn add_string(&mut self, name: &'a [u8]) -> StringId {
+        debug_assert_eq!(self.strtab_offset, 0);
+        self.strtab.add(name)
+    }
+
+    /// Reserve the range for the string table.
+    ///
+    /// The string table is padded to the pointer alignment.
+    ///
+    /// This must be called after [`Self::add_string`].
+    pub fn reserve_strtab(&mut self) {
+        debug_assert_eq!(self.strtab_offset, 0);
+        // Start with null name.
+        self.strtab_data = vec![0];
+        self.strtab.write(1, &mut self.strtab_data);
+        util::write_align(&mut self.strtab_data, self.pointer_align);
+        self.strtab_offset = self.reserve(self.strtab_data.len(), 1);
+    }
+
+    /// Write the string table.
+    pub fn write_strtab(&mut self) {
+        debug_assert_eq!(self.strtab_offset, self.buffer.len());
+        self.buffer.write_bytes(&self.strtab_data);
+    }
+
+    /// Reserve the range for the indirect symbol table.
+    ///
+    /// Each entry is the index of a symbol in the symbol table,
+    /// or one of `INDIRECT_SYMBOL_LOCAL` and `INDIRECT_SYMBOL_ABS`.
+    ///
+    /// Returns the offset of the range.
+    /// Does nothing and returns 0 if the count is zero.
+    pub fn reserve_indirect_symtab(&mut self, count: u32) -> usize {
+        debug_assert_eq!(self.indirect_symtab_offset, 0);
+        if count == 0 {
+            return 0;
+        }
+        self.indirect_symtab_num = count;
+        self.indirect_symtab_offset = self.reserve(count as usize * 4, 4);
+        self.indirect_symtab_offset
+    }
+
+    /// Reserve the range for a `LC_CODE_SIGNATURE` load command.
+    ///
+    /// Returns the offset of the command.
+    pub fn reserve_code_signature_command(&mut self) -> usize {
+        self.reserve_load_command(mem::size_of::<macho::LinkeditDataCommand<Endianness>>())
+    }
+
+    /// Write a `LC_CODE_SIGNATURE` load command.
+    ///
+    /// The range of the code signature must have been reserved.
+    pub fn write_code_signature_command(&mut self) {
+        let endian = self.endian;
+        self.buffer.write(&macho::LinkeditDataCommand {
+            cmd: U32::new(endian, macho::LC_CODE_SIGNATURE),
+            cmdsize: U32::new(
+                endian,
+                mem::size_of::<macho::LinkeditDataCommand<Endianness>>() as u32,
+            ),
+            dataoff: U32::new(endian, self.code_signature_offset as u32),
+            datasize: U32::new(endian, self.code_signature_size as u32),
+        });
+    }
+
+    /// Reserve the range for a code signature.
+    ///
+    /// This must be the last range that is reserved, since the signature
+    /// covers all of the file prior to it. It is usually the last data in
+    /// the `__LINKEDIT` segment.
+    ///
+    /// Returns the offset of the range.
+    pub fn reserve_code_signature(&mut self, signature: &CodeSignature<'_>) -> usize {
+        debug_assert_eq!(self.code_signature_offset, 0);
+        let offset = util::align(self.len, CODE_SIGNATURE_ALIGN);
+        self.code_signature_size = signature.size(offset);
+        self.code_signature_offset = self.reserve(self.code_signature_size, CODE_SIGNATURE_ALIGN);
+        self.code_signature_offset
+    }
+
+    /// Write the range for a code signature.
+    ///
+    /// This writes zero bytes for the signature. The contents must be
+    /// calculated using [`CodeSignature::sign`] after the file is written.
+    pub fn write_code_signature(&mut self) {
+        util::write_align(self.buffer, CODE_SIGNATURE_ALIGN);
+        debug_assert_eq!(self.code_signature_offset, self.buffer.len());
+        self.buffer.write_zeros(self.code_signature_size);
+    }
+
+    /// Write an entry in the indirect symbol table.
+    ///
+    /// Writes alignment bytes prior to the first entry.
+    pub fn write_indirect_symbol(&mut self, index: u32) {
+        util::write_align(self.buffer, 4);
+        self.buffer.write(&U32::new(self.endian, index));
+    }
+}
+
+/// Native endian version of [`macho::MachHeader64`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone)]
+pub struct MachHeader {
+    pub cputype: u32,
+    pub cpusubtype: u32,
+    pub filetype: u32,
+    pub flags: u32,
+}
+
+/// Native endian version of [`macho::SegmentCommand64`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone)]
+pub struct SegmentCommand {
+    pub segname: [u8; 16],
+    pub vmaddr: u64,
+    pub vmsize: u64,
+    pub fileoff: u64,
+    pub filesize: u64,
+    pub maxprot: u32,
+    pub initprot: u32,
+    pub nsects: u32,
+    pub flags: u32,
+}
+
+/// Native endian version of [`macho::Section64`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone)]
+pub struct SectionHeader {
+    pub sectname: [u8; 16],
+    pub segname: [u8; 16],
+    pub addr: u64,
+    pub size: u64,
+    pub offset: u32,
+    pub align: u32,
+    pub reloff: u32,
+    pub nreloc: u32,
+    pub flags: u32,
+    pub reserved1: u32,
+    pub reserved2: u32,
+}
+
+/// The symbol ranges in a [`macho::DysymtabCommand`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone)]
+pub struct Dysymtab {
+    pub ilocalsym: u32,
+    pub nlocalsym: u32,
+    pub iextdefsym: u32,
+    pub nextdefsym: u32,
+    pub iundefsym: u32,
+    pub nundefsym: u32,
+}
+
+/// Native endian version of [`macho::Nlist64`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone)]
+pub struct Nlist {
+    pub name: Option<StringId>,
+    pub n_type: u8,
+    pub n_sect: u8,
+    pub n_desc: u16,
+    pub n_value: u64,
+}
+
+/// The customizable portion of a [`macho::BuildVersionCommand`].
+#[derive(Debug, Default, Clone, Copy)]
+#[non_exhaustive] // May want to add the tool list?
+pub struct MachOBuildVersion {
+    /// One of the `PLATFORM_` constants (for example,
+    /// [`object::macho::PLATFORM_MACOS`](macho::PLATFORM_MACOS)).
+    pub platform: u32,
+    /// The minimum OS version, where `X.Y.Z` is encoded in nibbles as
+    /// `xxxx.yy.zz`.
+    pub minos: u32,
+    /// The SDK version as `X.Y.Z`, where `X.Y.Z` is encoded in nibbles as
+    /// `xxxx.yy.zz`.
+    pub sdk: u32,
+}
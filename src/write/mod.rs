@@ -7,11 +7,12 @@
 //! The submodules define helpers for writing the raw structs. These support
 //! writing both relocatable and executable files. There are writers for
 //! the following file formats: [COFF](coff::Writer), [ELF](elf::Writer),
-//! and [PE](pe::Writer).
+//! [Mach-O](macho::Writer), and [PE](pe::Writer).
 
 use alloc::borrow::Cow;
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash, Hasher};
 use core::{fmt, result, str};
 #[cfg(not(feature = "std"))]
 use hashbrown::HashMap;
@@ -31,7 +32,7 @@ pub use coff::CoffExportStyle;
 pub mod elf;
 
 #[cfg(feature = "macho")]
-mod macho;
+pub mod macho;
 #[cfg(feature = "macho")]
 pub use macho::MachOBuildVersion;
 
@@ -76,14 +77,19 @@ pub struct Object<'a> {
     sections: Vec<Section<'a>>,
     standard_sections: HashMap<StandardSection, SectionId>,
     symbols: Vec<Symbol>,
-    symbol_map: HashMap<Vec<u8>, SymbolId>,
+    symbol_map: SymbolMap,
     comdats: Vec<Comdat>,
+    common_symbol_mode: CommonSymbolMode,
+    symbol_order: SymbolOrder,
     /// File flags that are specific to each file format.
     pub flags: FileFlags,
     /// The symbol name mangling scheme.
     pub mangling: Mangling,
     #[cfg(feature = "coff")]
     stub_symbols: HashMap<SymbolId, SymbolId>,
+    /// COFF bigobj format.
+    #[cfg(feature = "coff")]
+    coff_bigobj: bool,
     /// Mach-O "_tlv_bootstrap" symbol.
     #[cfg(feature = "macho")]
     tlv_bootstrap: Option<SymbolId>,
@@ -108,12 +114,16 @@ impl<'a> Object<'a> {
             sections: Vec::new(),
             standard_sections: HashMap::new(),
             symbols: Vec::new(),
-            symbol_map: HashMap::new(),
+            symbol_map: SymbolMap::default(),
             comdats: Vec::new(),
+            common_symbol_mode: CommonSymbolMode::Common,
+            symbol_order: SymbolOrder::Format,
             flags: FileFlags::None,
             mangling: Mangling::default(format, architecture),
             #[cfg(feature = "coff")]
             stub_symbols: HashMap::new(),
+            #[cfg(feature = "coff")]
+            coff_bigobj: false,
             #[cfg(feature = "macho")]
             tlv_bootstrap: None,
             #[cfg(feature = "macho")]
@@ -160,6 +170,37 @@ impl<'a> Object<'a> {
         self.mangling = mangling;
     }
 
+    /// Return how symbols added by [`Self::add_common_symbol`] are placed.
+    #[inline]
+    pub fn common_symbol_mode(&self) -> CommonSymbolMode {
+        self.common_symbol_mode
+    }
+
+    /// Specify how symbols added by [`Self::add_common_symbol`] are placed.
+    ///
+    /// This only affects symbols that are added after it is called.
+    /// The mode applies to the whole object, but the resulting symbols depend on
+    /// the file format; see [`Self::add_common_symbol`] for details.
+    #[inline]
+    pub fn set_common_symbol_mode(&mut self, mode: CommonSymbolMode) {
+        self.common_symbol_mode = mode;
+    }
+
+    /// Return how symbols are ordered in the symbol table when writing.
+    #[inline]
+    pub fn symbol_order(&self) -> SymbolOrder {
+        self.symbol_order
+    }
+
+    /// Specify how symbols are ordered in the symbol table when writing.
+    ///
+    /// Use [`Self::emit_with_symbol_indices`] to find the resulting symbol
+    /// table indices.
+    #[inline]
+    pub fn set_symbol_order(&mut self, order: SymbolOrder) {
+        self.symbol_order = order;
+    }
+
     /// Return the name for a standard segment.
     ///
     /// This will vary based on the file format.
@@ -244,6 +285,7 @@ impl<'a> Object<'a> {
             data: Cow::Borrowed(&[]),
             relocations: Vec::new(),
             symbol: None,
+            order: 0,
             flags: SectionFlags::None,
         });
 
@@ -353,8 +395,12 @@ impl<'a> Object<'a> {
     }
 
     /// Get the `SymbolId` of the symbol with the given name.
+    ///
+    /// The name is the unmangled name, without any global prefix that was added
+    /// by `add_symbol`. If a symbol is renamed using `symbol_mut`, then it can be
+    /// found using its new name.
     pub fn symbol_id(&self, name: &[u8]) -> Option<SymbolId> {
-        self.symbol_map.get(name).cloned()
+        self.symbol_map.get(&self.symbols, name)
     }
 
     /// Get the symbol with the given `SymbolId`.
@@ -364,8 +410,10 @@ impl<'a> Object<'a> {
     }
 
     /// Mutably get the symbol with the given `SymbolId`.
-    #[inline]
     pub fn symbol_mut(&mut self, symbol: SymbolId) -> &mut Symbol {
+        // The name may be changed, so the symbol map must not use it until
+        // the returned borrow has ended.
+        self.symbol_map.remove(&self.symbols, symbol);
         &mut self.symbols[symbol.0]
     }
 
@@ -378,7 +426,7 @@ impl<'a> Object<'a> {
             // the automatically generated section symbol will have none.
             let symbol_id = self.section_symbol(symbol.section.id().unwrap());
             if symbol.flags != SymbolFlags::None {
-                self.symbol_mut(symbol_id).flags = symbol.flags;
+                self.symbols[symbol_id.0].flags = symbol.flags;
             }
             return symbol_id;
         }
@@ -387,12 +435,18 @@ impl<'a> Object<'a> {
                 || symbol.kind == SymbolKind::Data
                 || symbol.kind == SymbolKind::Tls)
         {
-            let unmangled_name = symbol.name.clone();
-            if let Some(prefix) = self.mangling.global_prefix() {
+            let prefix = self.mangling.global_prefix();
+            if let Some(prefix) = prefix {
                 symbol.name.insert(0, prefix);
             }
             let symbol_id = self.add_raw_symbol(symbol);
-            self.symbol_map.insert(unmangled_name, symbol_id);
+            self.symbol_map.insert(
+                &self.symbols,
+                SymbolMapEntry {
+                    symbol: symbol_id,
+                    prefix,
+                },
+            );
             symbol_id
         } else {
             self.add_raw_symbol(symbol)
@@ -419,11 +473,26 @@ impl<'a> Object<'a> {
 
     /// Add a new common symbol and return its `SymbolId`.
     ///
-    /// For Mach-O, this appends the symbol to the `__common` section.
+    /// The placement of the symbol depends on [`Self::common_symbol_mode`].
+    /// For [`CommonSymbolMode::Common`]:
+    /// - for ELF, the symbol is in `SHN_COMMON`, and `symbol.value` is replaced
+    ///   with `align`, since that is where ELF stores the alignment of common symbols
+    /// - for COFF, the symbol is external with a section number of zero, and its
+    ///   value is the size; `align` is not stored
+    /// - for Mach-O, this appends the symbol to the `__common` section
+    ///
+    /// For [`CommonSymbolMode::Bss`], this appends the symbol to the
+    /// uninitialized data section for all formats, and `symbol.value` is replaced
+    /// with the offset of the symbol in that section.
     ///
     /// `align` must be a power of two.
     pub fn add_common_symbol(&mut self, mut symbol: Symbol, size: u64, align: u64) -> SymbolId {
-        if self.has_common() {
+        if self.common_symbol_mode == CommonSymbolMode::Bss {
+            let symbol_id = self.add_symbol(symbol);
+            let section = self.section_id(StandardSection::UninitializedData);
+            self.add_symbol_bss(symbol_id, section, size, align);
+            symbol_id
+        } else if self.has_common() {
             let symbol_id = self.add_symbol(symbol);
             let section = self.section_id(StandardSection::Common);
             self.add_symbol_bss(symbol_id, section, size, align);
@@ -431,10 +500,47 @@ impl<'a> Object<'a> {
         } else {
             symbol.section = SymbolSection::Common;
             symbol.size = size;
+            if self.format == BinaryFormat::Elf {
+                symbol.value = align;
+            }
             self.add_symbol(symbol)
         }
     }
 
+    /// Add an alias for a defined symbol and return the `SymbolId` of the alias.
+    ///
+    /// The alias has the same kind, scope, section, value and size as the symbol,
+    /// so that it refers to the same address.
+    ///
+    /// For COFF, a weak alias is instead an undefined weak external symbol that uses
+    /// the symbol as its default definition, with `IMAGE_WEAK_EXTERN_SEARCH_ALIAS`.
+    pub fn add_alias(&mut self, symbol_id: SymbolId, name: Vec<u8>, weak: bool) -> SymbolId {
+        let symbol = &self.symbols[symbol_id.0];
+        debug_assert!(!symbol.is_undefined());
+        #[cfg_attr(not(feature = "coff"), allow(unused_mut))]
+        let mut alias = Symbol {
+            name,
+            value: symbol.value,
+            size: symbol.size,
+            kind: symbol.kind,
+            scope: symbol.scope,
+            weak,
+            section: symbol.section,
+            flags: SymbolFlags::None,
+        };
+        #[cfg(feature = "coff")]
+        if weak && self.format == BinaryFormat::Coff {
+            alias.value = 0;
+            alias.size = 0;
+            alias.section = SymbolSection::Undefined;
+            alias.flags = SymbolFlags::CoffWeakExternal {
+                default_symbol: symbol_id,
+                search_type: crate::pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS.into(),
+            };
+        }
+        self.add_symbol(alias)
+    }
+
     /// Add a new file symbol and return its `SymbolId`.
     pub fn add_file_symbol(&mut self, name: Vec<u8>) -> SymbolId {
         self.add_raw_symbol(Symbol {
@@ -550,7 +656,7 @@ impl<'a> Object<'a> {
             BinaryFormat::MachO => symbol_id = self.macho_add_thread_var(symbol_id),
             _ => {}
         }
-        let symbol = self.symbol_mut(symbol_id);
+        let symbol = &mut self.symbols[symbol_id.0];
         symbol.value = offset;
         symbol.size = size;
         symbol.section = SymbolSection::Section(section);
@@ -667,6 +773,19 @@ impl<'a> Object<'a> {
 
     /// Write the object to a `WritableBuffer`.
     pub fn emit(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        self.emit_with_symbol_indices(buffer).map(|_| ())
+    }
+
+    /// Write the object to a `WritableBuffer`, and return the index of each
+    /// symbol in the symbol table of the written file.
+    ///
+    /// This can be used to reference symbols in tables that are generated
+    /// separately from the object file. The indices depend on
+    /// [`Self::symbol_order`].
+    pub fn emit_with_symbol_indices(
+        &self,
+        buffer: &mut dyn WritableBuffer,
+    ) -> Result<SymbolIndices> {
         match self.format {
             #[cfg(feature = "coff")]
             BinaryFormat::Coff => self.coff_write(buffer),
@@ -764,6 +883,7 @@ pub struct Section<'a> {
     data: Cow<'a, [u8]>,
     relocations: Vec<Relocation>,
     symbol: Option<SymbolId>,
+    order: i32,
     /// Section flags that are specific to each file format.
     pub flags: SectionFlags,
 }
@@ -787,6 +907,24 @@ impl<'a> Section<'a> {
         self.kind.is_bss()
     }
 
+    /// Return the key used to order this section in the output file.
+    #[inline]
+    pub fn order(&self) -> i32 {
+        self.order
+    }
+
+    /// Set the key used to order this section in the output file.
+    ///
+    /// Sections are written in increasing order of this key. Sections with equal
+    /// keys are written in the order that they were added. The default key is 0.
+    ///
+    /// This does not change the `SectionId` of any section.
+    ///
+    /// This is currently only supported for ELF, and is ignored for other formats.
+    pub fn set_order(&mut self, order: i32) {
+        self.order = order;
+    }
+
     /// Set the data for a section.
     ///
     /// Must not be called for sections that already have data, or that contain uninitialized data.
@@ -946,6 +1084,100 @@ impl Symbol {
     }
 }
 
+/// A map from unmangled symbol names to symbols.
+///
+/// The entries refer to the names in `Object::symbols`, so that the map doesn't
+/// need its own copy of every name.
+#[derive(Debug, Default)]
+struct SymbolMap {
+    hash_builder: hashbrown::DefaultHashBuilder,
+    table: hashbrown::HashTable<SymbolMapEntry>,
+    /// An entry that was removed from the table because its name may be changed.
+    ///
+    /// This is added back to the table before the table is next modified, at
+    /// which point the name can no longer be changed.
+    renamed: Option<SymbolMapEntry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SymbolMapEntry {
+    symbol: SymbolId,
+    /// The global prefix that was added to the symbol name.
+    prefix: Option<u8>,
+}
+
+impl SymbolMapEntry {
+    fn name<'a>(&self, symbols: &'a [Symbol]) -> &'a [u8] {
+        let name = &*symbols[self.symbol.0].name;
+        match (self.prefix, name.split_first()) {
+            (Some(prefix), Some((first, rest))) if *first == prefix => rest,
+            _ => name,
+        }
+    }
+}
+
+impl SymbolMap {
+    fn hash(hash_builder: &hashbrown::DefaultHashBuilder, name: &[u8]) -> u64 {
+        let mut state = hash_builder.build_hasher();
+        name.hash(&mut state);
+        state.finish()
+    }
+
+    fn get(&self, symbols: &[Symbol], name: &[u8]) -> Option<SymbolId> {
+        if let Some(entry) = self.renamed {
+            if entry.name(symbols) == name {
+                return Some(entry.symbol);
+            }
+        }
+        let hash = Self::hash(&self.hash_builder, name);
+        self.table
+            .find(hash, |entry| entry.name(symbols) == name)
+            .map(|entry| entry.symbol)
+    }
+
+    /// Insert an entry, replacing any existing entry with the same name.
+    fn insert(&mut self, symbols: &[Symbol], entry: SymbolMapEntry) {
+        if let Some(renamed) = self.renamed.take() {
+            self.insert(symbols, renamed);
+        }
+        let name = entry.name(symbols);
+        let hash_builder = &self.hash_builder;
+        let hash = Self::hash(hash_builder, name);
+        match self.table.entry(
+            hash,
+            |other| other.name(symbols) == name,
+            |other| Self::hash(hash_builder, other.name(symbols)),
+        ) {
+            hashbrown::hash_table::Entry::Occupied(mut occupied) => *occupied.get_mut() = entry,
+            hashbrown::hash_table::Entry::Vacant(vacant) => {
+                vacant.insert(entry);
+            }
+        }
+    }
+
+    /// Remove the entry for a symbol whose name may be changed.
+    ///
+    /// The entry is kept in `renamed` until the next modification.
+    fn remove(&mut self, symbols: &[Symbol], symbol: SymbolId) {
+        if let Some(renamed) = self.renamed.take() {
+            if renamed.symbol == symbol {
+                self.renamed = Some(renamed);
+                return;
+            }
+            self.insert(symbols, renamed);
+        }
+        // The entry is hashed using either the full name or the name without its prefix.
+        let name = &*symbols[symbol.0].name;
+        for key in [name, name.get(1..).unwrap_or(&[])] {
+            let hash = Self::hash(&self.hash_builder, key);
+            if let Ok(occupied) = self.table.find_entry(hash, |entry| entry.symbol == symbol) {
+                self.renamed = Some(occupied.remove().0);
+                return;
+            }
+        }
+    }
+}
+
 /// A relocation in an object file.
 #[derive(Debug)]
 pub struct Relocation {
@@ -984,6 +1216,70 @@ pub struct Comdat {
     pub sections: Vec<SectionId>,
 }
 
+/// The placement of symbols added by [`Object::add_common_symbol`].
+///
+/// This is set for the whole object with [`Object::set_common_symbol_mode`].
+/// The representation of common symbols is format specific, and Mach-O has
+/// no separate representation for them: [`CommonSymbolMode::Common`] places them
+/// in the `__common` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CommonSymbolMode {
+    /// Emit common symbols, which the linker may merge with other definitions.
+    ///
+    /// This is the default.
+    Common,
+    /// Define the symbols in the uninitialized data section.
+    ///
+    /// This is equivalent to compiling with `-fno-common`.
+    Bss,
+}
+
+/// The order of symbols in the symbol table of a written object file.
+///
+/// This is used in [`Object::set_symbol_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SymbolOrder {
+    /// Use the order that is conventional for the file format.
+    ///
+    /// For ELF, local symbols are placed before global symbols.
+    /// For Mach-O, symbols are grouped into local, external, and undefined
+    /// symbols, and the external and undefined symbols are sorted by name.
+    /// For COFF and XCOFF, symbols are in the order that they were added.
+    ///
+    /// This is the default.
+    Format,
+    /// Keep symbols in the order that they were added, as far as the file
+    /// format allows.
+    ///
+    /// For ELF, an error is returned when writing if a local symbol was
+    /// added after a global symbol.
+    /// For Mach-O, symbols are still grouped into local, external, and
+    /// undefined symbols, but are not sorted by name.
+    Insertion,
+}
+
+/// The indices of symbols in the symbol table of a written object file.
+///
+/// This is returned by [`Object::emit_with_symbol_indices`].
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndices(Vec<Option<usize>>);
+
+impl SymbolIndices {
+    /// Return the index of the symbol in the symbol table.
+    ///
+    /// This is the index used to refer to the symbol in relocations. For
+    /// ELF, this counts the null symbol. For COFF and XCOFF, this counts the
+    /// auxiliary symbol records.
+    ///
+    /// Returns `None` if the symbol was not written to the symbol table.
+    /// This occurs for Mach-O file and section symbols.
+    pub fn get(&self, symbol: SymbolId) -> Option<usize> {
+        self.0.get(symbol.0).copied().flatten()
+    }
+}
+
 /// The symbol name mangling scheme.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
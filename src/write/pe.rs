@@ -1,6 +1,8 @@
 //! Helper for writing PE files.
+use alloc::borrow::Cow;
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::convert::TryFrom;
 use core::mem;
 
 use crate::endian::{LittleEndian as LE, *};
@@ -39,10 +41,34 @@ pub struct Writer<'a> {
 
     symbol_offset: u32,
     symbol_num: u32,
+    long_section_names: LongSectionNames,
+    strtab_data: Vec<u8>,
 
+    reloc_entries: Vec<(u32, u16)>,
     reloc_blocks: Vec<RelocBlock>,
     relocs: Vec<U16<LE>>,
     reloc_offset: u32,
+
+    exports: Vec<Export<'a>>,
+    export_dll_name: &'a [u8],
+    export_ordinal_base: u32,
+    export_address: u32,
+    export_offset: u32,
+
+    resources: Vec<Resource<'a>>,
+    resource_address: u32,
+    resource_offset: u32,
+
+    debug_entries: Vec<DebugEntry<'a>>,
+    debug_address: u32,
+    debug_offset: u32,
+
+    tls_callbacks: Vec<u32>,
+    tls_data: &'a [u8],
+    tls_image_base: u64,
+    tls_zero_fill_size: u32,
+    tls_characteristics: u32,
+    tls_range: TlsRange,
 }
 
 impl<'a> Writer<'a> {
@@ -78,10 +104,34 @@ impl<'a> Writer<'a> {
 
             symbol_offset: 0,
             symbol_num: 0,
+            long_section_names: LongSectionNames::default(),
+            strtab_data: Vec::new(),
 
+            reloc_entries: Vec::new(),
             reloc_blocks: Vec::new(),
             relocs: Vec::new(),
             reloc_offset: 0,
+
+            exports: Vec::new(),
+            export_dll_name: &[],
+            export_ordinal_base: 0,
+            export_address: 0,
+            export_offset: 0,
+
+            resources: Vec::new(),
+            resource_address: 0,
+            resource_offset: 0,
+
+            debug_entries: Vec::new(),
+            debug_address: 0,
+            debug_offset: 0,
+
+            tls_callbacks: Vec::new(),
+            tls_data: &[],
+            tls_image_base: 0,
+            tls_zero_fill_size: 0,
+            tls_characteristics: 0,
+            tls_range: TlsRange::default(),
         }
     }
 
@@ -171,8 +221,10 @@ impl<'a> Writer<'a> {
 
     /// Write padding up to the given file offset.
     pub fn pad_until(&mut self, offset: u32) {
-        debug_assert!(self.buffer.len() <= offset as usize);
-        self.buffer.resize(offset as usize);
+        let len = self.buffer.len();
+        debug_assert!(len <= offset as usize);
+        self.buffer
+            .write_zeros((offset as usize).saturating_sub(len));
     }
 
     /// Reserve the range for the DOS header.
@@ -462,10 +514,57 @@ impl<'a> Writer<'a> {
         }
     }
 
+    /// Set how [`Self::section_name`] handles names longer than 8 bytes.
+    ///
+    /// The default is [`LongSectionNames::Error`].
+    pub fn set_long_section_names(&mut self, long_section_names: LongSectionNames) {
+        self.long_section_names = long_section_names;
+    }
+
+    /// Convert a section name into the value of the section header name field.
+    ///
+    /// Names longer than 8 bytes are handled as specified by
+    /// [`Self::set_long_section_names`].
+    ///
+    /// The result can be passed to [`Self::reserve_section`].
+    pub fn section_name(&mut self, name: &[u8]) -> Result<[u8; pe::IMAGE_SIZEOF_SHORT_NAME]> {
+        let mut section_name = [0; pe::IMAGE_SIZEOF_SHORT_NAME];
+        if name.len() <= section_name.len() {
+            section_name[..name.len()].copy_from_slice(name);
+            return Ok(section_name);
+        }
+        match self.long_section_names {
+            LongSectionNames::Error => Err(Error(format!(
+                "PE section name is longer than 8 bytes: {}",
+                String::from_utf8_lossy(name)
+            ))),
+            LongSectionNames::Truncate => {
+                section_name.copy_from_slice(&name[..pe::IMAGE_SIZEOF_SHORT_NAME]);
+                Ok(section_name)
+            }
+            LongSectionNames::StringTable => {
+                debug_assert_eq!(self.symbol_offset, 0);
+                // The first 4 bytes of the string table are the length.
+                let offset = 4 + self.strtab_data.len();
+                if offset > 9_999_999 {
+                    return Err(Error(String::from("PE string table is too large")));
+                }
+                self.strtab_data.extend_from_slice(name);
+                self.strtab_data.push(0);
+                let offset = format!("/{}", offset);
+                section_name[..offset.len()].copy_from_slice(offset.as_bytes());
+                Ok(section_name)
+            }
+        }
+    }
+
     /// Reserve a section.
     ///
     /// Returns the file range and virtual address range that are reserved
     /// for the section.
+    ///
+    /// Use [`Self::section_name`] to create the `name` from a name that may
+    /// be longer than 8 bytes.
     pub fn reserve_section(
         &mut self,
         name: [u8; 8],
@@ -619,6 +718,216 @@ impl<'a> Writer<'a> {
         range
     }
 
+    /// Add an export to the export table.
+    ///
+    /// `ordinal` is the biased ordinal of the export, which is the ordinal used by importers.
+    /// It must be unique, must not be less than the ordinal base that is later passed to
+    /// `reserve_export_section`, and must not be more than 0xffff greater than that base.
+    ///
+    /// If `name` is `None`, then the export can only be imported by ordinal.
+    pub fn add_export(&mut self, ordinal: u32, name: Option<&'a [u8]>, target: ExportTarget<'a>) {
+        self.exports.push(Export {
+            ordinal,
+            name,
+            target,
+        });
+    }
+
+    /// Reserve an `.edata` section containing the export table.
+    ///
+    /// This contains the exports that were added with `add_export`. The export directory,
+    /// address table, name pointer table, ordinal table, and all strings are placed in
+    /// this section. The name pointer table is sorted by name as required by the loader.
+    ///
+    /// `dll_name` is the name of the DLL, and `ordinal_base` is the ordinal of the first
+    /// entry in the address table.
+    ///
+    /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_EXPORT` data directory.
+    pub fn reserve_export_section(
+        &mut self,
+        dll_name: &'a [u8],
+        ordinal_base: u32,
+    ) -> Result<SectionRange> {
+        self.exports.sort_by_key(|export| export.ordinal);
+        for (i, export) in self.exports.iter().enumerate() {
+            if export.ordinal < ordinal_base {
+                return Err(Error(format!(
+                    "PE export ordinal {} is less than ordinal base {}",
+                    export.ordinal, ordinal_base
+                )));
+            }
+            if export.ordinal - ordinal_base > u32::from(u16::MAX) {
+                return Err(Error(format!(
+                    "PE export ordinal {} is too large for ordinal base {}",
+                    export.ordinal, ordinal_base
+                )));
+            }
+            if i > 0 && self.exports[i - 1].ordinal == export.ordinal {
+                return Err(Error(format!(
+                    "Duplicate PE export ordinal {}",
+                    export.ordinal
+                )));
+            }
+        }
+        let mut names: Vec<&[u8]> = self.exports.iter().filter_map(|e| e.name).collect();
+        names.sort_unstable();
+        for window in names.windows(2) {
+            if window[0] == window[1] {
+                return Err(Error(format!(
+                    "Duplicate PE export name {:?}",
+                    String::from_utf8_lossy(window[0])
+                )));
+            }
+        }
+        self.export_dll_name = dll_name;
+        self.export_ordinal_base = ordinal_base;
+
+        let size = self.export_layout().size;
+        let range = self.reserve_edata_section(size);
+        self.export_address = range.virtual_address;
+        self.export_offset = range.file_offset;
+        Ok(range)
+    }
+
+    /// Write an `.edata` section containing the export table.
+    ///
+    /// This contains the exports that were added with `add_export`.
+    pub fn write_export_section(&mut self) {
+        if self.export_offset == 0 {
+            return;
+        }
+        self.pad_until(self.export_offset);
+
+        let layout = self.export_layout();
+        let address = self.export_address;
+        let base = self.export_ordinal_base;
+
+        // Sorted list of (name, index into address table).
+        let mut names: Vec<(&[u8], u16)> = self
+            .exports
+            .iter()
+            .filter_map(|export| {
+                let name = export.name?;
+                Some((name, (export.ordinal - base) as u16))
+            })
+            .collect();
+        names.sort_unstable_by_key(|(name, _)| *name);
+
+        self.buffer.write(&pe::ImageExportDirectory {
+            characteristics: U32::new(LE, 0),
+            time_date_stamp: U32::new(LE, 0),
+            major_version: U16::new(LE, 0),
+            minor_version: U16::new(LE, 0),
+            name: U32::new(LE, address + layout.dll_name_offset),
+            base: U32::new(LE, base),
+            number_of_functions: U32::new(LE, layout.address_count),
+            number_of_names: U32::new(LE, names.len() as u32),
+            address_of_functions: U32::new(LE, address + layout.addresses_offset),
+            address_of_names: U32::new(LE, address + layout.names_offset),
+            address_of_name_ordinals: U32::new(LE, address + layout.ordinals_offset),
+        });
+
+        // Address table. Unused ordinals have a zero address.
+        let mut forward_offset = layout.forwards_offset;
+        let mut exports = self.exports.iter().peekable();
+        for index in 0..layout.address_count {
+            let rva = match exports.next_if(|export| export.ordinal - base == index) {
+                Some(export) => match export.target {
+                    ExportTarget::Address(rva) => rva,
+                    ExportTarget::ForwardByOrdinal(..) | ExportTarget::ForwardByName(..) => {
+                        let rva = address + forward_offset;
+                        forward_offset += export.target.forward_len();
+                        rva
+                    }
+                },
+                None => 0,
+            };
+            self.buffer.write(&U32::new(LE, rva));
+        }
+
+        // Name pointer table.
+        let mut name_offset = layout.export_names_offset;
+        for (name, _) in &names {
+            self.buffer.write(&U32::new(LE, address + name_offset));
+            name_offset += name.len() as u32 + 1;
+        }
+
+        // Ordinal table.
+        for (_, index) in &names {
+            self.buffer.write(&U16::new(LE, *index));
+        }
+
+        // Strings.
+        self.buffer.write_bytes(self.export_dll_name);
+        self.buffer.write_bytes(&[0]);
+        for (name, _) in &names {
+            self.buffer.write_bytes(name);
+            self.buffer.write_bytes(&[0]);
+        }
+        for export in &self.exports {
+            match export.target {
+                ExportTarget::Address(_) => {}
+                ExportTarget::ForwardByOrdinal(library, ordinal) => {
+                    self.buffer.write_bytes(library);
+                    self.buffer.write_bytes(b".#");
+                    self.buffer.write_bytes(format!("{}", ordinal).as_bytes());
+                    self.buffer.write_bytes(&[0]);
+                }
+                ExportTarget::ForwardByName(library, name) => {
+                    self.buffer.write_bytes(library);
+                    self.buffer.write_bytes(b".");
+                    self.buffer.write_bytes(name);
+                    self.buffer.write_bytes(&[0]);
+                }
+            }
+        }
+        debug_assert_eq!(
+            self.buffer.len(),
+            (self.export_offset + layout.size) as usize
+        );
+
+        self.write_align(self.file_alignment);
+    }
+
+    fn export_layout(&self) -> ExportLayout {
+        let address_count = self
+            .exports
+            .iter()
+            .map(|export| export.ordinal - self.export_ordinal_base + 1)
+            .max()
+            .unwrap_or(0);
+        let name_count = self.exports.iter().filter(|e| e.name.is_some()).count() as u32;
+
+        let addresses_offset = mem::size_of::<pe::ImageExportDirectory>() as u32;
+        let names_offset = addresses_offset + address_count * 4;
+        let ordinals_offset = names_offset + name_count * 4;
+        let dll_name_offset = ordinals_offset + name_count * 2;
+        let export_names_offset = dll_name_offset + self.export_dll_name.len() as u32 + 1;
+        let forwards_offset = export_names_offset
+            + self
+                .exports
+                .iter()
+                .filter_map(|export| export.name)
+                .map(|name| name.len() as u32 + 1)
+                .sum::<u32>();
+        let size = forwards_offset
+            + self
+                .exports
+                .iter()
+                .map(|export| export.target.forward_len())
+                .sum::<u32>();
+        ExportLayout {
+            size,
+            address_count,
+            addresses_offset,
+            names_offset,
+            ordinals_offset,
+            dll_name_offset,
+            export_names_offset,
+            forwards_offset,
+        }
+    }
+
     /// Reserve a `.pdata` section.
     ///
     /// Contains exception information.
@@ -673,35 +982,576 @@ impl<'a> Writer<'a> {
         range
     }
 
-    /// Add a base relocation.
+    /// Add a resource to the resource directory.
     ///
-    /// `typ` must be one of the `IMAGE_REL_BASED_*` constants.
-    pub fn add_reloc(&mut self, mut virtual_address: u32, typ: u16) {
-        let reloc = U16::new(LE, typ << 12 | (virtual_address & 0xfff) as u16);
-        virtual_address &= !0xfff;
-        if let Some(block) = self.reloc_blocks.last_mut() {
-            if block.virtual_address == virtual_address {
-                self.relocs.push(reloc);
-                block.count += 1;
-                return;
+    /// The resource directory is a tree with three levels: the resource type,
+    /// the resource name, and the language ID. The type is usually one of the
+    /// `pe::RT_*` constants.
+    pub fn add_resource(
+        &mut self,
+        typ: ResourceName<'a>,
+        name: ResourceName<'a>,
+        language: u16,
+        data: &'a [u8],
+    ) {
+        self.resources.push(Resource {
+            typ,
+            name,
+            language,
+            code_page: 0,
+            data: Cow::Borrowed(data),
+        });
+    }
+
+    /// Add a `pe::RT_VERSION` resource containing the given version information.
+    ///
+    /// The resource uses ID 1 and the language of `info`.
+    ///
+    /// Returns an error if the version information is too large to encode.
+    pub fn add_version_info_resource(&mut self, info: &VersionInfo<'_>) -> Result<()> {
+        self.resources.push(Resource {
+            typ: ResourceName::Id(pe::RT_VERSION),
+            name: ResourceName::Id(1),
+            language: info.language,
+            code_page: 0,
+            data: Cow::Owned(info.data()?),
+        });
+        Ok(())
+    }
+
+    /// Add a `pe::RT_MANIFEST` resource containing the given XML manifest.
+    ///
+    /// `id` is usually `pe::CREATEPROCESS_MANIFEST_RESOURCE_ID` for executables,
+    /// or `pe::ISOLATIONAWARE_MANIFEST_RESOURCE_ID` for DLLs.
+    /// The resource uses the neutral language.
+    pub fn add_manifest_resource(&mut self, id: u16, data: &'a [u8]) {
+        self.add_resource(
+            ResourceName::Id(pe::RT_MANIFEST),
+            ResourceName::Id(id),
+            0,
+            data,
+        );
+    }
+
+    /// Reserve a `.rsrc` section containing the resource directory.
+    ///
+    /// This contains the resources that were added with `add_resource`.
+    /// The directory tables, data entries, and names are placed first, followed
+    /// by the data for each resource.
+    ///
+    /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_RESOURCE` data directory.
+    pub fn reserve_resource_section(&mut self) -> Result<SectionRange> {
+        self.resources.sort_by(|a, b| {
+            a.typ
+                .cmp(&b.typ)
+                .then_with(|| a.name.cmp(&b.name))
+                .then(a.language.cmp(&b.language))
+        });
+        for window in self.resources.windows(2) {
+            if window[0].typ == window[1].typ
+                && window[0].name == window[1].name
+                && window[0].language == window[1].language
+            {
+                return Err(Error(format!(
+                    "Duplicate PE resource {:?}/{:?}/{}",
+                    window[0].typ, window[0].name, window[0].language
+                )));
             }
-            // Blocks must have an even number of relocations.
-            if block.count & 1 != 0 {
-                self.relocs.push(U16::new(LE, 0));
-                block.count += 1;
+        }
+
+        let size = self.resource_layout().size;
+        let range = self.reserve_rsrc_section(size);
+        self.resource_address = range.virtual_address;
+        self.resource_offset = range.file_offset;
+        Ok(range)
+    }
+
+    /// Write a `.rsrc` section containing the resource directory.
+    ///
+    /// This contains the resources that were added with `add_resource`.
+    pub fn write_resource_section(&mut self) {
+        if self.resource_offset == 0 {
+            return;
+        }
+        self.pad_until(self.resource_offset);
+
+        let layout = self.resource_layout();
+        let resources = &self.resources;
+
+        // Groups of resources with the same type, and with the same type and name.
+        let mut types = Vec::new();
+        let mut names = Vec::new();
+        for (i, resource) in resources.iter().enumerate() {
+            if i == 0 || resources[i - 1].typ != resource.typ {
+                types.push(i);
+            }
+            if i == 0
+                || resources[i - 1].typ != resource.typ
+                || resources[i - 1].name != resource.name
+            {
+                names.push(i);
             }
-            debug_assert!(block.virtual_address < virtual_address);
         }
-        self.relocs.push(reloc);
-        self.reloc_blocks.push(RelocBlock {
-            virtual_address,
-            count: 1,
+
+        let mut string_offset = layout.strings_offset;
+        let mut write_table =
+            |buffer: &mut dyn WritableBuffer,
+             entries: &mut dyn Iterator<Item = (ResourceName<'_>, u32)>| {
+                let entries: Vec<_> = entries.collect();
+                let named = entries.iter().filter(|(n, _)| n.is_name()).count();
+                buffer.write(&pe::ImageResourceDirectory {
+                    characteristics: U32::new(LE, 0),
+                    time_date_stamp: U32::new(LE, 0),
+                    major_version: U16::new(LE, 0),
+                    minor_version: U16::new(LE, 0),
+                    number_of_named_entries: U16::new(LE, named as u16),
+                    number_of_id_entries: U16::new(LE, (entries.len() - named) as u16),
+                });
+                for (name, offset) in entries {
+                    let name_or_id = match name {
+                        ResourceName::Id(id) => u32::from(id),
+                        ResourceName::Name(_) => {
+                            let offset = string_offset;
+                            string_offset += name.size();
+                            offset | pe::IMAGE_RESOURCE_NAME_IS_STRING
+                        }
+                    };
+                    buffer.write(&pe::ImageResourceDirectoryEntry {
+                        name_or_id: U32::new(LE, name_or_id),
+                        offset_to_data_or_directory: U32::new(LE, offset),
+                    });
+                }
+            };
+
+        // Root table: one entry per type.
+        let mut table_offset = layout.root_size;
+        write_table(
+            self.buffer,
+            &mut types.iter().enumerate().map(|(i, &first)| {
+                let end = types.get(i + 1).copied().unwrap_or(resources.len());
+                let count = names.iter().filter(|&&n| n >= first && n < end).count();
+                let offset = table_offset;
+                table_offset += resource_table_size(count);
+                (
+                    resources[first].typ,
+                    offset | pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY,
+                )
+            }),
+        );
+
+        // Type tables: one entry per name.
+        for (i, &first) in types.iter().enumerate() {
+            let end = types.get(i + 1).copied().unwrap_or(resources.len());
+            write_table(
+                self.buffer,
+                &mut names
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &n)| n >= first && n < end)
+                    .map(|(j, &n)| {
+                        let end = names.get(j + 1).copied().unwrap_or(resources.len());
+                        let offset = table_offset;
+                        table_offset += resource_table_size(end - n);
+                        (
+                            resources[n].name,
+                            offset | pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY,
+                        )
+                    }),
+            );
+        }
+        debug_assert_eq!(table_offset, layout.data_entries_offset);
+
+        // Name tables: one entry per language.
+        let mut data_entry_offset = layout.data_entries_offset;
+        for (j, &first) in names.iter().enumerate() {
+            let end = names.get(j + 1).copied().unwrap_or(resources.len());
+            write_table(
+                self.buffer,
+                &mut resources[first..end].iter().map(|resource| {
+                    let offset = data_entry_offset;
+                    data_entry_offset += mem::size_of::<pe::ImageResourceDataEntry>() as u32;
+                    (ResourceName::Id(resource.language), offset)
+                }),
+            );
+        }
+
+        // Data entries.
+        let mut data_offset = layout.data_offset;
+        for resource in resources {
+            self.buffer.write(&pe::ImageResourceDataEntry {
+                offset_to_data: U32::new(LE, self.resource_address + data_offset),
+                size: U32::new(LE, resource.data.len() as u32),
+                code_page: U32::new(LE, resource.code_page),
+                reserved: U32::new(LE, 0),
+            });
+            data_offset = util::align_u32(data_offset + resource.data.len() as u32, 8);
+        }
+
+        // Names, in the same order as they were referenced.
+        let buffer = &mut *self.buffer;
+        let mut write_name = |name: ResourceName<'_>| {
+            if let ResourceName::Name(name) = name {
+                buffer.write(&U16::new(LE, name.encode_utf16().count() as u16));
+                for c in name.encode_utf16() {
+                    buffer.write(&U16::new(LE, c));
+                }
+            }
+        };
+        for &first in &types {
+            write_name(resources[first].typ);
+        }
+        for &first in &names {
+            write_name(resources[first].name);
+        }
+
+        // Resource data.
+        for resource in resources {
+            util::write_align(self.buffer, 8);
+            self.buffer.write_bytes(&resource.data);
+        }
+        debug_assert_eq!(
+            self.buffer.len(),
+            (self.resource_offset + layout.size) as usize
+        );
+
+        self.write_align(self.file_alignment);
+    }
+
+    fn resource_layout(&self) -> ResourceLayout {
+        let resources = &self.resources;
+        let mut type_count = 0;
+        let mut name_count = 0;
+        let mut strings_size = 0;
+        for (i, resource) in resources.iter().enumerate() {
+            let new_type = i == 0 || resources[i - 1].typ != resource.typ;
+            if new_type {
+                type_count += 1;
+                strings_size += resource.typ.size();
+            }
+            if new_type || resources[i - 1].name != resource.name {
+                name_count += 1;
+                strings_size += resource.name.size();
+            }
+        }
+
+        let root_size = resource_table_size(type_count);
+        let data_entries_offset = root_size
+            + type_count as u32 * resource_table_size(0)
+            + name_count as u32 * mem::size_of::<pe::ImageResourceDirectoryEntry>() as u32
+            + name_count as u32 * resource_table_size(0)
+            + resources.len() as u32 * mem::size_of::<pe::ImageResourceDirectoryEntry>() as u32;
+        let strings_offset = data_entries_offset
+            + resources.len() as u32 * mem::size_of::<pe::ImageResourceDataEntry>() as u32;
+        let data_offset = util::align_u32(strings_offset + strings_size, 8);
+        let mut size = data_offset;
+        for resource in resources {
+            size = util::align_u32(size, 8) + resource.data.len() as u32;
+        }
+        ResourceLayout {
+            size,
+            root_size,
+            data_entries_offset,
+            strings_offset,
+            data_offset,
+        }
+    }
+
+    /// Add an entry to the debug directory.
+    ///
+    /// `typ` must be one of the `IMAGE_DEBUG_TYPE_*` constants.
+    /// `data` is the raw data for the entry, and may be empty.
+    pub fn add_debug_entry(&mut self, typ: u32, data: &'a [u8]) {
+        self.debug_entries.push(DebugEntry {
+            typ,
+            data: Cow::Borrowed(data),
+        });
+    }
+
+    /// Add a CodeView debug directory entry that refers to a PDB file.
+    ///
+    /// This writes an `RSDS` record containing the PDB GUID, age and path.
+    /// The path should not include a null terminator.
+    pub fn add_debug_codeview(&mut self, guid: [u8; 16], age: u32, path: &[u8]) {
+        let mut data = Vec::with_capacity(24 + path.len() + 1);
+        data.extend_from_slice(b"RSDS");
+        data.extend_from_slice(&guid);
+        data.extend_from_slice(&age.to_le_bytes());
+        data.extend_from_slice(path);
+        data.push(0);
+        self.debug_entries.push(DebugEntry {
+            typ: pe::IMAGE_DEBUG_TYPE_CODEVIEW,
+            data: Cow::Owned(data),
+        });
+    }
+
+    /// Add a `pe::IMAGE_DEBUG_TYPE_REPRO` debug directory entry.
+    ///
+    /// This indicates that the image was built deterministically.
+    /// If `hash` is not empty, then the entry data contains the length of
+    /// the hash followed by the hash.
+    pub fn add_debug_repro(&mut self, hash: &[u8]) {
+        let mut data = Vec::new();
+        if !hash.is_empty() {
+            data.extend_from_slice(&(hash.len() as u32).to_le_bytes());
+            data.extend_from_slice(hash);
+        }
+        self.debug_entries.push(DebugEntry {
+            typ: pe::IMAGE_DEBUG_TYPE_REPRO,
+            data: Cow::Owned(data),
         });
     }
 
+    /// Reserve a `.debug` section containing the debug directory.
+    ///
+    /// This contains the entries that were added with `add_debug_entry`,
+    /// `add_debug_codeview` and `add_debug_repro`. The directory is placed
+    /// first, followed by the data for each entry.
+    ///
+    /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_DEBUG` data directory.
+    pub fn reserve_debug_section(&mut self) -> SectionRange {
+        let directory_size =
+            self.debug_entries.len() as u32 * mem::size_of::<pe::ImageDebugDirectory>() as u32;
+        let mut size = directory_size;
+        for entry in &self.debug_entries {
+            size = util::align_u32(size, 4) + entry.data.len() as u32;
+        }
+        let range = self.reserve_section(
+            *b".debug\0\0",
+            pe::IMAGE_SCN_CNT_INITIALIZED_DATA | pe::IMAGE_SCN_MEM_READ,
+            size,
+            size,
+        );
+        let dir = &mut self.data_directories[pe::IMAGE_DIRECTORY_ENTRY_DEBUG];
+        debug_assert_eq!(dir.virtual_address, 0);
+        *dir = DataDirectory {
+            virtual_address: range.virtual_address,
+            size: directory_size,
+        };
+        self.debug_address = range.virtual_address;
+        self.debug_offset = range.file_offset;
+        range
+    }
+
+    /// Write a `.debug` section containing the debug directory.
+    pub fn write_debug_section(&mut self) {
+        if self.debug_offset == 0 {
+            return;
+        }
+        self.pad_until(self.debug_offset);
+
+        let mut offset =
+            self.debug_entries.len() as u32 * mem::size_of::<pe::ImageDebugDirectory>() as u32;
+        for entry in &self.debug_entries {
+            offset = util::align_u32(offset, 4);
+            let size = entry.data.len() as u32;
+            let (address, pointer) = if size == 0 {
+                (0, 0)
+            } else {
+                (self.debug_address + offset, self.debug_offset + offset)
+            };
+            self.buffer.write(&pe::ImageDebugDirectory {
+                characteristics: U32::new(LE, 0),
+                time_date_stamp: U32::new(LE, 0),
+                major_version: U16::new(LE, 0),
+                minor_version: U16::new(LE, 0),
+                typ: U32::new(LE, entry.typ),
+                size_of_data: U32::new(LE, size),
+                address_of_raw_data: U32::new(LE, address),
+                pointer_to_raw_data: U32::new(LE, pointer),
+            });
+            offset += size;
+        }
+        for entry in &self.debug_entries {
+            util::write_align(self.buffer, 4);
+            self.buffer.write_bytes(&entry.data);
+        }
+
+        self.write_align(self.file_alignment);
+    }
+
+    /// Add a TLS callback.
+    ///
+    /// `address` is the address of the callback function, relative to the image base.
+    /// Callbacks are called in the order that they are added.
+    pub fn add_tls_callback(&mut self, address: u32) {
+        self.tls_callbacks.push(address);
+    }
+
+    /// Reserve a `.tls` section containing thread local storage.
+    ///
+    /// The section contains the TLS template data, followed by the TLS directory,
+    /// the null terminated array of callbacks that were added with `add_tls_callback`,
+    /// and the TLS index slot that is set by the loader.
+    ///
+    /// `data` is the initialized part of the template. `zero_fill_size` is the size of the
+    /// zero initialized part of the template that follows `data`. `characteristics` may
+    /// contain one of the `IMAGE_SCN_ALIGN_*` constants to specify the template alignment.
+    ///
+    /// The TLS directory and callbacks contain absolute addresses, so `image_base` must be
+    /// the same value that is later passed to `write_nt_headers`. This also adds the base
+    /// relocations for these addresses.
+    ///
+    /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_TLS` data directory.
+    pub fn reserve_tls_section(
+        &mut self,
+        image_base: u64,
+        data: &'a [u8],
+        zero_fill_size: u32,
+        characteristics: u32,
+    ) -> TlsRange {
+        let (pointer_size, directory_size, reloc_type) = if self.is_64 {
+            (
+                8,
+                mem::size_of::<pe::ImageTlsDirectory64>() as u32,
+                pe::IMAGE_REL_BASED_DIR64,
+            )
+        } else {
+            (
+                4,
+                mem::size_of::<pe::ImageTlsDirectory32>() as u32,
+                pe::IMAGE_REL_BASED_HIGHLOW,
+            )
+        };
+        let directory_offset = util::align_u32(data.len() as u32, 8);
+        let callbacks_offset = directory_offset + directory_size;
+        let index_offset = callbacks_offset + (self.tls_callbacks.len() as u32 + 1) * pointer_size;
+        let size = index_offset + 4;
+        let section = self.reserve_section(
+            *b".tls\0\0\0\0",
+            pe::IMAGE_SCN_CNT_INITIALIZED_DATA | pe::IMAGE_SCN_MEM_READ | pe::IMAGE_SCN_MEM_WRITE,
+            size,
+            size,
+        );
+        let range = TlsRange {
+            section,
+            data_address: section.virtual_address,
+            directory_address: section.virtual_address + directory_offset,
+            callbacks_address: section.virtual_address + callbacks_offset,
+            index_address: section.virtual_address + index_offset,
+        };
+
+        let dir = &mut self.data_directories[pe::IMAGE_DIRECTORY_ENTRY_TLS];
+        debug_assert_eq!(dir.virtual_address, 0);
+        *dir = DataDirectory {
+            virtual_address: range.directory_address,
+            size: directory_size,
+        };
+
+        // The first four fields of the directory are absolute addresses.
+        for i in 0..4 {
+            self.add_reloc(range.directory_address + i * pointer_size, reloc_type);
+        }
+        for i in 0..self.tls_callbacks.len() as u32 {
+            self.add_reloc(range.callbacks_address + i * pointer_size, reloc_type);
+        }
+
+        self.tls_data = data;
+        self.tls_image_base = image_base;
+        self.tls_zero_fill_size = zero_fill_size;
+        self.tls_characteristics = characteristics;
+        self.tls_range = range;
+        range
+    }
+
+    /// Write a `.tls` section containing thread local storage.
+    pub fn write_tls_section(&mut self) {
+        let range = self.tls_range;
+        if range.section.file_offset == 0 {
+            return;
+        }
+        self.pad_until(range.section.file_offset);
+        self.buffer.write_bytes(self.tls_data);
+        self.pad_until(range.section.file_offset + (range.directory_address - range.data_address));
+
+        let base = self.tls_image_base;
+        let start = base + u64::from(range.data_address);
+        let end = start + self.tls_data.len() as u64;
+        let index = base + u64::from(range.index_address);
+        let callbacks = base + u64::from(range.callbacks_address);
+        if self.is_64 {
+            self.buffer.write(&pe::ImageTlsDirectory64 {
+                start_address_of_raw_data: U64::new(LE, start),
+                end_address_of_raw_data: U64::new(LE, end),
+                address_of_index: U64::new(LE, index),
+                address_of_call_backs: U64::new(LE, callbacks),
+                size_of_zero_fill: U32::new(LE, self.tls_zero_fill_size),
+                characteristics: U32::new(LE, self.tls_characteristics),
+            });
+            for callback in &self.tls_callbacks {
+                self.buffer
+                    .write(&U64::new(LE, base + u64::from(*callback)));
+            }
+            self.buffer.write(&U64::new(LE, 0));
+        } else {
+            self.buffer.write(&pe::ImageTlsDirectory32 {
+                start_address_of_raw_data: U32::new(LE, start as u32),
+                end_address_of_raw_data: U32::new(LE, end as u32),
+                address_of_index: U32::new(LE, index as u32),
+                address_of_call_backs: U32::new(LE, callbacks as u32),
+                size_of_zero_fill: U32::new(LE, self.tls_zero_fill_size),
+                characteristics: U32::new(LE, self.tls_characteristics),
+            });
+            for callback in &self.tls_callbacks {
+                self.buffer
+                    .write(&U32::new(LE, (base + u64::from(*callback)) as u32));
+            }
+            self.buffer.write(&U32::new(LE, 0));
+        }
+        // The TLS index is set by the loader.
+        self.buffer.write(&U32::new(LE, 0));
+
+        self.write_align(self.file_alignment);
+    }
+
+    /// Add a base relocation.
+    ///
+    /// `typ` must be one of the `IMAGE_REL_BASED_*` constants.
+    ///
+    /// Relocations may be added in any order. They are sorted by address
+    /// when the `.reloc` section is reserved.
+    pub fn add_reloc(&mut self, virtual_address: u32, typ: u16) {
+        self.reloc_entries.push((virtual_address, typ));
+    }
+
+    /// Add base relocations for absolute addresses in the data of a section.
+    ///
+    /// This derives the base relocation type from the size of each address:
+    /// `pe::IMAGE_REL_BASED_DIR64` for 64-bit addresses, and
+    /// `pe::IMAGE_REL_BASED_HIGHLOW` for 32-bit addresses.
+    ///
+    /// `section` is the range that was returned when the section was reserved.
+    /// Returns an error if an address is not within the section, or has an unsupported size.
+    pub fn add_absolute_relocs(
+        &mut self,
+        section: &SectionRange,
+        relocs: &[AbsoluteRelocation],
+    ) -> Result<()> {
+        for reloc in relocs {
+            let typ = match reloc.size {
+                32 => pe::IMAGE_REL_BASED_HIGHLOW,
+                64 if self.is_64 => pe::IMAGE_REL_BASED_DIR64,
+                _ => {
+                    return Err(Error(format!(
+                        "Unsupported size {} for PE absolute relocation at offset {:#x}",
+                        reloc.size, reloc.offset
+                    )));
+                }
+            };
+            let end = reloc.offset.checked_add(u32::from(reloc.size / 8));
+            if end.map_or(true, |end| end > section.virtual_size) {
+                return Err(Error(format!(
+                    "Invalid offset {:#x} for PE absolute relocation",
+                    reloc.offset
+                )));
+            }
+            self.add_reloc(section.virtual_address + reloc.offset, typ);
+        }
+        Ok(())
+    }
+
     /// Return true if a base relocation has been added.
     pub fn has_relocs(&mut self) -> bool {
-        !self.relocs.is_empty()
+        !self.reloc_entries.is_empty()
     }
 
     /// Reserve a `.reloc` section.
@@ -710,6 +1560,29 @@ impl<'a> Writer<'a> {
     ///
     /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_BASERELOC` data directory.
     pub fn reserve_reloc_section(&mut self) -> SectionRange {
+        self.reloc_entries
+            .sort_by_key(|(virtual_address, _)| *virtual_address);
+        for &(mut virtual_address, typ) in &self.reloc_entries {
+            let reloc = U16::new(LE, typ << 12 | (virtual_address & 0xfff) as u16);
+            virtual_address &= !0xfff;
+            if let Some(block) = self.reloc_blocks.last_mut() {
+                if block.virtual_address == virtual_address {
+                    self.relocs.push(reloc);
+                    block.count += 1;
+                    continue;
+                }
+                // Blocks must have an even number of relocations.
+                if block.count & 1 != 0 {
+                    self.relocs.push(U16::new(LE, 0));
+                    block.count += 1;
+                }
+            }
+            self.relocs.push(reloc);
+            self.reloc_blocks.push(RelocBlock {
+                virtual_address,
+                count: 1,
+            });
+        }
         if let Some(block) = self.reloc_blocks.last_mut() {
             // Blocks must have an even number of relocations.
             if block.count & 1 != 0 {
@@ -760,6 +1633,36 @@ impl<'a> Writer<'a> {
         self.write_align(self.file_alignment);
     }
 
+    /// Reserve the COFF string table.
+    ///
+    /// This is only needed if [`LongSectionNames::StringTable`] is used.
+    /// Does nothing if no names were added to the string table.
+    ///
+    /// This must be called after all sections have been named, and is
+    /// usually placed after the section data since it is not loaded.
+    pub fn reserve_strtab(&mut self) {
+        if self.strtab_data.is_empty() {
+            return;
+        }
+        debug_assert_eq!(self.symbol_offset, 0);
+        // The string table immediately follows an empty symbol table.
+        self.symbol_offset = self.reserve(4 + self.strtab_data.len() as u32, 1);
+        self.symbol_num = 0;
+    }
+
+    /// Write the COFF string table.
+    ///
+    /// Does nothing if no names were added to the string table.
+    pub fn write_strtab(&mut self) {
+        if self.strtab_data.is_empty() {
+            return;
+        }
+        self.pad_until(self.symbol_offset);
+        self.buffer
+            .write(&U32::new(LE, 4 + self.strtab_data.len() as u32));
+        self.buffer.write_bytes(&self.strtab_data);
+    }
+
     /// Reserve the certificate table.
     ///
     /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_SECURITY` data directory.
@@ -812,6 +1715,29 @@ pub struct NtHeaders {
     pub size_of_heap_commit: u64,
 }
 
+/// How to handle section names that are longer than 8 bytes.
+///
+/// Used in [`Writer::set_long_section_names`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LongSectionNames {
+    /// Return an error.
+    #[default]
+    Error,
+    /// Truncate the name to 8 bytes.
+    ///
+    /// This is what the Microsoft linker does for image files.
+    Truncate,
+    /// Store the name in a COFF string table and use a `/nnnn` name that
+    /// contains the decimal offset of the name in the string table.
+    ///
+    /// This is the convention used by GNU tools for debug sections. The
+    /// string table is written with an empty symbol table, and must be
+    /// reserved and written using [`Writer::reserve_strtab`] and
+    /// [`Writer::write_strtab`].
+    StringTable,
+}
+
 #[derive(Default, Clone, Copy)]
 struct DataDirectory {
     virtual_address: u32,
@@ -827,6 +1753,23 @@ pub struct Section {
     pub range: SectionRange,
 }
 
+/// The addresses of the thread local storage reserved by [`Writer::reserve_tls_section`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TlsRange {
+    /// The range of the `.tls` section.
+    pub section: SectionRange,
+    /// The address of the TLS template data.
+    pub data_address: u32,
+    /// The address of the TLS directory.
+    pub directory_address: u32,
+    /// The address of the null terminated array of TLS callbacks.
+    pub callbacks_address: u32,
+    /// The address of the TLS index that is set by the loader.
+    ///
+    /// Code that accesses thread local variables uses this to find the TLS block.
+    pub index_address: u32,
+}
+
 /// The file range and virtual address range for a section.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Clone, Copy)]
@@ -837,6 +1780,301 @@ pub struct SectionRange {
     pub file_size: u32,
 }
 
+/// Where an export is pointing to.
+#[derive(Debug, Clone, Copy)]
+pub enum ExportTarget<'a> {
+    /// The address of the export, relative to the image base.
+    Address(u32),
+    /// Forwarded to an export ordinal in another DLL.
+    ///
+    /// This gives the name of the DLL, and the ordinal.
+    ForwardByOrdinal(&'a [u8], u32),
+    /// Forwarded to an export name in another DLL.
+    ///
+    /// This gives the name of the DLL, and the export name.
+    ForwardByName(&'a [u8], &'a [u8]),
+}
+
+impl<'a> ExportTarget<'a> {
+    /// The length of the forwarder string, including the null terminator.
+    fn forward_len(&self) -> u32 {
+        match self {
+            ExportTarget::Address(_) => 0,
+            ExportTarget::ForwardByOrdinal(library, ordinal) => {
+                library.len() as u32 + 2 + format!("{}", ordinal).len() as u32 + 1
+            }
+            ExportTarget::ForwardByName(library, name) => {
+                library.len() as u32 + 1 + name.len() as u32 + 1
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Export<'a> {
+    ordinal: u32,
+    name: Option<&'a [u8]>,
+    target: ExportTarget<'a>,
+}
+
+struct ExportLayout {
+    size: u32,
+    address_count: u32,
+    addresses_offset: u32,
+    names_offset: u32,
+    ordinals_offset: u32,
+    dll_name_offset: u32,
+    export_names_offset: u32,
+    forwards_offset: u32,
+}
+
+#[derive(Debug, Clone)]
+struct DebugEntry<'a> {
+    typ: u32,
+    data: Cow<'a, [u8]>,
+}
+
+/// The name or ID of an entry in the resource directory.
+///
+/// Names are compared case insensitively, in the same way as the Windows loader.
+#[derive(Debug, Clone, Copy)]
+pub enum ResourceName<'a> {
+    /// A string name.
+    ///
+    /// This is stored as UTF-16.
+    Name(&'a str),
+    /// A numeric ID.
+    Id(u16),
+}
+
+impl<'a> ResourceName<'a> {
+    fn is_name(&self) -> bool {
+        matches!(self, ResourceName::Name(_))
+    }
+
+    /// The size of the directory string for a name, including the length prefix.
+    fn size(&self) -> u32 {
+        match self {
+            ResourceName::Name(name) => 2 + 2 * name.encode_utf16().count() as u32,
+            ResourceName::Id(_) => 0,
+        }
+    }
+}
+
+impl<'a> PartialEq for ResourceName<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == core::cmp::Ordering::Equal
+    }
+}
+
+impl<'a> Eq for ResourceName<'a> {}
+
+impl<'a> PartialOrd for ResourceName<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Names sort before IDs, and names are compared case insensitively.
+impl<'a> Ord for ResourceName<'a> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        use core::cmp::Ordering;
+        match (self, other) {
+            (ResourceName::Name(a), ResourceName::Name(b)) => {
+                let a = a.encode_utf16().map(|c| {
+                    if c < 0x80 {
+                        u16::from((c as u8).to_ascii_uppercase())
+                    } else {
+                        c
+                    }
+                });
+                let b = b.encode_utf16().map(|c| {
+                    if c < 0x80 {
+                        u16::from((c as u8).to_ascii_uppercase())
+                    } else {
+                        c
+                    }
+                });
+                a.cmp(b)
+            }
+            (ResourceName::Name(_), ResourceName::Id(_)) => Ordering::Less,
+            (ResourceName::Id(_), ResourceName::Name(_)) => Ordering::Greater,
+            (ResourceName::Id(a), ResourceName::Id(b)) => a.cmp(b),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Resource<'a> {
+    typ: ResourceName<'a>,
+    name: ResourceName<'a>,
+    language: u16,
+    code_page: u32,
+    data: Cow<'a, [u8]>,
+}
+
+struct ResourceLayout {
+    size: u32,
+    root_size: u32,
+    data_entries_offset: u32,
+    strings_offset: u32,
+    data_offset: u32,
+}
+
+fn resource_table_size(entries: usize) -> u32 {
+    mem::size_of::<pe::ImageResourceDirectory>() as u32
+        + entries as u32 * mem::size_of::<pe::ImageResourceDirectoryEntry>() as u32
+}
+
+/// Information for a `VS_VERSIONINFO` resource.
+///
+/// Use [`Writer::add_version_info_resource`] to add this to the resource directory,
+/// or [`VersionInfo::data`] to encode it yourself.
+#[derive(Debug, Clone, Default)]
+pub struct VersionInfo<'a> {
+    /// The binary file version.
+    ///
+    /// The four components are packed from most to least significant.
+    pub file_version: [u16; 4],
+    /// The binary product version.
+    ///
+    /// The four components are packed from most to least significant.
+    pub product_version: [u16; 4],
+    /// A combination of `pe::VS_FF_*` flags.
+    pub file_flags: u32,
+    /// One of the `pe::VOS_*` constants.
+    pub file_os: u32,
+    /// One of the `pe::VFT_*` constants.
+    pub file_type: u32,
+    /// The function of the file. Depends on `file_type`.
+    pub file_subtype: u32,
+    /// The language ID of the string table.
+    pub language: u16,
+    /// The code page of the string table.
+    pub code_page: u16,
+    /// Key/value pairs for the string table.
+    ///
+    /// Common keys include `CompanyName`, `FileDescription`, `FileVersion`,
+    /// `InternalName`, `OriginalFilename`, `ProductName` and `ProductVersion`.
+    pub strings: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> VersionInfo<'a> {
+    /// Encode the `VS_VERSIONINFO` structure.
+    ///
+    /// Returns an error if the length of a block does not fit in 16 bits.
+    pub fn data(&self) -> Result<Vec<u8>> {
+        let version = |v: [u16; 4]| {
+            (
+                u32::from(v[0]) << 16 | u32::from(v[1]),
+                u32::from(v[2]) << 16 | u32::from(v[3]),
+            )
+        };
+        let (file_version_ms, file_version_ls) = version(self.file_version);
+        let (product_version_ms, product_version_ls) = version(self.product_version);
+        let fixed = pe::VsFixedFileInfo {
+            signature: U32::new(LE, pe::VS_FFI_SIGNATURE),
+            struc_version: U32::new(LE, pe::VS_FFI_STRUCVERSION),
+            file_version_ms: U32::new(LE, file_version_ms),
+            file_version_ls: U32::new(LE, file_version_ls),
+            product_version_ms: U32::new(LE, product_version_ms),
+            product_version_ls: U32::new(LE, product_version_ls),
+            file_flags_mask: U32::new(LE, pe::VS_FFI_FILEFLAGSMASK),
+            file_flags: U32::new(LE, self.file_flags),
+            file_os: U32::new(LE, self.file_os),
+            file_type: U32::new(LE, self.file_type),
+            file_subtype: U32::new(LE, self.file_subtype),
+            file_date_ms: U32::new(LE, 0),
+            file_date_ls: U32::new(LE, 0),
+        };
+
+        let mut data = Vec::new();
+        let root = version_block_start(&mut data, "VS_VERSION_INFO", 0);
+        data.extend_from_slice(crate::pod::bytes_of(&fixed));
+        version_block_value_len(&mut data, root, mem::size_of::<pe::VsFixedFileInfo>())?;
+
+        let string_file_info = version_block_start(&mut data, "StringFileInfo", 1);
+        let key = format!("{:04X}{:04X}", self.language, self.code_page);
+        let string_table = version_block_start(&mut data, &key, 1);
+        for (key, value) in &self.strings {
+            let string = version_block_start(&mut data, key, 1);
+            let len = write_utf16z(&mut data, value);
+            version_block_value_len(&mut data, string, len)?;
+            version_block_end(&mut data, string)?;
+        }
+        version_block_end(&mut data, string_table)?;
+        version_block_end(&mut data, string_file_info)?;
+
+        let var_file_info = version_block_start(&mut data, "VarFileInfo", 1);
+        let var = version_block_start(&mut data, "Translation", 0);
+        data.extend_from_slice(&self.language.to_le_bytes());
+        data.extend_from_slice(&self.code_page.to_le_bytes());
+        version_block_value_len(&mut data, var, 4)?;
+        version_block_end(&mut data, var)?;
+        version_block_end(&mut data, var_file_info)?;
+
+        version_block_end(&mut data, root)?;
+        Ok(data)
+    }
+}
+
+/// Start a version block with the given key and type, and return its offset.
+///
+/// The block header contains the length, the value length, and the type.
+fn version_block_start(data: &mut Vec<u8>, key: &str, typ: u16) -> usize {
+    util::write_align(data, 4);
+    let offset = data.len();
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&typ.to_le_bytes());
+    write_utf16z(data, key);
+    util::write_align(data, 4);
+    offset
+}
+
+/// Set the value length of a version block.
+///
+/// For text values, `len` is in units of 16-bit characters.
+fn version_block_value_len(data: &mut [u8], offset: usize, len: usize) -> Result<()> {
+    let len = u16::try_from(len).map_err(|_| {
+        Error(format!(
+            "Version block value length 0x{:x} is too large",
+            len
+        ))
+    })?;
+    data[offset + 2..offset + 4].copy_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+/// Set the length of a version block to include everything written since it was started.
+fn version_block_end(data: &mut [u8], offset: usize) -> Result<()> {
+    let len = data.len() - offset;
+    let len = u16::try_from(len)
+        .map_err(|_| Error(format!("Version block length 0x{:x} is too large", len)))?;
+    data[offset..offset + 2].copy_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+/// Write a null terminated UTF-16 string, and return its length in characters.
+fn write_utf16z(data: &mut Vec<u8>, s: &str) -> usize {
+    let mut len = 0;
+    for c in s.encode_utf16().chain(Some(0)) {
+        data.extend_from_slice(&c.to_le_bytes());
+        len += 1;
+    }
+    len
+}
+
+/// An absolute address in section data that needs a base relocation.
+///
+/// Used with [`Writer::add_absolute_relocs`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AbsoluteRelocation {
+    /// The offset of the address within the section.
+    pub offset: u32,
+    /// The size in bits of the address. Must be 32 or 64.
+    pub size: u8,
+}
+
 struct RelocBlock {
     virtual_address: u32,
     count: u32,
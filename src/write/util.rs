@@ -25,6 +25,30 @@ pub trait WritableBuffer {
     /// Writes the specified slice of bytes at the end of the buffer.
     fn write_bytes(&mut self, val: &[u8]);
 
+    /// Writes the specified number of zero bytes at the end of the buffer.
+    ///
+    /// This is used for padding and for gaps such as zero initialized data.
+    /// The default implementation writes the bytes in chunks using
+    /// [`Self::write_bytes`].
+    fn write_zeros(&mut self, len: usize) {
+        const ZEROS: [u8; 1024] = [0; 1024];
+        let mut remaining = len;
+        while remaining != 0 {
+            let write_len = remaining.min(ZEROS.len());
+            self.write_bytes(&ZEROS[..write_len]);
+            remaining -= write_len;
+        }
+    }
+
+    /// Writes each of the specified slices of bytes at the end of the buffer.
+    ///
+    /// The default implementation calls [`Self::write_bytes`] for each slice.
+    fn write_bytes_vectored(&mut self, vals: &[&[u8]]) {
+        for val in vals {
+            self.write_bytes(val);
+        }
+    }
+
     /// Writes the specified `Pod` type at the end of the buffer.
     fn write_pod<T: Pod>(&mut self, val: &T)
     where
@@ -78,6 +102,11 @@ impl WritableBuffer for Vec<u8> {
         debug_assert!(self.len() + val.len() <= self.capacity());
         self.extend_from_slice(val)
     }
+
+    #[inline]
+    fn write_zeros(&mut self, len: usize) {
+        self.resize(self.len() + len, 0);
+    }
 }
 
 /// A [`WritableBuffer`] that streams data to a [`Write`](std::io::Write) implementation.
@@ -131,10 +160,7 @@ impl<W: io::Write> WritableBuffer for StreamingBuffer<W> {
     #[inline]
     fn resize(&mut self, new_len: usize) {
         debug_assert!(self.len <= new_len);
-        while self.len < new_len {
-            let write_amt = (new_len - self.len - 1) % 1024 + 1;
-            self.write_bytes(&[0; 1024][..write_amt]);
-        }
+        self.write_zeros(new_len.saturating_sub(self.len));
     }
 
     #[inline]
@@ -144,6 +170,55 @@ impl<W: io::Write> WritableBuffer for StreamingBuffer<W> {
         }
         self.len += val.len();
     }
+
+    fn write_zeros(&mut self, len: usize) {
+        if self.result.is_ok() {
+            let mut zeros = io::Read::take(io::repeat(0), len as u64);
+            self.result = io::copy(&mut zeros, &mut self.writer).map(|_| ());
+        }
+        self.len += len;
+    }
+
+    fn write_bytes_vectored(&mut self, vals: &[&[u8]]) {
+        if self.result.is_ok() {
+            self.result = write_all_vectored(&mut self.writer, vals);
+        }
+        self.len += vals.iter().map(|val| val.len()).sum::<usize>();
+    }
+}
+
+/// Write all of the slices, using vectored writes where possible.
+#[cfg(feature = "std")]
+fn write_all_vectored<W: io::Write>(writer: &mut W, mut vals: &[&[u8]]) -> io::Result<()> {
+    // The number of bytes of `vals[0]` that have already been written.
+    let mut offset = 0;
+    while let Some(first) = vals.first() {
+        if offset == first.len() {
+            vals = &vals[1..];
+            offset = 0;
+            continue;
+        }
+        let mut slices = Vec::with_capacity(vals.len());
+        slices.push(io::IoSlice::new(&first[offset..]));
+        slices.extend(vals[1..].iter().map(|val| io::IoSlice::new(val)));
+        let mut written = match writer.write_vectored(&slices) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => written,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        while written != 0 {
+            let remaining = vals[0].len() - offset;
+            if written < remaining {
+                offset += written;
+                break;
+            }
+            written -= remaining;
+            vals = &vals[1..];
+            offset = 0;
+        }
+    }
+    Ok(())
 }
 
 /// A trait for mutable byte slices.
@@ -230,8 +305,8 @@ pub(crate) fn align_u64(offset: u64, size: u64) -> u64 {
 }
 
 pub(crate) fn write_align(buffer: &mut dyn WritableBuffer, size: usize) {
-    let new_len = align(buffer.len(), size);
-    buffer.resize(new_len);
+    let len = buffer.len();
+    buffer.write_zeros(align(len, size) - len);
 }
 
 #[cfg(test)]
@@ -258,4 +333,45 @@ mod tests {
         assert_eq!(bytes.write_at(4, &u16::to_be(0x89ab)), Err(()));
         assert_eq!([].write_at(0, &u32::to_be(0x89ab)), Err(()));
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn streaming_buffer() {
+        /// A writer that only writes part of each vectored write.
+        struct PartialWriter(Vec<u8>);
+
+        impl io::Write for PartialWriter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let len = buf.len().min(3);
+                self.0.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+
+            fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+                let buf = bufs.iter().find(|buf| !buf.is_empty());
+                self.write(buf.map_or(&[], |buf| buf))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut expected = Vec::new();
+        WritableBuffer::reserve(&mut expected, 2016).unwrap();
+        expected.write_bytes(&[1, 2]);
+        expected.write_zeros(2000);
+        expected.write_bytes_vectored(&[&[3, 4, 5, 6], &[], &[7], &[8, 9, 10, 11, 12]]);
+        write_align(&mut expected, 16);
+        assert_eq!(expected.len(), 2016);
+
+        let mut buffer = StreamingBuffer::new(PartialWriter(Vec::new()));
+        buffer.write_bytes(&[1, 2]);
+        buffer.write_zeros(2000);
+        buffer.write_bytes_vectored(&[&[3, 4, 5, 6], &[], &[7], &[8, 9, 10, 11, 12]]);
+        write_align(&mut buffer, 16);
+        assert_eq!(buffer.len(), 2016);
+        assert!(buffer.result().is_ok());
+        assert_eq!(buffer.into_inner().0, expected);
+    }
 }
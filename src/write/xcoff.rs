@@ -112,7 +112,7 @@ impl<'a> Object<'a> {
         Ok(r_rsize + 1)
     }
 
-    pub(crate) fn xcoff_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+    pub(crate) fn xcoff_write(&self, buffer: &mut dyn WritableBuffer) -> Result<SymbolIndices> {
         let is_64 = match self.architecture.address_size().unwrap() {
             AddressSize::U8 | AddressSize::U16 | AddressSize::U32 => false,
             AddressSize::U64 => true,
@@ -583,6 +583,11 @@ impl<'a> Object<'a> {
         buffer.write_bytes(&strtab_data);
 
         debug_assert_eq!(offset, buffer.len());
-        Ok(())
+        Ok(SymbolIndices(
+            symbol_offsets
+                .iter()
+                .map(|offsets| Some(offsets.index))
+                .collect(),
+        ))
     }
 }
@@ -252,3 +252,938 @@ fn test_dynsym() {
         }
     }
 }
+
+#[test]
+fn test_hash_parameters() {
+    use object::read::elf::{FileHeader, VersionTable};
+
+    let mut builder = build::elf::Builder::new(object::Endianness::Little, true);
+    builder.header.e_type = elf::ET_DYN;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+
+    let section = builder.sections.add();
+    section.name = b".shstrtab"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.data = build::elf::SectionData::SectionString;
+
+    let section = builder.sections.add();
+    section.name = b".text"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_EXECINSTR) as u64;
+    section.sh_addralign = 16;
+    section.data = build::elf::SectionData::Data(vec![0xcc; 100].into());
+    let text_id = section.id();
+
+    let section = builder.sections.add();
+    section.name = b".dynsym"[..].into();
+    section.sh_type = elf::SHT_DYNSYM;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 8;
+    section.data = build::elf::SectionData::DynamicSymbol;
+    let dynsym_id = section.id();
+
+    let section = builder.sections.add();
+    section.name = b".dynstr"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 1;
+    section.data = build::elf::SectionData::DynamicString;
+    let dynstr_id = section.id();
+
+    // Leave the hash parameters as zero so that they are calculated.
+    let section = builder.sections.add();
+    section.name = b".hash"[..].into();
+    section.sh_type = elf::SHT_HASH;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 4;
+    section.data = build::elf::SectionData::Hash;
+    let hash_id = section.id();
+
+    let section = builder.sections.add();
+    section.name = b".gnu.hash"[..].into();
+    section.sh_type = elf::SHT_GNU_HASH;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 8;
+    section.data = build::elf::SectionData::GnuHash;
+    let gnu_hash_id = section.id();
+
+    let names: Vec<_> = (0..100).map(|i| format!("symbol{}", i)).collect();
+    for name in &names {
+        let symbol = builder.dynamic_symbols.add();
+        symbol.name = name.as_bytes().into();
+        symbol.set_st_info(elf::STB_GLOBAL, elf::STT_FUNC);
+        symbol.section = Some(text_id);
+    }
+
+    builder.set_section_sizes();
+
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_LOAD;
+    segment.p_flags = elf::PF_R;
+    segment.p_filesz = 0x1000;
+    segment.p_memsz = 0x1000;
+    segment.p_align = 8;
+    segment.append_section(builder.sections.get_mut(text_id));
+    segment.append_section(builder.sections.get_mut(dynsym_id));
+    segment.append_section(builder.sections.get_mut(dynstr_id));
+    segment.append_section(builder.sections.get_mut(hash_id));
+    segment.append_section(builder.sections.get_mut(gnu_hash_id));
+
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+
+    let data = &*buf;
+    let header = elf::FileHeader64::<object::Endianness>::parse(data).unwrap();
+    let endian = header.endian().unwrap();
+    let sections = header.sections(endian, data).unwrap();
+    let hash = sections.hash_header(endian, data).unwrap().unwrap();
+    assert_eq!(hash.bucket_count.get(endian), 97);
+    assert_eq!(hash.chain_count.get(endian), 101);
+    let gnu_hash = sections.gnu_hash_header(endian, data).unwrap().unwrap();
+    assert_eq!(gnu_hash.bucket_count.get(endian), 97);
+    assert_eq!(gnu_hash.bloom_count.get(endian), 32);
+    assert_eq!(gnu_hash.bloom_shift.get(endian), 11);
+    assert_eq!(gnu_hash.symbol_base.get(endian), 1);
+
+    let (hash, link) = sections.hash(endian, data).unwrap().unwrap();
+    let (gnu_hash, gnu_link) = sections.gnu_hash(endian, data).unwrap().unwrap();
+    assert_eq!(link, gnu_link);
+    let symbols = sections.symbol_table_by_index(endian, data, link).unwrap();
+    let versions = VersionTable::default();
+    for name in &names {
+        let (index, symbol) = hash
+            .find(
+                endian,
+                name.as_bytes(),
+                elf::hash(name.as_bytes()),
+                None,
+                &symbols,
+                &versions,
+            )
+            .unwrap();
+        assert_eq!(
+            symbols.symbol_name(endian, symbol).unwrap(),
+            name.as_bytes()
+        );
+        let (gnu_index, _) = gnu_hash
+            .find(
+                endian,
+                name.as_bytes(),
+                elf::gnu_hash(name.as_bytes()),
+                None,
+                &symbols,
+                &versions,
+            )
+            .unwrap();
+        assert_eq!(index, gnu_index);
+    }
+}
+
+// Build an executable with a text segment and a data segment.
+fn build_executable() -> (Vec<u8>, build::elf::SectionId, build::elf::SectionId) {
+    let mut builder = build::elf::Builder::new(object::Endianness::Little, true);
+    builder.header.e_type = elf::ET_EXEC;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+
+    let section = builder.sections.add();
+    section.name = b".shstrtab"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.data = build::elf::SectionData::SectionString;
+
+    let section = builder.sections.add();
+    section.name = b".text"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_EXECINSTR) as u64;
+    section.sh_addr = 0x401000;
+    section.sh_offset = 0x1000;
+    section.sh_size = 4;
+    section.sh_addralign = 16;
+    section.data = build::elf::SectionData::Data(vec![0xcc; 4].into());
+    let text_id = section.id();
+
+    let section = builder.sections.add();
+    section.name = b".data"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_WRITE) as u64;
+    section.sh_addr = 0x403000;
+    section.sh_offset = 0x2000;
+    section.sh_size = 4;
+    section.sh_addralign = 8;
+    section.data = build::elf::SectionData::Data(b"data"[..].into());
+    let data_id = section.id();
+
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_PHDR;
+    segment.p_flags = elf::PF_R;
+    segment.p_offset = 0x40;
+    segment.p_vaddr = 0x400040;
+    segment.p_paddr = 0x400040;
+    segment.p_filesz = 5 * 0x38;
+    segment.p_memsz = 5 * 0x38;
+    segment.p_align = 8;
+
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_LOAD;
+    segment.p_flags = elf::PF_R;
+    segment.p_offset = 0;
+    segment.p_vaddr = 0x400000;
+    segment.p_paddr = 0x400000;
+    segment.p_filesz = 0x1000;
+    segment.p_memsz = 0x1000;
+    segment.p_align = 0x1000;
+
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_LOAD;
+    segment.p_flags = elf::PF_R | elf::PF_X;
+    segment.p_offset = 0x1000;
+    segment.p_vaddr = 0x401000;
+    segment.p_paddr = 0x401000;
+    segment.p_filesz = 4;
+    segment.p_memsz = 4;
+    segment.p_align = 0x1000;
+    segment.sections.push(text_id);
+
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_LOAD;
+    segment.p_flags = elf::PF_R | elf::PF_W;
+    segment.p_offset = 0x2000;
+    segment.p_vaddr = 0x403000;
+    segment.p_paddr = 0x403000;
+    segment.p_filesz = 4;
+    segment.p_memsz = 4;
+    segment.p_align = 0x1000;
+    segment.sections.push(data_id);
+
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_GNU_STACK;
+    segment.p_flags = elf::PF_R | elf::PF_W;
+    segment.p_align = 0x10;
+
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+    (buf, text_id, data_id)
+}
+
+// Test that new allocated sections are placed in new segments.
+#[test]
+fn test_place_sections() {
+    let (buf, text_id, _) = build_executable();
+
+    let mut builder = build::elf::Builder::read(&*buf).unwrap();
+    builder.load_align = 0x1000;
+    let section = builder.sections.add();
+    section.name = b".rodata.new"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 8;
+    section.data = build::elf::SectionData::Data(b"rodata"[..].into());
+    let section = builder.sections.add();
+    section.name = b".data.new"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_WRITE) as u64;
+    section.sh_addralign = 8;
+    section.data = build::elf::SectionData::Data(b"data"[..].into());
+    builder.place_sections().unwrap();
+
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+
+    let builder = build::elf::Builder::read(&*buf).unwrap();
+    assert_eq!(builder.segments.count(), 7);
+    for segment in &builder.segments {
+        if segment.p_type == elf::PT_PHDR {
+            assert_eq!(segment.p_filesz, 7 * 0x38);
+        }
+    }
+    let text = builder.sections.get(text_id);
+    assert_eq!(text.sh_offset, 0x1000);
+    assert_eq!(text.sh_addr, 0x401000);
+    for (name, data, p_flags) in [
+        (&b".rodata.new"[..], &b"rodata"[..], elf::PF_R),
+        (&b".data.new"[..], &b"data"[..], elf::PF_R | elf::PF_W),
+    ] {
+        let section = builder
+            .sections
+            .iter()
+            .find(|section| section.name.as_slice() == name)
+            .unwrap();
+        match &section.data {
+            build::elf::SectionData::Data(section_data) => {
+                assert_eq!(section_data.as_slice(), data)
+            }
+            _ => panic!("unexpected section data"),
+        }
+        assert_ne!(section.sh_offset, 0);
+        assert_eq!(section.sh_offset % 0x1000, section.sh_addr % 0x1000);
+        let segment = builder
+            .segments
+            .iter()
+            .find(|segment| segment.is_load() && segment.contains_offset(section.sh_offset))
+            .unwrap();
+        assert_eq!(segment.p_flags, p_flags);
+        assert!(segment.contains_address(section.sh_addr));
+        assert_eq!(
+            segment.address_from_offset(section.sh_offset),
+            section.sh_addr
+        );
+    }
+}
+
+// Test that data that isn't part of any section or header is preserved.
+#[test]
+fn test_padding() {
+    let (mut buf, _, _) = build_executable();
+    // Data within a segment, between segments, and at the end of the file.
+    buf[0x800..0x808].copy_from_slice(b"segment0");
+    buf[0x1800..0x1808].copy_from_slice(b"between0");
+    buf.extend_from_slice(b"overlay");
+
+    let builder = build::elf::Builder::read(&*buf).unwrap();
+    let padding: Vec<_> = builder
+        .padding
+        .iter()
+        .map(|padding| padding.offset)
+        .collect();
+    assert_eq!(padding, [0x158, 0x1004]);
+    assert_eq!(builder.overlay.as_slice(), b"overlay");
+    let mut out = Vec::new();
+    builder.write(&mut out).unwrap();
+    assert_eq!(out, buf);
+
+    let mut builder = build::elf::Builder::read(&*buf).unwrap();
+    builder.padding.clear();
+    builder.overlay = Default::default();
+    let mut out = Vec::new();
+    builder.write(&mut out).unwrap();
+    assert_eq!(out.len(), buf.len() - 7);
+    assert_eq!(&out[0x800..0x808], &[0; 8]);
+    assert_eq!(&out[0x1800..0x1808], &[0; 8]);
+}
+
+// Test that segments with invalid file ranges are ignored when reading padding.
+#[test]
+fn test_padding_invalid_segment() {
+    let (buf, _, _) = build_executable();
+    let builder = build::elf::Builder::read(&*buf).unwrap();
+    let phoff = builder.header.e_phoff as usize;
+    let index = builder
+        .segments
+        .iter()
+        .position(|segment| segment.p_type == elf::PT_GNU_STACK)
+        .unwrap();
+    // The p_offset and p_filesz fields of an Elf64_Phdr.
+    let phdr = phoff + index * 56;
+    let set_range = |buf: &mut Vec<u8>, p_offset: u64, p_filesz: u64| {
+        buf[phdr + 8..phdr + 16].copy_from_slice(&p_offset.to_le_bytes());
+        buf[phdr + 32..phdr + 40].copy_from_slice(&p_filesz.to_le_bytes());
+    };
+
+    for (p_offset, p_filesz) in [
+        (0xffff_ffff_ffff_fff0, 0),
+        (0xffff_ffff_ffff_fff0, 0x100),
+        (0x1000, 0x10_0000),
+    ] {
+        let mut buf = buf.clone();
+        set_range(&mut buf, p_offset, p_filesz);
+        let builder = build::elf::Builder::read(&*buf).unwrap();
+        assert!(builder.padding.is_empty());
+        assert!(builder.overlay.is_empty());
+    }
+}
+
+// Test extending a PT_LOAD segment and adding segments.
+#[test]
+fn test_segment_editing() {
+    let (buf, text_id, data_id) = build_executable();
+
+    let mut builder = build::elf::Builder::read(&*buf).unwrap();
+    let section = builder.sections.add();
+    section.name = b".text.new"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_EXECINSTR) as u64;
+    section.sh_addralign = 16;
+    section.data = build::elf::SectionData::Data(vec![0x90; 0x1000].into());
+    let new_id = section.id();
+    builder.set_section_sizes();
+
+    let text_segment = builder
+        .segments
+        .iter()
+        .find(|segment| segment.sections.contains(&text_id))
+        .unwrap()
+        .id();
+    builder.append_load_section(text_segment, new_id).unwrap();
+    let new_section = builder.sections.get(new_id);
+    assert_eq!(new_section.sh_addr, 0x401010);
+    assert_eq!(new_section.sh_offset, 0x1010);
+
+    // The segment can't be extended into the data segment.
+    let section = builder.sections.add();
+    section.name = b".text.big"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_EXECINSTR) as u64;
+    section.sh_size = 0x2000;
+    section.sh_addralign = 16;
+    section.data = build::elf::SectionData::Data(vec![0x90; 0x2000].into());
+    let big_id = section.id();
+    assert!(builder.append_load_section(text_segment, big_id).is_err());
+    builder.sections.get_mut(big_id).delete = true;
+
+    // Add a segment covering the data section, and delete the PT_GNU_STACK segment.
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_GNU_RELRO;
+    segment.p_flags = elf::PF_R;
+    segment.p_align = 1;
+    segment.append_section(builder.sections.get_mut(data_id));
+    for segment in &mut builder.segments {
+        if segment.p_type == elf::PT_GNU_STACK {
+            segment.delete = true;
+        }
+    }
+    builder.place_sections().unwrap();
+
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+
+    let builder = build::elf::Builder::read(&*buf).unwrap();
+    assert_eq!(builder.segments.count(), 5);
+    let data = builder.sections.get(data_id);
+    assert_eq!(data.sh_addr, 0x403000);
+    assert_eq!(data.sh_offset, 0x3000);
+    match &data.data {
+        build::elf::SectionData::Data(section_data) => {
+            assert_eq!(section_data.as_slice(), b"data")
+        }
+        _ => panic!("unexpected section data"),
+    }
+    let new_section = builder.sections.get(new_id);
+    assert_eq!(new_section.sh_addr, 0x401010);
+    assert_eq!(new_section.sh_offset, 0x1010);
+    for segment in &builder.segments {
+        match segment.p_type {
+            elf::PT_PHDR => assert_eq!(segment.p_filesz, 5 * 0x38),
+            elf::PT_GNU_RELRO => {
+                assert_eq!(segment.p_offset, 0x3000);
+                assert_eq!(segment.p_vaddr, 0x403000);
+                assert_eq!(segment.p_memsz, 4);
+            }
+            elf::PT_LOAD if segment.p_flags & elf::PF_X != 0 => {
+                assert_eq!(segment.p_offset, 0x1000);
+                assert_eq!(segment.p_filesz, 0x1010);
+            }
+            elf::PT_LOAD if segment.p_flags & elf::PF_W != 0 => {
+                assert_eq!(segment.p_offset, 0x3000);
+                assert_eq!(segment.p_vaddr, 0x403000);
+            }
+            elf::PT_LOAD => assert_eq!(segment.p_offset, 0),
+            _ => panic!("unexpected segment"),
+        }
+    }
+}
+
+// Test adding symbols and modifying symbol attributes.
+#[test]
+fn test_symbol_editing() {
+    use object::read::elf::{FileHeader, Sym};
+
+    let mut builder = build::elf::Builder::new(object::Endianness::Little, true);
+    builder.header.e_type = elf::ET_DYN;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+    builder.load_align = 0x1000;
+
+    let mut add_section = |name: &'static [u8], sh_type, sh_flags: u32, data| {
+        let section = builder.sections.add();
+        section.name = name.into();
+        section.sh_type = sh_type;
+        section.sh_flags = sh_flags.into();
+        section.sh_addralign = 8;
+        section.data = data;
+        section.id()
+    };
+    add_section(
+        b".shstrtab",
+        elf::SHT_STRTAB,
+        0,
+        build::elf::SectionData::SectionString,
+    );
+    let text_id = add_section(
+        b".text",
+        elf::SHT_PROGBITS,
+        elf::SHF_ALLOC | elf::SHF_EXECINSTR,
+        build::elf::SectionData::Data(vec![0xcc; 0x20].into()),
+    );
+    let mut alloc_ids = vec![text_id];
+    for (name, sh_type, data) in [
+        (
+            &b".dynsym"[..],
+            elf::SHT_DYNSYM,
+            build::elf::SectionData::DynamicSymbol,
+        ),
+        (
+            b".dynstr",
+            elf::SHT_STRTAB,
+            build::elf::SectionData::DynamicString,
+        ),
+        (
+            b".gnu.hash",
+            elf::SHT_GNU_HASH,
+            build::elf::SectionData::GnuHash,
+        ),
+        (
+            b".gnu.version",
+            elf::SHT_GNU_VERSYM,
+            build::elf::SectionData::GnuVersym,
+        ),
+    ] {
+        alloc_ids.push(add_section(name, sh_type, elf::SHF_ALLOC, data));
+    }
+    add_section(
+        b".symtab",
+        elf::SHT_SYMTAB,
+        0,
+        build::elf::SectionData::Symbol,
+    );
+    add_section(
+        b".strtab",
+        elf::SHT_STRTAB,
+        0,
+        build::elf::SectionData::String,
+    );
+
+    for name in ["first", "second"] {
+        let symbol = builder.dynamic_symbols.add();
+        symbol.name = name.as_bytes().into();
+        symbol.set_st_info(elf::STB_GLOBAL, elf::STT_FUNC);
+        symbol.section = Some(text_id);
+        symbol.version = build::elf::VersionId::global();
+        let symbol = builder.symbols.add();
+        symbol.name = name.as_bytes().into();
+        symbol.set_st_info(elf::STB_GLOBAL, elf::STT_FUNC);
+        symbol.section = Some(text_id);
+    }
+
+    builder.set_section_sizes();
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_LOAD;
+    segment.p_flags = elf::PF_R | elf::PF_X;
+    segment.p_offset = 0x1000;
+    segment.p_vaddr = 0x1000;
+    segment.p_paddr = 0x1000;
+    segment.p_align = 0x1000;
+    for id in &alloc_ids {
+        segment.append_section(builder.sections.get_mut(*id));
+    }
+
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+
+    // Add new symbols and modify the existing symbols.
+    let mut builder = build::elf::Builder::read(&*buf).unwrap();
+    for symbol in &mut builder.dynamic_symbols {
+        if symbol.name.as_slice() == b"first" {
+            symbol.set_st_bind(elf::STB_WEAK);
+            symbol.set_st_visibility(elf::STV_PROTECTED);
+            symbol.st_size = 0x10;
+        }
+    }
+    for symbol in &mut builder.symbols {
+        if symbol.name.as_slice() == b"second" {
+            symbol.set_st_bind(elf::STB_LOCAL);
+            symbol.set_st_type(elf::STT_OBJECT);
+        }
+    }
+    let symbol = builder.dynamic_symbols.add();
+    symbol.name = b"added"[..].into();
+    symbol.set_st_info(elf::STB_GLOBAL, elf::STT_FUNC);
+    symbol.set_st_visibility(elf::STV_DEFAULT);
+    symbol.section = Some(text_id);
+    symbol.st_value = 0x1010;
+    symbol.st_size = 0x10;
+    symbol.version = build::elf::VersionId::global();
+    assert!(!symbol.is_undefined());
+    let symbol = builder.symbols.add();
+    symbol.name = b"added"[..].into();
+    symbol.set_st_info(elf::STB_GLOBAL, elf::STT_FUNC);
+    symbol.section = Some(text_id);
+    symbol.st_value = 0x1010;
+    assert_eq!(builder.dynamic_symbols.count_defined(), 3);
+
+    // The dynamic sections may need to move because they are larger.
+    builder.place_sections().unwrap();
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+
+    let data = &*buf;
+    let header = elf::FileHeader64::<object::Endianness>::parse(data).unwrap();
+    let endian = header.endian().unwrap();
+    let sections = header.sections(endian, data).unwrap();
+
+    let symbols = sections.symbols(endian, data, elf::SHT_SYMTAB).unwrap();
+    let symtab = sections.section(symbols.section()).unwrap();
+    // Null symbol and "second" are local.
+    assert_eq!(symtab.sh_info.get(endian), 2);
+    let second = symbols.symbol(object::SymbolIndex(1)).unwrap();
+    assert_eq!(symbols.symbol_name(endian, second).unwrap(), b"second");
+    assert_eq!(second.st_type(), elf::STT_OBJECT);
+
+    let dynamic_symbols = sections.symbols(endian, data, elf::SHT_DYNSYM).unwrap();
+    assert_eq!(dynamic_symbols.len(), 4);
+    let versions = sections.versions(endian, data).unwrap().unwrap();
+    let (gnu_hash, link) = sections.gnu_hash(endian, data).unwrap().unwrap();
+    assert_eq!(link, dynamic_symbols.section());
+    for name in [&b"first"[..], b"second", b"added"] {
+        let (index, symbol) = gnu_hash
+            .find(
+                endian,
+                name,
+                elf::gnu_hash(name),
+                None,
+                &dynamic_symbols,
+                &versions,
+            )
+            .unwrap();
+        assert_eq!(versions.version_index(endian, index).0, elf::VER_NDX_GLOBAL);
+        match name {
+            b"first" => {
+                assert_eq!(symbol.st_bind(), elf::STB_WEAK);
+                assert_eq!(symbol.st_visibility(), elf::STV_PROTECTED);
+                assert_eq!(symbol.st_size(endian), 0x10);
+            }
+            b"added" => {
+                assert_eq!(symbol.st_bind(), elf::STB_GLOBAL);
+                assert_eq!(symbol.st_value(endian), 0x1010);
+            }
+            _ => assert_eq!(symbol.st_bind(), elf::STB_GLOBAL),
+        }
+    }
+}
+
+// Test editing arbitrary dynamic entries.
+#[test]
+fn test_dynamic_editing() {
+    const DT_CUSTOM: u32 = 0x6000_0010;
+
+    let mut builder = build::elf::Builder::new(object::Endianness::Little, true);
+    builder.header.e_type = elf::ET_DYN;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+
+    let section = builder.sections.add();
+    section.name = b".shstrtab"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.data = build::elf::SectionData::SectionString;
+
+    let section = builder.sections.add();
+    section.name = b".dynstr"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 1;
+    section.data = build::elf::SectionData::DynamicString;
+    let dynstr_id = section.id();
+
+    let section = builder.sections.add();
+    section.name = b".dynsym"[..].into();
+    section.sh_type = elf::SHT_DYNSYM;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 8;
+    section.data = build::elf::SectionData::DynamicSymbol;
+    let dynsym_id = section.id();
+
+    let section = builder.sections.add();
+    section.name = b".dynamic"[..].into();
+    section.sh_type = elf::SHT_DYNAMIC;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_WRITE) as u64;
+    section.sh_addralign = 8;
+    section.sh_link_section = Some(dynstr_id);
+    section.data = build::elf::SectionData::Dynamic(vec![
+        build::elf::Dynamic::String {
+            tag: elf::DT_NEEDED,
+            val: b"libc.so.6"[..].into(),
+        },
+        build::elf::Dynamic::Integer {
+            tag: elf::DT_DEBUG,
+            val: 0,
+        },
+        build::elf::Dynamic::Auto {
+            tag: elf::DT_STRTAB,
+        },
+        build::elf::Dynamic::Auto { tag: elf::DT_STRSZ },
+        build::elf::Dynamic::Integer {
+            tag: elf::DT_FLAGS_1,
+            val: u64::from(elf::DF_1_NODELETE),
+        },
+    ]);
+    let dynamic_id = section.id();
+
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_DYNAMIC;
+    segment.p_flags = elf::PF_R | elf::PF_W;
+    segment.sections.push(dynamic_id);
+
+    assert!(builder.dynamic_entry(elf::DT_SONAME).is_none());
+    builder
+        .set_dynamic(build::elf::Dynamic::String {
+            tag: elf::DT_SONAME,
+            val: b"libtest.so"[..].into(),
+        })
+        .unwrap();
+    builder
+        .insert_dynamic(
+            0,
+            build::elf::Dynamic::String {
+                tag: elf::DT_AUXILIARY,
+                val: b"libaux.so"[..].into(),
+            },
+        )
+        .unwrap();
+    builder
+        .insert_dynamic(
+            1,
+            build::elf::Dynamic::String {
+                tag: elf::DT_AUDIT,
+                val: b"libaudit.so"[..].into(),
+            },
+        )
+        .unwrap();
+    builder
+        .set_dynamic(build::elf::Dynamic::Integer {
+            tag: DT_CUSTOM,
+            val: 0x1234,
+        })
+        .unwrap();
+    assert_eq!(builder.remove_dynamic(elf::DT_DEBUG).unwrap(), 1);
+    assert_eq!(builder.remove_dynamic(elf::DT_DEBUG).unwrap(), 0);
+    builder
+        .update_dynamic_flags(
+            elf::DT_FLAGS_1,
+            u64::from(elf::DF_1_NOW | elf::DF_1_PIE),
+            u64::from(elf::DF_1_NODELETE),
+        )
+        .unwrap();
+    builder
+        .update_dynamic_flags(elf::DT_FLAGS, u64::from(elf::DF_BIND_NOW), 0)
+        .unwrap();
+    builder.move_dynamic(elf::DT_NEEDED, 0).unwrap();
+    assert!(builder.move_dynamic(elf::DT_DEBUG, 0).is_err());
+    assert!(builder.update_dynamic_flags(elf::DT_NEEDED, 1, 0).is_err());
+
+    builder.set_section_sizes();
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_LOAD;
+    segment.p_flags = elf::PF_R | elf::PF_W;
+    segment.p_offset = 0x1000;
+    segment.p_vaddr = 0x1000;
+    segment.p_paddr = 0x1000;
+    segment.p_align = 0x1000;
+    segment.append_section(builder.sections.get_mut(dynsym_id));
+    segment.append_section(builder.sections.get_mut(dynstr_id));
+    segment.append_section(builder.sections.get_mut(dynamic_id));
+    let dynamic = builder.sections.get(dynamic_id);
+    let (offset, addr, size) = (dynamic.sh_offset, dynamic.sh_addr, dynamic.sh_size);
+    for segment in &mut builder.segments {
+        if segment.p_type == elf::PT_DYNAMIC {
+            segment.p_offset = offset;
+            segment.p_vaddr = addr;
+            segment.p_paddr = addr;
+            segment.p_filesz = size;
+            segment.p_memsz = size;
+        }
+    }
+
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+
+    let builder = build::elf::Builder::read(&*buf).unwrap();
+    let tags: Vec<u32> = builder
+        .dynamic_data()
+        .unwrap()
+        .iter()
+        .map(|dynamic| dynamic.tag())
+        .collect();
+    assert_eq!(
+        tags,
+        [
+            elf::DT_NEEDED,
+            elf::DT_AUXILIARY,
+            elf::DT_AUDIT,
+            elf::DT_STRTAB,
+            elf::DT_STRSZ,
+            elf::DT_FLAGS_1,
+            elf::DT_SONAME,
+            DT_CUSTOM,
+            elf::DT_FLAGS,
+        ]
+    );
+    assert_eq!(
+        builder.dynamic_entry(elf::DT_AUDIT),
+        Some(&build::elf::Dynamic::String {
+            tag: elf::DT_AUDIT,
+            val: b"libaudit.so"[..].into(),
+        })
+    );
+    assert_eq!(
+        builder.dynamic_entry(elf::DT_FLAGS_1),
+        Some(&build::elf::Dynamic::Integer {
+            tag: elf::DT_FLAGS_1,
+            val: u64::from(elf::DF_1_NOW | elf::DF_1_PIE),
+        })
+    );
+    assert_eq!(
+        builder.dynamic_entry(DT_CUSTOM),
+        Some(&build::elf::Dynamic::Integer {
+            tag: DT_CUSTOM,
+            val: 0x1234,
+        })
+    );
+}
+
+#[test]
+fn test_build_id_hash() {
+    use build::elf::BuildIdHash;
+
+    let hex = |data: &[u8]| -> String { data.iter().map(|b| format!("{:02x}", b)).collect() };
+    assert_eq!(
+        hex(&BuildIdHash::Md5.hash(b"abc")),
+        "900150983cd24fb0d6963f7d28e17f72"
+    );
+    assert_eq!(
+        hex(&BuildIdHash::Sha1.hash(b"abc")),
+        "a9993e364706816aba3e25717850c26c9cd0d89d"
+    );
+    assert_eq!(
+        hex(&BuildIdHash::Sha1.hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+        "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+    );
+    assert_eq!(
+        BuildIdHash::XxHash64.hash(b""),
+        0xef46db3751d8e999u64.to_le_bytes()
+    );
+    assert_eq!(
+        BuildIdHash::XxHash64.hash(b"abc"),
+        0x44bc2cf5ad770999u64.to_le_bytes()
+    );
+    assert_eq!(
+        BuildIdHash::XxHash64.hash(b"Nobody inspects the spammish repetition"),
+        0xfbcea83c8a378bf1u64.to_le_bytes()
+    );
+}
+
+// Test changing the size of the build ID and recalculating it.
+#[test]
+fn test_build_id() {
+    use object::read::Object;
+
+    let mut builder = build::elf::Builder::new(object::Endianness::Little, true);
+    builder.header.e_type = elf::ET_EXEC;
+    builder.header.e_machine = elf::EM_X86_64;
+    builder.header.e_phoff = 0x40;
+    builder.load_align = 0x1000;
+
+    let section = builder.sections.add();
+    section.name = b".shstrtab"[..].into();
+    section.sh_type = elf::SHT_STRTAB;
+    section.data = build::elf::SectionData::SectionString;
+
+    let mut note = Vec::new();
+    note.extend_from_slice(&4u32.to_le_bytes());
+    note.extend_from_slice(&20u32.to_le_bytes());
+    note.extend_from_slice(&elf::NT_GNU_BUILD_ID.to_le_bytes());
+    note.extend_from_slice(b"GNU\0");
+    note.extend_from_slice(&[0x11; 20]);
+    let section = builder.sections.add();
+    section.name = b".note.gnu.build-id"[..].into();
+    section.sh_type = elf::SHT_NOTE;
+    section.sh_flags = elf::SHF_ALLOC as u64;
+    section.sh_addralign = 4;
+    section.data = build::elf::SectionData::Note(note.into());
+    let note_id = section.id();
+
+    let section = builder.sections.add();
+    section.name = b".text"[..].into();
+    section.sh_type = elf::SHT_PROGBITS;
+    section.sh_flags = (elf::SHF_ALLOC | elf::SHF_EXECINSTR) as u64;
+    section.sh_addralign = 16;
+    section.data = build::elf::SectionData::Data(vec![0xcc; 16].into());
+    let text_id = section.id();
+
+    builder.set_section_sizes();
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_NOTE;
+    segment.p_flags = elf::PF_R;
+    segment.p_align = 4;
+    segment.p_offset = 0x1000;
+    segment.p_vaddr = 0x1000;
+    segment.p_paddr = 0x1000;
+    let note_segment = segment.id();
+    let segment = builder.segments.add();
+    segment.p_type = elf::PT_LOAD;
+    segment.p_flags = elf::PF_R | elf::PF_X;
+    segment.p_offset = 0x1000;
+    segment.p_vaddr = 0x1000;
+    segment.p_paddr = 0x1000;
+    segment.p_align = 0x1000;
+    segment.append_section(builder.sections.get_mut(note_id));
+    segment.append_section(builder.sections.get_mut(text_id));
+    let note = builder.sections.get(note_id);
+    builder
+        .segments
+        .get_mut(note_segment)
+        .append_section_range(note);
+    builder
+        .segments
+        .get_mut(note_segment)
+        .sections
+        .push(note_id);
+
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+
+    // Shrink the build ID and calculate it.
+    let mut builder = build::elf::Builder::read(&*buf).unwrap();
+    assert_eq!(builder.build_id(), Some(&[0x11; 20][..]));
+    assert_eq!(builder.build_id_section(), Some(note_id));
+    builder.set_build_id(&[0; 8]).unwrap();
+    assert_eq!(builder.sections.get(note_id).sh_size, 24);
+    assert_eq!(builder.segments.get(note_segment).p_filesz, 24);
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+    let build_id =
+        build::elf::update_build_id(&mut buf, build::elf::BuildIdHash::XxHash64).unwrap();
+    assert_eq!(build_id.len(), 8);
+    let file = object::File::parse(&*buf).unwrap();
+    assert_eq!(file.build_id().unwrap(), Some(&*build_id));
+    assert!(build::elf::update_build_id(&mut buf, build::elf::BuildIdHash::Sha1).is_err());
+
+    // The build ID is calculated with the build ID set to zero.
+    let build_id_offset = buf
+        .windows(build_id.len())
+        .position(|window| window == &*build_id)
+        .unwrap();
+    let mut zeroed = buf.clone();
+    zeroed[build_id_offset..][..8].fill(0);
+    assert_eq!(build::elf::BuildIdHash::XxHash64.hash(&zeroed), build_id);
+
+    // Grow the build ID so that the note must be moved.
+    let mut builder = build::elf::Builder::read(&*buf).unwrap();
+    builder.set_build_id(&[0x22; 40]).unwrap();
+    builder.place_sections().unwrap();
+    let mut buf = Vec::new();
+    builder.write(&mut buf).unwrap();
+    let file = object::File::parse(&*buf).unwrap();
+    assert_eq!(file.build_id().unwrap(), Some(&[0x22; 40][..]));
+    let builder = build::elf::Builder::read(&*buf).unwrap();
+    let text = builder.sections.get(text_id);
+    assert_eq!(text.sh_offset, 0x1030);
+    let note = builder.sections.get(note_id);
+    assert_ne!(note.sh_offset, 0x1000);
+    assert_eq!(note.sh_size, 56);
+    for segment in &builder.segments {
+        if segment.p_type == elf::PT_NOTE {
+            assert_eq!(segment.p_offset, note.sh_offset);
+            assert_eq!(segment.p_filesz, 56);
+        }
+    }
+}
@@ -45,3 +45,154 @@ fn get_buildid_less_bad_elf() {
         b"\xf9\xc0\xc6\x05\xd3\x76\xbb\xa5\x7e\x02\xf5\x74\x50\x9d\x16\xcc\xe9\x9c\x1b\xf1"
     );
 }
+
+#[test]
+fn type_names() {
+    use object::elf::*;
+
+    assert_eq!(
+        name_for_sh_type(EM_X86_64, ELFOSABI_NONE, SHT_PROGBITS),
+        Some("SHT_PROGBITS")
+    );
+    assert_eq!(
+        name_for_sh_type(EM_X86_64, ELFOSABI_GNU, SHT_GNU_HASH),
+        Some("SHT_GNU_HASH")
+    );
+    assert_eq!(
+        name_for_sh_type(EM_SPARCV9, ELFOSABI_SOLARIS, SHT_SUNW_SIGNATURE),
+        Some("SHT_SUNW_SIGNATURE")
+    );
+    assert_eq!(
+        name_for_sh_type(EM_ARM, ELFOSABI_NONE, SHT_ARM_EXIDX),
+        Some("SHT_ARM_EXIDX")
+    );
+    assert_eq!(
+        name_for_sh_type(EM_X86_64, ELFOSABI_NONE, SHT_X86_64_UNWIND),
+        Some("SHT_X86_64_UNWIND")
+    );
+    assert_eq!(
+        name_for_sh_type(EM_386, ELFOSABI_NONE, SHT_LOPROC + 1),
+        None
+    );
+    assert_eq!(name_for_sh_type(EM_X86_64, ELFOSABI_NONE, SHT_LOUSER), None);
+
+    assert_eq!(
+        name_for_st_type(EM_X86_64, ELFOSABI_NONE, STT_FUNC),
+        Some("STT_FUNC")
+    );
+    assert_eq!(
+        name_for_st_type(EM_X86_64, ELFOSABI_GNU, STT_GNU_IFUNC),
+        Some("STT_GNU_IFUNC")
+    );
+    assert_eq!(
+        name_for_st_type(EM_PARISC, ELFOSABI_HPUX, STT_HP_OPAQUE),
+        Some("STT_HP_OPAQUE")
+    );
+    assert_eq!(
+        name_for_st_type(EM_ARM, ELFOSABI_NONE, STT_ARM_TFUNC),
+        Some("STT_ARM_TFUNC")
+    );
+    assert_eq!(name_for_st_type(EM_X86_64, ELFOSABI_NONE, STT_LOOS), None);
+
+    assert_eq!(
+        name_for_p_type(EM_X86_64, ELFOSABI_NONE, PT_LOAD),
+        Some("PT_LOAD")
+    );
+    assert_eq!(
+        name_for_p_type(EM_X86_64, ELFOSABI_GNU, PT_GNU_RELRO),
+        Some("PT_GNU_RELRO")
+    );
+    assert_eq!(
+        name_for_p_type(EM_PARISC, ELFOSABI_HPUX, PT_HP_TLS),
+        Some("PT_HP_TLS")
+    );
+    assert_eq!(
+        name_for_p_type(EM_ARM, ELFOSABI_NONE, PT_ARM_EXIDX),
+        Some("PT_ARM_EXIDX")
+    );
+    assert_eq!(name_for_p_type(EM_386, ELFOSABI_NONE, PT_LOPROC + 1), None);
+}
+
+#[test]
+fn arm_exidx() {
+    use object::read::elf::{
+        ArmExidxEntry, ArmExidxIterator, ArmExidxUnwind, ArmExtab, ArmPersonality,
+    };
+    use object::{elf, LittleEndian as LE};
+
+    fn prel31(place: u32, target: u32) -> u32 {
+        target.wrapping_sub(place) & 0x7fff_ffff
+    }
+    fn words(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    let exidx_address = 0x1000;
+    let extab_address = 0x2000;
+    let exidx = words(&[
+        prel31(0x1000, 0x100),
+        elf::EXIDX_CANTUNWIND,
+        prel31(0x1008, 0x200),
+        0x80a8_b0b0,
+        prel31(0x1010, 0x300),
+        prel31(0x1014, 0x2000),
+        prel31(0x1018, 0x400),
+        prel31(0x101c, 0x2008),
+    ]);
+    let extab = words(&[
+        0x8101_a8b0,
+        0xb1b2_b3b4,
+        prel31(0x2008, 0x5000),
+        0x1234_5678,
+    ]);
+
+    let entries = ArmExidxIterator::<elf::FileHeader32<LE>>::new(LE, exidx_address, &exidx)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        entries,
+        [
+            ArmExidxEntry {
+                function: 0x100,
+                unwind: ArmExidxUnwind::CantUnwind,
+            },
+            ArmExidxEntry {
+                function: 0x200,
+                unwind: ArmExidxUnwind::Inline(0x80a8_b0b0),
+            },
+            ArmExidxEntry {
+                function: 0x300,
+                unwind: ArmExidxUnwind::Table(0x2000),
+            },
+            ArmExidxEntry {
+                function: 0x400,
+                unwind: ArmExidxUnwind::Table(0x2008),
+            },
+        ]
+    );
+
+    let extab = ArmExtab::<elf::FileHeader32<LE>>::new(LE, extab_address, &extab);
+    assert!(extab.unwind(entries[0].unwind).unwrap().is_none());
+
+    let unwind = extab.unwind(entries[1].unwind).unwrap().unwrap();
+    assert_eq!(unwind.personality(), ArmPersonality::Compact(0));
+    assert_eq!(
+        unwind.instructions().collect::<Vec<_>>(),
+        [0xa8, 0xb0, 0xb0]
+    );
+
+    let unwind = extab.unwind(entries[2].unwind).unwrap().unwrap();
+    assert_eq!(unwind.personality(), ArmPersonality::Compact(1));
+    assert_eq!(
+        unwind.instructions().collect::<Vec<_>>(),
+        [0xa8, 0xb0, 0xb1, 0xb2, 0xb3, 0xb4]
+    );
+    assert_eq!(unwind.data().len(), 8);
+
+    let unwind = extab.unwind(entries[3].unwind).unwrap().unwrap();
+    assert_eq!(unwind.personality(), ArmPersonality::Generic(0x5000));
+    assert_eq!(unwind.instructions().count(), 0);
+    assert_eq!(unwind.data(), 0x1234_5678u32.to_le_bytes());
+
+    assert!(extab.unwind(ArmExidxUnwind::Table(0x1000)).is_err());
+}
@@ -47,3 +47,275 @@ fn test_go_macho() {
         }
     }
 }
+
+const DYLD_BASE: u64 = 0x1_8000_0000;
+
+/// Build a minimal dyld shared cache containing two images.
+///
+/// The cache has a single mapping with file offsets equal to the address
+/// offsets from `DYLD_BASE`. Each image has a `__TEXT` segment containing a
+/// `__text` section, and a symbol table in the shared `__LINKEDIT` segment.
+fn dyld_cache() -> Vec<u8> {
+    use object::endian::{U32, U64};
+    use object::macho;
+    use object::pod::bytes_of;
+    use object::LittleEndian as LE;
+
+    fn put(buf: &mut [u8], offset: u64, bytes: &[u8]) {
+        buf[offset as usize..][..bytes.len()].copy_from_slice(bytes);
+    }
+    fn name16(name: &[u8]) -> [u8; 16] {
+        let mut out = [0; 16];
+        out[..name.len()].copy_from_slice(name);
+        out
+    }
+
+    let mut buf = vec![0; 0x5000];
+
+    let mut magic = [0; 16];
+    magic.copy_from_slice(b"dyld_v1  x86_64\0");
+    put(&mut buf, 0, &magic);
+    // mapping_offset and mapping_count
+    put(&mut buf, 16, &0x300u32.to_le_bytes());
+    put(&mut buf, 20, &1u32.to_le_bytes());
+    // images_offset and images_count
+    put(&mut buf, 0x1c0, &0x320u32.to_le_bytes());
+    put(&mut buf, 0x1c4, &2u32.to_le_bytes());
+    let mapping = macho::DyldCacheMappingInfo {
+        address: U64::new(LE, DYLD_BASE),
+        size: U64::new(LE, 0x5000),
+        file_offset: U64::new(LE, 0),
+        max_prot: U32::new(LE, macho::VM_PROT_READ | macho::VM_PROT_EXECUTE),
+        init_prot: U32::new(LE, macho::VM_PROT_READ | macho::VM_PROT_EXECUTE),
+    };
+    put(&mut buf, 0x300, bytes_of(&mapping));
+
+    let images: [(&[u8], u64, u64); 2] = [
+        (b"/usr/lib/libfoo.dylib", 0x1000, 0x3000),
+        (b"/usr/lib/libbar.dylib", 0x2000, 0x3200),
+    ];
+    for (index, &(path, image_offset, symtab_offset)) in images.iter().enumerate() {
+        let path_offset = 0x360 + index as u64 * 0x20;
+        put(&mut buf, path_offset, path);
+        let info = macho::DyldCacheImageInfo {
+            address: U64::new(LE, DYLD_BASE + image_offset),
+            mod_time: U64::new(LE, 0),
+            inode: U64::new(LE, 0),
+            path_file_offset: U32::new(LE, path_offset as u32),
+            pad: U32::new(LE, 0),
+        };
+        put(&mut buf, 0x320 + index as u64 * 0x20, bytes_of(&info));
+
+        let text_size = core::mem::size_of::<macho::SegmentCommand64<LE>>()
+            + core::mem::size_of::<macho::Section64<LE>>();
+        let linkedit_size = core::mem::size_of::<macho::SegmentCommand64<LE>>();
+        let symtab_size = core::mem::size_of::<macho::SymtabCommand<LE>>();
+        let header = macho::MachHeader64 {
+            magic: U32::new(object::BigEndian, macho::MH_CIGAM_64),
+            cputype: U32::new(LE, macho::CPU_TYPE_X86_64),
+            cpusubtype: U32::new(LE, macho::CPU_SUBTYPE_X86_64_ALL),
+            filetype: U32::new(LE, macho::MH_DYLIB),
+            ncmds: U32::new(LE, 3),
+            sizeofcmds: U32::new(LE, (text_size + linkedit_size + symtab_size) as u32),
+            flags: U32::new(LE, macho::MH_DYLIB_IN_CACHE),
+            reserved: U32::new(LE, 0),
+        };
+        let mut offset = image_offset;
+        put(&mut buf, offset, bytes_of(&header));
+        offset += core::mem::size_of_val(&header) as u64;
+
+        let text = macho::SegmentCommand64 {
+            cmd: U32::new(LE, macho::LC_SEGMENT_64),
+            cmdsize: U32::new(LE, text_size as u32),
+            segname: name16(b"__TEXT"),
+            vmaddr: U64::new(LE, DYLD_BASE + image_offset),
+            vmsize: U64::new(LE, 0x1000),
+            fileoff: U64::new(LE, image_offset),
+            filesize: U64::new(LE, 0x1000),
+            maxprot: U32::new(LE, macho::VM_PROT_READ | macho::VM_PROT_EXECUTE),
+            initprot: U32::new(LE, macho::VM_PROT_READ | macho::VM_PROT_EXECUTE),
+            nsects: U32::new(LE, 1),
+            flags: U32::new(LE, 0),
+        };
+        put(&mut buf, offset, bytes_of(&text));
+        offset += core::mem::size_of_val(&text) as u64;
+        let section = macho::Section64 {
+            sectname: name16(b"__text"),
+            segname: name16(b"__TEXT"),
+            addr: U64::new(LE, DYLD_BASE + image_offset + 0x800),
+            size: U64::new(LE, 0x10),
+            offset: U32::new(LE, image_offset as u32 + 0x800),
+            align: U32::new(LE, 4),
+            reloff: U32::new(LE, 0),
+            nreloc: U32::new(LE, 0),
+            flags: U32::new(LE, macho::S_ATTR_PURE_INSTRUCTIONS),
+            reserved1: U32::new(LE, 0),
+            reserved2: U32::new(LE, 0),
+            reserved3: U32::new(LE, 0),
+        };
+        put(&mut buf, offset, bytes_of(&section));
+        offset += core::mem::size_of_val(&section) as u64;
+        put(&mut buf, image_offset + 0x800, &[0xc3 - index as u8; 0x10]);
+
+        let linkedit = macho::SegmentCommand64 {
+            cmd: U32::new(LE, macho::LC_SEGMENT_64),
+            cmdsize: U32::new(LE, linkedit_size as u32),
+            segname: name16(b"__LINKEDIT"),
+            vmaddr: U64::new(LE, DYLD_BASE + 0x3000),
+            vmsize: U64::new(LE, 0x2000),
+            fileoff: U64::new(LE, 0x3000),
+            filesize: U64::new(LE, 0x2000),
+            maxprot: U32::new(LE, macho::VM_PROT_READ),
+            initprot: U32::new(LE, macho::VM_PROT_READ),
+            nsects: U32::new(LE, 0),
+            flags: U32::new(LE, 0),
+        };
+        put(&mut buf, offset, bytes_of(&linkedit));
+        offset += core::mem::size_of_val(&linkedit) as u64;
+
+        let symtab = macho::SymtabCommand {
+            cmd: U32::new(LE, macho::LC_SYMTAB),
+            cmdsize: U32::new(LE, symtab_size as u32),
+            symoff: U32::new(LE, symtab_offset as u32),
+            nsyms: U32::new(LE, 1),
+            stroff: U32::new(LE, symtab_offset as u32 + 0x100),
+            strsize: U32::new(LE, 0x10),
+        };
+        put(&mut buf, offset, bytes_of(&symtab));
+
+        let nlist = macho::Nlist64 {
+            n_strx: U32::new(LE, 1),
+            n_type: macho::N_SECT | macho::N_EXT,
+            n_sect: 1,
+            n_desc: object::endian::U16::new(LE, 0),
+            n_value: object::endian::U64Bytes::new(LE, DYLD_BASE + image_offset + 0x800),
+        };
+        put(&mut buf, symtab_offset, bytes_of(&nlist));
+        let name = if index == 0 { b"\0_foo\0" } else { b"\0_bar\0" };
+        put(&mut buf, symtab_offset + 0x100, name);
+    }
+
+    // Local symbols for each image.
+    put(&mut buf, 0x48, &0x4000u64.to_le_bytes());
+    let info = macho::DyldCacheLocalSymbolsInfo {
+        nlist_offset: U32::new(LE, 0x40),
+        nlist_count: U32::new(LE, 2),
+        strings_offset: U32::new(LE, 0x80),
+        strings_size: U32::new(LE, 0x20),
+        entries_offset: U32::new(LE, 0x20),
+        entries_count: U32::new(LE, 2),
+    };
+    put(&mut buf, 0x4000, bytes_of(&info));
+    put(&mut buf, 0x4080, b"\0_foo_local\0_bar_local\0");
+    for (index, &(_, image_offset, _)) in images.iter().enumerate() {
+        let entry = macho::DyldCacheLocalSymbolsEntry64 {
+            dylib_offset: U64::new(LE, image_offset),
+            nlist_start_index: U32::new(LE, index as u32),
+            nlist_count: U32::new(LE, 1),
+        };
+        put(&mut buf, 0x4020 + index as u64 * 0x10, bytes_of(&entry));
+        let nlist = macho::Nlist64 {
+            n_strx: U32::new(LE, 1 + index as u32 * 11),
+            n_type: macho::N_SECT,
+            n_sect: 1,
+            n_desc: object::endian::U16::new(LE, 0),
+            n_value: object::endian::U64Bytes::new(LE, DYLD_BASE + image_offset + 0x808),
+        };
+        put(&mut buf, 0x4040 + index as u64 * 0x10, bytes_of(&nlist));
+    }
+    buf
+}
+
+#[test]
+fn dyld_cache_address() {
+    use object::read::macho::DyldCache;
+    use object::LittleEndian as LE;
+
+    let data = dyld_cache();
+    let cache = DyldCache::<LE>::parse(&*data, &[]).unwrap();
+
+    let mapping = cache.mapping_for_address(DYLD_BASE + 0x1800).unwrap();
+    assert_eq!(mapping.address(), DYLD_BASE);
+    assert_eq!(
+        mapping.address_to_file_offset(DYLD_BASE + 0x1800),
+        Some(0x1800)
+    );
+    assert!(cache.mapping_for_address(DYLD_BASE + 0x5000).is_none());
+
+    assert_eq!(
+        cache.read_bytes_at_address(DYLD_BASE + 0x1800, 0x10),
+        Ok(&[0xc3; 0x10][..])
+    );
+    assert!(cache
+        .read_bytes_at_address(DYLD_BASE + 0x4ff0, 0x20)
+        .is_err());
+    let magic = cache
+        .read_at_address::<object::endian::U32<LE>>(DYLD_BASE + 0x2000)
+        .unwrap();
+    assert_eq!(magic.get(LE), object::macho::MH_MAGIC_64);
+
+    let image = cache
+        .image_containing_address(DYLD_BASE + 0x1800)
+        .unwrap()
+        .unwrap();
+    assert_eq!(image.path(), Ok("/usr/lib/libfoo.dylib"));
+    let image = cache
+        .image_containing_address(DYLD_BASE + 0x2fff)
+        .unwrap()
+        .unwrap();
+    assert_eq!(image.path(), Ok("/usr/lib/libbar.dylib"));
+    // The shared `__LINKEDIT` segment does not belong to any image.
+    assert!(cache
+        .image_containing_address(DYLD_BASE + 0x3000)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn dyld_cache_extract() {
+    use object::read::macho::{DyldCache, MachOFile64};
+    use object::{macho, LittleEndian as LE, Object, ObjectSection, ObjectSegment, ObjectSymbol};
+
+    let data = dyld_cache();
+    let cache = DyldCache::<LE>::parse(&*data, &[]).unwrap();
+    let image = cache.images().nth(1).unwrap();
+    assert_eq!(image.path(), Ok("/usr/lib/libbar.dylib"));
+
+    let symbols = image
+        .local_symbols::<macho::MachHeader64<LE>>()
+        .unwrap()
+        .unwrap();
+    assert_eq!(symbols.len(), 1);
+
+    let bytes = image.extract().unwrap();
+    let file = MachOFile64::<LE>::parse(&*bytes).unwrap();
+    assert_eq!(
+        file.macho_header().flags.get(LE) & macho::MH_DYLIB_IN_CACHE,
+        0
+    );
+
+    let text = file.section_by_name("__text").unwrap();
+    assert_eq!(text.address(), DYLD_BASE + 0x2800);
+    assert_eq!(text.file_range(), Some((0x800, 0x10)));
+    assert_eq!(text.data(), Ok(&[0xc2; 0x10][..]));
+
+    let symbols = file
+        .symbols()
+        .map(|symbol| (symbol.name().unwrap(), symbol.address(), symbol.is_local()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [
+            ("_bar_local", DYLD_BASE + 0x2808, true),
+            ("_bar", DYLD_BASE + 0x2800, false),
+        ]
+    );
+
+    let linkedit = file
+        .segments()
+        .find(|segment| segment.name() == Ok(Some("__LINKEDIT")))
+        .unwrap();
+    assert_eq!(linkedit.file_range().0, 0x1000);
+
+    assert_eq!(object::verify::verify(&*bytes).unwrap(), Vec::new());
+}
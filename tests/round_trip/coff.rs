@@ -1,5 +1,5 @@
-use object::read::{Object, ObjectSection};
-use object::{read, write};
+use object::read::{Object, ObjectSection, ObjectSymbol};
+use object::{pe, read, write, LittleEndian};
 use object::{
     Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationFlags, RelocationKind,
     SymbolFlags, SymbolKind, SymbolScope,
@@ -56,3 +56,387 @@ fn reloc_overflow() {
     }
     assert_eq!(i, 0x10000);
 }
+
+#[test]
+fn bigobj() {
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    object.set_coff_bigobj(true);
+    object.add_file_symbol(b"a_file_name_longer_than_twenty_bytes.c".to_vec());
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3; 4], 4);
+    object.section_symbol(text);
+    object.add_symbol(write::Symbol {
+        name: b"f".to_vec(),
+        value: 0,
+        size: 4,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    let bytes = object.write().unwrap();
+
+    assert_eq!(
+        object::FileKind::parse(&*bytes).unwrap(),
+        object::FileKind::CoffBig
+    );
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.format(), BinaryFormat::Coff);
+    assert_eq!(object.architecture(), Architecture::X86_64);
+
+    let mut symbols = object.symbols();
+    let symbol = symbols.next().unwrap();
+    assert_eq!(symbol.kind(), SymbolKind::File);
+    assert_eq!(symbol.name(), Ok("a_file_name_longer_than_twenty_bytes.c"));
+
+    let symbol = symbols.next().unwrap();
+    assert_eq!(symbol.kind(), SymbolKind::Section);
+    assert_eq!(symbol.section_index(), Some(read::SectionIndex(1)));
+
+    let symbol = symbols.next().unwrap();
+    assert_eq!(symbol.name(), Ok("f"));
+    assert_eq!(symbol.kind(), SymbolKind::Text);
+    assert_eq!(symbol.section_index(), Some(read::SectionIndex(1)));
+    assert!(symbols.next().is_none());
+}
+
+#[test]
+fn bigobj_section_overflow() {
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let mut last = None;
+    for _ in 0..0x10000 {
+        let section = object.add_section(Vec::new(), b".text".to_vec(), object::SectionKind::Text);
+        last = Some(section);
+    }
+    let last = last.unwrap();
+    object.add_symbol(write::Symbol {
+        name: b"f".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(last),
+        flags: SymbolFlags::None,
+    });
+    let bytes = object.write().unwrap();
+
+    assert_eq!(
+        object::FileKind::parse(&*bytes).unwrap(),
+        object::FileKind::CoffBig
+    );
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.sections().count(), 0x10000);
+    let symbol = object.symbols().next().unwrap();
+    assert_eq!(symbol.name(), Ok("f"));
+    assert_eq!(symbol.section_index(), Some(read::SectionIndex(0x10000)));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compression_gnu() {
+    use std::io::Write;
+
+    let data = b"test data data data";
+    let len = data.len() as u32;
+
+    let mut buf = Vec::new();
+    buf.write_all(b"ZLIB\0\0\0\0").unwrap();
+    buf.write_all(&len.to_be_bytes()).unwrap();
+    let mut encoder = flate2::write::ZlibEncoder::new(buf, flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let section = object.add_section(
+        Vec::new(),
+        b".zdebug_info".to_vec(),
+        object::SectionKind::Other,
+    );
+    object.section_mut(section).set_data(compressed, 1);
+    let section = object.add_section(
+        Vec::new(),
+        b".debug_line".to_vec(),
+        object::SectionKind::Debug,
+    );
+    object.section_mut(section).set_data(&data[..], 1);
+    let bytes = object.write().unwrap();
+
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.format(), BinaryFormat::Coff);
+
+    let section = object.section_by_name(".zdebug_info").unwrap();
+    let uncompressed = section.uncompressed_data().unwrap();
+    assert_eq!(data, &*uncompressed);
+    assert_eq!(section.data_file_range().unwrap(), None);
+
+    let section = object.section_by_name(".debug_info").unwrap();
+    assert_eq!(section.name().unwrap(), ".zdebug_info");
+    let uncompressed = section.uncompressed_data().unwrap();
+    assert_eq!(data, &*uncompressed);
+
+    let section = object.section_by_name(".debug_line").unwrap();
+    let (offset, size) = section.data_file_range().unwrap().unwrap();
+    assert_eq!(size, data.len() as u64);
+    assert_eq!(&bytes[offset as usize..][..size as usize], data);
+}
+
+#[test]
+fn writer_aux_symbols() {
+    writer_aux_symbols_inner::<pe::ImageFileHeader>(false);
+    writer_aux_symbols_inner::<pe::AnonObjectHeaderBigobj>(true);
+}
+
+fn writer_aux_symbols_inner<Coff: read::coff::CoffHeader>(bigobj: bool) {
+    use object::write::coff::{
+        AuxSymbolFunction, AuxSymbolWeak, FileHeader, SectionHeader, Symbol, Writer,
+    };
+
+    let mut buffer = Vec::new();
+    let mut writer = if bigobj {
+        Writer::new_bigobj(&mut buffer)
+    } else {
+        Writer::new(&mut buffer)
+    };
+
+    writer.reserve_file_header();
+    writer.reserve_section_headers(1);
+    let text_offset = writer.reserve_section(4);
+    let text_name = writer.add_name(b".text");
+    let func_name = writer.add_name(b"a_long_function_name");
+    let weak_name = writer.add_name(b"weak");
+    let func_index = writer.reserve_symbol_index();
+    let func_aux = writer.reserve_aux_function();
+    let weak_index = writer.reserve_symbol_index();
+    let weak_aux = writer.reserve_aux_weak_external();
+    writer.reserve_symtab_strtab();
+
+    writer
+        .write_file_header(FileHeader {
+            machine: pe::IMAGE_FILE_MACHINE_AMD64,
+            time_date_stamp: 0,
+            characteristics: 0,
+        })
+        .unwrap();
+    writer.write_section_header(SectionHeader {
+        name: text_name,
+        size_of_raw_data: 4,
+        pointer_to_raw_data: text_offset,
+        characteristics: pe::IMAGE_SCN_CNT_CODE | pe::IMAGE_SCN_MEM_EXECUTE,
+        ..Default::default()
+    });
+    writer.write_section(&[0xc3; 4]);
+    writer.write_symbol(Symbol {
+        name: func_name,
+        value: 0,
+        section_number: 1,
+        typ: pe::IMAGE_SYM_DTYPE_FUNCTION << pe::IMAGE_SYM_DTYPE_SHIFT,
+        storage_class: pe::IMAGE_SYM_CLASS_EXTERNAL,
+        number_of_aux_symbols: func_aux,
+    });
+    writer.write_aux_function(AuxSymbolFunction {
+        tag_index: 0,
+        total_size: 4,
+        pointer_to_linenumber: 0,
+        pointer_to_next_function: 0,
+    });
+    writer.write_symbol(Symbol {
+        name: weak_name,
+        value: 0,
+        section_number: pe::IMAGE_SYM_UNDEFINED,
+        typ: 0,
+        storage_class: pe::IMAGE_SYM_CLASS_WEAK_EXTERNAL,
+        number_of_aux_symbols: weak_aux,
+    });
+    writer.write_aux_weak_external(AuxSymbolWeak {
+        weak_default_sym_index: func_index,
+        weak_search_type: pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS.into(),
+    });
+    writer.write_strtab();
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let object = read::coff::CoffFile::<_, Coff>::parse(&*buffer).unwrap();
+    let symbols = object.coff_symbol_table();
+    assert_eq!(symbols.len(), 4);
+    let aux = symbols
+        .aux_function(read::SymbolIndex(func_index as usize))
+        .unwrap();
+    assert_eq!(aux.total_size.get(LittleEndian), 4);
+    let aux = symbols
+        .get::<pe::ImageAuxSymbolWeak>(read::SymbolIndex(weak_index as usize), 1)
+        .unwrap();
+    assert_eq!(aux.weak_default_sym_index.get(LittleEndian), func_index);
+    assert_eq!(
+        aux.weak_search_type.get(LittleEndian),
+        u32::from(pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS)
+    );
+
+    let symbol = object
+        .symbol_by_index(read::SymbolIndex(func_index as usize))
+        .unwrap();
+    assert_eq!(symbol.name(), Ok("a_long_function_name"));
+    assert_eq!(symbol.kind(), SymbolKind::Text);
+    let symbol = object
+        .symbol_by_index(read::SymbolIndex(weak_index as usize))
+        .unwrap();
+    assert_eq!(symbol.name(), Ok("weak"));
+    assert!(symbol.is_weak());
+}
+
+#[test]
+fn import_library() {
+    use object::read::archive::{ArchiveFile, ArchiveKind};
+    use object::read::coff::{CoffFile, ImportFile, ImportName, ImportType};
+    use object::write::coff::{write_import_library, ImportLibraryExport, ImportLibraryFormat};
+
+    let exports = [
+        ImportLibraryExport {
+            name: b"func",
+            export_name: None,
+            ordinal_or_hint: 1,
+            import_type: pe::IMPORT_OBJECT_CODE,
+            name_type: pe::IMPORT_OBJECT_NAME,
+        },
+        ImportLibraryExport {
+            name: b"data",
+            export_name: None,
+            ordinal_or_hint: 0,
+            import_type: pe::IMPORT_OBJECT_DATA,
+            name_type: pe::IMPORT_OBJECT_NAME,
+        },
+        ImportLibraryExport {
+            name: b"by_ordinal",
+            export_name: None,
+            ordinal_or_hint: 7,
+            import_type: pe::IMPORT_OBJECT_CODE,
+            name_type: pe::IMPORT_OBJECT_ORDINAL,
+        },
+        ImportLibraryExport {
+            name: b"renamed",
+            export_name: Some(b"original"),
+            ordinal_or_hint: 2,
+            import_type: pe::IMPORT_OBJECT_CODE,
+            name_type: pe::IMPORT_OBJECT_NAME_EXPORTAS,
+        },
+    ];
+
+    for (format, kind) in [
+        (ImportLibraryFormat::Msvc, ArchiveKind::Coff),
+        (ImportLibraryFormat::Gnu, ArchiveKind::Gnu),
+    ] {
+        let mut buffer = Vec::new();
+        write_import_library(
+            &mut buffer,
+            b"a_long_library_name.dll",
+            pe::IMAGE_FILE_MACHINE_AMD64,
+            format,
+            &exports,
+        )
+        .unwrap();
+
+        let archive = ArchiveFile::parse(&*buffer).unwrap();
+        assert_eq!(archive.kind(), kind);
+
+        let symbols = archive
+            .symbols()
+            .unwrap()
+            .unwrap()
+            .map(|symbol| symbol.unwrap())
+            .collect::<Vec<_>>();
+        let mut names = symbols
+            .iter()
+            .map(|symbol| symbol.name())
+            .collect::<Vec<_>>();
+        if format == ImportLibraryFormat::Gnu {
+            names.sort();
+        }
+        assert_eq!(
+            names,
+            [
+                &b"__IMPORT_DESCRIPTOR_a_long_library_name"[..],
+                b"__NULL_IMPORT_DESCRIPTOR",
+                b"__imp_by_ordinal",
+                b"__imp_data",
+                b"__imp_func",
+                b"__imp_renamed",
+                b"by_ordinal",
+                b"func",
+                b"renamed",
+                b"\x7fa_long_library_name_NULL_THUNK_DATA",
+            ]
+        );
+
+        let members = archive
+            .members()
+            .map(|member| member.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(members.len(), 7);
+        for member in &members {
+            assert_eq!(member.name(), b"a_long_library_name.dll");
+        }
+
+        // Every symbol must refer to a member that defines it.
+        for symbol in &symbols {
+            let member = archive.member(symbol.offset()).unwrap();
+            let data = member.data(&*buffer).unwrap();
+            if let Ok(import) = ImportFile::parse(data) {
+                let name = symbol.name();
+                assert!(name == import.symbol() || name == [b"__imp_", import.symbol()].concat());
+            } else {
+                let object = CoffFile::<_>::parse(data).unwrap();
+                let defined = object
+                    .symbols()
+                    .any(|s| s.is_definition() && s.name_bytes() == Ok(symbol.name()));
+                assert!(defined);
+            }
+        }
+
+        let descriptor = CoffFile::<_>::parse(members[0].data(&*buffer).unwrap()).unwrap();
+        assert_eq!(descriptor.architecture(), Architecture::X86_64);
+        let idata2 = descriptor.section_by_name(".idata$2").unwrap();
+        assert_eq!(idata2.size(), 20);
+        assert_eq!(idata2.relocations().count(), 3);
+        let idata6 = descriptor.section_by_name(".idata$6").unwrap();
+        assert_eq!(idata6.data().unwrap(), b"a_long_library_name.dll\0");
+
+        let null_thunk = CoffFile::<_>::parse(members[2].data(&*buffer).unwrap()).unwrap();
+        let idata5 = null_thunk.section_by_name(".idata$5").unwrap();
+        assert_eq!(idata5.data().unwrap(), &[0; 8]);
+
+        let imports = members[3..]
+            .iter()
+            .map(|member| ImportFile::parse(member.data(&*buffer).unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        for import in &imports {
+            assert_eq!(import.architecture(), Architecture::X86_64);
+            assert_eq!(import.dll(), b"a_long_library_name.dll");
+        }
+        assert_eq!(imports[0].symbol(), b"func");
+        assert_eq!(imports[0].import(), ImportName::Name(b"func"));
+        assert_eq!(imports[0].import_type(), ImportType::Code);
+        assert_eq!(imports[1].symbol(), b"data");
+        assert_eq!(imports[1].import_type(), ImportType::Data);
+        assert_eq!(imports[2].import(), ImportName::Ordinal(7));
+        assert_eq!(imports[3].symbol(), b"renamed");
+        assert_eq!(imports[3].import(), ImportName::Name(b"original"));
+    }
+
+    // The export name must match the name type.
+    let mut buffer = Vec::new();
+    let export = ImportLibraryExport {
+        export_name: Some(b"original"),
+        ..exports[0]
+    };
+    assert!(write_import_library(
+        &mut buffer,
+        b"lib.dll",
+        pe::IMAGE_FILE_MACHINE_I386,
+        ImportLibraryFormat::Msvc,
+        &[export],
+    )
+    .is_err());
+}
@@ -142,7 +142,7 @@ fn elf_x86_64_common() {
     assert_eq!(symbol.scope(), SymbolScope::Linkage);
     assert!(!symbol.is_weak());
     assert!(!symbol.is_undefined());
-    assert_eq!(symbol.address(), 0);
+    assert_eq!(symbol.address(), 4);
     assert_eq!(symbol.size(), 4);
 
     let symbol = symbols.next().unwrap();
@@ -153,7 +153,7 @@ fn elf_x86_64_common() {
     assert_eq!(symbol.scope(), SymbolScope::Linkage);
     assert!(!symbol.is_weak());
     assert!(!symbol.is_undefined());
-    assert_eq!(symbol.address(), 0);
+    assert_eq!(symbol.address(), 8);
     assert_eq!(symbol.size(), 8);
 
     let symbol = symbols.next();
@@ -239,3 +239,49 @@ fn macho_x86_64_common() {
     let symbol = symbols.next();
     assert!(symbol.is_none(), "unexpected symbol {:?}", symbol);
 }
+
+#[test]
+fn bss_mode() {
+    for format in [BinaryFormat::Coff, BinaryFormat::Elf, BinaryFormat::MachO] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+        object.set_mangling(write::Mangling::None);
+        object.set_common_symbol_mode(write::CommonSymbolMode::Bss);
+
+        for (name, size) in [(&b"v1"[..], 4), (&b"v2"[..], 8)] {
+            let symbol = write::Symbol {
+                name: name.to_vec(),
+                value: 0,
+                size: 0,
+                kind: SymbolKind::Data,
+                scope: SymbolScope::Linkage,
+                weak: false,
+                section: write::SymbolSection::Undefined,
+                flags: SymbolFlags::None,
+            };
+            object.add_common_symbol(symbol, size, size);
+        }
+
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        assert_eq!(object.format(), format);
+        let bss = object
+            .sections()
+            .find(|section| section.kind() == SectionKind::UninitializedData)
+            .unwrap();
+        assert_eq!(bss.size(), 16);
+
+        for (name, address, size) in [("v1", 0, 4), ("v2", 8, 8)] {
+            let symbol = object
+                .symbols()
+                .find(|symbol| symbol.name() == Ok(name))
+                .unwrap();
+            assert_eq!(symbol.kind(), SymbolKind::Data, "{:?}", format);
+            assert_eq!(symbol.section_index(), Some(bss.index()), "{:?}", format);
+            assert_eq!(symbol.address(), bss.address() + address, "{:?}", format);
+            if format == BinaryFormat::Elf {
+                assert_eq!(symbol.size(), size);
+            }
+        }
+    }
+}
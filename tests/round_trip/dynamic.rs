@@ -0,0 +1,69 @@
+use object::read::dynamic::DynObject;
+use object::{read, write};
+use object::{
+    Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationFlags, RelocationKind,
+    SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+};
+
+#[test]
+fn dyn_object() {
+    for format in [BinaryFormat::Coff, BinaryFormat::Elf, BinaryFormat::MachO] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3; 16], 16);
+        let func = object.add_symbol(write::Symbol {
+            name: b"func".to_vec(),
+            value: 0,
+            size: 16,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        let data = object.add_section(Vec::new(), b".data.ptr".to_vec(), SectionKind::Data);
+        object.append_section_data(data, &[0; 8], 8);
+        object
+            .add_relocation(
+                data,
+                write::Relocation {
+                    offset: 0,
+                    symbol: func,
+                    addend: 0,
+                    flags: RelocationFlags::Generic {
+                        kind: RelocationKind::Absolute,
+                        encoding: RelocationEncoding::Generic,
+                        size: 64,
+                    },
+                },
+            )
+            .unwrap();
+        let bytes = object.write().unwrap();
+
+        let file = read::File::parse(&*bytes).unwrap();
+        let mut objects: Vec<Box<dyn DynObject>> = vec![Box::new(file)];
+        if format == BinaryFormat::Elf {
+            let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+            objects.push(Box::new(file));
+        }
+        for object in &objects {
+            assert_eq!(object.architecture(), Architecture::X86_64);
+            assert!(object.is_little_endian());
+
+            let section = object.sections().find(|s| s.data() == Ok(&[0; 8])).unwrap();
+            assert_eq!(section.relocations().count(), 1);
+            let section = object.section_by_index(section.index()).unwrap();
+            assert_eq!(section.size(), 8);
+
+            let symbol = object
+                .symbols()
+                .find(|s| s.name() == Ok("func") || s.name() == Ok("_func"))
+                .unwrap();
+            let name = symbol.name_bytes().unwrap();
+            assert_eq!(
+                object.symbol_by_name_bytes(name).unwrap().index(),
+                symbol.index()
+            );
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use object::read::elf::{FileHeader, SectionHeader};
-use object::read::{Object, ObjectSection, ObjectSymbol};
+use object::read::elf::{Dyn, FileHeader, SectionHeader};
+use object::read::{Object, ObjectSection, ObjectSegment, ObjectSymbol};
 use object::{
     elf, read, write, Architecture, BinaryFormat, Endianness, LittleEndian, SectionIndex,
-    SectionKind, SymbolFlags, SymbolKind, SymbolScope, SymbolSection, U32,
+    SectionKind, SymbolFlags, SymbolIndex, SymbolKind, SymbolScope, SymbolSection, U32,
 };
 use std::io::Write;
 
@@ -128,6 +128,11 @@ fn compression_zlib() {
     let section = object.section_by_name(".debug_info").unwrap();
     let uncompressed = section.uncompressed_data().unwrap();
     assert_eq!(data, &*uncompressed);
+
+    // Reuse a buffer that is larger than the data.
+    let mut buf = vec![0xff; 64];
+    section.uncompressed_data_into(&mut buf).unwrap();
+    assert_eq!(data, &*buf);
 }
 
 #[cfg(feature = "compression")]
@@ -165,6 +170,57 @@ fn compression_gnu() {
     let section = object.section_by_name(".zdebug_info").unwrap();
     let uncompressed = section.uncompressed_data().unwrap();
     assert_eq!(data, &*uncompressed);
+
+    // Reuse a buffer that is larger than the data.
+    let mut buf = vec![0xff; 64];
+    section.uncompressed_data_into(&mut buf).unwrap();
+    assert_eq!(data, &*buf);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compression_file() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xcc; 32], 1);
+    let bytes = object.write().unwrap();
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&bytes).unwrap();
+    let gzip = encoder.finish().unwrap();
+    assert_eq!(object::FileKind::parse(&*gzip), Ok(object::FileKind::Gzip));
+    assert!(read::File::parse(&*gzip).is_err());
+
+    let mut buffer = Vec::new();
+    let file = read::File::parse_decompressed(&gzip, &mut buffer).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Elf);
+    assert_eq!(file.architecture(), Architecture::X86_64);
+    let section = file.section_by_name(".text").unwrap();
+    assert_eq!(section.data(), Ok(&[0xcc; 32][..]));
+
+    // Uncompressed files are parsed directly.
+    let mut buffer = Vec::new();
+    let file = read::File::parse_decompressed(&bytes, &mut buffer).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Elf);
+    assert!(buffer.is_empty());
+
+    #[cfg(feature = "xz")]
+    {
+        let mut xz = Vec::new();
+        lzma_rs::xz_compress(&mut &*bytes, &mut xz).unwrap();
+        assert_eq!(object::FileKind::parse(&*xz), Ok(object::FileKind::Xz));
+
+        let mut buffer = Vec::new();
+        let file = read::File::parse_decompressed(&xz, &mut buffer).unwrap();
+        assert_eq!(file.format(), BinaryFormat::Elf);
+        let section = file.section_by_name(".text").unwrap();
+        assert_eq!(section.data(), Ok(&[0xcc; 32][..]));
+    }
+
+    // Truncated compressed data is an error.
+    let mut buffer = Vec::new();
+    assert!(read::File::parse_decompressed(&gzip[..gzip.len() / 2], &mut buffer).is_err());
 }
 
 #[test]
@@ -300,3 +356,1708 @@ fn gnu_property_inner<Elf: FileHeader<Endian = Endianness>>(architecture: Archit
     assert!(props.next().unwrap().is_none());
     assert!(notes.next().unwrap().is_none());
 }
+
+#[test]
+fn gnu_versions() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    writer.reserve_dynsym_section_index();
+    writer.reserve_dynstr_section_index();
+    writer.reserve_gnu_versym_section_index();
+    writer.reserve_gnu_verdef_section_index();
+    writer.reserve_gnu_verneed_section_index();
+    writer.reserve_shstrtab_section_index();
+
+    let defined = writer.add_dynamic_string(b"defined");
+    let undefined = writer.add_dynamic_string(b"undefined");
+    writer.reserve_null_dynamic_symbol_index();
+    writer.reserve_dynamic_symbol_index();
+    writer.reserve_dynamic_symbol_index();
+
+    writer.add_gnu_version_definition(elf::VER_FLG_BASE, 1, &[b"libtest.so"]);
+    writer.add_gnu_version_definition(0, 2, &[b"TEST_1.0"]);
+    writer.add_gnu_version_definition(0, 3, &[b"TEST_2.0", b"TEST_1.0"]);
+    writer.add_gnu_version_dependency(b"libc.so.6", &[(0, 4, b"GLIBC_2.2.5")]);
+
+    writer.reserve_dynsym();
+    writer.reserve_dynstr();
+    writer.reserve_gnu_versym();
+    writer.reserve_gnu_version_definitions();
+    writer.reserve_gnu_version_dependencies();
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_null_dynamic_symbol();
+    writer.write_dynamic_symbol(&object::write::elf::Sym {
+        name: Some(defined),
+        section: None,
+        st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+        st_other: 0,
+        st_shndx: elf::SHN_ABS,
+        st_value: 0x1000,
+        st_size: 0,
+    });
+    writer.write_dynamic_symbol(&object::write::elf::Sym {
+        name: Some(undefined),
+        section: None,
+        st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+        st_other: 0,
+        st_shndx: elf::SHN_UNDEF,
+        st_value: 0,
+        st_size: 0,
+    });
+    writer.write_dynstr();
+    writer.write_gnu_versyms(&[3, 4]);
+    writer.write_gnu_version_definitions();
+    writer.write_gnu_version_dependencies();
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    writer.write_dynsym_section_header(0, 1);
+    writer.write_dynstr_section_header(0);
+    writer.write_gnu_versym_section_header(0);
+    writer.write_gnu_verdef_section_header(0);
+    writer.write_gnu_verneed_section_header(0);
+    writer.write_shstrtab_section_header();
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let data = &*buffer;
+    let header = elf::FileHeader64::<Endianness>::parse(data).unwrap();
+    let endian = header.endian().unwrap();
+    let sections = header.sections(endian, data).unwrap();
+    let versions = sections.versions(endian, data).unwrap().unwrap();
+
+    let version = |index| {
+        let index = versions.version_index(endian, read::SymbolIndex(index));
+        versions.version(index).unwrap().unwrap()
+    };
+    let defined = version(1);
+    assert_eq!(defined.name(), b"TEST_2.0");
+    assert_eq!(defined.file(), None);
+    let undefined = version(2);
+    assert_eq!(undefined.name(), b"GLIBC_2.2.5");
+    assert_eq!(undefined.file(), Some(&b"libc.so.6"[..]));
+
+    let (mut verdefs, link) = sections.gnu_verdef(endian, data).unwrap().unwrap();
+    let strings = sections.strings(endian, data, link).unwrap();
+    let mut names = Vec::new();
+    while let Some((verdef, mut verdauxs)) = verdefs.next().unwrap() {
+        let mut aux_names = Vec::new();
+        while let Some(verdaux) = verdauxs.next().unwrap() {
+            aux_names.push(verdaux.name(endian, strings).unwrap());
+        }
+        names.push((verdef.vd_ndx.get(endian), aux_names));
+    }
+    assert_eq!(
+        names,
+        vec![
+            (1, vec![&b"libtest.so"[..]]),
+            (2, vec![&b"TEST_1.0"[..]]),
+            (3, vec![&b"TEST_2.0"[..], &b"TEST_1.0"[..]]),
+        ]
+    );
+}
+
+#[test]
+fn relr() {
+    relr_inner::<elf::FileHeader32<Endianness>>(false);
+    relr_inner::<elf::FileHeader64<Endianness>>(true);
+}
+
+fn relr_inner<Elf: FileHeader<Endian = Endianness>>(is_64: bool) {
+    let word_size = if is_64 { 8 } else { 4 };
+    let mut offsets = Vec::new();
+    for i in 0..200 {
+        if i % 3 != 1 {
+            offsets.push(0x1000 + i * word_size);
+        }
+    }
+    offsets.push(0x8000);
+    offsets.push(0x8000 + 64 * word_size);
+    offsets.push(0x10000);
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, is_64, &mut buffer);
+
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    writer.reserve_relr_section_index();
+    writer.reserve_dynamic_section_index();
+    writer.reserve_shstrtab_section_index();
+
+    let relr_offset = writer.reserve_relr(&offsets).unwrap();
+    writer.reserve_dynamic(4);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: if is_64 { elf::EM_X86_64 } else { elf::EM_386 },
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_relr();
+    writer.write_align_dynamic();
+    writer.write_relr_dynamics(relr_offset as u64);
+    writer.write_dynamic(elf::DT_NULL, 0);
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    writer.write_relr_section_header(relr_offset as u64);
+    writer.write_dynamic_section_header(0);
+    writer.write_shstrtab_section_header();
+    assert_eq!(writer.reserved_len(), writer.len());
+    let relr_size = writer.relr_size();
+
+    let data = &*buffer;
+    let header = Elf::parse(data).unwrap();
+    let endian = header.endian().unwrap();
+    let sections = header.sections(endian, data).unwrap();
+    let section = sections.section(SectionIndex(1)).unwrap();
+    assert_eq!(section.sh_type(endian), elf::SHT_RELR);
+    let relrs = section.relr(endian, data).unwrap().unwrap();
+    let decoded: Vec<u64> = relrs.map(Into::into).collect();
+    assert_eq!(decoded, offsets);
+
+    let section = sections.section(SectionIndex(2)).unwrap();
+    let (dynamics, _) = section.dynamic(endian, data).unwrap().unwrap();
+    let dynamics: Vec<(u64, u64)> = dynamics
+        .iter()
+        .map(|d| (d.d_tag(endian).into(), d.d_val(endian).into()))
+        .collect();
+    assert_eq!(
+        dynamics,
+        vec![
+            (elf::DT_RELR.into(), relr_offset as u64),
+            (elf::DT_RELRSZ.into(), relr_size as u64),
+            (elf::DT_RELRENT.into(), word_size),
+            (elf::DT_NULL.into(), 0),
+        ]
+    );
+}
+
+#[test]
+fn writer_validation() {
+    fn write(mistake: Option<&str>) -> write::Result<()> {
+        let mut buffer = Vec::new();
+        let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+        writer.set_validation(true);
+
+        writer.reserve_file_header();
+        writer.reserve_null_section_index();
+        writer.reserve_dynamic_section_index();
+        writer.reserve_shstrtab_section_index();
+        writer.reserve_dynamic(3);
+        writer.reserve_shstrtab();
+        writer.reserve_section_headers();
+
+        writer
+            .write_file_header(&object::write::elf::FileHeader {
+                os_abi: elf::ELFOSABI_NONE,
+                abi_version: 0,
+                e_type: elf::ET_DYN,
+                e_machine: elf::EM_X86_64,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .unwrap();
+        if mistake == Some("order") {
+            writer.write_shstrtab();
+        }
+        writer.write_align_dynamic();
+        writer.write_dynamic(elf::DT_FLAGS, 0);
+        if mistake != Some("size") {
+            writer.write_dynamic(elf::DT_FLAGS_1, 0);
+        }
+        writer.write_dynamic(elf::DT_NULL, 0);
+        if mistake != Some("order") {
+            writer.write_shstrtab();
+        }
+        writer.write_null_section_header();
+        writer.write_dynamic_section_header(0);
+        if mistake != Some("missing") {
+            writer.write_shstrtab_section_header();
+        }
+        writer.validate()
+    }
+
+    assert_eq!(write(None), Ok(()));
+    let error = write(Some("order")).unwrap_err().to_string();
+    assert!(error.contains("write of .shstrtab"), "{}", error);
+    assert!(error.contains("after file header"), "{}", error);
+    let error = write(Some("size")).unwrap_err().to_string();
+    assert!(error.contains("write of .shstrtab"), "{}", error);
+    assert!(error.contains("after .dynamic"), "{}", error);
+    let error = write(Some("missing")).unwrap_err().to_string();
+    assert!(error.contains("but reserved"), "{}", error);
+}
+
+#[test]
+fn segment_layout() {
+    use object::write::elf::{Class, SegmentLayout};
+
+    let mut layout = SegmentLayout::new(Class { is_64: true }, 0x400000, 0x1000);
+    let phdr = layout.add_segment(elf::PT_PHDR, elf::PF_R);
+    let interp = layout.add_segment(elf::PT_INTERP, elf::PF_R);
+    let text = layout.add_segment(elf::PT_LOAD, elf::PF_R | elf::PF_X);
+    let data = layout.add_segment(elf::PT_LOAD, elf::PF_R | elf::PF_W);
+    let dynamic = layout.add_segment(elf::PT_DYNAMIC, elf::PF_R | elf::PF_W);
+    let note = layout.add_segment(elf::PT_NOTE, elf::PF_R);
+
+    let interp_section = layout.add_section(text, 28, 1, false);
+    let note_section = layout.add_section(text, 0x24, 4, false);
+    let text_section = layout.add_section(text, 0x1234, 16, false);
+    let dynamic_section = layout.add_section(data, 0x100, 8, false);
+    let data_section = layout.add_section(data, 0x10, 32, false);
+    let bss_section = layout.add_section(data, 0x2000, 64, true);
+    layout.append_section(interp, interp_section);
+    layout.append_section(dynamic, dynamic_section);
+    layout.append_section(note, note_section);
+    layout.calculate().unwrap();
+
+    let headers_size = 0x40 + 6 * 0x38;
+    assert_eq!(layout.headers_size(), headers_size);
+    for index in [
+        interp_section,
+        note_section,
+        text_section,
+        dynamic_section,
+        data_section,
+    ] {
+        let section = layout.section(index);
+        assert_eq!(section.offset % 0x1000, section.address % 0x1000);
+        assert_eq!(section.address % section.align, 0);
+    }
+    assert_eq!(layout.section(interp_section).offset, headers_size);
+    assert_eq!(layout.section(note_section).offset, 0x1ac);
+    assert_eq!(layout.section(text_section).offset, 0x1d0);
+    assert_eq!(layout.section(dynamic_section).offset, 0x1408);
+    assert_eq!(layout.section(dynamic_section).address, 0x402408);
+    assert_eq!(layout.section(data_section).offset, 0x1520);
+    assert_eq!(layout.section(bss_section).offset, 0x1530);
+    assert_eq!(layout.section(bss_section).address, 0x402540);
+    assert_eq!(layout.file_size(), 0x1530);
+
+    let headers = layout.program_headers();
+    assert_eq!(headers.len(), 6);
+    let header = &headers[phdr];
+    assert_eq!(header.p_type, elf::PT_PHDR);
+    assert_eq!(
+        (header.p_offset, header.p_vaddr, header.p_filesz),
+        (0x40, 0x400040, 6 * 0x38)
+    );
+    let header = &headers[interp];
+    assert_eq!(
+        (
+            header.p_offset,
+            header.p_vaddr,
+            header.p_filesz,
+            header.p_align
+        ),
+        (headers_size, 0x400000 + headers_size, 28, 1)
+    );
+    let header = &headers[text];
+    assert_eq!(
+        (
+            header.p_offset,
+            header.p_vaddr,
+            header.p_filesz,
+            header.p_memsz
+        ),
+        (0, 0x400000, 0x1404, 0x1404)
+    );
+    assert_eq!(header.p_align, 0x1000);
+    let header = &headers[data];
+    assert_eq!(
+        (
+            header.p_offset,
+            header.p_vaddr,
+            header.p_filesz,
+            header.p_memsz
+        ),
+        (0x1408, 0x402408, 0x128, 0x2138)
+    );
+    let header = &headers[dynamic];
+    assert_eq!(
+        (
+            header.p_offset,
+            header.p_vaddr,
+            header.p_filesz,
+            header.p_align
+        ),
+        (0x1408, 0x402408, 0x100, 8)
+    );
+    let header = &headers[note];
+    assert_eq!(
+        (
+            header.p_offset,
+            header.p_vaddr,
+            header.p_memsz,
+            header.p_align
+        ),
+        (0x1ac, 0x4001ac, 0x24, 4)
+    );
+
+    // A fixed address that overlaps the previous segment is an error.
+    let mut layout = SegmentLayout::new(Class { is_64: true }, 0x400000, 0x1000);
+    let text = layout.add_segment(elf::PT_LOAD, elf::PF_R | elf::PF_X);
+    let data = layout.add_segment(elf::PT_LOAD, elf::PF_R | elf::PF_W);
+    layout.add_section(text, 0x2000, 16, false);
+    layout.add_section(data, 0x10, 8, false);
+    layout.set_segment_address(data, 0x401000);
+    assert!(layout.calculate().is_err());
+    layout.set_segment_address(data, 0x403000);
+    layout.calculate().unwrap();
+    let header = &layout.program_headers()[data];
+    assert_eq!((header.p_offset, header.p_vaddr), (0x3000, 0x403000));
+
+    // The sections of a segment must be consecutive.
+    let mut layout = SegmentLayout::new(Class { is_64: false }, 0, 0x1000);
+    let text = layout.add_segment(elf::PT_LOAD, elf::PF_R | elf::PF_X);
+    let note = layout.add_segment(elf::PT_NOTE, elf::PF_R);
+    let note1 = layout.add_section(text, 0x10, 4, false);
+    layout.add_section(text, 0x10, 4, false);
+    let note2 = layout.add_section(text, 0x10, 4, false);
+    layout.append_section(note, note1);
+    layout.append_section(note, note2);
+    assert!(layout.calculate().is_err());
+
+    // PT_GNU_STACK has no sections, and PT_GNU_RELRO covers sections in a PT_LOAD.
+    let mut layout = SegmentLayout::new(Class { is_64: true }, 0, 0x1000);
+    let data = layout.add_segment(elf::PT_LOAD, elf::PF_R | elf::PF_W);
+    let relro = layout.add_segment(elf::PT_GNU_RELRO, elf::PF_R);
+    let stack = layout.add_segment(elf::PT_GNU_STACK, elf::PF_R | elf::PF_W);
+    let dynamic_section = layout.add_section(data, 0x100, 8, false);
+    let got_section = layout.add_section(data, 0x18, 8, false);
+    layout.add_section(data, 0x10, 8, false);
+    layout.append_section(relro, dynamic_section);
+    layout.append_section(relro, got_section);
+    layout.calculate().unwrap();
+    let headers = layout.program_headers();
+    let header = &headers[relro];
+    assert_eq!(
+        (header.p_offset, header.p_vaddr, header.p_memsz),
+        (
+            layout.section(dynamic_section).offset,
+            layout.section(dynamic_section).address,
+            0x118
+        )
+    );
+    let header = &headers[stack];
+    assert_eq!(header.p_type, elf::PT_GNU_STACK);
+    assert_eq!(header.p_flags, elf::PF_R | elf::PF_W);
+    assert_eq!((header.p_offset, header.p_memsz, header.p_align), (0, 0, 0));
+}
+
+#[test]
+fn gnu_stack() {
+    for executable in [false, true] {
+        let mut object =
+            write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+        object.set_elf_gnu_stack(!executable);
+        object.set_elf_gnu_stack(executable);
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        let mut sections = object
+            .sections()
+            .filter(|section| section.name() == Ok(".note.GNU-stack"));
+        let section = sections.next().unwrap();
+        assert!(sections.next().is_none());
+        assert_eq!(section.size(), 0);
+        let object::SectionFlags::Elf { sh_flags } = section.flags() else {
+            panic!("unexpected section flags");
+        };
+        assert_eq!(sh_flags & u64::from(elf::SHF_EXECINSTR) != 0, executable);
+        assert_eq!(sh_flags & u64::from(elf::SHF_ALLOC), 0);
+    }
+}
+
+#[test]
+fn core() {
+    use object::read::elf::ProgramHeader;
+    use object::write::elf::{CoreFileMapping, CoreNotes, CoreSegment, PrPsInfo, PrStatus};
+
+    let registers: Vec<u64> = (0..27).collect();
+    let mut notes = CoreNotes::new(Endianness::Little, true, elf::EM_X86_64);
+    notes.add_prpsinfo(&PrPsInfo {
+        sname: b'R',
+        pid: 100,
+        fname: b"test",
+        psargs: b"test --arg",
+        ..Default::default()
+    });
+    notes
+        .add_prstatus(&PrStatus {
+            signal: 11,
+            pid: 101,
+            registers: &registers,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(notes
+        .add_prstatus(&PrStatus {
+            registers: &registers[1..],
+            ..Default::default()
+        })
+        .is_err());
+    notes.add_auxv(&[(6, 0x1000)]);
+    notes
+        .add_file(
+            0x1000,
+            &[CoreFileMapping {
+                start: 0x400000,
+                end: 0x401000,
+                offset: 0x2000,
+                name: b"/bin/test",
+            }],
+        )
+        .unwrap();
+
+    let memory = vec![0xaa; 0x100];
+    let mut buffer = Vec::new();
+    object::write::elf::write_core(
+        &mut buffer,
+        &notes,
+        &[CoreSegment {
+            p_flags: elf::PF_R | elf::PF_W,
+            address: 0x7fff0000,
+            mem_size: 0x1000,
+            data: &memory,
+        }],
+    )
+    .unwrap();
+
+    let data = &*buffer;
+    let header = elf::FileHeader64::<Endianness>::parse(data).unwrap();
+    let endian = header.endian().unwrap();
+    assert_eq!(header.e_type(endian), elf::ET_CORE);
+    let segments = header.program_headers(endian, data).unwrap();
+    assert_eq!(segments.len(), 2);
+
+    let mut notes = segments[0].notes(endian, data).unwrap().unwrap();
+    let note = notes.next().unwrap().unwrap();
+    assert_eq!(note.name(), elf::ELF_NOTE_CORE);
+    assert_eq!(note.n_type(endian), elf::NT_PRPSINFO);
+    let desc = note.desc();
+    assert_eq!(desc.len(), 136);
+    assert_eq!(desc[1], b'R');
+    assert_eq!(desc[24..28], 100u32.to_le_bytes());
+    assert_eq!(&desc[40..56], b"test\0\0\0\0\0\0\0\0\0\0\0\0");
+    assert_eq!(&desc[56..66], b"test --arg");
+
+    let note = notes.next().unwrap().unwrap();
+    assert_eq!(note.n_type(endian), elf::NT_PRSTATUS);
+    let desc = note.desc();
+    assert_eq!(desc.len(), 336);
+    assert_eq!(desc[0..4], 11u32.to_le_bytes());
+    assert_eq!(desc[12..14], 11u16.to_le_bytes());
+    assert_eq!(desc[32..36], 101u32.to_le_bytes());
+    assert_eq!(desc[112..120], 0u64.to_le_bytes());
+    assert_eq!(desc[320..328], 26u64.to_le_bytes());
+
+    let note = notes.next().unwrap().unwrap();
+    assert_eq!(note.n_type(endian), elf::NT_AUXV);
+    assert_eq!(note.desc().len(), 32);
+
+    let note = notes.next().unwrap().unwrap();
+    assert_eq!(note.n_type(endian), elf::NT_FILE);
+    let desc = note.desc();
+    assert_eq!(desc[0..8], 1u64.to_le_bytes());
+    assert_eq!(desc[8..16], 0x1000u64.to_le_bytes());
+    assert_eq!(desc[32..40], 2u64.to_le_bytes());
+    assert_eq!(&desc[40..], b"/bin/test\0");
+    assert!(notes.next().unwrap().is_none());
+
+    let segment = &segments[1];
+    assert_eq!(segment.p_type(endian), elf::PT_LOAD);
+    assert_eq!(segment.p_vaddr(endian), 0x7fff0000);
+    assert_eq!(segment.p_memsz(endian), 0x1000);
+    assert_eq!(segment.p_offset(endian) % 0x1000, 0);
+    assert_eq!(segment.data(endian, data).unwrap(), &memory[..]);
+
+    // Check the layouts for a 32-bit machine.
+    let registers: Vec<u64> = (0..17).collect();
+    let mut notes = CoreNotes::new(Endianness::Little, false, elf::EM_386);
+    notes.add_prpsinfo(&PrPsInfo::default());
+    notes
+        .add_prstatus(&PrStatus {
+            registers: &registers,
+            ..Default::default()
+        })
+        .unwrap();
+    let mut buffer = Vec::new();
+    object::write::elf::write_core(&mut buffer, &notes, &[]).unwrap();
+
+    let data = &*buffer;
+    let header = elf::FileHeader32::<Endianness>::parse(data).unwrap();
+    let endian = header.endian().unwrap();
+    let segments = header.program_headers(endian, data).unwrap();
+    let mut notes = segments[0].notes(endian, data).unwrap().unwrap();
+    assert_eq!(notes.next().unwrap().unwrap().desc().len(), 124);
+    assert_eq!(notes.next().unwrap().unwrap().desc().len(), 144);
+}
+
+#[test]
+fn eh_frame_hdr() {
+    use object::write::elf::{eh_frame_entries, eh_frame_hdr_size, write_eh_frame_hdr};
+
+    let eh_frame_address = 0x2000;
+    let mut eh_frame = Vec::new();
+    // CIE with "zR" augmentation, using PC relative FDE addresses.
+    eh_frame.extend_from_slice(&20u32.to_le_bytes());
+    eh_frame.extend_from_slice(&0u32.to_le_bytes());
+    eh_frame.extend_from_slice(&[1, b'z', b'R', 0, 1, 0x78, 16, 1, 0x1b]);
+    eh_frame.extend_from_slice(&[0; 7]);
+    // FDEs, not sorted by address.
+    for address in [0x1100u64, 0x1000] {
+        let offset = eh_frame.len();
+        eh_frame.extend_from_slice(&20u32.to_le_bytes());
+        eh_frame.extend_from_slice(&(offset as u32 + 4).to_le_bytes());
+        let pc = eh_frame_address + eh_frame.len() as u64;
+        eh_frame.extend_from_slice(&(address.wrapping_sub(pc) as u32).to_le_bytes());
+        eh_frame.extend_from_slice(&0x10u32.to_le_bytes());
+        eh_frame.extend_from_slice(&[0; 8]);
+    }
+    eh_frame.extend_from_slice(&0u32.to_le_bytes());
+
+    let entries = eh_frame_entries(&eh_frame, eh_frame_address, Endianness::Little, true).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].address, 0x1100);
+    assert_eq!(entries[0].fde_address, 0x2018);
+    assert_eq!(entries[1].address, 0x1000);
+    assert_eq!(entries[1].fde_address, 0x2030);
+
+    let eh_frame_hdr_address = 0x1800;
+    let mut buffer = Vec::new();
+    write_eh_frame_hdr(
+        &mut buffer,
+        Endianness::Little,
+        eh_frame_hdr_address,
+        eh_frame_address,
+        &entries,
+    )
+    .unwrap();
+    assert_eq!(buffer.len(), eh_frame_hdr_size(2));
+    let mut expected = vec![1, 0x1b, 0x03, 0x3b];
+    expected.extend_from_slice(&0x7fcu32.to_le_bytes());
+    expected.extend_from_slice(&2u32.to_le_bytes());
+    expected.extend_from_slice(&(-0x800i32).to_le_bytes());
+    expected.extend_from_slice(&0x830i32.to_le_bytes());
+    expected.extend_from_slice(&(-0x700i32).to_le_bytes());
+    expected.extend_from_slice(&0x818i32.to_le_bytes());
+    assert_eq!(buffer, expected);
+}
+
+#[test]
+fn mips64() {
+    use object::{I64, U16};
+
+    let endian = LittleEndian;
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::Mips64, Endianness::Little);
+
+    let abiflags = elf::MipsAbiFlags {
+        version: U16::new(endian, 0),
+        isa_level: 64,
+        isa_rev: 2,
+        gpr_size: elf::MIPS_AFL_REG_64,
+        cpr1_size: elf::MIPS_AFL_REG_64,
+        cpr2_size: elf::MIPS_AFL_REG_NONE,
+        fp_abi: elf::MIPS_ABI_FP_DOUBLE,
+        isa_ext: U32::new(endian, 0),
+        ases: U32::new(endian, elf::MIPS_AFL_ASE_MSA),
+        flags1: U32::new(endian, elf::MIPS_AFL_FLAGS1_ODDSPREG),
+        flags2: U32::new(endian, 0),
+    };
+    let section = object.add_section(
+        Vec::new(),
+        b".MIPS.abiflags".to_vec(),
+        SectionKind::Elf(elf::SHT_MIPS_ABIFLAGS),
+    );
+    object
+        .section_mut(section)
+        .set_data(object::bytes_of(&abiflags), 8);
+
+    let mut options = Vec::new();
+    options.extend_from_slice(object::bytes_of(&elf::MipsOptionHeader {
+        kind: elf::ODK_REGINFO as u8,
+        size: 40,
+        section: U16::new(endian, 0),
+        info: U32::new(endian, 0),
+    }));
+    options.extend_from_slice(object::bytes_of(&elf::MipsRegInfo64 {
+        ri_gprmask: U32::new(endian, 0x1234),
+        ri_pad: U32::new(endian, 0),
+        ri_cprmask: [U32::new(endian, 0); 4],
+        ri_gp_value: I64::new(endian, 0x8000),
+    }));
+    options.extend_from_slice(object::bytes_of(&elf::MipsOptionHeader {
+        kind: elf::ODK_HWAND as u8,
+        size: 16,
+        section: U16::new(endian, 0),
+        info: U32::new(endian, 0),
+    }));
+    options.extend_from_slice(object::bytes_of(&elf::MipsOptionHw {
+        hwp_flags1: U32::new(endian, elf::OHWA0_R4KEOP_CHECKED),
+        hwp_flags2: U32::new(endian, 0),
+    }));
+    let section = object.add_section(
+        Vec::new(),
+        b".MIPS.options".to_vec(),
+        SectionKind::Elf(elf::SHT_MIPS_OPTIONS),
+    );
+    object.section_mut(section).set_data(options, 8);
+
+    let data_section = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data_section, &[0; 16], 8);
+    let symbol = object.section_symbol(data_section);
+    let composite = elf::Mips64RelocationType {
+        r_ssym: elf::RSS_UNDEF,
+        r_type3: elf::R_MIPS_HI16 as u8,
+        r_type2: elf::R_MIPS_SUB as u8,
+        r_type: elf::R_MIPS_GPREL16 as u8,
+    };
+    for (offset, r_type) in [(0, elf::R_MIPS_64), (8, composite.to_r_type())] {
+        object
+            .add_relocation(
+                data_section,
+                write::Relocation {
+                    offset,
+                    symbol,
+                    addend: 0,
+                    flags: object::RelocationFlags::Elf { r_type },
+                },
+            )
+            .unwrap();
+    }
+    let bytes = object.write().unwrap();
+
+    let object = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let data = object.data();
+    let section = object.section_by_name(".data").unwrap();
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 2);
+    let (_, relocation) = &relocations[0];
+    assert_eq!(relocation.kind(), object::RelocationKind::Absolute);
+    assert_eq!(relocation.size(), 64);
+    let (_, relocation) = &relocations[1];
+    assert_eq!(relocation.kind(), object::RelocationKind::Unknown);
+    let object::RelocationFlags::Elf { r_type } = relocation.flags() else {
+        panic!("unexpected relocation flags");
+    };
+    assert_eq!(elf::Mips64RelocationType::from_r_type(r_type), composite);
+    assert_eq!(composite.single(), None);
+
+    let endian = object.endian();
+    let sections = object.elf_section_table();
+    let section = sections
+        .section_by_name(endian, b".MIPS.abiflags")
+        .unwrap()
+        .1;
+    let abiflags = section.mips_abiflags(endian, data).unwrap().unwrap();
+    assert_eq!(abiflags.isa_level, 64);
+    assert_eq!(abiflags.fp_abi, elf::MIPS_ABI_FP_DOUBLE);
+    assert_eq!(abiflags.ases.get(endian), elf::MIPS_AFL_ASE_MSA);
+    assert!(section.mips_options(endian, data).unwrap().is_none());
+
+    let section = sections
+        .section_by_name(endian, b".MIPS.options")
+        .unwrap()
+        .1;
+    let mut options = section.mips_options(endian, data).unwrap().unwrap();
+    let option = options.next().unwrap().unwrap();
+    assert_eq!(u32::from(option.kind()), elf::ODK_REGINFO);
+    assert_eq!(option.section(), SectionIndex(0));
+    let reginfo = option.reginfo64().unwrap().unwrap();
+    assert_eq!(reginfo.ri_gprmask.get(endian), 0x1234);
+    assert_eq!(reginfo.ri_gp_value.get(endian), 0x8000);
+    assert!(option.hw().unwrap().is_none());
+    let option = options.next().unwrap().unwrap();
+    assert_eq!(u32::from(option.kind()), elf::ODK_HWAND);
+    let hw = option.hw().unwrap().unwrap();
+    assert_eq!(hw.hwp_flags1.get(endian), elf::OHWA0_R4KEOP_CHECKED);
+    assert!(options.next().unwrap().is_none());
+}
+
+#[test]
+fn ppc64_opd() {
+    for (e_flags, expect) in [(1, Some(0x1234_5678)), (2, None)] {
+        let mut object =
+            write::Object::new(BinaryFormat::Elf, Architecture::PowerPc64, Endianness::Big);
+        object.flags = object::FileFlags::Elf {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_flags,
+        };
+        let opd = object.add_section(Vec::new(), b".opd".to_vec(), SectionKind::Data);
+        let mut descriptor = Vec::new();
+        descriptor.extend_from_slice(&0x1234_5678u64.to_be_bytes());
+        descriptor.extend_from_slice(&0x8000u64.to_be_bytes());
+        descriptor.extend_from_slice(&0u64.to_be_bytes());
+        object.section_mut(opd).set_data(descriptor, 8);
+        let bytes = object.write().unwrap();
+
+        let object = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+        assert_eq!(object.ppc64_opd_entry(0).unwrap(), expect);
+        assert_eq!(object.ppc64_opd_entry(24).unwrap(), None);
+    }
+
+    assert_eq!(elf::ppc64_local_entry_offset(0), 0);
+    assert_eq!(
+        elf::ppc64_local_entry_offset(1 << elf::STO_PPC64_LOCAL_BIT),
+        0
+    );
+    assert_eq!(
+        elf::ppc64_local_entry_offset(2 << elf::STO_PPC64_LOCAL_BIT),
+        4
+    );
+    assert_eq!(
+        elf::ppc64_local_entry_offset(3 << elf::STO_PPC64_LOCAL_BIT),
+        8
+    );
+    assert_eq!(
+        elf::ppc64_local_entry_offset(6 << elf::STO_PPC64_LOCAL_BIT),
+        64
+    );
+}
+
+#[test]
+fn plt_entries() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let plt_name = writer.add_section_name(b".plt");
+    writer.reserve_section_index();
+    let plt_sec_name = writer.add_section_name(b".plt.sec");
+    writer.reserve_section_index();
+    let got_plt_name = writer.add_section_name(b".got.plt");
+    writer.reserve_section_index();
+    let rela_plt_name = writer.add_section_name(b".rela.plt");
+    writer.reserve_section_index();
+    writer.reserve_shstrtab_section_index();
+
+    // PLT0 and two lazy binding entries.
+    let plt_offset = writer.reserve(48, 16);
+    // One IBT entry.
+    let plt_sec_offset = writer.reserve(16, 16);
+    // Three reserved entries and three slots.
+    let got_plt_offset = writer.reserve(48, 8);
+    let rela_plt_offset = writer.reserve_relocations(3, true);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    // Use file offsets as addresses.
+    let plt_address = plt_offset as u64;
+    let plt_sec_address = plt_sec_offset as u64;
+    let got_plt_address = got_plt_offset as u64;
+    let rip_rel = |address: u64, got: u64| ((got as i64 - address as i64) as i32).to_le_bytes();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+
+    writer.pad_until(plt_offset);
+    // pushq GOT+8(%rip); jmpq *GOT+16(%rip); nopl 0(%rax)
+    writer.write(&[0xff, 0x35]);
+    writer.write(&rip_rel(plt_address + 6, got_plt_address + 8));
+    writer.write(&[0xff, 0x25]);
+    writer.write(&rip_rel(plt_address + 12, got_plt_address + 16));
+    writer.write(&[0x0f, 0x1f, 0x40, 0x00]);
+    for i in 0..2u64 {
+        // jmpq *slot(%rip); pushq $i; jmpq PLT0
+        let address = plt_address + 16 + i * 16;
+        writer.write(&[0xff, 0x25]);
+        writer.write(&rip_rel(address + 6, got_plt_address + 24 + i * 8));
+        writer.write(&[0x68]);
+        writer.write(&(i as u32).to_le_bytes());
+        writer.write(&[0xe9]);
+        writer.write(&((plt_address as i64 - (address + 16) as i64) as i32).to_le_bytes());
+    }
+
+    writer.pad_until(plt_sec_offset);
+    // endbr64; bnd jmpq *slot(%rip); nopl 0(%rax,%rax,1)
+    writer.write(&[0xf3, 0x0f, 0x1e, 0xfa, 0xf2, 0xff, 0x25]);
+    writer.write(&rip_rel(plt_sec_address + 11, got_plt_address + 40));
+    writer.write(&[0x0f, 0x1f, 0x44, 0x00, 0x00]);
+
+    writer.pad_until(got_plt_offset);
+    writer.write(&[0; 48]);
+
+    writer.write_align_relocation();
+    for (slot, r_sym, r_type, r_addend) in [
+        (3, 1, elf::R_X86_64_JUMP_SLOT, 0),
+        (4, 2, elf::R_X86_64_JUMP_SLOT, 0),
+        (5, 0, elf::R_X86_64_IRELATIVE, 0x1234),
+    ] {
+        writer.write_relocation(
+            true,
+            &object::write::elf::Rel {
+                r_offset: got_plt_address + slot * 8,
+                r_sym,
+                r_type,
+                r_addend,
+            },
+        );
+    }
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(plt_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+        sh_addr: plt_address,
+        sh_offset: plt_offset as u64,
+        sh_size: 48,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 16,
+        sh_entsize: 16,
+    });
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(plt_sec_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+        sh_addr: plt_sec_address,
+        sh_offset: plt_sec_offset as u64,
+        sh_size: 16,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 16,
+        sh_entsize: 16,
+    });
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(got_plt_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_WRITE).into(),
+        sh_addr: got_plt_address,
+        sh_offset: got_plt_offset as u64,
+        sh_size: 48,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 8,
+        sh_entsize: 8,
+    });
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(rela_plt_name),
+        sh_type: elf::SHT_RELA,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_INFO_LINK).into(),
+        sh_addr: rela_plt_offset as u64,
+        sh_offset: rela_plt_offset as u64,
+        sh_size: 3 * 24,
+        sh_link: 0,
+        sh_info: 3,
+        sh_addralign: 8,
+        sh_entsize: 24,
+    });
+    writer.write_shstrtab_section_header();
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let object = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    let entries = object.plt_entries().unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            read::elf::ElfPltEntry {
+                address: plt_address + 16,
+                got_address: got_plt_address + 24,
+                symbol: Some(SymbolIndex(1)),
+                r_type: elf::R_X86_64_JUMP_SLOT,
+                addend: 0,
+            },
+            read::elf::ElfPltEntry {
+                address: plt_address + 32,
+                got_address: got_plt_address + 32,
+                symbol: Some(SymbolIndex(2)),
+                r_type: elf::R_X86_64_JUMP_SLOT,
+                addend: 0,
+            },
+            read::elf::ElfPltEntry {
+                address: plt_sec_address,
+                got_address: got_plt_address + 40,
+                symbol: None,
+                r_type: elf::R_X86_64_IRELATIVE,
+                addend: 0x1234,
+            },
+        ]
+    );
+}
+
+#[test]
+fn plt_entries_high_address() {
+    // A PLT section that wraps around the end of the address space.
+    fn plt_file(e_machine: u16, plt_address: u64, plt: &[u8], r_type: u32) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+
+        writer.reserve_file_header();
+        writer.reserve_null_section_index();
+        let plt_name = writer.add_section_name(b".plt");
+        writer.reserve_section_index();
+        let rela_plt_name = writer.add_section_name(b".rela.plt");
+        writer.reserve_section_index();
+        writer.reserve_shstrtab_section_index();
+        let plt_offset = writer.reserve(plt.len(), 16);
+        let rela_plt_offset = writer.reserve_relocations(1, true);
+        writer.reserve_shstrtab();
+        writer.reserve_section_headers();
+
+        writer
+            .write_file_header(&object::write::elf::FileHeader {
+                os_abi: elf::ELFOSABI_NONE,
+                abi_version: 0,
+                e_type: elf::ET_DYN,
+                e_machine,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .unwrap();
+        writer.pad_until(plt_offset);
+        writer.write(plt);
+        writer.write_align_relocation();
+        writer.write_relocation(
+            true,
+            &object::write::elf::Rel {
+                r_offset: 0x1000,
+                r_sym: 1,
+                r_type,
+                r_addend: 0,
+            },
+        );
+        writer.write_shstrtab();
+
+        writer.write_null_section_header();
+        writer.write_section_header(&object::write::elf::SectionHeader {
+            name: Some(plt_name),
+            sh_type: elf::SHT_PROGBITS,
+            sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+            sh_addr: plt_address,
+            sh_offset: plt_offset as u64,
+            sh_size: plt.len() as u64,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 4,
+            sh_entsize: 0,
+        });
+        writer.write_section_header(&object::write::elf::SectionHeader {
+            name: Some(rela_plt_name),
+            sh_type: elf::SHT_RELA,
+            sh_flags: (elf::SHF_ALLOC | elf::SHF_INFO_LINK).into(),
+            sh_addr: 0x2000,
+            sh_offset: rela_plt_offset as u64,
+            sh_size: 24,
+            sh_link: 0,
+            sh_info: 1,
+            sh_addralign: 8,
+            sh_entsize: 24,
+        });
+        writer.write_shstrtab_section_header();
+        assert_eq!(writer.reserved_len(), writer.len());
+        buffer
+    }
+
+    let plt_address = u64::MAX - 3;
+
+    // jmpq *disp(%rip), where the next instruction is at address 2.
+    let mut plt = vec![0xff, 0x25];
+    plt.extend_from_slice(&(0x1000u32 - 2).to_le_bytes());
+    let x86_64 = plt_file(elf::EM_X86_64, plt_address, &plt, elf::R_X86_64_JUMP_SLOT);
+
+    // bti c; adrp x16, 0x1000; ldr x17, [x16]
+    let mut plt = Vec::new();
+    for insn in [0xd503_245fu32, 0xb000_0010, 0xf940_0211] {
+        plt.extend_from_slice(&insn.to_le_bytes());
+    }
+    let aarch64 = plt_file(elf::EM_AARCH64, plt_address, &plt, elf::R_AARCH64_JUMP_SLOT);
+
+    for (buffer, r_type) in [
+        (x86_64, elf::R_X86_64_JUMP_SLOT),
+        (aarch64, elf::R_AARCH64_JUMP_SLOT),
+    ] {
+        let object = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+        assert_eq!(
+            object.plt_entries().unwrap(),
+            vec![read::elf::ElfPltEntry {
+                address: plt_address,
+                got_address: 0x1000,
+                symbol: Some(SymbolIndex(1)),
+                r_type,
+                addend: 0,
+            }]
+        );
+    }
+}
+
+#[test]
+fn symbol_table_builder() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let text_name = writer.add_section_name(b".text");
+    let mut sections = Vec::new();
+    for _ in 0..0xff01 {
+        sections.push(writer.reserve_section_index());
+    }
+    let low_section = sections[0];
+    let high_section = *sections.last().unwrap();
+    assert!(high_section.0 >= elf::SHN_LORESERVE.into());
+
+    let mut symtab = object::write::elf::SymbolTableBuilder::new();
+    let symbol = |name: &'static [u8], section, st_bind| object::write::elf::SymbolTableEntry {
+        name: Some(name),
+        section: Some(section),
+        st_info: (st_bind << 4) | elf::STT_FUNC,
+        st_other: elf::STV_DEFAULT,
+        st_shndx: 0,
+        st_value: 0,
+        st_size: 0,
+    };
+    let global1 = symtab.add_symbol(symbol(b"global1", low_section, elf::STB_GLOBAL));
+    let local1 = symtab.add_symbol(symbol(b"local1", high_section, elf::STB_LOCAL));
+    let global2 = symtab.add_symbol(symbol(b"global2", high_section, elf::STB_WEAK));
+    let local2 = symtab.add_symbol(symbol(b"local2", low_section, elf::STB_LOCAL));
+    assert_eq!(symtab.len(), 4);
+    symtab.reserve_indices(&mut writer);
+    assert_eq!(symtab.symbol_index(local1).0, 1);
+    assert_eq!(symtab.symbol_index(local2).0, 2);
+    assert_eq!(symtab.symbol_index(global1).0, 3);
+    assert_eq!(symtab.symbol_index(global2).0, 4);
+    assert_eq!(symtab.num_local(), 3);
+    assert!(writer.symtab_shndx_needed());
+
+    symtab.reserve(&mut writer);
+    writer.reserve_shstrtab_section_index();
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: elf::ET_REL,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    symtab.write(&mut writer);
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    for _ in &sections {
+        writer.write_section_header(&object::write::elf::SectionHeader {
+            name: Some(text_name),
+            sh_type: elf::SHT_PROGBITS,
+            sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+            sh_addr: 0,
+            sh_offset: 0,
+            sh_size: 0,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 1,
+            sh_entsize: 0,
+        });
+    }
+    symtab.write_section_headers(&mut writer);
+    writer.write_shstrtab_section_header();
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let object = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    let symtab_section = object.section_by_name(".symtab").unwrap();
+    assert_eq!(
+        symtab_section
+            .elf_section_header()
+            .sh_info(Endianness::Little),
+        3
+    );
+    assert!(object.section_by_name(".symtab_shndx").is_some());
+    let symbols: Vec<_> = object
+        .symbols()
+        .map(|symbol| (symbol.name().unwrap(), symbol.section()))
+        .collect();
+    assert_eq!(
+        symbols,
+        vec![
+            (
+                "local1",
+                SymbolSection::Section(SectionIndex(high_section.0 as usize))
+            ),
+            (
+                "local2",
+                SymbolSection::Section(SectionIndex(low_section.0 as usize))
+            ),
+            (
+                "global1",
+                SymbolSection::Section(SectionIndex(low_section.0 as usize))
+            ),
+            (
+                "global2",
+                SymbolSection::Section(SectionIndex(high_section.0 as usize))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn dynamic_builder() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+
+    let mut dynamic = object::write::elf::DynamicBuilder::new();
+    dynamic.add_needed(&mut writer, b"libc.so.6");
+    dynamic.add_soname(&mut writer, b"libtest.so");
+    let strtab = dynamic.add(elf::DT_STRTAB, 0);
+    let strsz = dynamic.add(elf::DT_STRSZ, 0);
+    dynamic.add(elf::DT_FLAGS, elf::DF_BIND_NOW.into());
+    assert_eq!(dynamic.len(), 6);
+
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    writer.reserve_dynstr_section_index();
+    writer.reserve_dynamic_section_index();
+    writer.reserve_shstrtab_section_index();
+
+    let dynstr_offset = writer.reserve_dynstr();
+    dynamic.set_value(strtab, dynstr_offset as u64);
+    dynamic.set_value(strsz, writer.dynstr_len() as u64);
+    let dynamic_offset = dynamic.reserve(&mut writer);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_dynstr();
+    dynamic.write(&mut writer);
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    writer.write_dynstr_section_header(dynstr_offset as u64);
+    writer.write_dynamic_section_header(dynamic_offset as u64);
+    writer.write_shstrtab_section_header();
+    assert_eq!(writer.reserved_len(), writer.len());
+    let dynstr_len = writer.dynstr_len() as u64;
+
+    let data = &*buffer;
+    let header = elf::FileHeader64::<Endianness>::parse(data).unwrap();
+    let endian = header.endian().unwrap();
+    let sections = header.sections(endian, data).unwrap();
+    let section = sections.section(SectionIndex(2)).unwrap();
+    let (dynamics, link) = section.dynamic(endian, data).unwrap().unwrap();
+    let strings = sections.strings(endian, data, link).unwrap();
+    let dynamics: Vec<_> = dynamics
+        .iter()
+        .map(|d| {
+            let value = if d.is_string(endian) {
+                d.string(endian, strings).unwrap().to_vec()
+            } else {
+                d.d_val(endian).to_string().into_bytes()
+            };
+            (d.tag32(endian).unwrap(), value)
+        })
+        .collect();
+    assert_eq!(
+        dynamics,
+        vec![
+            (elf::DT_NEEDED, b"libc.so.6".to_vec()),
+            (elf::DT_SONAME, b"libtest.so".to_vec()),
+            (elf::DT_STRTAB, dynstr_offset.to_string().into_bytes()),
+            (elf::DT_STRSZ, dynstr_len.to_string().into_bytes()),
+            (elf::DT_FLAGS, elf::DF_BIND_NOW.to_string().into_bytes()),
+            (elf::DT_NULL, b"0".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn section_order() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.add_section(Vec::new(), b".text".to_vec(), SectionKind::Text);
+    object.append_section_data(text, &[0; 8], 4);
+    let data = object.add_section(Vec::new(), b".data".to_vec(), SectionKind::Data);
+    object.append_section_data(data, &[1; 8], 8);
+    let boot = object.add_section(Vec::new(), b".boot".to_vec(), SectionKind::Text);
+    object.append_section_data(boot, &[2; 4], 16);
+    object.section_mut(boot).set_order(-1);
+    object.section_mut(data).set_order(1);
+
+    let symbol = object.add_symbol(write::Symbol {
+        name: b"value".to_vec(),
+        value: 0,
+        size: 8,
+        kind: SymbolKind::Data,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(data),
+        flags: SymbolFlags::None,
+    });
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 0,
+                symbol,
+                addend: 0,
+                flags: object::RelocationFlags::Elf {
+                    r_type: elf::R_X86_64_64,
+                },
+            },
+        )
+        .unwrap();
+    let bytes = object.write().unwrap();
+
+    let object = read::File::parse(&*bytes).unwrap();
+    let names: Vec<_> = object
+        .sections()
+        .filter(|section| section.kind() != SectionKind::Metadata)
+        .map(|section| section.name().unwrap().to_string())
+        .collect();
+    assert_eq!(names, [".boot", ".text", ".data"]);
+
+    let section = object.section_by_name(".boot").unwrap();
+    assert_eq!(section.data().unwrap(), &[2; 4]);
+    let section = object.section_by_name(".data").unwrap();
+    assert_eq!(section.data().unwrap(), &[1; 8]);
+
+    let symbol = object.symbol_by_name("value").unwrap();
+    assert_eq!(symbol.section_index(), Some(section.index()));
+
+    let section = object.section_by_name(".text").unwrap();
+    let (offset, relocation) = section.relocations().next().unwrap();
+    assert_eq!(offset, 0);
+    assert_eq!(
+        relocation.target(),
+        read::RelocationTarget::Symbol(symbol.index())
+    );
+}
+
+#[test]
+fn load_image() {
+    use object::write::elf::ProgramHeader;
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    // (physical address, data, memory size)
+    let segments: [(u64, &[u8], u64); 4] = [
+        (0x1000_0000, &[3; 4], 4),
+        (0x1000, &[1; 16], 0x18),
+        (0x1018, &[2; 8], 8),
+        (0x2000, &[], 0x100),
+    ];
+
+    writer.reserve_file_header();
+    writer.reserve_program_headers(segments.len() as u32 + 1);
+    let offsets: Vec<_> = segments
+        .iter()
+        .map(|(_, data, _)| writer.reserve(data.len(), 1))
+        .collect();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: elf::ET_EXEC,
+            e_machine: elf::EM_ARM,
+            e_entry: 0x8000_1001,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&ProgramHeader {
+        p_type: elf::PT_NOTE,
+        p_flags: elf::PF_R,
+        p_offset: offsets[0] as u64,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: 4,
+        p_memsz: 4,
+        p_align: 1,
+    });
+    for ((address, data, memsz), offset) in segments.iter().zip(&offsets) {
+        writer.write_program_header(&ProgramHeader {
+            p_type: elf::PT_LOAD,
+            p_flags: elf::PF_R,
+            p_offset: *offset as u64,
+            p_vaddr: 0x8000_0000 + address,
+            p_paddr: *address,
+            p_filesz: data.len() as u64,
+            p_memsz: *memsz,
+            p_align: 1,
+        });
+    }
+    for (_, data, _) in &segments {
+        writer.write(data);
+    }
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    for segment in file.segments() {
+        assert_eq!(
+            segment.permissions(),
+            object::Permissions::new(true, false, false)
+        );
+        assert_eq!(segment.name(), Ok(Some("LOAD")));
+        assert_eq!(
+            segment.flags(),
+            object::SegmentFlags::Elf {
+                p_type: elf::PT_LOAD,
+                p_flags: elf::PF_R
+            }
+        );
+    }
+    let image = file.load_image().unwrap();
+    assert_eq!(image.entry(), 0x8000_1001);
+    assert_eq!(image.load_address(), 0x1000);
+    assert_eq!(image.size(), 0x1000_0004 - 0x1000);
+    let segments = image.segments();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[0].physical_address, 0x1000);
+    assert_eq!(segments[0].virtual_address, 0x8000_1000);
+    assert_eq!(segments[0].memory_size, 0x18);
+    assert_eq!(segments[1].physical_address, 0x1018);
+    assert_eq!(segments[2].physical_address, 0x1000_0000);
+
+    let mut expect = vec![1; 16];
+    expect.extend_from_slice(&[0; 8]);
+    expect.extend_from_slice(&[2; 8]);
+    let chunks = image.chunks(0x1000).unwrap();
+    assert_eq!(
+        chunks,
+        [
+            read::elf::ElfLoadChunk {
+                address: 0x1000,
+                data: expect.clone(),
+            },
+            read::elf::ElfLoadChunk {
+                address: 0x1000_0000,
+                data: vec![3; 4],
+            },
+        ]
+    );
+    assert_eq!(image.chunks(0).unwrap().len(), 3);
+
+    let flat = image.to_vec().unwrap();
+    assert_eq!(flat.len() as u64, image.size());
+    assert_eq!(&flat[..expect.len()], &expect[..]);
+    assert!(flat[expect.len()..flat.len() - 4].iter().all(|&b| b == 0));
+    assert_eq!(&flat[flat.len() - 4..], &[3; 4]);
+}
+
+#[test]
+fn dwarf_package() {
+    use object::read::elf::{DwarfPackageSection, DwarfPackageUnit, ElfFile64};
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let info = object.add_section(Vec::new(), b".debug_info.dwo".to_vec(), SectionKind::Debug);
+    object.append_section_data(info, &[0; 0x50], 1);
+    let str = object.add_section(
+        Vec::new(),
+        b".debug_str.dwo".to_vec(),
+        SectionKind::DebugString,
+    );
+    object.append_section_data(str, b"a\0", 1);
+
+    let mut index = Vec::new();
+    // Version 5, 2 sections, 2 units, 4 slots.
+    for value in [5u32, 2, 2, 4] {
+        index.extend_from_slice(&value.to_le_bytes());
+    }
+    for signature in [0u64, 0x1111_0000_0000_0001, 0, 0x2222_0000_0000_0003] {
+        index.extend_from_slice(&signature.to_le_bytes());
+    }
+    for value in [0u32, 1, 0, 2] {
+        index.extend_from_slice(&value.to_le_bytes());
+    }
+    // Section identifiers, offsets and sizes.
+    for value in [1u32, 3, 0, 0, 0x20, 0x10, 0x20, 0x10, 0x30, 0x8] {
+        index.extend_from_slice(&value.to_le_bytes());
+    }
+    let cu_index = object.add_section(Vec::new(), b".debug_cu_index".to_vec(), SectionKind::Debug);
+    object.append_section_data(cu_index, &index, 1);
+
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    let section = object.section_by_name(".debug_info.dwo").unwrap();
+    assert_eq!(section.kind(), SectionKind::Debug);
+    let section = object.section_by_name(".debug_str.dwo").unwrap();
+    assert_eq!(section.kind(), SectionKind::DebugString);
+    let section = object.section_by_name(".debug_cu_index").unwrap();
+    assert_eq!(section.kind(), SectionKind::Debug);
+
+    let elf = ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert!(elf.dwarf_tu_index().unwrap().is_none());
+    let index = elf.dwarf_cu_index().unwrap().unwrap();
+    assert_eq!(index.version(), 5);
+    assert_eq!(index.unit_count(), 2);
+    assert_eq!(
+        index.section_ids().collect::<Vec<_>>(),
+        [
+            object::read::elf::DW_SECT_INFO,
+            object::read::elf::DW_SECT_ABBREV
+        ]
+    );
+    assert_eq!(
+        index.section_name(object::read::elf::DW_SECT_INFO),
+        Some(&b".debug_info.dwo"[..])
+    );
+
+    let second = DwarfPackageUnit {
+        signature: 0x2222_0000_0000_0003,
+        sections: vec![
+            DwarfPackageSection {
+                id: 1,
+                offset: 0x20,
+                size: 0x30,
+            },
+            DwarfPackageSection {
+                id: 3,
+                offset: 0x10,
+                size: 0x8,
+            },
+        ],
+    };
+    let units = index.units().unwrap();
+    assert_eq!(units.len(), 2);
+    assert_eq!(units[0].signature, 0x1111_0000_0000_0001);
+    assert_eq!(units[1], second);
+    assert_eq!(index.find(0x2222_0000_0000_0003).unwrap(), Some(second));
+    assert_eq!(index.find(0x2222_0000_0000_0002).unwrap(), None);
+}
+
+#[test]
+fn all_symbols() {
+    use object::write::elf::Sym;
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    let symbols = [(&b"a"[..], 0x1000), (b"b", 0x2000)];
+    let dynamic_symbols = [(&b"a"[..], 0x1000), (b"b", 0x3000), (b"c", 0x4000)];
+    let names: Vec<_> = symbols
+        .iter()
+        .map(|(name, _)| writer.add_string(name))
+        .collect();
+    let dynamic_names: Vec<_> = dynamic_symbols
+        .iter()
+        .map(|(name, _)| writer.add_dynamic_string(name))
+        .collect();
+
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    writer.reserve_dynsym_section_index();
+    writer.reserve_dynstr_section_index();
+    writer.reserve_symtab_section_index();
+    writer.reserve_strtab_section_index();
+    writer.reserve_shstrtab_section_index();
+    writer.reserve_null_dynamic_symbol_index();
+    for _ in &dynamic_symbols {
+        writer.reserve_dynamic_symbol_index();
+    }
+    writer.reserve_null_symbol_index();
+    for _ in &symbols {
+        writer.reserve_symbol_index(None);
+    }
+    writer.reserve_dynsym();
+    writer.reserve_dynstr();
+    writer.reserve_symtab();
+    writer.reserve_strtab();
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    let sym = |name, st_value| Sym {
+        name: Some(name),
+        section: None,
+        st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+        st_other: elf::STV_DEFAULT,
+        st_shndx: elf::SHN_ABS,
+        st_value,
+        st_size: 0,
+    };
+    writer.write_null_dynamic_symbol();
+    for (name, (_, value)) in dynamic_names.iter().zip(&dynamic_symbols) {
+        writer.write_dynamic_symbol(&sym(*name, *value));
+    }
+    writer.write_dynstr();
+    writer.write_null_symbol();
+    for (name, (_, value)) in names.iter().zip(&symbols) {
+        writer.write_symbol(&sym(*name, *value));
+    }
+    writer.write_strtab();
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    writer.write_dynsym_section_header(0, 1);
+    writer.write_dynstr_section_header(0);
+    writer.write_symtab_section_header(1);
+    writer.write_strtab_section_header();
+    writer.write_shstrtab_section_header();
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let object = read::File::parse(&*buffer).unwrap();
+    let all_symbols: Vec<_> = object
+        .all_symbols()
+        .iter()
+        .map(|symbol| (symbol.name().unwrap(), symbol.address()))
+        .collect();
+    assert_eq!(
+        all_symbols,
+        [("a", 0x1000), ("b", 0x2000), ("b", 0x3000), ("c", 0x4000)]
+    );
+
+    let object: Box<dyn object::read::dynamic::DynObject> = Box::new(object);
+    assert_eq!(object.all_symbols().len(), 4);
+}
+
+#[test]
+fn kernel_module() {
+    use object::read::elf::{ElfFile32, ElfFile64, ModInfo, ModVersion};
+
+    fn module(architecture: Architecture, word: usize) -> Vec<u8> {
+        let mut object = write::Object::new(BinaryFormat::Elf, architecture, Endianness::Little);
+        let modinfo = object.add_section(Vec::new(), b".modinfo".to_vec(), SectionKind::Data);
+        object.append_section_data(
+            modinfo,
+            b"license=GPL\0\0\0depends=\0vermagic=6.1.0 SMP\0",
+            1,
+        );
+
+        let mut versions = Vec::new();
+        for (crc, name) in [(0x1234_5678u32, &b"module_layout"[..]), (0x9abc, b"printk")] {
+            versions.extend_from_slice(&crc.to_le_bytes());
+            versions.resize(versions.len() + word - 4, 0);
+            versions.extend_from_slice(name);
+            versions.resize(versions.len() + 64 - word - name.len(), 0);
+        }
+        let section = object.add_section(Vec::new(), b"__versions".to_vec(), SectionKind::Data);
+        object.append_section_data(section, &versions, word as u64);
+
+        let mut this_module = vec![0; 3 * word];
+        this_module.extend_from_slice(b"hello");
+        this_module.resize(0x100, 0);
+        let section = object.add_section(
+            Vec::new(),
+            b".gnu.linkonce.this_module".to_vec(),
+            SectionKind::Data,
+        );
+        object.append_section_data(section, &this_module, word as u64);
+        object.write().unwrap()
+    }
+
+    let versions = [
+        ModVersion {
+            crc: 0x1234_5678,
+            name: b"module_layout",
+        },
+        ModVersion {
+            crc: 0x9abc,
+            name: b"printk",
+        },
+    ];
+
+    let bytes = module(Architecture::X86_64, 8);
+    let elf = ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let modinfo = elf
+        .modinfo()
+        .unwrap()
+        .unwrap()
+        .collect::<read::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(
+        modinfo,
+        [
+            ModInfo {
+                key: b"license",
+                value: b"GPL"
+            },
+            ModInfo {
+                key: b"depends",
+                value: b""
+            },
+            ModInfo {
+                key: b"vermagic",
+                value: b"6.1.0 SMP"
+            },
+        ]
+    );
+    assert_eq!(
+        elf.modinfo_value(b"vermagic").unwrap(),
+        Some(&b"6.1.0 SMP"[..])
+    );
+    assert_eq!(elf.modinfo_value(b"author").unwrap(), None);
+    let modversions = elf
+        .modversions()
+        .unwrap()
+        .unwrap()
+        .collect::<read::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(modversions, versions);
+    assert_eq!(elf.module_name().unwrap(), Some(&b"hello"[..]));
+
+    let bytes = module(Architecture::I386, 4);
+    let elf = ElfFile32::<Endianness>::parse(&*bytes).unwrap();
+    let modversions = elf
+        .modversions()
+        .unwrap()
+        .unwrap()
+        .collect::<read::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(modversions, versions);
+    assert_eq!(elf.module_name().unwrap(), Some(&b"hello"[..]));
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let modinfo = object.add_section(Vec::new(), b".modinfo".to_vec(), SectionKind::Data);
+    object.append_section_data(modinfo, b"license", 1);
+    let bytes = object.write().unwrap();
+    let elf = ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert!(elf.modversions().unwrap().is_none());
+    assert!(elf.module_name().unwrap().is_none());
+    assert!(elf.modinfo_value(b"license").is_err());
+}
@@ -1,5 +1,5 @@
-use object::read::macho::MachHeader;
-use object::read::{Object, ObjectSection};
+use object::read::macho::{FatArch, MachHeader};
+use object::read::{Object, ObjectSection, ObjectSymbol};
 use object::{macho, read, write, Architecture, BinaryFormat, Endianness};
 
 // Test that segment size is valid when the first section needs alignment.
@@ -62,3 +62,319 @@ fn issue_552_section_file_alignment() {
     assert_eq!(section.address(), 32);
     assert_eq!(section.size(), 1);
 }
+
+#[test]
+fn writer_executable() {
+    let mut buffer = Vec::new();
+    let mut writer = write::macho::Writer::new(Endianness::Little, true, &mut buffer);
+
+    let main = writer.add_string(b"_main");
+    let printf = writer.add_string(b"_printf");
+    writer.reserve_symbol_index();
+    writer.reserve_symbol_index();
+
+    writer.reserve_mach_header();
+    writer.reserve_segment_command(1);
+    writer.reserve_symtab_command();
+    writer.reserve_dysymtab_command();
+    writer.reserve_code_signature_command();
+    let text_offset = writer.reserve(4, 16);
+    writer.reserve_symtab();
+    writer.reserve_strtab();
+    writer.reserve_indirect_symtab(1);
+    let signature = write::macho::CodeSignature {
+        identifier: b"test",
+        exec_seg_base: 0,
+        exec_seg_limit: 0x1000,
+        exec_seg_flags: macho::CS_EXECSEG_MAIN_BINARY,
+    };
+    let signature_offset = writer.reserve_code_signature(&signature);
+
+    writer
+        .write_mach_header(&write::macho::MachHeader {
+            cputype: macho::CPU_TYPE_X86_64,
+            cpusubtype: macho::CPU_SUBTYPE_X86_64_ALL,
+            filetype: macho::MH_EXECUTE,
+            flags: 0,
+        })
+        .unwrap();
+    let mut segname = [0; 16];
+    segname[..6].copy_from_slice(b"__TEXT");
+    let mut sectname = [0; 16];
+    sectname[..6].copy_from_slice(b"__text");
+    writer.write_segment_command(&write::macho::SegmentCommand {
+        segname,
+        vmaddr: 0x1_0000_0000,
+        vmsize: 0x1000,
+        fileoff: 0,
+        filesize: text_offset as u64 + 4,
+        maxprot: macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+        initprot: macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+        nsects: 1,
+        flags: 0,
+    });
+    writer.write_section_header(&write::macho::SectionHeader {
+        sectname,
+        segname,
+        addr: 0x1_0000_0000 + text_offset as u64,
+        size: 4,
+        offset: text_offset as u32,
+        align: 4,
+        flags: macho::S_ATTR_PURE_INSTRUCTIONS,
+        ..Default::default()
+    });
+    writer.write_symtab_command();
+    writer.write_dysymtab_command(&write::macho::Dysymtab {
+        ilocalsym: 0,
+        nlocalsym: 0,
+        iextdefsym: 0,
+        nextdefsym: 1,
+        iundefsym: 1,
+        nundefsym: 1,
+    });
+    writer.write_code_signature_command();
+    writer.pad_until(text_offset);
+    writer.write(&[0xc3; 4]);
+    writer.write_align_symtab();
+    writer.write_nlist(&write::macho::Nlist {
+        name: Some(main),
+        n_type: macho::N_SECT | macho::N_EXT,
+        n_sect: 1,
+        n_desc: 0,
+        n_value: 0x1_0000_0000 + text_offset as u64,
+    });
+    writer.write_nlist(&write::macho::Nlist {
+        name: Some(printf),
+        n_type: macho::N_UNDF | macho::N_EXT,
+        n_sect: 0,
+        n_desc: 0,
+        n_value: 0,
+    });
+    writer.write_strtab();
+    writer.write_indirect_symbol(1);
+    writer.write_code_signature();
+    assert_eq!(writer.reserved_len(), writer.len());
+    signature.sign(&mut buffer, signature_offset).unwrap();
+
+    let object = read::File::parse(&*buffer).unwrap();
+    assert_eq!(object.format(), BinaryFormat::MachO);
+    assert_eq!(object.architecture(), Architecture::X86_64);
+    assert_eq!(object.kind(), object::ObjectKind::Executable);
+
+    let section = object.section_by_name("__text").unwrap();
+    assert_eq!(section.data().unwrap(), &[0xc3; 4]);
+
+    let symbols: Vec<_> = object
+        .symbols()
+        .map(|symbol| (symbol.name().unwrap(), symbol.is_undefined()))
+        .collect();
+    assert_eq!(symbols, [("_main", false), ("_printf", true)]);
+
+    let header = macho::MachHeader64::<Endianness>::parse(&*buffer, 0).unwrap();
+    let mut commands = header
+        .load_commands(Endianness::Little, &*buffer, 0)
+        .unwrap();
+    let mut dysymtab = None;
+    let mut code_signature = None;
+    while let Some(command) = commands.next().unwrap() {
+        if let Some(command) = command.dysymtab().unwrap() {
+            dysymtab = Some(command);
+        }
+        if command.cmd() == macho::LC_CODE_SIGNATURE {
+            code_signature = Some(
+                command
+                    .data::<macho::LinkeditDataCommand<Endianness>>()
+                    .unwrap(),
+            );
+        }
+    }
+    let code_signature = code_signature.unwrap();
+    let offset = code_signature.dataoff.get(Endianness::Little) as usize;
+    assert_eq!(offset, signature_offset);
+    assert_eq!(
+        code_signature.datasize.get(Endianness::Little) as usize,
+        buffer.len() - offset
+    );
+    assert_eq!(buffer[offset..offset + 4], [0xfa, 0xde, 0x0c, 0xc0]);
+    let dysymtab = dysymtab.unwrap();
+    assert_eq!(dysymtab.nindirectsyms.get(Endianness::Little), 1);
+    let offset = dysymtab.indirectsymoff.get(Endianness::Little) as usize;
+    assert_eq!(buffer[offset..offset + 4], [1, 0, 0, 0]);
+}
+
+#[test]
+fn fat() {
+    let mut slices = Vec::new();
+    for architecture in [Architecture::X86_64, Architecture::Aarch64] {
+        let mut object = write::Object::new(BinaryFormat::MachO, architecture, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3; 4], 4);
+        slices.push(object.write().unwrap());
+    }
+    let arches = [
+        write::macho::FatArch::new(
+            macho::CPU_TYPE_X86_64,
+            macho::CPU_SUBTYPE_X86_64_ALL,
+            &slices[0],
+        ),
+        write::macho::FatArch::new(
+            macho::CPU_TYPE_ARM64,
+            macho::CPU_SUBTYPE_ARM64_ALL,
+            &slices[1],
+        ),
+    ];
+
+    for is_64 in [false, true] {
+        let mut buffer = Vec::new();
+        write::macho::write_fat(&mut buffer, &arches, is_64).unwrap();
+
+        let kind = object::FileKind::parse(&*buffer).unwrap();
+        let file_arches: Vec<_> = if is_64 {
+            assert_eq!(kind, object::FileKind::MachOFat64);
+            let fat = read::macho::MachOFatFile64::parse(&*buffer).unwrap();
+            fat.arches()
+                .iter()
+                .map(|arch| {
+                    (
+                        arch.architecture(),
+                        arch.align(),
+                        arch.data(&*buffer).unwrap(),
+                    )
+                })
+                .collect()
+        } else {
+            assert_eq!(kind, object::FileKind::MachOFat32);
+            let fat = read::macho::MachOFatFile32::parse(&*buffer).unwrap();
+            fat.arches()
+                .iter()
+                .map(|arch| {
+                    (
+                        arch.architecture(),
+                        arch.align(),
+                        arch.data(&*buffer).unwrap(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(file_arches.len(), 2);
+        assert_eq!(file_arches[0].0, Architecture::X86_64);
+        assert_eq!(file_arches[0].1, 12);
+        assert_eq!(file_arches[0].2, &*slices[0]);
+        assert_eq!(file_arches[1].0, Architecture::Aarch64);
+        assert_eq!(file_arches[1].1, 14);
+        assert_eq!(file_arches[1].2, &*slices[1]);
+        assert_eq!(
+            file_arches[1].2.as_ptr() as usize - buffer.as_ptr() as usize,
+            0x4000
+        );
+    }
+
+    let mut buffer = Vec::new();
+    assert!(write::macho::write_fat(&mut buffer, &[arches[0], arches[0]], false).is_err());
+}
+
+#[test]
+fn code_signature() {
+    let signature = write::macho::CodeSignature {
+        identifier: b"test",
+        exec_seg_base: 0,
+        exec_seg_limit: 0x4000,
+        exec_seg_flags: macho::CS_EXECSEG_MAIN_BINARY,
+    };
+    let code = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    for (code_limit, hash) in [
+        (
+            3,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            56,
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        ),
+    ] {
+        let mut file = vec![0xff; code_limit + signature.size(code_limit)];
+        file[..code_limit].copy_from_slice(&code[..code_limit]);
+        signature.sign(&mut file, code_limit).unwrap();
+
+        let blob = &file[code_limit..];
+        let superblob = object::pod::from_bytes::<macho::CsSuperBlob>(blob)
+            .unwrap()
+            .0;
+        assert_eq!(
+            superblob.magic.get(object::BigEndian),
+            macho::CSMAGIC_EMBEDDED_SIGNATURE
+        );
+        assert_eq!(superblob.count.get(object::BigEndian), 1);
+        let directory = &blob[20..];
+        let cd = object::pod::from_bytes::<macho::CsCodeDirectory>(directory)
+            .unwrap()
+            .0;
+        assert_eq!(
+            cd.magic.get(object::BigEndian),
+            macho::CSMAGIC_CODEDIRECTORY
+        );
+        assert_eq!(cd.n_code_slots.get(object::BigEndian), 1);
+        assert_eq!(cd.code_limit.get(object::BigEndian), code_limit as u32);
+        let ident_offset = cd.ident_offset.get(object::BigEndian) as usize;
+        assert_eq!(&directory[ident_offset..][..5], b"test\0");
+        let hash_offset = cd.hash_offset.get(object::BigEndian) as usize;
+        let actual: String = directory[hash_offset..][..32]
+            .iter()
+            .map(|x| format!("{:02x}", x))
+            .collect();
+        assert_eq!(actual, hash);
+        let length = cd.length.get(object::BigEndian) as usize;
+        assert!(blob[20 + length..].iter().all(|x| *x == 0));
+    }
+}
+
+#[test]
+fn source_files() {
+    let mut buffer = Vec::new();
+    let mut writer = write::macho::Writer::new(Endianness::Little, true, &mut buffer);
+
+    let names = [&b"/src/"[..], b"a.c", b"", b"/src/b.c", b""];
+    let strings: Vec<_> = names
+        .iter()
+        .map(|name| {
+            writer.reserve_symbol_index();
+            (!name.is_empty()).then(|| writer.add_string(name))
+        })
+        .collect();
+
+    writer.reserve_mach_header();
+    writer.reserve_symtab_command();
+    writer.reserve_symtab();
+    writer.reserve_strtab();
+
+    writer
+        .write_mach_header(&write::macho::MachHeader {
+            cputype: macho::CPU_TYPE_X86_64,
+            cpusubtype: macho::CPU_SUBTYPE_X86_64_ALL,
+            filetype: macho::MH_EXECUTE,
+            flags: 0,
+        })
+        .unwrap();
+    writer.write_symtab_command();
+    writer.write_align_symtab();
+    for name in strings {
+        writer.write_nlist(&write::macho::Nlist {
+            name,
+            n_type: macho::N_SO,
+            n_sect: 0,
+            n_desc: 0,
+            n_value: 0,
+        });
+    }
+    writer.write_strtab();
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let object = read::File::parse(&*buffer).unwrap();
+    let files: Vec<_> = object
+        .source_files()
+        .unwrap()
+        .iter()
+        .map(|file| (file.directory(), file.name()))
+        .collect();
+    assert_eq!(files, [(&b"/src/"[..], &b"a.c"[..]), (b"", b"/src/b.c")]);
+}
@@ -11,10 +11,15 @@ mod bss;
 mod coff;
 mod comdat;
 mod common;
+mod dynamic;
 mod elf;
 mod macho;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pe;
 mod section_flags;
 mod tls;
+mod verify;
 
 #[test]
 fn coff_any() {
@@ -248,6 +253,10 @@ fn elf_x86_64() {
         read::RelocationTarget::Symbol(func1_symbol)
     );
     assert_eq!(relocation.addend(), 0);
+    let symbol = relocation.target_symbol(&object).unwrap().unwrap();
+    assert_eq!(symbol.name(), Ok("func1"));
+    assert_eq!(symbol.section_index(), Some(text_index));
+    assert!(relocation.target_dynamic_symbol(&object).is_err());
 
     let map = object.symbol_map();
     let symbol = map.get(func1_offset + 1).unwrap();
@@ -256,6 +265,112 @@ fn elf_x86_64() {
     assert_eq!(map.get(func1_offset - 1), None);
 }
 
+#[test]
+fn elf_x86_64_dynamic_relocations() {
+    let mut bytes = Vec::new();
+    let mut writer = write::elf::Writer::new(Endianness::Little, true, &mut bytes);
+
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let dynsym_index = writer.reserve_dynsym_section_index();
+    writer.reserve_dynstr_section_index();
+    let rela_name = writer.add_section_name(b".rela.dyn");
+    writer.reserve_section_index();
+    writer.reserve_shstrtab_section_index();
+
+    let func2_name = writer.add_dynamic_string(b"func2");
+    writer.reserve_null_dynamic_symbol_index();
+    let func2_symbol = writer.reserve_dynamic_symbol_index();
+
+    writer.reserve_dynsym();
+    writer.reserve_dynstr();
+    let rela_offset = writer.reserve_relocations(2, true);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&write::elf::FileHeader {
+            os_abi: object::elf::ELFOSABI_NONE,
+            abi_version: 0,
+            e_type: object::elf::ET_DYN,
+            e_machine: object::elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_null_dynamic_symbol();
+    writer.write_dynamic_symbol(&write::elf::Sym {
+        name: Some(func2_name),
+        section: None,
+        st_info: (object::elf::STB_GLOBAL << 4) | object::elf::STT_FUNC,
+        st_other: 0,
+        st_shndx: object::elf::SHN_UNDEF,
+        st_value: 0,
+        st_size: 0,
+    });
+    writer.write_dynstr();
+    writer.write_align_relocation();
+    writer.write_relocation(
+        true,
+        &write::elf::Rel {
+            r_offset: 0x1000,
+            r_sym: func2_symbol.0,
+            r_type: object::elf::R_X86_64_GLOB_DAT,
+            r_addend: 0,
+        },
+    );
+    writer.write_relocation(
+        true,
+        &write::elf::Rel {
+            r_offset: 0x1008,
+            r_sym: 0,
+            r_type: object::elf::R_X86_64_RELATIVE,
+            r_addend: 0x2000,
+        },
+    );
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    writer.write_dynsym_section_header(0, 1);
+    writer.write_dynstr_section_header(0);
+    writer.write_relocation_section_header(
+        rela_name,
+        write::elf::SectionIndex(0),
+        dynsym_index,
+        rela_offset,
+        2,
+        true,
+    );
+    writer.write_shstrtab_section_header();
+    assert_eq!(writer.reserved_len(), writer.len());
+
+    let object = read::File::parse(&*bytes).unwrap();
+    let mut relocations = object.dynamic_relocations().unwrap();
+
+    let (offset, relocation) = relocations.next().unwrap();
+    println!("{:?}", relocation);
+    assert_eq!(offset, 0x1000);
+    assert_eq!(
+        relocation.target(),
+        read::RelocationTarget::Symbol(object::SymbolIndex(func2_symbol.0 as usize))
+    );
+    let symbol = relocation.target_dynamic_symbol(&object).unwrap().unwrap();
+    assert_eq!(symbol.name(), Ok("func2"));
+    assert!(symbol.is_undefined());
+    // The symbol table doesn't exist, so the index isn't valid for it.
+    assert!(relocation.target_symbol(&object).is_err());
+
+    let (offset, relocation) = relocations.next().unwrap();
+    println!("{:?}", relocation);
+    assert_eq!(offset, 0x1008);
+    assert_eq!(relocation.target(), read::RelocationTarget::Absolute);
+    assert_eq!(relocation.addend(), 0x2000);
+    assert!(relocation.target_symbol(&object).unwrap().is_none());
+    assert!(relocation.target_dynamic_symbol(&object).unwrap().is_none());
+
+    assert!(relocations.next().is_none());
+}
+
 #[test]
 fn elf_any() {
     for (arch, endian) in [
@@ -684,3 +799,577 @@ fn xcoff_powerpc() {
         assert_eq!(relocation.addend(), 0);
     }
 }
+
+#[test]
+fn section_by_name() {
+    for (format, segment, names) in [
+        (
+            BinaryFormat::Coff,
+            &b""[..],
+            [&b".aaa"[..], b".debug_long_name"],
+        ),
+        (BinaryFormat::Elf, b"", [b".aaa", b".debug_long_name"]),
+        (BinaryFormat::MachO, b"__DWARF", [b"__aaa", b"__debug_line"]),
+        (BinaryFormat::Xcoff, b"", [b".aaa", b".dwinfo"]),
+    ] {
+        let mut object = if format == BinaryFormat::Xcoff {
+            write::Object::new(format, Architecture::PowerPc64, Endianness::Big)
+        } else {
+            write::Object::new(format, Architecture::X86_64, Endianness::Little)
+        };
+        for (i, name) in [names[0], names[1], names[0]].iter().enumerate() {
+            let section = object.add_section(segment.to_vec(), name.to_vec(), SectionKind::Data);
+            object.append_section_data(section, &[i as u8; 4], 4);
+        }
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        println!("{:?}", object.format());
+        let section = object.section_by_name_bytes(names[0]).unwrap();
+        assert_eq!(section.data().unwrap(), &[0; 4]);
+        let section = object.section_by_name_bytes(names[1]).unwrap();
+        assert_eq!(section.data().unwrap(), &[1; 4]);
+        assert!(object.section_by_name(".missing").is_none());
+
+        let index = object.section_name_index();
+        for (name, data) in [(names[0], [0; 4]), (names[1], [1; 4])] {
+            let section = object.section_by_index(index.get(name).unwrap()).unwrap();
+            assert_eq!(section.name_bytes().unwrap(), name);
+            assert_eq!(section.data().unwrap(), &data);
+        }
+        assert!(index.get(b".missing").is_none());
+        if format == BinaryFormat::MachO {
+            let section = object.section_by_name(".debug_line").unwrap();
+            assert_eq!(section.name_bytes().unwrap(), b"__debug_line");
+        }
+    }
+}
+
+#[test]
+fn symbol_id() {
+    for format in [BinaryFormat::Elf, BinaryFormat::MachO] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        let add_symbol = |object: &mut write::Object, name: &[u8]| {
+            object.add_symbol(write::Symbol {
+                name: name.to_vec(),
+                value: 0,
+                size: 0,
+                kind: SymbolKind::Text,
+                scope: SymbolScope::Linkage,
+                weak: false,
+                section: write::SymbolSection::Section(text),
+                flags: SymbolFlags::None,
+            })
+        };
+        let func1 = add_symbol(&mut object, b"func1");
+        let func2 = add_symbol(&mut object, b"func2");
+        // A later symbol with the same name replaces the earlier one.
+        let func1_dup = add_symbol(&mut object, b"func1");
+        assert_ne!(func1, func1_dup);
+
+        assert_eq!(object.symbol_id(b"func1"), Some(func1_dup));
+        assert_eq!(object.symbol_id(b"func2"), Some(func2));
+        assert_eq!(object.symbol_id(b"func3"), None);
+        // Lookups use the unmangled name.
+        assert_eq!(object.symbol_id(b"_func2"), None);
+        if format == BinaryFormat::MachO {
+            assert_eq!(object.symbol(func2).name, b"_func2");
+        } else {
+            assert_eq!(object.symbol(func2).name, b"func2");
+        }
+
+        // Lookups use the new name of a renamed symbol.
+        let prefix = if format == BinaryFormat::MachO {
+            "_"
+        } else {
+            ""
+        };
+        object.symbol_mut(func2).name = format!("{}renamed_func2", prefix).into_bytes();
+        assert_eq!(object.symbol_id(b"func2"), None);
+        assert_eq!(object.symbol_id(b"renamed_func2"), Some(func2));
+        assert_eq!(object.symbol_id(b"func1"), Some(func1_dup));
+        object.symbol_mut(func1_dup).name = b"renamed_func1".to_vec();
+        let funcs: Vec<_> = (0..100)
+            .map(|i| add_symbol(&mut object, format!("func{}", i + 10).as_bytes()))
+            .collect();
+        assert_eq!(object.symbol_id(b"renamed_func2"), Some(func2));
+        assert_eq!(object.symbol_id(b"renamed_func1"), Some(func1_dup));
+        assert_eq!(object.symbol_id(b"func1"), None);
+        assert_eq!(object.symbol_id(b"func50"), Some(funcs[40]));
+    }
+}
+
+#[test]
+fn symbol_indices() {
+    for format in [
+        BinaryFormat::Coff,
+        BinaryFormat::Elf,
+        BinaryFormat::MachO,
+        BinaryFormat::Xcoff,
+    ] {
+        for order in [write::SymbolOrder::Format, write::SymbolOrder::Insertion] {
+            let mut object = if format == BinaryFormat::Xcoff {
+                write::Object::new(format, Architecture::PowerPc64, Endianness::Big)
+            } else {
+                write::Object::new(format, Architecture::X86_64, Endianness::Little)
+            };
+            object.set_symbol_order(order);
+            let text = object.section_id(write::StandardSection::Text);
+            object.append_section_data(text, &[0; 16], 4);
+            let mut add_symbol = |name: &[u8], scope: SymbolScope| {
+                object.add_symbol(write::Symbol {
+                    name: name.to_vec(),
+                    value: 0,
+                    size: 0,
+                    kind: SymbolKind::Text,
+                    scope,
+                    weak: false,
+                    section: write::SymbolSection::Section(text),
+                    flags: SymbolFlags::None,
+                })
+            };
+            let zeta = add_symbol(b"zeta", SymbolScope::Linkage);
+            let alpha = add_symbol(b"alpha", SymbolScope::Linkage);
+            let local = add_symbol(b"local", SymbolScope::Compilation);
+
+            let mut bytes = Vec::new();
+            let result = object.emit_with_symbol_indices(&mut bytes);
+            if format == BinaryFormat::Elf && order == write::SymbolOrder::Insertion {
+                // Local symbols must come first.
+                assert!(result.is_err());
+                continue;
+            }
+            let indices = result.unwrap();
+
+            let file = read::File::parse(&*bytes).unwrap();
+            for id in [zeta, alpha, local] {
+                let index = indices.get(id).unwrap();
+                let symbol = file.symbol_by_index(object::SymbolIndex(index)).unwrap();
+                assert_eq!(symbol.name_bytes().unwrap(), object.symbol(id).name);
+            }
+            let order: Vec<_> = [zeta, alpha, local]
+                .iter()
+                .map(|id| indices.get(*id).unwrap())
+                .collect();
+            match (format, order.as_slice()) {
+                (BinaryFormat::Coff, [0, 1, 2])
+                | (BinaryFormat::Elf, [2, 3, 1])
+                | (BinaryFormat::Xcoff, [0, 2, 4]) => {}
+                (BinaryFormat::MachO, [2, 1, 0]) => {
+                    assert_eq!(object.symbol_order(), write::SymbolOrder::Format)
+                }
+                (BinaryFormat::MachO, [1, 2, 0]) => {
+                    assert_eq!(object.symbol_order(), write::SymbolOrder::Insertion)
+                }
+                _ => panic!("unexpected symbol indices {:?} for {:?}", order, format),
+            }
+        }
+    }
+}
+
+#[test]
+fn relocation_flags() {
+    for (format, flags) in [
+        (
+            BinaryFormat::Coff,
+            RelocationFlags::Coff {
+                typ: object::pe::IMAGE_REL_AMD64_ADDR64,
+            },
+        ),
+        (
+            BinaryFormat::Elf,
+            RelocationFlags::Elf {
+                r_type: object::elf::R_X86_64_64,
+            },
+        ),
+        (
+            BinaryFormat::MachO,
+            RelocationFlags::MachO {
+                r_type: object::macho::X86_64_RELOC_UNSIGNED,
+                r_pcrel: false,
+                r_length: 3,
+            },
+        ),
+    ] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+        let data = object.section_id(write::StandardSection::Data);
+        object.append_section_data(data, &[0; 8], 8);
+        let symbol = object.section_symbol(data);
+        object
+            .add_relocation(
+                data,
+                write::Relocation {
+                    offset: 0,
+                    symbol,
+                    addend: 0,
+                    flags: RelocationFlags::Generic {
+                        kind: RelocationKind::Absolute,
+                        encoding: RelocationEncoding::Generic,
+                        size: 64,
+                    },
+                },
+            )
+            .unwrap();
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        let section = object.section_by_name_bytes(b".data").unwrap();
+        let (_, relocation) = section.relocations().next().unwrap();
+        // The raw flags are available even though the kind is known.
+        assert_eq!(relocation.kind(), RelocationKind::Absolute);
+        assert_eq!(relocation.flags(), flags, "{:?}", format);
+    }
+}
+
+#[test]
+fn debug_files() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let section = object.add_section(Vec::new(), b".gnu_debuglink".to_vec(), SectionKind::Other);
+    object.append_section_data(section, b"file.debug\0\0\x78\x56\x34\x12", 4);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(
+        object.debug_files().unwrap(),
+        [read::DebugFile::GnuDebugLink {
+            filename: b"file.debug",
+            crc: 0x1234_5678
+        }]
+    );
+
+    let object = write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.debug_files().unwrap(), []);
+}
+
+#[test]
+fn go_build_info() {
+    let mod_info = b"0123456789abcdefpath\texample.com/m\nfedcba9876543210";
+
+    // ELF with a build ID note and inline build info.
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let mut note = Vec::new();
+    note.extend_from_slice(&3u32.to_le_bytes());
+    note.extend_from_slice(&8u32.to_le_bytes());
+    note.extend_from_slice(&object::elf::NT_GO_BUILD_ID.to_le_bytes());
+    note.extend_from_slice(b"Go\0\0abcd/efg");
+    let section = object.add_section(Vec::new(), b".note.go.buildid".to_vec(), SectionKind::Note);
+    object.append_section_data(section, &note, 4);
+    let mut buildinfo = Vec::new();
+    buildinfo.extend_from_slice(b"\xff Go buildinf:\x08\x02");
+    buildinfo.resize(32, 0);
+    buildinfo.push(8);
+    buildinfo.extend_from_slice(b"go1.21.0");
+    buildinfo.push(mod_info.len() as u8);
+    buildinfo.extend_from_slice(mod_info);
+    let section = object.add_section(Vec::new(), b".go.buildinfo".to_vec(), SectionKind::Data);
+    object.append_section_data(section, &buildinfo, 16);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.go_build_id().unwrap(), Some(&b"abcd/efg"[..]));
+    let info = object.go_build_info().unwrap().unwrap();
+    assert_eq!(info.version(), b"go1.21.0");
+    assert_eq!(info.mod_info(), b"path\texample.com/m\n");
+
+    // ELF with the older build info format that uses pointers.
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let mut buildinfo = Vec::new();
+    buildinfo.extend_from_slice(b"\xff Go buildinf:\x08\x00");
+    for value in [32u64, 48, 64, 8, 72, mod_info.len() as u64] {
+        buildinfo.extend_from_slice(&value.to_le_bytes());
+    }
+    buildinfo.extend_from_slice(b"go1.16.1");
+    buildinfo.extend_from_slice(mod_info);
+    let section = object.add_section(Vec::new(), b".go.buildinfo".to_vec(), SectionKind::Data);
+    object.append_section_data(section, &buildinfo, 16);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.go_build_id().unwrap(), None);
+    let info = object.go_build_info().unwrap().unwrap();
+    assert_eq!(info.version(), b"go1.16.1");
+    assert_eq!(info.mod_info(), b"path\texample.com/m\n");
+
+    // COFF with the build ID at the start of the text section, and the build
+    // info in the data section.
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, b"\xff Go build ID: \"abcd/efg\"\n \xff\xcc\xcc", 16);
+    let data = object.section_id(write::StandardSection::Data);
+    let mut buildinfo = vec![0; 16];
+    buildinfo.extend_from_slice(b"\xff Go buildinf:\x08\x02");
+    buildinfo.resize(48, 0);
+    buildinfo.push(8);
+    buildinfo.extend_from_slice(b"go1.21.0");
+    buildinfo.push(0);
+    object.append_section_data(data, &buildinfo, 16);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.go_build_id().unwrap(), Some(&b"abcd/efg"[..]));
+    let info = object.go_build_info().unwrap().unwrap();
+    assert_eq!(info.version(), b"go1.21.0");
+    assert_eq!(info.mod_info(), b"");
+
+    let object = write::Object::new(
+        BinaryFormat::MachO,
+        Architecture::X86_64,
+        Endianness::Little,
+    );
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.go_build_id().unwrap(), None);
+    assert_eq!(object.go_build_info().unwrap(), None);
+}
+
+#[test]
+fn rust_metadata() {
+    let blob = b"rust\0\0\0\x0a\0\0\0\0metadata";
+
+    // Dylib metadata with a header and length.
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let mut rustc = b"rust\0\0\0\x0a".to_vec();
+    rustc.extend_from_slice(&(blob.len() as u64).to_le_bytes());
+    rustc.extend_from_slice(blob);
+    rustc.extend_from_slice(&[0; 4]);
+    let section = object.add_section(Vec::new(), b".rustc".to_vec(), SectionKind::ReadOnlyData);
+    object.append_section_data(section, &rustc, 1);
+    let section = object.add_section(Vec::new(), b".llvmbc".to_vec(), SectionKind::Other);
+    object.append_section_data(section, b"BC\xc0\xde", 1);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    let metadata = object.rust_metadata().unwrap().unwrap();
+    assert_eq!(metadata.version(), 10);
+    assert!(!metadata.is_compressed());
+    assert_eq!(metadata.data(), blob);
+    assert_eq!(object.llvm_bitcode().unwrap(), Some(&b"BC\xc0\xde"[..]));
+
+    // Rlib metadata.
+    let mut object = write::Object::new(
+        BinaryFormat::MachO,
+        Architecture::X86_64,
+        Endianness::Little,
+    );
+    let section = object.add_section(b"__DWARF".to_vec(), b".rmeta".to_vec(), SectionKind::Debug);
+    object.append_section_data(section, blob, 1);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    let metadata = object.rust_metadata().unwrap().unwrap();
+    assert!(!metadata.is_compressed());
+    assert_eq!(metadata.data(), blob);
+    assert_eq!(object.llvm_bitcode().unwrap(), None);
+
+    // Compressed dylib metadata from older versions of rustc.
+    #[cfg(feature = "snappy")]
+    {
+        use std::io::Write;
+
+        let mut rustc = b"rust\0\0\0\x06".to_vec();
+        let mut encoder = snap::write::FrameEncoder::new(&mut rustc);
+        encoder.write_all(blob).unwrap();
+        drop(encoder);
+        let mut object =
+            write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+        let section = object.add_section(Vec::new(), b".rustc".to_vec(), SectionKind::ReadOnlyData);
+        object.append_section_data(section, &rustc, 1);
+        let bytes = object.write().unwrap();
+        let object = read::File::parse(&*bytes).unwrap();
+        let metadata = object.rust_metadata().unwrap().unwrap();
+        assert_eq!(metadata.version(), 6);
+        assert!(metadata.is_compressed());
+        assert_eq!(&*metadata.decompress().unwrap(), blob);
+    }
+
+    assert!(read::RustMetadata::parse(b"rust\0\0").is_err());
+}
+
+#[test]
+fn source_files() {
+    for (format, architecture, endian) in [
+        (BinaryFormat::Elf, Architecture::X86_64, Endianness::Little),
+        (BinaryFormat::Coff, Architecture::X86_64, Endianness::Little),
+        (
+            BinaryFormat::Xcoff,
+            Architecture::PowerPc64,
+            Endianness::Big,
+        ),
+    ] {
+        let mut object = write::Object::new(format, architecture, endian);
+        object.add_file_symbol(b"a.c".to_vec());
+        let section = object.section_id(write::StandardSection::Text);
+        object.append_section_data(section, &[0xcc; 4], 1);
+        object.add_file_symbol(b"a_very_long_file_name.c".to_vec());
+        let bytes = object.write().unwrap();
+        let object = read::File::parse(&*bytes).unwrap();
+        let files = object.source_files().unwrap();
+        let names: Vec<_> = files.iter().map(|file| file.name()).collect();
+        assert_eq!(
+            names,
+            [&b"a.c"[..], b"a_very_long_file_name.c"],
+            "{:?}",
+            format
+        );
+        assert!(files.iter().all(|file| file.directory().is_empty()));
+    }
+}
+
+#[test]
+fn parse_options() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0; 8], 4);
+    let func = object.add_symbol(write::Symbol {
+        name: b"func".to_vec(),
+        value: 0,
+        size: 8,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 0,
+                symbol: func,
+                addend: 0,
+                flags: RelocationFlags::Generic {
+                    kind: RelocationKind::Absolute,
+                    encoding: RelocationEncoding::Generic,
+                    size: 64,
+                },
+            },
+        )
+        .unwrap();
+    let mut bytes = object.write().unwrap();
+
+    let object = read::File::parse_with(&*bytes, &read::ParseOptions::default()).unwrap();
+    assert!(object.symbol_by_name("func").is_some());
+    let text = object.section_by_name(".text").unwrap();
+    assert_eq!(text.relocations().count(), 1);
+
+    let object = read::File::parse_with(&*bytes, &read::ParseOptions::minimal()).unwrap();
+    assert_eq!(object.symbols().count(), 0);
+    let text = object.section_by_name(".text").unwrap();
+    assert_eq!(text.data(), Ok(&[0; 8][..]));
+    assert_eq!(text.relocations().count(), 0);
+
+    let mut options = read::ParseOptions::minimal();
+    options.relocations = true;
+    let object = read::File::parse_with(&*bytes, &options).unwrap();
+    let text = object.section_by_name(".text").unwrap();
+    assert_eq!(text.relocations().count(), 1);
+
+    // Make the `.text` section extend outside the file.
+    let index = object.section_by_name(".text").unwrap().index();
+    let elf = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let shoff = elf.elf_header().e_shoff.get(Endianness::Little) as usize;
+    let sh_offset = shoff + index.0 * 64 + 24;
+    bytes[sh_offset..][..8].copy_from_slice(&0xffff_0000u64.to_le_bytes());
+
+    let mut options = read::ParseOptions::default();
+    assert!(read::File::parse_with(&*bytes, &options).is_ok());
+    options.verify = true;
+    assert!(read::File::parse_with(&*bytes, &options).is_err());
+}
+
+#[test]
+fn parse_permissive() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3; 8], 4);
+    object.add_symbol(write::Symbol {
+        name: b"func".to_vec(),
+        value: 0,
+        size: 8,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    let mut bytes = object.write().unwrap();
+
+    let object = read::File::parse(&*bytes).unwrap();
+    assert!(object.warnings().is_empty());
+    let index = object.section_by_name(".symtab").unwrap().index();
+
+    // Make the `.symtab` section extend outside the file.
+    let elf = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let shoff = elf.elf_header().e_shoff.get(Endianness::Little) as usize;
+    let sh_offset = shoff + index.0 * 64 + 24;
+    bytes[sh_offset..][..8].copy_from_slice(&0xffff_0000u64.to_le_bytes());
+
+    let mut options = read::ParseOptions::default();
+    assert!(read::File::parse_with(&*bytes, &options).is_err());
+
+    options.strictness = read::Strictness::Permissive;
+    let object = read::File::parse_with(&*bytes, &options).unwrap();
+    assert_eq!(object.warnings().len(), 1);
+    assert_eq!(object.symbols().count(), 0);
+    assert_eq!(
+        object.section_by_name(".text").unwrap().data(),
+        Ok(&[0xc3; 8][..])
+    );
+}
+
+#[test]
+fn alias() {
+    for format in [BinaryFormat::Coff, BinaryFormat::Elf, BinaryFormat::MachO] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+        object.set_mangling(write::Mangling::None);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3; 8], 4);
+        let func = object.add_symbol(write::Symbol {
+            name: b"func".to_vec(),
+            value: 4,
+            size: 4,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        object.add_alias(func, b"strong".to_vec(), false);
+        object.add_alias(func, b"weak".to_vec(), true);
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        let func = object.symbol_by_name("func").unwrap();
+        let strong = object.symbol_by_name("strong").unwrap();
+        assert_eq!(strong.address(), func.address(), "{:?}", format);
+        assert_eq!(strong.section(), func.section(), "{:?}", format);
+        assert_eq!(strong.kind(), SymbolKind::Text, "{:?}", format);
+        assert!(!strong.is_weak(), "{:?}", format);
+
+        let weak = object.symbol_by_name("weak").unwrap();
+        assert!(weak.is_weak(), "{:?}", format);
+        if format == BinaryFormat::Coff {
+            assert!(!weak.is_definition());
+            let coff = read::coff::CoffFile::<&[u8]>::parse(&*bytes).unwrap();
+            let aux = coff
+                .coff_symbol_table()
+                .get::<object::pe::ImageAuxSymbolWeak>(weak.index(), 1)
+                .unwrap();
+            assert_eq!(
+                aux.weak_default_sym_index.get(object::LittleEndian) as usize,
+                func.index().0
+            );
+            assert_eq!(
+                aux.weak_search_type.get(object::LittleEndian),
+                u32::from(object::pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS)
+            );
+        } else {
+            assert_eq!(weak.address(), func.address(), "{:?}", format);
+            assert_eq!(weak.section(), func.section(), "{:?}", format);
+        }
+    }
+}
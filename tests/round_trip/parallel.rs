@@ -0,0 +1,125 @@
+use object::read::{Object, ObjectSection};
+use object::{read, write};
+use object::{
+    Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationFlags, RelocationKind,
+    SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+};
+
+fn object() -> Vec<u8> {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+
+    let text = object.section_id(write::StandardSection::Text);
+    let mut symbols = Vec::new();
+    for i in 0..100 {
+        let offset = object.append_section_data(text, &[0xc3; 16], 16);
+        // Some symbols share addresses, so the map must choose between them.
+        let scope = if i % 2 == 0 {
+            SymbolScope::Linkage
+        } else {
+            SymbolScope::Compilation
+        };
+        symbols.push(object.add_symbol(write::Symbol {
+            name: format!("func{}", i).into_bytes(),
+            value: offset - offset % 32,
+            size: 16,
+            kind: SymbolKind::Text,
+            scope,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        }));
+    }
+
+    for name in [&b".debug_info"[..], b".debug_line"] {
+        let section = object.add_section(Vec::new(), name.to_vec(), SectionKind::Debug);
+        object.append_section_data(section, &[0; 8 * 100], 8);
+        for (i, symbol) in symbols.iter().enumerate() {
+            object
+                .add_relocation(
+                    section,
+                    write::Relocation {
+                        offset: i as u64 * 8,
+                        symbol: *symbol,
+                        addend: i as i64,
+                        flags: RelocationFlags::Generic {
+                            kind: RelocationKind::Absolute,
+                            encoding: RelocationEncoding::Generic,
+                            size: 64,
+                        },
+                    },
+                )
+                .unwrap();
+        }
+    }
+
+    object.write().unwrap()
+}
+
+#[test]
+fn par_symbol_map() {
+    let bytes = object();
+    let object = read::File::parse(&*bytes).unwrap();
+    let map = object.symbol_map();
+    assert_eq!(map.symbols().len(), 50);
+    assert_eq!(object.par_symbol_map().symbols(), map.symbols());
+}
+
+#[test]
+fn par_relocation_maps() {
+    let bytes = object();
+    let object = read::File::parse(&*bytes).unwrap();
+    let maps = object.par_relocation_maps().unwrap();
+    assert_eq!(maps.len(), 2);
+    for (index, map) in &maps {
+        let section = object.section_by_index(*index).unwrap();
+        let expected = section.relocation_map().unwrap();
+        for offset in (0..800).step_by(8) {
+            assert_eq!(map.relocate(offset, 0), expected.relocate(offset, 0));
+        }
+        assert_eq!(map.relocate(16, 0), 32 + 2);
+    }
+}
+
+#[test]
+fn par_parse_members() {
+    let bytes = object();
+    let mut archive = b"!<arch>\n".to_vec();
+    for name in ["a.o/", "b.o/"] {
+        archive.extend_from_slice(
+            format!(
+                "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+                name,
+                0,
+                0,
+                0,
+                644,
+                bytes.len()
+            )
+            .as_bytes(),
+        );
+        archive.extend_from_slice(&bytes);
+        if bytes.len() % 2 != 0 {
+            archive.push(b'\n');
+        }
+    }
+    archive.extend_from_slice(
+        format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            "c.txt/", 0, 0, 0, 644, 4
+        )
+        .as_bytes(),
+    );
+    archive.extend_from_slice(b"text");
+
+    let archive = read::archive::ArchiveFile::parse(&*archive).unwrap();
+    let members = archive.par_parse_members();
+    assert_eq!(members.len(), 3);
+    for (member, name) in members[..2].iter().zip([&b"a.o"[..], b"b.o"]) {
+        let (member, file) = member.as_ref().unwrap();
+        assert_eq!(member.name(), name);
+        assert_eq!(file.format(), BinaryFormat::Elf);
+        assert_eq!(file.symbol_map().symbols().len(), 50);
+    }
+    assert!(members[2].is_err());
+}
@@ -0,0 +1,598 @@
+use object::pe;
+use object::read::pe::{ExportTarget, PeFile64, ResourceNameOrId};
+use object::read::{Object, ObjectSection};
+use object::write::pe::{self as write_pe, NtHeaders, Writer};
+use object::LittleEndian as LE;
+
+fn nt_headers() -> NtHeaders {
+    NtHeaders {
+        machine: pe::IMAGE_FILE_MACHINE_AMD64,
+        time_date_stamp: 0,
+        characteristics: pe::IMAGE_FILE_EXECUTABLE_IMAGE | pe::IMAGE_FILE_DLL,
+        major_linker_version: 14,
+        minor_linker_version: 0,
+        address_of_entry_point: 0,
+        image_base: 0x1_8000_0000,
+        major_operating_system_version: 6,
+        minor_operating_system_version: 0,
+        major_image_version: 0,
+        minor_image_version: 0,
+        major_subsystem_version: 6,
+        minor_subsystem_version: 0,
+        subsystem: pe::IMAGE_SUBSYSTEM_WINDOWS_CUI,
+        dll_characteristics: 0,
+        size_of_stack_reserve: 0x10_0000,
+        size_of_stack_commit: 0x1000,
+        size_of_heap_reserve: 0x10_0000,
+        size_of_heap_commit: 0x1000,
+    }
+}
+
+#[test]
+fn export_table() {
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(2);
+    let text = writer.reserve_text_section(0x20);
+
+    writer.add_export(
+        3,
+        Some(b"zeta"),
+        write_pe::ExportTarget::Address(text.virtual_address),
+    );
+    writer.add_export(
+        1,
+        Some(b"alpha"),
+        write_pe::ExportTarget::Address(text.virtual_address + 0x10),
+    );
+    writer.add_export(
+        2,
+        Some(b"forward"),
+        write_pe::ExportTarget::ForwardByName(b"KERNEL32", b"ExitProcess"),
+    );
+    writer.add_export(
+        5,
+        None,
+        write_pe::ExportTarget::ForwardByOrdinal(b"NTDLL", 17),
+    );
+    writer.reserve_export_section(b"test.dll", 1).unwrap();
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xcc; 0x20]);
+    writer.write_export_section();
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let table = file.export_table().unwrap().unwrap();
+    let name = table.directory().name.get(LE);
+    assert_eq!(table.name_from_pointer(name).unwrap(), b"test.dll");
+    assert_eq!(table.ordinal_base(), 1);
+    assert_eq!(table.addresses().len(), 5);
+    // The name pointer table must be sorted.
+    let names = table
+        .name_iter()
+        .map(|(pointer, _)| table.name_from_pointer(pointer).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [&b"alpha"[..], b"forward", b"zeta"]);
+
+    // Unused ordinals have a zero address.
+    let exports = table
+        .exports()
+        .unwrap()
+        .into_iter()
+        .filter(|export| !matches!(export.target, ExportTarget::Address(0)))
+        .collect::<Vec<_>>();
+    assert_eq!(exports.len(), 4);
+    assert_eq!(exports[0].ordinal, 1);
+    assert_eq!(exports[0].name, Some(&b"alpha"[..]));
+    assert!(matches!(
+        exports[0].target,
+        ExportTarget::Address(address) if address == text.virtual_address + 0x10
+    ));
+    assert!(matches!(
+        exports[1].target,
+        ExportTarget::ForwardByName(b"KERNEL32", b"ExitProcess")
+    ));
+    assert_eq!(exports[2].ordinal, 3);
+    assert_eq!(exports[2].name, Some(&b"zeta"[..]));
+    assert_eq!(exports[3].ordinal, 5);
+    assert_eq!(exports[3].name, None);
+    assert!(matches!(
+        exports[3].target,
+        ExportTarget::ForwardByOrdinal(b"NTDLL", 17)
+    ));
+
+    let dir = file
+        .data_directory(pe::IMAGE_DIRECTORY_ENTRY_EXPORT)
+        .unwrap();
+    assert_ne!(dir.virtual_address.get(LE), 0);
+
+    assert_eq!(object::verify::verify(&*buffer).unwrap(), Vec::new());
+}
+
+#[test]
+fn export_table_duplicate_ordinal() {
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(1);
+    writer.add_export(1, Some(b"a"), write_pe::ExportTarget::Address(0x1000));
+    writer.add_export(1, Some(b"b"), write_pe::ExportTarget::Address(0x1000));
+    assert!(writer.reserve_export_section(b"test.dll", 1).is_err());
+}
+
+#[test]
+fn export_table_large_ordinal() {
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(1);
+    writer.add_export(
+        0x1_0001,
+        Some(b"a"),
+        write_pe::ExportTarget::Address(0x1000),
+    );
+    assert!(writer.reserve_export_section(b"test.dll", 1).is_err());
+}
+
+#[test]
+fn resource_directory() {
+    let manifest = b"<assembly/>";
+    let config = b"config data";
+    let info = write_pe::VersionInfo {
+        file_version: [1, 2, 3, 4],
+        product_version: [1, 2, 0, 0],
+        file_os: pe::VOS_NT_WINDOWS32,
+        file_type: pe::VFT_DLL,
+        language: 0x0409,
+        code_page: 1200,
+        strings: vec![("ProductName", "Test"), ("FileVersion", "1.2.3.4")],
+        ..Default::default()
+    };
+
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(1);
+    writer.add_resource(
+        write_pe::ResourceName::Name("MYTYPE"),
+        write_pe::ResourceName::Name("config"),
+        0x0409,
+        config,
+    );
+    writer.add_manifest_resource(pe::ISOLATIONAWARE_MANIFEST_RESOURCE_ID, manifest);
+    writer.add_version_info_resource(&info).unwrap();
+    writer.reserve_resource_section().unwrap();
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_resource_section();
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let sections = file.section_table();
+    let directory = file
+        .data_directories()
+        .resource_directory(&*buffer, &sections)
+        .unwrap()
+        .unwrap();
+    let root = directory.root().unwrap();
+    // Named entries come before ID entries.
+    assert_eq!(root.header.number_of_named_entries.get(LE), 1);
+    assert_eq!(root.header.number_of_id_entries.get(LE), 2);
+
+    let mut found = Vec::new();
+    for type_entry in root.entries {
+        let type_table = type_entry.data(directory).unwrap().table().unwrap();
+        for name_entry in type_table.entries {
+            let name_table = name_entry.data(directory).unwrap().table().unwrap();
+            for language_entry in name_table.entries {
+                let data_entry = language_entry.data(directory).unwrap().data().unwrap();
+                let data = sections
+                    .pe_data_at(&*buffer, data_entry.offset_to_data.get(LE))
+                    .unwrap();
+                let data = &data[..data_entry.size.get(LE) as usize];
+                let name = match name_entry.name_or_id() {
+                    ResourceNameOrId::Name(name) => name.to_string_lossy(directory).unwrap(),
+                    ResourceNameOrId::Id(id) => id.to_string(),
+                };
+                let typ = match type_entry.name_or_id() {
+                    ResourceNameOrId::Name(name) => name.to_string_lossy(directory).unwrap(),
+                    ResourceNameOrId::Id(id) => id.to_string(),
+                };
+                let language = language_entry.name_or_id().id().unwrap();
+                found.push((typ, name, language, data.to_vec()));
+            }
+        }
+    }
+    assert_eq!(found.len(), 3);
+    assert_eq!(
+        found[0],
+        ("MYTYPE".into(), "config".into(), 0x0409, config.to_vec())
+    );
+    assert_eq!(found[1].0, pe::RT_VERSION.to_string());
+    assert_eq!(found[1].3, info.data().unwrap());
+    assert_eq!(
+        found[2],
+        (
+            pe::RT_MANIFEST.to_string(),
+            "2".into(),
+            0,
+            manifest.to_vec()
+        )
+    );
+
+    // Check the fixed file info in the version resource.
+    let version = &found[1].3;
+    assert_eq!(
+        u16::from_le_bytes([version[0], version[1]]) as usize,
+        version.len()
+    );
+    let fixed = object::pod::from_bytes::<pe::VsFixedFileInfo>(&version[40..])
+        .unwrap()
+        .0;
+    assert_eq!(fixed.signature.get(LE), pe::VS_FFI_SIGNATURE);
+    assert_eq!(fixed.file_version_ms.get(LE), 0x0001_0002);
+    assert_eq!(fixed.file_version_ls.get(LE), 0x0003_0004);
+}
+
+#[test]
+fn version_info_too_large() {
+    let value = "x".repeat(0x8000);
+    let info = write_pe::VersionInfo {
+        strings: vec![("Comments", &value)],
+        ..Default::default()
+    };
+    assert!(info.data().is_err());
+
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    assert!(writer.add_version_info_resource(&info).is_err());
+}
+
+#[test]
+fn resource_name_case() {
+    use write_pe::ResourceName;
+
+    assert_eq!(ResourceName::Name("Foo"), ResourceName::Name("FOO"));
+    assert_ne!(ResourceName::Name("Foo"), ResourceName::Name("Fo"));
+
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.add_resource(ResourceName::Id(10), ResourceName::Name("Foo"), 1, b"a");
+    writer.add_resource(ResourceName::Id(10), ResourceName::Name("FOO"), 1, b"b");
+    assert!(writer.reserve_resource_section().is_err());
+
+    // Names that differ only in case share a directory entry.
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(1);
+    writer.add_resource(ResourceName::Id(10), ResourceName::Name("Foo"), 1, b"a");
+    writer.add_resource(ResourceName::Id(10), ResourceName::Name("FOO"), 2, b"b");
+    writer.reserve_resource_section().unwrap();
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_resource_section();
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let sections = file.section_table();
+    let directory = file
+        .data_directories()
+        .resource_directory(&*buffer, &sections)
+        .unwrap()
+        .unwrap();
+    let root = directory.root().unwrap();
+    assert_eq!(root.entries.len(), 1);
+    let type_table = root.entries[0].data(directory).unwrap().table().unwrap();
+    assert_eq!(type_table.entries.len(), 1);
+    let name_table = type_table.entries[0]
+        .data(directory)
+        .unwrap()
+        .table()
+        .unwrap();
+    assert_eq!(name_table.entries.len(), 2);
+}
+
+#[test]
+fn debug_directory() {
+    let guid = [
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+        0x10,
+    ];
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(1);
+    writer.add_debug_codeview(guid, 3, b"C:\\build\\test.pdb");
+    writer.add_debug_repro(&[0xaa; 32]);
+    writer.reserve_debug_section();
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_debug_section();
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let pdb_info = file.pdb_info().unwrap().unwrap();
+    assert_eq!(pdb_info.guid(), guid);
+    assert_eq!(pdb_info.age(), 3);
+    assert_eq!(pdb_info.path(), b"C:\\build\\test.pdb");
+
+    let dir = file
+        .data_directory(pe::IMAGE_DIRECTORY_ENTRY_DEBUG)
+        .unwrap();
+    let data = dir.data(&*buffer, &file.section_table()).unwrap();
+    let entries = object::pod::slice_from_all_bytes::<pe::ImageDebugDirectory>(data).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].typ.get(LE), pe::IMAGE_DEBUG_TYPE_REPRO);
+    assert_eq!(entries[1].size_of_data.get(LE), 36);
+    let offset = entries[1].pointer_to_raw_data.get(LE) as usize;
+    assert_eq!(&buffer[offset..][..4], &32u32.to_le_bytes());
+    assert_eq!(&buffer[offset + 4..][..32], &[0xaa; 32]);
+}
+
+#[test]
+fn clr_header() {
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(1);
+    let text = writer.reserve_text_section(0x100);
+    let header_size = core::mem::size_of::<pe::ImageCor20Header>() as u32;
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR,
+        text.virtual_address,
+        header_size,
+    );
+
+    let mut data = vec![0; 0x100];
+    data[0..4].copy_from_slice(&header_size.to_le_bytes());
+    data[4..6].copy_from_slice(&2u16.to_le_bytes());
+    data[6..8].copy_from_slice(&5u16.to_le_bytes());
+    let flags = pe::COMIMAGE_FLAGS_ILONLY | pe::COMIMAGE_FLAGS_STRONGNAMESIGNED;
+    data[16..20].copy_from_slice(&flags.to_le_bytes());
+    data[20..24].copy_from_slice(&0x0600_0001u32.to_le_bytes());
+    data[32..36].copy_from_slice(&(text.virtual_address + 0x80).to_le_bytes());
+    data[36..40].copy_from_slice(&0x80u32.to_le_bytes());
+    data[0x80..].fill(0x5a);
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &data);
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let header = file.cor20_header().unwrap().unwrap();
+    assert_eq!(header.major_runtime_version.get(LE), 2);
+    assert!(header.is_strong_name_signed());
+    assert_eq!(header.entry_point_token(), Some(0x0600_0001));
+    assert_eq!(header.native_entry_point(), None);
+
+    let sections = file.section_table();
+    let (offset, size) = header
+        .strong_name_signature_range(&sections)
+        .unwrap()
+        .unwrap();
+    assert_eq!((offset, size), (text.file_offset + 0x80, 0x80));
+    let signature = header
+        .strong_name_signature(&*buffer, &sections)
+        .unwrap()
+        .unwrap();
+    assert_eq!(signature, &[0x5a; 0x80][..]);
+}
+
+#[test]
+fn long_section_names() {
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    assert!(writer.section_name(b".debug_info").is_err());
+    writer.set_long_section_names(write_pe::LongSectionNames::Truncate);
+    assert_eq!(&writer.section_name(b".debug_info").unwrap(), b".debug_i");
+    writer.set_long_section_names(write_pe::LongSectionNames::StringTable);
+    assert_eq!(&writer.section_name(b".text").unwrap(), b".text\0\0\0");
+
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(2);
+    let info_name = writer.section_name(b".debug_info").unwrap();
+    let abbrev_name = writer.section_name(b".debug_abbrev").unwrap();
+    assert_eq!(&info_name, b"/4\0\0\0\0\0\0");
+    assert_eq!(&abbrev_name, b"/16\0\0\0\0\0");
+    let characteristics =
+        pe::IMAGE_SCN_CNT_INITIALIZED_DATA | pe::IMAGE_SCN_MEM_READ | pe::IMAGE_SCN_MEM_DISCARDABLE;
+    let info = writer.reserve_section(info_name, characteristics, 0x10, 0x10);
+    let abbrev = writer.reserve_section(abbrev_name, characteristics, 0x8, 0x8);
+    writer.reserve_strtab();
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(info.file_offset, &[1; 0x10]);
+    writer.write_section(abbrev.file_offset, &[2; 0x8]);
+    writer.write_strtab();
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let section = file.section_by_name(".debug_info").unwrap();
+    assert_eq!(section.data().unwrap(), &[1; 0x10]);
+    let section = file.section_by_name(".debug_abbrev").unwrap();
+    assert_eq!(section.data().unwrap(), &[2; 0x8]);
+}
+
+#[test]
+fn tls_directory() {
+    let image_base = nt_headers().image_base;
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(3);
+    let text = writer.reserve_text_section(0x20);
+    writer.add_tls_callback(text.virtual_address + 0x10);
+    let tls =
+        writer.reserve_tls_section(image_base, &[1, 2, 3, 4, 5], 11, pe::IMAGE_SCN_ALIGN_8BYTES);
+    // Added after the TLS relocations, but at a lower address.
+    writer.add_reloc(text.virtual_address + 8, pe::IMAGE_REL_BASED_DIR64);
+    writer.reserve_reloc_section();
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xcc; 0x20]);
+    writer.write_tls_section();
+    writer.write_reloc_section();
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let sections = file.section_table();
+    let dir = file.data_directory(pe::IMAGE_DIRECTORY_ENTRY_TLS).unwrap();
+    assert_eq!(dir.virtual_address.get(LE), tls.directory_address);
+    let data = dir.data(&*buffer, &sections).unwrap();
+    let directory = object::pod::from_bytes::<pe::ImageTlsDirectory64>(data)
+        .unwrap()
+        .0;
+    let start = directory.start_address_of_raw_data.get(LE);
+    assert_eq!(start, image_base + u64::from(tls.data_address));
+    assert_eq!(directory.end_address_of_raw_data.get(LE), start + 5);
+    assert_eq!(
+        directory.address_of_index.get(LE),
+        image_base + u64::from(tls.index_address)
+    );
+    assert_eq!(directory.size_of_zero_fill.get(LE), 11);
+    let template = sections.pe_data_at(&*buffer, tls.data_address).unwrap();
+    assert_eq!(&template[..5], &[1, 2, 3, 4, 5]);
+    let callbacks = sections
+        .pe_data_at(&*buffer, tls.callbacks_address)
+        .unwrap();
+    assert_eq!(
+        callbacks[..16],
+        [
+            (image_base + u64::from(text.virtual_address + 0x10)).to_le_bytes(),
+            [0; 8]
+        ]
+        .concat()[..]
+    );
+
+    let mut relocs = Vec::new();
+    let mut blocks = file
+        .data_directories()
+        .relocation_blocks(&*buffer, &sections)
+        .unwrap()
+        .unwrap();
+    while let Some(block) = blocks.next().unwrap() {
+        for reloc in block {
+            assert_eq!(reloc.typ, pe::IMAGE_REL_BASED_DIR64);
+            relocs.push(reloc.virtual_address);
+        }
+    }
+    assert_eq!(
+        relocs,
+        [
+            text.virtual_address + 8,
+            tls.directory_address,
+            tls.directory_address + 8,
+            tls.directory_address + 16,
+            tls.directory_address + 24,
+            tls.callbacks_address,
+        ]
+    );
+}
+
+#[test]
+fn absolute_relocs() {
+    let mut buffer = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(3);
+    let text = writer.reserve_text_section(0x20);
+    let data = writer.reserve_data_section(0x2000, 0x2000);
+    writer
+        .add_absolute_relocs(
+            &data,
+            &[
+                write_pe::AbsoluteRelocation {
+                    offset: 0x1ff8,
+                    size: 64,
+                },
+                write_pe::AbsoluteRelocation {
+                    offset: 0x10,
+                    size: 32,
+                },
+            ],
+        )
+        .unwrap();
+    writer
+        .add_absolute_relocs(
+            &text,
+            &[write_pe::AbsoluteRelocation {
+                offset: 2,
+                size: 64,
+            }],
+        )
+        .unwrap();
+    assert!(writer
+        .add_absolute_relocs(
+            &text,
+            &[write_pe::AbsoluteRelocation {
+                offset: 0x1c,
+                size: 64
+            }],
+        )
+        .is_err());
+    assert!(writer
+        .add_absolute_relocs(
+            &text,
+            &[write_pe::AbsoluteRelocation {
+                offset: 0,
+                size: 16
+            }],
+        )
+        .is_err());
+    writer.reserve_reloc_section();
+
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xcc; 0x20]);
+    writer.write_section(data.file_offset, &[0; 0x2000]);
+    writer.write_reloc_section();
+    assert_eq!(writer.reserved_len() as usize, writer.len());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let mut blocks = file
+        .data_directories()
+        .relocation_blocks(&*buffer, &file.section_table())
+        .unwrap()
+        .unwrap();
+    let mut relocs = Vec::new();
+    while let Some(block) = blocks.next().unwrap() {
+        for reloc in block {
+            relocs.push((reloc.virtual_address, reloc.typ));
+        }
+    }
+    assert_eq!(
+        relocs,
+        [
+            (text.virtual_address + 2, pe::IMAGE_REL_BASED_DIR64),
+            (data.virtual_address + 0x10, pe::IMAGE_REL_BASED_HIGHLOW),
+            (data.virtual_address + 0x1ff8, pe::IMAGE_REL_BASED_DIR64),
+        ]
+    );
+}
@@ -87,3 +87,70 @@ fn macho_x86_64_section_flags() {
         }
     );
 }
+
+#[test]
+fn section_permissions() {
+    use object::Permissions;
+
+    for (format, arch) in [
+        (BinaryFormat::Coff, Architecture::X86_64),
+        (BinaryFormat::Elf, Architecture::X86_64),
+        (BinaryFormat::MachO, Architecture::X86_64),
+        (BinaryFormat::Xcoff, Architecture::PowerPc64),
+    ] {
+        let endian = if format == BinaryFormat::Xcoff {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        };
+        let mut object = write::Object::new(format, arch, endian);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3], 1);
+        let data = object.section_id(write::StandardSection::Data);
+        object.append_section_data(data, &[1], 1);
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        let text = object
+            .section_by_name_bytes(&object_name(format, "text"))
+            .unwrap();
+        let data = object
+            .section_by_name_bytes(&object_name(format, "data"))
+            .unwrap();
+        if format == BinaryFormat::MachO {
+            // Object files have a single segment containing all sections.
+            assert!(text.permissions().execute, "{:?}", format);
+            assert!(data.permissions().write, "{:?}", format);
+        } else {
+            assert_eq!(
+                text.permissions(),
+                Permissions::new(true, false, true),
+                "{:?}",
+                format
+            );
+            assert_eq!(
+                data.permissions(),
+                Permissions::new(true, true, false),
+                "{:?}",
+                format
+            );
+        }
+    }
+
+    // Non-allocated ELF sections are not loaded.
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let comment = object.add_section(Vec::new(), b".comment".to_vec(), SectionKind::OtherString);
+    object.append_section_data(comment, b"a\0", 1);
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    let comment = object.section_by_name(".comment").unwrap();
+    assert_eq!(comment.permissions(), Permissions::default());
+}
+
+fn object_name(format: BinaryFormat, name: &str) -> Vec<u8> {
+    match format {
+        BinaryFormat::MachO => format!("__{}", name).into_bytes(),
+        _ => format!(".{}", name).into_bytes(),
+    }
+}
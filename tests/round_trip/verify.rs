@@ -0,0 +1,67 @@
+use std::convert::TryInto;
+
+use object::verify::{verify, DiagnosticKind};
+use object::{write, Architecture, BinaryFormat, Endianness, SectionKind, SymbolFlags};
+use object::{SymbolKind, SymbolScope};
+
+fn object(format: BinaryFormat) -> Vec<u8> {
+    let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3; 16], 16);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[1; 32], 8);
+    object.add_section(Vec::new(), b".debug_info".to_vec(), SectionKind::Debug);
+    object.add_symbol(write::Symbol {
+        name: b"func".to_vec(),
+        value: 0,
+        size: 16,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    object.write().unwrap()
+}
+
+#[test]
+fn verify_clean() {
+    for format in [BinaryFormat::Coff, BinaryFormat::Elf, BinaryFormat::MachO] {
+        let bytes = object(format);
+        assert_eq!(verify(&*bytes).unwrap(), Vec::new(), "{:?}", format);
+    }
+}
+
+#[test]
+fn verify_elf() {
+    let mut bytes = object(BinaryFormat::Elf);
+    let shoff = u64::from_le_bytes(bytes[0x28..0x30].try_into().unwrap()) as usize;
+    // Section 1: point sh_offset past the end of the file and sh_link out of range.
+    let header = shoff + 64;
+    let len = bytes.len() as u64;
+    bytes[header + 24..header + 32].copy_from_slice(&len.to_le_bytes());
+    bytes[header + 40..header + 44].copy_from_slice(&1000u32.to_le_bytes());
+
+    let kinds: Vec<_> = verify(&*bytes)
+        .unwrap()
+        .into_iter()
+        .map(|diagnostic| diagnostic.kind)
+        .collect();
+    assert!(kinds.contains(&DiagnosticKind::OutOfBounds), "{:?}", kinds);
+    assert!(
+        kinds.contains(&DiagnosticKind::InvalidReference),
+        "{:?}",
+        kinds
+    );
+}
+
+#[test]
+fn verify_macho() {
+    let mut bytes = object(BinaryFormat::MachO);
+    let sizeofcmds = u32::from_le_bytes(bytes[20..24].try_into().unwrap());
+    bytes[20..24].copy_from_slice(&(sizeofcmds + 8).to_le_bytes());
+
+    let diagnostics = verify(&*bytes).unwrap();
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::InvalidSize);
+}
@@ -137,6 +137,7 @@ fn cmd_features() -> Result<(), DynError> {
         "std",
         "compression",
         "unaligned",
+        "read,rayon",
     ] {
         cargo(&[
             "test",